@@ -4,6 +4,7 @@ use std::sync::Arc;
 use agent_core::{strng, telemetry, version};
 use agentgateway::app::Bound;
 use agentgateway::types::agent::ListenerTarget;
+use agentgateway::types::local::NormalizedLocalConfig;
 use agentgateway::{BackendConfig, Config, LoggingFormat, client, serdes};
 use tracing::{error, info};
 
@@ -93,12 +94,25 @@ async fn validate(
 	contents: String,
 	local_config_source: Option<agentgateway::ConfigSource>,
 ) -> anyhow::Result<()> {
+	let mut errors = agentgateway::config::validate_config(&contents)?;
+	if !errors.is_empty() {
+		// The local config is usually the same file, so still report its errors alongside.
+		if let Some(cfg) = local_config_source.as_ref() {
+			let cs = cfg.read_to_string().await?;
+			errors.extend(NormalizedLocalConfig::validate(&cs)?);
+		}
+		return report_errors(errors);
+	}
 	let config = agentgateway::config::parse_config(contents, local_config_source)?;
 	let client = client::Client::new(&config.dns, None, BackendConfig::default(), None);
 	if let Some(cfg) = config.xds.local_config.as_ref() {
 		let cs = cfg.read_to_string().await?;
+		let errors = NormalizedLocalConfig::validate(&cs)?;
+		if !errors.is_empty() {
+			return report_errors(errors);
+		}
 		let resources = agentgateway::resource_manager::ResourceFetcher::direct(client);
-		agentgateway::types::local::NormalizedLocalConfig::from(
+		NormalizedLocalConfig::from(
 			&config,
 			&resources,
 			ListenerTarget {
@@ -117,6 +131,13 @@ async fn validate(
 	Ok(())
 }
 
+fn report_errors(errors: Vec<String>) -> anyhow::Result<()> {
+	for error in &errors {
+		eprintln!("error: {error}");
+	}
+	anyhow::bail!("configuration is invalid: found {} error(s)", errors.len())
+}
+
 #[cfg(not(unix))]
 fn spawn_readiness(_: &Bound) {}
 
@@ -240,6 +240,21 @@ fn bench_execute_ref(b: Bencher, case_name: &str) {
 	});
 }
 
+// Compare with bench_execute_ref to see the effect of the result cache; every iteration is a hit.
+#[divan::bench(args = TEST_CASE_NAMES)]
+fn bench_execute_cached(b: Bencher, case_name: &str) {
+	let tc = get_test_case(case_name);
+	let expr = Expression::new_strict(tc.expression)
+		.unwrap()
+		.with_result_cache(16);
+	let req = (tc.request_builder)();
+	let exec = crate::cel::Executor::new_request(&req);
+
+	b.bench(|| {
+		let _ = divan::black_box(exec.eval(&expr).unwrap());
+	});
+}
+
 // lookup compares different ways to do field access in CEL
 mod lookup {
 	use std::collections::HashMap;
@@ -0,0 +1,86 @@
+use std::io;
+
+use cel::{Program, Value};
+use quick_cache::sync::Cache;
+use sha2::Digest;
+
+use super::{custom, properties};
+
+/// Functions whose result differs between calls with the same inputs (or, for `variables`, that
+/// read every attribute), so expressions calling them are never cached.
+const IMPURE_FUNCTIONS: &[&str] = &["random", "uuid", "variables"];
+
+/// ResultCache memoizes the results of a pure expression, keyed by a hash of the attributes it
+/// references. The cache holds at most `capacity` entries, evicting those hit least recently.
+pub(super) struct ResultCache {
+	// Attribute paths read by the expression, such as `["request", "headers"]`.
+	paths: Vec<Vec<String>>,
+	results: Cache<u128, Value<'static>>,
+}
+
+impl ResultCache {
+	/// Returns None if the expression is impure. Custom functions are treated as impure, as their
+	/// bodies may read attributes that don't appear in the calling expression.
+	pub(super) fn new(program: &Program, capacity: usize) -> Option<Self> {
+		let references = program.references();
+		if references
+			.functions()
+			.into_iter()
+			.any(|f| IMPURE_FUNCTIONS.contains(&f) || custom::is_custom_function(f))
+		{
+			return None;
+		}
+		let mut props: Vec<Vec<&str>> = Vec::with_capacity(5);
+		properties::properties(&program.expression().expr, &mut props, &mut Vec::default());
+		let mut paths: Vec<Vec<String>> = props
+			.into_iter()
+			.map(|p| p.into_iter().map(String::from).collect())
+			.collect();
+		paths.sort();
+		paths.dedup();
+		Some(Self {
+			paths,
+			results: Cache::new(capacity),
+		})
+	}
+
+	/// Computes the cache key for the current inputs. `lookup` resolves a path to its value, or to a
+	/// value containing it when the path cannot be followed further. Returns None if an input cannot
+	/// be serialized, in which case the result should not be cached.
+	///
+	/// The inputs are hashed as they are serialized, so large attributes (such as a request body)
+	/// are never held in the cache. A 128-bit prefix of SHA-256 makes a collision, which would return
+	/// another input's result, practically impossible.
+	pub(super) fn key<'a>(&self, lookup: impl Fn(&[String]) -> Value<'a>) -> Option<u128> {
+		let mut hasher = HashWriter(sha2::Sha256::new());
+		for path in &self.paths {
+			let input = lookup(path).json().ok()?;
+			serde_json::to_writer(&mut hasher, &input).ok()?;
+			// Separate inputs so adjacent values cannot run together.
+			hasher.0.update([0u8]);
+		}
+		let digest = hasher.0.finalize();
+		Some(u128::from_be_bytes(digest[..16].try_into().ok()?))
+	}
+
+	pub(super) fn get(&self, key: u128) -> Option<Value<'static>> {
+		self.results.get(&key)
+	}
+
+	pub(super) fn insert(&self, key: u128, value: &Value) {
+		self.results.insert(key, value.as_static());
+	}
+}
+
+struct HashWriter(sha2::Sha256);
+
+impl io::Write for HashWriter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
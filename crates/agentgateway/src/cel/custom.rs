@@ -42,17 +42,25 @@ pub(super) struct Registry {
 	attributes: HashMap<String, FlagSet<Attributes>>,
 }
 
-pub fn register(definitions: &str) -> Result<(), Error> {
-	if definitions.trim().is_empty() {
+pub fn register(
+	definitions: &str,
+	function_timeout: Option<std::time::Duration>,
+) -> Result<(), Error> {
+	if definitions.trim().is_empty() && function_timeout.is_none() {
 		return Ok(());
 	}
+	let too_late = || {
+		Error::Variable(if definitions.trim().is_empty() {
+			"the CEL function timeout must be configured before CEL is used".to_string()
+		} else {
+			"custom CEL functions must be registered before CEL is used".to_string()
+		})
+	};
 	// The CEL context is global so built-in and custom functions share one
 	// function table. Register custom functions before any expression can
 	// initialize that table without them.
 	if ROOT_CONTEXT.get().is_some() {
-		return Err(Error::Variable(
-			"custom CEL functions must be registered before CEL is used".to_string(),
-		));
+		return Err(too_late());
 	}
 
 	let parsed = parse_all(definitions)?;
@@ -62,6 +70,9 @@ pub fn register(definitions: &str) -> Result<(), Error> {
 
 	let mut ctx = Context::default();
 	agent_celx::insert_all(&mut ctx);
+	if let Some(timeout) = function_timeout {
+		ctx = ctx.with_function_timeout(timeout);
+	}
 	reject_builtin_collisions(&parsed, &ctx)?;
 	let mut registry = Registry::default();
 	for definition in parsed {
@@ -76,9 +87,7 @@ pub fn register(definitions: &str) -> Result<(), Error> {
 			context: ctx,
 			registry,
 		})
-		.map_err(|_| {
-			Error::Variable("custom CEL functions must be registered before CEL is used".to_string())
-		})?;
+		.map_err(|_| too_late())?;
 	Ok(())
 }
 
@@ -205,6 +214,13 @@ pub fn attributes_for_functions<'a>(
 	})
 }
 
+/// Returns true if `function` is a registered custom function.
+pub(super) fn is_custom_function(function: &str) -> bool {
+	ROOT_CONTEXT
+		.get()
+		.is_some_and(|root| root.registry.attributes.contains_key(function))
+}
+
 fn register_function(definition: &Definition) -> Result<Function, Error> {
 	let program = Program::compile_with_optimizer(&definition.body, agent_celx::DefaultOptimizer)?;
 	// Custom functions live in the global CEL context for the rest of the
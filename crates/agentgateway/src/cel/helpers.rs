@@ -16,3 +16,23 @@ pub fn value_as_byte_or_json(v: Value<'_>) -> anyhow::Result<Bytes> {
 		},
 	}
 }
+
+/// Converts a string or bytes value into a header value.
+/// Returns None for other types, or if the value contains bytes that are not allowed in a header.
+pub fn value_as_header_value(v: Value<'_>) -> Option<::http::HeaderValue> {
+	let v = v.always_materialize_owned();
+	let b = v.as_bytes_pre_materialized().ok()?;
+	::http::HeaderValue::from_bytes(b).ok()
+}
+
+/// Converts a `reject(status, body)` expression result into the response it describes.
+/// Returns None if the value is not a rejection.
+pub fn reject_response(v: &Value<'_>) -> Option<crate::http::Response> {
+	let reject = agent_celx::Reject::from_value(v)?;
+	let status = ::http::StatusCode::from_u16(reject.status).ok()?;
+	::http::Response::builder()
+		.status(status)
+		.header(::http::header::CONTENT_TYPE, "text/plain")
+		.body(crate::http::Body::from(reject.body))
+		.ok()
+}
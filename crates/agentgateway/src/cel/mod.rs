@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize, Serializer};
 use tracing::log::debug;
 pub use types::*;
 
+mod cache;
 mod custom;
 mod helpers;
 mod types;
@@ -27,6 +28,8 @@ pub enum Error {
 	Parses(#[from] ParseErrors),
 	#[error("variable: {0}")]
 	Variable(String),
+	#[error("reference: {0}")]
+	Reference(String),
 	#[error("failed to convert to json")]
 	JsonConvert,
 }
@@ -41,6 +44,7 @@ pub struct Expression {
 	attributes: FlagSet<Attributes>,
 	expression: Program,
 	pub original_expression: String,
+	cache: Option<cache::ResultCache>,
 }
 
 impl Serialize for Expression {
@@ -103,7 +107,16 @@ fn context() -> &'static Context {
 }
 
 pub fn register_custom_functions(definitions: &str) -> Result<(), Error> {
-	custom::register(definitions)
+	custom::register(definitions, None)
+}
+
+/// Sets up the global CEL context with custom functions and an optional time budget for each
+/// evaluation. Like `register_custom_functions`, this must happen before CEL is first used.
+pub fn configure(
+	custom_functions: &str,
+	function_timeout: Option<std::time::Duration>,
+) -> Result<(), Error> {
+	custom::register(custom_functions, function_timeout)
 }
 
 flagset::flags! {
@@ -133,6 +146,7 @@ flagset::flags! {
 		Extproc,
 		Metadata,
 		Proxy,
+		Baggage,
 	}
 }
 
@@ -221,6 +235,7 @@ impl ContextBuilder {
 			|| self.any_has(Attributes::Extauthz)
 			|| self.any_has(Attributes::Extproc)
 			|| self.any_has(Attributes::Metadata)
+			|| self.any_has(Attributes::Baggage)
 		{
 			// TODO: support partial snapshots based on what is requested
 			Some(types::snapshot_request(res, clear))
@@ -319,17 +334,20 @@ impl Expression {
 							.expect("must be valid")
 							.expression,
 						original_expression: expr,
+						cache: None,
 					},
 					Some(err),
 				)
 			},
 		}
 	}
-	/// new_strict compiles the expression, and returns an error if its invalid.
+	/// new_strict compiles the expression, and returns an error if its invalid or references an
+	/// unknown attribute (or an unknown field of a fixed-shape attribute, like `request`).
 	pub fn new_strict(original_expression: impl Into<String>) -> Result<Self, Error> {
 		let original_expression = original_expression.into();
 		let expression =
 			Program::compile_with_optimizer(&original_expression, agent_celx::DefaultOptimizer)?;
+		validate::check_references(&expression.expression().expr).map_err(Error::Reference)?;
 
 		let mut attributes = attributes_for(expression.expression());
 
@@ -344,8 +362,21 @@ impl Expression {
 			attributes,
 			expression,
 			original_expression,
+			cache: None,
 		})
 	}
+
+	/// with_result_cache memoizes the results of this expression, keyed by the values of the
+	/// attributes it references, keeping up to `capacity` results. Impure expressions (those calling
+	/// `random()`, `uuid()`, `variables()` or a custom function) are left uncached.
+	pub fn with_result_cache(mut self, capacity: usize) -> Self {
+		self.cache = cache::ResultCache::new(&self.expression, capacity);
+		self
+	}
+
+	pub fn is_result_cached(&self) -> bool {
+		self.cache.is_some()
+	}
 }
 
 fn attributes_for(expression: &cel::IdedExpr) -> FlagSet<Attributes> {
@@ -410,6 +441,9 @@ fn attributes_for(expression: &cel::IdedExpr) -> FlagSet<Attributes> {
 			["proxy", ..] => {
 				attributes |= Attributes::Proxy;
 			},
+			["baggage", ..] => {
+				attributes |= Attributes::Baggage;
+			},
 			_ => {},
 		}
 	}
@@ -425,3 +459,4 @@ mod tests;
 mod benches;
 mod properties;
 mod query;
+mod validate;
@@ -222,6 +222,51 @@ async fn log_only_response_body_records_without_buffering() {
 	);
 }
 
+#[tokio::test]
+async fn reject_converts_to_response() {
+	let exp = Expression::new_strict(r#"reject(429, "slow down")"#).unwrap();
+	let exec = crate::cel::Executor::new_empty();
+	let v = exec.eval(&exp).unwrap();
+	let resp = reject_response(&v).expect("reject() should produce a response");
+	assert_eq!(resp.status(), http::StatusCode::TOO_MANY_REQUESTS);
+	let body = resp.into_body().collect().await.unwrap().to_bytes();
+	assert_eq!(body.as_ref(), b"slow down");
+
+	let exp = Expression::new_strict("true").unwrap();
+	assert!(reject_response(&exec.eval(&exp).unwrap()).is_none());
+}
+
+#[test]
+fn backend_metadata() {
+	assert_eq!(
+		json!(true),
+		eval(r#"backend.metadata.tier == "premium""#).unwrap()
+	);
+	assert_eq!(
+		json!(false),
+		eval(r#"has(backend.metadata.region)"#).unwrap()
+	);
+}
+
+#[test]
+fn baggage() {
+	assert_eq!(json!("acme"), eval("baggage.tenant").unwrap());
+	assert_eq!(json!(false), eval("has(baggage.internal)").unwrap());
+
+	let mut req = ::http::Request::builder()
+		.method(Method::GET)
+		.uri("http://example.com")
+		.header("baggage", "tenant=acme%20corp")
+		.body(Body::empty())
+		.unwrap();
+	let baggage = crate::telemetry::trc::Baggage::from_headers(req.headers()).unwrap();
+	req.extensions_mut().insert(baggage);
+	assert_eq!(
+		Value::Bool(true),
+		eval_request(r#"baggage.tenant == "acme corp""#, req).unwrap()
+	);
+}
+
 #[test]
 fn list_in() {
 	let expr = "'san' in source.subjectAltNames";
@@ -270,6 +315,25 @@ mod headers {
 		);
 	}
 
+	#[test]
+	fn case_insensitive_accessors() {
+		let mut req = request_with_header_modes();
+		req
+			.headers_mut()
+			.append("set-cookie", "a=1".parse().unwrap());
+		req
+			.headers_mut()
+			.append("set-cookie", "b=2".parse().unwrap());
+		assert_eq!(
+			Value::Bool(true),
+			eval_request(
+				r#"request.headers.get("SINGLE") == 'z' && request.headers.get("Set-Cookie") == 'a=1,b=2' && request.headers.values("Set-Cookie") == ['a=1', 'b=2']"#,
+				req
+			)
+			.unwrap()
+		);
+	}
+
 	#[test]
 	fn redacted() {
 		assert_eq!(
@@ -578,3 +642,76 @@ fn unset_values() {
 		eval_request("has(jwt.sub)", req()).unwrap()
 	);
 }
+
+#[test]
+fn strict_rejects_unknown_references() {
+	let unknown = |expr: &str| {
+		assert!(
+			matches!(Expression::new_strict(expr), Err(Error::Reference(_))),
+			"{expr} should be rejected"
+		)
+	};
+	unknown("reqest.path");
+	unknown("request.pth");
+	unknown(r#"response.headers["x"] == "y" || response.stauts == 200"#);
+	unknown("[1, 2].exists(x, x == requestt.size())");
+	unknown("llm.inputToken > 10");
+
+	for expr in [
+		"request.path",
+		r#"request.headers["x-example"]"#,
+		"response.code == 200",
+		"jwt.sub",
+		"jwt.anyClaim.nested",
+		"metadata.anything",
+		"baggage.anything",
+		"source.address",
+		"llm.inputTokens",
+		"[1, 2].map(x, x * 2)",
+		"{'a': 1}.all(k, k != 'b')",
+		r#"request.path.split("/").with(parts, parts[0])"#,
+		r#"base64.encode(request.path)"#,
+		"type(request.path) == string",
+		"variables()",
+	] {
+		assert!(
+			Expression::new_strict(expr).is_ok(),
+			"{expr} should be accepted"
+		);
+	}
+}
+
+#[test]
+fn result_cache_keys_on_inputs() {
+	let expr = Expression::new_strict(r#"request.headers["x-user"] + ":" + request.path"#)
+		.unwrap()
+		.with_result_cache(8);
+	assert!(expr.is_result_cached());
+	let req = |user: &str, path: &str| {
+		::http::Request::builder()
+			.method(Method::GET)
+			.uri(format!("http://example.com{path}"))
+			.header("x-user", user)
+			.body(Body::empty())
+			.unwrap()
+	};
+	let eval = |req: &crate::http::Request| {
+		let exec = Executor::new_request(req);
+		exec.eval(&expr).unwrap().json().unwrap()
+	};
+
+	assert_eq!(json!("alice:/a"), eval(&req("alice", "/a")));
+	// Repeated inputs hit the cache; changing any input must produce a fresh result.
+	assert_eq!(json!("alice:/a"), eval(&req("alice", "/a")));
+	assert_eq!(json!("bob:/a"), eval(&req("bob", "/a")));
+	assert_eq!(json!("bob:/b"), eval(&req("bob", "/b")));
+	assert_eq!(json!("alice:/a"), eval(&req("alice", "/a")));
+}
+
+#[test]
+fn result_cache_skips_impure_expressions() {
+	for expr in ["random() > 0.5", "uuid()", "variables()"] {
+		let expr = Expression::new_strict(expr).unwrap().with_result_cache(8);
+		assert!(!expr.is_result_cached(), "{expr:?} should not be cached");
+	}
+}
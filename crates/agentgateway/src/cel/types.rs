@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
@@ -33,6 +34,7 @@ use crate::mcp::guardrails::McpGuardrailsDynamicMetadata;
 use crate::mcp::{MCPInfo, MCPTool};
 use crate::proxy::dtrace;
 use crate::serdes::schema;
+use crate::telemetry::trc::Baggage;
 use crate::transport::tls::TlsInfo;
 use crate::{apply, llm};
 
@@ -76,6 +78,8 @@ pub struct Executor<'a> {
 	pub mcp_guardrails: ExtensionOrDirect<'a, McpGuardrailsDynamicMetadata>,
 
 	pub metadata: ExtensionOrDirect<'a, TransformationMetadata>,
+
+	pub baggage: ExtensionOrDirect<'a, Baggage>,
 }
 
 #[apply(schema!)]
@@ -422,6 +426,9 @@ pub struct BackendContext {
 	/// The protocol of backend.
 	#[serde(default)]
 	pub protocol: BackendProtocol,
+	/// Operator-defined metadata attached to the backend, such as its region or tier.
+	#[serde(default)]
+	pub metadata: Arc<HashMap<String, String>>,
 }
 
 #[derive(
@@ -468,6 +475,7 @@ pub enum BackendProtocol {
 	a2a,
 	mcp,
 	llm,
+	grpc,
 }
 
 struct ExecutorResolver<'a> {
@@ -558,6 +566,7 @@ impl<'a> Executor<'a> {
 		self.extproc = ExtensionOrDirect::Extension(ext);
 		self.mcp_guardrails = ExtensionOrDirect::Extension(ext);
 		self.metadata = ExtensionOrDirect::Extension(ext);
+		self.baggage = ExtensionOrDirect::Extension(ext);
 		self.backend = ExtensionOrDirect::Extension(ext);
 		self.proxy = ExtensionOrDirect::Extension(ext);
 		self.source = ExtensionOrDirect::Extension(ext);
@@ -573,6 +582,7 @@ impl<'a> Executor<'a> {
 		self.extproc = ExtensionOrDirect::Direct(req.extproc.as_ref());
 		self.mcp_guardrails = ExtensionOrDirect::Direct(req.mcp_guardrails.as_ref());
 		self.metadata = ExtensionOrDirect::Direct(req.metadata.as_ref());
+		self.baggage = ExtensionOrDirect::Direct(req.baggage.as_ref());
 		self.backend = ExtensionOrDirect::Direct(req.backend.as_ref());
 		self.proxy = ExtensionOrDirect::Direct(req.proxy.as_ref());
 		self.source = ExtensionOrDirect::Direct(req.source.as_ref());
@@ -706,10 +716,38 @@ impl<'a> Executor<'a> {
 		resolver.slow_debug()
 	}
 
+	/// Resolves an attribute path for a result cache key, stopping at the deepest value reachable.
+	fn lookup_path(&'a self, path: &[String]) -> Value<'a> {
+		let Some((root, rest)) = path.split_first() else {
+			return Value::Null;
+		};
+		let mut value = self.field(root).unwrap_or(Value::Null);
+		for segment in rest {
+			let Value::Dynamic(d) = &value else {
+				break;
+			};
+			value = d.field(segment).unwrap_or(Value::Null);
+		}
+		value
+	}
+
 	pub fn eval(&'a self, expr: &'a Expression) -> Result<Value<'a>, Error> {
+		let cache_key = expr
+			.cache
+			.as_ref()
+			.and_then(|cache| cache.key(|path| self.lookup_path(path)));
+		if let Some(cache) = &expr.cache
+			&& let Some(key) = cache_key
+			&& let Some(hit) = cache.get(key)
+		{
+			return Ok(hit);
+		}
 		let resolver = ExecutorResolver { executor: self };
 		let start = dtrace::timed_start();
-		let res = Value::resolve(expr.expression.expression(), context(), &resolver);
+		let res = {
+			let _deadline = context().start_evaluation();
+			Value::resolve(expr.expression.expression(), context(), &resolver)
+		};
 		dtrace::trace(|t| {
 			t.cel_eval(
 				start,
@@ -723,6 +761,9 @@ impl<'a> Executor<'a> {
 					.unwrap_or_else(|e| json!({"error": e.to_string()})),
 			)
 		});
+		if let (Some(cache), Some(key), Ok(v)) = (&expr.cache, cache_key, &res) {
+			cache.insert(key, v);
+		}
 		match res {
 			Ok(v) => Ok(v),
 			Err(e) => {
@@ -804,8 +845,10 @@ pub fn snapshot_request(req: &mut crate::http::Request, clear: bool) -> RequestS
 		extproc: ext::<ExtProcDynamicMetadata>(req, clear),
 		mcp_guardrails: ext::<McpGuardrailsDynamicMetadata>(req, clear),
 		metadata: ext::<TransformationMetadata>(req, clear),
+		baggage: ext::<Baggage>(req, clear),
 		llm: ext::<LLMContext>(req, clear),
 		start_time: ext::<RequestTime>(req, clear),
+		id: ext::<RequestIdValue>(req, clear),
 	}
 }
 
@@ -856,10 +899,13 @@ pub struct RequestSnapshot {
 
 	pub start_time: Option<RequestTime>,
 
+	pub id: Option<RequestIdValue>,
+
 	pub extauthz: Option<ExtAuthzDynamicMetadata>,
 	pub extproc: Option<ExtProcDynamicMetadata>,
 	pub mcp_guardrails: Option<McpGuardrailsDynamicMetadata>,
 	pub metadata: Option<TransformationMetadata>,
+	pub baggage: Option<Baggage>,
 
 	pub llm: Option<LLMContext>,
 }
@@ -905,6 +951,10 @@ pub struct RequestRef<'a> {
 
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub end_time: Option<&'a RequestTime>,
+
+	/// The request ID, when a request ID policy is applied.
+	#[serde(skip_serializing_if = "is_extension_or_direct_none")]
+	pub id: ExtensionOrDirect<'a, RequestIdValue>,
 }
 
 #[derive(Debug, Clone)]
@@ -1004,6 +1054,9 @@ pub struct RequestRefSerde {
 	/// The time the request completed
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub end_time: Option<RequestTime>,
+	/// The ID of the request, from the `x-request-id` header or generated by the request ID policy.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<RequestIdValue>,
 }
 
 #[apply(schema!)]
@@ -1050,6 +1103,7 @@ impl<'a> From<&'a RequestSnapshot> for RequestRef<'a> {
 			},
 			start_time: value.start_time.as_ref().into(),
 			end_time: None,
+			id: value.id.as_ref().into(),
 		}
 	}
 }
@@ -1068,6 +1122,7 @@ impl<'a, B> From<&'a ::http::Request<B>> for RequestRef<'a> {
 			start_time: req.extensions().into(),
 			// Only known in snapshot phase...
 			end_time: None,
+			id: req.extensions().into(),
 		}
 	}
 }
@@ -1229,6 +1284,19 @@ impl DynamicType for RequestTime {
 	}
 }
 
+/// The ID of a request, as set by the request ID policy.
+#[apply(schema!)]
+pub struct RequestIdValue(pub Strng);
+
+impl DynamicType for RequestIdValue {
+	fn auto_materialize(&self) -> bool {
+		true
+	}
+	fn materialize(&self) -> Value<'_> {
+		Value::String(self.0.as_str().into())
+	}
+}
+
 impl PartialEq for RequestRef<'_> {
 	fn eq(&self, _: &Self) -> bool {
 		// Currently do not allow comparisons
@@ -1919,6 +1987,10 @@ pub struct ExecutorSerde {
 	/// `metadata` contains values set by transformation metadata expressions.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub metadata: Option<TransformationMetadata>,
+
+	/// `baggage` contains the W3C baggage entries received on the request, keyed by name.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub baggage: Option<Baggage>,
 }
 
 impl ExecutorSerde {
@@ -1974,6 +2046,7 @@ impl ExecutorSerde {
 				},
 				start_time: ExtensionOrDirect::Direct(req.start_time.as_ref()),
 				end_time: req.end_time.as_ref(),
+				id: ExtensionOrDirect::Direct(req.id.as_ref()),
 			});
 		}
 
@@ -2004,6 +2077,7 @@ impl ExecutorSerde {
 		exec.extproc = ExtensionOrDirect::Direct(self.extproc.as_ref());
 		exec.mcp_guardrails = ExtensionOrDirect::Direct(self.mcp_guardrails.as_ref());
 		exec.metadata = ExtensionOrDirect::Direct(self.metadata.as_ref());
+		exec.baggage = ExtensionOrDirect::Direct(self.baggage.as_ref());
 		exec.mcp = self.mcp.as_ref();
 
 		exec
@@ -2035,6 +2109,7 @@ pub fn full_example_executor() -> ExecutorSerde {
 			end_time: Some(RequestTime(
 				chrono::DateTime::parse_from_rfc3339("2000-01-01T12:00:01.12345678Z").unwrap(),
 			)),
+			id: Some(RequestIdValue(strng::literal!("req-1234"))),
 		}),
 		response: Some(ResponseRefSerde {
 			code: 200,
@@ -2177,11 +2252,13 @@ pub fn full_example_executor() -> ExecutorSerde {
 			name: "my-backend".into(),
 			backend_type: BackendType::Service,
 			protocol: BackendProtocol::http,
+			metadata: Arc::new(HashMap::from([("tier".to_string(), "premium".to_string())])),
 		}),
 		extauthz: Some(ExtAuthzDynamicMetadata::default()),
 		extproc: Some(ExtProcDynamicMetadata::default()),
 		mcp_guardrails: Some(McpGuardrailsDynamicMetadata::default()),
 		metadata: Some(TransformationMetadata::default()),
+		baggage: Some(Baggage([("tenant".to_string(), "acme".to_string())].into())),
 	}
 }
 
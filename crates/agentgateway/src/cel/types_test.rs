@@ -43,6 +43,7 @@ fn build_test_request() -> crate::http::Request {
 		name: "test-backend".into(),
 		backend_type: BackendType::Service,
 		protocol: BackendProtocol::http,
+		metadata: Default::default(),
 	};
 	req.extensions_mut().insert(backend);
 	req.extensions_mut().insert(ProxyContext {
@@ -0,0 +1,123 @@
+use cel::common::ast::{EntryExpr, Expr};
+
+use super::types::{
+	BackendContext, DestinationContext, EnvContext, Executor, LLMContext, ProxyContext, RequestRef,
+	ResponseRef,
+};
+use crate::mcp::MCPInfo;
+
+/// Fields of attributes with a fixed shape. Other attributes, such as `jwt` or `metadata`, hold
+/// arbitrary user data (or flatten it in, like `source`), so only their name is checked.
+fn known_fields(attribute: &str) -> Option<&'static [&'static str]> {
+	match attribute {
+		"request" => Some(RequestRef::DYNAMIC_FIELDS),
+		"response" => Some(ResponseRef::DYNAMIC_FIELDS),
+		"destination" => Some(DestinationContext::DYNAMIC_FIELDS),
+		"backend" => Some(BackendContext::DYNAMIC_FIELDS),
+		"env" => Some(EnvContext::DYNAMIC_FIELDS),
+		"llm" => Some(LLMContext::DYNAMIC_FIELDS),
+		"mcp" => Some(MCPInfo::DYNAMIC_FIELDS),
+		"proxy" => Some(ProxyContext::DYNAMIC_FIELDS),
+		_ => None,
+	}
+}
+
+/// Returns an error for the first reference to an unknown attribute, or to an unknown field of a
+/// fixed-shape attribute, so typos like `reqest.path` fail at compile time instead of evaluation.
+pub(super) fn check_references(expr: &Expr) -> Result<(), String> {
+	check(expr, &mut Vec::new())
+}
+
+fn check<'e>(expr: &'e Expr, scope: &mut Vec<&'e str>) -> Result<(), String> {
+	match expr {
+		Expr::Ident(name) => check_attribute(name, scope),
+		Expr::Select(select) => match &select.operand.expr {
+			Expr::Ident(name) if !is_local(name, scope) => {
+				check_attribute(name, scope)?;
+				match known_fields(name) {
+					Some(fields) if !fields.contains(&select.field.as_str()) => {
+						Err(format!("unknown field '{}' of '{name}'", select.field))
+					},
+					_ => Ok(()),
+				}
+			},
+			operand => check(operand, scope),
+		},
+		Expr::Call(call) => {
+			if let Some(target) = &call.target {
+				// A bare identifier target may be the namespace of a qualified function, such as
+				// `base64.encode()`, so it isn't required to be an attribute.
+				if !matches!(target.expr, Expr::Ident(_)) {
+					check(&target.expr, scope)?;
+				}
+			}
+			// These bind their first argument as a variable within the second.
+			if matches!(call.func_name.as_str(), "with" | "mapValues" | "filterKeys")
+				&& let [ident, body] = call.args.as_slice()
+				&& let Expr::Ident(ident) = &ident.expr
+			{
+				scope.push(ident);
+				let res = check(&body.expr, scope);
+				scope.pop();
+				return res;
+			}
+			call.args.iter().try_for_each(|arg| check(&arg.expr, scope))
+		},
+		Expr::Comprehension(comprehension) => {
+			check(&comprehension.iter_range.expr, scope)?;
+			check(&comprehension.accu_init.expr, scope)?;
+			let depth = scope.len();
+			scope.push(&comprehension.iter_var);
+			if let Some(iter_var2) = &comprehension.iter_var2 {
+				scope.push(iter_var2);
+			}
+			scope.push(&comprehension.accu_var);
+			let res = [
+				&comprehension.loop_cond,
+				&comprehension.loop_step,
+				&comprehension.result,
+			]
+			.into_iter()
+			.try_for_each(|e| check(&e.expr, scope));
+			scope.truncate(depth);
+			res
+		},
+		Expr::List(list) => list.elements.iter().try_for_each(|e| check(&e.expr, scope)),
+		Expr::Map(map) => map
+			.entries
+			.iter()
+			.try_for_each(|e| check_entry(&e.expr, scope)),
+		Expr::Struct(st) => st
+			.entries
+			.iter()
+			.try_for_each(|e| check_entry(&e.expr, scope)),
+		Expr::Optimized { original, .. } => check(&original.expr, scope),
+		Expr::Literal(_) | Expr::Inline(_) | Expr::Unspecified => Ok(()),
+	}
+}
+
+fn check_entry<'e>(entry: &'e EntryExpr, scope: &mut Vec<&'e str>) -> Result<(), String> {
+	match entry {
+		EntryExpr::StructField(field) => check(&field.value.expr, scope),
+		EntryExpr::MapEntry(entry) => {
+			check(&entry.key.expr, scope)?;
+			check(&entry.value.expr, scope)
+		},
+	}
+}
+
+fn check_attribute(name: &str, scope: &[&str]) -> Result<(), String> {
+	if is_local(name, scope)
+		|| Executor::DYNAMIC_FIELDS.contains(&name)
+		|| cel::objects::standard_type(name).is_some()
+	{
+		Ok(())
+	} else {
+		Err(format!("unknown attribute '{name}'"))
+	}
+}
+
+fn is_local(name: &str, scope: &[&str]) -> bool {
+	// Macro expansions use '@'-prefixed internal variables
+	name.starts_with('@') || scope.contains(&name)
+}
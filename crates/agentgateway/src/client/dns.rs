@@ -8,9 +8,10 @@ use anyhow::anyhow;
 use arc_swap::ArcSwapOption;
 use hickory_resolver::TokioResolver;
 use hickory_resolver::config::{ResolverConfig, ResolverOpts};
-use hickory_resolver::net::NetError;
 use hickory_resolver::net::runtime::TokioRuntimeProvider;
 
+use super::doh::DohResolver;
+use crate::metrics::{DnsCacheLabels, DnsCacheResult, Metrics};
 use crate::*;
 
 const ERROR_BACKOFF_MAX: Duration = Duration::from_secs(5);
@@ -41,10 +42,49 @@ impl<T: Copy> CircularBuffer<T> {
 	}
 }
 
+/// The network of a downstream client, sent as an EDNS Client Subnet (RFC 7871).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ClientSubnet {
+	/// The client address, with all bits past the prefix cleared.
+	pub addr: IpAddr,
+	pub prefix_len: u8,
+}
+
+impl ClientSubnet {
+	fn new(client: IpAddr, cfg: &super::ClientSubnetConfig) -> Self {
+		match client.to_canonical() {
+			IpAddr::V4(ip) => {
+				let prefix_len = cfg.ipv4_prefix_length.min(32);
+				let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+				ClientSubnet {
+					addr: IpAddr::V4((u32::from(ip) & mask).into()),
+					prefix_len,
+				}
+			},
+			IpAddr::V6(ip) => {
+				let prefix_len = cfg.ipv6_prefix_length.min(128);
+				let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+				ClientSubnet {
+					addr: IpAddr::V6((u128::from(ip) & mask).into()),
+					prefix_len,
+				}
+			},
+		}
+	}
+}
+
+// Entries are cached per client subnet, since answers may be tailored to it.
+type CacheKey = (Strng, Option<ClientSubnet>);
+
 #[derive(Debug, Clone)]
 pub struct CachedResolver {
 	dns: Arc<Resolver>,
-	entries: Arc<Mutex<HashMap<Strng, Arc<CacheEntry>>>>,
+	entries: Arc<Mutex<HashMap<CacheKey, Arc<CacheEntry>>>>,
+	// negative_ttl is the minimum time a failed lookup is cached before the name is queried again
+	negative_ttl: Duration,
+	// client_subnet, when set, sends the client's subnet with queries
+	client_subnet: Option<super::ClientSubnetConfig>,
+	metrics: Option<Arc<Metrics>>,
 }
 
 #[derive(Debug)]
@@ -59,9 +99,10 @@ pub struct CacheEntry {
 impl CacheEntry {
 	async fn background(
 		&self,
-		name: Strng,
+		key: CacheKey,
 		resolver: Arc<Resolver>,
-		cache: Arc<Mutex<HashMap<Strng, Arc<CacheEntry>>>>,
+		cache: Arc<Mutex<HashMap<CacheKey, Arc<CacheEntry>>>>,
+		negative_ttl: Duration,
 	) {
 		self.active.store(true, Ordering::Relaxed);
 
@@ -74,11 +115,12 @@ impl CacheEntry {
 				// We are done; no one requested this.
 				// Remove the cache entry if there is one.
 				if let Ok(mut cache) = cache.lock() {
-					cache.remove(&name);
+					cache.remove(&key);
 				}
 				return;
 			}
-			let (next_refresh, respect_small_value) = match resolver.resolve(name.as_str()).await {
+			let (name, subnet) = &key;
+			let (next_refresh, respect_small_value) = match resolver.resolve(name, *subnet).await {
 				Ok((ips, expiry)) => {
 					let cb = CircularBuffer::new(ips);
 					self.entries.store(Some(Arc::new(cb)));
@@ -95,7 +137,9 @@ impl CacheEntry {
 					debug!("resolution failed: {e:?}");
 
 					backoff = std::cmp::min(backoff * 2, ERROR_BACKOFF_MAX);
-					(Instant::now() + backoff, true)
+					// Remember the failure for at least the negative TTL, so a bad hostname is not
+					// re-queried on every request.
+					(Instant::now() + std::cmp::max(backoff, negative_ttl), true)
 				},
 			};
 			// NB: this will run even on error, so the first fetch for a failed response will hit this and
@@ -128,47 +172,89 @@ impl CacheEntry {
 #[allow(clippy::large_enum_variant)]
 enum Resolver {
 	Real(TokioResolver),
+	Doh {
+		doh: DohResolver,
+		// fallback is used when the DoH endpoint fails, if configured
+		fallback: Option<Box<Resolver>>,
+	},
 	#[cfg(test)]
 	Mock(Arc<tests::Mock>),
 }
 
 impl Resolver {
-	async fn resolve(&self, host: &str) -> Result<(Box<[IpAddr]>, Instant), NetError> {
+	/// Resolves `host`. The client subnet is only sent by resolvers that support it.
+	async fn resolve(
+		&self,
+		host: &str,
+		subnet: Option<ClientSubnet>,
+	) -> anyhow::Result<(Box<[IpAddr]>, Instant)> {
 		match self {
-			Resolver::Real(resolver) => resolver.lookup_ip(host).await.map(|lookup| {
+			Resolver::Real(resolver) => Ok(resolver.lookup_ip(host).await.map(|lookup| {
 				let expiry = lookup.valid_until();
 				let ips = lookup.iter().collect::<Box<_>>();
 				(ips, expiry)
-			}),
+			})?),
+			Resolver::Doh { doh, fallback } => match (doh.lookup_ip(host, subnet).await, fallback) {
+				(Err(e), Some(fallback)) => {
+					debug!(%host, "DNS-over-HTTPS lookup failed, using the system resolver: {e:#}");
+					Box::pin(fallback.resolve(host, subnet)).await
+				},
+				(res, _) => res,
+			},
 			#[cfg(test)]
-			Resolver::Mock(resolver) => resolver.resolve(host).await,
+			Resolver::Mock(resolver) => Ok(resolver.resolve(host).await?),
 		}
 	}
 }
 
 impl CachedResolver {
-	pub fn new(config: ResolverConfig, mut opts: ResolverOpts) -> Self {
-		// always consult the system's /etc/hosts file when resolving hostnames
-		opts.use_hosts_file = hickory_resolver::config::ResolveHosts::Always;
-		let mut rb =
-			hickory_resolver::Resolver::builder_with_config(config, TokioRuntimeProvider::default());
-		*rb.options_mut() = opts;
-		let dns_resolver = rb.build().expect("dns resolver config should be valid");
+	/// `doh_client` sends DNS-over-HTTPS queries, and is required when `cfg.doh` is set.
+	pub fn new(
+		cfg: &super::Config,
+		doh_client: Option<super::Client>,
+		metrics: Option<Arc<Metrics>>,
+	) -> Self {
+		let system = || {
+			Resolver::Real(system_resolver(
+				cfg.resolver_cfg.clone(),
+				cfg.resolver_opts.clone(),
+			))
+		};
+		let dns = match (&cfg.doh, doh_client) {
+			(Some(doh), Some(client)) => Resolver::Doh {
+				doh: DohResolver::new(doh.url.clone(), cfg.resolver_opts.ip_strategy, client),
+				fallback: doh.fallback_to_system.then(|| Box::new(system())),
+			},
+			_ => system(),
+		};
 		CachedResolver {
 			entries: Arc::new(Mutex::new(HashMap::new())),
-			dns: Arc::new(Resolver::Real(dns_resolver)),
+			dns: Arc::new(dns),
+			negative_ttl: cfg.negative_ttl,
+			client_subnet: cfg.client_subnet.clone(),
+			metrics,
 		}
 	}
 
-	pub async fn resolve(&self, name: Strng) -> anyhow::Result<IpAddr> {
+	/// Resolves `name` to the next of its addresses. `client` is the downstream client address,
+	/// sent as a client subnet when enabled.
+	pub async fn resolve(&self, name: Strng, client: Option<IpAddr>) -> anyhow::Result<IpAddr> {
+		let subnet = self
+			.client_subnet
+			.as_ref()
+			.zip(client)
+			.map(|(cfg, client)| ClientSubnet::new(client, cfg));
+		let key = (name, subnet);
 		// Check if we already have an entry
-		let entry = {
+		let (entry, cached) = {
 			let mut cache = self.entries.lock().unwrap();
-			let existing_entry = cache.get(&name).cloned();
+			let existing_entry = cache.get(&key).cloned();
 			if let Some(entry) = existing_entry {
 				// Mark as active and return next IP
 				entry.active.store(true, Ordering::Relaxed);
-				entry
+				// An entry with no result yet is still waiting on its first lookup
+				let cached = entry.entries.load().is_some();
+				(entry, cached)
 			} else {
 				let entry = Arc::new(CacheEntry {
 					active: AtomicBool::new(false),
@@ -177,25 +263,46 @@ impl CachedResolver {
 					background_task: Default::default(),
 				});
 
-				cache.insert(name.clone(), entry.clone());
+				cache.insert(key.clone(), entry.clone());
 				// Start background task
 				let bg_entry = entry.clone();
 				let dns = self.dns.clone();
 				let cache = self.entries.clone();
+				let negative_ttl = self.negative_ttl;
 				let handle = tokio::task::spawn(async move {
-					bg_entry.background(name, dns, cache).await;
+					bg_entry.background(key, dns, cache, negative_ttl).await;
 				});
 				entry.background_task.store(Some(Arc::new(handle)));
 
-				entry
+				(entry, false)
 			}
 		};
 
 		// Return next IP
-		entry.next().await.ok_or_else(|| anyhow!("no ip"))
+		let ip = entry.next().await;
+		if let Some(m) = &self.metrics {
+			let result = match (cached, ip) {
+				(false, _) => DnsCacheResult::Miss,
+				(true, Some(_)) => DnsCacheResult::Hit,
+				(true, None) => DnsCacheResult::Negative,
+			};
+			m.dns_cache_lookups
+				.get_or_create(&DnsCacheLabels { result })
+				.inc();
+		}
+		ip.ok_or_else(|| anyhow!("no ip"))
 	}
 }
 
+fn system_resolver(config: ResolverConfig, mut opts: ResolverOpts) -> TokioResolver {
+	// always consult the system's /etc/hosts file when resolving hostnames
+	opts.use_hosts_file = hickory_resolver::config::ResolveHosts::Always;
+	let mut rb =
+		hickory_resolver::Resolver::builder_with_config(config, TokioRuntimeProvider::default());
+	*rb.options_mut() = opts;
+	rb.build().expect("dns resolver config should be valid")
+}
+
 async fn sleep_until_expired(valid_until: Instant, respect_small_value: bool) {
 	const MINIMUM_TTL: Duration = Duration::from_secs(5);
 	let minimum = Instant::now() + MINIMUM_TTL;
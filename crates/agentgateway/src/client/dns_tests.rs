@@ -1,8 +1,10 @@
 use std::net::Ipv4Addr;
+use std::sync::atomic::AtomicUsize;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use assert_matches::assert_matches;
+use hickory_resolver::net::NetError;
 
 use super::*;
 
@@ -10,12 +12,14 @@ use super::*;
 pub(super) struct Mock {
 	#[allow(clippy::type_complexity)]
 	responses: Mutex<HashMap<String, (Box<[IpAddr]>, Instant)>>,
+	queries: AtomicUsize,
 }
 
 impl Mock {
 	pub fn new() -> Self {
 		Self {
 			responses: Mutex::new(HashMap::new()),
+			queries: AtomicUsize::new(0),
 		}
 	}
 
@@ -30,7 +34,12 @@ impl Mock {
 		responses.remove(host);
 	}
 
+	pub fn queries(&self) -> usize {
+		self.queries.load(Ordering::Relaxed)
+	}
+
 	pub async fn resolve(&self, host: &str) -> Result<(Box<[IpAddr]>, Instant), NetError> {
+		self.queries.fetch_add(1, Ordering::Relaxed);
 		let responses = self.responses.lock().unwrap();
 		responses
 			.get(host)
@@ -51,17 +60,20 @@ async fn test_basic_resolution() {
 	let resolver = CachedResolver {
 		dns: Arc::new(Resolver::Mock(mock)),
 		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
 	};
 
 	// First resolution should work
-	let ip1 = resolver.resolve("example.com".into()).await.unwrap();
-	let ip2 = resolver.resolve("example.com".into()).await.unwrap();
+	let ip1 = resolver.resolve("example.com".into(), None).await.unwrap();
+	let ip2 = resolver.resolve("example.com".into(), None).await.unwrap();
 
 	// Should get different IPs due to round-robin
 	assert_ne!(ip1, ip2);
 
 	// Third call should cycle back to first IP
-	let ip3 = resolver.resolve("example.com".into()).await.unwrap();
+	let ip3 = resolver.resolve("example.com".into(), None).await.unwrap();
 	assert_eq!(ip1, ip3);
 }
 
@@ -74,23 +86,32 @@ async fn test_ip_change() {
 	let resolver = CachedResolver {
 		dns: Arc::new(Resolver::Mock(mock.clone())),
 		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
 	};
 
 	// First resolution should work
-	let _ = resolver.resolve("example.com".into()).await.unwrap();
-	let _ = resolver.resolve("example.com".into()).await.unwrap();
+	let _ = resolver.resolve("example.com".into(), None).await.unwrap();
+	let _ = resolver.resolve("example.com".into(), None).await.unwrap();
 	mock.add_response("example.com", vec![IP3], 60);
 	tokio::time::sleep(Duration::from_secs(30)).await;
 	// Only some time has passed, cycle back to the first one
-	assert_eq!(resolver.resolve("example.com".into()).await.unwrap(), IP1);
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP1
+	);
 	tokio::time::sleep(Duration::from_secs(31)).await;
 
 	// Now we should refresh and get new IPs
-	assert_eq!(resolver.resolve("example.com".into()).await.unwrap(), IP3);
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP3
+	);
 
 	mock.add_response("example.com", vec![], 60);
 	tokio::time::sleep(Duration::from_secs(61)).await;
-	assert_matches!(resolver.resolve("example.com".into()).await, Err(_));
+	assert_matches!(resolver.resolve("example.com".into(), None).await, Err(_));
 }
 
 #[tokio::test(start_paused = true)]
@@ -101,18 +122,24 @@ async fn test_ip_error() {
 	let resolver = CachedResolver {
 		dns: Arc::new(Resolver::Mock(mock.clone())),
 		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
 	};
 
 	// We should get an error, no IPs yet
-	assert_matches!(resolver.resolve("example.com".into()).await, Err(_));
-	assert_matches!(resolver.resolve("example.com".into()).await, Err(_));
+	assert_matches!(resolver.resolve("example.com".into(), None).await, Err(_));
+	assert_matches!(resolver.resolve("example.com".into(), None).await, Err(_));
 	// Even once the error is resolved we should not immediately get success
 	mock.add_response("example.com", vec![IP3], 60);
-	assert_matches!(resolver.resolve("example.com".into()).await, Err(_));
+	assert_matches!(resolver.resolve("example.com".into(), None).await, Err(_));
 	// But once the retry occurs we will get it
 	tokio::time::sleep(ERROR_BACKOFF_BASE + Duration::from_secs(1)).await;
 	// Now we should get the IP
-	assert_eq!(resolver.resolve("example.com".into()).await.unwrap(), IP3);
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP3
+	);
 }
 
 #[tokio::test(start_paused = true)]
@@ -124,16 +151,161 @@ async fn test_ip_error_retains_last_known_good() {
 	let resolver = CachedResolver {
 		dns: Arc::new(Resolver::Mock(mock.clone())),
 		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
 	};
 
-	assert_eq!(resolver.resolve("example.com".into()).await.unwrap(), IP1);
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP1
+	);
 	tokio::time::sleep(Duration::from_secs(30)).await;
-	assert_eq!(resolver.resolve("example.com".into()).await.unwrap(), IP1);
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP1
+	);
 	mock.remove_response("example.com");
 	tokio::time::sleep(Duration::from_secs(30)).await;
 	tokio::task::yield_now().await;
 
-	assert_eq!(resolver.resolve("example.com".into()).await.unwrap(), IP1);
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP1
+	);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_negative_cache() {
+	agent_core::telemetry::testing::setup_test_logging();
+	let mock = Arc::new(Mock::new());
+	let mut registry = prometheus_client::registry::Registry::default();
+	let metrics = Arc::new(Metrics::new(&mut registry, Default::default()));
+
+	let resolver = CachedResolver {
+		dns: Arc::new(Resolver::Mock(mock.clone())),
+		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::from_secs(10),
+		client_subnet: None,
+		metrics: Some(metrics.clone()),
+	};
+	let lookups = |result| {
+		metrics
+			.dns_cache_lookups
+			.get_or_create(&DnsCacheLabels { result })
+			.get()
+	};
+
+	assert_matches!(resolver.resolve("example.com".into(), None).await, Err(_));
+	assert_eq!(mock.queries(), 1);
+	assert_eq!(lookups(DnsCacheResult::Miss), 1);
+
+	// Within the negative TTL, the failure is served from the cache even once the name resolves.
+	mock.add_response("example.com", vec![IP1], 60);
+	tokio::time::sleep(Duration::from_secs(5)).await;
+	assert_matches!(resolver.resolve("example.com".into(), None).await, Err(_));
+	assert_eq!(mock.queries(), 1);
+	assert_eq!(lookups(DnsCacheResult::Negative), 1);
+
+	// After the TTL, the name is queried again.
+	tokio::time::sleep(Duration::from_secs(6)).await;
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP1
+	);
+	assert_eq!(mock.queries(), 2);
+	assert_eq!(lookups(DnsCacheResult::Hit), 1);
+}
+
+#[tokio::test]
+async fn test_doh_fallback_to_system() {
+	let server = wiremock::MockServer::start().await;
+	wiremock::Mock::given(wiremock::matchers::method("POST"))
+		.respond_with(wiremock::ResponseTemplate::new(503))
+		.mount(&server)
+		.await;
+	let mock = Arc::new(Mock::new());
+	mock.add_response("example.com", vec![IP3], 60);
+
+	let doh_resolver = |fallback: Option<Box<Resolver>>| CachedResolver {
+		dns: Arc::new(Resolver::Doh {
+			doh: DohResolver::new(
+				server.uri(),
+				hickory_resolver::config::LookupIpStrategy::Ipv4Only,
+				super::super::Client::new(
+					&super::super::Config {
+						resolver_cfg: ResolverConfig::default(),
+						resolver_opts: ResolverOpts::default(),
+						negative_ttl: super::super::DEFAULT_DNS_NEGATIVE_TTL,
+						doh: None,
+						client_subnet: None,
+					},
+					None,
+					Default::default(),
+					None,
+				),
+			),
+			fallback,
+		}),
+		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
+	};
+
+	let resolver = doh_resolver(None);
+	assert_matches!(resolver.resolve("example.com".into(), None).await, Err(_));
+
+	let resolver = doh_resolver(Some(Box::new(Resolver::Mock(mock.clone()))));
+	assert_eq!(
+		resolver.resolve("example.com".into(), None).await.unwrap(),
+		IP3
+	);
+	assert_eq!(mock.queries(), 1);
+}
+
+#[tokio::test]
+async fn test_client_subnet_cache_keys() {
+	let mock = Arc::new(Mock::new());
+	mock.add_response("example.com", vec![IP1], 60);
+
+	let resolver = CachedResolver {
+		dns: Arc::new(Resolver::Mock(mock.clone())),
+		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: Some(crate::client::ClientSubnetConfig {
+			ipv4_prefix_length: 24,
+			ipv6_prefix_length: 56,
+		}),
+		metrics: None,
+	};
+
+	let client = |a, b, c, d| Some(IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+	// Clients in different subnets each get their own entry.
+	let _ = resolver
+		.resolve("example.com".into(), client(10, 0, 1, 1))
+		.await
+		.unwrap();
+	let _ = resolver
+		.resolve("example.com".into(), client(10, 0, 2, 1))
+		.await
+		.unwrap();
+	assert_eq!(mock.queries(), 2);
+	// Clients in the same subnet share one.
+	let _ = resolver
+		.resolve("example.com".into(), client(10, 0, 1, 200))
+		.await
+		.unwrap();
+	assert_eq!(mock.queries(), 2);
+
+	let entries = resolver.entries.lock().unwrap();
+	assert_eq!(entries.len(), 2);
+	let subnet = ClientSubnet {
+		addr: IpAddr::V4(Ipv4Addr::new(10, 0, 1, 0)),
+		prefix_len: 24,
+	};
+	let key: CacheKey = ("example.com".into(), Some(subnet));
+	assert!(entries.contains_key(&key));
 }
 
 #[tokio::test]
@@ -145,10 +317,13 @@ async fn test_multiple_hostnames() {
 	let resolver = CachedResolver {
 		dns: Arc::new(Resolver::Mock(mock)),
 		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
 	};
 
-	let ip1 = resolver.resolve("host1.com".into()).await.unwrap();
-	let ip2 = resolver.resolve("host2.com".into()).await.unwrap();
+	let ip1 = resolver.resolve("host1.com".into(), None).await.unwrap();
+	let ip2 = resolver.resolve("host2.com".into(), None).await.unwrap();
 
 	assert_eq!(ip1, Ipv4Addr::new(192, 168, 1, 1));
 	assert_eq!(ip2, Ipv4Addr::new(192, 168, 1, 2));
@@ -162,9 +337,12 @@ async fn test_resolution_failure() {
 	let resolver = CachedResolver {
 		dns: Arc::new(Resolver::Mock(mock)),
 		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
 	};
 
-	let result = resolver.resolve("nonexistent.com".into()).await;
+	let result = resolver.resolve("nonexistent.com".into(), None).await;
 	assert!(result.is_err());
 }
 
@@ -176,13 +354,16 @@ async fn test_concurrent_resolution() {
 	let resolver = Arc::new(CachedResolver {
 		dns: Arc::new(Resolver::Mock(mock)),
 		entries: Arc::new(Mutex::new(HashMap::new())),
+		negative_ttl: Duration::ZERO,
+		client_subnet: None,
+		metrics: None,
 	});
 
 	// Spawn multiple concurrent resolutions
 	let handles: Vec<_> = (0..10)
 		.map(|_| {
 			let resolver = resolver.clone();
-			tokio::spawn(async move { resolver.resolve("example.com".into()).await })
+			tokio::spawn(async move { resolver.resolve("example.com".into(), None).await })
 		})
 		.collect();
 
@@ -0,0 +1,217 @@
+//! DNS-over-HTTPS (RFC 8484) lookups.
+//!
+//! Queries are sent as DNS wire-format messages in the body of a POST to the configured endpoint.
+//! Only the address records needed to connect to backends are requested and read from the answer.
+
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, bail};
+use hickory_proto::op::{Edns, Message, MessageType, OpCode, Query, ResponseCode};
+use hickory_proto::rr::rdata::opt::{ClientSubnet as EdnsClientSubnet, EdnsOption};
+use hickory_proto::rr::{Name, RData, RecordType};
+use hickory_proto::serialize::binary::BinEncodable;
+use hickory_resolver::config::LookupIpStrategy;
+use tracing::debug;
+
+use super::dns::ClientSubnet;
+use crate::http::filters::BackendRequestTimeout;
+
+#[cfg(test)]
+#[path = "doh_tests.rs"]
+mod tests;
+
+const DNS_MESSAGE: &str = "application/dns-message";
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// DNS messages are at most 64 KiB.
+const RESPONSE_LIMIT: usize = 65_535;
+/// The UDP payload size advertised in the OPT record, per the DNS flag day 2020 recommendation.
+const EDNS_PAYLOAD_SIZE: u16 = 1232;
+
+#[derive(Debug)]
+pub struct DohResolver {
+	url: String,
+	client: super::Client,
+	strategy: LookupIpStrategy,
+}
+
+impl DohResolver {
+	/// `client` sends the queries. It must not itself resolve hostnames through this resolver.
+	pub fn new(url: String, strategy: LookupIpStrategy, client: super::Client) -> Self {
+		Self {
+			url,
+			client,
+			strategy,
+		}
+	}
+
+	/// Looks up the addresses of `host`, following the configured IP lookup strategy. Returns the
+	/// addresses along with when the answer expires.
+	pub async fn lookup_ip(
+		&self,
+		host: &str,
+		subnet: Option<ClientSubnet>,
+	) -> anyhow::Result<(Box<[IpAddr]>, Instant)> {
+		let answer = match self.strategy {
+			LookupIpStrategy::Ipv4Only => self.query(host, RecordType::A, subnet).await?,
+			LookupIpStrategy::Ipv6Only => self.query(host, RecordType::AAAA, subnet).await?,
+			LookupIpStrategy::Ipv4AndIpv6 => {
+				let (v4, v6) = tokio::join!(
+					self.query(host, RecordType::A, subnet),
+					self.query(host, RecordType::AAAA, subnet),
+				);
+				// Either family is enough to connect, so a failure of the other is not fatal.
+				match (v4, v6) {
+					(Ok(v4), Ok(v6)) => v4.merge(v6),
+					(Ok(answer), Err(e)) | (Err(e), Ok(answer)) => {
+						debug!(%host, "DNS-over-HTTPS lookup partially failed: {e:#}");
+						answer
+					},
+					(Err(e), Err(_)) => return Err(e),
+				}
+			},
+			LookupIpStrategy::Ipv4thenIpv6 => {
+				self
+					.query_then(host, RecordType::A, RecordType::AAAA, subnet)
+					.await?
+			},
+			LookupIpStrategy::Ipv6thenIpv4 => {
+				self
+					.query_then(host, RecordType::AAAA, RecordType::A, subnet)
+					.await?
+			},
+		};
+		let Some(ttl) = answer.ttl.filter(|_| !answer.ips.is_empty()) else {
+			bail!("no addresses found for {host}");
+		};
+		Ok((
+			answer.ips.into_boxed_slice(),
+			Instant::now() + Duration::from_secs(ttl.into()),
+		))
+	}
+
+	/// Queries `first`, and only if it has no addresses, `second`.
+	async fn query_then(
+		&self,
+		host: &str,
+		first: RecordType,
+		second: RecordType,
+		subnet: Option<ClientSubnet>,
+	) -> anyhow::Result<Answer> {
+		let answer = self.query(host, first, subnet).await?;
+		if !answer.ips.is_empty() {
+			return Ok(answer);
+		}
+		self.query(host, second, subnet).await
+	}
+
+	async fn query(
+		&self,
+		host: &str,
+		record_type: RecordType,
+		subnet: Option<ClientSubnet>,
+	) -> anyhow::Result<Answer> {
+		let mut req = ::http::Request::builder()
+			.method(::http::Method::POST)
+			.uri(&self.url)
+			.header(::http::header::CONTENT_TYPE, DNS_MESSAGE)
+			.header(::http::header::ACCEPT, DNS_MESSAGE)
+			.body(crate::http::Body::from(encode_query(
+				host,
+				record_type,
+				subnet,
+			)?))
+			.context("build DNS-over-HTTPS request")?;
+		req
+			.extensions_mut()
+			.insert(BackendRequestTimeout(QUERY_TIMEOUT));
+		let response = self
+			.client
+			.simple_call(req)
+			.await
+			.context("DNS-over-HTTPS request")?;
+		if !response.status().is_success() {
+			bail!(
+				"DNS-over-HTTPS request failed with status {}",
+				response.status()
+			);
+		}
+		let body = crate::http::read_body_with_limit(response.into_body(), RESPONSE_LIMIT)
+			.await
+			.context("read DNS-over-HTTPS response")?;
+		parse_response(&body, record_type)
+	}
+}
+
+#[derive(Debug, Default)]
+struct Answer {
+	ips: Vec<IpAddr>,
+	/// The lowest TTL of the address records, in seconds.
+	ttl: Option<u32>,
+}
+
+impl Answer {
+	fn merge(mut self, other: Answer) -> Answer {
+		self.ips.extend(other.ips);
+		self.ttl = match (self.ttl, other.ttl) {
+			(Some(a), Some(b)) => Some(a.min(b)),
+			(a, b) => a.or(b),
+		};
+		self
+	}
+}
+
+fn encode_query(
+	host: &str,
+	record_type: RecordType,
+	subnet: Option<ClientSubnet>,
+) -> anyhow::Result<Vec<u8>> {
+	let name = Name::from_ascii(host).with_context(|| format!("invalid hostname {host}"))?;
+	let mut msg = Message::new();
+	// RFC 8484 recommends an ID of 0, so responses are cache friendly.
+	msg
+		.set_id(0)
+		.set_message_type(MessageType::Query)
+		.set_op_code(OpCode::Query)
+		.set_recursion_desired(true)
+		.add_query(Query::query(name, record_type));
+	if let Some(subnet) = subnet {
+		// EDNS Client Subnet (RFC 7871), with a scope prefix length of 0 as required in queries.
+		let mut edns = Edns::new();
+		edns.set_max_payload(EDNS_PAYLOAD_SIZE);
+		edns
+			.options_mut()
+			.insert(EdnsOption::Subnet(EdnsClientSubnet::new(
+				subnet.addr,
+				subnet.prefix_len,
+				0,
+			)));
+		msg.set_edns(edns);
+	}
+	Ok(msg.to_vec()?)
+}
+
+/// Reads the records of `record_type` from a DNS response. Other records, such as the CNAMEs that
+/// led to them, are skipped.
+fn parse_response(msg: &[u8], record_type: RecordType) -> anyhow::Result<Answer> {
+	let msg = Message::from_vec(msg).context("malformed DNS message")?;
+	match msg.response_code() {
+		ResponseCode::NoError => {},
+		ResponseCode::NXDomain => bail!("domain does not exist"),
+		rcode => bail!("DNS server returned error code {rcode}"),
+	}
+	let mut answer = Answer::default();
+	for record in msg.answers() {
+		if record.record_type() != record_type {
+			continue;
+		}
+		let ip = match record.data() {
+			RData::A(a) => IpAddr::V4(a.0),
+			RData::AAAA(aaaa) => IpAddr::V6(aaaa.0),
+			_ => bail!("malformed address record"),
+		};
+		answer.ips.push(ip);
+		answer.ttl = Some(answer.ttl.map_or(record.ttl(), |t| t.min(record.ttl())));
+	}
+	Ok(answer)
+}
@@ -0,0 +1,262 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use hickory_proto::rr::rdata::opt::EdnsCode;
+use hickory_proto::rr::rdata::{A, AAAA, CNAME};
+use hickory_proto::rr::{DNSClass, Record};
+use wiremock::matchers::{body_bytes, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::*;
+
+const IP1: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+const IP2: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 2);
+const IP6: Ipv6Addr = Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1);
+
+fn record(host: &str, ttl: u32, data: RData) -> Record {
+	let mut record = Record::from_rdata(Name::from_ascii(host).unwrap(), ttl, data);
+	record.set_dns_class(DNSClass::IN);
+	record
+}
+
+fn a(ip: Ipv4Addr, ttl: u32) -> Record {
+	record("example.com", ttl, RData::A(A(ip)))
+}
+
+fn aaaa(ip: Ipv6Addr, ttl: u32) -> Record {
+	record("example.com", ttl, RData::AAAA(AAAA(ip)))
+}
+
+/// Builds a response to the query for `host`.
+fn response(
+	host: &str,
+	record_type: RecordType,
+	rcode: ResponseCode,
+	answers: Vec<Record>,
+) -> Vec<u8> {
+	let query = encode_query(host, record_type, None).unwrap();
+	let mut msg = Message::from_vec(&query).unwrap();
+	msg
+		.set_message_type(MessageType::Response)
+		.set_recursion_available(true)
+		.set_response_code(rcode)
+		.add_answers(answers);
+	msg.to_vec().unwrap()
+}
+
+async fn mock_answer(server: &MockServer, host: &str, record_type: RecordType, body: Vec<u8>) {
+	Mock::given(method("POST"))
+		.and(path("/dns-query"))
+		.and(header("content-type", DNS_MESSAGE))
+		.and(body_bytes(encode_query(host, record_type, None).unwrap()))
+		.respond_with(ResponseTemplate::new(200).set_body_raw(body, DNS_MESSAGE))
+		.expect(1)
+		.mount(server)
+		.await;
+}
+
+async fn mock_failure(server: &MockServer, host: &str, record_type: RecordType) {
+	Mock::given(method("POST"))
+		.and(path("/dns-query"))
+		.and(body_bytes(encode_query(host, record_type, None).unwrap()))
+		.respond_with(ResponseTemplate::new(503))
+		.expect(1)
+		.mount(server)
+		.await;
+}
+
+fn test_client() -> super::super::Client {
+	super::super::Client::new(
+		&super::super::Config {
+			resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
+			resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+			negative_ttl: super::super::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
+		},
+		None,
+		Default::default(),
+		None,
+	)
+}
+
+fn resolver(server: &MockServer, strategy: LookupIpStrategy) -> DohResolver {
+	DohResolver::new(
+		format!("{}/dns-query", server.uri()),
+		strategy,
+		test_client(),
+	)
+}
+
+#[tokio::test]
+async fn resolves_via_doh_endpoint() {
+	let server = MockServer::start().await;
+	let cname = record(
+		"example.com",
+		300,
+		RData::CNAME(CNAME(Name::from_ascii("alias.example.com").unwrap())),
+	);
+	let answers = vec![cname, a(IP1, 60), a(IP2, 30)];
+	mock_answer(
+		&server,
+		"example.com",
+		RecordType::A,
+		response("example.com", RecordType::A, ResponseCode::NoError, answers),
+	)
+	.await;
+
+	let (ips, expiry) = resolver(&server, LookupIpStrategy::Ipv4Only)
+		.lookup_ip("example.com", None)
+		.await
+		.unwrap();
+	assert_eq!(&*ips, &[IpAddr::V4(IP1), IpAddr::V4(IP2)]);
+	// The answer expires with its shortest-lived record.
+	let ttl = expiry - Instant::now();
+	assert!(ttl > Duration::from_secs(25) && ttl <= Duration::from_secs(30));
+}
+
+#[tokio::test]
+async fn falls_back_to_ipv6_when_no_ipv4() {
+	let server = MockServer::start().await;
+	mock_answer(
+		&server,
+		"example.com",
+		RecordType::A,
+		response("example.com", RecordType::A, ResponseCode::NoError, vec![]),
+	)
+	.await;
+	mock_answer(
+		&server,
+		"example.com",
+		RecordType::AAAA,
+		response(
+			"example.com",
+			RecordType::AAAA,
+			ResponseCode::NoError,
+			vec![aaaa(IP6, 60)],
+		),
+	)
+	.await;
+
+	let (ips, _) = resolver(&server, LookupIpStrategy::Ipv4thenIpv6)
+		.lookup_ip("example.com", None)
+		.await
+		.unwrap();
+	assert_eq!(&*ips, &[IpAddr::V6(IP6)]);
+}
+
+#[tokio::test]
+async fn returns_both_families() {
+	let server = MockServer::start().await;
+	mock_answer(
+		&server,
+		"example.com",
+		RecordType::A,
+		response(
+			"example.com",
+			RecordType::A,
+			ResponseCode::NoError,
+			vec![a(IP1, 60)],
+		),
+	)
+	.await;
+	mock_answer(
+		&server,
+		"example.com",
+		RecordType::AAAA,
+		response(
+			"example.com",
+			RecordType::AAAA,
+			ResponseCode::NoError,
+			vec![aaaa(IP6, 60)],
+		),
+	)
+	.await;
+
+	let (ips, _) = resolver(&server, LookupIpStrategy::Ipv4AndIpv6)
+		.lookup_ip("example.com", None)
+		.await
+		.unwrap();
+	assert_eq!(&*ips, &[IpAddr::V4(IP1), IpAddr::V6(IP6)]);
+}
+
+#[tokio::test]
+async fn returns_partial_results_when_one_family_fails() {
+	let server = MockServer::start().await;
+	mock_answer(
+		&server,
+		"example.com",
+		RecordType::A,
+		response(
+			"example.com",
+			RecordType::A,
+			ResponseCode::NoError,
+			vec![a(IP1, 60)],
+		),
+	)
+	.await;
+	mock_failure(&server, "example.com", RecordType::AAAA).await;
+
+	let (ips, _) = resolver(&server, LookupIpStrategy::Ipv4AndIpv6)
+		.lookup_ip("example.com", None)
+		.await
+		.unwrap();
+	assert_eq!(&*ips, &[IpAddr::V4(IP1)]);
+
+	// With both failing, the lookup fails.
+	let server = MockServer::start().await;
+	mock_failure(&server, "example.com", RecordType::A).await;
+	mock_failure(&server, "example.com", RecordType::AAAA).await;
+	let err = resolver(&server, LookupIpStrategy::Ipv4AndIpv6)
+		.lookup_ip("example.com", None)
+		.await
+		.unwrap_err();
+	assert!(err.to_string().contains("503"), "{err}");
+}
+
+#[tokio::test]
+async fn errors_on_nxdomain() {
+	let server = MockServer::start().await;
+	mock_answer(
+		&server,
+		"missing.example.com",
+		RecordType::A,
+		response(
+			"missing.example.com",
+			RecordType::A,
+			ResponseCode::NXDomain,
+			vec![],
+		),
+	)
+	.await;
+
+	let err = resolver(&server, LookupIpStrategy::Ipv4Only)
+		.lookup_ip("missing.example.com", None)
+		.await
+		.unwrap_err();
+	assert!(err.to_string().contains("does not exist"), "{err}");
+}
+
+#[test]
+fn encodes_client_subnet() {
+	let subnet = ClientSubnet {
+		addr: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 0)),
+		prefix_len: 24,
+	};
+	let msg = encode_query("example.com", RecordType::A, Some(subnet)).unwrap();
+	let msg = Message::from_vec(&msg).unwrap();
+	assert_eq!(msg.id(), 0);
+	assert!(msg.recursion_desired());
+	let edns = msg.extensions().as_ref().expect("EDNS");
+	assert_eq!(edns.max_payload(), EDNS_PAYLOAD_SIZE);
+	assert_eq!(
+		edns.option(EdnsCode::Subnet),
+		Some(&EdnsOption::Subnet(EdnsClientSubnet::new(
+			Ipv4Addr::new(192, 0, 2, 0).into(),
+			24,
+			0
+		)))
+	);
+
+	let plain = encode_query("example.com", RecordType::A, None).unwrap();
+	assert!(Message::from_vec(&plain).unwrap().extensions().is_none());
+}
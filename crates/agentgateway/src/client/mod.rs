@@ -1,6 +1,7 @@
 mod azure;
 mod connect_tunnel;
 mod dns;
+mod doh;
 mod hbone_tunnel;
 mod tls;
 
@@ -17,7 +18,8 @@ use crate::http::backendtls::VersionedBackendTLS;
 use crate::http::filters;
 use crate::http::filters::BackendRequestTimeout;
 use crate::proxy::ProxyError;
-use crate::transport::stream::{LoggingMode, Socket};
+use crate::telemetry::trc;
+use crate::transport::stream::{LoggingMode, Socket, TCPConnectionInfo};
 use crate::transport::{hbone, stream};
 use crate::types::agent::Target;
 use crate::*;
@@ -311,7 +313,7 @@ impl Connector {
 				// Tunnel case one: use CONNECT for non-plaintext HTTP
 				let proxy_dst: SocketAddr = self
 					// Never skip resolution for the actually proxy itself
-					.resolve_target(false, &tcfg.target)
+					.resolve_target(false, &tcfg.target, None)
 					.await
 					.map_err(crate::http::Error::new)?;
 				let dest = target.to_string();
@@ -328,7 +330,7 @@ impl Connector {
 				// Tunnel case two: use absolute form for plaintext HTTP
 				let proxy_dst: SocketAddr = self
 					// Never skip resolution for the actually proxy itself
-					.resolve_target(false, &tcfg.target)
+					.resolve_target(false, &tcfg.target, None)
 					.await
 					.map_err(crate::http::Error::new)?;
 				debug!("connected to tunnel proxy (HTTP)");
@@ -417,10 +419,13 @@ impl Connector {
 		Ok(socket)
 	}
 
+	/// Resolves the address to connect to for `target`. `client` is the downstream client address,
+	/// used for EDNS client subnet when enabled.
 	async fn resolve_target(
 		&self,
 		skip_resolution: bool,
 		target: &Target,
+		client: Option<IpAddr>,
 	) -> Result<SocketAddr, ProxyError> {
 		let dest = match &target {
 			Target::Address(addr) => *addr,
@@ -432,7 +437,7 @@ impl Connector {
 				}
 				let ip = self
 					.resolver
-					.resolve(hostname.clone())
+					.resolve(hostname.clone(), client)
 					.await
 					.map_err(|_| ProxyError::DnsResolution)?;
 				SocketAddr::from((ip, *port))
@@ -470,11 +475,38 @@ impl tower::Service<::http::Extensions> for Connector {
 	}
 }
 
+pub const DEFAULT_DNS_NEGATIVE_TTL: Duration = Duration::from_secs(1);
+
 #[derive(serde::Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
 	pub resolver_cfg: ResolverConfig,
 	pub resolver_opts: ResolverOpts,
+	/// How long a failed lookup is remembered before the hostname is queried again.
+	#[serde(with = "serde_dur")]
+	pub negative_ttl: Duration,
+	/// Resolve hostnames with DNS-over-HTTPS instead of the system resolver, when set.
+	pub doh: Option<DohConfig>,
+	/// Send the downstream client's subnet with lookups, when set.
+	pub client_subnet: Option<ClientSubnetConfig>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DohConfig {
+	/// The DNS-over-HTTPS endpoint, such as `https://dns.google/dns-query`.
+	pub url: String,
+	/// Whether to fall back to the system resolver when the endpoint fails to answer.
+	pub fallback_to_system: bool,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientSubnetConfig {
+	/// How many leading bits of an IPv4 client address are sent.
+	pub ipv4_prefix_length: u8,
+	/// How many leading bits of an IPv6 client address are sent.
+	pub ipv6_prefix_length: u8,
 }
 
 impl Client {
@@ -484,7 +516,21 @@ impl Client {
 		backend_config: BackendConfig,
 		metrics: Option<Arc<crate::metrics::Metrics>>,
 	) -> Client {
-		let resolver = dns::CachedResolver::new(cfg.resolver_cfg.clone(), cfg.resolver_opts.clone());
+		// DNS-over-HTTPS queries go through a client of their own, which finds the endpoint with the
+		// system resolver rather than through itself.
+		let doh_client = cfg.doh.as_ref().map(|_| {
+			Client::new(
+				&Config {
+					doh: None,
+					client_subnet: None,
+					..cfg.clone()
+				},
+				None,
+				backend_config.clone(),
+				metrics.clone(),
+			)
+		});
+		let resolver = dns::CachedResolver::new(cfg, doh_client, metrics.clone());
 		let mut b = agent_pool::Client::<_, PoolKey>::builder(::hyper_util::rt::TokioExecutor::new());
 		b.pool_timer(hyper_util::rt::tokio::TokioTimer::new());
 		b.pool_idle_timeout(backend_config.pool_idle_timeout);
@@ -541,9 +587,12 @@ impl Client {
 			transport,
 		} = call;
 
+		let client = source
+			.ext::<TCPConnectionInfo>()
+			.map(|tcp| tcp.peer_addr.ip());
 		let dest = self
 			.connector
-			.resolve_target(transport.skip_dns_resolution(), &target)
+			.resolve_target(transport.skip_dns_resolution(), &target, client)
 			.await?;
 
 		let transport_name = transport.name();
@@ -595,7 +644,7 @@ impl Client {
 	) -> Result<Socket, ProxyError> {
 		let dest = self
 			.connector
-			.resolve_target(transport.skip_dns_resolution(), &target)
+			.resolve_target(transport.skip_dns_resolution(), &target, None)
 			.await?;
 		self
 			.connector
@@ -618,8 +667,12 @@ impl Client {
 			transport,
 		} = call;
 		async move {
+			let client = req
+				.extensions()
+				.get::<TCPConnectionInfo>()
+				.map(|tcp| tcp.peer_addr.ip());
 			let dest = connector
-				.resolve_target(transport.skip_dns_resolution(), &target)
+				.resolve_target(transport.skip_dns_resolution(), &target, client)
 				.await?;
 			http::modify_req_uri(&mut req, |uri| {
 				let scheme = transport.scheme();
@@ -640,6 +693,11 @@ impl Client {
 			if req.extensions().get::<filters::AutoHostname>().is_some() {
 				req.headers_mut().remove(::http::header::HOST);
 			}
+			// Inject the trace context of the span for this request, so it is propagated even if
+			// policies rewrote or dropped the header along the way.
+			if let Some(tp) = req.extensions().get::<trc::TraceParent>().cloned() {
+				tp.insert_header(&mut req);
+			}
 			let version = req.version();
 			let transport_name = transport.name();
 			// We are going to do a HTTP absolute form tunnel request. For CONNECT this is handled
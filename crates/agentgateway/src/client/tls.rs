@@ -12,6 +12,8 @@ pub async fn handshake(
 	cfg: &VersionedBackendTLS,
 	target: Target,
 ) -> Result<Socket, crate::http::Error> {
+	// The server name is sent as SNI. It is also the name the certificate is verified against, unless
+	// the config verifies a different name (`verify_hostname`).
 	let server_name = if let Some(h) = cfg.hostname_override.clone() {
 		h
 	} else {
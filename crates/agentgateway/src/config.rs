@@ -7,6 +7,7 @@ use std::{cmp, env};
 use agent_core::durfmt;
 use agent_core::env::ENV;
 use agent_core::prelude::*;
+use agent_core::secrets::SecretRef;
 use secrecy::ExposeSecret;
 
 use crate::control::caclient;
@@ -29,13 +30,32 @@ struct TracingEnvOverrides {
 	protocol: Option<trc::Protocol>,
 }
 
+/// Check `contents` against the config schema, returning every error found rather than stopping at
+/// the first one. An empty result means the config is valid.
+pub fn validate_config(contents: &str) -> anyhow::Result<Vec<String>> {
+	let value: serde_json::Value = serdes::yamlviajson::from_str(contents).ctx("invalid config")?;
+	let nested = match serdes::from_value_collecting_errors::<NestedRawConfig>(value) {
+		Ok(nested) => nested,
+		Err(errors) => return Ok(errors),
+	};
+	let raw = nested.config.unwrap_or_default();
+	// Register custom functions so that CEL expressions in the local config referencing them validate.
+	Ok(
+		match cel::configure(&raw.custom_functions, raw.cel_function_timeout) {
+			Ok(()) => vec![],
+			Err(e) => vec![format!("config.customFunctions: {e}")],
+		},
+	)
+}
+
 pub fn parse_config(
 	contents: String,
 	local_config_source: Option<ConfigSource>,
 ) -> anyhow::Result<Config> {
 	let nested: NestedRawConfig = serdes::yamlviajson::from_str(&contents).ctx("invalid config")?;
 	let raw = nested.config.unwrap_or_default();
-	cel::register_custom_functions(&raw.custom_functions).ctx("invalid config.customFunctions")?;
+	cel::configure(&raw.custom_functions, raw.cel_function_timeout)
+		.ctx("invalid config.customFunctions")?;
 
 	let ipv6_enabled = parse::<bool>("IPV6_ENABLED")?
 		.or(raw.enable_ipv6)
@@ -67,6 +87,40 @@ pub fn parse_config(
 	.or(dns.lookup_family)
 	.unwrap_or_default();
 	let dns_edns0: Option<bool> = parse("DNS_EDNS0")?.or(dns.edns0);
+	let dns_negative_ttl = parse_duration("DNS_NEGATIVE_TTL")?
+		.or(dns.negative_ttl)
+		.unwrap_or(client::DEFAULT_DNS_NEGATIVE_TTL);
+	let dns_doh = dns
+		.doh
+		.map(|doh| {
+			let uri = http::Uri::try_from(&doh.url).ctx(format!("invalid URI {}", doh.url))?;
+			if uri.scheme() != Some(&http::uri::Scheme::HTTPS) {
+				anyhow::bail!("DNS-over-HTTPS endpoint {} must use https", doh.url);
+			}
+			Ok(client::DohConfig {
+				url: doh.url,
+				fallback_to_system: doh.fallback_to_system,
+			})
+		})
+		.transpose()
+		.ctx("invalid config.dns.doh")?;
+	let dns_client_subnet = dns
+		.client_subnet
+		.map(|cs| {
+			if dns_doh.is_none() {
+				anyhow::bail!("client subnet requires config.dns.doh to be set");
+			}
+			let subnet = client::ClientSubnetConfig {
+				ipv4_prefix_length: cs.ipv4_prefix_length.unwrap_or(24),
+				ipv6_prefix_length: cs.ipv6_prefix_length.unwrap_or(56),
+			};
+			if subnet.ipv4_prefix_length > 32 || subnet.ipv6_prefix_length > 128 {
+				anyhow::bail!("prefix length exceeds the address length");
+			}
+			Ok(subnet)
+		})
+		.transpose()
+		.ctx("invalid config.dns.clientSubnet")?;
 	let (resolver_cfg, resolver_opts) = {
 		let (cfg, opts) = hickory_resolver::system_conf::read_system_conf().unwrap_or_else(|e| {
 			warn!(err=?e, "failed to read system DNS config, using defaults");
@@ -112,6 +166,8 @@ pub fn parse_config(
 					crate::control::AuthSource::None
 				}
 			},
+			Some(p) if p.contains("://") => secret_ref_auth_source(&p, cluster.clone())
+				.ctx("invalid XDS_AUTH_TOKEN/config.xdsAuthToken")?,
 			Some(p) if Path::new(&p).exists() => {
 				// This is a file
 				crate::control::AuthSource::Token(PathBuf::from(p), cluster.clone())
@@ -124,18 +180,25 @@ pub fn parse_config(
 			"XDS_ROOT_CA",
 			"./var/run/secrets/xds/root-cert.pem".to_string(),
 		)?;
-		let xds_root_cert = if Path::new(&xds_cert).exists() {
-			crate::control::RootCert::File(xds_cert.into())
-		} else if xds_cert.eq("SYSTEM") {
-			// handle SYSTEM special case for ca
-			crate::control::RootCert::Default
-		} else {
-			crate::control::RootCert::Default
+		let xds_root_cert = parse_root_cert(&xds_cert);
+		let xds_client_cert = parse("XDS_CLIENT_CERT")?.or(raw.xds_client_cert);
+		let xds_client_key = parse("XDS_CLIENT_KEY")?.or(raw.xds_client_key);
+		let client_identity = match (xds_client_cert, xds_client_key) {
+			(Some(cert), Some(key)) => Some(crate::control::ClientIdentity {
+				cert: cert.into(),
+				key: key.into(),
+			}),
+			(None, None) => None,
+			_ => anyhow::bail!(
+				"XDS_CLIENT_CERT/config.xdsClientCert and XDS_CLIENT_KEY/config.xdsClientKey must be set together"
+			),
 		};
 		XDSConfig {
 			address,
 			auth,
 			ca_cert: xds_root_cert,
+			client_identity,
+			backoff: parse_xds_backoff()?,
 			namespace: namespace.into(),
 			gateway: gateway.into(),
 			local_config,
@@ -174,6 +237,8 @@ pub fn parse_config(
 					crate::control::AuthSource::None
 				}
 			},
+			Some(p) if p.contains("://") => secret_ref_auth_source(&p, cluster.clone())
+				.ctx("invalid CA_AUTH_TOKEN/config.caAuthToken")?,
 			Some(p) if Path::new(&p).exists() => {
 				// This is a file
 				crate::control::AuthSource::Token(PathBuf::from(p), cluster.clone())
@@ -187,14 +252,7 @@ pub fn parse_config(
 			"CA_ROOT_CA",
 			"./var/run/secrets/istio/root-cert.pem".to_string(),
 		)?;
-		let ca_root_cert = if Path::new(&ca_cert).exists() {
-			crate::control::RootCert::File(ca_cert.into())
-		} else if ca_cert.eq("SYSTEM") {
-			// handle SYSTEM special case for ca
-			crate::control::RootCert::Default
-		} else {
-			crate::control::RootCert::Default
-		};
+		let ca_root_cert = parse_root_cert(&ca_cert);
 		// Build the allowed trust domains list. The local trust domain is always first.
 		// ADDITIONAL_TRUST_DOMAINS is a comma-separated list of extra domains to accept.
 		let mut allowed_trust_domains: Vec<Strng> = vec![td.clone().into()];
@@ -412,6 +470,14 @@ pub fn parse_config(
 					let endpoint = otlp_endpoint.ctx(
 						"config.tracing requires otlpEndpoint or one of OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_TRACES_ENDPOINT, or OTEL_EXPORTER_OTLP_ENDPOINT",
 					)?;
+					if let Some(sampler) = &t.sampler {
+						if t.random_sampling.is_some() || t.client_sampling.is_some() {
+							anyhow::bail!(
+								"config.tracing.sampler cannot be combined with randomSampling or clientSampling"
+							)
+						}
+						sampler.validate().ctx("invalid config.tracing.sampler")?;
+					}
 				Ok::<_, anyhow::Error>(trc::DeprecatedConfig {
 					endpoint: Some(endpoint),
 					headers: otlp_headers.clone(),
@@ -423,6 +489,8 @@ pub fn parse_config(
 						.map(|fields| {
 							Ok::<_, anyhow::Error>(LoggingFields {
 								remove: Arc::new(fields.remove.into_iter().collect()),
+								redact: Arc::new(fields.redact.into_iter().collect()),
+								redact_strategy: fields.redact_strategy,
 								add: Arc::new(
 									fields
 											.add
@@ -458,6 +526,7 @@ pub fn parse_config(
 							})
 							.transpose()?
 							.map(Arc::new),
+					sampler: t.sampler,
 					path: otlp_path.unwrap_or_else(|| "/v1/traces".to_string()),
 				})
 			})
@@ -506,6 +575,15 @@ pub fn parse_config(
 					})
 					.transpose()?
 					.map(Arc::new),
+			sample: raw
+				.logging
+				.as_ref()
+				.and_then(|l| l.sample.as_ref())
+				.map(|expression| {
+					cel::Expression::new_strict(&expression.0).ctx("invalid config.logging.sample")
+				})
+				.transpose()?
+				.map(Arc::new),
 			level: match raw.logging.as_ref().and_then(|l| l.level.as_ref()) {
 				None => "".to_string(),
 				Some(RawLoggingLevel::Single(level)) => level.to_string(),
@@ -529,6 +607,9 @@ pub fn parse_config(
 		dns: client::Config {
 			resolver_cfg,
 			resolver_opts,
+			negative_ttl: dns_negative_ttl,
+			doh: dns_doh,
+			client_subnet: dns_client_subnet,
 		},
 		proxy_metadata: crate::ProxyMetadata {
 			instance_ip: ENV.instance_ip.clone(),
@@ -588,9 +669,13 @@ fn logging_fields(fields: Option<RawLoggingFields>) -> anyhow::Result<LoggingFie
 	let fields = fields.unwrap_or(RawLoggingFields {
 		remove: Vec::new(),
 		add: Default::default(),
+		redact: Vec::new(),
+		redact_strategy: Default::default(),
 	});
 	Ok(LoggingFields {
 		remove: Arc::new(fields.remove.into_iter().collect()),
+		redact: Arc::new(fields.redact.into_iter().collect()),
+		redact_strategy: fields.redact_strategy,
 		add: Arc::new(
 			fields
 				.add
@@ -625,6 +710,8 @@ fn database_logging_fields(
 
 	Ok(LoggingFields {
 		remove: Arc::default(),
+		redact: Arc::default(),
+		redact_strategy: Default::default(),
 		add: Arc::new(
 			add
 				.iter()
@@ -659,6 +746,20 @@ where
 	parse(env).map(|v| v.unwrap_or(default))
 }
 
+/// Maps a secret reference for an auth token to its source. File and environment references stay
+/// refreshable; other providers are resolved once, when the config is loaded.
+fn secret_ref_auth_source(
+	reference: &str,
+	cluster: String,
+) -> anyhow::Result<crate::control::AuthSource> {
+	let secret: SecretRef = reference.parse()?;
+	Ok(match secret.scheme() {
+		"file" => crate::control::AuthSource::Token(PathBuf::from(secret.path()), cluster),
+		"env" => crate::control::AuthSource::Env(secret.path().to_string(), cluster),
+		_ => crate::control::AuthSource::StaticToken(secret.resolve()?, cluster),
+	})
+}
+
 fn parse_duration(env: &str) -> anyhow::Result<Option<Duration>> {
 	parse::<String>(env)?
 		.map(|ds| {
@@ -667,6 +768,26 @@ fn parse_duration(env: &str) -> anyhow::Result<Option<Duration>> {
 		.transpose()
 }
 
+fn parse_xds_backoff() -> anyhow::Result<agent_xds::Backoff> {
+	let defaults = agent_xds::Backoff::default();
+	let initial = parse_duration("XDS_BACKOFF_INITIAL")?.unwrap_or(defaults.initial);
+	let max = parse_duration("XDS_BACKOFF_MAX")?.unwrap_or(defaults.max);
+	let jitter = parse::<f64>("XDS_BACKOFF_JITTER")?.unwrap_or(defaults.jitter);
+	if initial.is_zero() || initial > max {
+		anyhow::bail!(
+			"invalid XDS backoff: XDS_BACKOFF_INITIAL must be greater than zero and at most XDS_BACKOFF_MAX"
+		);
+	}
+	if !(0.0..=1.0).contains(&jitter) {
+		anyhow::bail!("invalid env var XDS_BACKOFF_JITTER={jitter} (must be between 0 and 1)");
+	}
+	Ok(agent_xds::Backoff {
+		initial,
+		max,
+		jitter,
+	})
+}
+
 fn parse_dynamic_ca_cert_cache_config() -> anyhow::Result<crate::DynamicCaCertCacheConfig> {
 	let defaults = crate::DynamicCaCertCacheConfig::default();
 	let ttl = parse_duration("DYNAMIC_CA_CERT_CACHE_TTL")?.unwrap_or(defaults.ttl);
@@ -677,6 +798,24 @@ fn parse_dynamic_ca_cert_cache_config() -> anyhow::Result<crate::DynamicCaCertCa
 	Ok(crate::DynamicCaCertCacheConfig { ttl, capacity })
 }
 
+/// Parses a root certificate setting: a file path, `SYSTEM` for the system roots, or
+/// `SYSTEM+<path>[,<path>...]` for the system roots plus the certificates in each file. A path that
+/// does not exist falls back to the system roots.
+fn parse_root_cert(value: &str) -> crate::control::RootCert {
+	if let Some(paths) = value.strip_prefix("SYSTEM+") {
+		crate::control::RootCert::SystemPlus(
+			paths
+				.split(',')
+				.map(|p| crate::control::RootCert::File(p.trim().into()))
+				.collect(),
+		)
+	} else if Path::new(value).exists() {
+		crate::control::RootCert::File(value.into())
+	} else {
+		crate::control::RootCert::Default
+	}
+}
+
 pub fn empty_to_none<A: AsRef<str>>(inp: Option<A>) -> Option<A> {
 	if let Some(inner) = &inp
 		&& inner.as_ref().is_empty()
@@ -927,6 +1066,44 @@ pub(crate) fn lock_env_for_tests() -> std::sync::MutexGuard<'static, ()> {
 	ENV_LOCK.lock().expect("env mutex poisoned")
 }
 
+/// Sets an environment variable for the duration of a test, restoring the previous value on drop.
+/// Callers must hold the guard returned by `lock_env_for_tests`.
+#[cfg(test)]
+pub(crate) struct TempEnvVar {
+	key: String,
+	previous: Option<std::ffi::OsString>,
+}
+
+#[cfg(test)]
+impl TempEnvVar {
+	pub(crate) fn set(key: &str, value: &str) -> Self {
+		let previous = std::env::var_os(key);
+		// SAFETY: callers serialize environment access through `lock_env_for_tests`.
+		unsafe {
+			std::env::set_var(key, value);
+		}
+		Self {
+			key: key.to_string(),
+			previous,
+		}
+	}
+}
+
+#[cfg(test)]
+impl Drop for TempEnvVar {
+	fn drop(&mut self) {
+		// SAFETY: see `TempEnvVar::set`.
+		match &self.previous {
+			Some(value) => unsafe {
+				std::env::set_var(&self.key, value);
+			},
+			None => unsafe {
+				std::env::remove_var(&self.key);
+			},
+		}
+	}
+}
+
 #[cfg(test)]
 mod parse_headers_tests {
 	use std::env;
@@ -1006,7 +1183,6 @@ mod parse_headers_tests {
 #[cfg(test)]
 mod tests {
 	use std::env;
-	use std::ffi::OsString;
 
 	use super::*;
 
@@ -1014,37 +1190,6 @@ mod tests {
 		lock_env_for_tests()
 	}
 
-	struct TempEnvVar {
-		key: String,
-		previous: Option<OsString>,
-	}
-
-	impl TempEnvVar {
-		fn set(key: &str, value: &str) -> Self {
-			let previous = env::var_os(key);
-			unsafe {
-				env::set_var(key, value);
-			}
-			Self {
-				key: key.to_string(),
-				previous,
-			}
-		}
-	}
-
-	impl Drop for TempEnvVar {
-		fn drop(&mut self) {
-			match &self.previous {
-				Some(value) => unsafe {
-					env::set_var(&self.key, value);
-				},
-				None => unsafe {
-					env::remove_var(&self.key);
-				},
-			}
-		}
-	}
-
 	#[test]
 	fn test_parse_otlp_headers() {
 		let _env_lock = lock_env();
@@ -1199,6 +1344,140 @@ config:
 		assert_eq!(tracing.protocol, trc::Protocol::Grpc);
 	}
 
+	fn tracing_sampler(sampler: &str) -> crate::telemetry::log::TraceSampler {
+		let tracing = parse_config(
+			format!(
+				r#"
+config:
+  tracing:
+    otlpEndpoint: http://collector.example:4317
+    sampler: {sampler}
+"#
+			),
+			None,
+		)
+		.expect("config should parse")
+		.tracing
+		.expect("tracing config should exist");
+		crate::telemetry::log::TraceSampler {
+			random_sampling: tracing.random_sampling,
+			client_sampling: tracing.client_sampling,
+			sampler: tracing.sampler,
+		}
+	}
+
+	#[test]
+	fn tracing_sampler_ratio() {
+		let _env_lock = lock_env();
+		let req = ::http::Request::builder()
+			.uri("http://example.com")
+			.body(crate::http::Body::empty())
+			.unwrap();
+		let trace_id = trc::TraceParent::new().trace_id;
+		let parent = |trace_id: u128, flags: u8| trc::TraceParent {
+			trace_id,
+			flags,
+			..trc::TraceParent::new()
+		};
+		let sampled_parent = parent(trace_id, 1);
+		let unsampled_parent = parent(trace_id, 0);
+
+		let drop_all = tracing_sampler("{traceidratio: 0.0}");
+		let keep_all = tracing_sampler("{traceidratio: 1.0}");
+		for p in [None, Some(&sampled_parent), Some(&unsampled_parent)] {
+			assert!(!drop_all.trace_sampled(&req, p, trace_id));
+			assert!(!drop_all.trace_sampled(&req, p, u128::MIN));
+			assert!(keep_all.trace_sampled(&req, p, trace_id));
+			assert!(keep_all.trace_sampled(&req, p, u128::MAX));
+		}
+
+		// The decision is derived from the low bits of the trace ID, so it is stable per trace and
+		// ignores the parent's sampled flag.
+		let half = tracing_sampler("{traceidratio: 0.5}");
+		let low = 0xffff_ffff_ffff_ffff_0000_0000_0000_0000u128;
+		let high = 0x0000_0000_0000_0000_ffff_ffff_ffff_ffffu128;
+		for _ in 0..20 {
+			assert!(half.trace_sampled(&req, None, low));
+			assert!(half.trace_sampled(&req, Some(&parent(low, 0)), low));
+			assert!(!half.trace_sampled(&req, None, high));
+			assert!(!half.trace_sampled(&req, Some(&parent(high, 1)), high));
+		}
+
+		// Parent based sampling applies the ratio to new traces and otherwise follows the parent.
+		let parent_based = tracing_sampler("{parentbased_traceidratio: 0.5}");
+		assert!(parent_based.trace_sampled(&req, None, low));
+		assert!(!parent_based.trace_sampled(&req, None, high));
+		assert!(parent_based.trace_sampled(&req, Some(&parent(high, 1)), high));
+		assert!(!parent_based.trace_sampled(&req, Some(&parent(low, 0)), low));
+
+		let always_off = tracing_sampler("always_off");
+		assert!(!always_off.trace_sampled(&req, None, trace_id));
+		assert!(!always_off.trace_sampled(&req, Some(&sampled_parent), trace_id));
+		let always_on = tracing_sampler("always_on");
+		assert!(always_on.trace_sampled(&req, None, trace_id));
+		assert!(always_on.trace_sampled(&req, Some(&unsampled_parent), trace_id));
+	}
+
+	#[test]
+	fn tracing_sampler_rejects_invalid_config() {
+		let _env_lock = lock_env();
+		let err = parse_config(
+			r#"
+config:
+  tracing:
+    otlpEndpoint: http://collector.example:4317
+    sampler: {traceidratio: 1.5}
+"#
+			.to_string(),
+			None,
+		)
+		.expect_err("ratio above 1.0 should fail");
+		assert!(
+			format!("{err:#}").contains("sampling ratio must be between"),
+			"unexpected error: {err:#}"
+		);
+
+		parse_config(
+			r#"
+config:
+  tracing:
+    otlpEndpoint: http://collector.example:4317
+    sampler: always_on
+    randomSampling: true
+"#
+			.to_string(),
+			None,
+		)
+		.expect_err("sampler and randomSampling are mutually exclusive");
+	}
+
+	#[test]
+	fn logging_fields_redact() {
+		let _env_lock = lock_env();
+		let cfg = parse_config(
+			r#"
+config:
+  logging:
+    fields:
+      add:
+        authorization: request.headers["authorization"]
+      redact: [authorization]
+      redactStrategy: sha256Prefix
+"#
+			.to_string(),
+			None,
+		)
+		.unwrap();
+		let fields = &cfg.logging.fields;
+		assert!(fields.redact.contains("authorization"));
+		assert_eq!(
+			fields.redact_strategy,
+			crate::telemetry::log::RedactStrategy::Sha256Prefix
+		);
+		// Redacted fields are kept, not removed.
+		assert!(!fields.remove.contains("authorization"));
+	}
+
 	#[test]
 	fn tracing_requires_endpoint_from_config_or_env() {
 		let _env_lock = lock_env();
@@ -1421,4 +1700,67 @@ config:
 			env::remove_var("SESSION_KEY");
 		}
 	}
+
+	#[test]
+	fn secret_ref_auth_token_sources() {
+		let _env_lock = lock_env();
+		let _token = TempEnvVar::set("TEST_SECRET_REF_XDS_TOKEN", "token");
+
+		assert!(matches!(
+			secret_ref_auth_source("file:///var/run/secrets/token", "cluster".to_string()).unwrap(),
+			crate::control::AuthSource::Token(path, _) if path == Path::new("/var/run/secrets/token")
+		));
+		assert!(matches!(
+			secret_ref_auth_source("env://TEST_SECRET_REF_XDS_TOKEN", "cluster".to_string()).unwrap(),
+			crate::control::AuthSource::Env(var, _) if var == "TEST_SECRET_REF_XDS_TOKEN"
+		));
+		let err = secret_ref_auth_source("vault://xds/token", "cluster".to_string()).unwrap_err();
+		assert!(err.to_string().contains("unknown secret provider"), "{err}");
+	}
+
+	#[test]
+	fn auth_token_env_requires_secret_ref() {
+		let _env_lock = lock_env();
+		let _token = TempEnvVar::set("TEST_XDS_TOKEN_ENV", "token");
+		let cfg = parse_config(
+			"config:\n  xdsAuthToken: env://TEST_XDS_TOKEN_ENV\n".to_string(),
+			None,
+		)
+		.unwrap();
+		assert!(matches!(
+			cfg.xds.auth,
+			crate::control::AuthSource::Env(var, _) if var == "TEST_XDS_TOKEN_ENV"
+		));
+		// The bare `env:` prefix is not a secret reference, so it is treated as a missing file.
+		let err = parse_config(
+			"config:\n  xdsAuthToken: env:TEST_XDS_TOKEN_ENV\n".to_string(),
+			None,
+		)
+		.unwrap_err();
+		assert!(err.to_string().contains("not found"), "{err}");
+	}
+
+	#[test]
+	fn validate_config_reports_all_errors() {
+		let errors = validate_config(
+			r#"
+config:
+  enableIpv6: maybe
+  bogusField: 1
+  dns:
+    edns0: 3
+"#,
+		)
+		.unwrap();
+		assert_eq!(errors.len(), 3, "{errors:?}");
+		assert!(errors[0].starts_with("config.enableIpv6: "), "{errors:?}");
+		assert!(
+			errors[1].starts_with("config.bogusField: unknown field `bogusField`"),
+			"{errors:?}"
+		);
+		assert!(errors[2].starts_with("config.dns.edns0: "), "{errors:?}");
+
+		let errors = validate_config("config:\n  enableIpv6: false\n").unwrap();
+		assert!(errors.is_empty(), "{errors:?}");
+	}
 }
@@ -28,6 +28,7 @@ use istio::ca::istio_certificate_service_client::IstioCertificateServiceClient;
 
 use crate::control::{AuthSource, RootCert};
 use crate::http::backendtls::VersionedBackendTLS;
+use crate::transport::tls::identity::TrustBundle;
 
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
@@ -81,7 +82,7 @@ pub struct Expiration {
 pub struct WorkloadCertificate {
 	// server_config: Arc<ServerConfig>,
 	// client_config: Arc<ClientConfig>,
-	roots: Arc<RootCertStore>,
+	trust_bundle: Arc<TrustBundle>,
 	chain: Vec<Certificate>,
 	private_key: PrivateKeyDer<'static>,
 	expiry: Expiration,
@@ -91,10 +92,13 @@ pub struct WorkloadCertificate {
 }
 
 impl WorkloadCertificate {
+	/// Parses a certificate issued by the CA. The roots distributed with it are merged into
+	/// `trust_bundle`.
 	fn new(
 		key: &[u8],
 		cert: &[u8],
 		chain: Vec<&[u8]>,
+		trust_bundle: Arc<TrustBundle>,
 		allowed_trust_domains: Arc<[Strng]>,
 		skip_validate_trust_domain: bool,
 	) -> Result<WorkloadCertificate, Error> {
@@ -116,8 +120,8 @@ impl WorkloadCertificate {
 		};
 		let key: PrivateKeyDer = parse_key(key)?;
 		let roots = parse_cert_multi(raw_root)?;
-		let (_valid, invalid) =
-			roots_store.add_parsable_certificates(roots.iter().map(|c| c.der.clone()));
+		let roots: Vec<_> = roots.into_iter().map(|c| c.der).collect();
+		let (_valid, invalid) = roots_store.add_parsable_certificates(roots.iter().cloned());
 		if invalid > 0 {
 			tracing::warn!("warning: found {invalid} invalid root certs");
 		}
@@ -130,9 +134,10 @@ impl WorkloadCertificate {
 		for c in chains {
 			cert_and_chain.push(c);
 		}
+		trust_bundle.add(&roots);
 
 		Ok(WorkloadCertificate {
-			roots: Arc::new(roots_store),
+			trust_bundle,
 			expiry,
 			private_key: key,
 			chain: cert_and_chain,
@@ -155,8 +160,10 @@ impl WorkloadCertificate {
 
 	pub fn legacy_mtls(&self, identity: Vec<Identity>) -> Result<VersionedBackendTLS, Error> {
 		// TODO: this is (way) too expensive to build per request
-		let roots = self.roots.clone();
-		let verifier = transport::tls::identity::IdentityVerifier { roots, identity };
+		let verifier = transport::tls::identity::IdentityVerifier {
+			trust_bundle: self.trust_bundle.clone(),
+			identity,
+		};
 		let mut cc = ClientConfig::builder_with_provider(transport::tls::provider())
 			.with_protocol_versions(transport::tls::ALL_TLS_VERSIONS)
 			.expect("client config must be valid")
@@ -177,8 +184,10 @@ impl WorkloadCertificate {
 	}
 	pub fn hbone_mtls(&self, identity: Vec<Identity>) -> Result<VersionedBackendTLS, Error> {
 		// TODO: this is (way) too expensive to build per request
-		let roots = self.roots.clone();
-		let verifier = transport::tls::identity::IdentityVerifier { roots, identity };
+		let verifier = transport::tls::identity::IdentityVerifier {
+			trust_bundle: self.trust_bundle.clone(),
+			identity,
+		};
 		let mut cc = ClientConfig::builder_with_provider(transport::tls::provider())
 			.with_protocol_versions(transport::tls::ALL_TLS_VERSIONS)
 			.expect("client config must be valid")
@@ -207,7 +216,7 @@ impl WorkloadCertificate {
 		require_client_cert: bool,
 	) -> Result<ServerConfig, Error> {
 		// TODO: this is too expensive to build per request
-		let roots = self.roots.clone();
+		let roots = self.trust_bundle.roots();
 		let scb = ServerConfig::builder_with_provider(transport::tls::provider())
 			.with_protocol_versions(transport::tls::ALL_TLS_VERSIONS)
 			.expect("server config must be valid");
@@ -364,6 +373,8 @@ pub struct CaClient {
 impl CaClient {
 	pub fn new(client: client::Client, config: Config) -> Result<Self, Error> {
 		let (state_tx, state_rx) = watch::channel(CertificateState::NotReady);
+		// Shared by every certificate issued to this client, so roots accumulate across rotations.
+		let trust_bundle = Arc::new(TrustBundle::default());
 
 		let headers: Vec<(http::header::HeaderName, http::HeaderValue)> = config
 			.ca_headers
@@ -385,7 +396,7 @@ impl CaClient {
 			let headers = headers.clone();
 
 			async move {
-				Self::run_fetcher(client, config, state_tx, headers).await;
+				Self::run_fetcher(client, config, state_tx, trust_bundle, headers).await;
 			}
 		});
 
@@ -426,13 +437,20 @@ impl CaClient {
 		client: client::Client,
 		config: Config,
 		state_tx: watch::Sender<CertificateState>,
+		trust_bundle: Arc<TrustBundle>,
 		headers: Vec<(http::header::HeaderName, http::HeaderValue)>,
 	) {
 		let mut interval = tokio::time::interval(Duration::from_secs(30)); // Check every 30 seconds
 
 		// Start with an immediate fetch
-		if let Err(e) =
-			Self::fetch_and_update_certificate(client.clone(), &config, &state_tx, headers.clone()).await
+		if let Err(e) = Self::fetch_and_update_certificate(
+			client.clone(),
+			&config,
+			&state_tx,
+			&trust_bundle,
+			headers.clone(),
+		)
+		.await
 		{
 			error!("Initial certificate fetch failed: {:?}", e);
 			let _ = state_tx.send(CertificateState::Error(e));
@@ -440,6 +458,7 @@ impl CaClient {
 
 		loop {
 			interval.tick().await;
+			trust_bundle.remove_expired();
 
 			// Check if we need to renew
 			let should_renew = {
@@ -460,6 +479,7 @@ impl CaClient {
 					client.clone(),
 					&config,
 					&state_tx,
+					&trust_bundle,
 					headers.clone(),
 				)
 				.await
@@ -486,6 +506,7 @@ impl CaClient {
 		client: client::Client,
 		config: &Config,
 		state_tx: &watch::Sender<CertificateState>,
+		trust_bundle: &Arc<TrustBundle>,
 		headers: Vec<(http::header::HeaderName, http::HeaderValue)>,
 	) -> Result<(), Error> {
 		info!("Fetching certificate for identity: {}", config.identity);
@@ -495,6 +516,7 @@ impl CaClient {
 			config.address.clone(),
 			config.auth.clone(),
 			config.ca_cert.clone(),
+			None,
 			headers.clone(),
 		)
 		.await
@@ -543,6 +565,7 @@ impl CaClient {
 			&private_key,
 			leaf_cert,
 			chain_certs,
+			trust_bundle.clone(),
 			config.allowed_trust_domains.clone(),
 			config.skip_validate_trust_domain,
 		)?);
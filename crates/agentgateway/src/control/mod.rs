@@ -17,8 +17,10 @@ use tower::Service;
 
 use crate::client::{ApplicationTransport, Transport};
 use crate::http::HeaderValue;
-use crate::http::backendtls::{BackendTLS, BackendTLSInfo, PerAlpnConfig, SYSTEM_TRUST};
-use crate::types::agent::Target;
+use crate::http::backendtls::{
+	BackendTLS, BackendTLSInfo, PerAlpnConfig, ResolvedBackendTLS, SYSTEM_ROOT, SYSTEM_TRUST,
+};
+use crate::types::agent::{Target, parse_cert, parse_key};
 use crate::*;
 
 pub mod caclient;
@@ -28,36 +30,51 @@ pub enum RootCert {
 	File(PathBuf),
 	Static(#[serde(skip)] Bytes),
 	Default,
+	/// The system roots, plus the certificates from each of the given sources.
+	SystemPlus(Vec<RootCert>),
 }
 
 impl RootCert {
 	pub async fn to_client_config(&self) -> anyhow::Result<BackendTLS> {
-		let mut metadata = BackendTLSInfo {
-			alpn: Some(vec!["h2".to_string()]),
-			..Default::default()
-		};
-		let roots = match self {
+		match self {
 			RootCert::File(f) => {
 				let certfile = tokio::fs::read(f).await?;
-				metadata.root = Some(String::from_utf8_lossy(&certfile).into());
-				let certs = CertificateDer::pem_slice_iter(&certfile).collect::<Result<Vec<_>, _>>()?;
-				let mut roots = rustls::RootCertStore::empty();
-				roots.add_parsable_certificates(certs);
-				roots
+				Self::client_config_from_pem(&certfile, false, None)
 			},
-			RootCert::Static(b) => {
-				metadata.root = Some(String::from_utf8_lossy(b).into());
-				let certs = CertificateDer::pem_slice_iter(b).collect::<Result<Vec<_>, _>>()?;
-				let mut roots = rustls::RootCertStore::empty();
-				roots.add_parsable_certificates(certs);
-				roots
+			RootCert::Static(b) => Self::client_config_from_pem(b, false, None),
+			RootCert::Default => Ok(SYSTEM_TRUST.clone()),
+			RootCert::SystemPlus(_) => {
+				let pem = self.read().await?.unwrap_or_default();
+				Self::client_config_from_pem(&pem, true, None)
 			},
-			RootCert::Default => return Ok(SYSTEM_TRUST.clone()),
+		}
+	}
+
+	fn client_config_from_pem(
+		pem: &[u8],
+		system_roots: bool,
+		identity: Option<&(Vec<u8>, Vec<u8>)>,
+	) -> anyhow::Result<BackendTLS> {
+		let metadata = BackendTLSInfo {
+			alpn: Some(vec!["h2".to_string()]),
+			cert: identity.map(|(cert, _)| String::from_utf8_lossy(cert).into()),
+			root: Some(String::from_utf8_lossy(pem).into()),
+			system_roots,
+			..Default::default()
+		};
+		let system: &[CertificateDer<'static>] = if system_roots {
+			&SYSTEM_ROOT.certs
+		} else {
+			&[]
 		};
-		let mut ccb = ClientConfig::builder_with_provider(transport::tls::provider())
+		let roots = Self::root_store(pem, system)?;
+		let ccb = ClientConfig::builder_with_provider(transport::tls::provider())
 			.with_protocol_versions(transport::tls::ALL_TLS_VERSIONS)?
-			.with_root_certificates(roots)
-			.with_no_client_auth();
+			.with_root_certificates(roots);
+		let mut ccb = match identity {
+			Some((cert, key)) => ccb.with_client_auth_cert(parse_cert(cert)?, parse_key(key)?)?,
+			None => ccb.with_no_client_auth(),
+		};
 		ccb.key_log = transport::tls::key_log();
 		ccb.alpn_protocols = vec![b"h2".to_vec()];
 		Ok(BackendTLS {
@@ -66,6 +83,182 @@ impl RootCert {
 			metadata,
 		})
 	}
+
+	/// Builds a store trusting the `system` roots along with those in `pem`.
+	fn root_store(
+		pem: &[u8],
+		system: &[CertificateDer<'static>],
+	) -> anyhow::Result<rustls::RootCertStore> {
+		let mut roots = rustls::RootCertStore::empty();
+		roots.add_parsable_certificates(system.iter().cloned());
+		let certs = CertificateDer::pem_slice_iter(pem).collect::<Result<Vec<_>, _>>()?;
+		roots.add_parsable_certificates(certs);
+		Ok(roots)
+	}
+
+	async fn read(&self) -> io::Result<Option<Bytes>> {
+		match self {
+			RootCert::File(f) => Ok(Some(tokio::fs::read(f).await?.into())),
+			RootCert::Static(b) => Ok(Some(b.clone())),
+			RootCert::Default => Ok(None),
+			RootCert::SystemPlus(sources) => {
+				let mut pem = Vec::new();
+				for source in sources {
+					if let Some(b) = Box::pin(source.read()).await? {
+						pem.extend_from_slice(&b);
+						pem.push(b'\n');
+					}
+				}
+				Ok(Some(pem.into()))
+			},
+		}
+	}
+
+	/// Whether any of the certificates are read from files, which may change.
+	fn has_files(&self) -> bool {
+		match self {
+			RootCert::File(_) => true,
+			RootCert::Static(_) | RootCert::Default => false,
+			RootCert::SystemPlus(sources) => sources.iter().any(RootCert::has_files),
+		}
+	}
+}
+
+/// ClientIdentity is a client certificate and private key, presented to the control plane for
+/// mutual TLS.
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientIdentity {
+	pub cert: PathBuf,
+	pub key: PathBuf,
+}
+
+/// The raw PEM contents a control plane TLS config is built from.
+#[derive(PartialEq, Eq)]
+struct TlsPem {
+	root: Option<Bytes>,
+	// Whether the system roots are trusted in addition to `root`.
+	system_roots: bool,
+	identity: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl TlsPem {
+	async fn read(root: &RootCert, identity: Option<&ClientIdentity>) -> anyhow::Result<TlsPem> {
+		let identity = match identity {
+			Some(id) => Some((
+				tokio::fs::read(&id.cert).await?,
+				tokio::fs::read(&id.key).await?,
+			)),
+			None => None,
+		};
+		Ok(TlsPem {
+			root: root.read().await?,
+			system_roots: matches!(root, RootCert::SystemPlus(_)),
+			identity,
+		})
+	}
+
+	fn to_client_config(&self) -> anyhow::Result<BackendTLS> {
+		match (&self.root, &self.identity) {
+			(None, None) => Ok(SYSTEM_TRUST.clone()),
+			(Some(root), identity) => {
+				RootCert::client_config_from_pem(root, self.system_roots, identity.as_ref())
+			},
+			(None, Some((cert, key))) => ResolvedBackendTLS {
+				cert: Some(cert.clone()),
+				key: Some(key.clone()),
+				alpn: Some(vec!["h2".to_string()]),
+				..Default::default()
+			}
+			.try_into(),
+		}
+	}
+}
+
+/// RootCertLoader holds the client TLS config built from a `RootCert` and optional
+/// `ClientIdentity`. Any files backing them are periodically re-read, and the config rebuilt when
+/// they change.
+#[derive(Debug)]
+pub struct RootCertLoader {
+	current: Arc<RwLock<BackendTLS>>,
+	drop_notifier: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl Drop for RootCertLoader {
+	fn drop(&mut self) {
+		if let Some(tx) = self.drop_notifier.take() {
+			let _ = tx.send(());
+		}
+	}
+}
+
+impl RootCertLoader {
+	pub async fn new(
+		root: RootCert,
+		identity: Option<ClientIdentity>,
+	) -> anyhow::Result<RootCertLoader> {
+		let mut interval = tokio::time::interval(Duration::from_secs(60));
+		interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+		Self::new_with_interval(root, identity, interval).await
+	}
+
+	async fn new_with_interval(
+		root: RootCert,
+		identity: Option<ClientIdentity>,
+		mut interval: tokio::time::Interval,
+	) -> anyhow::Result<RootCertLoader> {
+		let mut current_pem = TlsPem::read(&root, identity.as_ref()).await?;
+		let current = Arc::new(RwLock::new(current_pem.to_client_config()?));
+		if !root.has_files() && identity.is_none() {
+			// Nothing to reload
+			return Ok(RootCertLoader {
+				current,
+				drop_notifier: None,
+			});
+		}
+		let (tx, mut rx) = tokio::sync::oneshot::channel();
+		let config_pointer = current.clone();
+		tokio::spawn(async move {
+			loop {
+				tokio::select! {
+					_ = &mut rx => {
+						// Received shutdown signal
+						return;
+					}
+					_ = interval.tick() => {}
+				}
+				let new_pem = match TlsPem::read(&root, identity.as_ref()).await {
+					Ok(p) => p,
+					Err(e) => {
+						tracing::error!("Failed to reload control plane certificates: {}", e);
+						continue;
+					},
+				};
+				if new_pem == current_pem {
+					continue;
+				}
+				match new_pem.to_client_config() {
+					Ok(cfg) => {
+						tracing::info!("reloaded control plane certificates");
+						current_pem = new_pem;
+						*config_pointer.write().unwrap() = cfg;
+					},
+					Err(e) => {
+						tracing::error!("Failed to parse control plane certificates: {}", e);
+					},
+				}
+			}
+		});
+		Ok(RootCertLoader {
+			current,
+			drop_notifier: Some(tx),
+		})
+	}
+
+	/// Returns the most recently loaded client TLS config.
+	pub fn current(&self) -> BackendTLS {
+		self.current.read().unwrap().clone()
+	}
 }
 
 #[derive(serde::Serialize, Clone, Debug)]
@@ -76,9 +269,36 @@ pub enum AuthSource {
 	// JWT authentication source which contains a static token file.
 	// Note that this token is not refreshed, so its lifetime ought to be longer than ztunnel's
 	StaticToken(#[serde(serialize_with = "ser_redact")] SecretString, String),
+	// JWT authentication source which contains the name of an environment variable holding the token,
+	// and the cluster id. The variable is periodically re-read, so the token may be rotated in place.
+	Env(String, String),
 	None,
 }
 
+/// Where a refreshable token is read from.
+enum TokenSource {
+	File(PathBuf),
+	Env(String),
+}
+
+impl TokenSource {
+	async fn load(&self) -> io::Result<Vec<u8>> {
+		match self {
+			TokenSource::File(path) => AuthSourceLoader::load_token(path).await,
+			TokenSource::Env(var) => AuthSourceLoader::load_env_token(var),
+		}
+	}
+}
+
+impl fmt::Display for TokenSource {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			TokenSource::File(path) => write!(f, "file {}", path.display()),
+			TokenSource::Env(var) => write!(f, "env var {var}"),
+		}
+	}
+}
+
 #[derive(serde::Serialize, Clone)]
 struct AuthSourceLoaderInner {
 	cluster_id: String,
@@ -118,47 +338,14 @@ impl AuthSourceLoader {
 	}
 	async fn new_with_interval(
 		auth: AuthSource,
-		mut interval: tokio::time::Interval,
+		interval: tokio::time::Interval,
 	) -> anyhow::Result<AuthSourceLoader> {
 		Ok(match auth {
 			AuthSource::Token(path, cluster_id) => {
-				let mut current_token = Self::load_token(&path).await?;
-				let ret = AuthSourceLoaderInner {
-					cluster_id,
-					current_token: Arc::new(RwLock::new(Arc::new(Self::to_bearer(
-						current_token.as_slice(),
-					)))),
-				};
-				let (tx, mut rx) = tokio::sync::oneshot::channel();
-				let token_pointer = ret.current_token.clone();
-				tokio::spawn(async move {
-					loop {
-						tokio::select! {
-							_ = &mut rx => {
-								// Received shutdown signal
-								return;
-							}
-							_ = interval.tick() => {}
-						}
-						let new_token = match Self::load_token(&path).await {
-							Ok(t) => t,
-							Err(e) => {
-								tracing::error!("Failed to reload token from file {}: {}", path.display(), e);
-								continue;
-							},
-						};
-
-						if new_token != current_token {
-							current_token = new_token;
-							*token_pointer.write().unwrap().deref_mut() =
-								Arc::new(Self::to_bearer(current_token.as_slice()));
-						}
-					}
-				});
-				AuthSourceLoader {
-					inner: Some(ret),
-					drop_notifier: Some(tx),
-				}
+				Self::new_refreshing(TokenSource::File(path), cluster_id, interval).await?
+			},
+			AuthSource::Env(var, cluster_id) => {
+				Self::new_refreshing(TokenSource::Env(var), cluster_id, interval).await?
 			},
 			AuthSource::StaticToken(token, cluster_id) => AuthSourceLoader {
 				inner: Some(AuthSourceLoaderInner {
@@ -176,6 +363,50 @@ impl AuthSourceLoader {
 		})
 	}
 
+	async fn new_refreshing(
+		source: TokenSource,
+		cluster_id: String,
+		mut interval: tokio::time::Interval,
+	) -> anyhow::Result<AuthSourceLoader> {
+		let mut current_token = source.load().await?;
+		let ret = AuthSourceLoaderInner {
+			cluster_id,
+			current_token: Arc::new(RwLock::new(Arc::new(Self::to_bearer(
+				current_token.as_slice(),
+			)))),
+		};
+		let (tx, mut rx) = tokio::sync::oneshot::channel();
+		let token_pointer = ret.current_token.clone();
+		tokio::spawn(async move {
+			loop {
+				tokio::select! {
+					_ = &mut rx => {
+						// Received shutdown signal
+						return;
+					}
+					_ = interval.tick() => {}
+				}
+				let new_token = match source.load().await {
+					Ok(t) => t,
+					Err(e) => {
+						tracing::error!("Failed to reload token from {}: {}", source, e);
+						continue;
+					},
+				};
+
+				if new_token != current_token {
+					current_token = new_token;
+					*token_pointer.write().unwrap().deref_mut() =
+						Arc::new(Self::to_bearer(current_token.as_slice()));
+				}
+			}
+		});
+		Ok(AuthSourceLoader {
+			inner: Some(ret),
+			drop_notifier: Some(tx),
+		})
+	}
+
 	fn to_bearer(token: &[u8]) -> Vec<u8> {
 		const BEARER_PREFIX: &[u8] = b"Bearer ";
 		let mut bearer: Vec<u8> = Vec::with_capacity(BEARER_PREFIX.len() + token.len());
@@ -208,6 +439,15 @@ impl AuthSourceLoader {
 		}
 		Ok(t)
 	}
+
+	fn load_env_token(var: &str) -> io::Result<Vec<u8>> {
+		let t = std::env::var(var).map_err(io::Error::other)?;
+
+		if t.is_empty() {
+			return Err(io::Error::other("token env var is set, but was empty"));
+		}
+		Ok(t.into_bytes())
+	}
 }
 
 pub async fn grpc_connector(
@@ -215,14 +455,19 @@ pub async fn grpc_connector(
 	url: String,
 	auth: AuthSource,
 	root: RootCert,
+	identity: Option<ClientIdentity>,
 	headers: Vec<(http::header::HeaderName, http::HeaderValue)>,
 ) -> anyhow::Result<GrpcChannel> {
-	let root = root.to_client_config().await?;
-	let (target, transport) = get_target(&url, root)?;
+	let (target, tls) = get_target(&url)?;
+	let root = if tls {
+		Some(Arc::new(RootCertLoader::new(root, identity).await?))
+	} else {
+		None
+	};
 
 	Ok(GrpcChannel {
 		target,
-		transport,
+		root,
 		client,
 		auth: Arc::new(AuthSourceLoader::new(auth).await?),
 		headers,
@@ -232,7 +477,8 @@ pub async fn grpc_connector(
 #[derive(Clone, Debug)]
 pub struct GrpcChannel {
 	target: Target,
-	transport: Transport,
+	/// Root certificates to verify the server with; None for plaintext connections.
+	root: Option<Arc<RootCertLoader>>,
 	client: client::Client,
 	auth: Arc<AuthSourceLoader>,
 	headers: Vec<(http::header::HeaderName, http::HeaderValue)>,
@@ -251,7 +497,10 @@ impl tower::Service<::http::Request<tonic::body::Body>> for GrpcChannel {
 		let client = self.client.clone();
 		let auth = self.auth.clone();
 		let target = self.target.clone();
-		let transport = self.transport.clone();
+		let transport: Transport = match &self.root {
+			Some(root) => ApplicationTransport::Tls(root.current().base_config()).into(),
+			None => ApplicationTransport::Plaintext.into(),
+		};
 		let ca_headers = self.headers.clone();
 		let mut req = req.map(http::Body::new);
 
@@ -292,7 +541,8 @@ impl agent_xds::ClientTrait for GrpcChannel {
 	}
 }
 
-fn get_target(raw: &str, ca: BackendTLS) -> anyhow::Result<(Target, Transport)> {
+/// Parses the target from `raw`, and whether it should be connected to over TLS.
+fn get_target(raw: &str) -> anyhow::Result<(Target, bool)> {
 	let uri = raw.parse::<Uri>()?;
 
 	let target = if let Some(authority) = uri.authority() {
@@ -301,13 +551,13 @@ fn get_target(raw: &str, ca: BackendTLS) -> anyhow::Result<(Target, Transport)>
 		anyhow::bail!("URI must have authority")
 	};
 
-	let transport = match uri.scheme_str() {
-		Some("http") => ApplicationTransport::Plaintext.into(),
-		Some("https") => ApplicationTransport::Tls(ca.base_config()).into(),
+	let tls = match uri.scheme_str() {
+		Some("http") => false,
+		Some("https") => true,
 		_ => anyhow::bail!("Unsupported scheme: {}", uri.scheme_str().unwrap_or("none")),
 	};
 
-	Ok((target, transport))
+	Ok((target, tls))
 }
 
 #[cfg(test)]
@@ -413,6 +663,259 @@ mod tests {
 		);
 	}
 
+	fn ca_pem() -> String {
+		let key = rcgen::KeyPair::generate().unwrap();
+		let mut params = rcgen::CertificateParams::default();
+		params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+		params.self_signed(&key).unwrap().pem()
+	}
+
+	#[tokio::test]
+	async fn test_root_cert_file_loader_rotation() {
+		let dir = tempdir().unwrap();
+		let file_path = dir.path().join("root-cert.pem");
+		let first = ca_pem();
+		std::fs::write(&file_path, &first).unwrap();
+
+		let interval: tokio::time::Interval = tokio::time::interval(Duration::from_millis(10));
+		let loader =
+			RootCertLoader::new_with_interval(RootCert::File(file_path.clone()), None, interval)
+				.await
+				.unwrap();
+		assert_eq!(
+			loader.current().metadata.root.as_deref(),
+			Some(first.as_str())
+		);
+
+		let second = format!("{}{}", ca_pem(), ca_pem());
+		std::fs::write(&file_path, &second).unwrap();
+
+		tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+		assert_eq!(
+			loader.current().metadata.root.as_deref(),
+			Some(second.as_str())
+		);
+	}
+
+	#[tokio::test]
+	async fn test_root_cert_file_loader_keeps_roots_on_invalid_file() {
+		let dir = tempdir().unwrap();
+		let file_path = dir.path().join("root-cert.pem");
+		let first = ca_pem();
+		std::fs::write(&file_path, &first).unwrap();
+
+		let interval: tokio::time::Interval = tokio::time::interval(Duration::from_millis(10));
+		let loader =
+			RootCertLoader::new_with_interval(RootCert::File(file_path.clone()), None, interval)
+				.await
+				.unwrap();
+		std::fs::write(&file_path, "-----BEGIN CERTIFICATE-----\nnot a cert").unwrap();
+
+		tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+		assert_eq!(
+			loader.current().metadata.root.as_deref(),
+			Some(first.as_str())
+		);
+	}
+
+	#[test]
+	fn test_system_plus_trusts_both_roots() {
+		use rustls::client::danger::ServerCertVerifier;
+
+		let issue = || {
+			let ca_key = rcgen::KeyPair::generate().unwrap();
+			let mut params = rcgen::CertificateParams::default();
+			params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+			let ca = params.self_signed(&ca_key).unwrap();
+			let issuer = rcgen::Issuer::from_ca_cert_pem(&ca.pem(), ca_key).unwrap();
+			let key = rcgen::KeyPair::generate().unwrap();
+			let leaf = rcgen::CertificateParams::new(vec!["example.com".to_string()])
+				.unwrap()
+				.signed_by(&key, &issuer)
+				.unwrap();
+			(ca, leaf)
+		};
+		// Stands in for a public root from the system store.
+		let (public_root, public_leaf) = issue();
+		let (custom_root, custom_leaf) = issue();
+		let (_, untrusted_leaf) = issue();
+
+		let roots =
+			RootCert::root_store(custom_root.pem().as_bytes(), &[public_root.der().clone()]).unwrap();
+		let verifier = rustls::client::WebPkiServerVerifier::builder_with_provider(
+			Arc::new(roots),
+			transport::tls::provider(),
+		)
+		.build()
+		.unwrap();
+		let name = rustls::pki_types::ServerName::try_from("example.com").unwrap();
+		let verify = |leaf: &rcgen::Certificate| {
+			verifier.verify_server_cert(
+				leaf.der(),
+				&[],
+				&name,
+				&[],
+				rustls::pki_types::UnixTime::now(),
+			)
+		};
+		assert!(verify(&public_leaf).is_ok());
+		assert!(verify(&custom_leaf).is_ok());
+		assert!(verify(&untrusted_leaf).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_system_plus_reads_all_sources() {
+		let dir = tempdir().unwrap();
+		let file_path = dir.path().join("root-cert.pem");
+		let custom = ca_pem();
+		std::fs::write(&file_path, &custom).unwrap();
+		let extra = ca_pem();
+
+		let root = RootCert::SystemPlus(vec![
+			RootCert::File(file_path),
+			RootCert::Static(extra.clone().into()),
+		]);
+		let tls = root.to_client_config().await.unwrap();
+		assert!(tls.metadata.system_roots);
+		assert_eq!(
+			tls.metadata.root.as_deref(),
+			Some(format!("{custom}\n{extra}\n").as_str())
+		);
+	}
+
+	#[tokio::test]
+	async fn test_grpc_channel_client_identity() {
+		let dir = tempdir().unwrap();
+		let cert_path = dir.path().join("cert.pem");
+		let key_path = dir.path().join("key.pem");
+		let key = rcgen::KeyPair::generate().unwrap();
+		let cert = rcgen::CertificateParams::new(vec!["client.example.com".to_string()])
+			.unwrap()
+			.self_signed(&key)
+			.unwrap();
+		std::fs::write(&cert_path, cert.pem()).unwrap();
+		std::fs::write(&key_path, key.serialize_pem()).unwrap();
+
+		let client = crate::test_helpers::proxymock::setup_proxy_test("{}")
+			.unwrap()
+			.inputs()
+			.upstream
+			.clone();
+		let channel = grpc_connector(
+			client,
+			"https://xds.example.com:15010".to_string(),
+			AuthSource::None,
+			RootCert::Static(ca_pem().into()),
+			Some(ClientIdentity {
+				cert: cert_path,
+				key: key_path,
+			}),
+			vec![],
+		)
+		.await
+		.unwrap();
+
+		let tls = channel.root.as_ref().unwrap().current();
+		assert_eq!(tls.metadata.cert.as_deref(), Some(cert.pem().as_str()));
+		assert!(
+			tls
+				.base_config()
+				.config
+				.client_auth_cert_resolver
+				.has_certs()
+		);
+		assert_eq!(
+			tls.base_config().config.alpn_protocols,
+			vec![b"h2".to_vec()]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_grpc_channel_without_client_identity() {
+		let client = crate::test_helpers::proxymock::setup_proxy_test("{}")
+			.unwrap()
+			.inputs()
+			.upstream
+			.clone();
+		let channel = grpc_connector(
+			client,
+			"https://xds.example.com:15010".to_string(),
+			AuthSource::None,
+			RootCert::Static(ca_pem().into()),
+			None,
+			vec![],
+		)
+		.await
+		.unwrap();
+
+		let tls = channel.root.as_ref().unwrap().current();
+		assert!(tls.metadata.cert.is_none());
+		assert!(
+			!tls
+				.base_config()
+				.config
+				.client_auth_cert_resolver
+				.has_certs()
+		);
+	}
+
+	#[test]
+	fn test_env_token_loader_rotation() {
+		const VAR: &str = "AGENTGATEWAY_TEST_XDS_TOKEN";
+		// The loader re-reads the variable from a background task, so the environment lock is held for
+		// the whole test rather than only around the writes. A plain #[test] keeps the guard out of
+		// any async frame.
+		let _env_lock = crate::config::lock_env_for_tests();
+		let _token = crate::config::TempEnvVar::set(VAR, "env-token-value");
+
+		let rt = tokio::runtime::Builder::new_current_thread()
+			.enable_all()
+			.build()
+			.unwrap();
+		rt.block_on(async {
+			let interval: tokio::time::Interval = tokio::time::interval(Duration::from_millis(10));
+			let loader = AuthSourceLoader::new_with_interval(
+				AuthSource::Env(VAR.to_string(), "env-cluster".to_string()),
+				interval,
+			)
+			.await
+			.unwrap();
+
+			let mut headers = http::HeaderMap::new();
+			loader.insert_headers(&mut headers).unwrap();
+			assert_eq!(
+				headers.get("authorization").unwrap(),
+				&http::HeaderValue::from_static("Bearer env-token-value")
+			);
+			assert_eq!(
+				headers.get("clusterid").unwrap(),
+				&http::HeaderValue::from_static("env-cluster")
+			);
+
+			let _rotated = crate::config::TempEnvVar::set(VAR, "env-token-value-2");
+			tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+			let mut headers = http::HeaderMap::new();
+			loader.insert_headers(&mut headers).unwrap();
+			assert_eq!(
+				headers.get("authorization").unwrap(),
+				&http::HeaderValue::from_static("Bearer env-token-value-2")
+			);
+		});
+	}
+
+	#[tokio::test]
+	async fn test_env_token_loader_missing_var() {
+		let res = AuthSourceLoader::new(AuthSource::Env(
+			"AGENTGATEWAY_TEST_XDS_TOKEN_UNSET".to_string(),
+			"env-cluster".to_string(),
+		))
+		.await;
+		assert!(res.is_err());
+	}
+
 	#[tokio::test]
 	async fn test_none_auth_source_loader() {
 		let loader = AuthSourceLoader::new(AuthSource::None).await.unwrap();
@@ -47,6 +47,9 @@ pub enum AwsAuth {
 		/// AWS SigV4 signing service name (for example, "bedrock", "bedrock-agentcore", or "execute-api").
 		#[serde(skip_serializing_if = "Option::is_none")]
 		service_name: Option<String>,
+		/// AWS region to sign for. If unset, the region is taken from the backend (for example, Bedrock) or the AWS config.
+		#[serde(skip_serializing_if = "Option::is_none")]
+		region: Option<String>,
 		/// Optional AWS STS role to assume before signing requests.
 		#[serde(skip_serializing_if = "Option::is_none")]
 		assume_role: Option<AwsAssumeRole>,
@@ -570,8 +573,12 @@ pub(super) async fn sign_request(
 		AwsAuth::ExplicitConfig {
 			region: Some(region),
 			..
+		}
+		| AwsAuth::Implicit {
+			region: Some(region),
+			..
 		} => region.as_str(),
-		AwsAuth::ExplicitConfig { region: None, .. } | AwsAuth::Implicit { .. } => {
+		AwsAuth::ExplicitConfig { region: None, .. } | AwsAuth::Implicit { region: None, .. } => {
 			// Try to get region from request extensions first, then fall back to AWS config
 			if let Some(aws_region) = req.extensions().get::<AwsRegion>() {
 				aws_region.region.as_str()
@@ -1063,6 +1070,7 @@ mod resolve_tags_tests {
 	async fn sign_request_fails_closed_when_dynamic_tag_cannot_resolve() {
 		let auth = AwsAuth::Implicit {
 			service_name: None,
+			region: None,
 			assume_role: Some(AwsAssumeRole {
 				role_arn: "arn:aws:iam::123456789012:role/backend".to_string(),
 				session_name: None,
@@ -1090,6 +1098,7 @@ mod resolve_tags_tests {
 		assert!(err.is_some(), "expression should fail to compile");
 		let auth = AwsAuth::Implicit {
 			service_name: None,
+			region: None,
 			assume_role: Some(AwsAssumeRole {
 				role_arn: "arn:aws:iam::123456789012:role/backend".to_string(),
 				session_name: None,
@@ -1220,6 +1229,7 @@ mod resolve_session_name_tests {
 	async fn sign_request_fails_closed_when_session_name_cannot_resolve() {
 		let auth = AwsAuth::Implicit {
 			service_name: None,
+			region: None,
 			assume_role: Some(AwsAssumeRole {
 				role_arn: "arn:aws:iam::123456789012:role/backend".to_string(),
 				session_name: Some(dynamic(r#"request.headers["x-team"]"#)),
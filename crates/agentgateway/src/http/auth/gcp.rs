@@ -1,20 +1,23 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, anyhow};
 use google_cloud_auth::credentials::{self, AccessTokenCredentials};
 use headers::HeaderMapExt;
 use http::HeaderMap;
 use once_cell::sync::Lazy;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
 use secrecy::{ExposeSecret, SecretString};
 use serde::Deserialize;
 use serde_json::Value;
 use tracing::trace;
 
-use crate::serdes::{FileOrInline, schema};
+use crate::http::filters::BackendRequestTimeout;
+use crate::serdes::{FileOrInline, schema, serde_dur_option};
 use crate::types::agent::Target;
-use crate::{apply, const_string, ser_redact};
+use crate::{apply, client, const_string, ser_redact};
 
 const_string!(IdToken = "idToken");
 const_string!(AccessToken = "accessToken");
@@ -51,6 +54,10 @@ pub enum GcpAuth {
 		)]
 		#[cfg_attr(feature = "schema", schemars(with = "Option<FileOrInline>"))]
 		credential: Option<GcpCredential>,
+		/// Impersonate a service account. The credential (or ambient credentials) is used to mint
+		/// short-lived access tokens for the target service account via the IAM Credentials API.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		impersonate: Option<GcpImpersonation>,
 	},
 }
 
@@ -59,10 +66,164 @@ impl Default for GcpAuth {
 		Self::AccessToken {
 			r#type: Default::default(),
 			credential: Default::default(),
+			impersonate: Default::default(),
 		}
 	}
 }
 
+const IAM_CREDENTIALS_ENDPOINT: &str = "https://iamcredentials.googleapis.com";
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+/// Impersonated tokens are refreshed this long before they expire.
+const IMPERSONATED_TOKEN_REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+const IMPERSONATION_TIMEOUT: Duration = Duration::from_secs(10);
+const IMPERSONATION_RESPONSE_LIMIT: usize = 64 * 1024;
+
+#[apply(schema!)]
+pub struct GcpImpersonation {
+	/// Email of the service account to impersonate.
+	pub service_account: String,
+	/// Service accounts in the delegation chain, for when the caller cannot impersonate the target directly.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub delegates: Vec<String>,
+	/// OAuth scopes of the minted token. Defaults to `https://www.googleapis.com/auth/cloud-platform`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub scopes: Vec<String>,
+	/// Lifetime of the minted token. Defaults to 1h.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		with = "serde_dur_option"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	pub lifetime: Option<Duration>,
+	/// Base URL of the IAM Credentials API. Defaults to `https://iamcredentials.googleapis.com`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub endpoint: Option<String>,
+	/// The current impersonated token, populated on first use.
+	#[serde(skip)]
+	#[cfg_attr(feature = "schema", schemars(skip))]
+	pub(crate) cache: GcpImpersonatedTokenCache,
+}
+
+#[derive(Default, Clone)]
+pub struct GcpImpersonatedTokenCache(Arc<tokio::sync::Mutex<Option<ImpersonatedToken>>>);
+
+impl std::fmt::Debug for GcpImpersonatedTokenCache {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("GcpImpersonatedTokenCache")
+	}
+}
+
+struct ImpersonatedToken {
+	token: SecretString,
+	expires_at: SystemTime,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateAccessTokenResponse {
+	access_token: SecretString,
+	expire_time: String,
+}
+
+impl GcpImpersonation {
+	/// Returns a cached impersonated token, minting a new one with the token from `source` once the
+	/// cached token is within the refresh window of its expiry.
+	pub(super) async fn access_token(
+		&self,
+		client: &client::Client,
+		source: impl AsyncFnOnce() -> anyhow::Result<String>,
+	) -> anyhow::Result<String> {
+		// Hold the lock while refreshing so concurrent requests share a single mint.
+		let mut cached = self.cache.0.lock().await;
+		if let Some(token) = cached.as_ref()
+			&& token
+				.expires_at
+				.duration_since(SystemTime::now())
+				.is_ok_and(|remaining| remaining > IMPERSONATED_TOKEN_REFRESH_WINDOW)
+		{
+			return Ok(token.token.expose_secret().to_string());
+		}
+		let source_token = source().await?;
+		let token = self.generate_access_token(client, &source_token).await?;
+		let result = token.token.expose_secret().to_string();
+		*cached = Some(token);
+		Ok(result)
+	}
+
+	async fn generate_access_token(
+		&self,
+		client: &client::Client,
+		source_token: &str,
+	) -> anyhow::Result<ImpersonatedToken> {
+		let endpoint = self
+			.endpoint
+			.as_deref()
+			.unwrap_or(IAM_CREDENTIALS_ENDPOINT)
+			.trim_end_matches('/');
+		// The account is a single path segment; keep '/', '?' and friends from changing the URL.
+		const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+			.remove(b'-')
+			.remove(b'.')
+			.remove(b'_')
+			.remove(b'~')
+			.remove(b'@');
+		let uri = format!(
+			"{endpoint}/v1/projects/-/serviceAccounts/{}:generateAccessToken",
+			utf8_percent_encode(&self.service_account, PATH_SEGMENT)
+		);
+		let scopes: Vec<&str> = if self.scopes.is_empty() {
+			vec![CLOUD_PLATFORM_SCOPE]
+		} else {
+			self.scopes.iter().map(String::as_str).collect()
+		};
+		let mut body = serde_json::json!({
+			"scope": scopes,
+			"delegates": self
+				.delegates
+				.iter()
+				.map(|d| format!("projects/-/serviceAccounts/{d}"))
+				.collect::<Vec<_>>(),
+		});
+		if let Some(lifetime) = self.lifetime {
+			body["lifetime"] = Value::String(format!("{}s", lifetime.as_secs()));
+		}
+		let mut req = ::http::Request::builder()
+			.method(::http::Method::POST)
+			.uri(uri)
+			.header(::http::header::CONTENT_TYPE, "application/json")
+			.header(
+				::http::header::AUTHORIZATION,
+				format!("Bearer {source_token}"),
+			)
+			.body(crate::http::Body::from(serde_json::to_vec(&body)?))?;
+		req
+			.extensions_mut()
+			.insert(BackendRequestTimeout(IMPERSONATION_TIMEOUT));
+		let resp = client.simple_call(req).await?;
+		let status = resp.status();
+		let body = crate::http::read_body_with_limit(resp.into_body(), IMPERSONATION_RESPONSE_LIMIT)
+			.await
+			.context("failed to read generateAccessToken response")?;
+		if !status.is_success() {
+			anyhow::bail!(
+				"generateAccessToken for {} returned {status}: {}",
+				self.service_account,
+				String::from_utf8_lossy(&body)
+			);
+		}
+		let resp: GenerateAccessTokenResponse =
+			serde_json::from_slice(&body).context("failed to decode generateAccessToken response")?;
+		let expires_at = chrono::DateTime::parse_from_rfc3339(&resp.expire_time)
+			.with_context(|| format!("invalid expireTime {:?}", resp.expire_time))?;
+		trace!(service_account = %self.service_account, expire_time = %resp.expire_time, "minted impersonated GCP token");
+		Ok(ImpersonatedToken {
+			token: resp.access_token,
+			expires_at: expires_at.into(),
+		})
+	}
+}
+
 fn deser_optional_credential<'de, D>(deserializer: D) -> Result<Option<GcpCredential>, D::Error>
 where
 	D: serde::Deserializer<'de>,
@@ -329,7 +490,15 @@ async fn fetch_id_token(aud: &str) -> anyhow::Result<String> {
 	}
 }
 
+async fn source_access_token(credential: Option<&GcpCredential>) -> anyhow::Result<String> {
+	match credential {
+		Some(credential) => explicit_access_token(credential).await,
+		None => Ok(creds()?.access_token().await?.token),
+	}
+}
+
 pub(super) async fn insert_token(
+	client: &client::Client,
 	g: &GcpAuth,
 	call_target: &Target,
 	hm: &mut HeaderMap,
@@ -350,13 +519,18 @@ pub(super) async fn insert_token(
 				None => fetch_id_token(aud.as_ref()).await?,
 			}
 		},
-		GcpAuth::AccessToken { credential, .. } => match credential {
-			Some(credential) => explicit_access_token(credential).await?,
-			None => {
-				let token = creds()?.access_token().await?;
-				token.token
-			},
+		GcpAuth::AccessToken {
+			credential,
+			impersonate: Some(impersonate),
+			..
+		} => {
+			impersonate
+				.access_token(client, async || {
+					source_access_token(credential.as_ref()).await
+				})
+				.await?
 		},
+		GcpAuth::AccessToken { credential, .. } => source_access_token(credential.as_ref()).await?,
 	};
 	let header = headers::Authorization::bearer(&token)?;
 	hm.typed_insert(header);
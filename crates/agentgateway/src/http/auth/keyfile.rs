@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use super::AuthorizationLocation;
+use crate::apply;
+use crate::serdes::{schema, serde_dur_option};
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[apply(schema!)]
+pub struct LocalKeyFile {
+	/// Path to the file containing the secret.
+	pub path: PathBuf,
+	/// How often to re-read the file. Defaults to 60s.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		with = "serde_dur_option"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	pub refresh_interval: Option<Duration>,
+	/// Where to place the secret in the backend request.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub location: Option<AuthorizationLocation>,
+}
+
+/// A backend key read from a file that is periodically re-read, so rotated keys are picked up
+/// without a restart. If the file cannot be read, the last loaded key keeps being used.
+#[derive(Clone)]
+pub struct KeyFile {
+	pub path: PathBuf,
+	pub refresh_interval: Duration,
+	pub location: Option<AuthorizationLocation>,
+	state: Arc<Mutex<KeyState>>,
+}
+
+struct KeyState {
+	key: Arc<SecretString>,
+	checked: Instant,
+}
+
+impl KeyFile {
+	pub fn load(cfg: LocalKeyFile) -> anyhow::Result<Self> {
+		let contents = fs_err::read_to_string(&cfg.path)?;
+		let key = parse_key(contents)
+			.map_err(|e| anyhow::anyhow!("invalid key file {}: {e}", cfg.path.display()))?;
+		Ok(KeyFile {
+			path: cfg.path,
+			refresh_interval: cfg.refresh_interval.unwrap_or(DEFAULT_REFRESH_INTERVAL),
+			location: cfg.location,
+			state: Arc::new(Mutex::new(KeyState {
+				key: Arc::new(key),
+				checked: Instant::now(),
+			})),
+		})
+	}
+
+	/// Returns the current key, re-reading the file first if the refresh interval has elapsed.
+	pub(super) async fn key(&self) -> Arc<SecretString> {
+		let current = {
+			let mut state = self.state.lock().unwrap();
+			if state.checked.elapsed() < self.refresh_interval {
+				return state.key.clone();
+			}
+			// Claim this refresh; concurrent requests keep using the current key meanwhile.
+			state.checked = Instant::now();
+			state.key.clone()
+		};
+		let reloaded = tokio::fs::read_to_string(&self.path)
+			.await
+			.map_err(anyhow::Error::from)
+			.and_then(parse_key);
+		match reloaded {
+			Ok(key) => {
+				let key = Arc::new(key);
+				let mut state = self.state.lock().unwrap();
+				if !Arc::ptr_eq(&state.key, &current) {
+					// Raced with another refresh; keep whichever landed.
+					return state.key.clone();
+				}
+				debug!(path = %self.path.display(), "reloaded backend key file");
+				state.key = key.clone();
+				key
+			},
+			Err(e) => {
+				warn!(path = %self.path.display(), "failed to reload backend key file, keeping the previous key: {e}");
+				current
+			},
+		}
+	}
+}
+
+fn parse_key(contents: String) -> anyhow::Result<SecretString> {
+	let key = contents.trim();
+	if key.is_empty() {
+		anyhow::bail!("key file is empty");
+	}
+	Ok(SecretString::from(key.to_string()))
+}
+
+impl std::fmt::Debug for KeyFile {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("KeyFile")
+			.field("path", &self.path)
+			.field("refresh_interval", &self.refresh_interval)
+			.field("location", &self.location)
+			.finish_non_exhaustive()
+	}
+}
+
+impl Serialize for KeyFile {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		LocalKeyFile {
+			path: self.path.clone(),
+			refresh_interval: Some(self.refresh_interval),
+			location: self.location.clone(),
+		}
+		.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for KeyFile {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		let cfg = LocalKeyFile::deserialize(deserializer)?;
+		KeyFile::load(cfg).map_err(serde::de::Error::custom)
+	}
+}
@@ -2,6 +2,7 @@ pub mod aws;
 pub mod azure;
 mod copilot;
 pub mod gcp;
+pub mod keyfile;
 pub mod oauth;
 
 use std::borrow::Cow;
@@ -47,6 +48,11 @@ pub enum BackendAuth {
 		#[serde(default, skip_serializing_if = "Option::is_none")]
 		location: Option<AuthorizationLocation>,
 	},
+	/// Send a secret read from a file to the backend. The file is re-read periodically, so rotated
+	/// keys are picked up without a restart.
+	KeyFile(
+		#[cfg_attr(feature = "schema", schemars(with = "keyfile::LocalKeyFile"))] keyfile::KeyFile,
+	),
 	/// Authenticate to Google Cloud services.
 	#[serde(rename = "gcp")]
 	Gcp(gcp::GcpAuth),
@@ -146,10 +152,26 @@ pub async fn apply_backend_auth(
 				.extensions_mut()
 				.insert(AppliedBackendAuthLocation { explicit });
 		},
+		BackendAuth::KeyFile(key_file) => {
+			let explicit = key_file.location.is_some();
+			let resolved = key_file
+				.location
+				.as_ref()
+				.unwrap_or(&DEFAULT_AUTHORIZATION_LOCATION);
+			resolved.insert(req, key_file.key().await.expose_secret())?;
+			req
+				.extensions_mut()
+				.insert(AppliedBackendAuthLocation { explicit });
+		},
 		BackendAuth::Gcp(g) => {
-			gcp::insert_token(g, &backend_info.call_target, req.headers_mut())
-				.await
-				.map_err(ProxyError::BackendAuthenticationFailed)?;
+			gcp::insert_token(
+				&backend_info.inputs.upstream,
+				g,
+				&backend_info.call_target,
+				req.headers_mut(),
+			)
+			.await
+			.map_err(ProxyError::BackendAuthenticationFailed)?;
 		},
 		BackendAuth::Aws(_) => {
 			// We handle this in 'apply_late_backend_auth' since it must come at the end (due to request signing)!
@@ -1,5 +1,6 @@
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use agent_core::strng;
 use agent_core::strng::Strng;
@@ -13,8 +14,13 @@ use tracing::trace;
 use crate::serdes::{schema_de, schema_ser};
 use crate::transport::tls;
 use crate::types::agent::{parse_cert, parse_key};
+use crate::types::backend::HttpVersionPreference;
 use crate::{apply, transport};
 
+#[cfg(test)]
+#[path = "backendtls_tests.rs"]
+mod backendtls_tests;
+
 pub static SYSTEM_TRUST: Lazy<BackendTLS> =
 	Lazy::new(|| ResolvedBackendTLS::default().try_into().unwrap());
 pub static INSECURE_TRUST: Lazy<BackendTLS> = Lazy::new(|| {
@@ -23,6 +29,7 @@ pub static INSECURE_TRUST: Lazy<BackendTLS> = Lazy::new(|| {
 		key: None,
 		root: None,
 		hostname: None,
+		verify_hostname: None,
 		insecure: true,
 		insecure_host: false,
 		alpn: None,
@@ -40,6 +47,7 @@ pub struct PerAlpnConfig {
 	allow_custom_alpn: bool,
 	h1: Arc<std::sync::OnceLock<Arc<ClientConfig>>>,
 	h2: Arc<std::sync::OnceLock<Arc<ClientConfig>>>,
+	no_alpn: Arc<std::sync::OnceLock<Arc<ClientConfig>>>,
 }
 
 impl PerAlpnConfig {
@@ -49,6 +57,7 @@ impl PerAlpnConfig {
 			allow_custom_alpn,
 			h1: Default::default(),
 			h2: Default::default(),
+			no_alpn: Default::default(),
 		}
 	}
 
@@ -73,6 +82,20 @@ impl PerAlpnConfig {
 			_ => self.config.clone(),
 		}
 	}
+
+	fn config_without_alpn(&self) -> Arc<ClientConfig> {
+		if !self.allow_custom_alpn {
+			return self.config.clone();
+		}
+		self
+			.no_alpn
+			.get_or_init(|| {
+				let mut nc = Arc::unwrap_or_clone(self.config.clone());
+				nc.alpn_protocols = vec![];
+				Arc::new(nc)
+			})
+			.clone()
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -93,6 +116,23 @@ impl BackendTLS {
 			config: self.config.config_for(version_override),
 		}
 	}
+
+	/// Like [`BackendTLS::config_for`], but an explicit version preference decides which ALPN
+	/// protocols are offered. An `alpn` set on the TLS policy itself always wins.
+	pub fn config_for_preference(
+		&self,
+		preference: HttpVersionPreference,
+		version_override: Option<http::Version>,
+	) -> VersionedBackendTLS {
+		match preference {
+			HttpVersionPreference::Auto => self.config_for(version_override),
+			HttpVersionPreference::Http2PriorKnowledge => VersionedBackendTLS {
+				hostname_override: self.hostname_override.clone(),
+				config: self.config.config_without_alpn(),
+			},
+			pref => self.config_for(pref.version()),
+		}
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -135,6 +175,8 @@ pub struct BackendTLSInfo {
 	pub root: Option<Strng>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub hostname: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub verify_hostname: Option<String>,
 	#[serde(default, skip_serializing_if = "is_false")]
 	pub insecure: bool,
 	#[serde(default, skip_serializing_if = "is_false")]
@@ -155,6 +197,7 @@ impl BackendTLSInfo {
 			cert: tls.cert.as_ref().map(pem_to_string),
 			root: tls.root.as_ref().map(pem_to_string),
 			hostname: tls.hostname.clone(),
+			verify_hostname: tls.verify_hostname.clone(),
 			insecure: tls.insecure,
 			insecure_host: tls.insecure_host,
 			system_roots: tls.root.is_none(),
@@ -172,7 +215,7 @@ fn pem_to_string(pem: impl AsRef<[u8]>) -> Strng {
 fn is_false(value: &bool) -> bool {
 	!*value
 }
-static SYSTEM_ROOT: Lazy<rustls_native_certs::CertificateResult> =
+pub(crate) static SYSTEM_ROOT: Lazy<rustls_native_certs::CertificateResult> =
 	Lazy::new(rustls_native_certs::load_native_certs);
 
 #[apply(schema_de!)]
@@ -186,6 +229,8 @@ pub struct LocalBackendTLS {
 	root: Option<PathBuf>,
 	/// Server name to use for TLS verification and SNI.
 	hostname: Option<String>,
+	/// Server name to verify the backend certificate against, when it differs from the SNI sent.
+	verify_hostname: Option<String>,
 	/// Skip certificate trust verification for the backend connection.
 	#[serde(default)]
 	insecure: bool,
@@ -203,13 +248,15 @@ pub struct LocalBackendTLS {
 	key_exchange_groups: Option<Vec<tls::KeyExchangeGroup>>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ResolvedBackendTLS {
 	pub cert: Option<Vec<u8>>,
 	pub key: Option<Vec<u8>>,
 	pub root: Option<Vec<u8>>,
 	// If set, override the SNI. Otherwise, it will automatically be set.
 	pub hostname: Option<String>,
+	// If set, verify the certificate against this name rather than the SNI.
+	pub verify_hostname: Option<String>,
 	pub insecure: bool,
 	pub insecure_host: bool,
 	pub alpn: Option<Vec<String>>,
@@ -217,9 +264,42 @@ pub struct ResolvedBackendTLS {
 	pub key_exchange_groups: Option<Vec<tls::KeyExchangeGroup>>,
 }
 
+/// Backend TLS configs built from the local config, keyed by their resolved inputs.
+/// Connection pools are keyed by the identity of the TLS config, so handing out the same config for
+/// unchanged inputs lets a reload keep the existing connections to backends that did not change.
+#[derive(Debug, Default)]
+pub struct BackendTLSCache {
+	configs: Mutex<HashMap<ResolvedBackendTLS, BackendTLS>>,
+}
+
+impl BackendTLSCache {
+	/// Returns the config built earlier from identical inputs, or builds and caches a new one.
+	pub fn get_or_build(&self, resolved: ResolvedBackendTLS) -> anyhow::Result<BackendTLS> {
+		let mut configs = self.configs.lock().expect("mutex acquired");
+		if let Some(tls) = configs.get(&resolved) {
+			return Ok(tls.clone());
+		}
+		let tls = resolved.clone().try_into()?;
+		configs.insert(resolved, tls.clone());
+		Ok(tls)
+	}
+
+	/// Drops the configs built from inputs outside `retained`.
+	pub fn retain(&self, retained: &HashSet<ResolvedBackendTLS>) {
+		self
+			.configs
+			.lock()
+			.expect("mutex acquired")
+			.retain(|resolved, _| retained.contains(resolved));
+	}
+}
+
 impl ResolvedBackendTLS {
 	pub fn try_into(self) -> anyhow::Result<BackendTLS> {
 		let metadata = BackendTLSInfo::from_resolved(&self);
+		if self.verify_hostname.is_some() && self.subject_alt_names.is_some() {
+			anyhow::bail!("verifyHostname and subjectAltNames cannot both be set");
+		}
 		let mut roots = rustls::RootCertStore::empty();
 		if let Some(root) = self.root {
 			let certs = CertificateDer::pem_slice_iter(&root).collect::<Result<Vec<_>, _>>()?;
@@ -258,7 +338,11 @@ impl ResolvedBackendTLS {
 		} else if self.insecure {
 			cc.dangerous()
 				.set_certificate_verifier(Arc::new(tls::insecure::NoVerifier));
-		} else if let Some(alt_sans) = self.subject_alt_names {
+		} else if let Some(alt_sans) = self
+			.subject_alt_names
+			.or_else(|| self.verify_hostname.map(|h| vec![h]))
+		{
+			// The certificate is checked against these names, regardless of the SNI sent.
 			let sans = alt_sans
 				.into_iter()
 				.map(tls::ExtendedServerName::try_from)
@@ -316,17 +400,17 @@ impl LocalBackendTLS {
 			None => None,
 		};
 
-		ResolvedBackendTLS {
+		resources.backend_tls(ResolvedBackendTLS {
 			cert,
 			key,
 			root,
 			hostname: self.hostname,
+			verify_hostname: self.verify_hostname,
 			insecure: self.insecure,
 			insecure_host: self.insecure_host,
 			alpn: self.alpn,
 			subject_alt_names: self.subject_alt_names,
 			key_exchange_groups: self.key_exchange_groups,
-		}
-		.try_into()
+		})
 	}
 }
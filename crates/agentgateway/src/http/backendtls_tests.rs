@@ -0,0 +1,84 @@
+use super::*;
+use crate::http::tests_common::request;
+use crate::types::backend;
+
+fn backend_tls(alpn: Option<Vec<String>>) -> BackendTLS {
+	ResolvedBackendTLS {
+		insecure: true,
+		alpn,
+		..Default::default()
+	}
+	.try_into()
+	.unwrap()
+}
+
+fn alpn(tls: &VersionedBackendTLS) -> Vec<&[u8]> {
+	tls
+		.config
+		.alpn_protocols
+		.iter()
+		.map(Vec::as_slice)
+		.collect()
+}
+
+#[test]
+fn version_preference_selects_alpn() {
+	let tls = backend_tls(None);
+	let auto = tls.config_for_preference(HttpVersionPreference::Auto, None);
+	assert_eq!(alpn(&auto), vec![&b"h2"[..], &b"http/1.1"[..]]);
+
+	let h2 = tls.config_for_preference(HttpVersionPreference::Http2Only, None);
+	assert_eq!(alpn(&h2), vec![&b"h2"[..]]);
+	// The preference wins over a version detected from the service.
+	let h2 = tls.config_for_preference(
+		HttpVersionPreference::Http2Only,
+		Some(http::Version::HTTP_11),
+	);
+	assert_eq!(alpn(&h2), vec![&b"h2"[..]]);
+
+	let h1 = tls.config_for_preference(HttpVersionPreference::Http1Only, None);
+	assert_eq!(alpn(&h1), vec![&b"http/1.1"[..]]);
+	assert!(!alpn(&h1).contains(&&b"h2"[..]));
+
+	let prior = tls.config_for_preference(HttpVersionPreference::Http2PriorKnowledge, None);
+	assert!(alpn(&prior).is_empty());
+	// Configs are cached, so connections keep sharing a pool.
+	assert_eq!(
+		prior,
+		tls.config_for_preference(HttpVersionPreference::Http2PriorKnowledge, None)
+	);
+}
+
+#[test]
+fn explicit_alpn_is_not_overridden() {
+	let tls = backend_tls(Some(vec!["http/1.1".to_string()]));
+	for preference in [
+		HttpVersionPreference::Http2Only,
+		HttpVersionPreference::Http2PriorKnowledge,
+	] {
+		let cfg = tls.config_for_preference(preference, None);
+		assert_eq!(alpn(&cfg), vec![&b"http/1.1"[..]]);
+	}
+}
+
+#[test]
+fn version_preference_sets_request_version() {
+	for (preference, version) in [
+		(HttpVersionPreference::Http1Only, http::Version::HTTP_11),
+		(HttpVersionPreference::Http2Only, http::Version::HTTP_2),
+		(
+			HttpVersionPreference::Http2PriorKnowledge,
+			http::Version::HTTP_2,
+		),
+	] {
+		let mut req = request("http://example.com/", http::Method::GET, &[]);
+		backend::HTTP {
+			// The preference takes precedence over an explicit version.
+			version: Some(http::Version::HTTP_10),
+			http_version_preference: preference,
+			..Default::default()
+		}
+		.apply(&mut req, None);
+		assert_eq!(req.version(), version, "{preference:?}");
+	}
+}
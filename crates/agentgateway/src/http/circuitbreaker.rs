@@ -0,0 +1,319 @@
+//! Per-backend circuit breaking.
+//!
+//! Bounds the number of requests in flight to a backend. Once `max_concurrent_requests` are
+//! active, up to `max_pending_requests` more wait for a slot; anything beyond that is rejected
+//! immediately with a 503 rather than piling more load onto a saturated backend.
+//!
+//! Pending requests can be grouped by a `queueKey` expression (a tenant, a priority, ...). Freed
+//! slots are handed to the groups in turn, so a burst from one group cannot starve the others.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::Histogram;
+use serde::de::Error;
+use tokio::sync::oneshot;
+
+use crate::cel::{Executor, Expression};
+use crate::http::Request;
+use crate::proxy::ProxyError;
+use crate::{serde_dur_option, *};
+
+#[cfg(test)]
+#[path = "circuitbreaker_tests.rs"]
+mod circuitbreaker_tests;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(with = "CircuitBreakerSpec"))]
+#[derive(serde::Serialize)]
+pub struct CircuitBreaker {
+	#[serde(skip_serializing)]
+	state: Arc<State>,
+	#[serde(flatten)]
+	pub spec: CircuitBreakerSpec,
+}
+
+impl<'de> serde::Deserialize<'de> for CircuitBreaker {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		let spec = CircuitBreakerSpec::deserialize(deserializer)?;
+		CircuitBreaker::try_from(spec).map_err(D::Error::custom)
+	}
+}
+
+#[apply(schema!)]
+pub struct CircuitBreakerSpec {
+	/// Maximum number of requests sent to the backend at the same time.
+	pub max_concurrent_requests: u32,
+	/// Maximum number of requests waiting for a slot once the concurrency limit is reached.
+	/// Requests beyond this are rejected immediately.
+	#[serde(default)]
+	pub max_pending_requests: u32,
+	/// Maximum time a request waits for a slot before it is rejected. When unset, pending
+	/// requests wait until a slot is freed.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		with = "serde_dur_option"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	pub max_pending_wait: Option<Duration>,
+	/// CEL expression grouping pending requests, such as `jwt.sub` or a priority header. Freed
+	/// slots go to each group in turn. When unset, or when the expression fails to evaluate,
+	/// requests share one group and are admitted in arrival order.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub queue_key: Option<Arc<Expression>>,
+	/// Value of the `Retry-After` header returned when a request is rejected. Defaults to 1s.
+	#[serde(default = "default_retry_after", with = "serde_dur")]
+	#[cfg_attr(feature = "schema", schemars(with = "String"))]
+	pub retry_after: Duration,
+}
+
+fn default_retry_after() -> Duration {
+	Duration::from_secs(1)
+}
+
+#[derive(Debug)]
+struct State {
+	max_concurrent: u32,
+	queue: Mutex<Queue>,
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+	active: u32,
+	pending: u32,
+	next_id: u64,
+	/// Groups with waiting requests, in the order they are served.
+	groups: VecDeque<Strng>,
+	waiters: HashMap<Strng, VecDeque<Waiter>>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+	id: u64,
+	grant: oneshot::Sender<CircuitBreakerPermit>,
+}
+
+impl Queue {
+	fn push(&mut self, group: Strng, grant: oneshot::Sender<CircuitBreakerPermit>) -> u64 {
+		let id = self.next_id;
+		self.next_id += 1;
+		self.pending += 1;
+		let waiters = self.waiters.entry(group.clone()).or_default();
+		if waiters.is_empty() {
+			self.groups.push_back(group);
+		}
+		waiters.push_back(Waiter { id, grant });
+		id
+	}
+
+	/// Takes the first waiter of the next group, moving that group to the back of the rotation.
+	fn pop(&mut self) -> Option<Waiter> {
+		let group = self.groups.pop_front()?;
+		let waiters = self
+			.waiters
+			.get_mut(&group)
+			.expect("queued group has waiters");
+		let waiter = waiters.pop_front().expect("queued group has waiters");
+		if waiters.is_empty() {
+			self.waiters.remove(&group);
+		} else {
+			self.groups.push_back(group);
+		}
+		self.pending -= 1;
+		Some(waiter)
+	}
+
+	fn remove(&mut self, group: &Strng, id: u64) {
+		let Some(waiters) = self.waiters.get_mut(group) else {
+			return;
+		};
+		let Some(idx) = waiters.iter().position(|w| w.id == id) else {
+			return;
+		};
+		waiters.remove(idx);
+		self.pending -= 1;
+		if waiters.is_empty() {
+			self.waiters.remove(group);
+			self.groups.retain(|g| g != group);
+		}
+	}
+}
+
+impl State {
+	/// Hands a freed slot to the next pending request, or returns it to the pool.
+	fn release(self: &Arc<Self>) {
+		loop {
+			let waiter = {
+				let mut queue = self.queue.lock().expect("mutex poisoned");
+				match queue.pop() {
+					Some(waiter) => waiter,
+					None => {
+						queue.active -= 1;
+						return;
+					},
+				}
+			};
+			match waiter.grant.send(CircuitBreakerPermit {
+				state: Some(self.clone()),
+			}) {
+				Ok(()) => return,
+				// The waiter gave up; keep the slot and offer it to the next one.
+				Err(mut permit) => permit.state = None,
+			}
+		}
+	}
+}
+
+impl TryFrom<CircuitBreakerSpec> for CircuitBreaker {
+	type Error = anyhow::Error;
+	fn try_from(spec: CircuitBreakerSpec) -> Result<Self, Self::Error> {
+		if spec.max_concurrent_requests == 0 {
+			anyhow::bail!("maxConcurrentRequests must be greater than 0");
+		}
+		Ok(CircuitBreaker {
+			state: Arc::new(State {
+				max_concurrent: spec.max_concurrent_requests,
+				queue: Mutex::new(Queue::default()),
+			}),
+			spec,
+		})
+	}
+}
+
+/// Holds one of the backend's concurrency slots; the slot is released when this is dropped.
+#[derive(Debug)]
+pub struct CircuitBreakerPermit {
+	state: Option<Arc<State>>,
+}
+
+impl Drop for CircuitBreakerPermit {
+	fn drop(&mut self) {
+		if let Some(state) = self.state.take() {
+			state.release();
+		}
+	}
+}
+
+/// Metrics for requests waiting in the pending queue.
+#[derive(Debug, Clone)]
+pub struct QueueMetrics {
+	/// Number of requests currently waiting.
+	pub depth: Gauge,
+	/// Time each request spent waiting, whether it was admitted or rejected.
+	pub wait: Histogram,
+}
+
+impl CircuitBreaker {
+	/// Claims a concurrency slot, waiting in the pending queue if the backend is saturated.
+	/// Fails with [`ProxyError::CircuitBreakerOpen`] when the pending queue is full, or when no
+	/// slot is freed within `max_pending_wait`.
+	pub async fn acquire(
+		&self,
+		req: &Request,
+		metrics: &QueueMetrics,
+	) -> Result<CircuitBreakerPermit, ProxyError> {
+		let group = self.queue_key(req);
+		let (rx, id) = {
+			let mut queue = self.state.queue.lock().expect("mutex poisoned");
+			if queue.active < self.state.max_concurrent {
+				queue.active += 1;
+				return Ok(CircuitBreakerPermit {
+					state: Some(self.state.clone()),
+				});
+			}
+			if queue.pending >= self.spec.max_pending_requests {
+				return Err(self.open());
+			}
+			let (tx, rx) = oneshot::channel();
+			(rx, queue.push(group.clone(), tx))
+		};
+		let _pending = PendingGuard::new(&self.state, group, id, metrics);
+		let granted = match self.spec.max_pending_wait {
+			Some(wait) => tokio::time::timeout(wait, rx).await.ok(),
+			None => Some(rx.await),
+		};
+		match granted {
+			Some(Ok(permit)) => Ok(permit),
+			// Timed out. The sender is only dropped with the breaker itself.
+			_ => Err(self.open()),
+		}
+	}
+
+	/// Number of requests currently holding a slot.
+	pub fn active_requests(&self) -> u32 {
+		self.state.queue.lock().expect("mutex poisoned").active
+	}
+
+	/// Number of requests waiting for a slot.
+	pub fn pending_requests(&self) -> u32 {
+		self.state.queue.lock().expect("mutex poisoned").pending
+	}
+
+	fn queue_key(&self, req: &Request) -> Strng {
+		self
+			.spec
+			.queue_key
+			.as_ref()
+			.and_then(|expr| {
+				Executor::new_request(req)
+					.eval(expr)
+					.inspect_err(|e| trace!("circuit breaker queue key failed to evaluate: {e}"))
+					.ok()?
+					.as_string()
+					.ok()
+			})
+			.map(strng::new)
+			.unwrap_or_default()
+	}
+
+	fn open(&self) -> ProxyError {
+		ProxyError::CircuitBreakerOpen {
+			retry_after: self.spec.retry_after,
+		}
+	}
+}
+
+/// Leaves the pending queue when dropped, including when the waiting request is cancelled.
+struct PendingGuard<'a> {
+	state: &'a Arc<State>,
+	group: Strng,
+	id: u64,
+	metrics: &'a QueueMetrics,
+	start: Instant,
+}
+
+impl<'a> PendingGuard<'a> {
+	fn new(state: &'a Arc<State>, group: Strng, id: u64, metrics: &'a QueueMetrics) -> Self {
+		metrics.depth.inc();
+		PendingGuard {
+			state,
+			group,
+			id,
+			metrics,
+			start: Instant::now(),
+		}
+	}
+}
+
+impl Drop for PendingGuard<'_> {
+	fn drop(&mut self) {
+		self
+			.state
+			.queue
+			.lock()
+			.expect("mutex poisoned")
+			.remove(&self.group, self.id);
+		self.metrics.depth.dec();
+		self
+			.metrics
+			.wait
+			.observe(self.start.elapsed().as_secs_f64());
+	}
+}
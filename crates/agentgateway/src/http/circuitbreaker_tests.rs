@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use super::*;
+use crate::http::tests_common::request;
+
+fn breaker(max_concurrent_requests: u32, max_pending_requests: u32) -> CircuitBreaker {
+	CircuitBreaker::try_from(CircuitBreakerSpec {
+		max_concurrent_requests,
+		max_pending_requests,
+		max_pending_wait: None,
+		queue_key: None,
+		retry_after: Duration::from_secs(5),
+	})
+	.unwrap()
+}
+
+fn metrics() -> QueueMetrics {
+	QueueMetrics {
+		depth: Gauge::default(),
+		wait: Histogram::new([0.1, 1.0]),
+	}
+}
+
+fn tenant(name: &str) -> Request {
+	request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-tenant", name)],
+	)
+}
+
+async fn wait_for_pending(cb: &CircuitBreaker, n: u32) {
+	while cb.pending_requests() != n {
+		tokio::task::yield_now().await;
+	}
+}
+
+#[tokio::test]
+async fn exceeding_max_concurrency_is_rejected_with_503() {
+	let cb = breaker(2, 0);
+	let (req, m) = (tenant("a"), metrics());
+	let _a = cb.acquire(&req, &m).await.unwrap();
+	let _b = cb.acquire(&req, &m).await.unwrap();
+	assert_eq!(cb.active_requests(), 2);
+
+	let err = cb.acquire(&req, &m).await.unwrap_err();
+	assert!(matches!(err, ProxyError::CircuitBreakerOpen { .. }));
+	let resp = err.into_response_with_grpc(false);
+	assert_eq!(resp.status(), ::http::StatusCode::SERVICE_UNAVAILABLE);
+	assert_eq!(
+		resp.headers().get(::http::header::RETRY_AFTER).unwrap(),
+		"5"
+	);
+}
+
+#[tokio::test]
+async fn completion_frees_capacity() {
+	let cb = breaker(1, 0);
+	let (req, m) = (tenant("a"), metrics());
+	let permit = cb.acquire(&req, &m).await.unwrap();
+	assert!(cb.acquire(&req, &m).await.is_err());
+
+	drop(permit);
+	assert_eq!(cb.active_requests(), 0);
+	let _permit = cb
+		.acquire(&req, &m)
+		.await
+		.expect("slot is released on completion");
+	assert_eq!(cb.active_requests(), 1);
+}
+
+#[tokio::test]
+async fn pending_requests_wait_for_a_slot() {
+	let cb = breaker(1, 1);
+	let (req, m) = (tenant("a"), metrics());
+	let permit = cb.acquire(&req, &m).await.unwrap();
+
+	let waiter = tokio::spawn({
+		let (cb, m) = (cb.clone(), m.clone());
+		async move { cb.acquire(&tenant("a"), &m).await.map(|_| ()) }
+	});
+	wait_for_pending(&cb, 1).await;
+	assert_eq!(m.depth.get(), 1);
+	// The queue is full, so a third request is rejected.
+	assert!(cb.acquire(&req, &m).await.is_err());
+
+	drop(permit);
+	waiter
+		.await
+		.unwrap()
+		.expect("queued request gets the freed slot");
+	assert_eq!(cb.pending_requests(), 0);
+	assert_eq!(m.depth.get(), 0);
+	assert_eq!(cb.active_requests(), 0);
+}
+
+#[tokio::test]
+async fn pending_groups_are_served_in_turn() {
+	let cb = CircuitBreaker::try_from(CircuitBreakerSpec {
+		queue_key: Some(Arc::new(
+			Expression::new_strict(r#"request.headers["x-tenant"]"#).unwrap(),
+		)),
+		..breaker(1, 10).spec
+	})
+	.unwrap();
+	let m = metrics();
+	let permit = cb.acquire(&tenant("a"), &m).await.unwrap();
+
+	// A burst from tenant a queues ahead of a single request from tenant b.
+	let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+	for (i, name) in ["a1", "a2", "a3", "b1"].into_iter().enumerate() {
+		tokio::spawn({
+			let (cb, m, tx) = (cb.clone(), m.clone(), tx.clone());
+			async move {
+				let permit = cb.acquire(&tenant(&name[..1]), &m).await.unwrap();
+				tx.send((name, permit)).unwrap();
+			}
+		});
+		wait_for_pending(&cb, i as u32 + 1).await;
+	}
+
+	drop(permit);
+	let mut order = Vec::new();
+	for _ in 0..4 {
+		let (name, permit) = rx.recv().await.unwrap();
+		order.push(name);
+		drop(permit);
+	}
+	assert_eq!(order, vec!["a1", "b1", "a2", "a3"]);
+	assert_eq!(cb.active_requests(), 0);
+	assert_eq!(m.depth.get(), 0);
+}
+
+#[tokio::test]
+async fn pending_requests_give_up_after_max_wait() {
+	let cb = CircuitBreaker::try_from(CircuitBreakerSpec {
+		max_pending_wait: Some(Duration::from_millis(20)),
+		..breaker(1, 1).spec
+	})
+	.unwrap();
+	let (req, m) = (tenant("a"), metrics());
+	let permit = cb.acquire(&req, &m).await.unwrap();
+
+	let err = cb.acquire(&req, &m).await.unwrap_err();
+	assert!(matches!(err, ProxyError::CircuitBreakerOpen { .. }));
+	assert_eq!(cb.pending_requests(), 0);
+	assert_eq!(m.depth.get(), 0);
+
+	// The slot goes back to the pool rather than to the request that gave up.
+	drop(permit);
+	assert_eq!(cb.active_requests(), 0);
+}
+
+#[test]
+fn spec_validation() {
+	assert!(
+		CircuitBreaker::try_from(CircuitBreakerSpec {
+			max_concurrent_requests: 0,
+			..breaker(1, 0).spec
+		})
+		.is_err()
+	);
+
+	let cb: CircuitBreaker = serde_json::from_str(r#"{"maxConcurrentRequests": 3}"#).unwrap();
+	assert_eq!(cb.spec.max_pending_requests, 0);
+	assert_eq!(cb.spec.max_pending_wait, None);
+	assert_eq!(cb.spec.retry_after, Duration::from_secs(1));
+}
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use ::http::{HeaderValue, Method, StatusCode, header};
+use regex::Regex;
 use serde::de::Error;
 
 use crate::http::{PolicyResponse, Request, filters};
@@ -86,6 +87,8 @@ pub struct Cors {
 	allow_methods: WildcardOrList<::http::Method>,
 	#[serde(skip_serializing_if = "WildcardOrList::is_none")]
 	allow_origins: WildcardOrList<Strng>,
+	#[serde(with = "serde_regex", skip_serializing_if = "Vec::is_empty")]
+	allow_origin_regexes: Vec<Regex>,
 	#[serde(skip_serializing_if = "WildcardOrList::is_none")]
 	expose_headers: WildcardOrList<http::HeaderName>,
 	#[serde(serialize_with = "ser_string_or_bytes_option")]
@@ -115,6 +118,10 @@ pub struct CorsSerde {
 	/// Request origins that receive CORS response headers. Use `*` to match any origin.
 	#[serde(default)]
 	pub allow_origins: Vec<String>,
+	/// Regular expressions matched against the full request origin, such as `https://.*\.example\.com`.
+	/// Origins matching any expression are allowed in addition to `allowOrigins`.
+	#[serde(default)]
+	pub allow_origin_regexes: Vec<String>,
 	/// Values to return in `Access-Control-Expose-Headers` for allowed CORS responses.
 	#[serde(default)]
 	pub expose_headers: Vec<String>,
@@ -132,6 +139,11 @@ impl TryFrom<CorsSerde> for Cors {
 			allow_headers: WildcardOrList::try_from(value.allow_headers)?,
 			allow_methods: WildcardOrList::try_from(value.allow_methods)?,
 			allow_origins: WildcardOrList::try_from(value.allow_origins)?,
+			allow_origin_regexes: value
+				.allow_origin_regexes
+				.iter()
+				.map(|r| Regex::new(&format!("^(?:{r})$")))
+				.collect::<Result<_, _>>()?,
 			expose_headers: WildcardOrList::try_from(value.expose_headers)?,
 			max_age: value
 				.max_age
@@ -174,7 +186,12 @@ impl Cors {
 					.iter()
 					.any(|allowed_origin| matches_allowed_origin(allowed_origin.as_str(), request_origin))
 			}),
-		};
+		} || origin.to_str().is_ok_and(|origin| {
+			self
+				.allow_origin_regexes
+				.iter()
+				.any(|re| re.is_match(origin))
+		});
 
 		if !origin_allowed {
 			if is_preflight {
@@ -513,6 +530,7 @@ mod tests {
 			allow_headers: vec!["*".to_string()],
 			allow_methods: vec!["*".to_string()],
 			allow_origins: vec!["*".to_string()],
+			allow_origin_regexes: vec![],
 			expose_headers: vec![],
 			max_age: None,
 		})
@@ -546,4 +564,91 @@ mod tests {
 			Some("x-header-1, x-header-2")
 		);
 	}
+
+	fn regex_cors() -> Cors {
+		Cors::try_from(CorsSerde {
+			allow_credentials: true,
+			allow_headers: vec!["content-type".to_string()],
+			allow_methods: vec!["POST".to_string()],
+			allow_origins: vec![],
+			allow_origin_regexes: vec![r"https://[a-z]+\.example\.com".to_string()],
+			expose_headers: vec![],
+			max_age: Some(Duration::from_secs(60)),
+		})
+		.expect("valid cors policy")
+	}
+
+	fn request(method: Method, origin: &str) -> Request {
+		::http::Request::builder()
+			.method(method)
+			.uri("http://lo")
+			.header(header::ORIGIN, origin)
+			.header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+			.body(crate::http::Body::empty())
+			.expect("valid request")
+	}
+
+	#[test]
+	fn regex_origin_match_adds_response_headers() {
+		let mut req = request(Method::POST, "https://app.example.com");
+		let response = regex_cors().apply(&mut req).expect("cors evaluation");
+		assert!(response.direct_response.is_none());
+		let headers = response.response_headers.expect("cors headers");
+		assert_eq!(
+			headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+			"https://app.example.com"
+		);
+		assert_eq!(
+			headers
+				.get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+				.unwrap(),
+			"true"
+		);
+	}
+
+	#[test]
+	fn regex_origin_must_match_fully() {
+		for origin in [
+			"https://app.example.com.evil.com",
+			"http://app.example.com",
+			"https://example.com",
+		] {
+			let mut req = request(Method::POST, origin);
+			let response = regex_cors().apply(&mut req).expect("cors evaluation");
+			assert!(response.direct_response.is_none(), "{origin}");
+			assert!(response.response_headers.is_none(), "{origin}");
+		}
+	}
+
+	#[test]
+	fn regex_origin_preflight_short_circuits() {
+		let mut req = request(Method::OPTIONS, "https://app.example.com");
+		let response = regex_cors().apply(&mut req).expect("cors evaluation");
+		let direct = response.direct_response.expect("preflight response");
+		assert_eq!(direct.status(), StatusCode::OK);
+		let headers = direct.headers();
+		assert_eq!(
+			headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+			"https://app.example.com"
+		);
+		assert_eq!(
+			headers.get(header::ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+			"POST"
+		);
+		assert_eq!(
+			headers.get(header::ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+			"content-type"
+		);
+		assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "60");
+
+		let mut req = request(Method::OPTIONS, "https://example.org");
+		let response = regex_cors().apply(&mut req).expect("cors evaluation");
+		let direct = response.direct_response.expect("preflight response");
+		assert!(
+			direct
+				.headers()
+				.get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+				.is_none()
+		);
+	}
 }
@@ -0,0 +1,238 @@
+//! gRPC-Web translation.
+//!
+//! Browsers cannot read HTTP/2 trailers, so gRPC-Web carries them in the response body instead.
+//! This policy turns `application/grpc-web[+proto]` and `application/grpc-web-text[+proto]`
+//! requests into regular gRPC requests for the backend, and rewrites the backend's response so
+//! its trailers are sent as a final length-prefixed frame with the `0x80` flag set. The `-text`
+//! variants are additionally base64 encoded in both directions.
+
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use ::http::HeaderValue;
+use ::http::header::{CONTENT_LENGTH, CONTENT_TYPE, TE};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use bytes::{BufMut, Bytes, BytesMut};
+use http_body::Frame;
+use pin_project_lite::pin_project;
+
+use crate::http::{HeaderMap, Request, Response};
+use crate::*;
+
+#[cfg(test)]
+#[path = "grpcweb_tests.rs"]
+mod grpcweb_tests;
+
+const GRPC: &str = "application/grpc";
+const GRPC_WEB: &str = "application/grpc-web";
+const GRPC_WEB_TEXT: &str = "application/grpc-web-text";
+/// Flag byte marking a gRPC-Web frame that carries trailers rather than a message.
+const TRAILER_FLAG: u8 = 0x80;
+
+#[apply(schema!)]
+#[derive(Default)]
+pub struct GrpcWeb {}
+
+/// How the client encoded a gRPC-Web request, and therefore how its response must be encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+	/// `application/grpc-web`: gRPC framing, with trailers in the body.
+	Binary,
+	/// `application/grpc-web-text`: like `Binary`, but base64 encoded.
+	Text,
+}
+
+impl GrpcWeb {
+	/// Rewrites a gRPC-Web request into a gRPC request. Returns the encoding the response must be
+	/// translated back to, or `None` if the request is not gRPC-Web and was left untouched.
+	pub fn translate_request(&self, req: &mut Request) -> Option<Encoding> {
+		let (encoding, rest) = parse_content_type(req.headers())?;
+		let content_type =
+			HeaderValue::try_from(format!("{GRPC}{rest}")).expect("derived from a valid header value");
+		let headers = req.headers_mut();
+		headers.insert(CONTENT_TYPE, content_type);
+		headers.insert(TE, HeaderValue::from_static("trailers"));
+		if encoding == Encoding::Text {
+			headers.remove(CONTENT_LENGTH);
+			let body = std::mem::replace(req.body_mut(), crate::http::Body::empty());
+			*req.body_mut() = crate::http::Body::new(Base64DecodeBody {
+				inner: body,
+				pending: BytesMut::new(),
+			});
+		}
+		Some(encoding)
+	}
+}
+
+impl crate::store::HasExpressions for GrpcWeb {}
+
+/// Rewrites a gRPC response for a client that sent a gRPC-Web request: trailers are moved into the
+/// body, which is base64 encoded for `-text` clients. Responses that are not gRPC, such as errors
+/// generated before the backend was called, are left untouched.
+pub fn translate_response(resp: &mut Response, encoding: Encoding) {
+	if !crate::http::is_grpc_content_type(resp.headers()) {
+		return;
+	}
+	let Some(rest) = resp
+		.headers()
+		.get(CONTENT_TYPE)
+		.and_then(|v| v.to_str().ok())
+		.and_then(|ct| strip_prefix_ignore_case(ct, GRPC))
+	else {
+		return;
+	};
+	let prefix = match encoding {
+		Encoding::Binary => GRPC_WEB,
+		Encoding::Text => GRPC_WEB_TEXT,
+	};
+	let content_type =
+		HeaderValue::try_from(format!("{prefix}{rest}")).expect("derived from a valid header value");
+	resp.headers_mut().insert(CONTENT_TYPE, content_type);
+	resp.headers_mut().remove(CONTENT_LENGTH);
+	let body = std::mem::replace(resp.body_mut(), crate::http::Body::empty());
+	*resp.body_mut() = crate::http::Body::new(ResponseBody {
+		inner: body,
+		encoding,
+	});
+}
+
+/// Splits a gRPC-Web content type into its encoding and whatever follows the media type, such as
+/// `+proto` or parameters.
+fn parse_content_type(headers: &HeaderMap) -> Option<(Encoding, &str)> {
+	let ct = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+	let (encoding, rest) = if let Some(rest) = strip_prefix_ignore_case(ct, GRPC_WEB_TEXT) {
+		(Encoding::Text, rest)
+	} else {
+		(Encoding::Binary, strip_prefix_ignore_case(ct, GRPC_WEB)?)
+	};
+	(rest.is_empty() || rest.starts_with(['+', ';'])).then_some((encoding, rest))
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+	s.get(..prefix.len())
+		.filter(|p| p.eq_ignore_ascii_case(prefix))
+		.map(|_| &s[prefix.len()..])
+}
+
+/// Encodes trailers as a gRPC-Web trailer frame: the flag byte, a big-endian length, then the
+/// trailers as HTTP/1 style `name:value` lines.
+fn encode_trailers(trailers: &HeaderMap) -> Bytes {
+	let mut block = BytesMut::new();
+	for (name, value) in trailers {
+		block.extend_from_slice(name.as_str().as_bytes());
+		block.put_u8(b':');
+		block.extend_from_slice(value.as_bytes());
+		block.extend_from_slice(b"\r\n");
+	}
+	let mut frame = BytesMut::with_capacity(5 + block.len());
+	frame.put_u8(TRAILER_FLAG);
+	frame.put_u32(block.len() as u32);
+	frame.extend_from_slice(&block);
+	frame.freeze()
+}
+
+/// Decodes base64 made of one or more separately padded segments, as `grpc-web-text` clients may
+/// encode each message on its own. `input` must be a whole number of 4 byte groups.
+fn decode_base64(mut input: &[u8]) -> Result<Bytes, crate::http::Error> {
+	let mut out = Vec::with_capacity(input.len() / 4 * 3);
+	while !input.is_empty() {
+		let end = input
+			.chunks_exact(4)
+			.position(|group| group[3] == b'=')
+			.map(|i| (i + 1) * 4)
+			.unwrap_or(input.len());
+		STANDARD
+			.decode_vec(&input[..end], &mut out)
+			.map_err(crate::http::Error::new)?;
+		input = &input[end..];
+	}
+	Ok(out.into())
+}
+
+pin_project! {
+	// Decodes a `grpc-web-text` request body. Base64 groups may be split across body frames.
+	struct Base64DecodeBody {
+		#[pin]
+		inner: crate::http::Body,
+		pending: BytesMut,
+	}
+}
+
+impl http_body::Body for Base64DecodeBody {
+	type Data = Bytes;
+	type Error = crate::http::Error;
+
+	fn poll_frame(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		let mut this = self.project();
+		loop {
+			match ready!(this.inner.as_mut().poll_frame(cx)) {
+				Some(Ok(frame)) => {
+					let data = match frame.into_data() {
+						Ok(data) => data,
+						Err(frame) => return Poll::Ready(Some(Ok(frame))),
+					};
+					this.pending.extend_from_slice(&data);
+					let aligned = this.pending.len() - this.pending.len() % 4;
+					if aligned == 0 {
+						continue;
+					}
+					let group = this.pending.split_to(aligned);
+					return Poll::Ready(Some(decode_base64(&group).map(Frame::data)));
+				},
+				Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+				None if this.pending.is_empty() => return Poll::Ready(None),
+				None => {
+					this.pending.clear();
+					return Poll::Ready(Some(Err(crate::http::Error::new(
+						"grpc-web-text body ended with incomplete base64",
+					))));
+				},
+			}
+		}
+	}
+}
+
+pin_project! {
+	// Encodes a gRPC response body for a gRPC-Web client.
+	struct ResponseBody {
+		#[pin]
+		inner: crate::http::Body,
+		encoding: Encoding,
+	}
+}
+
+impl http_body::Body for ResponseBody {
+	type Data = Bytes;
+	type Error = crate::http::Error;
+
+	fn poll_frame(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		let this = self.project();
+		let frame = match ready!(this.inner.poll_frame(cx)) {
+			Some(Ok(frame)) => frame,
+			other => return Poll::Ready(other),
+		};
+		let data = match frame.into_data() {
+			Ok(data) => data,
+			Err(frame) => match frame.into_trailers() {
+				Ok(trailers) => encode_trailers(&trailers),
+				Err(frame) => return Poll::Ready(Some(Ok(frame))),
+			},
+		};
+		let data = match this.encoding {
+			Encoding::Binary => data,
+			Encoding::Text => Bytes::from(STANDARD.encode(&data)),
+		};
+		Poll::Ready(Some(Ok(Frame::data(data))))
+	}
+
+	fn is_end_stream(&self) -> bool {
+		self.inner.is_end_stream()
+	}
+}
@@ -0,0 +1,144 @@
+use ::http::StatusCode;
+use http_body_util::StreamBody;
+
+use super::*;
+use crate::http::tests_common::{assert_header, read_body_for_test, request};
+
+/// A length-prefixed gRPC message frame.
+fn message(payload: &[u8]) -> Vec<u8> {
+	let mut frame = vec![0];
+	frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+	frame.extend_from_slice(payload);
+	frame
+}
+
+fn body(frames: Vec<Frame<Bytes>>) -> crate::http::Body {
+	crate::http::Body::new(StreamBody::new(futures_util::stream::iter(
+		frames.into_iter().map(Ok::<_, crate::http::Error>),
+	)))
+}
+
+fn grpc_web_request(content_type: &str, chunks: &[&[u8]]) -> Request {
+	let mut req = request(
+		"http://example.com/svc.Greeter/SayHello",
+		::http::Method::POST,
+		&[("content-type", content_type), ("content-length", "100")],
+	);
+	*req.body_mut() = body(
+		chunks
+			.iter()
+			.map(|c| Frame::data(Bytes::copy_from_slice(c)))
+			.collect(),
+	);
+	req
+}
+
+fn grpc_response(payload: &[u8]) -> Response {
+	let mut trailers = HeaderMap::new();
+	trailers.insert("grpc-status", HeaderValue::from_static("0"));
+	trailers.insert("grpc-message", HeaderValue::from_static("ok"));
+	::http::Response::builder()
+		.status(StatusCode::OK)
+		.header(CONTENT_TYPE, "application/grpc+proto")
+		.body(body(vec![
+			Frame::data(Bytes::from(message(payload))),
+			Frame::trailers(trailers),
+		]))
+		.unwrap()
+}
+
+fn trailer_frame() -> Vec<u8> {
+	let block = b"grpc-status:0\r\ngrpc-message:ok\r\n";
+	let mut frame = vec![TRAILER_FLAG];
+	frame.extend_from_slice(&(block.len() as u32).to_be_bytes());
+	frame.extend_from_slice(block);
+	frame
+}
+
+#[tokio::test]
+async fn unary_grpc_web_round_trip() {
+	let mut req = grpc_web_request("application/grpc-web+proto", &[&message(b"hello")]);
+	let encoding = GrpcWeb::default().translate_request(&mut req);
+	assert_eq!(encoding, Some(Encoding::Binary));
+	assert_eq!(
+		req.headers().get(CONTENT_TYPE).unwrap(),
+		"application/grpc+proto"
+	);
+	assert_eq!(req.headers().get(TE).unwrap(), "trailers");
+	assert_eq!(req.headers().get(CONTENT_LENGTH).unwrap(), "100");
+	assert_eq!(
+		read_body_for_test(req.into_body()).await.as_ref(),
+		message(b"hello")
+	);
+
+	let mut resp = grpc_response(b"world");
+	translate_response(&mut resp, Encoding::Binary);
+	assert_header(&resp, CONTENT_TYPE, "application/grpc-web+proto");
+	let mut want = message(b"world");
+	want.extend_from_slice(&trailer_frame());
+	assert_eq!(read_body_for_test(resp).await.as_ref(), want);
+}
+
+#[tokio::test]
+async fn unary_grpc_web_text_round_trip() {
+	// Two separately padded segments, split across frames mid-group.
+	let encoded = format!(
+		"{}{}",
+		STANDARD.encode(message(b"hi")),
+		STANDARD.encode(message(b"there"))
+	);
+	let (a, b) = encoded.as_bytes().split_at(5);
+	let mut req = grpc_web_request("application/grpc-web-text", &[a, b]);
+	let encoding = GrpcWeb::default().translate_request(&mut req);
+	assert_eq!(encoding, Some(Encoding::Text));
+	assert_eq!(req.headers().get(CONTENT_TYPE).unwrap(), "application/grpc");
+	assert!(req.headers().get(CONTENT_LENGTH).is_none());
+	let mut want = message(b"hi");
+	want.extend_from_slice(&message(b"there"));
+	assert_eq!(read_body_for_test(req.into_body()).await.as_ref(), want);
+
+	let mut resp = grpc_response(b"world");
+	translate_response(&mut resp, Encoding::Text);
+	assert_header(&resp, CONTENT_TYPE, "application/grpc-web-text+proto");
+	let want = format!(
+		"{}{}",
+		STANDARD.encode(message(b"world")),
+		STANDARD.encode(trailer_frame())
+	);
+	assert_eq!(read_body_for_test(resp).await.as_ref(), want.as_bytes());
+}
+
+#[tokio::test]
+async fn truncated_text_body_is_rejected() {
+	let mut req = grpc_web_request("application/grpc-web-text", &[b"AAAAAA"]);
+	GrpcWeb::default().translate_request(&mut req);
+	assert!(
+		crate::http::read_body_with_limit(req.into_body(), 100)
+			.await
+			.is_err()
+	);
+}
+
+#[tokio::test]
+async fn other_content_types_are_untouched() {
+	for content_type in [
+		"application/grpc",
+		"application/json",
+		"application/grpc-webfoo",
+	] {
+		let mut req = grpc_web_request(content_type, &[b"{}"]);
+		assert_eq!(GrpcWeb::default().translate_request(&mut req), None);
+		assert_eq!(req.headers().get(CONTENT_TYPE).unwrap(), content_type);
+		assert!(req.headers().get(TE).is_none());
+	}
+
+	// A gateway-generated error is not gRPC, so it is passed through as is.
+	let mut resp = ::http::Response::builder()
+		.status(StatusCode::UNAUTHORIZED)
+		.header(CONTENT_TYPE, "text/plain")
+		.body(crate::http::Body::from("denied"))
+		.unwrap();
+	translate_response(&mut resp, Encoding::Text);
+	assert_header(&resp, CONTENT_TYPE, "text/plain");
+	assert_eq!(read_body_for_test(resp).await.as_ref(), b"denied");
+}
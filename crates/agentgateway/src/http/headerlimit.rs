@@ -0,0 +1,73 @@
+use ::http::StatusCode;
+
+use crate::http::{PolicyResponse, Request};
+use crate::*;
+
+#[cfg(test)]
+#[path = "headerlimit_tests.rs"]
+mod headerlimit_tests;
+
+#[apply(schema!)]
+#[derive(Default)]
+pub struct HeaderLimit {
+	/// Maximum number of request headers. Requests with more headers are rejected with a 431.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_count: Option<usize>,
+	/// Maximum cumulative size of request header names and values, in bytes. Requests exceeding
+	/// this are rejected with a 431.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_bytes: Option<usize>,
+}
+
+impl HeaderLimit {
+	pub fn apply(&self, req: &Request) -> PolicyResponse {
+		let headers = req.headers();
+		if let Some(max_count) = self.max_count
+			&& headers.len() > max_count
+		{
+			debug!(
+				count = headers.len(),
+				max_count, "request exceeds header count limit"
+			);
+			return Self::too_large();
+		}
+		if let Some(max_bytes) = self.max_bytes {
+			let bytes = header_bytes(headers);
+			if bytes > max_bytes {
+				debug!(bytes, max_bytes, "request exceeds header size limit");
+				return Self::too_large();
+			}
+		}
+		Default::default()
+	}
+
+	fn too_large() -> PolicyResponse {
+		let response = ::http::Response::builder()
+			.status(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE)
+			.body(crate::http::Body::empty())
+			.expect("static response builds");
+		PolicyResponse {
+			direct_response: Some(response),
+			response_headers: None,
+		}
+	}
+}
+
+/// Cumulative size of the header names and values, in bytes.
+pub fn header_bytes(headers: &::http::HeaderMap) -> usize {
+	headers
+		.iter()
+		.map(|(k, v)| k.as_str().len() + v.len())
+		.sum()
+}
+
+impl crate::store::RequestPolicyTrait for HeaderLimit {
+	async fn apply(
+		&self,
+		_client: &crate::proxy::httpproxy::PolicyClient,
+		_log: &mut crate::telemetry::log::RequestLog,
+		req: &mut Request,
+	) -> Result<PolicyResponse, crate::proxy::ProxyResponse> {
+		Ok(self.apply(req))
+	}
+}
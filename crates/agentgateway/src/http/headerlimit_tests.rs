@@ -0,0 +1,63 @@
+use super::*;
+use crate::http::tests_common::request;
+
+fn status(resp: &PolicyResponse) -> Option<u16> {
+	resp.direct_response.as_ref().map(|r| r.status().as_u16())
+}
+
+#[test]
+fn header_count_limit() {
+	let limit = HeaderLimit {
+		max_count: Some(2),
+		max_bytes: None,
+	};
+	let under = request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("a", "1"), ("b", "2")],
+	);
+	assert_eq!(status(&limit.apply(&under)), None);
+
+	let over = request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("a", "1"), ("b", "2"), ("c", "3")],
+	);
+	assert_eq!(status(&limit.apply(&over)), Some(431));
+}
+
+#[test]
+fn header_bytes_limit() {
+	let limit = HeaderLimit {
+		max_count: None,
+		// "x-foo" + "12345" = 10 bytes
+		max_bytes: Some(10),
+	};
+	let under = request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-foo", "12345")],
+	);
+	assert_eq!(status(&limit.apply(&under)), None);
+
+	let over = request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-foo", "123456")],
+	);
+	assert_eq!(status(&limit.apply(&over)), Some(431));
+}
+
+#[test]
+fn repeated_headers_count_individually() {
+	let limit = HeaderLimit {
+		max_count: Some(1),
+		max_bytes: None,
+	};
+	let req = request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-foo", "1"), ("x-foo", "2")],
+	);
+	assert_eq!(status(&limit.apply(&req)), Some(431));
+}
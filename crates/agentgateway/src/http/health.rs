@@ -3,12 +3,13 @@
 //! When a response is considered unhealthy (by CEL or default 5xx), the backend can be
 //! evicted for a configurable duration. If no health policy is configured, no eviction
 //! is applied. Optional health/failure thresholds and recovery health support multi-request
-//! and recovery behavior.
+//! and recovery behavior, and an optional cap limits how much of a backend can be evicted at once.
 
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::cel::{ContextBuilder, Expression};
+use crate::http::healthcheck::ActiveHealthCheck;
 use crate::{serde_dur_option, *};
 
 /// Eviction sub-policy: how long to remove a backend from the active set after an unhealthy response.
@@ -40,6 +41,12 @@ pub struct Eviction {
 	/// When neither is set, a single unhealthy response triggers eviction.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub health_threshold: Option<f64>,
+
+	/// Maximum percentage (0–100) of a backend's endpoints that may be evicted at the same time.
+	/// Once reached, further unhealthy responses lower health but do not evict.
+	/// When absent, there is no cap; if every endpoint is evicted, evicted endpoints are used.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_eviction_percent: Option<f64>,
 }
 
 /// Health policy: determines when a backend is unhealthy and how to evict it.
@@ -58,6 +65,11 @@ pub struct Policy {
 	/// Eviction settings. When absent, falls back to defaults.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub eviction: Option<Eviction>,
+
+	/// Active health checking. When set, each endpoint of a service backend is probed
+	/// periodically, and endpoints failing their probes are excluded from load balancing.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub active: Option<ActiveHealthCheck>,
 }
 
 const DEFAULT_EVICTION_SECS: u64 = 3;
@@ -130,6 +142,16 @@ impl Policy {
 		};
 		(health, eviction_duration, ev.and_then(|e| e.restore_health))
 	}
+
+	/// Whether another endpoint may be evicted, given the fraction (0.0–1.0) of the backend's
+	/// endpoints that are already evicted.
+	pub(crate) fn eviction_allowed(&self, evicted_fraction: f64) -> bool {
+		self
+			.eviction
+			.as_ref()
+			.and_then(|e| e.max_eviction_percent)
+			.is_none_or(|max| evicted_fraction * 100.0 < max)
+	}
 }
 
 /// Local/config eviction sub-policy with duration as string; mirrors `Eviction`.
@@ -156,6 +178,10 @@ pub struct LocalEviction {
 	/// Health score threshold below which an unhealthy response can evict the backend.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub health_threshold: Option<f64>,
+
+	/// Maximum percentage (0–100) of the backend's endpoints that may be evicted at once.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_eviction_percent: Option<f64>,
 }
 
 /// Local/config health policy with CEL as string; converted to Policy by compiling the expression.
@@ -170,6 +196,10 @@ pub struct LocalHealthPolicy {
 	/// Settings for temporarily removing unhealthy backends.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub eviction: Option<LocalEviction>,
+	/// Periodically probe each endpoint and exclude endpoints failing their probes from load balancing.
+	/// Applies to service backends; checks start once the backend receives its first request.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub active: Option<ActiveHealthCheck>,
 }
 
 impl TryFrom<LocalHealthPolicy> for Policy {
@@ -189,11 +219,19 @@ impl TryFrom<LocalHealthPolicy> for Policy {
 				};
 				validate_score("healthThreshold", e.health_threshold)?;
 				validate_score("restoreHealth", e.restore_health)?;
+				if let Some(v) = e.max_eviction_percent
+					&& !(0.0..=100.0).contains(&v)
+				{
+					return Err(crate::cel::Error::Variable(
+						"health.eviction.maxEvictionPercent must be between 0 and 100".to_string(),
+					));
+				}
 				Some(Eviction {
 					duration: e.duration,
 					restore_health: e.restore_health,
 					consecutive_failures: e.consecutive_failures,
 					health_threshold: e.health_threshold,
+					max_eviction_percent: e.max_eviction_percent,
 				})
 			},
 			None => None,
@@ -206,6 +244,7 @@ impl TryFrom<LocalHealthPolicy> for Policy {
 		Ok(Policy {
 			unhealthy_expression,
 			eviction,
+			active: local.active,
 		})
 	}
 }
@@ -385,6 +424,43 @@ mod tests {
 		assert!(eviction.is_none());
 	}
 
+	// --- max_eviction_percent ---
+
+	#[test]
+	fn eviction_allowed_without_cap() {
+		assert!(Policy::default().eviction_allowed(1.0));
+		assert!(policy_with_eviction_duration(10).eviction_allowed(0.9));
+	}
+
+	#[test]
+	fn eviction_allowed_below_cap_only() {
+		let policy = Policy {
+			eviction: Some(Eviction {
+				max_eviction_percent: Some(50.0),
+				..Default::default()
+			}),
+			..Default::default()
+		};
+		assert!(policy.eviction_allowed(0.0));
+		assert!(policy.eviction_allowed(0.25));
+		assert!(!policy.eviction_allowed(0.5));
+		assert!(!policy.eviction_allowed(0.75));
+	}
+
+	#[test]
+	fn local_max_eviction_percent_out_of_range_rejected() {
+		let local = |pct: f64| LocalHealthPolicy {
+			eviction: Some(LocalEviction {
+				max_eviction_percent: Some(pct),
+				..Default::default()
+			}),
+			..Default::default()
+		};
+		assert!(Policy::try_from(local(150.0)).is_err());
+		let policy = Policy::try_from(local(30.0)).unwrap();
+		assert_eq!(policy.eviction.unwrap().max_eviction_percent, Some(30.0));
+	}
+
 	// --- restore_health passthrough ---
 
 	#[test]
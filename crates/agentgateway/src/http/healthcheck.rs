@@ -0,0 +1,189 @@
+//! Active health checking.
+//!
+//! Each endpoint of a backend is periodically probed with an HTTP GET. Endpoints that fail
+//! `unhealthy_threshold` consecutive probes are moved out of the active set, so load balancing
+//! only falls back to them when nothing else is available. They are restored once they pass
+//! `healthy_threshold` consecutive probes.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::client::{Call, Client, Transport};
+use crate::types::agent::Target;
+use crate::types::loadbalancer::EndpointSet;
+use crate::*;
+
+#[cfg(test)]
+#[path = "healthcheck_tests.rs"]
+mod healthcheck_tests;
+
+#[apply(schema!)]
+pub struct ActiveHealthCheck {
+	/// Path requested on each endpoint.
+	#[serde(default = "defaults::path")]
+	pub path: String,
+	/// Time between probes.
+	#[serde(default = "defaults::interval", with = "serde_dur")]
+	#[cfg_attr(feature = "schema", schemars(with = "String"))]
+	pub interval: Duration,
+	/// Time to wait for a probe response before counting the probe as failed.
+	#[serde(default = "defaults::timeout", with = "serde_dur")]
+	#[cfg_attr(feature = "schema", schemars(with = "String"))]
+	pub timeout: Duration,
+	/// Consecutive passing probes required to mark an unhealthy endpoint healthy again.
+	#[serde(default = "defaults::healthy_threshold")]
+	pub healthy_threshold: u32,
+	/// Consecutive failing probes required to mark an endpoint unhealthy.
+	#[serde(default = "defaults::unhealthy_threshold")]
+	pub unhealthy_threshold: u32,
+	/// Response status codes considered healthy. When empty, any 2xx status is healthy.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub expected_statuses: Vec<u16>,
+}
+
+impl Default for ActiveHealthCheck {
+	fn default() -> Self {
+		Self {
+			path: defaults::path(),
+			interval: defaults::interval(),
+			timeout: defaults::timeout(),
+			healthy_threshold: defaults::healthy_threshold(),
+			unhealthy_threshold: defaults::unhealthy_threshold(),
+			expected_statuses: Vec::new(),
+		}
+	}
+}
+
+mod defaults {
+	use std::time::Duration;
+
+	pub fn path() -> String {
+		"/".to_string()
+	}
+	pub fn interval() -> Duration {
+		Duration::from_secs(10)
+	}
+	pub fn timeout() -> Duration {
+		Duration::from_secs(1)
+	}
+	pub fn healthy_threshold() -> u32 {
+		2
+	}
+	pub fn unhealthy_threshold() -> u32 {
+		3
+	}
+}
+
+impl ActiveHealthCheck {
+	fn status_healthy(&self, status: ::http::StatusCode) -> bool {
+		if self.expected_statuses.is_empty() {
+			status.is_success()
+		} else {
+			self.expected_statuses.contains(&status.as_u16())
+		}
+	}
+
+	async fn probe(&self, client: &Client, target: Target, transport: Transport) -> bool {
+		let uri = format!("http://{}{}", target.hostport(), self.path);
+		let req = match ::http::Request::get(uri).body(crate::http::Body::empty()) {
+			Ok(req) => req,
+			Err(e) => {
+				debug!("invalid health check request for {target}: {e}");
+				return false;
+			},
+		};
+		let call = client.call(Call {
+			req,
+			target: target.clone(),
+			transport,
+		});
+		match tokio::time::timeout(self.timeout, call).await {
+			Ok(Ok(resp)) => {
+				let healthy = self.status_healthy(resp.status());
+				trace!(%target, status=%resp.status(), healthy, "health check");
+				healthy
+			},
+			Ok(Err(e)) => {
+				trace!(%target, "health check failed: {e}");
+				false
+			},
+			Err(_) => {
+				trace!(%target, "health check timed out");
+				false
+			},
+		}
+	}
+}
+
+/// Tracks consecutive probe results for one endpoint.
+#[derive(Debug, Default)]
+struct ProbeState {
+	unhealthy: bool,
+	/// Consecutive probes disagreeing with the current state.
+	streak: u32,
+}
+
+impl ProbeState {
+	/// Records a probe result, returning the new health once a threshold is crossed.
+	fn record(&mut self, cfg: &ActiveHealthCheck, healthy: bool) -> Option<bool> {
+		if healthy != self.unhealthy {
+			self.streak = 0;
+			return None;
+		}
+		self.streak += 1;
+		let threshold = if healthy {
+			cfg.healthy_threshold
+		} else {
+			cfg.unhealthy_threshold
+		};
+		if self.streak < threshold.max(1) {
+			return None;
+		}
+		self.unhealthy = !healthy;
+		self.streak = 0;
+		Some(healthy)
+	}
+}
+
+/// Probes every endpoint in `endpoints` once per interval, forever, and reports endpoints
+/// crossing a threshold to the set. `target` maps an endpoint to the address to probe;
+/// endpoints it returns `None` for are not checked.
+pub async fn run<T, F>(
+	cfg: ActiveHealthCheck,
+	endpoints: EndpointSet<T>,
+	client: Client,
+	transport: Transport,
+	target: F,
+) where
+	T: Clone + Sync + Send + 'static,
+	F: Fn(&T) -> Option<Target>,
+{
+	let mut states: HashMap<Strng, ProbeState> = HashMap::new();
+	let mut interval = tokio::time::interval(cfg.interval);
+	interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+	loop {
+		interval.tick().await;
+		let probes = endpoints
+			.all_endpoints()
+			.into_iter()
+			.filter_map(|(key, ep)| {
+				let target = target(ep.as_ref())?;
+				let (cfg, client, transport) = (&cfg, &client, transport.clone());
+				Some(async move { (key, cfg.probe(client, target, transport).await) })
+			})
+			.collect::<Vec<_>>();
+		let results = futures::future::join_all(probes).await;
+
+		// Drop state for endpoints that are no longer present.
+		let mut next = HashMap::with_capacity(results.len());
+		for (key, healthy) in results {
+			let mut state = states.remove(&key).unwrap_or_default();
+			if let Some(healthy) = state.record(&cfg, healthy) {
+				debug!(endpoint=%key, healthy, "health check status changed");
+				endpoints.set_health_check_status(key.clone(), healthy);
+			}
+			next.insert(key, state);
+		}
+		states = next;
+	}
+}
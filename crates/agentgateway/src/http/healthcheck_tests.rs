@@ -0,0 +1,167 @@
+use std::net::SocketAddr;
+
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::*;
+
+fn test_client() -> Client {
+	Client::new(
+		&crate::client::Config {
+			resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
+			resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+			negative_ttl: crate::client::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
+		},
+		None,
+		Default::default(),
+		None,
+	)
+}
+
+fn check() -> ActiveHealthCheck {
+	ActiveHealthCheck {
+		path: "/healthz".to_string(),
+		interval: Duration::from_millis(20),
+		timeout: Duration::from_millis(500),
+		healthy_threshold: 2,
+		unhealthy_threshold: 2,
+		expected_statuses: Vec::new(),
+	}
+}
+
+async fn respond_with(server: &MockServer, status: u16) {
+	server.reset().await;
+	Mock::given(method("GET"))
+		.and(path("/healthz"))
+		.respond_with(ResponseTemplate::new(status))
+		.mount(server)
+		.await;
+}
+
+fn active(eps: &EndpointSet<SocketAddr>) -> Vec<SocketAddr> {
+	let mut out = eps.iter().iter().map(|(ep, _)| **ep).collect::<Vec<_>>();
+	out.sort();
+	out
+}
+
+async fn wait_for_active(eps: &EndpointSet<SocketAddr>, want: &[SocketAddr]) {
+	let mut want = want.to_vec();
+	want.sort();
+	for _ in 0..200 {
+		if active(eps) == want {
+			return;
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	panic!("expected active endpoints {want:?}, got {:?}", active(eps));
+}
+
+#[test]
+fn probe_state_thresholds() {
+	let cfg = ActiveHealthCheck {
+		healthy_threshold: 2,
+		unhealthy_threshold: 3,
+		..Default::default()
+	};
+	let mut state = ProbeState::default();
+	assert_eq!(state.record(&cfg, true), None);
+	assert_eq!(state.record(&cfg, false), None);
+	assert_eq!(state.record(&cfg, false), None);
+	// A pass resets the failure streak.
+	assert_eq!(state.record(&cfg, true), None);
+	assert_eq!(state.record(&cfg, false), None);
+	assert_eq!(state.record(&cfg, false), None);
+	assert_eq!(state.record(&cfg, false), Some(false));
+	assert_eq!(state.record(&cfg, false), None);
+	assert_eq!(state.record(&cfg, true), None);
+	assert_eq!(state.record(&cfg, true), Some(true));
+}
+
+#[test]
+fn expected_statuses() {
+	let default = ActiveHealthCheck::default();
+	assert!(default.status_healthy(::http::StatusCode::OK));
+	assert!(default.status_healthy(::http::StatusCode::NO_CONTENT));
+	assert!(!default.status_healthy(::http::StatusCode::SERVICE_UNAVAILABLE));
+
+	let explicit = ActiveHealthCheck {
+		expected_statuses: vec![200, 429],
+		..Default::default()
+	};
+	assert!(explicit.status_healthy(::http::StatusCode::TOO_MANY_REQUESTS));
+	assert!(!explicit.status_healthy(::http::StatusCode::NO_CONTENT));
+}
+
+#[tokio::test]
+async fn unhealthy_endpoint_is_excluded_from_selection() {
+	let stable = MockServer::start().await;
+	let flaky = MockServer::start().await;
+	respond_with(&stable, 200).await;
+	respond_with(&flaky, 200).await;
+	let (stable_addr, flaky_addr) = (*stable.address(), *flaky.address());
+
+	let eps = EndpointSet::new(vec![vec![
+		(strng::literal!("stable"), stable_addr),
+		(strng::literal!("flaky"), flaky_addr),
+	]]);
+	eps.start_health_checks(|eps| {
+		tokio::spawn(run(
+			check(),
+			eps,
+			test_client(),
+			Transport::default(),
+			|addr: &SocketAddr| Some(Target::Address(*addr)),
+		))
+	});
+	wait_for_active(&eps, &[stable_addr, flaky_addr]).await;
+
+	respond_with(&flaky, 503).await;
+	wait_for_active(&eps, &[stable_addr]).await;
+	for i in 0..100 {
+		let (ep, _) = eps.select_by_hash(&i).unwrap();
+		assert_eq!(*ep, stable_addr);
+	}
+
+	respond_with(&flaky, 200).await;
+	wait_for_active(&eps, &[stable_addr, flaky_addr]).await;
+}
+
+#[tokio::test]
+async fn health_checker_stops_when_endpoint_set_is_dropped() {
+	let server = MockServer::start().await;
+	respond_with(&server, 200).await;
+	let addr = *server.address();
+
+	let eps = EndpointSet::new(vec![vec![(strng::literal!("ep"), addr)]]);
+	let mut checker = None;
+	eps.start_health_checks(|eps| {
+		let task = tokio::spawn(run(
+			check(),
+			eps,
+			test_client(),
+			Transport::default(),
+			|addr: &SocketAddr| Some(Target::Address(*addr)),
+		));
+		checker = Some(task.abort_handle());
+		task
+	});
+	let checker = checker.expect("health checker started");
+	// Starting again is a no-op while the checker is running.
+	eps.start_health_checks(|_| panic!("health checker started twice"));
+
+	let clone = eps.clone();
+	drop(eps);
+	tokio::time::sleep(Duration::from_millis(50)).await;
+	assert!(!checker.is_finished(), "a clone of the set is still alive");
+
+	drop(clone);
+	for _ in 0..100 {
+		if checker.is_finished() {
+			return;
+		}
+		tokio::task::yield_now().await;
+	}
+	panic!("health checker kept running after the endpoint set was dropped");
+}
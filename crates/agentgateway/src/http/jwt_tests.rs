@@ -646,6 +646,7 @@ fn make_min_req_log() -> crate::telemetry::log::RequestLog {
 
 	let log_cfg = log::Config {
 		filter: None,
+		sample: None,
 		fields: LoggingFields::default(),
 		database_fields: LoggingFields::default(),
 		level: "info".to_string(),
@@ -1,9 +1,19 @@
+use quick_cache::sync::Cache;
 use serde::de::Error;
 
+use crate::cel::{Executor, Expression};
 use crate::llm::LLMRequest;
 use crate::proxy::ProxyError;
 use crate::*;
 
+#[cfg(test)]
+#[path = "localratelimit_tests.rs"]
+mod localratelimit_tests;
+
+/// Maximum number of per-key buckets kept for a keyed limit. The least recently used buckets are
+/// evicted beyond this, which resets their limit.
+const MAX_KEYED_BUCKETS: usize = 10_000;
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[cfg_attr(feature = "schema", schemars(with = "RateLimitSpec"))]
@@ -11,6 +21,9 @@ use crate::*;
 pub struct RateLimit {
 	#[serde(skip_serializing)]
 	ratelimit: Arc<ratelimit::Ratelimiter>,
+	/// Buckets for each evaluated `key`, when one is configured.
+	#[serde(skip_serializing)]
+	keyed: Option<Arc<Cache<String, Arc<ratelimit::Ratelimiter>>>>,
 	#[serde(flatten)]
 	pub spec: RateLimitSpec,
 }
@@ -41,6 +54,12 @@ pub struct RateLimitSpec {
 	#[serde(default)]
 	#[serde(rename = "type")]
 	pub limit_type: RateLimitType,
+	/// CEL expression selecting the bucket a request counts against, such as `jwt.sub` or
+	/// `source.address`. Each distinct value gets its own bucket. Requests where the expression
+	/// fails to evaluate share a single bucket. When unset, all requests share one bucket.
+	/// Only supported for `requests` limits.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub key: Option<Arc<Expression>>,
 }
 
 #[apply(schema!)]
@@ -55,28 +74,42 @@ pub enum RateLimitType {
 	Tokens,
 }
 
+impl RateLimitSpec {
+	fn bucket(&self) -> Result<ratelimit::Ratelimiter, ratelimit::Error> {
+		ratelimit::Ratelimiter::builder(self.tokens_per_fill, self.fill_interval)
+			.initial_available(self.max_tokens)
+			.max_tokens(self.max_tokens)
+			.build()
+	}
+}
+
 impl TryFrom<RateLimitSpec> for RateLimit {
-	type Error = ratelimit::Error;
+	type Error = anyhow::Error;
 	fn try_from(value: RateLimitSpec) -> Result<Self, Self::Error> {
-		let rl = ratelimit::Ratelimiter::builder(value.tokens_per_fill, value.fill_interval)
-			.initial_available(value.max_tokens)
-			.max_tokens(value.max_tokens)
-			.build()?;
+		if value.key.is_some() && value.limit_type != RateLimitType::Requests {
+			anyhow::bail!("key is only supported for requests rate limits");
+		}
+		let rl = value.bucket()?;
 		Ok(RateLimit {
 			ratelimit: Arc::new(rl),
+			keyed: value
+				.key
+				.as_ref()
+				.map(|_| Arc::new(Cache::new(MAX_KEYED_BUCKETS))),
 			spec: value,
 		})
 	}
 }
 
 impl RateLimit {
-	pub fn check_request(&self) -> Result<(), ProxyError> {
+	pub fn check_request(&self, req: &http::Request) -> Result<(), ProxyError> {
 		if self.spec.limit_type != RateLimitType::Requests {
 			return Ok(());
 		}
+		let keyed = self.keyed_bucket(req)?;
+		let ratelimit = keyed.as_ref().unwrap_or(&self.ratelimit);
 		// TODO: return headers on success, not just failure
-		self
-			.ratelimit
+		ratelimit
 			.try_wait()
 			.map_err(|(limit, remaining, reset)| ProxyError::RateLimitExceeded {
 				limit,
@@ -85,6 +118,34 @@ impl RateLimit {
 			})
 	}
 
+	/// Returns the bucket for the request's key, or `None` if the shared bucket should be used.
+	fn keyed_bucket(
+		&self,
+		req: &http::Request,
+	) -> Result<Option<Arc<ratelimit::Ratelimiter>>, ProxyError> {
+		let (Some(expr), Some(keyed)) = (&self.spec.key, &self.keyed) else {
+			return Ok(None);
+		};
+		let key = match Executor::new_request(req)
+			.eval(expr)
+			.map_err(|e| e.to_string())
+			.and_then(|v| v.as_string().map_err(|e| e.to_string()))
+		{
+			Ok(key) => key,
+			Err(e) => {
+				trace!("local rate limit key failed to evaluate: {e}");
+				return Ok(None);
+			},
+		};
+		keyed
+			.get_or_insert_with(&key, || self.spec.bucket().map(Arc::new))
+			.map(Some)
+			.map_err(|e| {
+				warn!("failed to create local rate limit bucket: {e}");
+				ProxyError::RateLimitFailed
+			})
+	}
+
 	pub fn check_llm_request(&self, req: &LLMRequest) -> Result<(), ProxyError> {
 		if self.spec.limit_type != RateLimitType::Tokens {
 			return Ok(());
@@ -132,13 +193,17 @@ impl crate::store::RequestPolicyTrait for Vec<RateLimit> {
 		&self,
 		_client: &crate::proxy::httpproxy::PolicyClient,
 		_log: &mut crate::telemetry::log::RequestLog,
-		_req: &mut http::Request,
+		req: &mut http::Request,
 	) -> Result<http::PolicyResponse, crate::proxy::ProxyResponse> {
 		for rate_limit in self {
-			rate_limit.check_request()?;
+			rate_limit.check_request(req)?;
 		}
 		Ok(http::PolicyResponse::default())
 	}
+
+	fn expressions(&self) -> impl Iterator<Item = &Expression> {
+		self.iter().filter_map(|rl| rl.spec.key.as_deref())
+	}
 }
 
 // Forked from https://github.com/pelikan-io/rustcommon/tree/main/ratelimit to provide some additional functions
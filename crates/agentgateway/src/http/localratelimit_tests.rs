@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use super::*;
+use crate::http::tests_common::request;
+
+fn keyed_limit(key: &str, max_tokens: u64) -> RateLimit {
+	RateLimit::try_from(RateLimitSpec {
+		max_tokens,
+		tokens_per_fill: 1,
+		fill_interval: Duration::from_secs(3600),
+		limit_type: RateLimitType::Requests,
+		key: Some(Arc::new(Expression::new_strict(key).unwrap())),
+	})
+	.unwrap()
+}
+
+fn user(name: &str) -> http::Request {
+	request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-user", name)],
+	)
+}
+
+#[test]
+fn keyed_limit_has_a_bucket_per_value() {
+	let rl = keyed_limit(r#"request.headers["x-user"]"#, 2);
+
+	assert!(rl.check_request(&user("alice")).is_ok());
+	assert!(rl.check_request(&user("alice")).is_ok());
+	let err = rl.check_request(&user("alice")).unwrap_err();
+	assert!(matches!(
+		err,
+		ProxyError::RateLimitExceeded {
+			limit: 2,
+			remaining: 0,
+			..
+		}
+	));
+	assert_eq!(
+		err.into_response_with_grpc(false).status(),
+		::http::StatusCode::TOO_MANY_REQUESTS
+	);
+
+	// bob gets a separate bucket, unaffected by alice exhausting theirs.
+	assert!(rl.check_request(&user("bob")).is_ok());
+	assert!(rl.check_request(&user("bob")).is_ok());
+	assert!(rl.check_request(&user("bob")).is_err());
+	assert!(rl.check_request(&user("alice")).is_err());
+}
+
+#[test]
+fn requests_without_a_key_share_a_bucket() {
+	let rl = keyed_limit(r#"request.headers["x-user"]"#, 1);
+	let anonymous = || request("http://example.com/", ::http::Method::GET, &[]);
+
+	assert!(rl.check_request(&anonymous()).is_ok());
+	assert!(rl.check_request(&anonymous()).is_err());
+	assert!(rl.check_request(&user("alice")).is_ok());
+}
+
+#[test]
+fn unkeyed_limit_is_shared() {
+	let rl = RateLimit::try_from(RateLimitSpec {
+		max_tokens: 1,
+		tokens_per_fill: 1,
+		fill_interval: Duration::from_secs(3600),
+		limit_type: RateLimitType::Requests,
+		key: None,
+	})
+	.unwrap();
+	assert!(rl.check_request(&user("alice")).is_ok());
+	assert!(rl.check_request(&user("bob")).is_err());
+}
+
+#[test]
+fn key_requires_requests_limit() {
+	let spec = RateLimitSpec {
+		max_tokens: 1,
+		tokens_per_fill: 1,
+		fill_interval: Duration::from_secs(1),
+		limit_type: RateLimitType::Tokens,
+		key: Some(Arc::new(Expression::new_strict("jwt.sub").unwrap())),
+	};
+	assert!(RateLimit::try_from(spec).is_err());
+}
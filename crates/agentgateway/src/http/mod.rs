@@ -1,10 +1,12 @@
 pub mod filters;
 pub mod health;
+pub mod healthcheck;
 pub mod timeout;
 
 pub mod buffer;
 pub mod bufferbody;
 mod buflist;
+pub mod circuitbreaker;
 pub mod cors;
 pub mod jwt;
 pub mod localratelimit;
@@ -21,12 +23,16 @@ pub mod csrf;
 pub mod envoy_proto_common;
 pub mod ext_authz;
 pub mod ext_proc;
+pub mod grpcweb;
+pub mod headerlimit;
 pub(crate) mod oauth;
 pub mod oidc;
 pub mod outlierdetection;
 mod peekbody;
 mod recordbody;
+pub mod redisratelimit;
 pub mod remoteratelimit;
+pub mod requestid;
 pub mod sessionpersistence;
 pub mod tests_common;
 pub mod transformation_cel;
@@ -326,11 +332,9 @@ impl HeaderOrPseudoValue {
 
 	pub fn from_cel_result(k: &HeaderOrPseudo, res: Option<Value>) -> Option<HeaderOrPseudoValue> {
 		match (res?.always_materialize_owned(), k) {
-			(v, HeaderOrPseudo::Header(_)) => v
-				.as_bytes_pre_materialized()
-				.ok()
-				.and_then(|b| HeaderValue::from_bytes(b).ok())
-				.map(HeaderOrPseudoValue::Header),
+			(v, HeaderOrPseudo::Header(_)) => {
+				crate::cel::value_as_header_value(v).map(HeaderOrPseudoValue::Header)
+			},
 			(v, HeaderOrPseudo::Status) => v
 				.as_unsigned()
 				.ok()
@@ -245,6 +245,9 @@ async fn compile_local_policy(
 		&crate::client::Config {
 			resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
 			resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+			negative_ttl: crate::client::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
 		},
 		None,
 		crate::BackendConfig::default(),
@@ -0,0 +1,607 @@
+//! Distributed rate limiting backed by Redis.
+//!
+//! Each bucket is a Redis hash that a Lua script refills, checks and decrements in a single
+//! atomic step, so the limit is shared by every gateway pointed at the same Redis. Requires
+//! Redis 5 or newer, which allows scripts to read the server clock.
+
+use std::io::Write;
+use std::sync::{LazyLock, Mutex};
+
+use futures::future::BoxFuture;
+use rustls::pki_types::ServerName;
+use secrecy::{ExposeSecret, SecretString};
+use serde::de::Error;
+use sha1::{Digest, Sha1};
+use tokio::io::{
+	AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufStream,
+};
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+
+use crate::cel::{Executor, Expression};
+use crate::http::backendtls::{BackendTLS, ResolvedBackendTLS};
+use crate::http::localratelimit::RateLimitType;
+use crate::http::remoteratelimit::FailureMode;
+use crate::http::{PolicyResponse, Request};
+use crate::proxy::ProxyError;
+use crate::{serde_dur_option, *};
+
+#[cfg(test)]
+#[path = "redisratelimit_tests.rs"]
+mod redisratelimit_tests;
+
+/// Largest reply line or bulk string accepted from Redis. The script replies are a few integers,
+/// so anything near this is a misbehaving server.
+const MAX_REPLY_LEN: usize = 64 * 1024;
+/// Largest number of elements accepted in an array reply.
+const MAX_REPLY_ITEMS: usize = 1024;
+
+/// Refills the bucket for the elapsed intervals, then takes `requested` tokens if they are all
+/// available. With `force` set the tokens are always taken (or returned, if negative), clamped
+/// to the bucket size; this is used to true up token counts after a response.
+/// A `requested` of 0 only checks that at least one token is available.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local fill = tonumber(ARGV[2])
+local interval = tonumber(ARGV[3])
+local requested = tonumber(ARGV[4])
+local ttl = tonumber(ARGV[5])
+local force = ARGV[6] == '1'
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'refilled')
+local tokens = tonumber(state[1]) or capacity
+local refilled = tonumber(state[2]) or now
+local intervals = math.floor((now - refilled) / interval)
+if intervals > 0 then
+	tokens = math.min(capacity, tokens + intervals * fill)
+	refilled = refilled + intervals * interval
+end
+local allowed = 1
+if force then
+	tokens = math.max(0, math.min(capacity, tokens - requested))
+elseif requested == 0 then
+	if tokens < 1 then
+		allowed = 0
+	end
+elseif tokens >= requested then
+	tokens = tokens - requested
+else
+	allowed = 0
+end
+redis.call('HSET', KEYS[1], 'tokens', tokens, 'refilled', refilled)
+redis.call('PEXPIRE', KEYS[1], ttl)
+return {allowed, tokens, refilled + interval - now}
+"#;
+
+/// SHA1 of [`TOKEN_BUCKET_SCRIPT`], so calls can send `EVALSHA` instead of the whole script.
+static TOKEN_BUCKET_SHA: LazyLock<String> =
+	LazyLock::new(|| hex::encode(Sha1::digest(TOKEN_BUCKET_SCRIPT)));
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(with = "RedisRateLimitSpec"))]
+#[derive(serde::Serialize)]
+pub struct RedisRateLimit {
+	#[serde(skip_serializing)]
+	pool: Arc<Pool>,
+	#[serde(skip_serializing)]
+	tls: Option<BackendTLS>,
+	#[serde(flatten)]
+	pub spec: RedisRateLimitSpec,
+}
+
+impl<'de> serde::Deserialize<'de> for RedisRateLimit {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		let spec = RedisRateLimitSpec::deserialize(deserializer)?;
+		RedisRateLimit::try_from(spec).map_err(D::Error::custom)
+	}
+}
+
+#[apply(schema!)]
+pub struct RedisRateLimitSpec {
+	/// Address of the Redis server, as `host:port`.
+	pub host: String,
+	/// Username sent with `AUTH` after connecting, for servers using ACLs. Requires `password`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub username: Option<String>,
+	/// Password sent with `AUTH` after connecting.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		serialize_with = "crate::serdes::ser_redact"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	pub password: Option<SecretString>,
+	/// Connect to Redis over TLS, when set.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub tls: Option<RedisTLS>,
+	/// Namespace for the keys of this limit in Redis. Limits sharing a domain share buckets.
+	pub domain: String,
+	/// Maximum number of tokens a bucket can hold.
+	pub max_tokens: u64,
+	/// Number of tokens added to a bucket each fill interval.
+	pub tokens_per_fill: u64,
+	/// How often buckets are refilled.
+	#[serde(with = "serde_dur")]
+	#[cfg_attr(feature = "schema", schemars(with = "String"))]
+	pub fill_interval: Duration,
+	/// Whether this limit counts requests or LLM tokens.
+	#[serde(default)]
+	#[serde(rename = "type")]
+	pub limit_type: RateLimitType,
+	/// CEL expression selecting the bucket a request counts against, such as `jwt.sub`. Each
+	/// distinct value gets its own bucket. When unset, or when the expression fails to evaluate,
+	/// requests share the domain's bucket.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub key: Option<Arc<Expression>>,
+	/// How long an idle bucket is kept in Redis. Defaults to the time needed to refill an empty
+	/// bucket.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		with = "serde_dur_option"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	pub key_ttl: Option<Duration>,
+	/// Maximum number of connections kept open to Redis.
+	#[serde(default = "defaults::pool_size")]
+	pub pool_size: u32,
+	/// Time allowed for each Redis call, including connecting.
+	#[serde(default = "defaults::timeout", with = "serde_dur")]
+	#[cfg_attr(feature = "schema", schemars(with = "String"))]
+	pub timeout: Duration,
+	/// Behavior when Redis is unavailable or returns an error.
+	/// Defaults to failClosed, denying requests with a 500 status.
+	#[serde(default)]
+	pub failure_mode: FailureMode,
+}
+
+#[apply(schema!)]
+#[derive(Default)]
+pub struct RedisTLS {
+	/// PEM encoded certificates to verify the server with, instead of the system roots.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "deser_root"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<FileOrInline>"))]
+	pub root: Option<String>,
+	/// Server name to send and to verify the certificate against. Defaults to the host in `host`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub hostname: Option<String>,
+	/// Skip verification of the server certificate.
+	#[serde(default)]
+	pub insecure: bool,
+}
+
+fn deser_root<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	<Option<FileOrInline> as serde::Deserialize>::deserialize(deserializer)?
+		.map(|root| root.load())
+		.transpose()
+		.map_err(D::Error::custom)
+}
+
+mod defaults {
+	use std::time::Duration;
+
+	pub fn pool_size() -> u32 {
+		8
+	}
+	pub fn timeout() -> Duration {
+		Duration::from_millis(100)
+	}
+}
+
+impl TryFrom<RedisRateLimitSpec> for RedisRateLimit {
+	type Error = anyhow::Error;
+	fn try_from(spec: RedisRateLimitSpec) -> Result<Self, Self::Error> {
+		if spec.tokens_per_fill == 0 || spec.tokens_per_fill > spec.max_tokens {
+			anyhow::bail!("tokensPerFill must be between 1 and maxTokens");
+		}
+		if spec.fill_interval.as_millis() == 0 {
+			anyhow::bail!("fillInterval must be at least 1ms");
+		}
+		if spec.pool_size == 0 {
+			anyhow::bail!("poolSize must be greater than 0");
+		}
+		if spec.username.is_some() && spec.password.is_none() {
+			anyhow::bail!("username requires password");
+		}
+		let tls = spec
+			.tls
+			.as_ref()
+			.map(|tls| {
+				let hostname = match &tls.hostname {
+					Some(hostname) => hostname.clone(),
+					None => host_name(&spec.host)?.to_string(),
+				};
+				ResolvedBackendTLS {
+					root: tls.root.clone().map(String::into_bytes),
+					hostname: Some(hostname),
+					insecure: tls.insecure,
+					..Default::default()
+				}
+				.try_into()
+			})
+			.transpose()?;
+		Ok(RedisRateLimit {
+			pool: Arc::new(Pool::new(spec.pool_size)),
+			tls,
+			spec,
+		})
+	}
+}
+
+impl RedisRateLimit {
+	pub async fn check_request(&self, req: &Request) -> Result<(), ProxyError> {
+		if self.spec.limit_type != RateLimitType::Requests {
+			return Ok(());
+		}
+		let key = self.bucket_key(req);
+		self.check(&key, 1).await
+	}
+
+	/// Takes `input_tokens` from the request's bucket, or only checks that a token is available
+	/// when the input was not tokenized. The returned handle trues up the count once the response
+	/// usage is known.
+	///
+	/// A request costing more than `maxTokens` could never be admitted, so it only waits for a full
+	/// bucket and drains it; the excess is charged with the response usage.
+	pub async fn check_llm(
+		&self,
+		req: &Request,
+		input_tokens: u64,
+	) -> Result<Option<RedisRateLimitAmend>, ProxyError> {
+		if self.spec.limit_type != RateLimitType::Tokens {
+			return Ok(None);
+		}
+		let key = self.bucket_key(req);
+		let taken = input_tokens.min(self.spec.max_tokens);
+		self.check(&key, taken).await?;
+		Ok(Some(RedisRateLimitAmend {
+			limit: self.clone(),
+			key,
+			deferred: i64::try_from(input_tokens - taken).unwrap_or(i64::MAX),
+		}))
+	}
+
+	fn bucket_key(&self, req: &Request) -> String {
+		let value = self.spec.key.as_ref().and_then(|expr| {
+			Executor::new_request(req)
+				.eval(expr)
+				.inspect_err(|e| trace!("redis rate limit key failed to evaluate: {e}"))
+				.ok()?
+				.as_string()
+				.ok()
+		});
+		match value {
+			Some(value) => format!("{}:{}", self.spec.domain, value),
+			None => self.spec.domain.clone(),
+		}
+	}
+
+	async fn check(&self, key: &str, requested: u64) -> Result<(), ProxyError> {
+		match self.take(key, requested as i64, false).await {
+			Ok(outcome) if outcome.allowed => Ok(()),
+			Ok(outcome) => Err(ProxyError::RateLimitExceeded {
+				limit: self.spec.max_tokens,
+				remaining: outcome.remaining,
+				reset_seconds: outcome.reset.as_secs(),
+			}),
+			Err(e) => {
+				warn!(host=%self.spec.host, "redis rate limit failed: {e}");
+				match self.spec.failure_mode {
+					FailureMode::FailOpen => Ok(()),
+					FailureMode::FailClosed => Err(ProxyError::RateLimitFailed),
+				}
+			},
+		}
+	}
+
+	async fn take(&self, key: &str, requested: i64, force: bool) -> anyhow::Result<Outcome> {
+		let capacity = self.spec.max_tokens.to_string();
+		let fill = self.spec.tokens_per_fill.to_string();
+		let interval = self.spec.fill_interval.as_millis().to_string();
+		let requested = requested.to_string();
+		let ttl = self.key_ttl().as_millis().max(1).to_string();
+		let params: [&[u8]; 8] = [
+			b"1",
+			key.as_bytes(),
+			capacity.as_bytes(),
+			fill.as_bytes(),
+			interval.as_bytes(),
+			requested.as_bytes(),
+			ttl.as_bytes(),
+			if force { b"1" } else { b"0" },
+		];
+		let call = async {
+			let evalsha: Vec<&[u8]> = [b"EVALSHA".as_slice(), TOKEN_BUCKET_SHA.as_bytes()]
+				.into_iter()
+				.chain(params)
+				.collect();
+			match self
+				.pool
+				.call(&self.spec, self.tls.as_ref(), &evalsha)
+				.await?
+			{
+				// The script is not cached on this server yet, or the cache was flushed. EVAL runs it
+				// and caches it for the following calls.
+				Value::Error(e) if e.starts_with("NOSCRIPT") => {
+					let eval: Vec<&[u8]> = [b"EVAL".as_slice(), TOKEN_BUCKET_SCRIPT.as_bytes()]
+						.into_iter()
+						.chain(params)
+						.collect();
+					self.pool.call(&self.spec, self.tls.as_ref(), &eval).await
+				},
+				reply => Ok(reply),
+			}
+		};
+		let reply = tokio::time::timeout(self.spec.timeout, call)
+			.await
+			.map_err(|_| anyhow::anyhow!("timed out"))??;
+		if let Value::Error(e) = reply {
+			anyhow::bail!("redis error: {e}");
+		}
+		Outcome::try_from(reply)
+	}
+
+	fn key_ttl(&self) -> Duration {
+		self.spec.key_ttl.unwrap_or_else(|| {
+			let fills = self.spec.max_tokens.div_ceil(self.spec.tokens_per_fill);
+			self
+				.spec
+				.fill_interval
+				.saturating_mul(u32::try_from(fills).unwrap_or(u32::MAX))
+		})
+	}
+}
+
+impl crate::store::RequestPolicyTrait for RedisRateLimit {
+	async fn apply(
+		&self,
+		_client: &crate::proxy::httpproxy::PolicyClient,
+		_log: &mut crate::telemetry::log::RequestLog,
+		req: &mut Request,
+	) -> Result<PolicyResponse, crate::proxy::ProxyResponse> {
+		self.check_request(req).await?;
+		Ok(PolicyResponse::default())
+	}
+
+	fn expressions(&self) -> impl Iterator<Item = &Expression> {
+		self.spec.key.as_deref().into_iter()
+	}
+}
+
+/// Adjusts an LLM token bucket once the actual usage of a request is known.
+#[derive(Debug)]
+pub struct RedisRateLimitAmend {
+	limit: RedisRateLimit,
+	key: String,
+	/// Input tokens that were over `maxTokens` and so not taken up front.
+	deferred: i64,
+}
+
+impl RedisRateLimitAmend {
+	/// Removes `tokens` from the bucket (or returns them, if negative) in the background.
+	/// The bucket is clamped between empty and full.
+	pub fn amend_tokens(self, tokens: i64) {
+		let tokens = tokens.saturating_add(self.deferred);
+		if tokens == 0 {
+			return;
+		}
+		tokio::task::spawn(async move {
+			if let Err(e) = self.limit.take(&self.key, tokens, true).await {
+				debug!(host=%self.limit.spec.host, "redis rate limit amend failed: {e}");
+			}
+		});
+	}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Outcome {
+	allowed: bool,
+	remaining: u64,
+	reset: Duration,
+}
+
+impl TryFrom<Value> for Outcome {
+	type Error = anyhow::Error;
+	fn try_from(value: Value) -> Result<Self, Self::Error> {
+		let Value::Array(Some(items)) = value else {
+			anyhow::bail!("unexpected script reply: {value:?}");
+		};
+		match items.as_slice() {
+			[
+				Value::Int(allowed),
+				Value::Int(remaining),
+				Value::Int(reset),
+			] => Ok(Outcome {
+				allowed: *allowed == 1,
+				remaining: (*remaining).max(0) as u64,
+				reset: Duration::from_millis((*reset).max(0) as u64),
+			}),
+			_ => anyhow::bail!("unexpected script reply: {items:?}"),
+		}
+	}
+}
+
+/// A reply in the Redis serialization protocol (RESP2).
+// Every reply type must be parsed to keep the stream in sync, even those we never inspect.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+	Simple(String),
+	Error(String),
+	Int(i64),
+	Bulk(Option<Vec<u8>>),
+	Array(Option<Vec<Value>>),
+}
+
+/// The host part of a `host:port` address, without the brackets of an IPv6 address.
+fn host_name(host: &str) -> anyhow::Result<&str> {
+	let (name, _port) = host
+		.rsplit_once(':')
+		.ok_or_else(|| anyhow::anyhow!("host must be in the form host:port"))?;
+	Ok(name.trim_start_matches('[').trim_end_matches(']'))
+}
+
+trait Io: AsyncRead + AsyncWrite + Send + Unpin + std::fmt::Debug {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin + std::fmt::Debug> Io for T {}
+
+#[derive(Debug)]
+struct Connection {
+	stream: BufStream<Box<dyn Io>>,
+}
+
+impl Connection {
+	async fn connect(spec: &RedisRateLimitSpec, tls: Option<&BackendTLS>) -> anyhow::Result<Self> {
+		let stream = TcpStream::connect(spec.host.as_str()).await?;
+		stream.set_nodelay(true)?;
+		let stream: Box<dyn Io> = match tls {
+			Some(tls) => {
+				let tls = tls.base_config();
+				let server_name = match tls.hostname_override {
+					Some(name) => name,
+					None => ServerName::try_from(host_name(&spec.host)?.to_string())?,
+				};
+				Box::new(
+					tokio_rustls::TlsConnector::from(tls.config)
+						.connect(server_name, stream)
+						.await?,
+				)
+			},
+			None => Box::new(stream),
+		};
+		let mut conn = Connection {
+			stream: BufStream::new(stream),
+		};
+		if let Some(password) = &spec.password {
+			let password = password.expose_secret().as_bytes();
+			let reply = match &spec.username {
+				Some(username) => {
+					conn
+						.command(&[b"AUTH", username.as_bytes(), password])
+						.await?
+				},
+				None => conn.command(&[b"AUTH", password]).await?,
+			};
+			if let Value::Error(e) = reply {
+				anyhow::bail!("authentication failed: {e}");
+			}
+		}
+		Ok(conn)
+	}
+
+	async fn command(&mut self, args: &[&[u8]]) -> anyhow::Result<Value> {
+		let mut buf = Vec::with_capacity(64 + args.iter().map(|a| a.len() + 16).sum::<usize>());
+		write!(buf, "*{}\r\n", args.len())?;
+		for arg in args {
+			write!(buf, "${}\r\n", arg.len())?;
+			buf.extend_from_slice(arg);
+			buf.extend_from_slice(b"\r\n");
+		}
+		self.stream.write_all(&buf).await?;
+		self.stream.flush().await?;
+		read_value(&mut self.stream).await
+	}
+}
+
+fn read_value<'a, R: AsyncBufRead + Unpin + Send + 'a>(
+	r: &'a mut R,
+) -> BoxFuture<'a, anyhow::Result<Value>> {
+	Box::pin(async move {
+		let mut line = Vec::new();
+		// Bound the line, so a server that never sends a newline cannot grow it without limit.
+		if (&mut *r)
+			.take(MAX_REPLY_LEN as u64)
+			.read_until(b'\n', &mut line)
+			.await?
+			== 0
+		{
+			anyhow::bail!("connection closed");
+		}
+		let Some(line) = line.strip_suffix(b"\r\n") else {
+			anyhow::bail!("malformed reply line");
+		};
+		let (kind, rest) = line
+			.split_first()
+			.ok_or_else(|| anyhow::anyhow!("empty reply line"))?;
+		let rest = std::str::from_utf8(rest)?;
+		Ok(match *kind {
+			b'+' => Value::Simple(rest.to_string()),
+			b'-' => Value::Error(rest.to_string()),
+			b':' => Value::Int(rest.parse()?),
+			b'$' => match rest.parse::<i64>()? {
+				-1 => Value::Bulk(None),
+				len => {
+					let len = usize::try_from(len)?;
+					if len > MAX_REPLY_LEN {
+						anyhow::bail!("bulk reply of {len} bytes exceeds the limit");
+					}
+					let mut data = vec![0; len + 2];
+					r.read_exact(&mut data).await?;
+					data.truncate(data.len() - 2);
+					Value::Bulk(Some(data))
+				},
+			},
+			b'*' => match rest.parse::<i64>()? {
+				-1 => Value::Array(None),
+				len => {
+					let len = usize::try_from(len)?;
+					if len > MAX_REPLY_ITEMS {
+						anyhow::bail!("array reply of {len} items exceeds the limit");
+					}
+					let mut items = Vec::with_capacity(len);
+					for _ in 0..len {
+						items.push(read_value(r).await?);
+					}
+					Value::Array(Some(items))
+				},
+			},
+			other => anyhow::bail!("unknown reply type {:?}", other as char),
+		})
+	})
+}
+
+/// A bounded set of connections to Redis. Connections are reused after a successful call and
+/// discarded after any I/O error, so a broken connection is never handed out twice. Error replies
+/// are returned as [`Value::Error`] for the caller to handle.
+#[derive(Debug)]
+struct Pool {
+	permits: Semaphore,
+	idle: Mutex<Vec<Connection>>,
+}
+
+impl Pool {
+	fn new(size: u32) -> Self {
+		Pool {
+			permits: Semaphore::new(size as usize),
+			idle: Mutex::new(Vec::new()),
+		}
+	}
+
+	async fn call(
+		&self,
+		spec: &RedisRateLimitSpec,
+		tls: Option<&BackendTLS>,
+		args: &[&[u8]],
+	) -> anyhow::Result<Value> {
+		let _permit = self.permits.acquire().await?;
+		let idle = self.idle.lock().expect("mutex poisoned").pop();
+		let mut conn = match idle {
+			Some(conn) => conn,
+			None => Connection::connect(spec, tls).await?,
+		};
+		let reply = conn.command(args).await?;
+		self.idle.lock().expect("mutex poisoned").push(conn);
+		Ok(reply)
+	}
+}
@@ -0,0 +1,611 @@
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+
+use tokio::net::TcpListener;
+
+use super::*;
+use crate::http::tests_common::request;
+
+/// A minimal Redis stand-in. It understands `AUTH`, and runs the token bucket script (refill,
+/// check, forced true-up and `PEXPIRE`) against a manual clock, one command at a time, like
+/// Redis does. `EVALSHA` only succeeds once the script was loaded by an `EVAL`.
+#[derive(Clone)]
+struct MockRedis {
+	buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+	scripts: Arc<Mutex<HashSet<String>>>,
+	evals: Arc<AtomicUsize>,
+	now: Arc<AtomicI64>,
+	connections: Arc<AtomicUsize>,
+	username: Option<String>,
+	password: Option<String>,
+	tls: Option<Arc<rustls::ServerConfig>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+	tokens: i64,
+	refilled: i64,
+	expires_at: i64,
+}
+
+impl Default for MockRedis {
+	fn default() -> Self {
+		MockRedis {
+			buckets: Default::default(),
+			scripts: Default::default(),
+			evals: Default::default(),
+			now: Arc::new(AtomicI64::new(1_000_000)),
+			connections: Default::default(),
+			username: None,
+			password: None,
+			tls: None,
+		}
+	}
+}
+
+impl MockRedis {
+	async fn start(self) -> (Self, SocketAddr) {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		let mock = self.clone();
+		tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				mock.connections.fetch_add(1, Ordering::SeqCst);
+				let mock = mock.clone();
+				tokio::spawn(async move {
+					match mock.tls.clone() {
+						Some(tls) => {
+							if let Ok(stream) = tokio_rustls::TlsAcceptor::from(tls).accept(stream).await {
+								mock.serve(stream).await
+							}
+						},
+						None => mock.serve(stream).await,
+					}
+				});
+			}
+		});
+		(self, addr)
+	}
+
+	async fn serve<S: AsyncRead + AsyncWrite + Send + Unpin>(self, stream: S) {
+		let mut stream = BufStream::new(stream);
+		let mut authenticated = self.password.is_none();
+		while let Ok(Value::Array(Some(args))) = read_value(&mut stream).await {
+			let args = args
+				.into_iter()
+				.map(|a| match a {
+					Value::Bulk(Some(b)) => String::from_utf8(b).unwrap(),
+					other => panic!("unexpected argument {other:?}"),
+				})
+				.collect::<Vec<_>>();
+			let reply = match args[0].as_str() {
+				"AUTH" if self.authenticates(&args[1..]) => {
+					authenticated = true;
+					"+OK\r\n".to_string()
+				},
+				"AUTH" => "-WRONGPASS invalid password\r\n".to_string(),
+				_ if !authenticated => "-NOAUTH Authentication required.\r\n".to_string(),
+				"EVAL" | "EVALSHA" => self.eval(&args),
+				other => format!("-ERR unknown command '{other}'\r\n"),
+			};
+			if stream.write_all(reply.as_bytes()).await.is_err() || stream.flush().await.is_err() {
+				return;
+			}
+		}
+	}
+
+	fn authenticates(&self, args: &[String]) -> bool {
+		match args {
+			[password] => self.username.is_none() && Some(password) == self.password.as_ref(),
+			[username, password] => {
+				Some(username) == self.username.as_ref() && Some(password) == self.password.as_ref()
+			},
+			_ => false,
+		}
+	}
+
+	fn eval(&self, args: &[String]) -> String {
+		let [
+			command,
+			script,
+			_,
+			key,
+			capacity,
+			fill,
+			interval,
+			requested,
+			ttl,
+			force,
+		] = args
+		else {
+			return "-ERR wrong number of arguments\r\n".to_string();
+		};
+		if command == "EVAL" {
+			assert_eq!(script, TOKEN_BUCKET_SCRIPT, "unexpected script");
+			self.evals.fetch_add(1, Ordering::SeqCst);
+			let sha = hex::encode(Sha1::digest(script));
+			self.scripts.lock().unwrap().insert(sha);
+		} else if !self.scripts.lock().unwrap().contains(script) {
+			return "-NOSCRIPT No matching script. Please use EVAL.\r\n".to_string();
+		}
+		let [capacity, fill, interval, requested, ttl] =
+			[capacity, fill, interval, requested, ttl].map(|v| v.parse::<i64>().unwrap());
+		let force = force == "1";
+		let now = self.now.load(Ordering::SeqCst);
+
+		let mut buckets = self.buckets.lock().unwrap();
+		buckets.retain(|_, b| b.expires_at > now);
+		let bucket = buckets.entry(key.clone()).or_insert(Bucket {
+			tokens: capacity,
+			refilled: now,
+			expires_at: 0,
+		});
+		let intervals = (now - bucket.refilled) / interval;
+		if intervals > 0 {
+			bucket.tokens = capacity.min(bucket.tokens + intervals * fill);
+			bucket.refilled += intervals * interval;
+		}
+		let allowed = if force {
+			bucket.tokens = (bucket.tokens - requested).clamp(0, capacity);
+			1
+		} else if requested == 0 {
+			i64::from(bucket.tokens >= 1)
+		} else if bucket.tokens >= requested {
+			bucket.tokens -= requested;
+			1
+		} else {
+			0
+		};
+		bucket.expires_at = now + ttl;
+		format!(
+			"*3\r\n:{allowed}\r\n:{}\r\n:{}\r\n",
+			bucket.tokens,
+			bucket.refilled + interval - now
+		)
+	}
+
+	/// Tokens left in `key`, or `None` if the bucket was never created or has expired.
+	fn tokens(&self, key: &str) -> Option<i64> {
+		let now = self.now.load(Ordering::SeqCst);
+		self
+			.buckets
+			.lock()
+			.unwrap()
+			.get(key)
+			.filter(|b| b.expires_at > now)
+			.map(|b| b.tokens)
+	}
+
+	fn advance(&self, d: Duration) {
+		self.now.fetch_add(d.as_millis() as i64, Ordering::SeqCst);
+	}
+
+	/// Waits for a background true-up to reach the bucket.
+	async fn wait_for_tokens(&self, key: &str, tokens: i64) {
+		for _ in 0..100 {
+			if self.tokens(key) == Some(tokens) {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(5)).await;
+		}
+		assert_eq!(self.tokens(key), Some(tokens));
+	}
+}
+
+fn limit(addr: SocketAddr, max_tokens: u64) -> RedisRateLimitSpec {
+	RedisRateLimitSpec {
+		host: addr.to_string(),
+		username: None,
+		password: None,
+		tls: None,
+		domain: "test".to_string(),
+		max_tokens,
+		tokens_per_fill: 1,
+		fill_interval: Duration::from_secs(60),
+		limit_type: RateLimitType::Requests,
+		key: None,
+		key_ttl: None,
+		pool_size: 4,
+		timeout: Duration::from_secs(1),
+		failure_mode: FailureMode::FailClosed,
+	}
+}
+
+fn user(name: &str) -> Request {
+	request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-user", name)],
+	)
+}
+
+#[tokio::test]
+async fn concurrent_requests_decrement_atomically() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(limit(addr, 5)).unwrap();
+
+	let req = user("alice");
+	let results = futures::future::join_all((0..20).map(|_| rl.check_request(&req))).await;
+	let allowed = results.iter().filter(|r| r.is_ok()).count();
+	assert_eq!(allowed, 5);
+	for err in results.into_iter().filter_map(Result::err) {
+		assert!(matches!(
+			err,
+			ProxyError::RateLimitExceeded {
+				limit: 5,
+				remaining: 0,
+				reset_seconds: 60,
+			}
+		));
+	}
+	assert_eq!(mock.tokens("test"), Some(0));
+	assert!(mock.connections.load(Ordering::SeqCst) <= 4);
+}
+
+#[tokio::test]
+async fn key_expression_selects_bucket() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(RedisRateLimitSpec {
+		key: Some(Arc::new(
+			Expression::new_strict(r#"request.headers["x-user"]"#).unwrap(),
+		)),
+		..limit(addr, 2)
+	})
+	.unwrap();
+
+	assert!(rl.check_request(&user("alice")).await.is_ok());
+	assert!(rl.check_request(&user("alice")).await.is_ok());
+	assert!(rl.check_request(&user("alice")).await.is_err());
+	assert!(rl.check_request(&user("bob")).await.is_ok());
+	assert_eq!(mock.tokens("test:alice"), Some(0));
+	assert_eq!(mock.tokens("test:bob"), Some(1));
+}
+
+#[tokio::test]
+async fn connections_are_reused() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(RedisRateLimitSpec {
+		pool_size: 1,
+		..limit(addr, 10)
+	})
+	.unwrap();
+	for _ in 0..3 {
+		rl.check_request(&user("alice")).await.unwrap();
+	}
+	assert_eq!(mock.connections.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn password_is_sent() {
+	let (_mock, addr) = MockRedis {
+		password: Some("hunter2".to_string()),
+		..Default::default()
+	}
+	.start()
+	.await;
+
+	let authed = RedisRateLimit::try_from(RedisRateLimitSpec {
+		password: Some(SecretString::new("hunter2".into())),
+		..limit(addr, 1)
+	})
+	.unwrap();
+	assert!(authed.check_request(&user("alice")).await.is_ok());
+
+	let unauthed = RedisRateLimit::try_from(limit(addr, 1)).unwrap();
+	assert!(matches!(
+		unauthed.check_request(&user("alice")).await,
+		Err(ProxyError::RateLimitFailed)
+	));
+}
+
+#[tokio::test]
+async fn username_is_sent() {
+	let (_mock, addr) = MockRedis {
+		username: Some("gateway".to_string()),
+		password: Some("hunter2".to_string()),
+		..Default::default()
+	}
+	.start()
+	.await;
+
+	let authed = RedisRateLimit::try_from(RedisRateLimitSpec {
+		username: Some("gateway".to_string()),
+		password: Some(SecretString::new("hunter2".into())),
+		..limit(addr, 1)
+	})
+	.unwrap();
+	assert!(authed.check_request(&user("alice")).await.is_ok());
+
+	let default_user = RedisRateLimit::try_from(RedisRateLimitSpec {
+		password: Some(SecretString::new("hunter2".into())),
+		..limit(addr, 1)
+	})
+	.unwrap();
+	assert!(default_user.check_request(&user("alice")).await.is_err());
+}
+
+#[tokio::test]
+async fn connects_over_tls() {
+	let key = rcgen::KeyPair::generate().unwrap();
+	let cert = rcgen::CertificateParams::new(vec!["redis.example.com".to_string()])
+		.unwrap()
+		.self_signed(&key)
+		.unwrap();
+	let server = rustls::ServerConfig::builder_with_provider(crate::transport::tls::provider())
+		.with_safe_default_protocol_versions()
+		.unwrap()
+		.with_no_client_auth()
+		.with_single_cert(
+			vec![cert.der().clone()],
+			rustls::pki_types::PrivatePkcs8KeyDer::from(key.serialize_der()).into(),
+		)
+		.unwrap();
+	let (mock, addr) = MockRedis {
+		tls: Some(Arc::new(server)),
+		..Default::default()
+	}
+	.start()
+	.await;
+
+	let rl = RedisRateLimit::try_from(RedisRateLimitSpec {
+		tls: Some(RedisTLS {
+			root: Some(cert.pem()),
+			hostname: Some("redis.example.com".to_string()),
+			insecure: false,
+		}),
+		..limit(addr, 1)
+	})
+	.unwrap();
+	assert!(rl.check_request(&user("alice")).await.is_ok());
+	assert_eq!(mock.tokens("test"), Some(0));
+
+	// The server certificate is verified against the configured name.
+	let wrong_name = RedisRateLimit::try_from(RedisRateLimitSpec {
+		tls: Some(RedisTLS {
+			root: Some(cert.pem()),
+			hostname: Some("other.example.com".to_string()),
+			insecure: false,
+		}),
+		..limit(addr, 1)
+	})
+	.unwrap();
+	assert!(matches!(
+		wrong_name.check_request(&user("alice")).await,
+		Err(ProxyError::RateLimitFailed)
+	));
+}
+
+#[tokio::test]
+async fn redis_unavailable_follows_failure_mode() {
+	// Reserve a port, then close it so nothing is listening.
+	let addr = TcpListener::bind("127.0.0.1:0")
+		.await
+		.unwrap()
+		.local_addr()
+		.unwrap();
+
+	let closed = RedisRateLimit::try_from(limit(addr, 1)).unwrap();
+	assert!(matches!(
+		closed.check_request(&user("alice")).await,
+		Err(ProxyError::RateLimitFailed)
+	));
+
+	let open = RedisRateLimit::try_from(RedisRateLimitSpec {
+		failure_mode: FailureMode::FailOpen,
+		..limit(addr, 1)
+	})
+	.unwrap();
+	assert!(open.check_request(&user("alice")).await.is_ok());
+}
+
+#[tokio::test]
+async fn llm_tokens_are_trued_up() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(RedisRateLimitSpec {
+		limit_type: RateLimitType::Tokens,
+		..limit(addr, 10)
+	})
+	.unwrap();
+	let req = user("alice");
+
+	// Token limits don't count requests.
+	rl.check_request(&req).await.unwrap();
+	assert_eq!(mock.tokens("test"), None);
+
+	let amend = rl.check_llm(&req, 4).await.unwrap().expect("token limit");
+	assert_eq!(mock.tokens("test"), Some(6));
+	amend.amend_tokens(5);
+	mock.wait_for_tokens("test", 1).await;
+
+	assert!(rl.check_llm(&req, 2).await.is_err());
+	// Untokenized requests pass while any token is left.
+	let amend = rl.check_llm(&req, 0).await.unwrap().expect("token limit");
+	assert_eq!(mock.tokens("test"), Some(1));
+
+	// Returned tokens never overfill the bucket.
+	amend.amend_tokens(-100);
+	mock.wait_for_tokens("test", 10).await;
+}
+
+#[tokio::test]
+async fn llm_cost_above_max_tokens_is_charged_on_true_up() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(RedisRateLimitSpec {
+		limit_type: RateLimitType::Tokens,
+		key_ttl: Some(Duration::from_secs(3600)),
+		..limit(addr, 10)
+	})
+	.unwrap();
+	let req = user("alice");
+
+	// A request over the bucket size is admitted once the bucket is full, and drains it.
+	let amend = rl.check_llm(&req, 25).await.unwrap().expect("token limit");
+	assert_eq!(mock.tokens("test"), Some(0));
+
+	// The 15 tokens over the limit are still charged when the response is accounted for.
+	mock.advance(Duration::from_secs(600));
+	assert!(rl.check_llm(&req, 0).await.is_ok());
+	assert_eq!(mock.tokens("test"), Some(10));
+	amend.amend_tokens(0);
+	mock.wait_for_tokens("test", 0).await;
+}
+
+#[tokio::test]
+async fn buckets_refill_each_interval() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(RedisRateLimitSpec {
+		tokens_per_fill: 2,
+		key_ttl: Some(Duration::from_secs(3600)),
+		..limit(addr, 3)
+	})
+	.unwrap();
+	let req = user("alice");
+
+	for _ in 0..3 {
+		rl.check_request(&req).await.unwrap();
+	}
+	assert!(matches!(
+		rl.check_request(&req).await,
+		Err(ProxyError::RateLimitExceeded {
+			remaining: 0,
+			reset_seconds: 60,
+			..
+		})
+	));
+
+	// A partial interval adds nothing, and the reset counts down to the next fill.
+	mock.advance(Duration::from_secs(45));
+	assert!(matches!(
+		rl.check_request(&req).await,
+		Err(ProxyError::RateLimitExceeded {
+			reset_seconds: 15,
+			..
+		})
+	));
+
+	mock.advance(Duration::from_secs(15));
+	rl.check_request(&req).await.unwrap();
+	assert_eq!(mock.tokens("test"), Some(1));
+
+	// Refills stop at maxTokens.
+	mock.advance(Duration::from_secs(600));
+	rl.check_request(&req).await.unwrap();
+	assert_eq!(mock.tokens("test"), Some(2));
+}
+
+#[tokio::test]
+async fn idle_buckets_expire() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(RedisRateLimitSpec {
+		key_ttl: Some(Duration::from_secs(30)),
+		..limit(addr, 2)
+	})
+	.unwrap();
+	let req = user("alice");
+
+	rl.check_request(&req).await.unwrap();
+	mock.advance(Duration::from_secs(29));
+	rl.check_request(&req).await.unwrap();
+	// Each call extends the TTL.
+	mock.advance(Duration::from_secs(29));
+	assert_eq!(mock.tokens("test"), Some(0));
+
+	mock.advance(Duration::from_secs(1));
+	assert_eq!(mock.tokens("test"), None);
+	// An expired bucket starts over full.
+	rl.check_request(&req).await.unwrap();
+	assert_eq!(mock.tokens("test"), Some(1));
+}
+
+#[tokio::test]
+async fn script_is_loaded_once() {
+	let (mock, addr) = MockRedis::default().start().await;
+	let rl = RedisRateLimit::try_from(limit(addr, 10)).unwrap();
+	let req = user("alice");
+
+	for _ in 0..3 {
+		rl.check_request(&req).await.unwrap();
+	}
+	assert_eq!(mock.evals.load(Ordering::SeqCst), 1);
+
+	// After the server's script cache is flushed, the script is sent again.
+	mock.scripts.lock().unwrap().clear();
+	rl.check_request(&req).await.unwrap();
+	rl.check_request(&req).await.unwrap();
+	assert_eq!(mock.evals.load(Ordering::SeqCst), 2);
+	assert_eq!(mock.tokens("test"), Some(5));
+}
+
+#[test]
+fn spec_validation() {
+	let addr: SocketAddr = "127.0.0.1:6379".parse().unwrap();
+	assert!(
+		RedisRateLimit::try_from(RedisRateLimitSpec {
+			tokens_per_fill: 0,
+			..limit(addr, 1)
+		})
+		.is_err()
+	);
+	assert!(
+		RedisRateLimit::try_from(RedisRateLimitSpec {
+			pool_size: 0,
+			..limit(addr, 1)
+		})
+		.is_err()
+	);
+	assert!(
+		RedisRateLimit::try_from(RedisRateLimitSpec {
+			username: Some("gateway".to_string()),
+			..limit(addr, 1)
+		})
+		.is_err()
+	);
+
+	let rl: RedisRateLimit = serde_json::from_value(serde_json::json!({
+		"host": "redis:6379",
+		"domain": "api",
+		"maxTokens": 100,
+		"tokensPerFill": 10,
+		"fillInterval": "1s",
+	}))
+	.unwrap();
+	assert_eq!(rl.spec.pool_size, 8);
+	assert_eq!(rl.spec.timeout, Duration::from_millis(100));
+	assert_eq!(rl.spec.failure_mode, FailureMode::FailClosed);
+	assert_eq!(rl.key_ttl(), Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn parses_replies() {
+	let mut input: &[u8] = b"*4\r\n:1\r\n$3\r\nabc\r\n$-1\r\n*1\r\n+OK\r\n";
+	let mut reader = tokio::io::BufReader::new(&mut input);
+	assert_eq!(
+		read_value(&mut reader).await.unwrap(),
+		Value::Array(Some(vec![
+			Value::Int(1),
+			Value::Bulk(Some(b"abc".to_vec())),
+			Value::Bulk(None),
+			Value::Array(Some(vec![Value::Simple("OK".to_string())])),
+		]))
+	);
+}
+
+#[tokio::test]
+async fn rejects_oversized_replies() {
+	let mut input: &[u8] = b"$1073741824\r\n";
+	let mut reader = tokio::io::BufReader::new(&mut input);
+	let err = read_value(&mut reader).await.unwrap_err();
+	assert!(err.to_string().contains("exceeds the limit"), "{err}");
+
+	let mut input: &[u8] = b"*1073741824\r\n";
+	let mut reader = tokio::io::BufReader::new(&mut input);
+	let err = read_value(&mut reader).await.unwrap_err();
+	assert!(err.to_string().contains("exceeds the limit"), "{err}");
+
+	// A line that never ends is cut off rather than buffered forever.
+	let long = vec![b'+'; MAX_REPLY_LEN * 2];
+	let mut input: &[u8] = &long;
+	let mut reader = tokio::io::BufReader::new(&mut input);
+	assert!(read_value(&mut reader).await.is_err());
+}
@@ -246,7 +246,7 @@ fn amend_tokens_keeps_descriptor_costs_aligned_when_dropping_descriptor() {
 	};
 	let descriptor_costs = vec![
 		Some(Arc::new(
-			cel::Expression::new_strict("missingField").expect("valid CEL expression"),
+			cel::Expression::new_strict("jwt.missingField").expect("valid CEL expression"),
 		)),
 		Some(Arc::new(
 			cel::Expression::new_strict("uint(7)").expect("valid CEL expression"),
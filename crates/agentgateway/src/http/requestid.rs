@@ -0,0 +1,84 @@
+use ::http::HeaderValue;
+use ::http::header::HeaderName;
+
+use crate::cel::RequestIdValue;
+use crate::http::{HeaderMap, PolicyResponse, Request};
+use crate::*;
+
+#[cfg(test)]
+#[path = "requestid_tests.rs"]
+mod requestid_tests;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+#[apply(schema!)]
+#[derive(Default, Copy, PartialEq, Eq)]
+pub enum RequestIdFormat {
+	// A random UUID (version 4).
+	#[default]
+	UuidV4,
+	// A time-ordered UUID (version 7). Like a ULID, generated IDs sort by creation time.
+	UuidV7,
+}
+
+impl RequestIdFormat {
+	fn generate(&self) -> String {
+		match self {
+			RequestIdFormat::UuidV4 => uuid::Uuid::new_v4().to_string(),
+			RequestIdFormat::UuidV7 => uuid::Uuid::now_v7().to_string(),
+		}
+	}
+}
+
+#[apply(schema!)]
+#[derive(Default)]
+pub struct RequestId {
+	/// Format used to generate an ID when the request does not already carry an `x-request-id` header.
+	#[serde(default)]
+	pub format: RequestIdFormat,
+}
+
+impl RequestId {
+	/// Ensures the request carries an `x-request-id`, keeping an incoming one or generating a new one.
+	/// The ID is exposed as the `request.id` CEL attribute and echoed on the response.
+	pub fn apply(&self, req: &mut Request) -> PolicyResponse {
+		let existing = req
+			.headers()
+			.get(REQUEST_ID_HEADER)
+			.filter(|v| v.to_str().is_ok_and(|s| !s.is_empty()))
+			.cloned();
+		let value = match existing {
+			Some(v) => v,
+			None => {
+				let v =
+					HeaderValue::try_from(self.format.generate()).expect("uuid is a valid header value");
+				req.headers_mut().insert(REQUEST_ID_HEADER, v.clone());
+				v
+			},
+		};
+		let id = strng::new(
+			value
+				.to_str()
+				.expect("request id was checked to be valid utf-8"),
+		);
+		req.extensions_mut().insert(RequestIdValue(id));
+
+		let mut response_headers = HeaderMap::new();
+		response_headers.insert(REQUEST_ID_HEADER, value);
+		PolicyResponse {
+			direct_response: None,
+			response_headers: Some(response_headers),
+		}
+	}
+}
+
+impl crate::store::RequestPolicyTrait for RequestId {
+	async fn apply(
+		&self,
+		_client: &crate::proxy::httpproxy::PolicyClient,
+		_log: &mut crate::telemetry::log::RequestLog,
+		req: &mut Request,
+	) -> Result<PolicyResponse, crate::proxy::ProxyResponse> {
+		Ok(self.apply(req))
+	}
+}
@@ -0,0 +1,79 @@
+use super::*;
+use crate::http::tests_common::request;
+
+#[test]
+fn existing_request_id_is_passed_through() {
+	let mut req = request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-request-id", "abc-123")],
+	);
+	let resp = RequestId::default().apply(&mut req);
+
+	assert_eq!(req.headers().get(REQUEST_ID_HEADER).unwrap(), "abc-123");
+	assert_eq!(
+		req.extensions().get::<RequestIdValue>().unwrap().0.as_str(),
+		"abc-123"
+	);
+	assert!(resp.direct_response.is_none());
+	assert_eq!(
+		resp
+			.response_headers
+			.unwrap()
+			.get(REQUEST_ID_HEADER)
+			.unwrap(),
+		"abc-123"
+	);
+}
+
+#[test]
+fn missing_request_id_is_generated() {
+	for format in [RequestIdFormat::UuidV4, RequestIdFormat::UuidV7] {
+		let mut req = request("http://example.com/", ::http::Method::GET, &[]);
+		let resp = RequestId { format }.apply(&mut req);
+
+		let header = req
+			.headers()
+			.get(REQUEST_ID_HEADER)
+			.expect("request id is generated")
+			.to_str()
+			.unwrap()
+			.to_string();
+		let parsed = uuid::Uuid::parse_str(&header).expect("generated id is a uuid");
+		let want_version = match format {
+			RequestIdFormat::UuidV4 => 4,
+			RequestIdFormat::UuidV7 => 7,
+		};
+		assert_eq!(parsed.get_version_num(), want_version);
+		assert_eq!(
+			req.extensions().get::<RequestIdValue>().unwrap().0.as_str(),
+			header
+		);
+		assert_eq!(
+			resp
+				.response_headers
+				.unwrap()
+				.get(REQUEST_ID_HEADER)
+				.unwrap(),
+			header.as_str()
+		);
+	}
+}
+
+#[test]
+fn empty_request_id_is_replaced() {
+	let mut req = request(
+		"http://example.com/",
+		::http::Method::GET,
+		&[("x-request-id", "")],
+	);
+	RequestId::default().apply(&mut req);
+
+	let header = req
+		.headers()
+		.get(REQUEST_ID_HEADER)
+		.unwrap()
+		.to_str()
+		.unwrap();
+	assert!(uuid::Uuid::parse_str(header).is_ok());
+}
@@ -167,6 +167,7 @@ pub fn select_best_route(
 				hostnames: vec![],
 				matches: vec![],
 				inline_policies: vec![],
+				backend_hash_key: None,
 				llm_router: None,
 				backends: vec![RouteBackendReference {
 					weight: 1,
@@ -42,6 +42,7 @@ fn setup_listener(routes: &[(&str, Vec<&str>, Vec<RouteMatch>)]) -> (Arc<Listene
 		matches,
 		name: Default::default(),
 		backends: vec![],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: vec![],
 	};
@@ -1202,6 +1203,7 @@ fn service_route(key: &str, service_key: NamespacedHostname, matches: Vec<RouteM
 		hostnames: vec![], // GAMMA: hostname matching skipped for service routes
 		matches,
 		backends: vec![],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: vec![],
 	}
@@ -1505,6 +1507,7 @@ fn bench(b: Bencher, (host, route): (u64, u64)) {
 		hostnames: host.into_iter().map(|s| s.into()).collect(),
 		matches,
 		backends: vec![],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: vec![],
 	}) {
@@ -1,4 +1,4 @@
-use ::http::{HeaderName, header};
+use ::http::{HeaderName, HeaderValue, header};
 use agent_core::prelude::Strng;
 use serde_with::serde_as;
 
@@ -6,8 +6,8 @@ use crate::cel::{Expression, RequestSnapshot};
 use crate::http::{
 	HeaderOrPseudo, HeaderOrPseudoValue, PolicyResponse, Request, RequestOrResponse, Response,
 };
-use crate::proxy::ProxyResponse;
 use crate::proxy::httpproxy::PolicyClient;
+use crate::proxy::{ProxyError, ProxyResponse};
 use crate::telemetry::log::RequestLog;
 use crate::{cel, *};
 
@@ -39,10 +39,21 @@ pub struct LocalTransform {
 	/// CEL expression that computes a replacement body.
 	#[serde(default)]
 	pub body: Option<Strng>,
+	/// CEL expression that computes a replacement JSON body, for example
+	/// `json(request.body).merge({"stream": false})`. The expression must produce an object, which is
+	/// serialized as the new body; otherwise the request or response is rejected.
+	/// Cannot be combined with `body`.
+	#[serde(default)]
+	pub json_body: Option<Strng>,
 	/// Metadata values to add using CEL expressions.
 	#[serde(default)]
 	#[serde_as(as = "serde_with::Map<_, _>")]
 	pub metadata: Vec<(Strng, Strng)>,
+	/// Cache up to this many results of each expression, keyed by a hash of the attributes it
+	/// references. Useful for expressions over stable inputs, such as `jwt.sub`. Expressions calling
+	/// `random()`, `uuid()`, `variables()` or a custom function are never cached.
+	#[serde(default)]
+	pub cache_size: Option<usize>,
 }
 
 #[apply(schema!)]
@@ -58,27 +69,37 @@ impl TransformerConfig {
 	where
 		F: FnMut(&str, &cel::Error),
 	{
-		fn compile<F>(s: &str, strict: bool, warnings: &mut F) -> anyhow::Result<cel::Expression>
+		fn compile<F>(
+			s: &str,
+			strict: bool,
+			cache_size: Option<usize>,
+			warnings: &mut F,
+		) -> anyhow::Result<cel::Expression>
 		where
 			F: FnMut(&str, &cel::Error),
 		{
-			if strict {
-				Ok(cel::Expression::new_strict(s)?)
+			let expression = if strict {
+				cel::Expression::new_strict(s)?
 			} else {
 				let (expression, err) = cel::Expression::new_permissive(s);
 				if let Some(err) = &err {
 					warnings(s, err);
 				}
-				Ok(expression)
-			}
+				expression
+			};
+			Ok(match cache_size {
+				Some(capacity) => expression.with_result_cache(capacity),
+				None => expression,
+			})
 		}
 
+		let cache_size = req.cache_size;
 		let set = req
 			.set
 			.into_iter()
 			.map(|(k, v)| {
 				let tk = HeaderOrPseudo::try_from(k.as_str())?;
-				let tv = compile(v.as_str(), strict, warnings)?;
+				let tv = compile(v.as_str(), strict, cache_size, warnings)?;
 				Ok::<_, anyhow::Error>((tk, tv))
 			})
 			.collect::<Result<_, _>>()?;
@@ -87,7 +108,7 @@ impl TransformerConfig {
 			.into_iter()
 			.map(|(k, v)| {
 				let tk = HeaderOrPseudo::try_from(k.as_str())?;
-				let tv = compile(v.as_str(), strict, warnings)?;
+				let tv = compile(v.as_str(), strict, cache_size, warnings)?;
 				Ok::<_, anyhow::Error>((tk, tv))
 			})
 			.collect::<Result<_, _>>()?;
@@ -96,20 +117,28 @@ impl TransformerConfig {
 			.into_iter()
 			.map(|k| HeaderName::try_from(k.as_str()))
 			.collect::<Result<_, _>>()?;
+		if req.body.is_some() && req.json_body.is_some() {
+			anyhow::bail!("only one of body and jsonBody may be set");
+		}
 		let body = req
 			.body
-			.map(|b| compile(b.as_str(), strict, warnings))
+			.map(|b| compile(b.as_str(), strict, cache_size, warnings))
+			.transpose()?;
+		let json_body = req
+			.json_body
+			.map(|b| compile(b.as_str(), strict, cache_size, warnings))
 			.transpose()?;
 		let metadata = req
 			.metadata
 			.into_iter()
-			.map(|(k, v)| Ok::<_, anyhow::Error>((k, compile(v.as_str(), strict, warnings)?)))
+			.map(|(k, v)| Ok::<_, anyhow::Error>((k, compile(v.as_str(), strict, cache_size, warnings)?)))
 			.collect::<Result<_, _>>()?;
 		Ok(TransformerConfig {
 			set,
 			add,
 			remove,
 			body,
+			json_body,
 			metadata,
 		})
 	}
@@ -167,6 +196,8 @@ pub struct TransformerConfig {
 	pub remove: Vec<HeaderName>,
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub body: Option<cel::Expression>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub json_body: Option<cel::Expression>,
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub metadata: Vec<(Strng, cel::Expression)>,
 }
@@ -190,7 +221,7 @@ fn eval_body(
 	}
 }
 
-fn eval_metadata(
+fn eval_json(
 	r: &RequestOrResponse,
 	expr: &Expression,
 	request: Option<&cel::RequestSnapshot>,
@@ -213,8 +244,20 @@ fn eval_metadata(
 	}
 }
 
+fn eval_json_body(
+	r: &RequestOrResponse,
+	expr: &Expression,
+	request: Option<&cel::RequestSnapshot>,
+) -> anyhow::Result<Bytes> {
+	let v = eval_json(r, expr, request)?;
+	if !v.is_object() {
+		anyhow::bail!("json body expression must produce an object, got {v}");
+	}
+	Ok(Bytes::from(serde_json::to_vec(&v)?))
+}
+
 impl Transformation {
-	pub fn apply_request(&self, req: &mut crate::http::Request) {
+	pub fn apply_request(&self, req: &mut crate::http::Request) -> Result<(), ProxyError> {
 		Self::apply(req.into(), self.request.as_ref(), None)
 	}
 
@@ -222,7 +265,7 @@ impl Transformation {
 		&self,
 		resp: &mut crate::http::Response,
 		request: Option<&RequestSnapshot>,
-	) {
+	) -> Result<(), ProxyError> {
 		if let Some(request_metadata) = request.and_then(|req| req.metadata.as_ref()) {
 			// Transformation metadata is currently stored in request/response extensions.
 			// Seed request metadata into the response extension so response-phase CEL,
@@ -252,25 +295,45 @@ impl Transformation {
 		match r {
 			RequestOrResponse::Request(r) => {
 				let exec = cel::Executor::new_request(r);
-				let v = exec.eval(expr).ok();
-				HeaderOrPseudoValue::from_cel_result(k, v)
+				Self::header_value(k, exec.eval(expr))
 			},
 			RequestOrResponse::Response(r) => {
 				let exec = cel::Executor::new_response(request, r);
-				let v = exec.eval(expr).ok();
-				HeaderOrPseudoValue::from_cel_result(k, v)
+				Self::header_value(k, exec.eval(expr))
 			},
 		}
 	}
 
+	/// Converts an expression result into a header value. Failed evaluations and values that cannot
+	/// be represented as a header are skipped with a warning; the header is then removed rather than set.
+	/// A null result (for example, an unset optional field) is skipped silently.
+	fn header_value(
+		k: &HeaderOrPseudo,
+		res: Result<cel::Value<'_>, cel::Error>,
+	) -> Option<HeaderOrPseudoValue> {
+		let v = match res {
+			Ok(cel::Value::Null) => return None,
+			Ok(v) => v,
+			Err(e) => {
+				debug!(header = %k, "failed to evaluate header expression: {e}");
+				return None;
+			},
+		};
+		let value = HeaderOrPseudoValue::from_cel_result(k, Some(v));
+		if value.is_none() {
+			debug!(header = %k, "header expression produced an invalid value, skipping");
+		}
+		value
+	}
+
 	fn apply<'a>(
 		mut r: RequestOrResponse<'a>,
 		cfg: &TransformerConfig,
 		request: Option<&'a RequestSnapshot>,
-	) {
+	) -> Result<(), ProxyError> {
 		if !cfg.metadata.is_empty() {
 			for (name, expr) in &cfg.metadata {
-				if let Ok(v) = eval_metadata(&r, expr, request) {
+				if let Ok(v) = eval_json(&r, expr, request) {
 					let metadata = Self::get_meta(&mut r);
 					metadata.0.insert(name.to_string(), v);
 				}
@@ -293,6 +356,17 @@ impl Transformation {
 			*r.body() = http::Body::from(b);
 			r.headers().remove(&header::CONTENT_LENGTH);
 		}
+		if let Some(b) = &cfg.json_body {
+			let b = eval_json_body(&r, b, request).map_err(ProxyError::Processing)?;
+			r.headers()
+				.insert(header::CONTENT_LENGTH, HeaderValue::from(b.len()));
+			r.headers().insert(
+				header::CONTENT_TYPE,
+				HeaderValue::from_static("application/json"),
+			);
+			*r.body() = http::Body::from(b);
+		}
+		Ok(())
 	}
 
 	fn get_meta<'a>(r: &'a mut RequestOrResponse<'_>) -> &'a mut TransformationMetadata {
@@ -318,7 +392,7 @@ impl crate::store::RequestPolicyTrait for Transformation {
 		_log: &mut crate::telemetry::log::RequestLog,
 		req: &mut crate::http::Request,
 	) -> Result<crate::http::PolicyResponse, crate::proxy::ProxyResponse> {
-		self.apply_request(req);
+		self.apply_request(req)?;
 		Ok(crate::http::PolicyResponse::default())
 	}
 
@@ -330,10 +404,12 @@ impl crate::store::RequestPolicyTrait for Transformation {
 			.map(|v| &v.1)
 			.chain(self.request.set.iter().map(|v| &v.1))
 			.chain(self.request.body.as_ref())
+			.chain(self.request.json_body.as_ref())
 			.chain(self.request.metadata.iter().map(|v| &v.1))
 			.chain(self.response.add.iter().map(|v| &v.1))
 			.chain(self.response.set.iter().map(|v| &v.1))
 			.chain(self.response.body.as_ref())
+			.chain(self.response.json_body.as_ref())
 			.chain(self.response.metadata.iter().map(|v| &v.1))
 	}
 }
@@ -345,7 +421,7 @@ impl store::BackendPolicyTrait for Transformation {
 		_log: &mut Option<&mut RequestLog>,
 		req: &mut Request,
 	) -> Result<PolicyResponse, ProxyResponse> {
-		self.apply_request(req);
+		self.apply_request(req)?;
 		Ok(crate::http::PolicyResponse::default())
 	}
 }
@@ -356,7 +432,7 @@ impl store::ResponsePolicyTrait for Transformation {
 		log: &mut RequestLog,
 		resp: &mut Response,
 	) -> Result<PolicyResponse, ProxyResponse> {
-		self.apply_response(resp, log.request_snapshot.as_deref());
+		self.apply_response(resp, log.request_snapshot.as_deref())?;
 		Ok(crate::http::PolicyResponse::default())
 	}
 }
@@ -26,7 +26,7 @@ fn test_transformation() {
 		.body(crate::http::Body::empty())
 		.unwrap();
 	let xfm = build([("x-insert", r#""hello " + request.headers["x-custom-foo"]"#)]);
-	xfm.apply_request(&mut req);
+	xfm.apply_request(&mut req).unwrap();
 	assert_eq!(req.headers().get("x-insert").unwrap(), "hello Bar");
 }
 
@@ -51,7 +51,7 @@ async fn test_transformation_body() {
 		.body(crate::http::Body::empty())
 		.unwrap();
 	let snap = cel::snapshot_request(&mut req, true);
-	xfm.apply_response(&mut resp, Some(&snap));
+	xfm.apply_response(&mut resp, Some(&snap)).unwrap();
 	let b = http::read_body_with_limit(resp.into_body(), 1000)
 		.await
 		.unwrap();
@@ -92,7 +92,7 @@ request.body
 	};
 	let xfm = Transformation::try_from_local_config(c, true).unwrap();
 
-	xfm.apply_request(&mut req);
+	xfm.apply_request(&mut req).unwrap();
 
 	assert!(req.headers().get(::http::header::CONTENT_LENGTH).is_none());
 	let body = crate::http::read_body_with_limit(req.into_body(), 1000)
@@ -120,7 +120,7 @@ request.body
 			b"grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code&device_code=abc",
 		)));
 
-	xfm.apply_request(&mut req);
+	xfm.apply_request(&mut req).unwrap();
 
 	let body = crate::http::read_body_with_limit(req.into_body(), 1000)
 		.await
@@ -176,7 +176,7 @@ json(response.body).with(body,
 	));
 
 	let snap = cel::snapshot_request(&mut req, true);
-	xfm.apply_response(&mut resp, Some(&snap));
+	xfm.apply_response(&mut resp, Some(&snap)).unwrap();
 	let body = crate::http::read_body_with_limit(resp.into_body(), 1000)
 		.await
 		.unwrap();
@@ -207,7 +207,7 @@ fn test_transformation_pseudoheader() {
 		(":path", r#""/" + request.uri.split("://")[0]"#),
 		(":authority", r#""example.com""#),
 	]);
-	xfm.apply_request(&mut req);
+	xfm.apply_request(&mut req).unwrap();
 	assert_eq!(req.method().as_str(), "POST");
 	assert_eq!(req.uri().to_string().as_str(), "https://example.com/https");
 }
@@ -220,7 +220,7 @@ fn test_transformation_host_header_lifts_to_authority() {
 		.body(crate::http::Body::empty())
 		.unwrap();
 	let xfm = build([("host", r#""example.com:8443""#)]);
-	xfm.apply_request(&mut req);
+	xfm.apply_request(&mut req).unwrap();
 	assert_eq!(req.uri().to_string().as_str(), "https://example.com:8443/");
 	assert!(req.headers().get(::http::header::HOST).is_none());
 }
@@ -243,7 +243,7 @@ fn test_transformation_metadata() {
 		response: None,
 	};
 	let xfm = Transformation::try_from_local_config(c, true).unwrap();
-	xfm.apply_request(&mut req);
+	xfm.apply_request(&mut req).unwrap();
 	let md = req
 		.extensions()
 		.get::<TransformationMetadata>()
@@ -290,10 +290,10 @@ fn test_response_transformation_metadata_available_to_headers() {
 		}),
 	};
 	let xfm = Transformation::try_from_local_config(c, true).unwrap();
-	xfm.apply_request(&mut req);
+	xfm.apply_request(&mut req).unwrap();
 	let snap = cel::snapshot_request(&mut req, true);
 
-	xfm.apply_response(&mut resp, Some(&snap));
+	xfm.apply_response(&mut resp, Some(&snap)).unwrap();
 
 	assert_eq!(resp.headers().get("x-static").unwrap(), "hello-world");
 	assert_eq!(resp.headers().get("x-copied").unwrap(), "from-request");
@@ -341,3 +341,154 @@ fn test_response_transformation_metadata_available_to_headers() {
 		cel::Value::String("from-request,hello-world,response".into())
 	);
 }
+
+#[test]
+fn test_response_transformation_headers_from_llm_context() {
+	let mut req = ::http::Request::builder()
+		.method("POST")
+		.uri("https://api.example.com/v1/chat/completions")
+		.body(crate::http::Body::empty())
+		.unwrap();
+	let mut resp = ::http::Response::builder()
+		.status(200)
+		.header("x-stale", "stale")
+		.body(crate::http::Body::empty())
+		.unwrap();
+	let c = super::LocalTransformationConfig {
+		request: None,
+		response: Some(super::LocalTransform {
+			set: vec![
+				("x-model".into(), "llm.responseModel".into()),
+				("x-constant".into(), r#""fixed""#.into()),
+				("x-stale".into(), r#""bad\nvalue""#.into()),
+			],
+			..Default::default()
+		}),
+	};
+	let xfm = Transformation::try_from_local_config(c, true).unwrap();
+	let mut snap = cel::snapshot_request(&mut req, true);
+	snap.llm = Some(cel::LLMContext {
+		response_model: Some(strng::literal!("gpt-4o-2024-08-06")),
+		..cel::LLMContext::from(llm::LLMRequest {
+			input_tokens: None,
+			input_format: llm::InputFormat::Completions,
+			cache_convention: llm::CacheTokenConvention::InputIncludesCache,
+			request_model: strng::literal!("gpt-4o"),
+			provider: strng::literal!("openai"),
+			streaming: false,
+			params: llm::LLMRequestParams::default(),
+			prompt: None,
+			provider_state: None,
+		})
+	});
+
+	xfm.apply_response(&mut resp, Some(&snap)).unwrap();
+
+	assert_eq!(resp.headers().get("x-model").unwrap(), "gpt-4o-2024-08-06");
+	assert_eq!(resp.headers().get("x-constant").unwrap(), "fixed");
+	// Values that are not valid header values are skipped, never set verbatim.
+	assert!(resp.headers().get("x-stale").is_none());
+}
+
+fn json_body_request(body: &'static [u8]) -> crate::http::Request {
+	let mut req = ::http::Request::builder()
+		.method("POST")
+		.uri("https://api.example.com/v1/items")
+		.header("content-type", "application/json")
+		.header("content-length", body.len())
+		.body(crate::http::Body::from(bytes::Bytes::from_static(body)))
+		.unwrap();
+	req
+		.extensions_mut()
+		.insert(crate::cel::BufferedBody(bytes::Bytes::from_static(body)));
+	req
+}
+
+fn json_body_transformation(expr: &str) -> Transformation {
+	let c = super::LocalTransformationConfig {
+		request: Some(super::LocalTransform {
+			json_body: Some(expr.into()),
+			..Default::default()
+		}),
+		response: None,
+	};
+	Transformation::try_from_local_config(c, true).unwrap()
+}
+
+#[tokio::test]
+async fn test_transformation_json_body() {
+	let mut req = json_body_request(br#"{"name":"widget","count":1}"#);
+	let xfm = json_body_transformation(
+		r#"json(request.body).merge({"name": jsonGet(json(request.body), "name") + "-v2", "source": "gateway"})"#,
+	);
+
+	xfm.apply_request(&mut req).unwrap();
+
+	let content_length = req
+		.headers()
+		.get(::http::header::CONTENT_LENGTH)
+		.unwrap()
+		.clone();
+	let body = crate::http::read_body_with_limit(req.into_body(), 1000)
+		.await
+		.unwrap();
+	assert_eq!(content_length, body.len().to_string());
+	let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+	assert_eq!(
+		body,
+		serde_json::json!({"name": "widget-v2", "count": 1, "source": "gateway"})
+	);
+}
+
+#[test]
+fn test_transformation_json_body_rejects_non_object() {
+	let mut req = json_body_request(br#"{"name":"widget"}"#);
+	let xfm = json_body_transformation(r#"jsonGet(json(request.body), "name")"#);
+
+	let err = xfm.apply_request(&mut req).unwrap_err();
+	assert!(matches!(err, ProxyError::Processing(_)), "{err}");
+}
+
+#[test]
+fn test_transformation_json_body_conflicts_with_body() {
+	let c = super::LocalTransformationConfig {
+		request: Some(super::LocalTransform {
+			body: Some(r#""raw""#.into()),
+			json_body: Some("{}".into()),
+			..Default::default()
+		}),
+		response: None,
+	};
+	assert!(Transformation::try_from_local_config(c, true).is_err());
+}
+
+#[test]
+fn test_transformation_cache_size() {
+	let c = super::LocalTransformationConfig {
+		request: Some(super::LocalTransform {
+			set: vec![(
+				strng::new("x-user"),
+				strng::new(r#""user-" + request.headers["x-custom-foo"]"#),
+			)],
+			cache_size: Some(8),
+			..Default::default()
+		}),
+		response: None,
+	};
+	let xfm = Transformation::try_from_local_config(c, true).unwrap();
+	assert!(xfm.request.set.iter().all(|(_, e)| e.is_result_cached()));
+
+	for foo in ["a", "b", "a"] {
+		let mut req = ::http::Request::builder()
+			.method("GET")
+			.uri("https://www.rust-lang.org/")
+			.header("X-Custom-Foo", foo)
+			.body(crate::http::Body::empty())
+			.unwrap();
+		xfm.apply_request(&mut req).unwrap();
+		assert_eq!(
+			req.headers().get("x-user").unwrap(),
+			format!("user-{foo}").as_str()
+		);
+	}
+}
@@ -50,7 +50,7 @@ pub mod util;
 use control::caclient;
 use telemetry::{metrics, trc};
 
-use crate::control::{AuthSource, RootCert};
+use crate::control::{AuthSource, ClientIdentity, RootCert};
 use crate::telemetry::trc::Protocol;
 use crate::types::agent::{ListenerTarget, PolicyTargetRef};
 use crate::types::local;
@@ -139,6 +139,36 @@ pub struct RawDnsConfig {
 	/// When `None`, the system-provided resolver setting is preserved.
 	/// Can also be set via the `DNS_EDNS0` environment variable.
 	edns0: Option<bool>,
+
+	/// How long a failed lookup is remembered before the hostname is queried again.
+	/// Defaults to 1s. Can also be set via the `DNS_NEGATIVE_TTL` environment variable.
+	#[serde(default, with = "serde_dur_option")]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	negative_ttl: Option<Duration>,
+
+	/// Resolve hostnames with DNS-over-HTTPS (RFC 8484) instead of the system resolver.
+	doh: Option<RawDohConfig>,
+
+	/// Include an EDNS Client Subnet (RFC 7871) derived from the downstream client address, so
+	/// geo-aware DNS servers can answer with nearby endpoints. Only supported with `doh`.
+	client_subnet: Option<RawClientSubnetConfig>,
+}
+
+#[apply(schema_de!)]
+pub struct RawDohConfig {
+	/// URL of the DNS-over-HTTPS endpoint, such as `https://dns.google/dns-query`.
+	url: String,
+	/// Fall back to the system resolver when the endpoint fails to answer. Defaults to false.
+	#[serde(default)]
+	fallback_to_system: bool,
+}
+
+#[apply(schema_de!)]
+pub struct RawClientSubnetConfig {
+	/// How many leading bits of an IPv4 client address are sent. Defaults to 24.
+	ipv4_prefix_length: Option<u8>,
+	/// How many leading bits of an IPv6 client address are sent. Defaults to 56.
+	ipv6_prefix_length: Option<u8>,
 }
 
 #[apply(schema_de!)]
@@ -161,12 +191,18 @@ pub struct RawConfig {
 
 	/// Address of the Certificate Authority used to issue SPIFFE certificates.
 	ca_address: Option<String>,
-	/// Authentication token for communicating with the Certificate Authority.
+	/// Authentication token file for communicating with the Certificate Authority, or a secret
+	/// reference such as `env://<VAR>` or `vault://<path>`.
 	ca_auth_token: Option<String>,
 	/// Address of the xDS control plane used for dynamic configuration.
 	xds_address: Option<String>,
-	/// Authentication token for communicating with the xDS control plane.
+	/// Authentication token file for communicating with the xDS control plane, or a secret
+	/// reference such as `env://<VAR>` or `vault://<path>`.
 	xds_auth_token: Option<String>,
+	/// Client certificate file presented to the xDS control plane for mutual TLS.
+	xds_client_cert: Option<String>,
+	/// Private key file for `xdsClientCert`.
+	xds_client_key: Option<String>,
 	/// Kubernetes namespace for this gateway instance.
 	namespace: Option<String>,
 	/// Name of this gateway. Required when xDS is configured.
@@ -208,6 +244,11 @@ pub struct RawConfig {
 	/// `  this.joined(prefix, parts...) { prefix + this + parts.join("") }`
 	#[serde(default)]
 	custom_functions: String,
+	/// Maximum time a single CEL expression evaluation may take. This is checked after each function
+	/// call and comprehension iteration, and an evaluation that runs over fails. Unset by default.
+	#[serde(default, with = "serde_dur_option")]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	cel_function_timeout: Option<Duration>,
 
 	/// Maximum time to wait for connections to close gracefully during shutdown.
 	#[serde(default, with = "serde_dur_option")]
@@ -386,6 +427,9 @@ pub struct RawTracing {
 	/// This should evaluate to either a float between 0.0-1.0 (0-100%) or true/false.
 	/// This defaults to 'true'.
 	client_sampling: Option<StringBoolFloat>,
+	/// Built-in sampler, as an alternative to `randomSampling` and `clientSampling`.
+	/// Cannot be combined with either of them.
+	sampler: Option<trc::Sampler>,
 	/// OTLP path. Default is /v1/traces
 	path: Option<String>,
 }
@@ -394,6 +438,11 @@ pub struct RawTracing {
 pub struct RawLogging {
 	/// CEL expression that selects which requests are logged.
 	filter: Option<String>,
+	/// CEL expression that samples which requests are logged, evaluated per request after `filter`.
+	/// This should evaluate to either a float between 0.0-1.0 (0-100%) or true/false.
+	/// For example, `response.code >= 500 ? true : 0.01` logs all server errors and 1% of other requests.
+	/// This defaults to 'true'.
+	sample: Option<StringBoolFloat>,
 	/// Custom fields to add to or remove from log entries.
 	fields: Option<RawLoggingFields>,
 	/// Log level: a single level (e.g. `info`), a comma-separated string of per-module levels (e.g. `info,agent_core=trace`), or a list of per-module levels (e.g. `[info, agent_core=trace]`).
@@ -451,6 +500,12 @@ pub struct RawLoggingFields {
 		schemars(with = "std::collections::HashMap<String, String>")
 	)]
 	add: IndexMap<String, String>,
+	/// Field names whose values are redacted, rather than removed, in log entries.
+	#[serde(default)]
+	redact: Vec<String>,
+	/// How redacted field values are rendered. Defaults to `mask`.
+	#[serde(default)]
+	redact_strategy: telemetry::log::RedactStrategy,
 }
 
 #[derive(Clone, Debug)]
@@ -695,6 +750,10 @@ pub struct XDSConfig {
 	pub address: Option<String>,
 	pub auth: AuthSource,
 	pub ca_cert: RootCert,
+	/// Client certificate to present to the XDS server, if it requires mutual TLS.
+	pub client_identity: Option<ClientIdentity>,
+	/// Backoff between attempts to reconnect to the XDS server.
+	pub backoff: agent_xds::Backoff,
 	pub namespace: Strng,
 	pub gateway: Strng,
 
@@ -6,7 +6,7 @@ use ::http::uri::{Authority, PathAndQuery};
 use ::http::{HeaderMap, HeaderName, HeaderValue, header};
 use agent_core::prelude::Strng;
 use agent_core::strng;
-pub use agent_llm::tokenizer::{num_tokens_from_messages, preload_tokenizers};
+pub use agent_llm::tokenizer::{Tokenize, Tokenizer, num_tokens_from_messages, preload_tokenizers};
 pub use agent_llm::{
 	AIError, CacheTokenConvention, ChatFormat, InputFormat, LLMInfo, LLMRequest, LLMRequestParams,
 	LLMResponse, PromptCachingConfig, Provider, ProviderState, RequestType, ResponseType, RouteType,
@@ -15,6 +15,7 @@ pub use agent_llm::{
 };
 use axum_extra::headers::authorization::Bearer;
 use headers::{ContentEncoding, HeaderMapExt};
+use http_body_util::BodyExt;
 pub use policy::Policy;
 use rand::RngExt;
 use serde::de::DeserializeOwned;
@@ -25,6 +26,7 @@ use crate::http::{Body, Request, Response};
 use crate::proxy::httpproxy::PolicyClient;
 use crate::store::{BackendPolicies, LLMResponsePolicies};
 use crate::telemetry::log::{AsyncLog, RequestLog};
+use crate::telemetry::metrics::{GenAIBodyLabels, Metrics};
 use crate::types::agent::{BackendTrafficPolicy, SimpleBackendReference, Target};
 use crate::types::loadbalancer::{ActiveHandle, EndpointWithInfo};
 use crate::*;
@@ -35,6 +37,7 @@ pub mod cost;
 pub mod policy;
 
 use policy::streaming_guardrails::GuardedSseBody;
+use policy::streaming_mask::MaskingSseBody;
 
 use crate::cel::{Executor, LLMContext, RequestSnapshot};
 use crate::proxy::dtrace;
@@ -86,6 +89,26 @@ impl AIBackend {
 		let handle = self.providers.start_request(ep.name.clone(), ep_info);
 		Some((ep, handle))
 	}
+
+	/// Selects the provider named by `fallback`, even if it is currently evicted, applying the
+	/// fallback's model override if one is set.
+	pub fn select_fallback(
+		&self,
+		fallback: &policy::ModelFallback,
+	) -> Option<(Arc<NamedAIProvider>, ActiveHandle)> {
+		let (ep, ep_info) = self.providers.find_endpoint(|ep, info| {
+			(ep.name == fallback.provider).then(|| (ep.clone(), info.clone()))
+		})?;
+		let handle = self.providers.start_request(ep.name.clone(), &ep_info);
+		let ep = match &fallback.model {
+			Some(model) => Arc::new(NamedAIProvider {
+				provider: ep.provider.with_model(model.clone()),
+				..(*ep).clone()
+			}),
+			None => ep,
+		};
+		Some((ep, handle))
+	}
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -103,10 +126,35 @@ pub struct NamedAIProvider {
 	/// This comes with the cost of an expensive operation.
 	#[serde(default)]
 	pub tokenize: bool,
+	/// Explicit tokenizer to use when counting tokens, overriding the one inferred from the model.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub tokenizer: Option<Tokenizer>,
+	/// Maximum size, in bytes, of a buffered response from this provider. Overrides the default
+	/// response buffer limit, for providers that return large non-streaming responses.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub response_buffer_limit: Option<usize>,
+	/// TLS settings presenting a client certificate to the provider's default endpoint, for
+	/// providers that require mutual TLS. Used in place of the system trust defaults.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub client_tls: Option<http::backendtls::BackendTLS>,
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub inline_policies: Vec<BackendTrafficPolicy>,
 }
 
+impl NamedAIProvider {
+	pub fn tokenization(&self) -> Tokenize {
+		Tokenize::new(self.tokenize, self.tokenizer)
+	}
+
+	/// Applies this provider's response buffer limit override, if any, so it is used when the
+	/// response is buffered in `process_response`.
+	pub fn apply_response_buffer_limit(&self, resp: &mut Response) {
+		if let Some(limit) = self.response_buffer_limit {
+			resp.extensions_mut().insert(http::BufferLimit::new(limit));
+		}
+	}
+}
+
 #[apply(schema!)]
 pub enum AIProvider {
 	OpenAI(openai::Provider),
@@ -124,6 +172,10 @@ pub enum AIProvider {
 pub struct BedrockProvider {
 	#[serde(flatten)]
 	pub provider: bedrock::Provider,
+	/// Bedrock `requestMetadata` entries computed from CEL expressions evaluated against the
+	/// request. These take precedence over keys supplied through the `x-bedrock-metadata` header.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub metadata_expressions: HashMap<String, Arc<cel::Expression>>,
 	#[serde(skip)]
 	pub source_credentials_cache: crate::http::auth::aws::AwsCredentialsCache,
 	#[serde(skip)]
@@ -134,10 +186,36 @@ impl BedrockProvider {
 	pub fn new(provider: bedrock::Provider) -> Self {
 		Self {
 			provider,
+			metadata_expressions: Default::default(),
 			source_credentials_cache: Default::default(),
 			assume_role_cache: Default::default(),
 		}
 	}
+
+	pub fn expressions(&self) -> impl Iterator<Item = &cel::Expression> {
+		self.metadata_expressions.values().map(AsRef::as_ref)
+	}
+
+	/// Evaluates `metadata_expressions` against the request. Like `x-bedrock-metadata`, only
+	/// string, number, and boolean results are forwarded; anything else is skipped.
+	fn eval_request_metadata(&self, req: &Request) -> HashMap<String, String> {
+		let exec = cel::Executor::new_request(req);
+		self
+			.metadata_expressions
+			.iter()
+			.filter_map(|(k, expr)| {
+				let value = match exec.eval(expr).ok()?.json().ok()? {
+					serde_json::Value::String(s) => s,
+					v @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_)) => v.to_string(),
+					_ => {
+						debug!("skipping non-scalar bedrock metadata {k:?}");
+						return None;
+					},
+				};
+				Some((k.clone(), value))
+			})
+			.collect()
+	}
 }
 
 #[cfg(feature = "schema")]
@@ -147,7 +225,18 @@ impl schemars::JsonSchema for BedrockProvider {
 	}
 
 	fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
-		<bedrock::Provider as schemars::JsonSchema>::json_schema(generator)
+		let mut schema = <bedrock::Provider as schemars::JsonSchema>::json_schema(generator);
+		let metadata_expressions = generator.subschema_for::<HashMap<String, cel::Expression>>();
+		if let Some(props) = schema
+			.get_mut("properties")
+			.and_then(serde_json::Value::as_object_mut)
+		{
+			props.insert(
+				"metadataExpressions".to_string(),
+				metadata_expressions.to_value(),
+			);
+		}
+		schema
 	}
 }
 
@@ -272,6 +361,7 @@ struct ChatRequestContext<'a> {
 	provider: &'a AIProvider,
 	headers: &'a HeaderMap,
 	prompt_caching: Option<&'a policy::PromptCachingConfig>,
+	bedrock_metadata: Option<&'a HashMap<String, String>>,
 }
 
 // Context provider to each response translation
@@ -299,6 +389,8 @@ const CHAT_TRANSLATIONS: &[ChatTranslation] = {
 		ChatTranslation { input, output }
 	}
 	&[
+		// Native provider APIs, preferred over their OpenAI-compatible shims
+		chat(InputFormat::Completions, ChatFormat::GeminiGenerateContent),
 		// Direct passthrough
 		chat(InputFormat::Responses, ChatFormat::OpenAIResponses),
 		chat(InputFormat::Completions, ChatFormat::OpenAICompletions),
@@ -349,6 +441,15 @@ fn render_anthropic_messages(req: types::ChatRequest<'_>) -> Result<Vec<u8>, AIE
 	}
 }
 
+fn render_gemini_generate_content(req: types::ChatRequest<'_>) -> Result<Vec<u8>, AIError> {
+	match req {
+		types::ChatRequest::Completions(req) => conversion::gemini::from_completions::translate(req),
+		_ => Err(AIError::UnsupportedConversion(strng::literal!(
+			"expected completions request"
+		))),
+	}
+}
+
 fn render_bedrock_converse(
 	req: types::ChatRequest<'_>,
 	ctx: &ChatRequestContext<'_>,
@@ -363,15 +464,20 @@ fn render_bedrock_converse(
 			req,
 			provider,
 			Some(ctx.headers),
+			ctx.bedrock_metadata,
 			ctx.prompt_caching,
 		),
-		types::ChatRequest::Messages(req) => {
-			conversion::bedrock::from_messages::translate(req, provider, Some(ctx.headers))
-		},
+		types::ChatRequest::Messages(req) => conversion::bedrock::from_messages::translate(
+			req,
+			provider,
+			Some(ctx.headers),
+			ctx.bedrock_metadata,
+		),
 		types::ChatRequest::Responses(req) => conversion::bedrock::from_responses::translate(
 			req,
 			provider,
 			Some(ctx.headers),
+			ctx.bedrock_metadata,
 			ctx.prompt_caching,
 		),
 	}?;
@@ -403,6 +509,7 @@ impl ChatTranslation {
 				InputFormat::Responses => custom::ProviderFormat::Responses,
 				_ => unreachable!("chat translation selected for non-chat input"),
 			},
+			ChatFormat::GeminiGenerateContent => custom::ProviderFormat::Completions,
 		}
 	}
 
@@ -419,6 +526,7 @@ impl ChatTranslation {
 			},
 			ChatFormat::AnthropicMessages => render_anthropic_messages(req),
 			ChatFormat::BedrockConverse => return render_bedrock_converse(req, ctx),
+			ChatFormat::GeminiGenerateContent => render_gemini_generate_content(req),
 		}?;
 		Ok(RenderedChatRequest {
 			body,
@@ -487,6 +595,16 @@ impl ChatTranslation {
 					self.input
 				))),
 			},
+			ChatFormat::GeminiGenerateContent => match self.input {
+				InputFormat::Completions => {
+					conversion::gemini::from_completions::translate_response(bytes, ctx.model)
+				},
+				_ => Err(AIError::UnsupportedConversion(strng::format!(
+					"from {:?} to {:?}",
+					self.output,
+					self.input
+				))),
+			},
 		}
 	}
 
@@ -580,6 +698,18 @@ impl ChatTranslation {
 				},
 				_ => resp,
 			},
+
+			ChatFormat::GeminiGenerateContent => match self.input {
+				InputFormat::Completions => resp.map(|b| {
+					conversion::gemini::from_completions::translate_stream(
+						b,
+						ctx.buffer_limit,
+						ctx.logger,
+						&ctx.model,
+					)
+				}),
+				_ => resp,
+			},
 		}
 	}
 
@@ -656,6 +786,14 @@ impl ChatTranslation {
 				},
 				_ => unsupported(),
 			},
+
+			ChatFormat::GeminiGenerateContent => match format {
+				ChatErrorFormat::Google => match self.input {
+					InputFormat::Completions => conversion::gemini::from_completions::translate_error(bytes),
+					_ => unsupported(),
+				},
+				_ => unsupported(),
+			},
 		}
 	}
 }
@@ -719,6 +857,23 @@ impl AIProvider {
 		}
 	}
 
+	/// Returns a copy of this provider that overrides the request model with `model`.
+	pub fn with_model(&self, model: Strng) -> AIProvider {
+		let mut provider = self.clone();
+		let slot = match &mut provider {
+			AIProvider::OpenAI(p) => &mut p.model,
+			AIProvider::Anthropic(p) => &mut p.model,
+			AIProvider::Gemini(p) => &mut p.model,
+			AIProvider::Vertex(p) => &mut p.model,
+			AIProvider::Bedrock(p) => &mut p.model,
+			AIProvider::Azure(p) => &mut p.model,
+			AIProvider::Copilot(p) => &mut p.model,
+			AIProvider::Custom(p) => &mut p.model,
+		};
+		*slot = Some(model);
+		provider
+	}
+
 	pub fn supported_formats(&self, request_model: Option<&str>) -> Vec<custom::ProviderFormat> {
 		use custom::ProviderFormat::*;
 		match self {
@@ -779,13 +934,21 @@ impl AIProvider {
 			},
 			AIProvider::Azure(_) => vec![ChatFormat::OpenAIResponses, ChatFormat::OpenAICompletions],
 
-			AIProvider::Gemini(_) => vec![ChatFormat::OpenAICompletions],
+			// Completions use the native API; other chat inputs go through the OpenAI-compatible shim.
+			AIProvider::Gemini(_) => vec![
+				ChatFormat::GeminiGenerateContent,
+				ChatFormat::OpenAICompletions,
+			],
 			AIProvider::Anthropic(_) => vec![ChatFormat::AnthropicMessages],
 			AIProvider::Bedrock(_) => vec![ChatFormat::BedrockConverse],
 
 			AIProvider::Vertex(p) if p.is_anthropic_model(request_model) => {
 				vec![ChatFormat::AnthropicMessages]
 			},
+			AIProvider::Vertex(p) if p.is_google_model(request_model) => vec![
+				ChatFormat::GeminiGenerateContent,
+				ChatFormat::OpenAICompletions,
+			],
 			AIProvider::Vertex(_) => vec![ChatFormat::OpenAICompletions],
 
 			AIProvider::Custom(p) => p
@@ -808,6 +971,7 @@ impl AIProvider {
 	) -> ChatErrorFormat {
 		match (self, translation.output) {
 			(AIProvider::Gemini(_), ChatFormat::OpenAICompletions) => ChatErrorFormat::Google,
+			(_, ChatFormat::GeminiGenerateContent) => ChatErrorFormat::Google,
 			(AIProvider::Vertex(p), ChatFormat::OpenAICompletions)
 				if !p.is_anthropic_model(request_model) =>
 			{
@@ -819,6 +983,17 @@ impl AIProvider {
 		}
 	}
 
+	/// The wire format a chat request is sent upstream in, or None for non-chat requests.
+	fn upstream_chat_format(&self, req: &LLMRequest) -> Option<ChatFormat> {
+		if !req.input_format.is_chat() {
+			return None;
+		}
+		self
+			.chat_translation(req.input_format, Some(req.request_model.as_str()))
+			.ok()
+			.map(|t| t.output)
+	}
+
 	fn chat_translation(
 		&self,
 		input_format: InputFormat,
@@ -871,9 +1046,16 @@ impl AIProvider {
 	/// [`Self::default_connector_target`] so callers can compute effective policies, resolve the LLM
 	/// route from them, and only then pick the connection target. Returns `None` for custom providers,
 	/// which require an explicit host override or provider backend.
-	pub fn default_connector_policies(&self) -> Option<BackendPolicies> {
+	pub fn default_connector_policies(
+		&self,
+		client_tls: Option<&http::backendtls::BackendTLS>,
+	) -> Option<BackendPolicies> {
 		let btls = BackendPolicies {
-			backend_tls: Some(http::backendtls::SYSTEM_TRUST.clone()),
+			backend_tls: Some(
+				client_tls
+					.cloned()
+					.unwrap_or_else(|| http::backendtls::SYSTEM_TRUST.clone()),
+			),
 			// We will use original request for now
 			..Default::default()
 		};
@@ -890,6 +1072,7 @@ impl AIProvider {
 			AIProvider::Bedrock(p) => BackendPolicies {
 				backend_auth: Some(BackendAuth::Aws(AwsAuth::Implicit {
 					service_name: None,
+					region: None,
 					assume_role: None,
 					source_credentials_cache: p.source_credentials_cache.clone(),
 					assume_role_cache: p.assume_role_cache.clone(),
@@ -1056,7 +1239,14 @@ impl AIProvider {
 			}),
 			AIProvider::Gemini(_) => http::modify_req(req, |req| {
 				http::modify_uri(req, |uri| {
-					let path = Self::with_path_prefix(gemini::path(route_type), path_prefix);
+					let path = match llm_request {
+						// The native API puts the model in the path.
+						Some(l) if self.upstream_chat_format(l) == Some(ChatFormat::GeminiGenerateContent) => {
+							gemini::native_path(l.request_model.as_str(), l.streaming)
+						},
+						_ => strng::new(gemini::path(route_type)),
+					};
+					let path = Self::with_path_prefix(&path, path_prefix);
 					Self::set_path_and_query(uri, &path)?;
 					Ok(())
 				})?;
@@ -1067,7 +1257,17 @@ impl AIProvider {
 				let streaming = llm_request.map(|l| l.streaming).unwrap_or(false);
 				http::modify_req(req, |req| {
 					http::modify_uri(req, |uri| {
-						let path = provider.get_path_for_model(route_type, request_model, streaming);
+						let path = match llm_request {
+							// Chat inputs without a native translation use the OpenAI-compatible endpoint,
+							// even for publisher models that generateContent would otherwise serve.
+							Some(l)
+								if route_type == RouteType::Completions
+									&& self.upstream_chat_format(l) == Some(ChatFormat::OpenAICompletions) =>
+							{
+								provider.openai_compat_path()
+							},
+							_ => provider.get_path_for_model(route_type, request_model, streaming),
+						};
 						let path = Self::with_path_prefix(&path, path_prefix);
 						Self::set_path_and_query(uri, &path)?;
 						Ok(())
@@ -1252,21 +1452,28 @@ impl AIProvider {
 		backend_info: &crate::http::auth::BackendInfo,
 		policies: Option<&Policy>,
 		req: Request,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<RequestResult, AIError> {
 		let (parts, mut req) = self
-			.read_body_and_default_model::<types::completions::Request>(policies, req, log)
+			.read_body_and_default_model::<types::completions::Request>(
+				policies,
+				InputFormat::Completions,
+				req,
+				log,
+			)
 			.await?;
 		self.apply_model_alias(policies, &mut req);
 
 		// If a user doesn't request usage, we will not get token information which we need
-		// We always set it.
-		// TODO?: this may impact the user, if they make assumptions about the stream NOT including usage.
+		// We set it unless the policy opts out with `forceStreamUsage: false`.
+		// This may impact the user, if they make assumptions about the stream NOT including usage.
 		// Notably, this adds a final SSE event.
-		// We could actually go remove that on the response, but it would mean we cannot do passthrough-parsing,
-		// so unless we have a compelling use case for it, for now we keep it.
-		if req.stream.unwrap_or_default() && req.stream_options.is_none() {
+		// We could actually go remove that on the response, but it would mean we cannot do passthrough-parsing.
+		if req.stream.unwrap_or_default()
+			&& req.stream_options.is_none()
+			&& policies.is_none_or(Policy::force_stream_usage)
+		{
 			req.stream_options = Some(types::completions::StreamOptions {
 				include_usage: true,
 				rest: Default::default(),
@@ -1297,11 +1504,16 @@ impl AIProvider {
 		backend_info: &crate::http::auth::BackendInfo,
 		policies: Option<&Policy>,
 		req: Request,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<RequestResult, AIError> {
 		let (parts, mut req) = self
-			.read_body_and_default_model::<types::messages::Request>(policies, req, log)
+			.read_body_and_default_model::<types::messages::Request>(
+				policies,
+				InputFormat::Messages,
+				req,
+				log,
+			)
 			.await?;
 		self.apply_model_alias(policies, &mut req);
 
@@ -1324,11 +1536,16 @@ impl AIProvider {
 		backend_info: &crate::http::auth::BackendInfo,
 		policies: Option<&Policy>,
 		req: Request,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<RequestResult, AIError> {
 		let (parts, mut req) = self
-			.read_body_and_default_model::<types::embeddings::Request>(policies, req, log)
+			.read_body_and_default_model::<types::embeddings::Request>(
+				policies,
+				InputFormat::Embeddings,
+				req,
+				log,
+			)
 			.await?;
 		self.apply_model_alias(policies, &mut req);
 
@@ -1351,11 +1568,16 @@ impl AIProvider {
 		backend_info: &crate::http::auth::BackendInfo,
 		policies: Option<&Policy>,
 		req: Request,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<RequestResult, AIError> {
 		let (parts, mut req) = self
-			.read_body_and_default_model::<types::rerank::Request>(policies, req, log)
+			.read_body_and_default_model::<types::rerank::Request>(
+				policies,
+				InputFormat::Rerank,
+				req,
+				log,
+			)
 			.await?;
 		self.apply_model_alias(policies, &mut req);
 
@@ -1378,11 +1600,16 @@ impl AIProvider {
 		backend_info: &crate::http::auth::BackendInfo,
 		policies: Option<&Policy>,
 		req: Request,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<RequestResult, AIError> {
 		let (mut parts, mut req) = self
-			.read_body_and_default_model::<types::responses::Request>(policies, req, log)
+			.read_body_and_default_model::<types::responses::Request>(
+				policies,
+				InputFormat::Responses,
+				req,
+				log,
+			)
 			.await?;
 		self.apply_model_alias(policies, &mut req);
 
@@ -1411,10 +1638,16 @@ impl AIProvider {
 		backend_info: &crate::http::auth::BackendInfo,
 		req: Request,
 		policies: Option<&Policy>,
+		tokenizer: Option<Tokenizer>,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<RequestResult, AIError> {
 		let (parts, mut req) = self
-			.read_body_and_default_model::<types::count_tokens::Request>(policies, req, log)
+			.read_body_and_default_model::<types::count_tokens::Request>(
+				policies,
+				InputFormat::CountTokens,
+				req,
+				log,
+			)
 			.await?;
 		self.apply_model_alias(policies, &mut req);
 
@@ -1429,7 +1662,7 @@ impl AIProvider {
 		if use_local {
 			let messages = req.get_messages();
 			let model = req.model.as_deref().unwrap_or_default();
-			let count = num_tokens_from_messages(model, &messages)?;
+			let count = num_tokens_from_messages(model, tokenizer, &messages)?;
 			let body = serde_json::to_vec(&types::count_tokens::Response {
 				input_tokens: count,
 			})
@@ -1449,7 +1682,7 @@ impl AIProvider {
 				InputFormat::CountTokens,
 				req,
 				parts,
-				false,
+				Tokenize::Disabled,
 				log,
 				|provider, req, parts, request_model| {
 					provider.render_count_tokens_request(req, &parts.headers, request_model)
@@ -1478,6 +1711,7 @@ impl AIProvider {
 		let Ok(bytes) = http::read_body_with_limit(body, buffer).await else {
 			return Err(AIError::RequestTooLarge);
 		};
+		self.record_request_body_size(InputFormat::Detect, bytes.len(), log);
 
 		let req = if is_json {
 			if let Some(p) = policies
@@ -1499,7 +1733,7 @@ impl AIProvider {
 				InputFormat::Detect,
 				req,
 				parts,
-				false,
+				Tokenize::Disabled,
 				log,
 				|_, req, _, _| match req {
 					types::detect::Request::Raw(bytes) => Ok(bytes.to_vec()),
@@ -1590,17 +1824,17 @@ impl AIProvider {
 		req: &mut impl RequestType,
 		parts: &mut Parts,
 		provider_format: Option<custom::ProviderFormat>,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<PreparedRequest, AIError> {
+		req.normalize_roles()?;
 		if let Some(p) = policies {
 			p.apply_prompt_enrichment(req);
 
 			if original_format.supports_prompt_guard() {
-				let http_headers = &parts.headers;
 				let claims = parts.extensions.get::<Claims>().cloned();
 				if let Some(dr) = p
-					.apply_prompt_guard(backend_info, req, http_headers, claims)
+					.apply_prompt_guard(backend_info, req, &mut parts.headers, claims, log)
 					.await
 					.map_err(|e| {
 						warn!("failed to call prompt guard webhook: {e}");
@@ -1635,7 +1869,7 @@ impl AIProvider {
 		original_format: InputFormat,
 		mut req: T,
 		mut parts: Parts,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 		chat_request: F,
 	) -> Result<RequestResult, AIError>
@@ -1667,12 +1901,23 @@ impl AIProvider {
 			PreparedRequest::Rejected(resp) => return Ok(RequestResult::Rejected(resp)),
 		};
 
+		let bedrock_metadata = match self {
+			AIProvider::Bedrock(p) if !p.metadata_expressions.is_empty() => {
+				// Expressions only read the request head; the body is already split off here.
+				let head = Request::from_parts(parts, Body::empty());
+				let metadata = p.eval_request_metadata(&head);
+				parts = head.into_parts().0;
+				Some(metadata)
+			},
+			_ => None,
+		};
 		let rendered = chat_translation.render_request(
 			chat_request(&req),
 			&ChatRequestContext {
 				provider: self,
 				headers: &parts.headers,
 				prompt_caching: policies.and_then(|p| p.prompt_caching.as_ref()),
+				bedrock_metadata: bedrock_metadata.as_ref(),
 			},
 		)?;
 		llm_info.provider_state = rendered.provider_state;
@@ -1693,7 +1938,7 @@ impl AIProvider {
 		original_format: InputFormat,
 		mut req: T,
 		mut parts: Parts,
-		tokenize: bool,
+		tokenize: Tokenize,
 		log: &mut Option<&mut RequestLog>,
 		render: F,
 	) -> Result<RequestResult, AIError>
@@ -1758,6 +2003,7 @@ impl AIProvider {
 		log: AsyncLog<llm::LLMInfo>,
 		include_completion_in_log: bool,
 		model_catalog: Option<&Arc<cost::ModelCatalog>>,
+		metrics: Option<&Metrics>,
 		resp: Response,
 	) -> Result<Response, AIError> {
 		// Non-success responses are plain JSON, not event-stream data.
@@ -1772,12 +2018,19 @@ impl AIProvider {
 				log,
 				include_completion_in_log,
 				model_catalog.cloned(),
+				metrics,
 				resp,
 			);
 		}
 		let model_catalog = model_catalog.map(Arc::as_ref);
 
 		let buffered = Self::buffer_response(resp).await?;
+		if let Some(metrics) = metrics {
+			metrics
+				.gen_ai_response_body_bytes
+				.get_or_create(&self.body_size_labels(req.input_format))
+				.observe(buffered.bytes.len() as f64);
+		}
 
 		match req.input_format {
 			InputFormat::CountTokens => {
@@ -1874,7 +2127,10 @@ impl AIProvider {
 			));
 		let resp = Response::from_parts(parts, body);
 
-		if !rate_limit.local_rate_limit.is_empty() || rate_limit.remote_rate_limit.is_some() {
+		if !rate_limit.local_rate_limit.is_empty()
+			|| rate_limit.remote_rate_limit.is_some()
+			|| rate_limit.redis_rate_limit.is_some()
+		{
 			let exec = cel::Executor::new_response(req_snapshot.as_deref(), &resp);
 			// In the initial request, we subtracted the approximate request tokens.
 			// Now we should have the real request tokens and the response tokens
@@ -2136,6 +2392,7 @@ impl AIProvider {
 		log: AsyncLog<llm::LLMInfo>,
 		include_completion_in_log: bool,
 		model_catalog: Option<Arc<cost::ModelCatalog>>,
+		metrics: Option<&Metrics>,
 		resp: Response,
 	) -> Result<Response, AIError> {
 		let model = req.request_model.clone();
@@ -2160,6 +2417,25 @@ impl AIProvider {
 		let ce = parts.headers.typed_get::<ContentEncoding>();
 		let (body, decompressed_encoding) = http::compression::decompress_body(body, ce.as_ref())
 			.map_err(|e| map_compression_error(e, &parts.headers))?;
+		// Like the buffered path, measure the decompressed body; the size is recorded once the stream ends.
+		let body = match metrics {
+			Some(metrics) => {
+				let mut size = StreamedBodySize {
+					histogram: metrics
+						.gen_ai_response_body_bytes
+						.get_or_create(&self.body_size_labels(input_format))
+						.clone(),
+					len: 0,
+				};
+				Body::new(body.map_frame(move |frame| {
+					if let Some(data) = frame.data_ref() {
+						size.len += data.len();
+					}
+					frame
+				}))
+			},
+			None => body,
+		};
 
 		// Strip encoding headers after successful decompression
 		if decompressed_encoding.is_some() {
@@ -2185,19 +2461,34 @@ impl AIProvider {
 		// SSE output, not raw upstream bytes. Applying them before translation silently
 		// breaks Bedrock (AWS Event Stream is binary, not SSE) and any provider whose
 		// wire format differs from SSE. Detect paths are raw pass-throughs; skip them.
-		let evaluators = if response_policies.streaming_prompt_guard_enabled
+		// Regex guards that mask cannot be expressed as a pass/block window evaluation; they
+		// rewrite the streamed text deltas instead.
+		let (mask_rules, evaluators) = if response_policies.streaming_prompt_guard_enabled
 			&& !response_policies.prompt_guard.is_empty()
 			&& !matches!(input_format, InputFormat::Detect)
 		{
 			use policy::PromptGuard;
+			let mut mask_rules = Vec::new();
+			let mut response = Vec::new();
+			for g in &response_policies.prompt_guard {
+				match &g.kind {
+					policy::ResponseGuardKind::Regex(r) if matches!(r.action, policy::Action::Mask) => {
+						mask_rules.push(r.clone())
+					},
+					_ => response.push(g.clone()),
+				}
+			}
 			let temp_guard = PromptGuard {
 				streaming: policy::PromptGuardStreamingMode::Enabled,
 				request: vec![],
-				response: response_policies.prompt_guard.clone(),
+				response,
 			};
-			temp_guard.begin_streaming_response_guard(&client, &prompt_guard_headers)
+			(
+				mask_rules,
+				temp_guard.begin_streaming_response_guard(&client, &prompt_guard_headers),
+			)
 		} else {
-			vec![]
+			(vec![], vec![])
 		};
 
 		let logger = AmendOnDrop::new(log, response_policies, req_snapshot, model_catalog).into_llm();
@@ -2245,6 +2536,11 @@ impl AIProvider {
 			}
 		};
 
+		let translated = if mask_rules.is_empty() {
+			translated
+		} else {
+			translated.map(|b| MaskingSseBody::new(b, mask_rules, buffer))
+		};
 		if !evaluators.is_empty() {
 			// `logger` is owned by the translated body; pass None to avoid double-logging.
 			return Ok(translated.map(|b| GuardedSseBody::new(b, evaluators, buffer, None)));
@@ -2255,6 +2551,7 @@ impl AIProvider {
 	async fn read_body_and_default_model<T: RequestType + DeserializeOwned>(
 		&self,
 		policies: Option<&Policy>,
+		input_format: InputFormat,
 		hreq: Request,
 		log: &mut Option<&mut RequestLog>,
 	) -> Result<(Parts, T), AIError> {
@@ -2279,6 +2576,7 @@ impl AIProvider {
 			parts.headers.remove(header::CONTENT_ENCODING);
 			parts.headers.remove(header::TRANSFER_ENCODING);
 		}
+		self.record_request_body_size(input_format, bytes.len(), log);
 
 		if self.override_model().is_none()
 			&& types::detect::extract_model_from_path(parts.uri.path()).is_none()
@@ -2306,6 +2604,28 @@ impl AIProvider {
 		Ok((parts, req))
 	}
 
+	fn body_size_labels(&self, input_format: InputFormat) -> GenAIBodyLabels {
+		GenAIBodyLabels {
+			gen_ai_system: self.provider().into(),
+			gen_ai_input_format: Some(input_format).into(),
+		}
+	}
+
+	fn record_request_body_size(
+		&self,
+		input_format: InputFormat,
+		len: usize,
+		log: &Option<&mut RequestLog>,
+	) {
+		if let Some(log) = log.as_deref() {
+			log
+				.metrics
+				.gen_ai_request_body_bytes
+				.get_or_create(&self.body_size_labels(input_format))
+				.observe(len as f64);
+		}
+	}
+
 	fn set_provider_request_model(
 		&self,
 		parts: &Parts,
@@ -2424,6 +2744,19 @@ fn map_compression_error(e: http::compression::Error, headers: &::http::HeaderMa
 	}
 }
 
+/// StreamedBodySize counts the bytes of a streaming response and observes the total when the body
+/// is dropped, which happens once the stream completes (or the client goes away).
+struct StreamedBodySize {
+	histogram: prometheus_client::metrics::histogram::Histogram,
+	len: usize,
+}
+
+impl Drop for StreamedBodySize {
+	fn drop(&mut self) {
+		self.histogram.observe(self.len as f64);
+	}
+}
+
 fn response_prompt_guard_headers(
 	response_headers: &HeaderMap,
 	request_traceparent: Option<&HeaderValue>,
@@ -2456,6 +2789,9 @@ fn amend_tokens(rate_limit: store::LLMResponsePolicies, llm_resp: &LLMInfo, exec
 	if let Some(rrl) = rate_limit.remote_rate_limit {
 		rrl.amend_tokens(tokens_to_remove, &exec)
 	}
+	if let Some(rrl) = rate_limit.redis_rate_limit {
+		rrl.amend_tokens(tokens_to_remove)
+	}
 }
 
 pub struct AmendOnDrop {
@@ -2484,7 +2820,9 @@ impl AmendOnDrop {
 	}
 	pub fn report_usage(&mut self) {
 		if let Some(pol) = self.pol.take()
-			&& (!pol.local_rate_limit.is_empty() || pol.remote_rate_limit.is_some())
+			&& (!pol.local_rate_limit.is_empty()
+				|| pol.remote_rate_limit.is_some()
+				|| pol.redis_rate_limit.is_some())
 		{
 			self.log.non_atomic_mutate(|r| {
 				let ctx = LLMContext::from_llm_info(r.clone(), self.catalog.as_deref());
@@ -122,6 +122,7 @@ impl BedrockGuardrails {
 		pols.push(BackendTrafficPolicy::BackendAuth(BackendAuth::Aws(
 			AwsAuth::Implicit {
 				service_name: None,
+				region: None,
 				assume_role: None,
 				source_credentials_cache: Default::default(),
 				assume_role_cache: Default::default(),
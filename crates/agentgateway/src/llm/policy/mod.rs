@@ -12,6 +12,7 @@ use crate::llm::policy::webhook::{MaskActionBody, RequestAction, ResponseAction}
 use crate::llm::{AIError, RequestType, ResponseType};
 use crate::proxy::httpproxy::PolicyClient;
 use crate::telemetry::log::RequestLog;
+use crate::telemetry::metrics::{GuardrailAction, GuardrailKind, GuardrailLabels, GuardrailPhase};
 use crate::types::agent::{BackendTrafficPolicy, HeaderMatch, SimpleBackendReference};
 use crate::*;
 
@@ -30,6 +31,7 @@ mod google_model_armor;
 mod moderation;
 mod pii;
 pub mod streaming_guardrails;
+pub mod streaming_mask;
 #[cfg(test)]
 #[path = "tests.rs"]
 mod tests;
@@ -152,6 +154,31 @@ pub struct Policy {
 		schemars(with = "std::collections::HashMap<String, crate::llm::RouteType>")
 	)]
 	pub routes: SortedRoutes,
+	/// Ordered providers to re-issue a non-streaming request to when the upstream responds with
+	/// a retryable status (429 or 5xx).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub fallbacks: Vec<ModelFallback>,
+	/// Whether to request usage in streaming chat completions that do not set `stream_options`,
+	/// so token usage can be recorded. This adds a final usage event to the stream.
+	/// Defaults to true; when disabled, token accounting for such streams may be less accurate.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub force_stream_usage: Option<bool>,
+}
+
+/// A provider (and optionally a model on it) to fall back to when an LLM request fails.
+#[apply(schema!)]
+pub struct ModelFallback {
+	/// Name of the provider within the backend.
+	pub provider: Strng,
+	/// Model to request from the provider. Defaults to the provider's configured model.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub model: Option<Strng>,
+}
+
+impl Policy {
+	pub fn force_stream_usage(&self) -> bool {
+		self.force_stream_usage.unwrap_or(true)
+	}
 }
 
 impl crate::store::HasExpressions for Policy {
@@ -207,10 +234,18 @@ pub use agent_llm::PromptCachingConfig;
 #[apply(schema!)]
 pub struct PromptEnrichment {
 	/// Messages appended to the end of each chat request.
-	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	#[serde(
+		default,
+		skip_serializing_if = "Vec::is_empty",
+		deserialize_with = "crate::llm::types::deserialize_messages"
+	)]
 	pub append: Vec<crate::llm::SimpleChatCompletionMessage>,
 	/// Messages prepended to the beginning of each chat request.
-	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	#[serde(
+		default,
+		skip_serializing_if = "Vec::is_empty",
+		deserialize_with = "crate::llm::types::deserialize_messages"
+	)]
 	pub prepend: Vec<crate::llm::SimpleChatCompletionMessage>,
 }
 
@@ -256,6 +291,63 @@ enum GuardrailOutcome {
 	/// Guard service was unreachable and `failure_mode = FailOpen`; request is allowed
 	/// through but must be recorded as `FailOpen`, not `Allow`.
 	FailOpen,
+	/// Guard is in `audit` mode and detected something; the request is left untouched.
+	Audited(Vec<String>),
+}
+
+impl GuardrailOutcome {
+	fn audited(mut findings: Vec<String>) -> Self {
+		if findings.is_empty() {
+			return GuardrailOutcome::None;
+		}
+		findings.sort_unstable();
+		findings.dedup();
+		GuardrailOutcome::Audited(findings)
+	}
+
+	fn action(&self) -> GuardrailAction {
+		match self {
+			GuardrailOutcome::None => GuardrailAction::Allow,
+			GuardrailOutcome::Masked => GuardrailAction::Mask,
+			GuardrailOutcome::Rejected(_) => GuardrailAction::Reject,
+			GuardrailOutcome::FailOpen => GuardrailAction::FailOpen,
+			GuardrailOutcome::Audited(_) => GuardrailAction::Audit,
+		}
+	}
+}
+
+/// The outcome of a single guard, along with what is needed to record it.
+struct GuardrailCheck {
+	kind: GuardrailKind,
+	/// Entity types the guard detected, for guards that report them.
+	entity_types: Vec<String>,
+	outcome: GuardrailOutcome,
+}
+
+impl GuardrailCheck {
+	fn new(kind: GuardrailKind, outcome: GuardrailOutcome) -> Self {
+		GuardrailCheck {
+			kind,
+			entity_types: Vec::new(),
+			outcome,
+		}
+	}
+
+	/// Records the check in `guardrail_checks`. A check that detected several entity types is
+	/// counted once per type.
+	fn record(&self, client: &PolicyClient, phase: GuardrailPhase) {
+		let action = self.outcome.action();
+		let detected = match &self.outcome {
+			GuardrailOutcome::Audited(findings) => findings,
+			_ => &self.entity_types,
+		};
+		if detected.is_empty() {
+			Policy::record_guardrail_check(client, phase, self.kind, action, None);
+		}
+		for entity_type in detected.iter().unique() {
+			Policy::record_guardrail_check(client, phase, self.kind, action, Some(entity_type));
+		}
+	}
 }
 
 /// A streaming guardrail evaluator. Each guard kind gets one stateless implementation
@@ -375,64 +467,51 @@ impl PromptGuard {
 		text: &str,
 		client: &crate::proxy::httpproxy::PolicyClient,
 	) -> Option<Bytes> {
-		let headers = ::http::HeaderMap::new();
+		let mut headers = ::http::HeaderMap::new();
 		let mut req = TextRequest {
 			content: text.to_string(),
 		};
 		for g in &self.request {
-			match Policy::apply_single_request_guard(g, &mut req, &headers, client, None).await {
-				Ok(GuardrailOutcome::Rejected(rejected)) => {
-					Policy::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::Reject,
-					);
-					let body = rejected
-						.into_body()
-						.collect()
-						.await
-						.map(|b| b.to_bytes())
-						.unwrap_or_else(|_| g.rejection.body.clone());
-					return Some(body);
-				},
-				// Masking is not supported in the realtime path; treat as pass but still record.
-				Ok(GuardrailOutcome::Masked) => {
-					Policy::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::Mask,
-					);
-				},
-				Ok(GuardrailOutcome::None) => {
-					Policy::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::Allow,
-					);
-				},
-				Ok(GuardrailOutcome::FailOpen) => {
-					Policy::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::FailOpen,
-					);
+			match Policy::apply_single_request_guard(g, &mut req, &mut headers, client, None).await {
+				Ok(check) => {
+					check.record(client, GuardrailPhase::Request);
+					match check.outcome {
+						GuardrailOutcome::Rejected(rejected) => {
+							let body = rejected
+								.into_body()
+								.collect()
+								.await
+								.map(|b| b.to_bytes())
+								.unwrap_or_else(|_| g.rejection.body.clone());
+							return Some(body);
+						},
+						GuardrailOutcome::Audited(findings) => {
+							debug!("realtime prompt guard audit findings: {findings:?}");
+						},
+						// Masking is not supported in the realtime path; treat as pass.
+						GuardrailOutcome::Masked | GuardrailOutcome::None | GuardrailOutcome::FailOpen => {},
+					}
 				},
 				Err(e) => match g.failure_mode() {
 					FailureMode::FailClosed => {
 						tracing::warn!("request guard error in realtime path, failing closed: {e}");
-						Policy::record_guardrail_trip(
+						Policy::record_guardrail_check(
 							client,
-							crate::telemetry::metrics::GuardrailPhase::Request,
-							crate::telemetry::metrics::GuardrailAction::Reject,
+							GuardrailPhase::Request,
+							g.kind.metric_kind(),
+							GuardrailAction::Reject,
+							None,
 						);
 						return Some(g.rejection.body.clone());
 					},
 					FailureMode::FailOpen => {
 						tracing::warn!("request guard error in realtime path, failing open: {e}");
-						Policy::record_guardrail_trip(
+						Policy::record_guardrail_check(
 							client,
-							crate::telemetry::metrics::GuardrailPhase::Request,
-							crate::telemetry::metrics::GuardrailAction::FailOpen,
+							GuardrailPhase::Request,
+							g.kind.metric_kind(),
+							GuardrailAction::FailOpen,
+							None,
 						);
 					},
 				},
@@ -474,7 +553,10 @@ impl PromptGuard {
 		let mut resp = TextResponse {
 			content: window.to_string(),
 		};
-		match Policy::apply_single_response_guard(guard, &mut resp, http_headers, client).await? {
+		match Policy::apply_single_response_guard(guard, &mut resp, http_headers, client)
+			.await?
+			.outcome
+		{
 			GuardrailOutcome::Rejected(rejected) => {
 				let body = rejected.into_body().collect().await?.to_bytes();
 				Ok(Some(StreamingGuardrailOutcome::Blocked(body)))
@@ -482,12 +564,13 @@ impl PromptGuard {
 			GuardrailOutcome::Masked => {
 				debug_assert!(
 					false,
-					"streaming response guard unexpectedly returned Masked; streaming masking is not supported"
+					"streaming response guard unexpectedly returned Masked; masking guards are applied by MaskingSseBody"
 				);
 				Ok(None)
 			},
 			GuardrailOutcome::None => Ok(None),
 			GuardrailOutcome::FailOpen => Ok(None),
+			GuardrailOutcome::Audited(_) => Ok(None),
 		}
 	}
 }
@@ -664,8 +747,9 @@ impl Policy {
 		&self,
 		backend_info: &auth::BackendInfo,
 		req: &mut dyn RequestType,
-		http_headers: &HeaderMap,
+		http_headers: &mut HeaderMap,
 		claims: Option<Claims>,
+		log: &mut Option<&mut RequestLog>,
 	) -> anyhow::Result<Option<Response>> {
 		let client = PolicyClient::new(backend_info.inputs.clone());
 		for g in self
@@ -674,36 +758,18 @@ impl Policy {
 			.iter()
 			.flat_map(|g| g.request.iter())
 		{
-			match Self::apply_single_request_guard(g, req, http_headers, &client, claims.clone()).await? {
-				GuardrailOutcome::Rejected(res) => {
-					Self::record_guardrail_trip(
-						&client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::Reject,
-					);
-					return Ok(Some(res));
-				},
-				GuardrailOutcome::Masked => {
-					Self::record_guardrail_trip(
-						&client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::Mask,
-					);
-				},
-				GuardrailOutcome::None => {
-					Self::record_guardrail_trip(
-						&client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::Allow,
-					);
-				},
-				GuardrailOutcome::FailOpen => {
-					Self::record_guardrail_trip(
-						&client,
-						crate::telemetry::metrics::GuardrailPhase::Request,
-						crate::telemetry::metrics::GuardrailAction::FailOpen,
-					);
+			let check =
+				Self::apply_single_request_guard(g, req, http_headers, &client, claims.clone()).await?;
+			check.record(&client, GuardrailPhase::Request);
+			match check.outcome {
+				GuardrailOutcome::Rejected(res) => return Ok(Some(res)),
+				GuardrailOutcome::Audited(findings) => {
+					debug!("prompt guard audit findings: {findings:?}");
+					if let Some(log) = log.as_deref_mut() {
+						log.guardrail_findings.extend(findings);
+					}
 				},
+				GuardrailOutcome::Masked | GuardrailOutcome::None | GuardrailOutcome::FailOpen => {},
 			}
 		}
 		Ok(None)
@@ -711,29 +777,38 @@ impl Policy {
 
 	/// Evaluate a single request guard against `req` and return the outcome.
 	///
-	/// Callers are responsible for recording metrics and acting on the result.
+	/// Callers are responsible for recording the check and acting on the result.
 	/// This is the single place where every `RequestGuardKind` is dispatched,
 	/// so both the HTTP request path (`apply_prompt_guard`) and the realtime
 	/// WebSocket path (`apply_realtime_request_guards`) stay in sync.
 	async fn apply_single_request_guard(
 		guard: &RequestGuard,
 		req: &mut dyn RequestType,
-		http_headers: &HeaderMap,
+		http_headers: &mut HeaderMap,
 		client: &PolicyClient,
 		claims: Option<Claims>,
-	) -> anyhow::Result<GuardrailOutcome> {
-		match &guard.kind {
-			RequestGuardKind::Regex(rg) => Self::apply_regex(req, rg, &guard.rejection),
-			RequestGuardKind::Webhook(wh) => Self::apply_webhook(req, http_headers, client, wh).await,
+	) -> anyhow::Result<GuardrailCheck> {
+		let mode = guard.mode;
+		let outcome = match &guard.kind {
+			RequestGuardKind::Regex(rg) => {
+				return Self::apply_regex(req, rg, &guard.rejection, mode);
+			},
+			RequestGuardKind::Webhook(wh) => {
+				Self::apply_webhook(req, http_headers, client, wh, mode).await?
+			},
 			RequestGuardKind::OpenAIModeration(m) => {
-				match Self::apply_moderation(req, claims.clone(), client, &guard.rejection, m).await? {
-					Some(res) => Ok(GuardrailOutcome::Rejected(res)),
-					None => Ok(GuardrailOutcome::None),
-				}
+				Self::apply_moderation(req, claims.clone(), client, &guard.rejection, m, mode).await?
 			},
 			RequestGuardKind::BedrockGuardrails(bg) => {
-				Self::apply_bedrock_guardrails_request(req, claims.clone(), client, &guard.rejection, bg)
-					.await
+				Self::apply_bedrock_guardrails_request(
+					req,
+					claims.clone(),
+					client,
+					&guard.rejection,
+					bg,
+					mode,
+				)
+				.await?
 			},
 			RequestGuardKind::GoogleModelArmor(gma) => {
 				match Self::apply_google_model_armor_request(
@@ -745,8 +820,11 @@ impl Policy {
 				)
 				.await?
 				{
-					Some(res) => Ok(GuardrailOutcome::Rejected(res)),
-					None => Ok(GuardrailOutcome::None),
+					Some(_) if mode.is_audit() => {
+						GuardrailOutcome::audited(vec!["GOOGLE_MODEL_ARMOR".to_string()])
+					},
+					Some(res) => GuardrailOutcome::Rejected(res),
+					None => GuardrailOutcome::None,
 				}
 			},
 			RequestGuardKind::AzureContentSafety(acs) => {
@@ -759,11 +837,15 @@ impl Policy {
 				)
 				.await?
 				{
-					Some(res) => Ok(GuardrailOutcome::Rejected(res)),
-					None => Ok(GuardrailOutcome::None),
+					Some(_) if mode.is_audit() => {
+						GuardrailOutcome::audited(vec!["AZURE_CONTENT_SAFETY".to_string()])
+					},
+					Some(res) => GuardrailOutcome::Rejected(res),
+					None => GuardrailOutcome::None,
 				}
 			},
-		}
+		};
+		Ok(GuardrailCheck::new(guard.kind.metric_kind(), outcome))
 	}
 
 	async fn apply_moderation(
@@ -772,12 +854,17 @@ impl Policy {
 		client: &PolicyClient,
 		rej: &RequestRejection,
 		moderation: &Moderation,
-	) -> anyhow::Result<Option<Response>> {
+		mode: GuardMode,
+	) -> anyhow::Result<GuardrailOutcome> {
 		let resp = moderation::send_request(req, claims, client, moderation).await?;
-		if resp.results.iter().any(|r| r.flagged) {
-			Ok(Some(rej.as_response()))
+		if !resp.results.iter().any(|r| r.flagged) {
+			Ok(GuardrailOutcome::None)
+		} else if mode.is_audit() {
+			Ok(GuardrailOutcome::audited(vec![
+				"OPENAI_MODERATION".to_string(),
+			]))
 		} else {
-			Ok(None)
+			Ok(GuardrailOutcome::Rejected(rej.as_response()))
 		}
 	}
 
@@ -787,8 +874,18 @@ impl Policy {
 		client: &PolicyClient,
 		rej: &RequestRejection,
 		guardrails: &BedrockGuardrails,
+		mode: GuardMode,
 	) -> anyhow::Result<GuardrailOutcome> {
 		let resp = bedrock_guardrails::send_request(req, claims.clone(), client, guardrails).await?;
+		if mode.is_audit() {
+			let tripped = resp.is_blocked() || resp.is_anonymized();
+			return Ok(GuardrailOutcome::audited(
+				tripped
+					.then(|| "BEDROCK_GUARDRAILS".to_string())
+					.into_iter()
+					.collect(),
+			));
+		}
 		if resp.is_blocked() {
 			Ok(GuardrailOutcome::Rejected(rej.as_response()))
 		} else if resp.is_anonymized() {
@@ -957,15 +1054,35 @@ impl Policy {
 		req: &mut dyn RequestType,
 		rgx: &RegexRules,
 		rej: &RequestRejection,
-	) -> anyhow::Result<GuardrailOutcome> {
+		mode: GuardMode,
+	) -> anyhow::Result<GuardrailCheck> {
+		if mode.is_audit() {
+			let findings = req
+				.get_messages()
+				.iter()
+				.flat_map(|msg| Self::detect_prompt_guard_regex(&msg.content, rgx))
+				.collect();
+			return Ok(GuardrailCheck::new(
+				GuardrailKind::Regex,
+				GuardrailOutcome::audited(findings),
+			));
+		}
+		let mut check = GuardrailCheck::new(GuardrailKind::Regex, GuardrailOutcome::None);
 		let mut msgs = req.get_messages();
 		let mut any_changed = false;
 		for msg in &mut msgs {
 			match Self::apply_prompt_guard_regex(&msg.content, rgx) {
 				Some(RegexResult::Reject) => {
-					return Ok(GuardrailOutcome::Rejected(rej.as_response()));
+					check
+						.entity_types
+						.extend(Self::detect_prompt_guard_regex(&msg.content, rgx));
+					check.outcome = GuardrailOutcome::Rejected(rej.as_response());
+					return Ok(check);
 				},
 				Some(RegexResult::Mask(content)) => {
+					check
+						.entity_types
+						.extend(Self::detect_prompt_guard_regex(&msg.content, rgx));
 					any_changed = true;
 					msg.content = content.into();
 				},
@@ -974,24 +1091,32 @@ impl Policy {
 		}
 		if any_changed {
 			req.set_messages(msgs);
-			return Ok(GuardrailOutcome::Masked);
+			check.outcome = GuardrailOutcome::Masked;
 		}
-		Ok(GuardrailOutcome::None)
+		Ok(check)
 	}
 
 	fn apply_regex_response(
 		resp: &mut dyn ResponseType,
 		rgx: &RegexRules,
 		rej: &RequestRejection,
-	) -> anyhow::Result<GuardrailOutcome> {
+	) -> anyhow::Result<GuardrailCheck> {
+		let mut check = GuardrailCheck::new(GuardrailKind::Regex, GuardrailOutcome::None);
 		let mut msgs = resp.to_webhook_choices();
 		let mut any_changed = false;
 		for msg in &mut msgs {
 			match Self::apply_prompt_guard_regex(&msg.message.content, rgx) {
 				Some(RegexResult::Reject) => {
-					return Ok(GuardrailOutcome::Rejected(rej.as_response()));
+					check
+						.entity_types
+						.extend(Self::detect_prompt_guard_regex(&msg.message.content, rgx));
+					check.outcome = GuardrailOutcome::Rejected(rej.as_response());
+					return Ok(check);
 				},
 				Some(RegexResult::Mask(content)) => {
+					check
+						.entity_types
+						.extend(Self::detect_prompt_guard_regex(&msg.message.content, rgx));
 					any_changed = true;
 					msg.message.content = content.into();
 				},
@@ -1000,19 +1125,24 @@ impl Policy {
 		}
 		if any_changed {
 			resp.set_webhook_choices(msgs)?;
-			return Ok(GuardrailOutcome::Masked);
+			check.outcome = GuardrailOutcome::Masked;
 		}
-		Ok(GuardrailOutcome::None)
+		Ok(check)
 	}
 
 	async fn apply_webhook(
 		req: &mut dyn RequestType,
-		http_headers: &HeaderMap,
+		http_headers: &mut HeaderMap,
 		client: &PolicyClient,
 		webhook: &Webhook,
+		mode: GuardMode,
 	) -> anyhow::Result<GuardrailOutcome> {
 		let messsages = req.get_messages();
-		let headers = Self::get_webhook_forward_headers(http_headers, &webhook.forward_header_matches);
+		let headers = Self::get_webhook_forward_headers(
+			http_headers,
+			&webhook.forward_header_matches,
+			&webhook.forward_header_names,
+		);
 		let whr = match webhook::send_request(client, &webhook.target, &headers, messsages).await {
 			Ok(whr) => whr,
 			Err(e) => {
@@ -1025,6 +1155,13 @@ impl Policy {
 				};
 			},
 		};
+		if mode.is_audit() {
+			return Ok(match whr.action {
+				RequestAction::Mask(_) => GuardrailOutcome::audited(vec!["WEBHOOK_MASK".to_string()]),
+				RequestAction::Reject(_) => GuardrailOutcome::audited(vec!["WEBHOOK_REJECT".to_string()]),
+				RequestAction::Pass(_) => GuardrailOutcome::None,
+			});
+		}
 		match whr.action {
 			RequestAction::Mask(mask) => {
 				debug!(
@@ -1033,10 +1170,14 @@ impl Policy {
 						.reason
 						.unwrap_or_else(|| "no reason specified".to_string())
 				);
-				let MaskActionBody::PromptMessages(body) = mask.body else {
+				let MaskActionBody::PromptMessages(mut body) = mask.body else {
 					anyhow::bail!("invalid webhook response");
 				};
+				crate::llm::types::normalize_roles(&mut body.messages)?;
 				req.set_messages(body.messages);
+				if let Some(headers) = mask.headers {
+					headers.apply(http_headers)?;
+				}
 				Ok(GuardrailOutcome::Masked)
 			},
 			RequestAction::Reject(rej) => {
@@ -1059,6 +1200,9 @@ impl Policy {
 						.reason
 						.unwrap_or_else(|| "no reason specified".to_string())
 				);
+				if let Some(headers) = pass.headers {
+					headers.apply(http_headers)?;
+				}
 				Ok(GuardrailOutcome::None)
 			},
 		}
@@ -1071,7 +1215,11 @@ impl Policy {
 		webhook: &Webhook,
 	) -> anyhow::Result<GuardrailOutcome> {
 		let messsages = resp.to_webhook_choices();
-		let headers = Self::get_webhook_forward_headers(http_headers, &webhook.forward_header_matches);
+		let headers = Self::get_webhook_forward_headers(
+			http_headers,
+			&webhook.forward_header_matches,
+			&webhook.forward_header_names,
+		);
 		let whr = match webhook::send_response(client, &webhook.target, &headers, messsages).await {
 			Ok(whr) => whr,
 			Err(e) => {
@@ -1126,6 +1274,7 @@ impl Policy {
 	fn get_webhook_forward_headers(
 		http_headers: &HeaderMap,
 		header_matches: &[HeaderMatch],
+		header_names: &[Strng],
 	) -> HeaderMap {
 		let mut headers = HeaderMap::new();
 		for HeaderMatch { name, value } in header_matches {
@@ -1142,19 +1291,39 @@ impl Policy {
 				headers.append(header_name.clone(), have.clone());
 			}
 		}
+		for name in http_headers.keys() {
+			if headers.contains_key(name)
+				|| !header_names
+					.iter()
+					.any(|pattern| header_name_matches(pattern, name.as_str()))
+			{
+				continue;
+			}
+			for have in http_headers.get_all(name) {
+				headers.append(name.clone(), have.clone());
+			}
+		}
 		headers
 	}
 
-	fn record_guardrail_trip(
+	/// Counts one guardrail check in `guardrail_checks`.
+	fn record_guardrail_check(
 		client: &PolicyClient,
-		phase: crate::telemetry::metrics::GuardrailPhase,
-		action: crate::telemetry::metrics::GuardrailAction,
+		phase: GuardrailPhase,
+		kind: GuardrailKind,
+		action: GuardrailAction,
+		entity_type: Option<&str>,
 	) {
 		client
 			.inputs
 			.metrics
 			.guardrail_checks
-			.get_or_create(&crate::telemetry::metrics::GuardrailLabels { phase, action })
+			.get_or_create(&GuardrailLabels {
+				phase,
+				kind,
+				action,
+				entity_type: entity_type.map(strng::new).into(),
+			})
 			.inc();
 	}
 
@@ -1182,22 +1351,18 @@ impl Policy {
 
 		for r in &rgx.rules {
 			match r {
-				RegexRule::Builtin { builtin } => {
-					let rec = match builtin {
-						Builtin::Ssn => &*pii::SSN,
-						Builtin::CreditCard => &*pii::CC,
-						Builtin::PhoneNumber => &*pii::PHONE,
-						Builtin::Email => &*pii::EMAIL,
-						Builtin::CaSin => &*pii::CA_SIN,
-					};
-					let results = pii::recognizer(rec, working.as_deref().unwrap_or(original_content));
+				RegexRule::Builtin { builtin, mask } => {
+					let results = pii::recognizer(
+						builtin.recognizer(),
+						working.as_deref().unwrap_or(original_content),
+					);
 					if results.is_empty() {
 						continue;
 					}
 					match &rgx.action {
 						Action::Reject => return Some(RegexResult::Reject),
 						Action::Mask => {
-							let replacement = format!("<{}>", results[0].entity_type);
+							let entity_type = results[0].entity_type.clone();
 							let buf = working.get_or_insert_with(|| original_content.to_string());
 							// Replace in reverse order to avoid index shifting, coalescing overlaps
 							for range in results
@@ -1211,12 +1376,14 @@ impl Policy {
 										Err((a, b))
 									}
 								}) {
+								let replacement =
+									MaskFormat::render(mask.as_ref(), "<{type}>", &entity_type, &buf[range.clone()]);
 								buf.replace_range(range, &replacement);
 							}
 						},
 					}
 				},
-				RegexRule::Regex { pattern } => {
+				RegexRule::Regex { pattern, mask } => {
 					let content = working.as_deref().unwrap_or(original_content);
 					if matches!(rgx.action, Action::Reject) {
 						if pattern.is_match(content) {
@@ -1232,7 +1399,9 @@ impl Policy {
 					let buf = working.get_or_insert_with(|| original_content.to_string());
 					// Reverse order to avoid index shifting
 					for range in ranges.into_iter().rev() {
-						buf.replace_range(range, "<masked>");
+						let replacement =
+							MaskFormat::render(mask.as_ref(), "<masked>", "REGEX", &buf[range.clone()]);
+						buf.replace_range(range, &replacement);
 					}
 				},
 			}
@@ -1240,6 +1409,23 @@ impl Policy {
 		working.map(RegexResult::Mask)
 	}
 
+	/// Returns the entity types each rule in `rgx` detects in `content`, without modifying it.
+	/// Custom regex rules are reported as `REGEX`.
+	fn detect_prompt_guard_regex(content: &str, rgx: &RegexRules) -> Vec<String> {
+		rgx
+			.rules
+			.iter()
+			.flat_map(|r| match r {
+				RegexRule::Builtin { builtin, .. } => pii::recognizer(builtin.recognizer(), content)
+					.into_iter()
+					.map(|r| r.entity_type)
+					.collect_vec(),
+				RegexRule::Regex { pattern, .. } if pattern.is_match(content) => vec!["REGEX".to_string()],
+				RegexRule::Regex { .. } => vec![],
+			})
+			.collect()
+	}
+
 	pub async fn apply_response_prompt_guard(
 		client: &PolicyClient,
 		resp: &mut dyn ResponseType,
@@ -1247,36 +1433,10 @@ impl Policy {
 		guards: &Vec<ResponseGuard>,
 	) -> anyhow::Result<Option<Response>> {
 		for g in guards {
-			match Self::apply_single_response_guard(g, resp, http_headers, client).await? {
-				GuardrailOutcome::Rejected(res) => {
-					Self::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Response,
-						crate::telemetry::metrics::GuardrailAction::Reject,
-					);
-					return Ok(Some(res));
-				},
-				GuardrailOutcome::Masked => {
-					Self::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Response,
-						crate::telemetry::metrics::GuardrailAction::Mask,
-					);
-				},
-				GuardrailOutcome::None => {
-					Self::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Response,
-						crate::telemetry::metrics::GuardrailAction::Allow,
-					);
-				},
-				GuardrailOutcome::FailOpen => {
-					Self::record_guardrail_trip(
-						client,
-						crate::telemetry::metrics::GuardrailPhase::Response,
-						crate::telemetry::metrics::GuardrailAction::FailOpen,
-					);
-				},
+			let check = Self::apply_single_response_guard(g, resp, http_headers, client).await?;
+			check.record(client, GuardrailPhase::Response);
+			if let GuardrailOutcome::Rejected(res) = check.outcome {
+				return Ok(Some(res));
 			}
 		}
 		Ok(None)
@@ -1287,32 +1447,35 @@ impl Policy {
 		resp: &mut dyn ResponseType,
 		http_headers: &HeaderMap,
 		client: &PolicyClient,
-	) -> anyhow::Result<GuardrailOutcome> {
-		match &guard.kind {
-			ResponseGuardKind::Regex(rg) => Self::apply_regex_response(resp, rg, &guard.rejection),
+	) -> anyhow::Result<GuardrailCheck> {
+		let outcome = match &guard.kind {
+			ResponseGuardKind::Regex(rg) => {
+				return Self::apply_regex_response(resp, rg, &guard.rejection);
+			},
 			ResponseGuardKind::Webhook(wh) => {
-				Self::apply_webhook_response(resp, http_headers, client, wh).await
+				Self::apply_webhook_response(resp, http_headers, client, wh).await?
 			},
 			ResponseGuardKind::BedrockGuardrails(bg) => {
-				Self::apply_bedrock_guardrails_response(resp, None, client, &guard.rejection, bg).await
+				Self::apply_bedrock_guardrails_response(resp, None, client, &guard.rejection, bg).await?
 			},
 			ResponseGuardKind::GoogleModelArmor(gma) => {
 				match Self::apply_google_model_armor_response(resp, None, client, &guard.rejection, gma)
 					.await?
 				{
-					Some(res) => Ok(GuardrailOutcome::Rejected(res)),
-					None => Ok(GuardrailOutcome::None),
+					Some(res) => GuardrailOutcome::Rejected(res),
+					None => GuardrailOutcome::None,
 				}
 			},
 			ResponseGuardKind::AzureContentSafety(acs) => {
 				match Self::apply_azure_content_safety_response(resp, None, client, &guard.rejection, acs)
 					.await?
 				{
-					Some(res) => Ok(GuardrailOutcome::Rejected(res)),
-					None => Ok(GuardrailOutcome::None),
+					Some(res) => GuardrailOutcome::Rejected(res),
+					None => GuardrailOutcome::None,
 				}
 			},
-		}
+		};
+		Ok(GuardrailCheck::new(guard.kind.metric_kind(), outcome))
 	}
 }
 
@@ -1326,6 +1489,9 @@ pub struct RequestGuard {
 	/// Response returned when the request is rejected.
 	#[serde(default)]
 	pub rejection: RequestRejection,
+	/// Whether the guard enforces its decision, or only records what it detected.
+	#[serde(default, skip_serializing_if = "GuardMode::is_enforce")]
+	pub mode: GuardMode,
 	/// Guardrail provider or rule set to apply.
 	#[serde(flatten)]
 	pub kind: RequestGuardKind,
@@ -1342,6 +1508,26 @@ impl RequestGuard {
 	}
 }
 
+#[apply(schema!)]
+#[derive(Default, Copy, PartialEq, Eq)]
+pub enum GuardMode {
+	/// Reject or mask requests that trip the guard (default).
+	#[default]
+	Enforce,
+	/// Record what the guard detected in the request log, without modifying or rejecting the request.
+	Audit,
+}
+
+impl GuardMode {
+	pub(crate) fn is_enforce(&self) -> bool {
+		*self == Self::Enforce
+	}
+
+	pub(crate) fn is_audit(&self) -> bool {
+		*self == Self::Audit
+	}
+}
+
 #[apply(schema!)]
 pub enum RequestGuardKind {
 	/// Apply regex-based masking or rejection rules.
@@ -1358,6 +1544,19 @@ pub enum RequestGuardKind {
 	AzureContentSafety(AzureContentSafety),
 }
 
+impl RequestGuardKind {
+	fn metric_kind(&self) -> GuardrailKind {
+		match self {
+			RequestGuardKind::Regex(_) => GuardrailKind::Regex,
+			RequestGuardKind::Webhook(_) => GuardrailKind::Webhook,
+			RequestGuardKind::OpenAIModeration(_) => GuardrailKind::Moderation,
+			RequestGuardKind::BedrockGuardrails(_) => GuardrailKind::BedrockGuardrails,
+			RequestGuardKind::GoogleModelArmor(_) => GuardrailKind::GoogleModelArmor,
+			RequestGuardKind::AzureContentSafety(_) => GuardrailKind::AzureContentSafety,
+		}
+	}
+}
+
 #[apply(schema!)]
 pub struct RegexRules {
 	/// Action to take when a regex rule matches.
@@ -1374,6 +1573,9 @@ pub enum RegexRule {
 	Builtin {
 		/// Built-in pattern name.
 		builtin: Builtin,
+		/// How matches are replaced when masking. Defaults to `<{type}>`.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		mask: Option<MaskFormat>,
 	},
 	/// Use a custom regular expression.
 	Regex {
@@ -1381,9 +1583,44 @@ pub enum RegexRule {
 		#[serde(with = "serde_regex")]
 		#[cfg_attr(feature = "schema", schemars(with = "String"))]
 		pattern: regex::Regex,
+		/// How matches are replaced when masking. Defaults to `<masked>`.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		mask: Option<MaskFormat>,
 	},
 }
 
+#[apply(schema!)]
+pub struct MaskFormat {
+	/// Replacement text for a match. `{type}` is replaced with the detected entity type,
+	/// such as `EMAIL_ADDRESS`, or `REGEX` for custom patterns.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub mask_template: Option<Strng>,
+	/// Number of trailing characters of the match to leave visible after the replacement,
+	/// such as the last 4 digits of a card number.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub keep_last_n: Option<usize>,
+}
+
+impl MaskFormat {
+	/// Renders the replacement for `matched`, using `default_template` when no template is set.
+	fn render(
+		mask: Option<&MaskFormat>,
+		default_template: &str,
+		entity_type: &str,
+		matched: &str,
+	) -> String {
+		let template = mask
+			.and_then(|m| m.mask_template.as_deref())
+			.unwrap_or(default_template);
+		let mut out = template.replace("{type}", entity_type);
+		if let Some(n) = mask.and_then(|m| m.keep_last_n) {
+			let len = matched.chars().count();
+			out.extend(matched.chars().skip(len.saturating_sub(n)));
+		}
+		out
+	}
+}
+
 impl RequestRejection {
 	pub fn as_response(&self) -> Response {
 		let mut response = ::http::response::Builder::new()
@@ -1415,6 +1652,25 @@ pub enum Builtin {
 	Email,
 	/// Canadian Social Insurance Number pattern.
 	CaSin,
+	/// International Bank Account Number, validated with the mod-97 checksum.
+	Iban,
+	/// Passport number pattern.
+	Passport,
+}
+
+impl Builtin {
+	#[allow(clippy::borrowed_box)]
+	fn recognizer(&self) -> &'static Box<dyn pii::Recognizer + Sync + Send + 'static> {
+		match self {
+			Builtin::Ssn => &*pii::SSN,
+			Builtin::CreditCard => &*pii::CC,
+			Builtin::PhoneNumber => &*pii::PHONE,
+			Builtin::Email => &*pii::EMAIL,
+			Builtin::CaSin => &*pii::CA_SIN,
+			Builtin::Iban => &*pii::IBAN,
+			Builtin::Passport => &*pii::PASSPORT,
+		}
+	}
 }
 
 #[apply(schema!)]
@@ -1450,6 +1706,28 @@ pub enum FailureMode {
 	FailOpen,
 }
 
+/// Matches a (lowercase) header name against a case-insensitive glob where `*` matches any
+/// sequence of characters.
+fn header_name_matches(pattern: &str, name: &str) -> bool {
+	let pattern = pattern.to_ascii_lowercase();
+	let mut parts = pattern.split('*');
+	let Some(mut rest) = name.strip_prefix(parts.next().unwrap_or_default()) else {
+		return false;
+	};
+	let parts: Vec<_> = parts.collect();
+	let Some((last, middle)) = parts.split_last() else {
+		// No wildcard: the whole name must match.
+		return rest.is_empty();
+	};
+	for part in middle {
+		let Some(idx) = rest.find(part) else {
+			return false;
+		};
+		rest = &rest[idx + part.len()..];
+	}
+	rest.ends_with(last)
+}
+
 #[apply(schema!)]
 pub struct Webhook {
 	/// Backend that receives guardrail webhook requests.
@@ -1457,6 +1735,10 @@ pub struct Webhook {
 	/// Incoming request headers to forward to the webhook.
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub forward_header_matches: Vec<HeaderMatch>,
+	/// Incoming request headers to forward to the webhook by name, regardless of value.
+	/// `*` matches any sequence of characters, so `x-tenant-*` forwards every tenant header.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub forward_header_names: Vec<Strng>,
 	/// Behavior when the webhook is unreachable or returns an error.
 	/// Defaults to `failClosed`.
 	#[serde(default, skip_serializing_if = "crate::serdes::is_default")]
@@ -1643,6 +1925,18 @@ pub enum ResponseGuardKind {
 	AzureContentSafety(AzureContentSafety),
 }
 
+impl ResponseGuardKind {
+	fn metric_kind(&self) -> GuardrailKind {
+		match self {
+			ResponseGuardKind::Regex(_) => GuardrailKind::Regex,
+			ResponseGuardKind::Webhook(_) => GuardrailKind::Webhook,
+			ResponseGuardKind::BedrockGuardrails(_) => GuardrailKind::BedrockGuardrails,
+			ResponseGuardKind::GoogleModelArmor(_) => GuardrailKind::GoogleModelArmor,
+			ResponseGuardKind::AzureContentSafety(_) => GuardrailKind::AzureContentSafety,
+		}
+	}
+}
+
 #[apply(schema!)]
 pub struct PromptGuardRegex {}
 fn default_code() -> StatusCode {
@@ -1862,28 +2156,52 @@ fn test_unmarshal_request_with_transformation_policy() {
 #[cfg(test)]
 #[rstest::rstest]
 #[case::single_email(
-  vec![RegexRule::Builtin { builtin: Builtin::Email }],
+  vec![RegexRule::Builtin { builtin: Builtin::Email, mask: None }],
 	"contact john.doe@example.com now",
 	"contact <EMAIL_ADDRESS> now",
 )]
 #[case::multiple_emails(
-  vec![RegexRule::Builtin { builtin: Builtin::Email }],
+  vec![RegexRule::Builtin { builtin: Builtin::Email, mask: None }],
 	"contact john@example.com or jane@other.com for help",
 	"contact <EMAIL_ADDRESS> or <EMAIL_ADDRESS> for help",
 )]
 #[case::ssn_in_sentence(
-  vec![RegexRule::Builtin { builtin: Builtin::Ssn }],
+  vec![RegexRule::Builtin { builtin: Builtin::Ssn, mask: None }],
 	"My ssn is 123-45-6789 ok",
 	"My ssn is <SSN> ok",
 )]
 #[case::builtin_credit_card_and_regex(
   vec![
-    RegexRule::Builtin { builtin: Builtin::CreditCard },
-    RegexRule::Regex { pattern: regex::Regex::new(r"\d{2}").unwrap() },
+    RegexRule::Builtin { builtin: Builtin::CreditCard, mask: None },
+    RegexRule::Regex { pattern: regex::Regex::new(r"\d{2}").unwrap(), mask: None },
   ],
 	"Card number: 4111-1111-1111-1111 or id:12-34",
 	"Card number: <CREDIT_CARD> or id:<masked>-<masked>",
 )]
+#[case::templated_builtin(
+  vec![RegexRule::Builtin {
+    builtin: Builtin::Email,
+    mask: Some(MaskFormat { mask_template: Some(strng::literal!("[REDACTED-{type}]")), keep_last_n: None }),
+  }],
+	"contact john.doe@example.com now",
+	"contact [REDACTED-EMAIL_ADDRESS] now",
+)]
+#[case::card_last_four(
+  vec![RegexRule::Builtin {
+    builtin: Builtin::CreditCard,
+    mask: Some(MaskFormat { mask_template: Some(strng::literal!("****")), keep_last_n: Some(4) }),
+  }],
+	"Card number: 4111-1111-1111-1111 thanks",
+	"Card number: ****1111 thanks",
+)]
+#[case::templated_regex(
+  vec![RegexRule::Regex {
+    pattern: regex::Regex::new(r"id:\d+").unwrap(),
+    mask: Some(MaskFormat { mask_template: Some(strng::literal!("[{type}]")), keep_last_n: None }),
+  }],
+	"lookup id:1234 please",
+	"lookup [REGEX] please",
+)]
 fn test_apply_prompt_guard_regex_mask(
 	#[case] rules: Vec<RegexRule>,
 	#[case] input: &str,
@@ -1904,8 +2222,8 @@ fn test_apply_prompt_guard_regex_mask(
 
 #[cfg(test)]
 #[rstest::rstest]
-#[case::regex(vec![RegexRule::Regex { pattern: regex::Regex::new(r"\d{2}").unwrap() }], "id:12")]
-#[case::builtin(vec![RegexRule::Builtin { builtin: Builtin::Email }], "contact john.doe@example.com")]
+#[case::regex(vec![RegexRule::Regex { pattern: regex::Regex::new(r"\d{2}").unwrap(), mask: None }], "id:12")]
+#[case::builtin(vec![RegexRule::Builtin { builtin: Builtin::Email, mask: None }], "contact john.doe@example.com")]
 fn test_apply_prompt_guard_regex_reject(#[case] rules: Vec<RegexRule>, #[case] input: &str) {
 	let result = Policy::apply_prompt_guard_regex(
 		input,
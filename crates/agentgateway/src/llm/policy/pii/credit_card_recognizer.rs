@@ -29,19 +29,22 @@ impl CreditCardRecognizer {
 			r"\b4\d{3}[- ]?(\d{3,4})[- ]?(\d{3,4})[- ]?(\d{3,5})\b",
 			0.3,
 		);
+		// Mastercard issues from both the 51-55 and 2221-2720 ranges
 		recognizer.add_pattern(
 			"mastercard",
-			r"\b5[0-5]\d{2}[- ]?(\d{3,4})[- ]?(\d{3,4})[- ]?(\d{3,5})\b",
+			r"\b(?:5[0-5]\d{2}|2(?:22[1-9]|2[3-9]\d|[3-6]\d{2}|7[01]\d|720))[- ]?(\d{3,4})[- ]?(\d{3,4})[- ]?(\d{3,5})\b",
 			0.3,
 		);
+		// Discover: 6011, 644-649 and 65
 		recognizer.add_pattern(
 			"discover",
-			r"\b6\d{3}[- ]?(\d{3,4})[- ]?(\d{3,4})[- ]?(\d{3,5})\b",
+			r"\b6(?:011|5\d{2}|4[4-9]\d)[- ]?(\d{3,4})[- ]?(\d{3,4})[- ]?(\d{3,5})\b",
 			0.3,
 		);
+		// Amex (34, 37), JCB (35) and Diners Club (300-305, 36, 38)
 		recognizer.add_pattern(
 			"amex",
-			r"\b3\d{3}[- ]?(\d{3,4})[- ]?(\d{3,4})[- ]?(\d{3,5})\b",
+			r"\b3(?:[4-8]\d|0[0-5])\d[- ]?(\d{3,4})[- ]?(\d{3,4})[- ]?(\d{3,5})\b",
 			0.3,
 		);
 		// For Diners Club (1xxx), we need to be more specific to avoid 13-digit matches
@@ -55,9 +58,38 @@ impl CreditCardRecognizer {
 	}
 }
 
+/// Validates a card number with the Luhn checksum, ignoring separators.
+pub fn passes_luhn(candidate: &str) -> bool {
+	let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+	if !(13..=19).contains(&digits.len()) {
+		return false;
+	}
+	let sum: u32 = digits
+		.iter()
+		.rev()
+		.enumerate()
+		.map(|(i, &d)| {
+			if i % 2 == 0 {
+				d
+			} else if d * 2 > 9 {
+				d * 2 - 9
+			} else {
+				d * 2
+			}
+		})
+		.sum();
+	sum % 10 == 0
+}
+
 impl Recognizer for CreditCardRecognizer {
 	fn recognize(&self, text: &str) -> Vec<super::recognizer_result::RecognizerResult> {
-		self.recognizer.recognize(text)
+		// Long digit runs such as order IDs often match the patterns, so require a valid checksum
+		self
+			.recognizer
+			.recognize(text)
+			.into_iter()
+			.filter(|r| passes_luhn(&r.matched))
+			.collect()
 	}
 	fn name(&self) -> &str {
 		self.recognizer.name()
@@ -0,0 +1,62 @@
+use crate::llm::policy::pii::pattern_recognizer::PatternRecognizer;
+use crate::llm::policy::pii::recognizer::Recognizer;
+
+pub struct IbanRecognizer {
+	recognizer: PatternRecognizer,
+}
+
+impl IbanRecognizer {
+	pub fn new() -> Self {
+		let mut recognizer = PatternRecognizer::new(
+			"IBAN",
+			vec![
+				"iban".to_string(),
+				"bank".to_string(),
+				"account".to_string(),
+			],
+		);
+		// Country code, check digits, then the BBAN, optionally grouped in blocks of 4
+		recognizer.add_pattern(
+			"IBAN",
+			r"\b[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,4})?\b",
+			0.5,
+		);
+
+		Self { recognizer }
+	}
+}
+
+/// Validates an IBAN using the ISO 13616 mod-97 checksum.
+pub fn is_valid_iban(candidate: &str) -> bool {
+	let iban: Vec<u8> = candidate.bytes().filter(|b| *b != b' ').collect();
+	if !(15..=34).contains(&iban.len()) {
+		return false;
+	}
+	let (head, tail) = iban.split_at(4);
+	let mut remainder: u32 = 0;
+	for b in tail.iter().chain(head) {
+		let value = match b {
+			b'0'..=b'9' => u32::from(b - b'0'),
+			b'A'..=b'Z' => u32::from(b - b'A') + 10,
+			_ => return false,
+		};
+		let scale = if value >= 10 { 100 } else { 10 };
+		remainder = (remainder * scale + value) % 97;
+	}
+	remainder == 1
+}
+
+impl Recognizer for IbanRecognizer {
+	fn recognize(&self, text: &str) -> Vec<super::recognizer_result::RecognizerResult> {
+		// Discard matches that fail the checksum, as the pattern alone matches many identifiers
+		self
+			.recognizer
+			.recognize(text)
+			.into_iter()
+			.filter(|r| is_valid_iban(&r.matched))
+			.collect()
+	}
+	fn name(&self) -> &str {
+		self.recognizer.name()
+	}
+}
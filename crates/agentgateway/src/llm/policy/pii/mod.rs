@@ -4,11 +4,12 @@ use once_cell::sync::Lazy;
 
 use crate::llm::policy::pii::email_recognizer::EmailRecognizer;
 use crate::llm::policy::pii::phone_recognizer::PhoneRecognizer;
-use crate::llm::policy::pii::recognizer::Recognizer;
 
 mod ca_sin_recognizer;
 mod credit_card_recognizer;
 mod email_recognizer;
+mod iban_recognizer;
+mod passport_recognizer;
 mod pattern_recognizer;
 mod phone_recognizer;
 mod recognizer;
@@ -16,6 +17,8 @@ mod recognizer_result;
 mod url_recognizer;
 mod us_ssn_recognizer;
 
+pub use recognizer::Recognizer;
+
 pub static EMAIL: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
 	Lazy::new(|| Box::new(EmailRecognizer::new()));
 
@@ -31,6 +34,12 @@ pub static SSN: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
 pub static CA_SIN: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
 	Lazy::new(|| Box::new(ca_sin_recognizer::CaSinRecognizer::new()));
 
+pub static IBAN: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
+	Lazy::new(|| Box::new(iban_recognizer::IbanRecognizer::new()));
+
+pub static PASSPORT: Lazy<Box<dyn Recognizer + Sync + Send + 'static>> =
+	Lazy::new(|| Box::new(passport_recognizer::PassportRecognizer::new()));
+
 #[allow(clippy::borrowed_box)]
 pub fn recognizer(
 	r: &Box<dyn Recognizer + Sync + Send + 'static>,
@@ -0,0 +1,30 @@
+use crate::llm::policy::pii::pattern_recognizer::PatternRecognizer;
+use crate::llm::policy::pii::recognizer::Recognizer;
+
+pub struct PassportRecognizer {
+	recognizer: PatternRecognizer,
+}
+
+impl PassportRecognizer {
+	pub fn new() -> Self {
+		let mut recognizer = PatternRecognizer::new(
+			"PASSPORT",
+			vec!["passport".to_string(), "travel document".to_string()],
+		);
+		// One or two letters followed by digits, as used by many European passports
+		recognizer.add_pattern("PASSPORT_ALPHA_PREFIX", r"\b[A-Z]{1,2}[0-9]{6,8}\b", 0.4);
+		// French-style passports: two digits, two letters, five digits
+		recognizer.add_pattern("PASSPORT_FR", r"\b[0-9]{2}[A-Z]{2}[0-9]{5}\b", 0.4);
+
+		Self { recognizer }
+	}
+}
+
+impl Recognizer for PassportRecognizer {
+	fn recognize(&self, text: &str) -> Vec<super::recognizer_result::RecognizerResult> {
+		self.recognizer.recognize(text)
+	}
+	fn name(&self) -> &str {
+		self.recognizer.name()
+	}
+}
@@ -106,6 +106,32 @@ fn test_credit_card_recognizer() {
 	}
 }
 
+#[test]
+fn test_credit_card_recognizer_requires_luhn() {
+	let recognizer = credit_card_recognizer::CreditCardRecognizer::new();
+
+	// Luhn-valid Visa test number
+	let results = recognizer.recognize("Card: 4111 1111 1111 1111");
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0].matched, "4111 1111 1111 1111");
+
+	// Visa-shaped, but the checksum does not pass
+	assert!(recognizer.recognize("Card: 4111 1111 1111 1112").is_empty());
+
+	// Luhn-valid order ID, but not a known card prefix
+	assert!(recognizer.recognize("Order 7845120399110048").is_empty());
+}
+
+#[test]
+fn test_passes_luhn() {
+	assert!(credit_card_recognizer::passes_luhn("4111-1111-1111-1111"));
+	assert!(credit_card_recognizer::passes_luhn("378282246310005"));
+	assert!(credit_card_recognizer::passes_luhn("2223003122003222"));
+	assert!(!credit_card_recognizer::passes_luhn("4111111111111112"));
+	// Too short to be a card number
+	assert!(!credit_card_recognizer::passes_luhn("0"));
+}
+
 #[test]
 fn test_ssn_recognizer() {
 	let recognizer = us_ssn_recognizer::UsSsnRecognizer::new();
@@ -290,3 +316,50 @@ fn test_multiple_recognizers() {
 		"Expected at least 5 total matches, got {total_results}"
 	);
 }
+
+#[test]
+fn test_iban_recognizer() {
+	let recognizer = iban_recognizer::IbanRecognizer::new();
+
+	let text = "Pay GB82 WEST 1234 5698 7654 32 or DE89370400440532013000 today";
+	let results = recognizer.recognize(text);
+
+	let matched: Vec<&str> = results.iter().map(|r| r.matched.as_str()).collect();
+	assert_eq!(
+		matched,
+		vec!["GB82 WEST 1234 5698 7654 32", "DE89370400440532013000"]
+	);
+	assert!(results.iter().all(|r| r.entity_type == "IBAN"));
+}
+
+#[test]
+fn test_iban_recognizer_rejects_bad_checksum() {
+	let recognizer = iban_recognizer::IbanRecognizer::new();
+
+	// Same shape as valid IBANs, but the check digits do not match
+	let text = "Pay GB82 WEST 1234 5698 7654 33 or DE00370400440532013000 today";
+	assert!(recognizer.recognize(text).is_empty());
+}
+
+#[test]
+fn test_is_valid_iban() {
+	assert!(iban_recognizer::is_valid_iban(
+		"FR14 2004 1010 0505 0001 3M02 606"
+	));
+	assert!(iban_recognizer::is_valid_iban("NL91ABNA0417164300"));
+	assert!(!iban_recognizer::is_valid_iban("NL91ABNA0417164301"));
+	// Too short to be an IBAN
+	assert!(!iban_recognizer::is_valid_iban("GB82WEST"));
+}
+
+#[test]
+fn test_passport_recognizer() {
+	let recognizer = passport_recognizer::PassportRecognizer::new();
+
+	let text = "Passport numbers: AB1234567 and 12AB34567, reference ab1234567";
+	let results = recognizer.recognize(text);
+
+	let matched: Vec<&str> = results.iter().map(|r| r.matched.as_str()).collect();
+	assert_eq!(matched, vec!["AB1234567", "12AB34567"]);
+	assert!(results.iter().all(|r| r.entity_type == "PASSPORT"));
+}
@@ -0,0 +1,389 @@
+//! Streaming PII masking: `MaskingSseBody` rewrites text deltas of an SSE response so regex
+//! guards with the `mask` action apply to streamed LLM responses.
+//!
+//! Unlike `GuardedSseBody`, which can only pass or block a window, masking has to change the
+//! text the client sees. To mask a value that is split across several deltas, the body:
+//!
+//! 1. Holds back the most recent text event, and the last `holdback` bytes of text received so
+//!    far. Text is only released once enough text follows it that any PII starting in the
+//!    released part is fully visible.
+//! 2. Moves the release point back to the start of any match that straddles it, so a match is
+//!    always masked as a whole.
+//! 3. Re-emits the held event with the masked, released text as its delta. Text therefore
+//!    shifts between events, but the concatenated output is the masked response.
+//!
+//! Any non-text event (or the end of the stream) flushes all held text first. Events that are
+//! not recognized as text deltas are forwarded unchanged.
+
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use bytes::{Bytes, BytesMut};
+use http_body::Frame;
+use itertools::Itertools;
+use pin_project_lite::pin_project;
+
+use super::streaming_guardrails::{OVERLAP_BYTES, tail_chars};
+use super::{Policy, RegexResult, RegexRule, RegexRules, pii};
+
+pin_project! {
+	// An `http_body::Body` wrapper that masks PII in streamed SSE text deltas.
+	pub struct MaskingSseBody {
+		#[pin]
+		inner: crate::http::Body,
+		masker: SseMasker,
+		buffer_limit: usize,
+		raw: BytesMut,
+		done: bool,
+	}
+}
+
+impl MaskingSseBody {
+	/// Create a new `MaskingSseBody` holding back [`OVERLAP_BYTES`] of text.
+	///
+	/// * `inner` – the (translated) upstream SSE body.
+	/// * `rules` – regex guards with the `mask` action, applied in order.
+	/// * `buffer_limit` – max bytes of an incomplete SSE event before it is forwarded as-is.
+	// We do actually return Self; just wrapped in an http_body::Body. The annotation silences a false positive from clippy about that.
+	#[allow(clippy::new_ret_no_self)]
+	pub fn new(
+		inner: crate::http::Body,
+		rules: Vec<RegexRules>,
+		buffer_limit: usize,
+	) -> crate::http::Body {
+		Self::with_holdback(inner, rules, buffer_limit, OVERLAP_BYTES)
+	}
+
+	/// Like [`MaskingSseBody::new`] but with an explicit number of held back text bytes.
+	pub fn with_holdback(
+		inner: crate::http::Body,
+		rules: Vec<RegexRules>,
+		buffer_limit: usize,
+		holdback: usize,
+	) -> crate::http::Body {
+		crate::http::Body::new(Self {
+			inner,
+			masker: SseMasker {
+				rules,
+				holdback,
+				pending_text: String::new(),
+				held_event: None,
+			},
+			buffer_limit,
+			raw: BytesMut::new(),
+			done: false,
+		})
+	}
+}
+
+impl http_body::Body for MaskingSseBody {
+	type Data = Bytes;
+	type Error = crate::http::Error;
+
+	fn poll_frame(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		let mut this = self.project();
+		loop {
+			if *this.done {
+				return Poll::Ready(None);
+			}
+			let mut out = BytesMut::new();
+			match ready!(this.inner.as_mut().poll_frame(cx)) {
+				Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+				Some(Ok(frame)) => {
+					let Some(data) = frame.data_ref() else {
+						return Poll::Ready(Some(Ok(frame)));
+					};
+					this.raw.extend_from_slice(data);
+					while let Some((end, terminator)) = next_event_end(this.raw) {
+						let event = this.raw.split_to(end + terminator);
+						this
+							.masker
+							.push_event(&event[..end], &event[end..], &mut out);
+					}
+					if this.raw.len() > *this.buffer_limit {
+						// An event this large is not something we can parse; forward it untouched.
+						this.masker.flush(&mut out);
+						out.extend_from_slice(&this.raw.split());
+					}
+				},
+				None => {
+					*this.done = true;
+					this.masker.flush(&mut out);
+					out.extend_from_slice(&this.raw.split());
+				},
+			}
+			if !out.is_empty() {
+				return Poll::Ready(Some(Ok(Frame::data(out.freeze()))));
+			}
+		}
+	}
+}
+
+/// Returns the end of the first complete SSE event in `buf` and the length of its terminator.
+fn next_event_end(buf: &[u8]) -> Option<(usize, usize)> {
+	let lf = buf.windows(2).position(|w| w == b"\n\n").map(|p| (p, 2));
+	let crlf = buf
+		.windows(4)
+		.position(|w| w == b"\r\n\r\n")
+		.map(|p| (p, 4));
+	match (lf, crlf) {
+		(Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+		(a, b) => a.or(b),
+	}
+}
+
+/// The SSE-level masking state, independent of how the body is polled.
+struct SseMasker {
+	rules: Vec<RegexRules>,
+	holdback: usize,
+	/// Text received but not yet released to the client.
+	pending_text: String,
+	/// The most recent text event; released text is emitted through it.
+	held_event: Option<TextEvent>,
+}
+
+impl SseMasker {
+	fn push_event(&mut self, event: &[u8], terminator: &[u8], out: &mut BytesMut) {
+		let Some(event) = TextEvent::parse(event) else {
+			self.flush(out);
+			out.extend_from_slice(event);
+			out.extend_from_slice(terminator);
+			return;
+		};
+		self.pending_text.push_str(event.text());
+		if let Some(mut held) = self.held_event.replace(event) {
+			let split = self.release_point();
+			let released: String = self.pending_text.drain(..split).collect();
+			held.set_text(self.mask(&released));
+			held.encode(out);
+		}
+	}
+
+	/// Emit all held text, masked, through the held event.
+	fn flush(&mut self, out: &mut BytesMut) {
+		let Some(mut held) = self.held_event.take() else {
+			return;
+		};
+		let text = std::mem::take(&mut self.pending_text);
+		held.set_text(self.mask(&text));
+		held.encode(out);
+	}
+
+	/// How much of the pending text can be released without cutting a match in two.
+	fn release_point(&self) -> usize {
+		let text = &self.pending_text;
+		let mut split = text.len() - tail_chars(text, self.holdback).len();
+		let matches = match_ranges(text, &self.rules);
+		while let Some(m) = matches.iter().find(|m| m.start < split && m.end > split) {
+			split = m.start;
+		}
+		split
+	}
+
+	fn mask(&self, text: &str) -> String {
+		let mut out = text.to_string();
+		for rules in &self.rules {
+			if let Some(RegexResult::Mask(masked)) = Policy::apply_prompt_guard_regex(&out, rules) {
+				out = masked;
+			}
+		}
+		out
+	}
+}
+
+/// Byte ranges in `text` matched by any of the rules.
+fn match_ranges(text: &str, rules: &[RegexRules]) -> Vec<Range<usize>> {
+	rules
+		.iter()
+		.flat_map(|r| &r.rules)
+		.flat_map(|rule| match rule {
+			RegexRule::Builtin { builtin, .. } => pii::recognizer(builtin.recognizer(), text)
+				.into_iter()
+				.map(|r| r.start..r.end)
+				.collect_vec(),
+			RegexRule::Regex { pattern, .. } => pattern.find_iter(text).map(|m| m.range()).collect_vec(),
+		})
+		.collect()
+}
+
+/// An SSE event carrying a text delta in one of the formats `GuardedSseBody` understands.
+struct TextEvent {
+	/// Non-`data` lines (`event:`, `id:`, ...), forwarded verbatim.
+	fields: Vec<String>,
+	data: serde_json::Value,
+}
+
+impl TextEvent {
+	fn parse(event: &[u8]) -> Option<Self> {
+		let event = std::str::from_utf8(event).ok()?;
+		let mut fields = Vec::new();
+		let mut data = Vec::new();
+		for line in event.lines() {
+			if let Some(d) = line.strip_prefix("data:") {
+				data.push(d.strip_prefix(' ').unwrap_or(d));
+			} else {
+				fields.push(line.to_string());
+			}
+		}
+		let data: serde_json::Value = serde_json::from_str(&data.join("\n")).ok()?;
+		let event = TextEvent { fields, data };
+		event.text_field().is_some().then_some(event)
+	}
+
+	fn text_field(&self) -> Option<&str> {
+		let v = &self.data;
+		// OpenAI responses: response.output_text.delta
+		if v.get("type").and_then(|t| t.as_str()) == Some("response.output_text.delta") {
+			return v.get("delta").and_then(|s| s.as_str());
+		}
+		// OpenAI completions: choices[0].delta.content
+		if let Some(text) = v
+			.get("choices")
+			.and_then(|c| c.get(0))
+			.and_then(|c| c.get("delta"))
+			.and_then(|d| d.get("content"))
+			.and_then(|s| s.as_str())
+		{
+			return Some(text);
+		}
+		// Anthropic messages: delta.text
+		v.get("delta")
+			.and_then(|d| d.get("text"))
+			.and_then(|s| s.as_str())
+	}
+
+	fn text(&self) -> &str {
+		self.text_field().unwrap_or_default()
+	}
+
+	fn set_text(&mut self, text: String) {
+		let v = &mut self.data;
+		let field = if v.get("type").and_then(|t| t.as_str()) == Some("response.output_text.delta") {
+			v.get_mut("delta")
+		} else if v
+			.pointer("/choices/0/delta/content")
+			.is_some_and(|c| c.is_string())
+		{
+			v.pointer_mut("/choices/0/delta/content")
+		} else {
+			v.pointer_mut("/delta/text")
+		};
+		if let Some(field) = field {
+			*field = serde_json::Value::String(text);
+		}
+	}
+
+	fn encode(&self, out: &mut BytesMut) {
+		for field in &self.fields {
+			out.extend_from_slice(field.as_bytes());
+			out.extend_from_slice(b"\n");
+		}
+		out.extend_from_slice(b"data: ");
+		out.extend_from_slice(self.data.to_string().as_bytes());
+		out.extend_from_slice(b"\n\n");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use http_body_util::BodyExt as _;
+
+	use super::*;
+	use crate::llm::policy::{Action, Builtin};
+
+	fn delta_bytes(text: &str) -> Bytes {
+		Bytes::from(format!(
+			"data: {}\n\n",
+			serde_json::json!({"choices": [{"delta": {"content": text}}]})
+		))
+	}
+
+	fn make_body(chunks: Vec<Bytes>) -> crate::http::Body {
+		use std::convert::Infallible;
+
+		use futures_util::stream;
+		let stream = stream::iter(chunks.into_iter().map(Ok::<Bytes, Infallible>));
+		crate::http::Body::from_stream(stream)
+	}
+
+	fn email_rules() -> Vec<RegexRules> {
+		vec![RegexRules {
+			action: Action::Mask,
+			rules: vec![RegexRule::Builtin {
+				builtin: Builtin::Email,
+				mask: None,
+			}],
+		}]
+	}
+
+	/// Concatenate the text deltas of every event in an SSE body.
+	fn streamed_text(bytes: &[u8]) -> String {
+		let mut text = String::new();
+		let mut rest = bytes;
+		while let Some((end, terminator)) = next_event_end(rest) {
+			if let Some(event) = TextEvent::parse(&rest[..end]) {
+				text.push_str(event.text());
+			}
+			rest = &rest[end + terminator..];
+		}
+		text
+	}
+
+	#[tokio::test]
+	async fn masks_email_split_across_chunks() {
+		let body = make_body(vec![
+			delta_bytes("contact me at alice@exa"),
+			delta_bytes("mple.com please"),
+			Bytes::from_static(b"data: [DONE]\n\n"),
+		]);
+
+		let masked = MaskingSseBody::new(body, email_rules(), 1024 * 1024);
+		let bytes = masked.collect().await.unwrap().to_bytes();
+
+		assert_eq!(
+			streamed_text(&bytes),
+			"contact me at <EMAIL_ADDRESS> please"
+		);
+		assert!(bytes.ends_with(b"data: [DONE]\n\n"));
+	}
+
+	#[tokio::test]
+	async fn releases_text_without_cutting_a_match() {
+		// With a tiny holdback the release point lands inside the email; it must be moved back so
+		// the address is only emitted once fully visible, and masked as a whole.
+		let body = make_body(vec![
+			delta_bytes("mail alice@exa"),
+			delta_bytes("mple.com"),
+			delta_bytes(" now thanks"),
+		]);
+
+		let masked = MaskingSseBody::with_holdback(body, email_rules(), 1024 * 1024, 4);
+		let bytes = masked.collect().await.unwrap().to_bytes();
+
+		assert_eq!(streamed_text(&bytes), "mail <EMAIL_ADDRESS> now thanks");
+		assert!(!String::from_utf8_lossy(&bytes).contains("alice"));
+	}
+
+	#[tokio::test]
+	async fn forwards_non_text_events_unchanged() {
+		let start = Bytes::from_static(b"event: message_start\ndata: {\"type\":\"message_start\"}\n\n");
+		let delta = Bytes::from(format!(
+			"event: content_block_delta\ndata: {}\n\n",
+			serde_json::json!({"type": "content_block_delta", "delta": {"type": "text_delta", "text": "bob@example.com"}})
+		));
+		let stop = Bytes::from_static(b"event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n");
+		let body = make_body(vec![start.clone(), delta, stop.clone()]);
+
+		let masked = MaskingSseBody::new(body, email_rules(), 1024 * 1024);
+		let bytes = masked.collect().await.unwrap().to_bytes();
+
+		assert!(bytes.starts_with(&start));
+		assert!(bytes.ends_with(&stop));
+		assert_eq!(streamed_text(&bytes), "<EMAIL_ADDRESS>");
+		assert!(String::from_utf8_lossy(&bytes).contains("event: content_block_delta\n"));
+	}
+}
@@ -7,16 +7,20 @@ use crate::types::agent::HeaderValueMatch;
 /// must not additionally record `Allow`.
 #[tokio::test]
 async fn webhook_fail_open_emits_single_metric() {
-	use crate::telemetry::metrics::{GuardrailAction, GuardrailLabels, GuardrailPhase};
+	use crate::telemetry::metrics::{
+		GuardrailAction, GuardrailKind, GuardrailLabels, GuardrailPhase,
+	};
 	use crate::types::agent::SimpleBackendReference;
 
 	let guard = PromptGuard {
 		streaming: Default::default(),
 		request: vec![RequestGuard {
 			rejection: Default::default(),
+			mode: Default::default(),
 			kind: RequestGuardKind::Webhook(Webhook {
 				target: SimpleBackendReference::Invalid,
 				forward_header_matches: vec![],
+				forward_header_names: vec![],
 				failure_mode: FailureMode::FailOpen,
 			}),
 		}],
@@ -35,7 +39,9 @@ async fn webhook_fail_open_emits_single_metric() {
 		.guardrail_checks
 		.get_or_create(&GuardrailLabels {
 			phase: GuardrailPhase::Request,
+			kind: GuardrailKind::Webhook,
 			action: GuardrailAction::FailOpen,
+			entity_type: Default::default(),
 		})
 		.get();
 	let allow = client
@@ -44,7 +50,9 @@ async fn webhook_fail_open_emits_single_metric() {
 		.guardrail_checks
 		.get_or_create(&GuardrailLabels {
 			phase: GuardrailPhase::Request,
+			kind: GuardrailKind::Webhook,
 			action: GuardrailAction::Allow,
+			entity_type: Default::default(),
 		})
 		.get();
 
@@ -55,6 +63,279 @@ async fn webhook_fail_open_emits_single_metric() {
 	);
 }
 
+/// In audit mode a regex guard must leave the request untouched, but record what it detected.
+#[tokio::test]
+async fn regex_audit_mode_logs_without_masking() {
+	use crate::telemetry::metrics::{
+		GuardrailAction, GuardrailKind, GuardrailLabels, GuardrailPhase,
+	};
+
+	let policy = Policy {
+		prompt_guard: Some(PromptGuard {
+			streaming: Default::default(),
+			request: vec![RequestGuard {
+				rejection: Default::default(),
+				mode: GuardMode::Audit,
+				kind: RequestGuardKind::Regex(RegexRules {
+					action: Action::Mask,
+					rules: vec![RegexRule::Builtin {
+						builtin: Builtin::Email,
+						mask: None,
+					}],
+				}),
+			}],
+			response: vec![],
+		}),
+		..Default::default()
+	};
+	let mut req: crate::llm::types::completions::Request =
+		serde_json::from_value(serde_json::json!({
+			"model": "gpt-4o",
+			"messages": [{"role": "user", "content": "contact me at test@example.com"}]
+		}))
+		.unwrap();
+
+	let backend_info = crate::http::auth::BackendInfo {
+		target: crate::types::agent::BackendTarget::Invalid,
+		call_target: crate::types::agent::Target::Hostname(crate::strng::new("unused"), 0),
+		inputs: crate::test_helpers::proxymock::setup_proxy_test("{}")
+			.unwrap()
+			.inputs(),
+	};
+	let metrics = backend_info.inputs.metrics.clone();
+	let mut log = crate::test_helpers::request_log(metrics.clone());
+	let rejected = policy
+		.apply_prompt_guard(
+			&backend_info,
+			&mut req,
+			&mut HeaderMap::new(),
+			None,
+			&mut Some(&mut log),
+		)
+		.await
+		.unwrap();
+	assert!(rejected.is_none(), "audit mode must not reject");
+	assert_eq!(
+		req.get_messages()[0].content.as_str(),
+		"contact me at test@example.com"
+	);
+	assert_eq!(log.guardrail_findings, vec!["EMAIL_ADDRESS".to_string()]);
+
+	let audited = metrics
+		.guardrail_checks
+		.get_or_create(&GuardrailLabels {
+			phase: GuardrailPhase::Request,
+			kind: GuardrailKind::Regex,
+			action: GuardrailAction::Audit,
+			entity_type: Some(crate::strng::new("EMAIL_ADDRESS")).into(),
+		})
+		.get();
+	assert_eq!(audited, 1);
+}
+
+/// Masking an SSN with a regex guard records a `mask` check labeled with the guard kind and
+/// the detected entity type.
+#[tokio::test]
+async fn regex_mask_records_guardrail_check() {
+	use crate::telemetry::metrics::{
+		GuardrailAction, GuardrailKind, GuardrailLabels, GuardrailPhase,
+	};
+
+	let policy = Policy {
+		prompt_guard: Some(PromptGuard {
+			streaming: Default::default(),
+			request: vec![RequestGuard {
+				rejection: Default::default(),
+				mode: Default::default(),
+				kind: RequestGuardKind::Regex(RegexRules {
+					action: Action::Mask,
+					rules: vec![RegexRule::Builtin {
+						builtin: Builtin::Ssn,
+						mask: None,
+					}],
+				}),
+			}],
+			response: vec![],
+		}),
+		..Default::default()
+	};
+	let mut req: crate::llm::types::completions::Request =
+		serde_json::from_value(serde_json::json!({
+			"model": "gpt-4o",
+			"messages": [{"role": "user", "content": "my ssn is 123-45-6789"}]
+		}))
+		.unwrap();
+
+	let backend_info = crate::http::auth::BackendInfo {
+		target: crate::types::agent::BackendTarget::Invalid,
+		call_target: crate::types::agent::Target::Hostname(crate::strng::new("unused"), 0),
+		inputs: crate::test_helpers::proxymock::setup_proxy_test("{}")
+			.unwrap()
+			.inputs(),
+	};
+	let metrics = backend_info.inputs.metrics.clone();
+	let rejected = policy
+		.apply_prompt_guard(
+			&backend_info,
+			&mut req,
+			&mut HeaderMap::new(),
+			None,
+			&mut None,
+		)
+		.await
+		.unwrap();
+	assert!(rejected.is_none());
+	assert_eq!(req.get_messages()[0].content.as_str(), "my ssn is <SSN>");
+
+	let checks = |action, entity_type: Option<&str>| {
+		metrics
+			.guardrail_checks
+			.get_or_create(&GuardrailLabels {
+				phase: GuardrailPhase::Request,
+				kind: GuardrailKind::Regex,
+				action,
+				entity_type: entity_type.map(crate::strng::new).into(),
+			})
+			.get()
+	};
+	assert_eq!(checks(GuardrailAction::Mask, Some("SSN")), 1);
+	assert_eq!(checks(GuardrailAction::Mask, None), 0);
+	assert_eq!(checks(GuardrailAction::Allow, None), 0);
+}
+
+/// Response guards record their checks under the response phase, with the detected entity type.
+#[tokio::test]
+async fn response_regex_records_guardrail_checks() {
+	use crate::telemetry::metrics::{
+		GuardrailAction, GuardrailKind, GuardrailLabels, GuardrailPhase,
+	};
+
+	let guards = vec![ResponseGuard {
+		rejection: Default::default(),
+		kind: ResponseGuardKind::Regex(RegexRules {
+			action: Action::Reject,
+			rules: vec![RegexRule::Builtin {
+				builtin: Builtin::Ssn,
+				mask: None,
+			}],
+		}),
+	}];
+	let client = crate::test_helpers::policy_client();
+	let checks = |action, entity_type: Option<&str>| {
+		client
+			.inputs
+			.metrics
+			.guardrail_checks
+			.get_or_create(&GuardrailLabels {
+				phase: GuardrailPhase::Response,
+				kind: GuardrailKind::Regex,
+				action,
+				entity_type: entity_type.map(crate::strng::new).into(),
+			})
+			.get()
+	};
+
+	let mut clean = TextResponse {
+		content: "nothing to see here".to_string(),
+	};
+	let rejected =
+		Policy::apply_response_prompt_guard(&client, &mut clean, &HeaderMap::new(), &guards)
+			.await
+			.unwrap();
+	assert!(rejected.is_none());
+	assert_eq!(checks(GuardrailAction::Allow, None), 1);
+
+	let mut leaky = TextResponse {
+		content: "the ssn is 123-45-6789".to_string(),
+	};
+	let rejected =
+		Policy::apply_response_prompt_guard(&client, &mut leaky, &HeaderMap::new(), &guards)
+			.await
+			.unwrap();
+	assert!(rejected.is_some());
+	assert_eq!(checks(GuardrailAction::Reject, Some("SSN")), 1);
+	assert_eq!(checks(GuardrailAction::Allow, None), 1);
+}
+
+/// A webhook may pass a request while asking for headers to be added before it goes upstream.
+#[tokio::test]
+async fn webhook_pass_applies_request_headers() {
+	use wiremock::{Mock, MockServer, ResponseTemplate};
+
+	use crate::types::agent::{SimpleBackendReference, Target};
+
+	let mock = MockServer::start().await;
+	Mock::given(wiremock::matchers::path("/request"))
+		.respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+			"action": {
+				"reason": "classified",
+				"headers": {
+					"set": {"x-classification": "safe"},
+					"remove": ["x-remove-me"]
+				}
+			}
+		})))
+		.mount(&mock)
+		.await;
+
+	let policy = Policy {
+		prompt_guard: Some(PromptGuard {
+			streaming: Default::default(),
+			request: vec![RequestGuard {
+				rejection: Default::default(),
+				mode: Default::default(),
+				kind: RequestGuardKind::Webhook(Webhook {
+					target: SimpleBackendReference::InlineBackend(Target::Address(*mock.address())),
+					forward_header_matches: vec![],
+					forward_header_names: vec![],
+					failure_mode: FailureMode::FailClosed,
+				}),
+			}],
+			response: vec![],
+		}),
+		..Default::default()
+	};
+	let mut req: crate::llm::types::completions::Request =
+		serde_json::from_value(serde_json::json!({
+			"model": "gpt-4o",
+			"messages": [{"role": "user", "content": "hello"}]
+		}))
+		.unwrap();
+	let backend_info = crate::http::auth::BackendInfo {
+		target: crate::types::agent::BackendTarget::Invalid,
+		call_target: Target::Hostname(crate::strng::new("unused"), 0),
+		inputs: crate::test_helpers::proxymock::setup_proxy_test("{}")
+			.unwrap()
+			.inputs(),
+	};
+	let mut headers = HeaderMap::new();
+	headers.insert("x-remove-me", HeaderValue::from_static("1"));
+
+	let rejected = policy
+		.apply_prompt_guard(&backend_info, &mut req, &mut headers, None, &mut None)
+		.await
+		.unwrap();
+	assert!(rejected.is_none(), "pass action must not reject");
+	assert_eq!(headers.get("x-classification").unwrap(), "safe");
+	assert!(headers.get("x-remove-me").is_none());
+	assert_eq!(req.get_messages()[0].content.as_str(), "hello");
+}
+
+#[test]
+fn test_guard_mode_deserialization() {
+	let guard: RequestGuard = serde_json::from_value(serde_json::json!({
+		"mode": "audit",
+		"regex": {"rules": [{"builtin": "email"}]}
+	}))
+	.unwrap();
+	assert_eq!(guard.mode, GuardMode::Audit);
+
+	let guard: RequestGuard =
+		serde_json::from_value(serde_json::json!({"regex": {"rules": [{"builtin": "email"}]}}))
+			.unwrap();
+	assert_eq!(guard.mode, GuardMode::Enforce);
+}
+
 #[test]
 fn test_get_webhook_forward_headers() {
 	let mut headers = HeaderMap::new();
@@ -96,7 +377,7 @@ fn test_get_webhook_forward_headers() {
 		},
 	];
 
-	let result = Policy::get_webhook_forward_headers(&headers, &header_matches);
+	let result = Policy::get_webhook_forward_headers(&headers, &header_matches, &[]);
 
 	assert_eq!(result.len(), 3);
 	assert_eq!(
@@ -117,6 +398,35 @@ fn test_get_webhook_forward_headers() {
 	assert!(!result.contains_key("x-comma-header"));
 }
 
+#[test]
+fn test_get_webhook_forward_headers_by_name_pattern() {
+	let mut headers = HeaderMap::new();
+	headers.insert("x-tenant-id", HeaderValue::from_static("acme"));
+	headers.insert("x-tenant-region", HeaderValue::from_static("eu-west"));
+	headers.insert("x-internal-secret", HeaderValue::from_static("hunter2"));
+	headers.insert("x-tenant", HeaderValue::from_static("no-suffix"));
+
+	let result = Policy::get_webhook_forward_headers(&headers, &[], &[strng::literal!("X-Tenant-*")]);
+
+	assert_eq!(result.len(), 2);
+	assert_eq!(result.get("x-tenant-id").unwrap(), "acme");
+	assert_eq!(result.get("x-tenant-region").unwrap(), "eu-west");
+	assert!(!result.contains_key("x-internal-secret"));
+	assert!(!result.contains_key("x-tenant"));
+}
+
+#[test]
+fn test_header_name_matches() {
+	assert!(header_name_matches("x-tenant-*", "x-tenant-id"));
+	assert!(header_name_matches("*-id", "x-tenant-id"));
+	assert!(header_name_matches("x-*-id", "x-tenant-id"));
+	assert!(header_name_matches("x-tenant-id", "x-tenant-id"));
+	assert!(header_name_matches("*", "anything"));
+	assert!(!header_name_matches("x-tenant-id", "x-tenant-idx"));
+	assert!(!header_name_matches("x-*-id", "x-id"));
+	assert!(!header_name_matches("x-tenant-*", "x-internal-secret"));
+}
+
 #[test]
 fn test_rejection_with_json_headers() {
 	let rejection = RequestRejection {
@@ -303,6 +613,65 @@ fn test_prompt_caching_with_offset() {
 	assert_eq!(caching.cache_message_offset, 4);
 }
 
+#[test]
+fn test_prompt_enrichment_normalizes_roles() {
+	let policy: Policy = serde_json::from_value(serde_json::json!({
+		"prompts": {
+			"prepend": [{"role": "Human", "content": "be brief"}],
+			"append": [{"role": "system", "content": "answer in JSON"}]
+		}
+	}))
+	.unwrap();
+	let mut req: crate::llm::types::completions::Request =
+		serde_json::from_value(serde_json::json!({
+			"messages": [{"role": "user", "content": "hello"}]
+		}))
+		.unwrap();
+	policy.apply_prompt_enrichment(&mut req);
+	let roles: Vec<_> = req
+		.get_messages()
+		.into_iter()
+		.map(|m| m.role.to_string())
+		.collect();
+	assert_eq!(roles, vec!["user", "user", "system"]);
+}
+
+#[test]
+fn test_prompt_enrichment_rejects_unknown_roles() {
+	let err = serde_json::from_value::<Policy>(serde_json::json!({
+		"prompts": {
+			"prepend": [{"role": "assistent", "content": "typo"}]
+		}
+	}))
+	.unwrap_err();
+	assert!(err.to_string().contains("unknown message role"), "{err}");
+}
+
+#[test]
+fn test_client_roles_normalized() {
+	let mut req: crate::llm::types::completions::Request =
+		serde_json::from_value(serde_json::json!({
+			"messages": [
+				{"role": "System", "content": "be brief"},
+				{"role": "human", "content": "hello"}
+			]
+		}))
+		.unwrap();
+	req.normalize_roles().unwrap();
+	let roles: Vec<_> = req.messages.iter().map(|m| m.role.as_str()).collect();
+	assert_eq!(roles, vec!["system", "user"]);
+
+	let mut req: crate::llm::types::completions::Request =
+		serde_json::from_value(serde_json::json!({
+			"messages": [{"role": "narrator", "content": "hello"}]
+		}))
+		.unwrap();
+	assert!(matches!(
+		req.normalize_roles(),
+		Err(AIError::UnsupportedContent)
+	));
+}
+
 #[test]
 fn test_resolve_route() {
 	let mut routes = IndexMap::new();
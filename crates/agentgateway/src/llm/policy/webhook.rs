@@ -3,6 +3,7 @@ use ::http::{HeaderMap, HeaderValue, header};
 pub use agent_llm::webhook::{Message, ResponseChoice};
 use serde::{Deserialize, Serialize};
 
+use crate::http::filters::HeaderModifier;
 use crate::llm::policy::with_default_timeout;
 use crate::proxy::httpproxy::PolicyClient;
 use crate::telemetry::metrics::{OutboundCallKind, OutboundCallSubtype};
@@ -68,6 +69,10 @@ pub struct PassAction {
 	/// reason is a human readable string that explains the reason for the action.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub reason: Option<String>,
+	/// headers are added, set, or removed on the request before it is sent upstream.
+	/// Only applied to prompt (request) guards.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub headers: Option<HeaderModifier>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +85,10 @@ pub struct MaskAction {
 	/// reason is a human readable string that explains the reason for the action.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub reason: Option<String>,
+	/// headers are added, set, or removed on the request before it is sent upstream.
+	/// Only applied to prompt (request) guards.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub headers: Option<HeaderModifier>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
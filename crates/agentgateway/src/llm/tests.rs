@@ -30,6 +30,7 @@ fn streaming_amend_on_drop_updates_local_rate_limit() {
 			tokens_per_fill: 10,
 			fill_interval: std::time::Duration::from_secs(60),
 			limit_type: crate::http::localratelimit::RateLimitType::Tokens,
+			key: None,
 		})
 		.unwrap();
 	let log = AsyncLog::default();
@@ -163,7 +164,7 @@ async fn openai_provider_normalizes_max_tokens_before_forwarding() {
 		llm_request,
 		..
 	} = provider
-		.process_completions_request(&backend_info, None, req, false, &mut None)
+		.process_completions_request(&backend_info, None, req, Tokenize::Disabled, &mut None)
 		.await
 		.expect("OpenAI completions request should process")
 	else {
@@ -179,6 +180,172 @@ async fn openai_provider_normalizes_max_tokens_before_forwarding() {
 	assert_eq!(llm_request.params.max_tokens, Some(1024));
 }
 
+#[tokio::test]
+async fn openai_passthrough_preserves_unknown_request_fields() {
+	use crate::http::auth::BackendInfo;
+	use crate::llm::policy::Policy;
+	use crate::test_helpers::proxymock::setup_proxy_test;
+	use crate::types::agent::BackendTarget;
+
+	let provider = AIProvider::OpenAI(openai::Provider { model: None });
+	let inputs = setup_proxy_test("{}").unwrap().pi;
+	let backend_info = BackendInfo {
+		target: BackendTarget::Invalid,
+		call_target: Target::from(("api.openai.com", 443)),
+		inputs,
+	};
+	// Overrides force the request through the JSON mutation path in `unmarshal_request`.
+	let policy = Policy {
+		overrides: Some(std::collections::HashMap::from([(
+			"temperature".to_string(),
+			json!(0.5),
+		)])),
+		..Default::default()
+	};
+	let req = ::http::Request::builder()
+		.uri("/v1/chat/completions")
+		.header(::http::header::CONTENT_TYPE, "application/json")
+		.body(Body::from(
+			br#"{
+				"model": "gpt-4o",
+				"messages": [{"role": "user", "content": "hello"}],
+				"some_new_param": {"enabled": true}
+			}"#
+				.to_vec(),
+		))
+		.unwrap();
+
+	let RequestResult::Success {
+		request: forwarded, ..
+	} = provider
+		.process_completions_request(
+			&backend_info,
+			Some(&policy),
+			req,
+			Tokenize::Disabled,
+			&mut None,
+		)
+		.await
+		.expect("OpenAI completions request should process")
+	else {
+		panic!("expected forwarded request");
+	};
+
+	let forwarded_body = forwarded.collect().await.unwrap().to_bytes();
+	let forwarded_json: Value =
+		serde_json::from_slice(&forwarded_body).expect("forwarded request should be JSON");
+
+	assert_eq!(forwarded_json["some_new_param"], json!({"enabled": true}));
+	assert_eq!(forwarded_json["temperature"], json!(0.5));
+}
+
+async fn forwarded_stream_options(policy: Option<&crate::llm::policy::Policy>) -> Option<Value> {
+	use crate::http::auth::BackendInfo;
+	use crate::test_helpers::proxymock::setup_proxy_test;
+	use crate::types::agent::BackendTarget;
+
+	let provider = AIProvider::OpenAI(openai::Provider { model: None });
+	let backend_info = BackendInfo {
+		target: BackendTarget::Invalid,
+		call_target: Target::from(("api.openai.com", 443)),
+		inputs: setup_proxy_test("{}").unwrap().pi,
+	};
+	let req = ::http::Request::builder()
+		.uri("/v1/chat/completions")
+		.header(::http::header::CONTENT_TYPE, "application/json")
+		.body(Body::from(
+			br#"{"model": "gpt-4o", "stream": true, "messages": [{"role": "user", "content": "hello"}]}"#
+				.to_vec(),
+		))
+		.unwrap();
+
+	let RequestResult::Success {
+		request: forwarded, ..
+	} = provider
+		.process_completions_request(&backend_info, policy, req, Tokenize::Disabled, &mut None)
+		.await
+		.expect("OpenAI completions request should process")
+	else {
+		panic!("expected forwarded request");
+	};
+	let forwarded_body = forwarded.collect().await.unwrap().to_bytes();
+	let forwarded_json: Value =
+		serde_json::from_slice(&forwarded_body).expect("forwarded request should be JSON");
+	forwarded_json.get("stream_options").cloned()
+}
+
+#[tokio::test]
+async fn streaming_completions_request_usage_by_default() {
+	assert_eq!(
+		forwarded_stream_options(None).await,
+		Some(json!({"include_usage": true}))
+	);
+}
+
+#[tokio::test]
+async fn streaming_completions_request_not_mutated_when_usage_not_forced() {
+	let policy = crate::llm::policy::Policy {
+		force_stream_usage: Some(false),
+		..Default::default()
+	};
+	assert_eq!(forwarded_stream_options(Some(&policy)).await, None);
+}
+
+#[tokio::test]
+async fn request_body_size_metric_observes_body_length() {
+	use frozen_collections::FzHashSet;
+	use prometheus_client::registry::Registry;
+
+	use crate::http::auth::BackendInfo;
+	use crate::telemetry::metrics::Metrics;
+	use crate::test_helpers::proxymock::setup_proxy_test;
+	use crate::types::agent::BackendTarget;
+
+	let provider = AIProvider::OpenAI(openai::Provider { model: None });
+	let inputs = setup_proxy_test("{}").unwrap().pi;
+	let backend_info = BackendInfo {
+		target: BackendTarget::Invalid,
+		call_target: Target::from(("api.openai.com", 443)),
+		inputs,
+	};
+	let body = br#"{"model": "gpt-4o", "messages": [{"role": "user", "content": "hello"}]}"#;
+	let req = ::http::Request::builder()
+		.uri("/v1/chat/completions")
+		.header(::http::header::CONTENT_TYPE, "application/json")
+		.body(Body::from(body.to_vec()))
+		.unwrap();
+
+	let mut registry = Registry::default();
+	let metrics = Arc::new(Metrics::new(&mut registry, FzHashSet::default()));
+	let mut rl = crate::test_helpers::request_log(metrics);
+	provider
+		.process_completions_request(
+			&backend_info,
+			None,
+			req,
+			Tokenize::Disabled,
+			&mut Some(&mut rl),
+		)
+		.await
+		.expect("OpenAI completions request should process");
+
+	let mut encoded = String::new();
+	prometheus_client::encoding::text::encode(&mut encoded, &registry).unwrap();
+	let sample = |suffix: &str| {
+		let prefix = format!(
+			"gen_ai_request_body_bytes_{suffix}{{gen_ai_system=\"openai\",gen_ai_input_format=\"Completions\"}} "
+		);
+		encoded
+			.lines()
+			.find_map(|l| l.strip_prefix(prefix.as_str()))
+			.unwrap_or_else(|| panic!("missing {prefix} in:\n{encoded}"))
+			.parse::<f64>()
+			.unwrap()
+	};
+	assert_eq!(sample("count"), 1.0);
+	assert_eq!(sample("sum"), body.len() as f64);
+}
+
 #[tokio::test]
 async fn openai_provider_normalizes_max_tokens_after_model_alias() {
 	use crate::http::auth::BackendInfo;
@@ -218,7 +385,13 @@ async fn openai_provider_normalizes_max_tokens_after_model_alias() {
 		llm_request,
 		..
 	} = provider
-		.process_completions_request(&backend_info, Some(&policy), req, false, &mut None)
+		.process_completions_request(
+			&backend_info,
+			Some(&policy),
+			req,
+			Tokenize::Disabled,
+			&mut None,
+		)
 		.await
 		.expect("OpenAI completions request should process")
 	else {
@@ -267,7 +440,7 @@ async fn openai_provider_preserves_max_tokens_for_non_gpt_models() {
 		llm_request,
 		..
 	} = provider
-		.process_completions_request(&backend_info, None, req, false, &mut None)
+		.process_completions_request(&backend_info, None, req, Tokenize::Disabled, &mut None)
 		.await
 		.expect("OpenAI-compatible completions request should process")
 	else {
@@ -321,7 +494,7 @@ async fn count_tokens_resolves_model_alias_once_for_upstream_request() {
 		llm_request,
 		..
 	} = provider
-		.process_count_tokens_request(&backend_info, req, Some(&policy), &mut None)
+		.process_count_tokens_request(&backend_info, req, Some(&policy), None, &mut None)
 		.await
 		.expect("count_tokens request should process")
 	else {
@@ -379,7 +552,7 @@ async fn count_tokens_uses_native_endpoint_after_model_alias() {
 		upstream_route_type,
 		..
 	} = provider
-		.process_count_tokens_request(&backend_info, req, Some(&policy), &mut None)
+		.process_count_tokens_request(&backend_info, req, Some(&policy), None, &mut None)
 		.await
 		.expect("count_tokens request should process")
 	else {
@@ -395,6 +568,46 @@ async fn count_tokens_uses_native_endpoint_after_model_alias() {
 	assert_eq!(llm_request.request_model, "claude-3-5-sonnet");
 }
 
+#[tokio::test]
+async fn count_tokens_counts_locally_for_openai() {
+	use crate::http::auth::BackendInfo;
+	use crate::test_helpers::proxymock::setup_proxy_test;
+	use crate::types::agent::BackendTarget;
+
+	let provider = AIProvider::OpenAI(openai::Provider { model: None });
+	let inputs = setup_proxy_test("{}").unwrap().pi;
+	let backend_info = BackendInfo {
+		target: BackendTarget::Invalid,
+		call_target: Target::from(("api.openai.com", 443)),
+		inputs,
+	};
+	let req = ::http::Request::builder()
+		.uri("/v1/messages/count_tokens")
+		.header(::http::header::CONTENT_TYPE, "application/json")
+		.body(Body::from(
+			br#"{
+				"model": "gpt-4o",
+				"messages": [{"role": "user", "content": "hello"}]
+			}"#
+				.to_vec(),
+		))
+		.unwrap();
+
+	let RequestResult::Rejected(resp) = provider
+		.process_count_tokens_request(&backend_info, req, None, None, &mut None)
+		.await
+		.expect("count_tokens request should process")
+	else {
+		panic!("expected a local count_tokens response");
+	};
+
+	assert_eq!(resp.status(), ::http::StatusCode::OK);
+	let body = resp.into_body().collect().await.unwrap().to_bytes();
+	let body: Value = serde_json::from_slice(&body).expect("count_tokens response should be JSON");
+	// 3 tokens of per-message overhead, 1 for the role, 1 for "hello", and 3 to prime the reply.
+	assert_eq!(body, json!({"input_tokens": 8}));
+}
+
 #[tokio::test]
 async fn vertex_anthropic_messages_prepares_vertex_body() {
 	use crate::http::auth::BackendInfo;
@@ -430,7 +643,7 @@ async fn vertex_anthropic_messages_prepares_vertex_body() {
 		upstream_route_type,
 		..
 	} = provider
-		.process_messages_request(&backend_info, None, req, false, &mut None)
+		.process_messages_request(&backend_info, None, req, Tokenize::Disabled, &mut None)
 		.await
 		.expect("Vertex Anthropic messages request should process")
 	else {
@@ -449,6 +662,80 @@ async fn vertex_anthropic_messages_prepares_vertex_body() {
 	);
 }
 
+#[tokio::test]
+async fn bedrock_metadata_expressions_populate_request_metadata() {
+	use secrecy::SecretString;
+
+	use crate::http::auth::BackendInfo;
+	use crate::http::jwt::Claims;
+	use crate::test_helpers::proxymock::setup_proxy_test;
+	use crate::types::agent::BackendTarget;
+
+	let mut bedrock = BedrockProvider::new(bedrock::Provider {
+		model: Some(strng::new("anthropic.claude-3-5-sonnet-20240620-v1:0")),
+		region: strng::new("us-east-1"),
+		guardrail_identifier: None,
+		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
+	});
+	bedrock.metadata_expressions = HashMap::from([
+		(
+			"user".to_string(),
+			Arc::new(crate::cel::Expression::new_strict("jwt.sub").unwrap()),
+		),
+		(
+			"object".to_string(),
+			Arc::new(crate::cel::Expression::new_strict("jwt").unwrap()),
+		),
+	]);
+	let provider = AIProvider::Bedrock(bedrock);
+	let inputs = setup_proxy_test("{}").unwrap().pi;
+	let backend_info = BackendInfo {
+		target: BackendTarget::Invalid,
+		call_target: Target::from(("bedrock-runtime.us-east-1.amazonaws.com", 443)),
+		inputs,
+	};
+	let mut req = ::http::Request::builder()
+		.uri("/v1/chat/completions")
+		.header(::http::header::CONTENT_TYPE, "application/json")
+		.header(
+			"x-bedrock-metadata",
+			r#"{"team": "infra", "user": "spoofed"}"#,
+		)
+		.body(Body::from(
+			br#"{"model": "claude", "messages": [{"role": "user", "content": "hi"}]}"#.to_vec(),
+		))
+		.unwrap();
+	let serde_json::Value::Object(claims) = json!({"sub": "alice"}) else {
+		unreachable!()
+	};
+	req.extensions_mut().insert(Claims {
+		inner: claims,
+		jwt: SecretString::new("header.payload.signature".into()),
+	});
+
+	let RequestResult::Success {
+		request: forwarded, ..
+	} = provider
+		.process_completions_request(&backend_info, None, req, Tokenize::Disabled, &mut None)
+		.await
+		.expect("Bedrock completions request should process")
+	else {
+		panic!("expected forwarded request");
+	};
+
+	let forwarded_body = forwarded.collect().await.unwrap().to_bytes();
+	let forwarded_json: Value =
+		serde_json::from_slice(&forwarded_body).expect("forwarded request should be JSON");
+	// The configured expression wins over the header, non-scalar results are dropped, and
+	// header-only keys are kept.
+	assert_eq!(
+		forwarded_json["requestMetadata"],
+		json!({"team": "infra", "user": "alice"})
+	);
+}
+
 #[tokio::test]
 async fn provider_model_is_set_before_llm_transformations() {
 	use crate::http::auth::BackendInfo;
@@ -495,7 +782,13 @@ async fn provider_model_is_set_before_llm_transformations() {
 		llm_request,
 		..
 	} = provider
-		.process_completions_request(&backend_info, Some(&policy), req, false, &mut None)
+		.process_completions_request(
+			&backend_info,
+			Some(&policy),
+			req,
+			Tokenize::Disabled,
+			&mut None,
+		)
 		.await
 		.expect("OpenAI completions request should process")
 	else {
@@ -551,7 +844,13 @@ async fn llm_transformations_can_set_missing_model() {
 		llm_request,
 		..
 	} = provider
-		.process_completions_request(&backend_info, Some(&policy), req, false, &mut None)
+		.process_completions_request(
+			&backend_info,
+			Some(&policy),
+			req,
+			Tokenize::Disabled,
+			&mut None,
+		)
 		.await
 		.expect("OpenAI completions request should process")
 	else {
@@ -597,7 +896,7 @@ async fn copilot_anthropic_model_uses_messages_route() {
 		llm_request,
 		upstream_route_type,
 	} = provider
-		.process_messages_request(&backend_info, None, req, false, &mut None)
+		.process_messages_request(&backend_info, None, req, Tokenize::Disabled, &mut None)
 		.await
 		.expect("Copilot Anthropic messages request should process")
 	else {
@@ -631,6 +930,63 @@ async fn copilot_anthropic_model_uses_messages_route() {
 	assert_eq!(forwarded_json["max_tokens"], json!(64));
 }
 
+#[test]
+fn tokenizer_override_changes_input_tokens() {
+	let req: types::completions::Request = serde_json::from_value(json!({
+		"model": "gpt-4o",
+		"messages": [{"role": "user", "content": "你好，世界。今天天气很好，我们去公园散步吧。"}]
+	}))
+	.unwrap();
+	let input_tokens = |tokenize| {
+		req
+			.to_llm_request(strng::literal!("openai"), tokenize)
+			.unwrap()
+			.input_tokens
+	};
+
+	assert_eq!(input_tokens(Tokenize::Disabled), None);
+	let inferred = input_tokens(Tokenize::FromModel);
+	let overridden = input_tokens(Tokenize::With(Tokenizer::Gpt2));
+	assert!(inferred.is_some());
+	assert!(overridden.is_some());
+	assert_ne!(inferred, overridden);
+	// Explicitly selecting the tokenizer the model maps to is a no-op
+	assert_eq!(input_tokens(Tokenize::With(Tokenizer::O200kBase)), inferred);
+}
+
+#[test]
+fn named_provider_tokenization() {
+	let provider = NamedAIProvider {
+		name: "titan".into(),
+		provider: AIProvider::bedrock(bedrock::Provider {
+			model: Some(strng::new("amazon.titan-text-express-v1")),
+			region: strng::new("us-east-1"),
+			guardrail_identifier: None,
+			guardrail_version: None,
+			inference_profile: None,
+			latency_optimized: None,
+		}),
+		provider_backend: None,
+		host_override: None,
+		path_override: None,
+		path_prefix: None,
+		tokenize: true,
+		tokenizer: Some(Tokenizer::Cl100kBase),
+		response_buffer_limit: None,
+		client_tls: None,
+		inline_policies: vec![],
+	};
+	assert_eq!(
+		provider.tokenization(),
+		Tokenize::With(Tokenizer::Cl100kBase)
+	);
+	assert_eq!(
+		Tokenize::new(false, Some(Tokenizer::Gpt2)),
+		Tokenize::Disabled
+	);
+	assert_eq!(Tokenize::new(true, None), Tokenize::FromModel);
+}
+
 #[test]
 fn openai_token_limit_normalization_keeps_explicit_max_completion_tokens() {
 	let mut request: types::completions::Request = serde_json::from_value(json!({
@@ -785,6 +1141,74 @@ fn test_messages_output_config_format_maps_to_openai_response_format() {
 	);
 }
 
+#[tokio::test]
+async fn provider_response_buffer_limit_override() {
+	use crate::proxy::httpproxy::PolicyClient;
+	use crate::test_helpers::proxymock::setup_proxy_test;
+
+	let mut provider = NamedAIProvider {
+		name: "openai".into(),
+		provider: AIProvider::OpenAI(openai::Provider { model: None }),
+		provider_backend: None,
+		host_override: None,
+		path_override: None,
+		path_prefix: None,
+		tokenize: false,
+		tokenizer: None,
+		response_buffer_limit: None,
+		client_tls: None,
+		inline_policies: vec![],
+	};
+	let client = PolicyClient::new(setup_proxy_test("{}").unwrap().pi);
+	let process = async |provider: &NamedAIProvider| {
+		let req = LLMRequest {
+			input_tokens: None,
+			input_format: InputFormat::Completions,
+			cache_convention: CacheTokenConvention::pending(),
+			request_model: "gpt-4".into(),
+			provider: Default::default(),
+			streaming: false,
+			params: Default::default(),
+			prompt: None,
+			provider_state: None,
+		};
+		let body = json!({"error": {"message": "a".repeat(1024), "type": "invalid_request_error"}});
+		let mut resp = Response::new(Body::from(body.to_string()));
+		*resp.status_mut() = ::http::StatusCode::BAD_REQUEST;
+		resp.headers_mut().insert(
+			::http::header::CONTENT_TYPE,
+			"application/json".parse().unwrap(),
+		);
+		// A gateway-wide limit too small for the response
+		resp.extensions_mut().insert(http::BufferLimit::new(512));
+		provider.apply_response_buffer_limit(&mut resp);
+		provider
+			.provider
+			.process_response(
+				client.clone(),
+				req,
+				LLMResponsePolicies::default(),
+				None,
+				AsyncLog::default(),
+				false,
+				None,
+				None,
+				resp,
+			)
+			.await
+	};
+
+	assert!(matches!(
+		process(&provider).await,
+		Err(AIError::ResponseTooLarge)
+	));
+	provider.response_buffer_limit = Some(64 * 1024);
+	let resp = process(&provider)
+		.await
+		.expect("raised limit should allow buffering the response");
+	assert_eq!(resp.status(), ::http::StatusCode::BAD_REQUEST);
+}
+
 /// Verifies that `process_response` routes a non-success response through
 /// the buffered error path even when the request has `streaming: true`.
 ///
@@ -801,6 +1225,8 @@ async fn process_response_routes_streaming_error_to_buffered_path() {
 		region: strng::new("us-west-2"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	});
 
 	let error_json = r#"{"message":"Expected toolResult blocks at messages.2.content for the following Ids: tooluse_abc123"}"#;
@@ -836,6 +1262,7 @@ async fn process_response_routes_streaming_error_to_buffered_path() {
 			AsyncLog::default(),
 			false,
 			None,
+			None,
 			resp,
 		)
 		.await
@@ -936,6 +1363,8 @@ async fn process_streaming_bedrock_completions_normalizes_sse_headers_and_done()
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	});
 
 	let body = Body::from(
@@ -972,6 +1401,7 @@ async fn process_streaming_bedrock_completions_normalizes_sse_headers_and_done()
 			AsyncLog::default(),
 			false,
 			None,
+			None,
 			resp,
 		)
 		.expect("Bedrock streaming translation should succeed");
@@ -994,6 +1424,71 @@ async fn process_streaming_bedrock_completions_normalizes_sse_headers_and_done()
 	);
 }
 
+#[tokio::test]
+async fn process_streaming_records_response_body_size_when_stream_ends() {
+	use frozen_collections::FzHashSet;
+	use prometheus_client::registry::Registry;
+
+	use crate::proxy::httpproxy::PolicyClient;
+	use crate::telemetry::metrics::Metrics;
+	use crate::test_helpers::proxymock::setup_proxy_test;
+	let provider = AIProvider::OpenAI(openai::Provider { model: None });
+	let body = b"data: {\"choices\":[]}\n\ndata: [DONE]\n\n";
+	let mut resp = Response::new(Body::from(body.to_vec()));
+	resp.headers_mut().insert(
+		::http::header::CONTENT_TYPE,
+		"text/event-stream".parse().unwrap(),
+	);
+
+	let mut registry = Registry::default();
+	let metrics = Metrics::new(&mut registry, FzHashSet::default());
+	let client = PolicyClient::new(setup_proxy_test("{}").unwrap().pi);
+	let translated = provider
+		.process_streaming(
+			client,
+			LLMRequest {
+				input_tokens: None,
+				input_format: InputFormat::Detect,
+				cache_convention: CacheTokenConvention::pending(),
+				request_model: "input-model".into(),
+				provider: Default::default(),
+				streaming: true,
+				params: Default::default(),
+				prompt: None,
+				provider_state: None,
+			},
+			LLMResponsePolicies::default(),
+			None,
+			AsyncLog::default(),
+			false,
+			None,
+			Some(&metrics),
+			resp,
+		)
+		.expect("streaming passthrough should succeed");
+
+	let encode = || {
+		let mut encoded = String::new();
+		prometheus_client::encoding::text::encode(&mut encoded, &registry).unwrap();
+		encoded
+	};
+	let sample = |encoded: &str, suffix: &str| {
+		let prefix = format!(
+			"gen_ai_response_body_bytes_{suffix}{{gen_ai_system=\"openai\",gen_ai_input_format=\"Detect\"}} "
+		);
+		encoded
+			.lines()
+			.find_map(|l| l.strip_prefix(prefix.as_str()))
+			.map(|v| v.parse::<f64>().unwrap())
+	};
+	assert_eq!(sample(&encode(), "count"), Some(0.0));
+
+	translated.collect().await.unwrap();
+	let encoded = encode();
+	assert_eq!(sample(&encoded, "count"), Some(1.0));
+	assert_eq!(sample(&encoded, "sum"), Some(body.len() as f64));
+}
+
 #[test]
 fn setup_request_openai_applies_prefixed_path_without_host_override() {
 	let provider = AIProvider::OpenAI(openai::Provider { model: None });
@@ -1140,6 +1635,83 @@ fn setup_request_gemini_applies_path_prefix_with_host_override() {
 	);
 }
 
+#[test]
+fn setup_request_gemini_completions_use_native_path() {
+	let provider = AIProvider::Gemini(gemini::Provider { model: None });
+	for (streaming, expected_path, expected_query) in [
+		(false, "/v1beta/models/gemini-2.5-pro:generateContent", None),
+		(
+			true,
+			"/v1beta/models/gemini-2.5-pro:streamGenerateContent",
+			Some("alt=sse"),
+		),
+	] {
+		let llm_request = LLMRequest {
+			input_format: InputFormat::Completions,
+			streaming,
+			..llm_request_for_path("gemini-2.5-pro")
+		};
+		let mut req = crate::http::tests_common::request(
+			"https://proxy.example.com/v1/chat/completions",
+			http::Method::POST,
+			&[],
+		);
+		provider
+			.setup_request(
+				&mut req,
+				RouteType::Completions,
+				Some(&llm_request),
+				None,
+				None,
+				false,
+			)
+			.expect("setup_request should succeed");
+		assert_eq!(req.uri().path(), expected_path);
+		assert_eq!(req.uri().query(), expected_query);
+	}
+}
+
+#[test]
+fn setup_request_vertex_publisher_models_by_input_format() {
+	let provider = AIProvider::Vertex(vertex::Provider {
+		model: None,
+		region: Some(strng::new("us-central1")),
+		project_id: strng::new("example-project"),
+	});
+	for (input_format, expected_path) in [
+		(
+			InputFormat::Completions,
+			"/v1/projects/example-project/locations/us-central1/publishers/google/models/gemini-1.5-pro:generateContent",
+		),
+		// Messages have no native Gemini translation, so they stay on the OpenAI-compatible endpoint.
+		(
+			InputFormat::Messages,
+			"/v1/projects/example-project/locations/us-central1/endpoints/openapi/chat/completions",
+		),
+	] {
+		let llm_request = LLMRequest {
+			input_format,
+			..llm_request_for_path("gemini-1.5-pro")
+		};
+		let mut req = crate::http::tests_common::request(
+			"https://proxy.example.com/v1/chat/completions",
+			http::Method::POST,
+			&[],
+		);
+		provider
+			.setup_request(
+				&mut req,
+				RouteType::Completions,
+				Some(&llm_request),
+				None,
+				None,
+				false,
+			)
+			.expect("setup_request should succeed");
+		assert_eq!(req.uri().path(), expected_path);
+	}
+}
+
 #[test]
 fn setup_request_vertex_applies_path_prefix_with_host_override() {
 	assert_prefixed_host_override_path(
@@ -1162,6 +1734,8 @@ fn setup_request_bedrock_applies_path_prefix_with_host_override() {
 			region: strng::new("us-east-1"),
 			guardrail_identifier: None,
 			guardrail_version: None,
+			inference_profile: None,
+			latency_optimized: None,
 		}),
 		"anthropic.claude-3-5-sonnet-20241022-v2:0",
 		"/proxy/model/anthropic.claude-3-5-sonnet-20241022-v2:0/converse",
@@ -1185,6 +1759,49 @@ fn setup_request_azure_applies_path_prefix_with_host_override() {
 	);
 }
 
+#[test]
+fn setup_request_azure_api_version() {
+	for (api_version, expected_path, expected_query) in [
+		(
+			Some("2024-10-21"),
+			"/openai/deployments/gpt-4.1/chat/completions",
+			Some("api-version=2024-10-21"),
+		),
+		// Without an explicit version, the v1 surface is used, which takes no api-version.
+		(None, "/openai/v1/chat/completions", None),
+	] {
+		let provider = AIProvider::azure(azure::Provider {
+			model: None,
+			resource_name: strng::new("example"),
+			resource_type: azure::AzureResourceType::OpenAI,
+			api_version: api_version.map(strng::new),
+			project_name: None,
+		});
+		let llm_request = llm_request_for_path("gpt-4.1");
+		let mut req = crate::http::tests_common::request(
+			"https://proxy.example.com/v1/chat/completions",
+			http::Method::POST,
+			&[],
+		);
+		provider
+			.setup_request(
+				&mut req,
+				RouteType::Completions,
+				Some(&llm_request),
+				None,
+				None,
+				false,
+			)
+			.expect("setup_request should succeed");
+		assert_eq!(req.uri().path(), expected_path);
+		assert_eq!(req.uri().query(), expected_query);
+		assert_eq!(
+			req.uri().authority().map(|a| a.as_str()),
+			Some("example.openai.azure.com")
+		);
+	}
+}
+
 #[test]
 fn completions_response_missing_message_and_usage_fields() {
 	// Gemini's OpenAI-compat endpoint can omit `message` from choices and
@@ -1630,3 +2247,71 @@ fn fixed_providers_classify_by_family() {
 		CacheTokenConvention::InputIncludesCache,
 	);
 }
+
+#[test]
+fn default_connector_presents_client_certificate() {
+	let key = rcgen::KeyPair::generate().unwrap();
+	let cert = rcgen::CertificateParams::new(vec!["client.example.com".to_string()])
+		.unwrap()
+		.self_signed(&key)
+		.unwrap();
+	let client_tls = crate::http::backendtls::ResolvedBackendTLS {
+		cert: Some(cert.pem().into_bytes()),
+		key: Some(key.serialize_pem().into_bytes()),
+		..Default::default()
+	}
+	.try_into()
+	.unwrap();
+	let provider = AIProvider::OpenAI(openai::Provider { model: None });
+
+	let tls = provider
+		.default_connector_policies(Some(&client_tls))
+		.unwrap()
+		.backend_tls
+		.unwrap();
+	assert_eq!(tls.metadata.cert.as_deref(), Some(cert.pem().as_str()));
+	assert!(tls.metadata.system_roots);
+	assert!(
+		tls
+			.base_config()
+			.config
+			.client_auth_cert_resolver
+			.has_certs()
+	);
+
+	// Without a client certificate, the system trust defaults are used.
+	let tls = provider
+		.default_connector_policies(None)
+		.unwrap()
+		.backend_tls
+		.unwrap();
+	assert!(
+		!tls
+			.base_config()
+			.config
+			.client_auth_cert_resolver
+			.has_certs()
+	);
+}
+
+#[test]
+fn default_connector_uses_entra_auth_for_azure() {
+	let provider = AIProvider::azure(azure::Provider {
+		model: None,
+		resource_name: strng::new("example"),
+		resource_type: azure::AzureResourceType::OpenAI,
+		api_version: None,
+		project_name: None,
+	});
+	// Azure OpenAI authenticates with Entra ID tokens (workload/managed identity, environment
+	// client credentials, or developer tools) unless the backend overrides backendAuth.
+	let policies = provider.default_connector_policies(None).unwrap();
+	assert!(
+		matches!(
+			policies.backend_auth,
+			Some(BackendAuth::Azure(AzureAuth::Implicit { .. }))
+		),
+		"{:?}",
+		policies.backend_auth
+	);
+}
@@ -178,6 +178,7 @@ fn admin_router(state: Arc<AdminState>) -> Router {
 		.route("/quitquitquit", post(handle_server_shutdown))
 		.route("/debug/tasks", get(handle_tokio_tasks))
 		.route("/debug/trace", post(handle_debug_trace))
+		.route("/debug/tap", post(handle_debug_tap))
 		.route("/config_dump", get(handle_config_dump))
 		.route("/logging", post(handle_logging))
 		.with_state(state.clone());
@@ -343,18 +344,72 @@ pub async fn handle_debug_trace(req: Request) -> Response {
 		None => None,
 	};
 	let rx = crate::proxy::dtrace::track_expression(expression);
-	let sse_stream = trace_sse_stream(rx);
+	sse_response(rx)
+}
+
+/// Streams request/response pairs matching the `expression` query parameter as server-sent events.
+/// `count`, `maxBodyBytes` and `redactHeaders` (comma separated) tune what is captured.
+pub async fn handle_debug_tap(req: Request) -> Response {
+	match parse_tap_config(req.uri().query().unwrap_or_default()) {
+		Ok(config) => sse_response(crate::proxy::tap::register(config)),
+		Err(err) => plaintext_response(hyper::StatusCode::BAD_REQUEST, format!("{err}\n")),
+	}
+}
+
+fn parse_tap_config(query: &str) -> anyhow::Result<crate::proxy::tap::TapConfig> {
+	let mut config = crate::proxy::tap::TapConfig::default();
+	for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+		match key.as_ref() {
+			"expression" => {
+				config.expression = Some(
+					crate::cel::Expression::new_strict(&value)
+						.map_err(|err| anyhow::anyhow!("invalid expression: {err}"))?,
+				)
+			},
+			"count" => {
+				config.count = value
+					.parse()
+					.map_err(|err| anyhow::anyhow!("invalid count: {err}"))?
+			},
+			"maxBodyBytes" => {
+				config.max_body_bytes = value
+					.parse()
+					.map_err(|err| anyhow::anyhow!("invalid maxBodyBytes: {err}"))?
+			},
+			"redactHeaders" => {
+				config.redact_headers = value
+					.split(',')
+					.map(|h| HeaderName::from_str(h.trim()))
+					.collect::<Result<_, _>>()
+					.map_err(|err| anyhow::anyhow!("invalid redactHeaders: {err}"))?
+			},
+			_ => {},
+		}
+	}
+	if config.count == 0 {
+		anyhow::bail!("count must be greater than 0");
+	}
+	Ok(config)
+}
+
+fn sse_response<S, T>(rx: S) -> Response
+where
+	S: futures_util::Stream<Item = T> + Unpin + Send + 'static,
+	T: serde::Serialize + Send + 'static,
+{
 	::http::Response::builder()
 		.status(hyper::StatusCode::OK)
 		.header("Content-Type", "text/event-stream")
 		.header("Cache-Control", "no-cache")
-		.body(crate::http::Body::from_stream(sse_stream))
+		.body(crate::http::Body::from_stream(sse_stream(rx)))
 		.expect("builder with known status code should not fail")
 }
 
-fn trace_sse_stream(
-	rx: crate::proxy::dtrace::TraceReceiver,
-) -> impl futures_util::Stream<Item = Result<Bytes, Infallible>> {
+fn sse_stream<S, T>(rx: S) -> impl futures_util::Stream<Item = Result<Bytes, Infallible>>
+where
+	S: futures_util::Stream<Item = T> + Unpin,
+	T: serde::Serialize,
+{
 	let keepalive = time::interval_at(
 		time::Instant::now() + Duration::from_secs(1),
 		Duration::from_secs(1),
@@ -478,6 +533,7 @@ static HELP_STRING: &str = "
 usage: POST /logging\t\t\t\t\t\t(To list current level)
 usage: POST /logging?level=<level>\t\t\t\t(To change global levels)
 usage: POST /logging?level={mod1}:{level1},{mod2}:{level2}\t(To change specific mods' logging level)
+usage: POST /logging?filter={level},{mod1}={level1}\t\t(To replace the entire logging filter)
 
 hint: loglevel:\terror|warn|info|debug|trace|off
 hint: mod_name:\tthe module name, i.e. ztunnel::agentgateway
@@ -492,6 +548,9 @@ async fn handle_logging(req: Request) -> Response {
 				.collect()
 		})
 		.unwrap_or_default();
+	if let Some(filter) = qp.get("filter") {
+		return replace_log_filter(filter);
+	}
 	let level = qp.get("level").cloned();
 	let reset = qp.get("reset").cloned();
 	if level.is_some() || reset.is_some() {
@@ -531,6 +590,22 @@ fn validate_log_level(level: &str) -> anyhow::Result<()> {
 	Ok(())
 }
 
+fn replace_log_filter(filter: &str) -> Response {
+	if let Err(e) = filter::Targets::from_str(filter) {
+		return plaintext_response(
+			hyper::StatusCode::BAD_REQUEST,
+			format!("Invalid filter provided: {filter}: {e}\n{HELP_STRING}"),
+		);
+	}
+	match telemetry::set_filter(filter) {
+		Ok(_) => list_loggers(),
+		Err(e) => plaintext_response(
+			hyper::StatusCode::INTERNAL_SERVER_ERROR,
+			format!("Failed to set new filter: {e}\n{HELP_STRING}"),
+		),
+	}
+}
+
 fn change_log_level(reset: bool, level: &str) -> Response {
 	if !reset && level.is_empty() {
 		return list_loggers();
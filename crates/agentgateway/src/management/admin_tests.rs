@@ -57,3 +57,23 @@ config:
 		"config dump should preserve non-sensitive header values: {body}"
 	);
 }
+
+#[tokio::test]
+async fn test_admin_logging_rejects_invalid_filter() {
+	let cfg = r#"
+config:
+  adminAddr: localhost:0
+"#;
+	let (addr, _drain_tx) = spawn_admin(cfg).await;
+
+	let resp = reqwest::Client::new()
+		.post(format!(
+			"http://{addr}/logging?filter=info%2Cagent_core%3Dnotalevel"
+		))
+		.send()
+		.await
+		.expect("request should succeed");
+	assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+	let body = resp.text().await.unwrap();
+	assert!(body.contains("Invalid filter provided"), "{body}");
+}
@@ -5,8 +5,7 @@ use std::sync::{Arc, Mutex};
 
 use agent_core::drain::DrainWatcher;
 use hyper::Request;
-use hyper::body::Incoming;
-use prometheus_client::encoding::text::encode;
+use prometheus_client::encoding::text::{encode_eof, encode_registry};
 use prometheus_client::registry::Registry;
 
 use super::hyper_helpers;
@@ -42,26 +41,71 @@ impl Server {
 	}
 }
 
-async fn handle_metrics(reg: Arc<Mutex<Registry>>, req: Request<Incoming>) -> Response {
+async fn handle_metrics<T>(reg: Arc<Mutex<Registry>>, req: Request<T>) -> Response {
+	let content_type = ContentType::negotiate(&req);
 	let mut buf = String::new();
 	let reg = reg.lock().expect("mutex");
-	if let Err(err) = encode(&mut buf, &reg) {
+	if let Err(err) = encode(&mut buf, &reg, content_type) {
 		return ::http::Response::builder()
 			.status(hyper::StatusCode::INTERNAL_SERVER_ERROR)
 			.body(err.to_string().into())
 			.expect("builder with known status code should not fail");
 	}
 
-	let response_content_type = content_type(&req);
-
 	::http::Response::builder()
 		.status(hyper::StatusCode::OK)
-		.header(hyper::header::CONTENT_TYPE, response_content_type)
+		.header(hyper::header::CONTENT_TYPE, <&str>::from(content_type))
 		.body(buf.into())
 		.expect("builder with known status code should not fail")
 }
 
-#[derive(Default)]
+/// Encode the registry in the requested exposition format. Only OpenMetrics is terminated with `# EOF`
+/// and carries exemplars; the Prometheus text format supports neither.
+fn encode(buf: &mut String, reg: &Registry, content_type: ContentType) -> std::fmt::Result {
+	match content_type {
+		ContentType::PlainText => {
+			let mut om = String::new();
+			encode_registry(&mut om, reg)?;
+			strip_exemplars(buf, &om);
+			Ok(())
+		},
+		ContentType::OpenMetrics => {
+			encode_registry(buf, reg)?;
+			encode_eof(buf)
+		},
+	}
+}
+
+/// Copy `src` into `buf`, dropping the exemplar (` # {trace_id="..."} 0.1`) from each sample line.
+fn strip_exemplars(buf: &mut String, src: &str) {
+	for line in src.lines() {
+		let sample = if line.starts_with('#') {
+			line
+		} else {
+			&line[..exemplar_start(line).unwrap_or(line.len())]
+		};
+		buf.push_str(sample);
+		buf.push('\n');
+	}
+}
+
+/// The index of the ` # ` separating a sample from its exemplar, skipping over quoted label values.
+fn exemplar_start(line: &str) -> Option<usize> {
+	let mut quoted = false;
+	let mut escaped = false;
+	for (i, c) in line.char_indices() {
+		match c {
+			_ if escaped => escaped = false,
+			'\\' if quoted => escaped = true,
+			'"' => quoted = !quoted,
+			'#' if !quoted && line[..i].ends_with(' ') => return Some(i - 1),
+			_ => {},
+		}
+	}
+	None
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 enum ContentType {
 	#[default]
 	PlainText,
@@ -77,35 +121,38 @@ impl From<ContentType> for &str {
 	}
 }
 
-#[inline(always)]
-fn content_type<T>(req: &Request<T>) -> &str {
-	req
-		.headers()
-		.get_all(http::header::ACCEPT)
-		.iter()
-		.flat_map(|entry| entry.to_str().ok())
-		// get_all can return multiple in one line still
-		.flat_map(|entry| {
-			entry
-				.split(",")
-				.map(str::trim)
-				.map(|entry| entry.to_lowercase())
-		})
-		.find_map(|v| match v.split(";").collect::<Vec<_>>().first() {
-			Some(&"application/openmetrics-text") => Some(ContentType::OpenMetrics),
-			_ => None,
-		})
-		.unwrap_or_default()
-		.into()
+impl ContentType {
+	fn negotiate<T>(req: &Request<T>) -> ContentType {
+		req
+			.headers()
+			.get_all(http::header::ACCEPT)
+			.iter()
+			.flat_map(|entry| entry.to_str().ok())
+			// get_all can return multiple in one line still
+			.flat_map(|entry| {
+				entry
+					.split(",")
+					.map(str::trim)
+					.map(|entry| entry.to_lowercase())
+			})
+			.find_map(|v| match v.split(";").collect::<Vec<_>>().first() {
+				Some(&"application/openmetrics-text") => Some(ContentType::OpenMetrics),
+				_ => None,
+			})
+			.unwrap_or_default()
+	}
 }
 
+#[cfg(test)]
 mod test {
+	use super::ContentType;
+
 	#[test]
 	fn test_content_type() {
 		let plain_text_req = http::Request::new("I want some plain text");
 		assert_eq!(
-			super::content_type(&plain_text_req),
-			"text/plain; charset=utf-8"
+			ContentType::negotiate(&plain_text_req),
+			ContentType::PlainText
 		);
 
 		let openmetrics_req = http::Request::builder()
@@ -115,8 +162,8 @@ mod test {
 			.body("I would like openmetrics")
 			.unwrap();
 		assert_eq!(
-			super::content_type(&openmetrics_req),
-			"application/openmetrics-text;charset=utf-8;version=1.0.0"
+			ContentType::negotiate(&openmetrics_req),
+			ContentType::OpenMetrics
 		);
 
 		let mixed_req = http::Request::builder()
@@ -124,10 +171,7 @@ mod test {
           .header("Accept", "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited;q=0.6,application/openmetrics-text;version=1.0.0;escaping=allow-utf-8;q=0.5,application/openmetrics-text;version=0.0.1;q=0.4,text/plain;version=1.0.0;escaping=allow-utf-8;q=0.3,text/plain;version=0.0.4;q=0.2,*/*;q=0.1")
           .body("I would like openmetrics")
           .unwrap();
-		assert_eq!(
-			super::content_type(&mixed_req),
-			"application/openmetrics-text;charset=utf-8;version=1.0.0"
-		);
+		assert_eq!(ContentType::negotiate(&mixed_req), ContentType::OpenMetrics);
 
 		let unsupported_req_accept = http::Request::builder()
 			.header("Accept", "application/json")
@@ -135,8 +179,79 @@ mod test {
 			.unwrap();
 		// asking for something we don't support, fall back to plaintext
 		assert_eq!(
-			super::content_type(&unsupported_req_accept),
-			"text/plain; charset=utf-8"
+			ContentType::negotiate(&unsupported_req_accept),
+			ContentType::PlainText
 		)
 	}
+
+	#[tokio::test]
+	async fn test_scrape_formats() {
+		use std::sync::{Arc, Mutex};
+
+		use http_body_util::BodyExt;
+		use prometheus_client::metrics::counter::Counter;
+		use prometheus_client::registry::Registry;
+
+		use prometheus_client::metrics::exemplar::HistogramWithExemplars;
+
+		use crate::telemetry::metrics::TraceExemplar;
+
+		let mut registry = Registry::default();
+		let counter: Counter = Counter::default();
+		counter.inc();
+		registry.register("requests", "Total requests", counter);
+		let histogram = HistogramWithExemplars::new([0.5, 1.0]);
+		histogram.observe(
+			0.25,
+			Some(TraceExemplar {
+				trace_id: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+			}),
+			None,
+		);
+		registry.register("duration", "Request duration", histogram);
+		let registry = Arc::new(Mutex::new(registry));
+
+		let scrape = |accept: Option<&'static str>| {
+			let registry = registry.clone();
+			async move {
+				let mut req = http::Request::builder().uri("/metrics");
+				if let Some(accept) = accept {
+					req = req.header(http::header::ACCEPT, accept);
+				}
+				let resp = super::handle_metrics(registry, req.body(()).unwrap()).await;
+				assert_eq!(resp.status(), http::StatusCode::OK);
+				let content_type = resp.headers()[http::header::CONTENT_TYPE]
+					.to_str()
+					.unwrap()
+					.to_string();
+				let body = resp.into_body().collect().await.unwrap().to_bytes();
+				(content_type, String::from_utf8(body.to_vec()).unwrap())
+			}
+		};
+
+		let (content_type, body) = scrape(None).await;
+		assert_eq!(content_type, "text/plain; charset=utf-8");
+		assert!(body.contains("requests_total 1"), "{body}");
+		assert!(body.contains("duration_bucket{le=\"0.5\"} 1\n"), "{body}");
+		assert!(!body.contains("trace_id"), "{body}");
+		assert!(!body.contains("# EOF"), "{body}");
+
+		let (content_type, body) = scrape(Some("text/plain;version=0.0.4")).await;
+		assert_eq!(content_type, "text/plain; charset=utf-8");
+		assert!(!body.contains("# EOF"), "{body}");
+
+		let (content_type, body) = scrape(Some("application/openmetrics-text;version=1.0.0")).await;
+		assert_eq!(
+			content_type,
+			"application/openmetrics-text;charset=utf-8;version=1.0.0"
+		);
+		assert!(body.contains("requests_total 1"), "{body}");
+		assert!(
+			body.contains(
+				"duration_bucket{le=\"0.5\"} 1 # {trace_id=\"4bf92f3577b34da6a3ce929d0e0e4736\"} 0.25"
+			),
+			"{body}"
+		);
+		assert!(body.ends_with("# EOF\n"), "{body}");
+	}
 }
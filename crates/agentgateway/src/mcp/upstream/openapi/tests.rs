@@ -36,6 +36,9 @@ async fn setup_with_prefix(prefix: &str) -> (MockServer, Handler) {
 		&client::Config {
 			resolver_cfg: ResolverConfig::default(),
 			resolver_opts: ResolverOpts::default(),
+			negative_ttl: client::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
 		},
 		None,
 		BackendConfig::default(),
@@ -1445,6 +1448,9 @@ async fn test_openapi_from_url() {
 		&client::Config {
 			resolver_cfg: ResolverConfig::default(),
 			resolver_opts: ResolverOpts::default(),
+			negative_ttl: client::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
 		},
 		None,
 		BackendConfig::default(),
@@ -1739,6 +1745,9 @@ async fn test_call_tool_with_binary_body() {
 		&client::Config {
 			resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
 			resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+			negative_ttl: client::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
 		},
 		None,
 		BackendConfig::default(),
@@ -1,19 +1,22 @@
 use std::io::{Error, IoSlice};
 use std::pin::Pin;
 use std::task::{Context, Poll, ready};
+use std::time::Instant;
 
 use async_openai::types::realtime::{
 	RealtimeClientEvent, RealtimeResponseUsage, RealtimeServerEvent, UserMessageContent,
 };
 use bytes::{Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream, ReadBuf};
+use tracing::debug;
 use websocket_sans_io::{FrameInfo, Opcode, WebsocketFrameEncoder, WebsocketFrameEvent};
 
 use crate::llm::policy::PromptGuard;
 use crate::llm::{LLMInfo, LLMResponse};
 use crate::proxy::httpproxy::PolicyClient;
 use crate::telemetry::log::AsyncLog;
+use crate::types::backend;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ResponseDoneEvent {
@@ -144,6 +147,10 @@ impl<IO: AsyncRead + Unpin + 'static> AsyncRead for Parser<IO> {
 				self.emit(got.freeze());
 			}
 		}
+		if self.frames.message_too_large() {
+			// The bytes are still passed through; we just stop inspecting them.
+			self.disabled = true;
+		}
 		Poll::Ready(Ok(()))
 	}
 }
@@ -182,28 +189,21 @@ where
 
 /// Encode a WebSocket text frame (server-side, unmasked) containing `payload`.
 fn encode_ws_text_frame(payload: &[u8]) -> Bytes {
-	let mut encoder = WebsocketFrameEncoder::new();
-	let frame_info = FrameInfo {
-		opcode: Opcode::Text,
-		payload_length: payload.len() as u64,
-		mask: None,
-		fin: true,
-		reserved: 0,
-	};
-	let header = encoder.start_frame(&frame_info);
-	let mut out = BytesMut::with_capacity(header.len() + payload.len());
-	out.extend_from_slice(&header);
-	out.extend_from_slice(payload);
-	out.freeze()
+	encode_ws_frame(Opcode::Text, payload, None)
 }
 
 /// Encode a WebSocket text frame with `mask` (client-side, masked).
 fn encode_ws_text_frame_masked(payload: &[u8], mask: [u8; 4]) -> Bytes {
+	encode_ws_frame(Opcode::Text, payload, Some(mask))
+}
+
+/// Encode a single, final WebSocket frame. Frames sent to a server must be masked.
+fn encode_ws_frame(opcode: Opcode, payload: &[u8], mask: Option<[u8; 4]>) -> Bytes {
 	let mut encoder = WebsocketFrameEncoder::new();
 	let frame_info = FrameInfo {
-		opcode: Opcode::Text,
+		opcode,
 		payload_length: payload.len() as u64,
-		mask: Some(mask),
+		mask,
 		fin: true,
 		reserved: 0,
 	};
@@ -252,30 +252,81 @@ struct WsFrameAccumulator {
 	pending: BytesMut,
 	frame_raw: BytesMut,
 	frame_payload: BytesMut,
+	/// Payload of the ping or pong being decoded. Control frames may arrive between the fragments
+	/// of a message, so they are kept apart from `frame_payload`.
+	control_payload: BytesMut,
+	/// Raw bytes of the earlier fragments of the current text message.
+	message_raw: BytesMut,
+	/// Payload bytes of the current data message so far, across its fragments.
+	message_len: u64,
+	max_message_size: u64,
+	too_large: bool,
 }
 
+/// Maximum size of a reassembled message when none is configured, so a peer cannot make us buffer
+/// an unbounded message.
+const DEFAULT_MAX_MESSAGE_SIZE: u64 = 16 * 1024 * 1024;
+
 enum WsCompletedFrame {
-	Text { raw: Bytes, payload: Bytes },
-	Other { raw: Bytes },
+	/// A complete text message, reassembled from its fragments.
+	Text {
+		raw: Bytes,
+		payload: Bytes,
+	},
+	/// A ping or pong.
+	Control {
+		opcode: Opcode,
+		raw: Bytes,
+		payload: Bytes,
+	},
+	Other {
+		raw: Bytes,
+	},
+}
+
+impl WsCompletedFrame {
+	fn into_raw(self) -> Bytes {
+		match self {
+			WsCompletedFrame::Text { raw, .. }
+			| WsCompletedFrame::Control { raw, .. }
+			| WsCompletedFrame::Other { raw } => raw,
+		}
+	}
 }
 
 impl WsFrameAccumulator {
 	fn new() -> Self {
+		Self::with_max_message_size(None)
+	}
+
+	fn with_max_message_size(max_message_size: Option<u64>) -> Self {
 		Self {
 			decoder: websocket_sans_io::WebsocketFrameDecoder::new(),
 			pending: BytesMut::new(),
 			frame_raw: BytesMut::new(),
 			frame_payload: BytesMut::new(),
+			control_payload: BytesMut::new(),
+			message_raw: BytesMut::new(),
+			message_len: 0,
+			max_message_size: max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE),
+			too_large: false,
 		}
 	}
 
 	fn push(&mut self, data: &[u8]) {
-		self.pending.extend_from_slice(data);
+		if !self.too_large {
+			self.pending.extend_from_slice(data);
+		}
+	}
+
+	/// Whether a message exceeded the maximum message size. Once set, no more frames are decoded.
+	fn message_too_large(&self) -> bool {
+		self.too_large
 	}
 
 	fn drain_frames(&mut self) -> Vec<WsCompletedFrame> {
 		let mut result = Vec::new();
-		loop {
+		while !self.too_large {
 			let mut copy = self.pending.to_vec();
 			let ret = match self.decoder.add_data(&mut copy) {
 				Ok(r) => r,
@@ -284,10 +335,13 @@ impl WsFrameAccumulator {
 					// clear accumulator state so the proxy makes progress rather than
 					// retrying the same bytes and stalling indefinitely.
 					if !self.pending.is_empty() {
-						let raw = self.pending.split().freeze();
+						let mut raw = self.message_raw.split();
+						raw.extend_from_slice(&self.pending.split());
 						self.frame_raw.clear();
 						self.frame_payload.clear();
-						result.push(WsCompletedFrame::Other { raw });
+						self.control_payload.clear();
+						self.message_len = 0;
+						result.push(WsCompletedFrame::Other { raw: raw.freeze() });
 					}
 					break;
 				},
@@ -300,12 +354,43 @@ impl WsFrameAccumulator {
 			self.frame_raw.extend_from_slice(&raw_chunk);
 
 			match ret.event {
+				Some(WebsocketFrameEvent::Start {
+					frame_info,
+					original_opcode: Opcode::Text | Opcode::Binary,
+				}) => {
+					// Check the declared length up front, so an oversized message is never buffered.
+					self.message_len += frame_info.payload_length;
+					if self.message_len > self.max_message_size {
+						self.too_large = true;
+						self.pending.clear();
+						self.frame_raw.clear();
+						self.frame_payload.clear();
+						self.message_raw.clear();
+					}
+				},
+				Some(WebsocketFrameEvent::PayloadChunk {
+					original_opcode: Opcode::Text,
+				}) => {
+					self
+						.frame_payload
+						.extend_from_slice(&copy[..ret.consumed_bytes]);
+				},
 				Some(WebsocketFrameEvent::PayloadChunk {
+					original_opcode: Opcode::Ping | Opcode::Pong,
+				}) => {
+					self
+						.control_payload
+						.extend_from_slice(&copy[..ret.consumed_bytes]);
+				},
+				Some(WebsocketFrameEvent::End {
 					original_opcode: Opcode::Text,
+					frame_info: FrameInfo { fin: false, .. },
 				}) => {
+					// More fragments follow; hold this one until the message is complete.
 					self
 						.frame_payload
 						.extend_from_slice(&copy[..ret.consumed_bytes]);
+					self.message_raw.extend_from_slice(&self.frame_raw.split());
 				},
 				Some(WebsocketFrameEvent::End {
 					original_opcode: Opcode::Text,
@@ -314,13 +399,38 @@ impl WsFrameAccumulator {
 					self
 						.frame_payload
 						.extend_from_slice(&copy[..ret.consumed_bytes]);
-					let raw = self.frame_raw.split().freeze();
+					let mut raw = self.message_raw.split();
+					raw.extend_from_slice(&self.frame_raw.split());
 					let payload = self.frame_payload.split().freeze();
-					result.push(WsCompletedFrame::Text { raw, payload });
+					self.message_len = 0;
+					result.push(WsCompletedFrame::Text {
+						raw: raw.freeze(),
+						payload,
+					});
+				},
+				Some(WebsocketFrameEvent::End {
+					original_opcode: opcode @ (Opcode::Ping | Opcode::Pong),
+					..
+				}) => {
+					self
+						.control_payload
+						.extend_from_slice(&copy[..ret.consumed_bytes]);
+					let raw = self.frame_raw.split().freeze();
+					let payload = self.control_payload.split().freeze();
+					result.push(WsCompletedFrame::Control {
+						opcode,
+						raw,
+						payload,
+					});
 				},
-				Some(WebsocketFrameEvent::End { .. }) => {
+				Some(WebsocketFrameEvent::End {
+					original_opcode,
+					frame_info,
+				}) => {
+					if matches!(original_opcode, Opcode::Binary) && frame_info.fin {
+						self.message_len = 0;
+					}
 					let raw = self.frame_raw.split().freeze();
-					self.frame_payload.clear();
 					result.push(WsCompletedFrame::Other { raw });
 				},
 				Some(WebsocketFrameEvent::PayloadChunk { .. })
@@ -419,8 +529,8 @@ pub async fn guarded_realtime_proxy<C, S>(
 							}
 							let _ = server_tx.send(raw).await;
 						},
-						WsCompletedFrame::Other { raw } => {
-							let _ = server_tx.send(raw).await;
+						frame @ (WsCompletedFrame::Control { .. } | WsCompletedFrame::Other { .. }) => {
+							let _ = server_tx.send(frame.into_raw()).await;
 						},
 					}
 				}
@@ -556,8 +666,8 @@ pub async fn guarded_realtime_proxy<C, S>(
 								},
 							}
 						},
-						WsCompletedFrame::Other { raw } => {
-							let _ = client_tx.send(raw).await;
+						frame @ (WsCompletedFrame::Control { .. } | WsCompletedFrame::Other { .. }) => {
+							let _ = client_tx.send(frame.into_raw()).await;
 						},
 					}
 				}
@@ -593,8 +703,167 @@ pub async fn guarded_realtime_proxy<C, S>(
 	let _ = tokio::join!(client_writer_join, server_writer_join);
 }
 
+// ---------------------------------------------------------------------------
+// Connection relay
+// ---------------------------------------------------------------------------
+
+/// Close status for a message that is too big to process (RFC 6455 section 7.4.1).
+const CLOSE_MESSAGE_TOO_BIG: u16 = 1009;
+
+/// Relays frames between one side of an upgraded WebSocket connection and the returned stream,
+/// which takes its place in the rest of the proxy.
+///
+/// When the peer has been quiet for `ping_interval`, a ping is sent to it; its pong is consumed
+/// here rather than forwarded. The connection is closed when a ping goes unanswered for
+/// `ping_timeout`, or when no messages flow in either direction for `idle_timeout`. A message
+/// from the peer larger than `max_message_size` closes both sides with status 1009. Frames
+/// sent to a backend (`peer_is_server`) are masked, as the protocol requires for client frames.
+pub fn relay<IO>(peer: IO, cfg: backend::WebSocket, peer_is_server: bool) -> DuplexStream
+where
+	IO: AsyncRead + AsyncWrite + Send + 'static,
+{
+	let (proxy_side, relay_side) = tokio::io::duplex(64 * 1024);
+	tokio::spawn(run_relay(peer, relay_side, cfg, peer_is_server));
+	proxy_side
+}
+
+/// Encode a close frame with status `code`, masked when it is sent to a server.
+fn encode_close_frame(code: u16, to_server: bool) -> Bytes {
+	encode_ws_frame(
+		Opcode::ConnectionClose,
+		&code.to_be_bytes(),
+		to_server.then(rand::random),
+	)
+}
+
+async fn run_relay<IO>(peer: IO, proxy: DuplexStream, cfg: backend::WebSocket, peer_is_server: bool)
+where
+	IO: AsyncRead + AsyncWrite + Send + 'static,
+{
+	let (mut peer_reader, mut peer_writer) = tokio::io::split(peer);
+	let (mut proxy_reader, mut proxy_writer) = tokio::io::split(proxy);
+	let max_message_size = cfg.max_message_size.map(|m| m as u64);
+	let mut from_peer = WsFrameAccumulator::with_max_message_size(max_message_size);
+	let mut to_peer = WsFrameAccumulator::with_max_message_size(max_message_size);
+	let mut peer_buf = [0u8; 4096];
+	let mut proxy_buf = [0u8; 4096];
+
+	let start = Instant::now();
+	// Last message in either direction, excluding our own pings and pongs.
+	let mut last_message = start;
+	// Last bytes received from the peer.
+	let mut last_heard = start;
+	// Payload and send time of the ping awaiting a pong.
+	let mut outstanding_ping: Option<([u8; 8], Instant)> = None;
+	let mut pings_sent = 0u64;
+
+	loop {
+		let ping_deadline = match outstanding_ping {
+			Some((_, sent)) => Some(sent + cfg.ping_timeout),
+			None => cfg.ping_interval.map(|i| last_heard + i),
+		};
+		let idle_deadline = cfg.idle_timeout.map(|t| last_message + t);
+		let deadline = match (ping_deadline, idle_deadline) {
+			(Some(a), Some(b)) => Some(a.min(b)),
+			(a, b) => a.or(b),
+		};
+		let timer = async {
+			match deadline {
+				Some(d) => tokio::time::sleep_until(d.into()).await,
+				None => std::future::pending().await,
+			}
+		};
+
+		tokio::select! {
+			res = peer_reader.read(&mut peer_buf) => {
+				let n = match res {
+					Ok(0) | Err(_) => break,
+					Ok(n) => n,
+				};
+				let now = Instant::now();
+				last_heard = now;
+				from_peer.push(&peer_buf[..n]);
+				for frame in from_peer.drain_frames() {
+					if let WsCompletedFrame::Control { opcode: Opcode::Pong, payload, .. } = &frame
+						&& outstanding_ping.is_some_and(|(p, _)| payload[..] == p)
+					{
+						outstanding_ping = None;
+						continue;
+					}
+					last_message = now;
+					if proxy_writer.write_all(&frame.into_raw()).await.is_err() {
+						return;
+					}
+				}
+				if from_peer.message_too_large() {
+					debug!("closing websocket connection: message exceeds the maximum size");
+					let _ = peer_writer
+						.write_all(&encode_close_frame(CLOSE_MESSAGE_TOO_BIG, peer_is_server))
+						.await;
+					let _ = proxy_writer
+						.write_all(&encode_close_frame(CLOSE_MESSAGE_TOO_BIG, !peer_is_server))
+						.await;
+					break;
+				}
+			},
+			res = proxy_reader.read(&mut proxy_buf) => {
+				let n = match res {
+					Ok(0) | Err(_) => break,
+					Ok(n) => n,
+				};
+				to_peer.push(&proxy_buf[..n]);
+				for frame in to_peer.drain_frames() {
+					last_message = Instant::now();
+					if peer_writer.write_all(&frame.into_raw()).await.is_err() {
+						return;
+					}
+				}
+				if to_peer.message_too_large() {
+					debug!("closing websocket connection: message exceeds the maximum size");
+					let _ = peer_writer
+						.write_all(&encode_close_frame(CLOSE_MESSAGE_TOO_BIG, peer_is_server))
+						.await;
+					let _ = proxy_writer
+						.write_all(&encode_close_frame(CLOSE_MESSAGE_TOO_BIG, !peer_is_server))
+						.await;
+					break;
+				}
+			},
+			_ = timer => {
+				let now = Instant::now();
+				if idle_deadline.is_some_and(|d| now >= d) {
+					debug!("closing idle websocket connection");
+					break;
+				}
+				if let Some((_, sent)) = outstanding_ping {
+					if now >= sent + cfg.ping_timeout {
+						debug!("closing websocket connection: ping was not answered");
+						break;
+					}
+				} else if ping_deadline.is_some_and(|d| now >= d) {
+					let payload = pings_sent.to_be_bytes();
+					pings_sent += 1;
+					let mask = peer_is_server.then(rand::random);
+					if peer_writer
+						.write_all(&encode_ws_frame(Opcode::Ping, &payload, mask))
+						.await
+						.is_err()
+					{
+						break;
+					}
+					outstanding_ping = Some((payload, now));
+				}
+			},
+		}
+	}
+	let _ = peer_writer.shutdown().await;
+	let _ = proxy_writer.shutdown().await;
+}
+
 #[cfg(test)]
 mod tests {
+	use std::time::Duration;
+
 	use super::*;
 
 	#[test]
@@ -617,4 +886,252 @@ mod tests {
 		assert!(parser.text_buffer.is_empty());
 		assert_eq!(parser.text_buffer.capacity(), 0);
 	}
+
+	fn keepalive_cfg(ping_interval: Duration, ping_timeout: Duration) -> backend::WebSocket {
+		backend::WebSocket {
+			idle_timeout: None,
+			ping_interval: Some(ping_interval),
+			ping_timeout,
+			max_message_size: None,
+		}
+	}
+
+	/// Reads frames from `io` until one completes; `None` once `io` is closed.
+	async fn next_frame(
+		io: &mut DuplexStream,
+		accum: &mut WsFrameAccumulator,
+	) -> Option<WsCompletedFrame> {
+		let mut buf = [0u8; 1024];
+		loop {
+			if let Some(frame) = accum.drain_frames().into_iter().next() {
+				return Some(frame);
+			}
+			let n = io.read(&mut buf).await.ok()?;
+			if n == 0 {
+				return None;
+			}
+			accum.push(&buf[..n]);
+		}
+	}
+
+	#[tokio::test]
+	async fn idle_connection_receives_pings() {
+		let (peer, mut client) = tokio::io::duplex(4096);
+		let mut proxy = relay(
+			peer,
+			keepalive_cfg(Duration::from_millis(20), Duration::from_secs(5)),
+			false,
+		);
+		let mut accum = WsFrameAccumulator::new();
+		for _ in 0..3 {
+			let Some(WsCompletedFrame::Control {
+				opcode: Opcode::Ping,
+				payload,
+				..
+			}) = next_frame(&mut client, &mut accum).await
+			else {
+				panic!("expected a ping");
+			};
+			client
+				.write_all(&encode_ws_frame(Opcode::Pong, &payload, Some([1, 2, 3, 4])))
+				.await
+				.unwrap();
+		}
+
+		// Pongs are answered by the keepalive and are not forwarded; messages still are.
+		client
+			.write_all(&encode_ws_text_frame_masked(b"hello", [1, 2, 3, 4]))
+			.await
+			.unwrap();
+		let Some(WsCompletedFrame::Text { payload, .. }) =
+			next_frame(&mut proxy, &mut WsFrameAccumulator::new()).await
+		else {
+			panic!("expected the text message");
+		};
+		assert_eq!(&payload[..], b"hello");
+	}
+
+	#[tokio::test]
+	async fn unresponsive_peer_is_closed() {
+		let (peer, mut backend) = tokio::io::duplex(4096);
+		let mut proxy = relay(
+			peer,
+			keepalive_cfg(Duration::from_millis(20), Duration::from_millis(50)),
+			true,
+		);
+		let mut accum = WsFrameAccumulator::new();
+		let start = Instant::now();
+		assert!(matches!(
+			next_frame(&mut backend, &mut accum).await,
+			Some(WsCompletedFrame::Control {
+				opcode: Opcode::Ping,
+				..
+			})
+		));
+		// Never answer the ping; both sides are closed once the ping times out.
+		let closed = tokio::time::timeout(Duration::from_secs(5), async {
+			assert!(next_frame(&mut backend, &mut accum).await.is_none());
+			assert_eq!(proxy.read(&mut [0u8; 16]).await.unwrap(), 0);
+		})
+		.await;
+		assert!(closed.is_ok(), "connection was not closed");
+		assert!(start.elapsed() >= Duration::from_millis(70));
+	}
+
+	#[tokio::test]
+	async fn idle_timeout_closes_connection() {
+		let (peer, mut client) = tokio::io::duplex(4096);
+		let mut proxy = relay(
+			peer,
+			backend::WebSocket {
+				idle_timeout: Some(Duration::from_millis(50)),
+				ping_interval: None,
+				ping_timeout: Duration::from_secs(5),
+				max_message_size: None,
+			},
+			false,
+		);
+		proxy
+			.write_all(&encode_ws_text_frame(b"hello"))
+			.await
+			.unwrap();
+		let mut accum = WsFrameAccumulator::new();
+		assert!(matches!(
+			next_frame(&mut client, &mut accum).await,
+			Some(WsCompletedFrame::Text { .. })
+		));
+		let closed =
+			tokio::time::timeout(Duration::from_secs(5), next_frame(&mut client, &mut accum)).await;
+		assert!(matches!(closed, Ok(None)), "idle connection was not closed");
+	}
+
+	fn fragment(opcode: Opcode, payload: &[u8], fin: bool) -> Bytes {
+		let mut encoder = WebsocketFrameEncoder::new();
+		let header = encoder.start_frame(&FrameInfo {
+			opcode,
+			payload_length: payload.len() as u64,
+			mask: None,
+			fin,
+			reserved: 0,
+		});
+		let mut out = BytesMut::new();
+		out.extend_from_slice(&header);
+		out.extend_from_slice(payload);
+		out.freeze()
+	}
+
+	#[test]
+	fn fragmented_text_is_reassembled() {
+		let first = fragment(Opcode::Text, b"hel", false);
+		let last = fragment(Opcode::Continuation, b"lo", true);
+		let mut accum = WsFrameAccumulator::new();
+		accum.push(&first);
+		// Control frames may be interleaved with the fragments of a message.
+		accum.push(&encode_ws_frame(Opcode::Ping, b"p", None));
+		accum.push(&last);
+
+		let frames = accum.drain_frames();
+		assert_eq!(frames.len(), 2);
+		assert!(matches!(
+			&frames[0],
+			WsCompletedFrame::Control { opcode: Opcode::Ping, payload, .. } if &payload[..] == b"p"
+		));
+		let WsCompletedFrame::Text { raw, payload } = &frames[1] else {
+			panic!("expected the reassembled message");
+		};
+		assert_eq!(&payload[..], b"hello");
+		assert_eq!(&raw[..], &[&first[..], &last[..]].concat()[..]);
+	}
+
+	fn size_limited(max_message_size: usize) -> backend::WebSocket {
+		backend::WebSocket {
+			idle_timeout: None,
+			ping_interval: None,
+			ping_timeout: Duration::from_secs(5),
+			max_message_size: Some(max_message_size),
+		}
+	}
+
+	#[tokio::test]
+	async fn fragmented_message_under_limit_is_forwarded() {
+		let (peer, mut client) = tokio::io::duplex(4096);
+		let mut proxy = relay(peer, size_limited(10), false);
+		client
+			.write_all(&fragment(Opcode::Text, b"hello", false))
+			.await
+			.unwrap();
+		client
+			.write_all(&fragment(Opcode::Continuation, b"world", true))
+			.await
+			.unwrap();
+
+		let Some(WsCompletedFrame::Text { payload, .. }) =
+			next_frame(&mut proxy, &mut WsFrameAccumulator::new()).await
+		else {
+			panic!("expected the text message");
+		};
+		assert_eq!(&payload[..], b"helloworld");
+	}
+
+	#[tokio::test]
+	async fn fragmented_message_over_limit_closes_connection() {
+		let (peer, mut client) = tokio::io::duplex(4096);
+		let mut proxy = relay(peer, size_limited(8), false);
+		client
+			.write_all(&fragment(Opcode::Text, b"hello", false))
+			.await
+			.unwrap();
+		client
+			.write_all(&fragment(Opcode::Continuation, b"world", true))
+			.await
+			.unwrap();
+
+		// The client gets an unmasked close frame with status 1009, then the connection ends.
+		let mut accum = WsFrameAccumulator::new();
+		let Some(WsCompletedFrame::Other { raw }) = next_frame(&mut client, &mut accum).await else {
+			panic!("expected a close frame");
+		};
+		assert_eq!(raw[0], 0x88);
+		assert_eq!(u16::from_be_bytes([raw[2], raw[3]]), CLOSE_MESSAGE_TOO_BIG);
+		assert!(next_frame(&mut client, &mut accum).await.is_none());
+
+		// The backend side gets a close frame instead of any part of the message.
+		let mut accum = WsFrameAccumulator::new();
+		assert!(matches!(
+			next_frame(&mut proxy, &mut accum).await,
+			Some(WsCompletedFrame::Other { .. })
+		));
+		assert!(next_frame(&mut proxy, &mut accum).await.is_none());
+	}
+
+	#[tokio::test]
+	async fn message_to_peer_over_limit_closes_connection() {
+		let (peer, mut client) = tokio::io::duplex(4096);
+		let mut proxy = relay(peer, size_limited(8), false);
+		proxy
+			.write_all(&encode_ws_text_frame(b"helloworld"))
+			.await
+			.unwrap();
+
+		let mut accum = WsFrameAccumulator::new();
+		let Some(WsCompletedFrame::Other { raw }) = next_frame(&mut client, &mut accum).await else {
+			panic!("expected a close frame");
+		};
+		assert_eq!(u16::from_be_bytes([raw[2], raw[3]]), CLOSE_MESSAGE_TOO_BIG);
+		assert!(next_frame(&mut client, &mut accum).await.is_none());
+	}
+
+	#[test]
+	fn default_max_message_size_applies() {
+		let mut accum = WsFrameAccumulator::new();
+		// Only the header of a text frame declaring a payload just over the default limit.
+		let mut header = vec![0x81, 127];
+		header.extend_from_slice(&(DEFAULT_MAX_MESSAGE_SIZE + 1).to_be_bytes());
+		accum.push(&header);
+		assert!(accum.drain_frames().is_empty());
+		assert!(accum.message_too_large());
+		// Nothing more is buffered once the limit is hit.
+		accum.push(&[0; 1024]);
+		assert!(accum.pending.is_empty());
+	}
 }
@@ -16,6 +16,7 @@ use futures_util::FutureExt;
 use http::StatusCode;
 use hyper_util::rt::TokioIo;
 use hyper_util::server::conn::auto;
+use prometheus_client::metrics::gauge::Gauge;
 use rand::RngExt;
 use tokio::net::TcpStream;
 use tokio::sync::watch;
@@ -25,7 +26,7 @@ use tracing::{Instrument, debug, error, event, info, info_span, warn};
 
 use crate::proxy::{ProxyError, WaypointService, dtrace};
 use crate::store::{BindEvent, BindListeners, FrontendPolices};
-use crate::telemetry::metrics::TCPLabels;
+use crate::telemetry::metrics::{BindLabels, TCPLabels};
 use crate::transport::BufferLimit;
 use crate::transport::stream::{
 	ConnectHeaders, Extension, LoggingMode, Socket, TCPConnectionInfo, TLSConnectionInfo,
@@ -246,6 +247,52 @@ impl Gateway {
 		};
 		let listener = tokio::net::TcpListener::from_std(listener)?;
 		info!(bind = name.as_str(), "started bind");
+		let active = pi
+			.metrics
+			.downstream_active_connections
+			.get_or_create(&BindLabels {
+				bind: Some(&name).into(),
+			})
+			.clone();
+		let handle = |stream: TcpStream, drain: DrainWatcher| {
+			let pi = pi.clone();
+			let name = name.clone();
+			async move {
+				let Ok(mut stream) = Socket::from_tcp(stream) else {
+					// Can fail if they immediately disconnected; not much we can do.
+					return;
+				};
+				stream.with_logging(LoggingMode::Downstream);
+				Self::handle_tunnel(name, bind_protocol, tunnel_protocol, stream, pi, drain).await
+			}
+		};
+		Self::accept_connections(
+			name.clone(),
+			listener,
+			drain,
+			min_deadline,
+			max_deadline,
+			active,
+			handle,
+		)
+		.await;
+		Ok(())
+	}
+
+	/// Accepts connections from `listener` and serves each with `handle` until a drain completes.
+	/// `active` tracks the number of open connections.
+	async fn accept_connections<H, F>(
+		name: BindKey,
+		listener: tokio::net::TcpListener,
+		drain: DrainWatcher,
+		min_deadline: Duration,
+		max_deadline: Duration,
+		active: Gauge,
+		handle: H,
+	) where
+		H: Fn(TcpStream, DrainWatcher) -> F,
+		F: Future<Output = ()> + Send + 'static,
+	{
 		let component = format!("bind {name}");
 
 		// Desired drain semantics:
@@ -257,10 +304,10 @@ impl Gateway {
 		// Therefor, we should have a minimum drain time and a maximum drain time.
 		// No matter what, we will continue accepting connections for <min time>. Any new connections will
 		// be "discouraged" via disabling keepalive.
-		// After that, we will continue processing connections as long as there are any remaining open.
+		// After that, we close the listener, so new connections are refused, and continue processing
+		// connections as long as there are any remaining open, up to <max time>.
 		// This handles gracefully serving any long-running requests.
-		// New connections may still be made during this time which we will attempt to serve, though they
-		// are at increased risk of early termination.
+		// Any connections still open after <max time> are forcefully terminated.
 		let accept = |drain: DrainWatcher, force_shutdown: watch::Receiver<()>| async move {
 			// We will need to be able to watch for drains, so take a copy
 			let drain_watch = drain.clone();
@@ -271,14 +318,10 @@ impl Gateway {
 			let (inner_trigger, inner_drain) = drain::new();
 			drop(inner_drain);
 			let handle_stream = |stream: TcpStream, upgrader: &DrainUpgrader| {
-				let Ok(mut stream) = Socket::from_tcp(stream) else {
-					// Can fail if they immediately disconnected; not much we can do.
-					return;
-				};
-				stream.with_logging(LoggingMode::Downstream);
-				let pi = pi.clone();
 				// We got the connection; make a strong drain blocker.
 				let drain = upgrader.upgrade(weak.clone());
+				let conn = handle(stream, drain);
+				let active = ActiveConnection::new(active.clone());
 				let start = Instant::now();
 				let mut force_shutdown = force_shutdown.clone();
 				let name = name.clone();
@@ -289,8 +332,9 @@ impl Gateway {
 						_ = force_shutdown.changed() => {
 							info!(bind=?name, "connection forcefully terminated");
 						}
-						_ = Self::handle_tunnel(name.clone(), bind_protocol, tunnel_protocol, stream, pi, drain) => {}
+						_ = conn => {}
 					}
+					drop(active);
 					debug!(bind=?name, dur=?start.elapsed(), "connection completed");
 				}));
 			};
@@ -360,7 +404,7 @@ impl Gateway {
 						Err(e) => {
 							if is_accept_error_permanent(&e) {
 								error!(bind=?name, "fatal accept error during drain, stopping listener: {e}");
-								return;
+								break;
 							}
 							if is_accept_error_per_connection(&e) {
 								debug!(bind=?name, "per-connection accept error during drain: {e}");
@@ -372,23 +416,27 @@ impl Gateway {
 							);
 							tokio::select! {
 								_ = tokio::time::sleep(jittered) => {},
-								_ = &mut drained_for_minimum => { return; }
+								_ = &mut drained_for_minimum => { break; }
 							}
 							backoff = (backoff * 2).min(BACKOFF_MAX);
 							continue;
 						}
 					},
 					_ = &mut drained_for_minimum => {
-						// We are done! exit.
-						// This will stop accepting new connections
-						return;
+						break;
 					}
 				}
 			}
+			// Stop accepting new connections; they will now be refused.
+			drop(listener);
+			info!(bind=?name, remaining=active.get(), "stopped accepting connections, waiting for remaining connections to complete");
+			// Keep running until the remaining connections complete or the maximum deadline forces them
+			// to shut down. Returning early would force them to shut down immediately.
+			let mut force_shutdown = force_shutdown;
+			let _ = force_shutdown.changed().await;
 		};
 
 		drain::run_with_drain(component, drain, max_deadline, min_deadline, accept).await;
-		Ok(())
 	}
 
 	pub async fn proxy_bind(
@@ -1080,6 +1128,7 @@ impl Gateway {
 			let best = listeners
 				.best_match_tls(sni)
 				.ok_or(anyhow!("no TLS listener match for {sni}"))?;
+			best.protocol.start_ocsp_refresh(&inp.upstream);
 			match best.protocol.tls(tls_pol, inp.ca.as_ref()).await {
 				Some(Err(e)) => {
 					// There is a TLS config for this listener, but its invalid. Reject the connection
@@ -1593,6 +1642,22 @@ pub fn auto_server(c: Option<&frontend::HTTP>) -> auto::Builder<::hyper_util::rt
 	b
 }
 
+/// Counts an accepted connection in the active connection gauge until it is dropped.
+struct ActiveConnection(Gauge);
+
+impl ActiveConnection {
+	fn new(gauge: Gauge) -> Self {
+		gauge.inc();
+		Self(gauge)
+	}
+}
+
+impl Drop for ActiveConnection {
+	fn drop(&mut self) {
+		self.0.dec();
+	}
+}
+
 /// The listening socket itself is broken; retrying won't help.
 /// EBADF/ENOTSOCK: fd is dead on all platforms.
 /// EINVAL: permanent on Linux (socket not listening), transient on macOS (can recover).
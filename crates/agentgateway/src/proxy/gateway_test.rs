@@ -1,7 +1,13 @@
+use std::time::Duration;
+
+use agent_core::drain::{self, DrainMode, DrainWatcher};
 use agent_core::strng;
 use assert_matches::assert_matches;
+use prometheus_client::metrics::gauge::Gauge;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 
-use super::{HboneAddress, find_service_by_hostname};
+use super::{Gateway, HboneAddress, find_service_by_hostname};
 use crate::proxy::httpproxy;
 use crate::store::LocalWorkload;
 use crate::test_helpers::proxymock::{setup_proxy_test, simple_mock};
@@ -610,9 +616,17 @@ async fn ingress_use_waypoint_build_transport_falls_back_without_ca() {
 	assert!(backend_call.waypoint().is_some());
 
 	// build_transport with no CA should fall back to plain transport
-	let transport = httpproxy::build_transport(&t.pi, &backend_call, None, None, None, None)
-		.await
-		.expect("build_transport should succeed");
+	let transport = httpproxy::build_transport(
+		&t.pi,
+		&backend_call,
+		None,
+		None,
+		None,
+		Default::default(),
+		None,
+	)
+	.await
+	.expect("build_transport should succeed");
 	// Without CA, it falls back to Plain
 	assert_eq!(transport.name(), "plaintext");
 }
@@ -755,3 +769,113 @@ async fn network_gateway_hostname_resolves_via_service_endpoint() {
 		]
 	);
 }
+
+/// Echoes a single byte, holding the connection open until the client sends it.
+async fn echo_once(mut stream: TcpStream, _drain: DrainWatcher) {
+	let mut buf = [0u8; 1];
+	if let Ok(1) = stream.read(&mut buf).await {
+		let _ = stream.write_all(&buf).await;
+	}
+}
+
+async fn start_draining_bind(
+	drain: DrainWatcher,
+	min_deadline: Duration,
+	max_deadline: Duration,
+	active: Gauge,
+) -> std::net::SocketAddr {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	tokio::spawn(Gateway::accept_connections(
+		strng::literal!("drain-test"),
+		listener,
+		drain,
+		min_deadline,
+		max_deadline,
+		active,
+		echo_once,
+	));
+	addr
+}
+
+async fn wait_for_active(active: &Gauge, want: i64) {
+	tokio::time::timeout(Duration::from_secs(5), async {
+		while active.get() != want {
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	})
+	.await
+	.unwrap_or_else(|_| panic!("expected {want} active connections, got {}", active.get()));
+}
+
+#[tokio::test]
+async fn drain_rejects_new_connections_and_lets_existing_finish() {
+	let (trigger, watcher) = drain::new();
+	let active = Gauge::default();
+	let addr = start_draining_bind(
+		watcher,
+		Duration::from_millis(50),
+		Duration::from_secs(30),
+		active.clone(),
+	)
+	.await;
+	let mut conn = TcpStream::connect(addr).await.unwrap();
+	wait_for_active(&active, 1).await;
+
+	let drained = tokio::spawn(trigger.start_drain_and_wait(DrainMode::Graceful));
+	// Once the minimum deadline has passed, new connections are refused.
+	tokio::time::timeout(Duration::from_secs(5), async {
+		loop {
+			match TcpStream::connect(addr).await {
+				Ok(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+				Err(e) => {
+					assert_eq!(e.kind(), std::io::ErrorKind::ConnectionRefused);
+					return;
+				},
+			}
+		}
+	})
+	.await
+	.expect("new connections should be refused during drain");
+
+	// The existing connection is still served, and holds the drain open until it completes.
+	wait_for_active(&active, 1).await;
+	assert!(!drained.is_finished());
+	conn.write_all(b"x").await.unwrap();
+	let mut buf = [0u8; 1];
+	conn.read_exact(&mut buf).await.unwrap();
+	assert_eq!(&buf, b"x");
+
+	// The drain completes as soon as the connection does, well before the maximum deadline.
+	tokio::time::timeout(Duration::from_secs(5), drained)
+		.await
+		.expect("drain should complete once connections finish")
+		.unwrap();
+	wait_for_active(&active, 0).await;
+}
+
+#[tokio::test]
+async fn drain_force_closes_connections_after_max_deadline() {
+	let (trigger, watcher) = drain::new();
+	let active = Gauge::default();
+	let addr = start_draining_bind(
+		watcher,
+		Duration::from_millis(10),
+		Duration::from_millis(100),
+		active.clone(),
+	)
+	.await;
+	let mut conn = TcpStream::connect(addr).await.unwrap();
+	wait_for_active(&active, 1).await;
+
+	// The connection never completes on its own, so it is closed once the maximum deadline passes.
+	tokio::time::timeout(
+		Duration::from_secs(5),
+		trigger.start_drain_and_wait(DrainMode::Graceful),
+	)
+	.await
+	.expect("drain should complete at the maximum deadline");
+	wait_for_active(&active, 0).await;
+	let mut buf = [0u8; 1];
+	assert_eq!(conn.read(&mut buf).await.unwrap(), 0);
+}
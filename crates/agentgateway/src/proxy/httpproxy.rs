@@ -15,6 +15,7 @@ use hyper::upgrade::OnUpgrade;
 use hyper_util::rt::TokioIo;
 use rand::RngExt;
 use rand::seq::{IndexedRandom, IteratorRandom};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tracing::{debug, trace};
 use types::agent::*;
 use types::discovery::*;
@@ -26,10 +27,10 @@ use crate::http::buffer::Buffer;
 use crate::http::ext_proc::{ExtProcRequest, InferenceRoutingDestinationMode};
 use crate::http::filters::{AutoHostname, BackendRequestTimeout};
 use crate::http::transformation_cel::Transformation;
-use crate::http::x_headers::TRACEPARENT;
+use crate::http::x_headers::{TRACEPARENT, TRACESTATE};
 use crate::http::{
-	Authority, HeaderName, HeaderValue, Request, Response, Scheme, StatusCode, Uri, auth, filters,
-	merge_in_headers, retry,
+	Authority, HeaderName, HeaderValue, Request, Response, Scheme, StatusCode, Uri, auth,
+	circuitbreaker, filters, merge_in_headers, retry,
 };
 use crate::llm::{
 	InputFormat, LLMInfo, LLMRequest, LLMResponse, RequestResult, RouteType, model_router,
@@ -37,6 +38,7 @@ use crate::llm::{
 use crate::proxy::tcpproxy::TCPProxy;
 use crate::proxy::{
 	ProxyError, ProxyResponse, ProxyResponseReason, WaypointService, dtrace, resolve_simple_backend,
+	tap,
 };
 use crate::store::{
 	BackendPolicies, FrontendPolices, GatewayPolicies, LLMRequestPolicies, LLMResponsePolicies,
@@ -44,14 +46,23 @@ use crate::store::{
 };
 use crate::telemetry::log;
 use crate::telemetry::log::{AsyncLog, DropOnLog, LogBody, RequestLog, TraceSampler};
-use crate::telemetry::metrics::{OutboundCallKind, OutboundCallLabels, OutboundCallSubtype};
+use crate::telemetry::metrics::{
+	CircuitBreakerLabels, OutboundCallKind, OutboundCallLabels, OutboundCallSubtype,
+};
 use crate::telemetry::trc::TraceParent;
 use crate::transport::stream::{Extension, Socket, TCPConnectionInfo, TLSConnectionInfo};
 use crate::types::local::InternalBackend;
 use crate::types::{backend, frontend};
 use crate::{ProxyInputs, store, *};
 
-fn select_backend(route: &Route, _req: &Request) -> Option<RouteBackendReference> {
+fn select_backend(route: &Route, req: &Request) -> Option<RouteBackendReference> {
+	if let Some(hash) = route
+		.backend_hash_key
+		.as_ref()
+		.and_then(|expr| backend_hash(expr, req))
+	{
+		return select_backend_by_hash(&route.backends, hash).cloned();
+	}
 	route
 		.backends
 		.choose_weighted(&mut rand::rng(), |b| b.weight)
@@ -59,6 +70,46 @@ fn select_backend(route: &Route, _req: &Request) -> Option<RouteBackendReference
 		.cloned()
 }
 
+/// Computes a stable hash of the route's backend hash key. Returns None if the key evaluates to
+/// null or fails to evaluate, in which case selection falls back to random.
+fn backend_hash(expr: &crate::cel::Expression, req: &Request) -> Option<u64> {
+	use sha2::Digest;
+	let exec = crate::cel::Executor::new_request(req);
+	let key = match exec.eval(expr) {
+		Ok(crate::cel::Value::Null) => return None,
+		Ok(v) => crate::cel::value_as_byte_or_json(v).ok()?,
+		Err(e) => {
+			debug!("failed to evaluate backend hash key: {e}");
+			return None;
+		},
+	};
+	let digest = sha2::Sha256::digest(&key);
+	Some(u64::from_be_bytes(
+		digest[..8].try_into().expect("digest is 32 bytes"),
+	))
+}
+
+/// Picks a backend by mapping the hash onto the cumulative weights, so a given hash always lands
+/// on the same backend while the share of hashes each backend receives follows its weight.
+fn select_backend_by_hash(
+	backends: &[RouteBackendReference],
+	hash: u64,
+) -> Option<&RouteBackendReference> {
+	let total: u64 = backends.iter().map(|b| b.weight as u64).sum();
+	if total == 0 {
+		return None;
+	}
+	let mut point = hash % total;
+	for b in backends {
+		let weight = b.weight as u64;
+		if point < weight {
+			return Some(b);
+		}
+		point -= weight;
+	}
+	None
+}
+
 #[derive(Debug)]
 struct SelectedRouteChain {
 	routes: Vec<Arc<Route>>,
@@ -126,6 +177,8 @@ pub fn apply_logging_policy_to_log(log: &mut RequestLog, lp: &frontend::LoggingP
 			log::LoggingFields {
 				add: fields.add.clone(),
 				remove: fields.remove.clone(),
+				redact: log.cel.fields.redact.clone(),
+				redact_strategy: log.cel.fields.redact_strategy,
 			}
 		} else {
 			log.cel.fields.clone()
@@ -136,6 +189,9 @@ pub fn apply_logging_policy_to_log(log: &mut RequestLog, lp: &frontend::LoggingP
 	{
 		log.cel.database_fields.add = database.add.clone();
 	}
+	if lp.format.is_some() {
+		log.cel.format = lp.format.clone();
+	}
 }
 
 async fn apply_request_policies(
@@ -145,6 +201,17 @@ async fn apply_request_policies(
 	req: &mut Request,
 	rp: &mut ResponsePolicies,
 ) -> Result<(), ProxyResponse> {
+	// Request ID runs first so the ID is available to every later policy, and is echoed on the
+	// response even if the request is rejected.
+	pol
+		.request_id
+		.apply_without_response("request id", c, l, req, rp.headers())
+		.await?;
+	pol
+		.header_limit
+		.apply_without_response("header limit", c, l, req, rp.headers())
+		.await?;
+
 	// CORS must run before authentication, authorization and rate limiting so that:
 	// 1. Preflight OPTIONS requests short-circuit without requiring credentials
 	// 2. CORS response headers are queued even if the request is later rejected,
@@ -154,6 +221,13 @@ async fn apply_request_policies(
 		.apply_without_response("cors", c, l, req, rp.headers())
 		.await?;
 
+	// gRPC-Web is translated right after CORS (browsers send a preflight first), so every later
+	// policy and the backend see a regular gRPC request.
+	rp.grpc_web = pol
+		.grpc_web
+		.select("grpc web", req)
+		.and_then(|p| p.translate_request(req));
+
 	pol
 		.oidc
 		.apply_without_response("oidc", c, l, req, rp.headers())
@@ -193,6 +267,11 @@ async fn apply_request_policies(
 		.apply_selected("remote rate limit", c, l, req, rp.headers())
 		.await?;
 
+	rp.llm_request_policies.redis_rate_limit = pol
+		.redis_rate_limit
+		.apply_selected("redis rate limit", c, l, req, rp.headers())
+		.await?;
+
 	rp.buffer = pol.buffer.apply("buffer", c, l, req, rp.headers()).await?;
 
 	// ExtProc uses RequestPolicy for conditional selection and CEL registration only.
@@ -472,6 +551,13 @@ async fn apply_llm_request_policies(
 		(http::PolicyResponse::default(), None)
 	};
 	rl_resp.apply(response_headers)?;
+	let redis_rate_limit = match &policies.redis_rate_limit {
+		Some(rl) => {
+			rl.check_llm(req, llm_req.input_tokens.unwrap_or_default())
+				.await?
+		},
+		None => None,
+	};
 	let prompt_guard = policies
 		.llm
 		.as_deref()
@@ -479,6 +565,7 @@ async fn apply_llm_request_policies(
 	Ok(store::LLMResponsePolicies {
 		local_rate_limit,
 		remote_rate_limit: response,
+		redis_rate_limit,
 		request_traceparent: req.headers().get(TRACEPARENT).cloned(),
 		prompt_guard: prompt_guard.map(|g| g.response.clone()).unwrap_or_default(),
 		streaming_prompt_guard_enabled: prompt_guard.is_some_and(|g| g.streaming.is_enabled()),
@@ -570,6 +657,7 @@ impl HTTPProxy {
 		req
 			.extensions_mut()
 			.insert(RequestTime(start.as_datetime()));
+		let tap = tap::start(&mut req);
 		let log = RequestLog::new(
 			log::CelLogging::new(
 				self.inputs.cfg.logging.clone(),
@@ -598,7 +686,8 @@ impl HTTPProxy {
 				} else {
 					None
 				}
-			})
+			});
+			l.route_timeout = matches!(ret, Err(ProxyResponse::Error(ProxyError::RequestTimeout)));
 		});
 		let reason = match &ret {
 			Ok(_) => ProxyResponseReason::Upstream,
@@ -652,6 +741,11 @@ impl HTTPProxy {
 				.await
 				.unwrap_or_else(|e| e.into_response_with_grpc(is_grpc_request))
 		} else {
+			// Only regular responses are captured; tunnels and upgrades are not.
+			let resp = match tap {
+				Some(tap) => tap.finish(resp),
+				None => resp,
+			};
 			resp.map(move |b| http::Body::new(LogBody::new(b, log)))
 		}
 	}
@@ -663,7 +757,7 @@ impl HTTPProxy {
 		response_policies: &mut ResponsePolicies,
 	) -> Result<Response, SnapshottedProxyResponse> {
 		log.tls_info = req.extensions().get::<TLSConnectionInfo>().cloned();
-		log.backend_protocol = Some(cel::BackendProtocol::http);
+		log.backend_protocol = Some(request_protocol(&req));
 
 		let selected_listener = self.selected_listener.clone();
 		let inputs = self.inputs.clone();
@@ -892,7 +986,7 @@ impl HTTPProxy {
 		backend_policies.register_cel_expressions(log.cel.ctx());
 		log.cel.ctx().maybe_buffer_request_body(&mut req).await;
 		log.health_policy = backend_policies.health.clone();
-		log.backend_info = Some(selected_backend.backend.backend.backend_info());
+		log.backend_info = Some(selected_backend.backend.backend_info());
 		if let Some(bp) = selected_backend.backend.backend.backend_protocol() {
 			log.backend_protocol = Some(bp)
 		}
@@ -920,33 +1014,44 @@ impl HTTPProxy {
 				.snapshot_on_err(log, &mut req);
 		}
 
-		let route_request_mirrors = route_policies.request_mirror.select("request mirror", &req);
-		let route_llm = route_policies.llm.select("llm", &req);
-		let (head, body) = req.into_parts();
-		for mirror in route_request_mirrors
+		let mirrors = route_policies
+			.request_mirror
+			.select("request mirror", &req)
 			.iter()
 			.flat_map(|mirrors| mirrors.iter())
 			.chain(backend_policies.request_mirror.iter())
-		{
-			if !rand::rng().random_bool(mirror.percentage) {
-				trace!(
-					"skipping mirror, percentage {} not triggered",
-					mirror.percentage
-				);
-				continue;
-			}
-			// TODO: mirror the body. For now, we just ignore the body
-			let req = Request::from_parts(head.clone(), http::Body::empty());
-			let inputs = inputs.clone();
-			let policy_client = self.policy_client();
-			let mirror = mirror.clone();
-			tokio::task::spawn(async move {
-				if let Err(e) = send_mirror(inputs, policy_client, mirror, req).await {
-					warn!("error sending mirror request: {}", e);
+			.filter(|mirror| {
+				let triggered = rand::rng().random_bool(mirror.percentage);
+				if !triggered {
+					trace!(
+						"skipping mirror, percentage {} not triggered",
+						mirror.percentage
+					);
 				}
-			});
+				triggered
+			})
+			.cloned()
+			.collect::<Vec<_>>();
+		let mirror_body = if mirrors.is_empty() {
+			None
+		} else {
+			mirror_body(&mut req).await
+		};
+		let route_llm = route_policies.llm.select("llm", &req);
+		let (head, body) = req.into_parts();
+		if let Some(mirror_body) = mirror_body {
+			for mirror in mirrors {
+				let req = Request::from_parts(head.clone(), http::Body::from(mirror_body.clone()));
+				let inputs = inputs.clone();
+				let policy_client = self.policy_client();
+				// Mirrors are fire-and-forget: the response is discarded and errors never reach the client.
+				tokio::task::spawn(async move {
+					if let Err(e) = send_mirror(inputs, policy_client, mirror, req).await {
+						warn!("error sending mirror request: {}", e);
+					}
+				});
+			}
 		}
-		const MAX_BUFFERED_BYTES: usize = 64 * 1024;
 		let retries = route_retry;
 
 		// LLM token rate limiting reuses the rate-limit policy selected above in the normal
@@ -956,8 +1061,21 @@ impl HTTPProxy {
 		llm_request_policies.llm = route_llm;
 		let llm_request_policies = Arc::new(llm_request_policies);
 
+		// LLM fallbacks re-issue the request to another provider, so they count as extra attempts.
+		let fallbacks = llm_request_policies
+			.llm
+			.as_ref()
+			.or(backend_policies.llm.as_ref())
+			.map(|p| p.fallbacks.clone())
+			.unwrap_or_default();
+		let mut remaining_fallbacks = fallbacks.iter();
+		let mut remaining_retries = retries.as_ref().map(|r| r.attempts.get()).unwrap_or(0);
+		// Once we fall back, later retries stay on that provider rather than returning to the original.
+		let mut active_fallback: Option<&llm::policy::ModelFallback> = None;
 		// attempts is the total number of attempts, not the retries
-		let attempts = retries.as_ref().map(|r| r.attempts.get() + 1).unwrap_or(1);
+		let attempts = remaining_retries
+			.saturating_add(u8::try_from(fallbacks.len()).unwrap_or(u8::MAX))
+			.saturating_add(1);
 		let retry_backoff = retries.as_ref().and_then(|r| r.backoff);
 		let request_timeout = response_policies
 			.timeout
@@ -1008,6 +1126,9 @@ impl HTTPProxy {
 				next = Some(this.clone());
 			}
 			let mut head = head.clone();
+			if let Some(fallback) = active_fallback {
+				head.extensions.insert(fallback.clone());
+			}
 			if n > 0 {
 				log.retry_attempt = Some(n);
 				head.headers.insert(
@@ -1028,26 +1149,41 @@ impl HTTPProxy {
 					req,
 				)
 				.await;
-			if last
-				|| !should_retry(
-					&res,
-					retries.as_ref().unwrap(),
-					log.request_snapshot.as_deref(),
-				) {
+			let next_fallback = if should_fall_back(&res, log) {
+				remaining_fallbacks.next()
+			} else {
+				None
+			};
+			let retry = next_fallback.is_none()
+				&& remaining_retries > 0
+				&& retries
+					.as_ref()
+					.is_some_and(|r| should_retry(&res, r, log.request_snapshot.as_deref()));
+			if last || (next_fallback.is_none() && !retry) {
 				if !last {
 					debug!("response not retry-able");
 				}
 				return res;
 			}
-			debug!(
-				backoff=?retry_backoff,
-				"attempting another retry, last result was {} {:?}",
-				res.is_err(),
-				res.as_ref().map(|r| r.status())
-			);
+			if let Some(fallback) = next_fallback {
+				active_fallback = Some(fallback);
+				debug!(
+					provider=%fallback.provider,
+					"falling back to another provider, last result was {:?}",
+					res.as_ref().map(|r| r.status())
+				);
+			} else {
+				remaining_retries -= 1;
+				debug!(
+					backoff=?retry_backoff,
+					"attempting another retry, last result was {} {:?}",
+					res.is_err(),
+					res.as_ref().map(|r| r.status())
+				);
+			}
 			finalize_attempt_for_retry(log, &mut res);
 			last_res = Some(res);
-			if let Some(bo) = retry_backoff {
+			if retry && let Some(bo) = retry_backoff {
 				let fut = if let Some(request_timeout) = request_timeout {
 					let deadline = tokio::time::Instant::from_std(log.start.as_instant() + request_timeout);
 					tokio::time::timeout_at(deadline, tokio::time::sleep(bo)).await
@@ -1170,6 +1306,7 @@ impl HTTPProxy {
 				sampler.client_sampling = Some(cs.clone());
 				log.cel.cel_context.register_expression(cs.as_ref());
 			}
+			sampler.sampler = tp.config.sampler;
 			if let Some(f) = &tp.config.filter {
 				log.cel.cel_context.register_log_expression(f.as_ref());
 			}
@@ -1177,8 +1314,25 @@ impl HTTPProxy {
 		}
 		log.cel.ctx().maybe_buffer_request_body(req).await;
 
+		if let Some(baggage) = trc::Baggage::from_headers(req.headers()) {
+			req.extensions_mut().insert(baggage);
+		}
+		if let Some(allowed) = frontend_policies
+			.tracing
+			.as_deref()
+			.and_then(|tp| tp.config.baggage.as_deref())
+		{
+			trc::Baggage::retain_allowed(req.headers_mut(), allowed);
+		}
+
 		let trace_parent = trc::TraceParent::from_request(req);
-		let trace_sampled = sampler.trace_sampled(req, trace_parent.as_ref());
+		// Pick the ID of a new trace up front, since the built-in sampler decides by trace ID.
+		let new_trace = TraceParent::new();
+		let trace_sampled = sampler.trace_sampled(
+			req,
+			trace_parent.as_ref(),
+			trace_parent.as_ref().unwrap_or(&new_trace).trace_id,
+		);
 
 		// Use dynamic tracer from frontend policy if available, otherwise use static tracer
 		if trace_sampled {
@@ -1212,8 +1366,9 @@ impl HTTPProxy {
 					ns
 				},
 				None => {
-					// Build an entirely new trace
-					let mut ns = TraceParent::new();
+					// Build an entirely new trace. Any tracestate belongs to a different (or invalid) trace.
+					req.headers_mut().remove(TRACESTATE);
+					let mut ns = new_trace;
 					ns.flags = 1;
 					ns
 				},
@@ -1306,7 +1461,7 @@ impl HTTPProxy {
 		let call = make_backend_call(
 			self.inputs.clone(),
 			route_policies.clone(),
-			&selected_backend.backend.backend,
+			&selected_backend.backend,
 			backend_policies,
 			route_path,
 			MustSnapshot::new(&mut req_opt),
@@ -1409,6 +1564,10 @@ async fn handle_upgrade(
 			resp_upgrade_type,
 		));
 	}
+	let websocket = resp
+		.extensions_mut()
+		.remove::<backend::WebSocket>()
+		.filter(|_| upgrade_type.as_bytes().eq_ignore_ascii_case(b"websocket"));
 	let response_upgraded = resp
 		.extensions_mut()
 		.remove::<OnUpgrade>()
@@ -1423,40 +1582,15 @@ async fn handle_upgrade(
 				return;
 			},
 		};
-		let mut server = TokioIo::new(response_upgraded);
-		if let Some(log) = log.as_ref()
-			&& let Some(llm_req) = log.llm_request.as_ref()
-			&& llm_req.input_format == InputFormat::Realtime
-		{
-			let llm = log.llm_response.clone();
-			let llm_info = LLMInfo::new(llm_req.clone(), LLMResponse::default());
-			llm.store(Some(llm_info));
-			if let Some(guard_context) = realtime_guard_context {
-				parse::websocket::guarded_realtime_proxy(
-					TokioIo::new(req),
-					server,
-					guard_context.prompt_guard,
-					guard_context.policy_client,
-					llm,
-					guard_context.req_headers,
-				)
-				.await;
-				return;
-			}
-			let mut server = parse::websocket::parser(server, llm).await;
-			let _ = agent_core::copy::copy_bidirectional(
-				&mut TokioIo::new(req),
-				&mut server,
-				&agent_core::copy::ConnectionResult {},
-			)
-			.await;
-		} else {
-			let _ = agent_core::copy::copy_bidirectional(
-				&mut TokioIo::new(req),
-				&mut server,
-				&agent_core::copy::ConnectionResult {},
-			)
-			.await;
+		let client = TokioIo::new(req);
+		let server = TokioIo::new(response_upgraded);
+		match websocket {
+			Some(ws) => {
+				let client = parse::websocket::relay(client, ws.clone(), false);
+				let server = parse::websocket::relay(server, ws, true);
+				proxy_upgraded(client, server, &log, realtime_guard_context).await;
+			},
+			None => proxy_upgraded(client, server, &log, realtime_guard_context).await,
 		}
 		// Make sure we only emit log after we are done with the entire connection
 		drop(log);
@@ -1464,6 +1598,51 @@ async fn handle_upgrade(
 	Ok(resp)
 }
 
+async fn proxy_upgraded<C, S>(
+	mut client: C,
+	mut server: S,
+	log: &DropOnLog,
+	realtime_guard_context: Option<RealtimeGuardContext>,
+) where
+	C: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+	S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+	if let Some(log) = log.as_ref()
+		&& let Some(llm_req) = log.llm_request.as_ref()
+		&& llm_req.input_format == InputFormat::Realtime
+	{
+		let llm = log.llm_response.clone();
+		let llm_info = LLMInfo::new(llm_req.clone(), LLMResponse::default());
+		llm.store(Some(llm_info));
+		if let Some(guard_context) = realtime_guard_context {
+			parse::websocket::guarded_realtime_proxy(
+				client,
+				server,
+				guard_context.prompt_guard,
+				guard_context.policy_client,
+				llm,
+				guard_context.req_headers,
+			)
+			.await;
+			return;
+		}
+		let mut server = parse::websocket::parser(server, llm).await;
+		let _ = agent_core::copy::copy_bidirectional(
+			&mut client,
+			&mut server,
+			&agent_core::copy::ConnectionResult {},
+		)
+		.await;
+	} else {
+		let _ = agent_core::copy::copy_bidirectional(
+			&mut client,
+			&mut server,
+			&agent_core::copy::ConnectionResult {},
+		)
+		.await;
+	}
+}
+
 fn handle_connect_tunnel(connect: ConnectTunnel, resp: Response, log: DropOnLog) -> Response {
 	tokio::task::spawn(async move {
 		let Some(mut upstream) = connect
@@ -1552,9 +1731,15 @@ pub async fn build_transport(
 	hbone_source: Option<HboneSourceRole>,
 	backend_tls: Option<BackendTLS>,
 	backend_tunnel: Option<&backend::Tunnel>,
+	backend_http_version_preference: backend::HttpVersionPreference,
 	backend_http_version_override: Option<::http::Version>,
 ) -> Result<Transport, ProxyError> {
-	let backend_tls = backend_tls.map(|btls| btls.config_for(backend_http_version_override));
+	let backend_tls = backend_tls.map(|btls| {
+		btls.config_for_preference(
+			backend_http_version_preference,
+			backend_http_version_override,
+		)
+	});
 	let app_transport = if let Some(tls) = backend_tls {
 		ApplicationTransport::Tls(tls)
 	} else {
@@ -1574,6 +1759,7 @@ pub async fn build_transport(
 			hbone_source,
 			tunnel_backend_tls,
 			None,
+			backend::HttpVersionPreference::Auto,
 			// Currently we only support HTTP/1.1
 			Some(::http::Version::HTTP_11),
 		))
@@ -1693,6 +1879,12 @@ async fn build_backend_transport(
 		hbone_source,
 		backend_call.backend_policies.backend_tls.clone(),
 		backend_call.backend_policies.tunnel.as_ref(),
+		backend_call
+			.backend_policies
+			.http
+			.as_ref()
+			.map(|h| h.http_version_preference)
+			.unwrap_or_default(),
 		backend_call
 			.backend_policies
 			.http
@@ -1893,6 +2085,7 @@ async fn build_simple_backend_call(
 				backend_tls: Some(http::backendtls::SYSTEM_TRUST.clone()),
 				backend_auth: Some(auth::BackendAuth::Aws(auth::AwsAuth::Implicit {
 					service_name: Some(config.service_name().to_string()),
+					region: None,
 					assume_role: None,
 					source_credentials_cache: Default::default(),
 					assume_role_cache: Default::default(),
@@ -1915,13 +2108,15 @@ async fn build_simple_backend_call(
 async fn make_backend_call(
 	inputs: Arc<ProxyInputs>,
 	route_policies: Arc<store::LLMRequestPolicies>,
-	backend: &Backend,
+	backend: &BackendWithPolicies,
 	base_policies: Arc<BackendPolicies>,
 	route_path: Option<RoutePath<'_>>,
 	mut req: MustSnapshot<'_>,
 	mut log: Option<&mut RequestLog>,
 	response_policies: &mut ResponsePolicies,
 ) -> Result<Response, ProxyResponse> {
+	let metadata = backend.metadata.clone();
+	let backend = &backend.backend;
 	if let Backend::LLMRouter(_, router) = backend {
 		let resolved = match router.resolve(&mut req).await {
 			model_router::ResolveResult::DirectResponse(resp) => return Ok(resp),
@@ -1936,11 +2131,10 @@ async fn make_backend_call(
 		);
 		let route_policies = route_policies.merge_backend_policies(Some(resolved.llm_policy));
 		let policies = Arc::new(base_policies.as_ref().clone().merge(concrete_policies));
-		let backend = selected_backend.backend.backend;
 		return Box::pin(make_backend_call(
 			inputs,
 			route_policies,
-			&backend,
+			&selected_backend.backend,
 			policies,
 			route_path,
 			req,
@@ -1986,7 +2180,10 @@ async fn make_backend_call(
 	};
 
 	log.add(|l| {
-		l.backend_info = Some(backend.backend_info());
+		l.backend_info = Some(BackendInfo {
+			metadata,
+			..backend.backend_info()
+		});
 		if let Some(bp) = backend.backend_protocol() {
 			l.backend_protocol = Some(bp)
 		}
@@ -1994,7 +2191,11 @@ async fn make_backend_call(
 
 	let (mut backend_call, mut maybe_inference) = match backend {
 		Backend::AI(n, ai) => {
-			let (provider, handle) = ai.select_provider().ok_or(ProxyError::NoHealthyEndpoints)?;
+			let selected = match req.extensions().get::<llm::policy::ModelFallback>() {
+				Some(fallback) => ai.select_fallback(fallback),
+				None => ai.select_provider(),
+			};
+			let (provider, handle) = selected.ok_or(ProxyError::NoHealthyEndpoints)?;
 			log.add(move |l| l.request_handle = Some(handle));
 			let sub_backend_name = BackendTargetRef::Backend {
 				name: n.name.as_ref(),
@@ -2039,7 +2240,7 @@ async fn make_backend_call(
 					None => {
 						let mut pol = provider
 							.provider
-							.default_connector_policies()
+							.default_connector_policies(provider.client_tls.as_ref())
 							.ok_or_else(|| {
 								ProxyError::ProcessingString(
 									"custom providers require an explicit host override or provider backend"
@@ -2173,6 +2374,31 @@ async fn make_backend_call(
 		Backend::Invalid => return Err(ProxyResponse::from(ProxyError::BackendDoesNotExist)),
 	};
 	log.add(|l| l.health_policy = backend_call.backend_policies.health.clone());
+	// Hold a circuit breaker slot until the request completes. Without a log to attach it to, the
+	// slot is only held until the response headers are received.
+	let mut _circuit_breaker_permit = None;
+	if let Some(cb) = &backend_call.backend_policies.circuit_breaker {
+		let labels = CircuitBreakerLabels {
+			backend: backend.name().into(),
+		};
+		let metrics = circuitbreaker::QueueMetrics {
+			depth: inputs
+				.metrics
+				.circuit_breaker_queue_depth
+				.get_or_create(&labels)
+				.clone(),
+			wait: inputs
+				.metrics
+				.circuit_breaker_queue_wait
+				.get_or_create(&labels)
+				.clone(),
+		};
+		let permit = cb.acquire(&req, &metrics).await?;
+		match log.as_mut() {
+			Some(log) => log.circuit_breaker_permit = Some(permit),
+			None => _circuit_breaker_permit = Some(permit),
+		}
+	}
 	if let Some(log) = log.as_mut() {
 		backend_call
 			.backend_policies
@@ -2245,7 +2471,7 @@ async fn make_backend_call(
 							&backend_info,
 							llm_request_policies.llm.as_deref(),
 							req,
-							llm.tokenize,
+							llm.tokenization(),
 							&mut log,
 						))
 						.await
@@ -2254,7 +2480,7 @@ async fn make_backend_call(
 							&backend_info,
 							llm_request_policies.llm.as_deref(),
 							req,
-							llm.tokenize,
+							llm.tokenization(),
 							&mut log,
 						))
 						.await
@@ -2263,7 +2489,7 @@ async fn make_backend_call(
 							&backend_info,
 							llm_request_policies.llm.as_deref(),
 							req,
-							llm.tokenize,
+							llm.tokenization(),
 							&mut log,
 						))
 						.await
@@ -2272,7 +2498,7 @@ async fn make_backend_call(
 							&backend_info,
 							llm_request_policies.llm.as_deref(),
 							req,
-							llm.tokenize,
+							llm.tokenization(),
 							&mut log,
 						))
 						.await
@@ -2281,7 +2507,7 @@ async fn make_backend_call(
 							&backend_info,
 							llm_request_policies.llm.as_deref(),
 							req,
-							llm.tokenize,
+							llm.tokenization(),
 							&mut log,
 						))
 						.await
@@ -2290,6 +2516,7 @@ async fn make_backend_call(
 							&backend_info,
 							req,
 							llm_request_policies.llm.as_deref(),
+							llm.tokenizer,
 							&mut log,
 						))
 						.await
@@ -2477,17 +2704,20 @@ async fn make_backend_call(
 		.map(|l| l.cel.cel_context.needs_llm_completion())
 		.unwrap_or_default();
 	let a2a_type = response_policies.a2a_type.clone();
+	let metrics = log.as_ref().map(|l| l.metrics.clone());
 
 	let outbound_subtype = if backend_call.backend_policies.llm_provider.is_some() {
 		OutboundCallSubtype::Llm
 	} else {
 		OutboundCallSubtype::Http
 	};
+	let request_header_bytes = http::headerlimit::header_bytes(call.req.headers()) as u64;
 	let outbound_start = std::time::Instant::now();
 	log.add(|l| {
 		if l.request_processing_duration.is_none() {
 			l.request_processing_duration = Some(l.request_processing_start.elapsed());
 		}
+		l.request_header_bytes = Some(request_header_bytes);
 	});
 	let resp = upstream.call(call).await;
 	let outbound_end = Instant::now();
@@ -2500,8 +2730,9 @@ async fn make_backend_call(
 			})
 			.observe((outbound_end - outbound_start).as_secs_f64());
 		l.upstream_duration = Some(outbound_end - outbound_start);
-		if resp.is_ok() {
+		if let Ok(resp) = &resp {
 			l.response_processing_start = Some(outbound_end);
+			l.response_header_bytes = Some(http::headerlimit::header_bytes(resp.headers()) as u64);
 		}
 	});
 	dtrace::trace(|trace| match &resp {
@@ -2519,6 +2750,16 @@ async fn make_backend_call(
 		),
 	});
 	let mut resp = resp?;
+	if resp.status() == StatusCode::SWITCHING_PROTOCOLS
+		&& let Some(ws) = backend_call
+			.backend_policies
+			.http
+			.as_ref()
+			.and_then(|h| h.websocket.clone())
+	{
+		// Picked up by handle_upgrade once the connection is upgraded.
+		resp.extensions_mut().insert(ws);
+	}
 	if let Some(log) = log.as_ref() {
 		resp
 			.extensions_mut()
@@ -2540,6 +2781,7 @@ async fn make_backend_call(
 		backend_call.backend_policies.llm_provider.clone(),
 		llm_request,
 	) {
+		llm.apply_response_buffer_limit(&mut resp);
 		Box::pin(
 			llm
 				.provider
@@ -2551,6 +2793,7 @@ async fn make_backend_call(
 					llm_response_log.expect("must be set"),
 					include_completion_in_log,
 					Some(&inputs.model_catalog),
+					metrics.as_deref(),
 					resp,
 				)
 				.assert_size::<{ 4 * 1024 }>(),
@@ -2586,10 +2829,21 @@ fn set_backend_cel_context(req: &mut http::Request, log: Option<&&mut RequestLog
 			name: bi.backend_name.clone(),
 			backend_type: bi.backend_type,
 			protocol: bp,
+			metadata: bi.metadata.clone(),
 		});
 	}
 }
 
+/// request_protocol is the protocol of an HTTP request, before any backend-specific protocol (such
+/// as MCP or LLM) is known.
+fn request_protocol(req: &Request) -> cel::BackendProtocol {
+	if http::is_grpc_request(req) {
+		cel::BackendProtocol::grpc
+	} else {
+		cel::BackendProtocol::http
+	}
+}
+
 fn connect_authority_target(req: &Request) -> Result<Target, ProxyError> {
 	let authority = req.uri().authority().ok_or(ProxyError::InvalidRequest)?;
 	let port = authority.port_u16().ok_or(ProxyError::InvalidRequest)?;
@@ -2663,6 +2917,14 @@ pub fn build_service_call(
 		});
 	}
 
+	if let Some(check) = backend_policies
+		.health
+		.as_ref()
+		.and_then(|h| h.active.as_ref())
+	{
+		start_service_health_checks(inputs, svc, port, check);
+	}
+
 	let discovery = inputs.stores.read_discovery();
 	let workloads = &discovery.workloads;
 	let (ep, handle, wl) = svc
@@ -2827,6 +3089,36 @@ pub fn build_service_call(
 	})
 }
 
+/// Starts active health checks for a service's endpoints, unless they are already running. Probes
+/// are sent in plaintext directly to each workload on the endpoint's target port.
+fn start_service_health_checks(
+	inputs: &ProxyInputs,
+	svc: &Service,
+	svc_port: u16,
+	check: &http::healthcheck::ActiveHealthCheck,
+) {
+	svc.endpoints.start_health_checks(|endpoints| {
+		let stores = inputs.stores.clone();
+		// Capture only what the probe needs; holding the Service would keep the checker alive.
+		let svc_target_port = svc.ports.get(&svc_port).copied().filter(|p| *p > 0);
+		tokio::spawn(http::healthcheck::run(
+			check.clone(),
+			endpoints,
+			inputs.upstream.clone(),
+			Transport::default(),
+			move |ep: &Endpoint| {
+				let wl = stores
+					.read_discovery()
+					.workloads
+					.find_uid(&ep.workload_uid)?;
+				let ip = *wl.workload_ips.first()?;
+				let port = ep.port.get(&svc_port).copied().or(svc_target_port)?;
+				Some(Target::Address(SocketAddr::new(ip, port)))
+			},
+		))
+	});
+}
+
 fn select_service_target_port(
 	ep: &Endpoint,
 	svc: &Service,
@@ -3039,18 +3331,30 @@ fn should_retry(
 	}
 }
 
+/// should_fall_back reports whether a non-streaming LLM request failed with a status that the
+/// next configured fallback provider may be able to serve.
+fn should_fall_back(res: &Result<Response, SnapshottedProxyResponse>, log: &RequestLog) -> bool {
+	let Ok(resp) = res else {
+		return false;
+	};
+	if log.llm_request.as_ref().is_none_or(|r| r.streaming) {
+		return false;
+	}
+	resp.status() == StatusCode::TOO_MANY_REQUESTS || resp.status().is_server_error()
+}
+
 #[cfg(test)]
 mod tests {
 	use std::collections::{HashMap, HashSet};
 	use std::net::SocketAddr;
 	use std::sync::Arc;
 
-	use ::http::Method;
+	use ::http::{Method, header};
 	use serde_json::json;
 	use wiremock::{Mock, ResponseTemplate};
 
 	use super::{
-		apply_auto_hostname, apply_llm_request_policies, hop_by_hop_headers,
+		apply_auto_hostname, apply_llm_request_policies, hop_by_hop_headers, request_protocol,
 		resolved_workload_target_hostname, select_service_target_port,
 	};
 	use crate::http::filters::AutoHostname;
@@ -3063,7 +3367,7 @@ mod tests {
 	use crate::types::agent::{Backend, ResourceName, Target};
 	use crate::types::discovery::{AppProtocol, Endpoint, HealthStatus, Service};
 	use crate::types::local::LocalAIBackend;
-	use crate::{http, llm};
+	use crate::{cel, http, llm};
 
 	fn retry_policy(codes: &[u16], condition: Option<&str>) -> crate::http::retry::Policy {
 		crate::http::retry::Policy {
@@ -3086,6 +3390,7 @@ mod tests {
 				action: Default::default(),
 				rules: vec![RegexRule::Regex {
 					pattern: regex::Regex::new("secret").unwrap(),
+					mask: None,
 				}],
 			}),
 		}
@@ -3388,6 +3693,206 @@ mod tests {
 		assert!(!req.headers().contains_key("x-original-url"));
 	}
 
+	#[test]
+	fn grpc_request_sets_backend_protocol() {
+		let build = |content_type: &str| {
+			::http::Request::builder()
+				.uri("http://app/pkg.Service/Method")
+				.header(header::CONTENT_TYPE, content_type)
+				.body(http::Body::empty())
+				.expect("request should build")
+		};
+		assert_eq!(
+			request_protocol(&build("application/json")),
+			cel::BackendProtocol::http
+		);
+
+		let mut req = build("application/grpc+proto");
+		req.extensions_mut().insert(cel::BackendContext {
+			name: "backend".into(),
+			backend_type: cel::BackendType::Service,
+			protocol: request_protocol(&req),
+			metadata: Default::default(),
+		});
+		let expr = cel::Expression::new_strict(r#"backend.protocol == "grpc""#).unwrap();
+		assert!(cel::Executor::new_request(&req).eval_bool(&expr));
+	}
+
+	async fn wait_for_requests(mock: &wiremock::MockServer) -> Vec<wiremock::Request> {
+		for _ in 0..50 {
+			let requests = mock.received_requests().await.expect("request recording");
+			if !requests.is_empty() {
+				return requests;
+			}
+			tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+		}
+		Vec::new()
+	}
+
+	#[tokio::test]
+	async fn request_mirror_receives_request_body() {
+		let primary = proxymock::body_mock(b"primary").await;
+		let mirror = proxymock::body_mock(b"mirror").await;
+		let mut bind = proxymock::base_gateway(&primary);
+		bind
+			.attach_route_policy(json!({
+				"requestMirror": {
+					"backend": {
+						"host": mirror.address().to_string()
+					},
+					"percentage": 1.0
+				}
+			}))
+			.await;
+		let io = bind.serve_http(proxymock::BIND_KEY);
+
+		let res =
+			proxymock::send_request_body(io, Method::POST, "http://lo/mirror", b"hello mirror").await;
+		assert_eq!(res.status(), 200);
+		let body = http::read_resp_body(res).await.unwrap();
+		assert_eq!(body.as_ref(), b"primary");
+
+		let primary_requests = primary.received_requests().await.unwrap();
+		assert_eq!(primary_requests.len(), 1);
+		assert_eq!(primary_requests[0].body, b"hello mirror");
+
+		// The mirror is sent in the background, so it may arrive after the client response.
+		let mirrored = wait_for_requests(&mirror).await;
+		assert_eq!(mirrored.len(), 1);
+		assert_eq!(mirrored[0].method, Method::POST);
+		assert_eq!(mirrored[0].url.path(), "/mirror");
+		assert_eq!(mirrored[0].body, b"hello mirror");
+	}
+
+	#[tokio::test]
+	async fn request_mirror_errors_do_not_affect_client() {
+		let primary = proxymock::body_mock(b"primary").await;
+		let mut bind = proxymock::base_gateway(&primary);
+		bind
+			.attach_route_policy(json!({
+				"requestMirror": {
+					"backend": {
+						// Nothing listens here, so the mirror call fails.
+						"host": "127.0.0.1:1"
+					},
+					"percentage": 1.0
+				}
+			}))
+			.await;
+		let io = bind.serve_http(proxymock::BIND_KEY);
+
+		let res =
+			proxymock::send_request_body(io, Method::POST, "http://lo/mirror", b"hello mirror").await;
+		assert_eq!(res.status(), 200);
+		let body = http::read_resp_body(res).await.unwrap();
+		assert_eq!(body.as_ref(), b"primary");
+	}
+
+	/// Reads the total of the `route_timeouts` counter, waiting for request logs to be flushed.
+	async fn route_timeouts(bind: &proxymock::TestBind) -> u64 {
+		let mut registry = prometheus_client::registry::Registry::default();
+		registry.register(
+			"route_timeouts",
+			"",
+			bind.inputs().metrics.route_timeouts.clone(),
+		);
+		let mut total = 0;
+		for _ in 0..50 {
+			let mut encoded = String::new();
+			prometheus_client::encoding::text::encode(&mut encoded, &registry).unwrap();
+			total = encoded
+				.lines()
+				.filter(|l| l.starts_with("route_timeouts_total{"))
+				.filter_map(|l| l.rsplit(' ').next()?.parse::<u64>().ok())
+				.sum();
+			if total > 0 {
+				break;
+			}
+			tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+		}
+		total
+	}
+
+	#[tokio::test]
+	async fn route_timeout_fires_within_backend_timeout() {
+		let mock = wiremock::MockServer::start().await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(500)))
+			.mount(&mock)
+			.await;
+		let mut bind = proxymock::base_gateway(&mock);
+		bind
+			.attach_route_policy(json!({
+				"timeout": {
+					"requestTimeout": "100ms",
+					"backendRequestTimeout": "5s"
+				}
+			}))
+			.await;
+		let io = bind.serve_http(proxymock::BIND_KEY);
+
+		let res = proxymock::send_request(io, Method::GET, "http://lo/slow").await;
+		assert_eq!(res.status(), 504);
+		assert_eq!(route_timeouts(&bind).await, 1);
+	}
+
+	#[tokio::test]
+	async fn route_timeout_spans_retries() {
+		let mock = wiremock::MockServer::start().await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(503).set_delay(std::time::Duration::from_millis(150)))
+			.mount(&mock)
+			.await;
+		let mut bind = proxymock::base_gateway(&mock);
+		bind
+			.attach_route_policy(json!({
+				"timeout": {
+					"requestTimeout": "250ms",
+					"backendRequestTimeout": "5s"
+				},
+				"retry": {
+					"attempts": 5,
+					"codes": [503]
+				}
+			}))
+			.await;
+		let io = bind.serve_http(proxymock::BIND_KEY);
+
+		// Every attempt finishes well within the backend timeout, but together they exceed the
+		// route timeout.
+		let res = proxymock::send_request(io, Method::GET, "http://lo/retry").await;
+		assert_eq!(res.status(), 504);
+		let attempts = mock.received_requests().await.unwrap().len();
+		assert!(
+			(1..5).contains(&attempts),
+			"unexpected attempts: {attempts}"
+		);
+		assert_eq!(route_timeouts(&bind).await, 1);
+	}
+
+	#[tokio::test]
+	async fn backend_timeout_is_not_a_route_timeout() {
+		let mock = wiremock::MockServer::start().await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(500)))
+			.mount(&mock)
+			.await;
+		let mut bind = proxymock::base_gateway(&mock);
+		bind
+			.attach_route_policy(json!({
+				"timeout": {
+					"requestTimeout": "5s",
+					"backendRequestTimeout": "100ms"
+				}
+			}))
+			.await;
+		let io = bind.serve_http(proxymock::BIND_KEY);
+
+		let res = proxymock::send_request(io, Method::GET, "http://lo/slow").await;
+		assert_eq!(res.status(), 504);
+		assert_eq!(route_timeouts(&bind).await, 0);
+	}
+
 	#[tokio::test]
 	async fn llm_retry_evicts_failed_priority_group_before_next_attempt() {
 		let primary = wiremock::MockServer::start().await;
@@ -3504,6 +4009,184 @@ mod tests {
 			Some("1")
 		);
 	}
+
+	/// Binds an AI backend with a `primary` provider group followed by a `fallback` group, routed
+	/// through a policy that falls back to `fallback` with model `gpt-fallback`.
+	async fn llm_fallback_bind(
+		primary: &wiremock::MockServer,
+		fallback: &wiremock::MockServer,
+		retry: Option<serde_json::Value>,
+	) -> proxymock::TestBind {
+		let mut bind = proxymock::setup_proxy_test("{}").expect("proxy test harness");
+		let local_backend: LocalAIBackend = serde_json::from_value(json!({
+			"groups": [
+				{
+					"providers": [{
+						"name": "primary",
+						"hostOverride": primary.address().to_string(),
+						"provider": {
+							"openAI": {
+								"model": "gpt-primary"
+							}
+						}
+					}]
+				},
+				{
+					"providers": [{
+						"name": "fallback",
+						"hostOverride": fallback.address().to_string(),
+						"provider": {
+							"openAI": {
+								"model": null
+							}
+						}
+					}]
+				}
+			]
+		}))
+		.expect("local AI backend");
+		let backend = Backend::AI(
+			ResourceName::new("llm".into(), "".into()),
+			local_backend
+				.translate(&crate::resource_manager::ResourceFetcher::direct(
+					bind.pi.upstream.clone(),
+				))
+				.await
+				.expect("translated backend"),
+		);
+		bind
+			.pi
+			.stores
+			.binds
+			.write()
+			.insert_backend(backend.name(), backend.into());
+		bind = bind
+			.with_bind(proxymock::simple_bind())
+			.with_route(proxymock::basic_named_route("/llm".into()));
+		let mut policy = json!({
+			"ai": {
+				"routes": {
+					"/v1/chat/completions": "completions"
+				},
+				"fallbacks": [{
+					"provider": "fallback",
+					"model": "gpt-fallback"
+				}]
+			}
+		});
+		if let Some(retry) = retry {
+			policy["retry"] = retry;
+		}
+		bind.attach_route_policy(policy).await;
+		bind
+	}
+
+	#[tokio::test]
+	async fn llm_falls_back_to_next_provider_on_server_error() {
+		let primary = wiremock::MockServer::start().await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(503))
+			.mount(&primary)
+			.await;
+
+		let fallback = wiremock::MockServer::start().await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(200).set_body_raw(
+				include_bytes!("../../../llm/src/tests/response/completions/basic.json").to_vec(),
+				"application/json",
+			))
+			.mount(&fallback)
+			.await;
+
+		let bind = llm_fallback_bind(&primary, &fallback, None).await;
+		let io = bind.serve_http(proxymock::BIND_KEY);
+
+		let res = proxymock::send_request_body(
+			io,
+			Method::POST,
+			"http://lo/v1/chat/completions",
+			include_bytes!("../../../llm/src/tests/requests/completions/basic.json"),
+		)
+		.await;
+
+		assert_eq!(res.status(), 200);
+
+		let primary_requests = primary
+			.received_requests()
+			.await
+			.expect("primary request recording");
+		assert_eq!(primary_requests.len(), 1);
+
+		let fallback_requests = fallback
+			.received_requests()
+			.await
+			.expect("fallback request recording");
+		assert_eq!(fallback_requests.len(), 1);
+		let body: serde_json::Value =
+			serde_json::from_slice(&fallback_requests[0].body).expect("fallback request body");
+		assert_eq!(body["model"], "gpt-fallback");
+	}
+
+	#[tokio::test]
+	async fn llm_retry_after_fallback_stays_on_fallback_provider() {
+		let primary = wiremock::MockServer::start().await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(503))
+			.mount(&primary)
+			.await;
+
+		let fallback = wiremock::MockServer::start().await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(429))
+			.up_to_n_times(1)
+			.mount(&fallback)
+			.await;
+		Mock::given(wiremock::matchers::any())
+			.respond_with(ResponseTemplate::new(200).set_body_raw(
+				include_bytes!("../../../llm/src/tests/response/completions/basic.json").to_vec(),
+				"application/json",
+			))
+			.mount(&fallback)
+			.await;
+
+		let bind = llm_fallback_bind(
+			&primary,
+			&fallback,
+			Some(json!({
+				"attempts": 1,
+				"codes": [429]
+			})),
+		)
+		.await;
+		let io = bind.serve_http(proxymock::BIND_KEY);
+
+		let res = proxymock::send_request_body(
+			io,
+			Method::POST,
+			"http://lo/v1/chat/completions",
+			include_bytes!("../../../llm/src/tests/requests/completions/basic.json"),
+		)
+		.await;
+
+		assert_eq!(res.status(), 200);
+
+		let primary_requests = primary
+			.received_requests()
+			.await
+			.expect("primary request recording");
+		assert_eq!(primary_requests.len(), 1);
+
+		let fallback_requests = fallback
+			.received_requests()
+			.await
+			.expect("fallback request recording");
+		assert_eq!(fallback_requests.len(), 2);
+		for req in &fallback_requests {
+			let body: serde_json::Value =
+				serde_json::from_slice(&req.body).expect("fallback request body");
+			assert_eq!(body["model"], "gpt-fallback");
+		}
+	}
 }
 
 pub fn maybe_set_grpc_status(status: &AsyncLog<u8>, headers: &HeaderMap) {
@@ -3519,6 +4202,30 @@ pub fn parse_grpc_status(headers: &HeaderMap) -> Option<u8> {
 		.and_then(|status| status.parse().ok())
 }
 
+/// Maximum request body size that is buffered so the request can be retried or sent to mirrors.
+const MAX_BUFFERED_BYTES: usize = 64 * 1024;
+
+/// Buffers the request body so a copy can be sent to mirrors, leaving the original body intact.
+/// Returns None if the body cannot be mirrored in full, in which case mirroring is skipped rather
+/// than sending a truncated copy.
+async fn mirror_body(req: &mut Request) -> Option<bytes::Bytes> {
+	use http_body::Body as _;
+	if req.body().is_end_stream() {
+		return Some(bytes::Bytes::new());
+	}
+	match http::inspect_body_with_limit(req.body_mut(), MAX_BUFFERED_BYTES + 1).await {
+		Ok(body) if body.len() <= MAX_BUFFERED_BYTES => Some(body),
+		Ok(_) => {
+			debug!("request body too large to mirror, skipping mirrors");
+			None
+		},
+		Err(e) => {
+			debug!("failed to buffer request body for mirror: {e}");
+			None
+		},
+	}
+}
+
 async fn send_mirror(
 	inputs: Arc<ProxyInputs>,
 	upstream: PolicyClient,
@@ -3805,6 +4512,7 @@ struct ResponsePolicies {
 	response_headers: HeaderMap,
 	ext_proc: Option<ExtProcRequest>,
 	gateway_ext_proc: Option<ExtProcRequest>,
+	grpc_web: Option<http::grpcweb::Encoding>,
 	// Populated by the standard request-policy flow after conditional rate-limit policies are
 	// evaluated. The later LLM path uses these selected policies and does not re-evaluate conditions.
 	llm_request_policies: LLMRequestPolicies,
@@ -3878,6 +4586,11 @@ impl ResponsePolicies {
 			}
 		}
 
+		if let Some(encoding) = self.grpc_web {
+			http::grpcweb::translate_response(resp, encoding);
+			dtrace::snapshot!(Response, "grpc web", l, &resp);
+		}
+
 		if !self.response_headers.is_empty() {
 			merge_in_headers(Some(self.response_headers.clone()), resp.headers_mut());
 			dtrace::snapshot!(Response, "response headers", l, &resp);
@@ -4028,7 +4741,7 @@ impl PolicyClient {
 				make_backend_call(
 					self.inputs.clone(),
 					Arc::new(LLMRequestPolicies::default()),
-					&backend,
+					&BackendWithPolicies::from(backend),
 					pols.into(),
 					None,
 					MustSnapshot::new(&mut req),
@@ -4099,6 +4812,7 @@ mod route_chain_tests {
 				target,
 				inline_policies: Vec::new(),
 			}],
+			backend_hash_key: None,
 			llm_router: None,
 			inline_policies: Vec::new(),
 		}
@@ -4123,6 +4837,7 @@ mod route_chain_tests {
 				query: Vec::new(),
 			}],
 			backends: Vec::new(),
+			backend_hash_key: None,
 			llm_router: None,
 			inline_policies: Vec::new(),
 		}
@@ -4252,6 +4967,81 @@ mod route_chain_tests {
 		}
 	}
 
+	fn weighted_route(hash_key: Option<&str>) -> Route {
+		let mut route = route_without_backends("weighted", "/");
+		route.backends = [("stable", 90), ("canary", 10)]
+			.into_iter()
+			.map(|(name, weight)| RouteBackendReference {
+				weight,
+				target: RouteBackendTarget::Backend(strng::new(name)),
+				inline_policies: Vec::new(),
+			})
+			.collect();
+		route.backend_hash_key =
+			hash_key.map(|k| Arc::new(crate::cel::Expression::new_strict(k).unwrap()));
+		route
+	}
+
+	fn selected_backend_name(route: &Route, req: &Request) -> Strng {
+		match select_backend(route, req)
+			.expect("backend should be selected")
+			.target
+		{
+			RouteBackendTarget::Backend(name) => name,
+			other => panic!("expected backend target, got {other:?}"),
+		}
+	}
+
+	#[test]
+	fn select_backend_follows_weights() {
+		let route = weighted_route(None);
+		let req = request("/");
+		let canary = (0..10_000)
+			.filter(|_| selected_backend_name(&route, &req).as_str() == "canary")
+			.count();
+		// Expect ~1000; the bounds are wide enough that the test does not flake.
+		assert!(
+			(800..=1200).contains(&canary),
+			"canary selected {canary} times"
+		);
+	}
+
+	#[test]
+	fn select_backend_with_hash_key_is_sticky_and_weighted() {
+		let route = weighted_route(Some(r#"request.headers["x-user-id"]"#));
+		let mut canary = 0;
+		for user in 0..10_000u32 {
+			let mut req = request("/");
+			req
+				.headers_mut()
+				.insert("x-user-id", ::http::HeaderValue::from(user));
+			let selected = selected_backend_name(&route, &req);
+			for _ in 0..3 {
+				assert_eq!(selected, selected_backend_name(&route, &req));
+			}
+			if selected.as_str() == "canary" {
+				canary += 1;
+			}
+		}
+		assert!(
+			(800..=1200).contains(&canary),
+			"canary selected {canary} times"
+		);
+	}
+
+	#[test]
+	fn select_backend_without_hash_key_value_falls_back_to_random() {
+		let route = weighted_route(Some(r#"request.headers["x-user-id"]"#));
+		let req = request("/");
+		let canary = (0..10_000)
+			.filter(|_| selected_backend_name(&route, &req).as_str() == "canary")
+			.count();
+		assert!(
+			(800..=1200).contains(&canary),
+			"canary selected {canary} times"
+		);
+	}
+
 	fn listener_address() -> SocketAddr {
 		"127.0.0.1:80".parse().unwrap()
 	}
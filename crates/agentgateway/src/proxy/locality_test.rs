@@ -795,6 +795,7 @@ fn service_route() -> Route {
 			method: None,
 			query: vec![],
 		}],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: Default::default(),
 		backends: vec![RouteBackendReference {
@@ -3,6 +3,7 @@ mod gateway;
 pub mod httpproxy;
 pub mod proxy_protocol;
 pub mod request_builder;
+pub mod tap;
 pub mod tcpproxy;
 
 use std::sync::Arc;
@@ -78,6 +79,7 @@ impl ProxyResponse {
 			ProxyError::RateLimitFailed | ProxyError::RateLimitExceeded { .. } => {
 				ProxyResponseReason::RateLimit
 			},
+			ProxyError::CircuitBreakerOpen { .. } => ProxyResponseReason::CircuitBreaker,
 		}
 	}
 	pub fn downcast(self) -> ProxyError {
@@ -120,6 +122,8 @@ pub enum ProxyResponseReason {
 	ExtProc,
 	/// Rate limit exceeded
 	RateLimit,
+	/// The backend circuit breaker rejected the request
+	CircuitBreaker,
 	/// MCP
 	MCP,
 	/// The upstream request failed
@@ -206,6 +210,8 @@ pub enum ProxyError {
 	},
 	#[error("rate limit failed")]
 	RateLimitFailed,
+	#[error("circuit breaker open")]
+	CircuitBreakerOpen { retry_after: Duration },
 	#[error("invalid request")]
 	InvalidRequest,
 	#[error("method not allowed")]
@@ -289,6 +295,7 @@ impl ProxyError {
 			// Rate limit service communication failure is a server error (500), not a rate limit (429).
 			// This matches Envoy's behavior (status_on_error defaults to 500).
 			ProxyError::RateLimitFailed => StatusCode::INTERNAL_SERVER_ERROR,
+			ProxyError::CircuitBreakerOpen { .. } => StatusCode::SERVICE_UNAVAILABLE,
 
 			// Shouldn't happen on this path
 			ProxyError::UpstreamTCPCallFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -344,6 +351,12 @@ impl ProxyError {
 			}
 		}
 
+		if let ProxyError::CircuitBreakerOpen { retry_after } = self
+			&& let Ok(hv) = HeaderValue::try_from(retry_after.as_secs().max(1).to_string())
+		{
+			rb = rb.header(hyper::header::RETRY_AFTER, hv)
+		}
+
 		// Add WWW-Authenticate header for basic auth failures
 		if let ProxyError::BasicAuthenticationFailure(err) = &self {
 			let realm = match err {
@@ -506,6 +506,51 @@ mod tests {
 		assert!(err.to_string().contains("only allows v1"));
 	}
 
+	#[tokio::test]
+	async fn test_parse_proxy_protocol_rejects_malformed_v1() {
+		for header in [
+			&b"PROXY TCP4 not-an-ip 10.0.0.1 12345 8080\r\n"[..],
+			&b"PROXY TCP4 192.168.1.1 10.0.0.1 99999 8080\r\n"[..],
+			&b"PROXY TCP9 192.168.1.1 10.0.0.1 12345 8080\r\n"[..],
+		] {
+			let mut cursor = std::io::Cursor::new(header.to_vec());
+			let err = parse_proxy_protocol(&mut cursor, frontend::ProxyVersion::All)
+				.await
+				.unwrap_err();
+			assert!(
+				err.to_string().contains("invalid PROXY protocol v1 header"),
+				"{err}"
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_parse_proxy_protocol_rejects_malformed_v2() {
+		let mut header = build_v2_proxy_header("192.168.1.1:12345", "10.0.0.1:8080", None);
+		// Unsupported protocol version in the high nibble of the version/command byte.
+		header[12] = 0x31;
+		let mut cursor = std::io::Cursor::new(header);
+		let err = parse_proxy_protocol(&mut cursor, frontend::ProxyVersion::All)
+			.await
+			.unwrap_err();
+		assert!(
+			err.to_string().contains("invalid PROXY protocol v2 header"),
+			"{err}"
+		);
+	}
+
+	#[tokio::test]
+	async fn test_parse_proxy_protocol_rejects_truncated_header() {
+		let header = build_v2_proxy_header("192.168.1.1:12345", "10.0.0.1:8080", None);
+		for data in [&b"PROXY TCP4 192.168.1.1"[..], &header[..header.len() - 1]] {
+			let mut cursor = std::io::Cursor::new(data.to_vec());
+			let err = parse_proxy_protocol(&mut cursor, frontend::ProxyVersion::All)
+				.await
+				.unwrap_err();
+			assert!(err.to_string().contains("unexpected EOF"), "{err}");
+		}
+	}
+
 	#[tokio::test]
 	async fn test_parse_proxy_protocol_accepts_exact_max_len_v2_header_with_overread() {
 		// Regression test for an unshipped issue in the original parser
@@ -0,0 +1,279 @@
+//! Request/response taps, for debugging.
+//!
+//! A tap is registered through the admin `/debug/tap` endpoint. Requests matching its CEL
+//! expression are recorded as they stream through the proxy, with bodies cut off at a size limit
+//! and credentials redacted, and each request/response pair is sent to the admin client once the
+//! response has been sent. Like debug traces, nothing is recorded while no tap is registered.
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use ::http::header::{AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION, SET_COOKIE};
+use ::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri, Version};
+use serde::Serialize;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use crate::cel::{Executor, Expression};
+use crate::http::{Body, DropBody, RecordedBody, RecordedBodyHandle, Request, Response};
+
+#[cfg(test)]
+#[path = "tap_tests.rs"]
+mod tap_tests;
+
+/// Headers that are always redacted from captures.
+const REDACTED_HEADERS: [HeaderName; 5] = [
+	AUTHORIZATION,
+	PROXY_AUTHORIZATION,
+	COOKIE,
+	SET_COOKIE,
+	HeaderName::from_static("x-api-key"),
+];
+const REDACTED: HeaderValue = HeaderValue::from_static("<redacted>");
+
+#[derive(Debug, Clone)]
+pub struct TapConfig {
+	/// Only requests matching this expression are captured. All requests match when unset.
+	pub expression: Option<Expression>,
+	/// Number of request/response pairs to capture before the tap closes.
+	pub count: usize,
+	/// Maximum number of body bytes captured from each request and response.
+	pub max_body_bytes: usize,
+	/// Headers to redact, in addition to credentials and cookies.
+	pub redact_headers: Vec<HeaderName>,
+}
+
+impl Default for TapConfig {
+	fn default() -> Self {
+		Self {
+			expression: None,
+			count: 10,
+			max_body_bytes: 4096,
+			redact_headers: Vec::new(),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capture {
+	pub request: CapturedRequest,
+	pub response: CapturedResponse,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedRequest {
+	#[serde(with = "http_serde::method")]
+	pub method: Method,
+	#[serde(with = "http_serde::uri")]
+	pub uri: Uri,
+	#[serde(with = "http_serde::version")]
+	pub version: Version,
+	#[serde(with = "http_serde::header_map")]
+	pub headers: HeaderMap,
+	pub body: CapturedBody,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedResponse {
+	#[serde(with = "http_serde::status_code")]
+	pub status: StatusCode,
+	#[serde(with = "http_serde::header_map")]
+	pub headers: HeaderMap,
+	pub body: CapturedBody,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedBody {
+	/// The captured bytes, decoded as UTF-8 with invalid sequences replaced.
+	pub content: String,
+	/// Whether the body was longer than the capture limit.
+	pub truncated: bool,
+}
+
+impl CapturedBody {
+	fn new(handle: &RecordedBodyHandle, limit: usize) -> Self {
+		// Bodies are recorded one byte past the limit, to tell whether anything was cut off.
+		let bytes = handle.bytes();
+		let truncated = bytes.len() > limit;
+		let content = String::from_utf8_lossy(&bytes[..bytes.len().min(limit)]).into_owned();
+		CapturedBody { content, truncated }
+	}
+}
+
+struct Tap {
+	id: u64,
+	config: Arc<TapConfig>,
+	remaining: usize,
+	sender: Sender<Capture>,
+}
+
+static HAS_TAPS: AtomicBool = AtomicBool::new(false);
+static NEXT_TAP_ID: AtomicU64 = AtomicU64::new(0);
+static TAPS: Mutex<Vec<Tap>> = Mutex::new(Vec::new());
+
+pub struct TapReceiver {
+	id: u64,
+	receiver: Receiver<Capture>,
+}
+
+impl TapReceiver {
+	pub async fn recv(&mut self) -> Option<Capture> {
+		self.receiver.recv().await
+	}
+}
+
+impl tokio_stream::Stream for TapReceiver {
+	type Item = Capture;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		Pin::new(&mut this.receiver).poll_recv(cx)
+	}
+}
+
+impl Drop for TapReceiver {
+	fn drop(&mut self) {
+		let Ok(mut taps) = TAPS.lock() else {
+			return;
+		};
+		taps.retain(|tap| tap.id != self.id);
+		HAS_TAPS.store(!taps.is_empty(), Ordering::Release);
+	}
+}
+
+/// Registers a tap. The receiver yields captures until `count` requests have been captured, and
+/// the tap is removed once the receiver is dropped.
+pub fn register(config: TapConfig) -> TapReceiver {
+	let (tx, rx) = tokio::sync::mpsc::channel(32);
+	let id = NEXT_TAP_ID.fetch_add(1, Ordering::Relaxed);
+	let Ok(mut taps) = TAPS.lock() else {
+		return TapReceiver { id, receiver: rx };
+	};
+	taps.push(Tap {
+		id,
+		remaining: config.count,
+		config: Arc::new(config),
+		sender: tx,
+	});
+	HAS_TAPS.store(true, Ordering::Release);
+	TapReceiver { id, receiver: rx }
+}
+
+fn take_match(req: &Request) -> Option<(Arc<TapConfig>, Sender<Capture>)> {
+	if !HAS_TAPS.load(Ordering::Acquire) {
+		return None;
+	}
+	let Ok(mut taps) = TAPS.lock() else {
+		return None;
+	};
+	taps.retain(|tap| !tap.sender.is_closed());
+	let executor = Executor::new_request(req);
+	let matched = taps
+		.iter_mut()
+		.find(|tap| {
+			tap.remaining > 0
+				&& tap
+					.config
+					.expression
+					.as_ref()
+					.is_none_or(|expr| executor.eval_bool(expr))
+		})
+		.map(|tap| {
+			tap.remaining -= 1;
+			(tap.config.clone(), tap.sender.clone())
+		});
+	// A tap that has used up its count is dropped here; its receiver closes once the captures still
+	// in flight have been sent.
+	taps.retain(|tap| tap.remaining > 0);
+	HAS_TAPS.store(!taps.is_empty(), Ordering::Release);
+	matched
+}
+
+fn redact(headers: &HeaderMap, config: &TapConfig) -> HeaderMap {
+	let mut headers = headers.clone();
+	for name in REDACTED_HEADERS.iter().chain(&config.redact_headers) {
+		if headers.contains_key(name) {
+			headers.insert(name.clone(), REDACTED);
+		}
+	}
+	headers
+}
+
+/// A request matched by a tap, being recorded until its response completes.
+pub struct Recorder {
+	config: Arc<TapConfig>,
+	sender: Sender<Capture>,
+	method: Method,
+	uri: Uri,
+	version: Version,
+	headers: HeaderMap,
+	body: RecordedBodyHandle,
+}
+
+/// Starts recording `req` if it matches a registered tap. The request body is recorded as it is
+/// forwarded, without being buffered.
+pub fn start(req: &mut Request) -> Option<Recorder> {
+	let (config, sender) = take_match(req)?;
+	let body = std::mem::replace(req.body_mut(), Body::empty());
+	let (body, handle) = RecordedBody::new_with_limit(body, config.max_body_bytes + 1);
+	*req.body_mut() = Body::new(body);
+	Some(Recorder {
+		method: req.method().clone(),
+		uri: req.uri().clone(),
+		version: req.version(),
+		headers: redact(req.headers(), &config),
+		body: handle,
+		config,
+		sender,
+	})
+}
+
+impl Recorder {
+	/// Records the response. The capture is sent once the response body has been sent to the
+	/// client, or dropped.
+	pub fn finish(self, resp: Response) -> Response {
+		let (parts, body) = resp.into_parts();
+		let (body, handle) = RecordedBody::new_with_limit(body, self.config.max_body_bytes + 1);
+		let pending = PendingCapture {
+			status: parts.status,
+			headers: redact(&parts.headers, &self.config),
+			body: handle,
+			recorder: self,
+		};
+		Response::from_parts(parts, DropBody::new(body, pending))
+	}
+}
+
+struct PendingCapture {
+	recorder: Recorder,
+	status: StatusCode,
+	headers: HeaderMap,
+	body: RecordedBodyHandle,
+}
+
+impl Drop for PendingCapture {
+	fn drop(&mut self) {
+		let limit = self.recorder.config.max_body_bytes;
+		let capture = Capture {
+			request: CapturedRequest {
+				method: self.recorder.method.clone(),
+				uri: self.recorder.uri.clone(),
+				version: self.recorder.version,
+				headers: std::mem::take(&mut self.recorder.headers),
+				body: CapturedBody::new(&self.recorder.body, limit),
+			},
+			response: CapturedResponse {
+				status: self.status,
+				headers: std::mem::take(&mut self.headers),
+				body: CapturedBody::new(&self.body, limit),
+			},
+		};
+		// A slow admin client loses captures rather than holding up traffic.
+		let _ = self.recorder.sender.try_send(capture);
+	}
+}
@@ -0,0 +1,74 @@
+use super::*;
+use crate::http::tests_common::{read_body_for_test, request};
+
+fn tap(path: &str) -> TapReceiver {
+	register(TapConfig {
+		expression: Some(Expression::new_strict(&format!(r#"request.path == "{path}""#)).unwrap()),
+		count: 1,
+		max_body_bytes: 5,
+		redact_headers: vec![HeaderName::from_static("x-secret")],
+	})
+}
+
+fn post(path: &str, body: &'static str) -> Request {
+	let mut req = request(
+		&format!("http://example.com{path}"),
+		Method::POST,
+		&[
+			("authorization", "Bearer token"),
+			("x-secret", "hunter2"),
+			("x-visible", "yes"),
+		],
+	);
+	*req.body_mut() = Body::from(body);
+	req
+}
+
+#[tokio::test]
+async fn matching_request_is_captured() {
+	let mut rx = tap("/tap/captured");
+	let mut req = post("/tap/captured", "hello world");
+	let recorder = start(&mut req).expect("request matches the tap");
+	// The request is forwarded unchanged.
+	assert_eq!(read_body_for_test(req.into_body()).await, "hello world");
+
+	let resp = ::http::Response::builder()
+		.status(StatusCode::CREATED)
+		.header(SET_COOKIE, "session=abc")
+		.body(Body::from("hi"))
+		.unwrap();
+	let resp = recorder.finish(resp);
+	assert_eq!(read_body_for_test(resp).await, "hi");
+
+	let capture = rx.recv().await.expect("capture is sent");
+	let req = &capture.request;
+	assert_eq!(req.method, Method::POST);
+	assert_eq!(req.uri.path(), "/tap/captured");
+	assert_eq!(req.headers.get(AUTHORIZATION).unwrap(), REDACTED);
+	assert_eq!(req.headers.get("x-secret").unwrap(), REDACTED);
+	assert_eq!(req.headers.get("x-visible").unwrap(), "yes");
+	assert_eq!(req.body.content, "hello");
+	assert!(req.body.truncated);
+
+	let resp = &capture.response;
+	assert_eq!(resp.status, StatusCode::CREATED);
+	assert_eq!(resp.headers.get(SET_COOKIE).unwrap(), REDACTED);
+	assert_eq!(resp.body.content, "hi");
+	assert!(!resp.body.truncated);
+
+	// The tap closes once its count is used up.
+	assert!(start(&mut post("/tap/captured", "again")).is_none());
+	assert!(rx.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn non_matching_request_is_not_captured() {
+	let rx = tap("/tap/wanted");
+	let mut req = post("/tap/other", "hello");
+	assert!(start(&mut req).is_none());
+	assert_eq!(read_body_for_test(req.into_body()).await, "hello");
+
+	// Dropping the receiver removes the tap.
+	drop(rx);
+	assert!(start(&mut post("/tap/wanted", "hello")).is_none());
+}
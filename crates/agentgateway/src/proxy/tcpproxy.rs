@@ -196,6 +196,7 @@ impl TCPProxy {
 			hbone_source,
 			backend_call.backend_policies.backend_tls.clone(),
 			backend_call.backend_policies.tunnel.as_ref(),
+			Default::default(),
 			// TODO: for TCP we should actually probably do something here: telling it to not use ALPN at all?
 			None,
 		)
@@ -242,6 +243,7 @@ impl TCPProxy {
 					backend_auth: Some(http::auth::BackendAuth::Aws(
 						http::auth::AwsAuth::Implicit {
 							service_name: None,
+							region: None,
 							assume_role: None,
 							source_credentials_cache: Default::default(),
 							assume_role_cache: Default::default(),
@@ -903,6 +905,111 @@ mod tests {
 		assert_eq!(route.unwrap().key.as_str(), "_waypoint-default-tcp");
 	}
 
+	/// Builds the first flight of a TLS handshake, a ClientHello for `sni`.
+	fn client_hello(sni: &str) -> Vec<u8> {
+		let config = crate::http::backendtls::INSECURE_TRUST.base_config().config;
+		let server_name = rustls::pki_types::ServerName::try_from(sni.to_string()).unwrap();
+		let mut conn = rustls::ClientConnection::new(config, server_name).unwrap();
+		let mut hello = Vec::new();
+		conn.write_tls(&mut hello).unwrap();
+		hello
+	}
+
+	/// Sniffs the SNI from a ClientHello sent on a fresh downstream connection.
+	async fn sniff(hello: &[u8]) -> crate::transport::stream::Socket {
+		use tokio::io::AsyncWriteExt;
+
+		use crate::transport::stream::{Socket, TCPConnectionInfo};
+
+		let (mut client, server) = tokio::io::duplex(8192);
+		let socket = Socket::from_memory(
+			server,
+			TCPConnectionInfo {
+				peer_addr: "127.0.0.1:12345".parse().unwrap(),
+				local_addr: "127.0.0.1:443".parse().unwrap(),
+				start: std::time::Instant::now(),
+				raw_peer_addr: None,
+			},
+		);
+		client.write_all(hello).await.unwrap();
+		// Keep the client open so the sniffed socket can still be read from.
+		tokio::spawn(async move {
+			let _ = tokio::io::AsyncReadExt::read_to_end(&mut client, &mut Vec::new()).await;
+		});
+		super::TCPProxy::sniff_tls_sni(socket, &Default::default())
+			.await
+			.unwrap()
+	}
+
+	fn sni(socket: &crate::transport::stream::Socket) -> Option<String> {
+		socket
+			.ext::<crate::transport::stream::TLSConnectionInfo>()
+			.and_then(|tls| tls.server_name.clone())
+	}
+
+	#[tokio::test]
+	async fn test_sniff_tls_sni_extracts_server_name() {
+		use tokio::io::AsyncReadExt;
+
+		let hello = client_hello("db.example.com");
+		let mut socket = sniff(&hello).await;
+		assert_eq!(sni(&socket).as_deref(), Some("db.example.com"));
+		// TLS is not terminated: the ClientHello is still passed through to the backend.
+		let mut forwarded = vec![0; hello.len()];
+		socket.read_exact(&mut forwarded).await.unwrap();
+		assert_eq!(forwarded, hello);
+	}
+
+	#[tokio::test]
+	async fn test_select_best_route_by_sni() {
+		let stores = stores_with_services(vec![]);
+		let listener = Arc::new(crate::types::agent::Listener {
+			key: strng::literal!("tls-passthrough"),
+			name: crate::types::agent::ListenerName {
+				gateway_name: strng::EMPTY,
+				gateway_namespace: strng::EMPTY,
+				listener_name: strng::literal!("tls-passthrough"),
+				listener_set: None,
+			},
+			hostname: Default::default(),
+			protocol: ListenerProtocol::TLS(None),
+		});
+		let route = |key: &'static str, hostnames: &[&'static str]| crate::types::agent::TCPRoute {
+			key: strng::new(key),
+			service_key: None,
+			service_port: 0,
+			name: Default::default(),
+			hostnames: hostnames.iter().map(|h| strng::new(h)).collect(),
+			backends: vec![],
+		};
+		{
+			let mut binds = stores.binds.write();
+			binds.insert_tcp_route(route("exact", &["db.example.com"]), listener.key.clone());
+			binds.insert_tcp_route(route("wildcard", &["*.example.com"]), listener.key.clone());
+			binds.insert_tcp_route(route("default", &[]), listener.key.clone());
+		}
+		let network = strng::literal!("network");
+		let dst = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 443);
+
+		for (server_name, want) in [
+			("db.example.com", "exact"),
+			("cache.example.com", "wildcard"),
+			("db.other.com", "default"),
+		] {
+			let socket = sniff(&client_hello(server_name)).await;
+			let route = super::select_best_route(
+				sni(&socket).as_deref(),
+				listener.clone(),
+				&stores,
+				&network,
+				dst,
+				None,
+			)
+			.expect("route should match");
+			assert_eq!(route.key.as_str(), want, "{server_name}");
+		}
+	}
+
 	fn make_proxy_inputs() -> Arc<crate::ProxyInputs> {
 		use agent_core::metrics;
 		use hickory_resolver::config::{ResolverConfig, ResolverOpts};
@@ -918,6 +1025,9 @@ mod tests {
 			&client::Config {
 				resolver_cfg: ResolverConfig::default(),
 				resolver_opts: ResolverOpts::default(),
+				negative_ttl: client::DEFAULT_DNS_NEGATIVE_TTL,
+				doh: None,
+				client_subnet: None,
 			},
 			None,
 			BackendConfig::default(),
@@ -17,6 +17,7 @@ use tracing::{trace, warn};
 
 use crate::client::Client;
 use crate::http::Body;
+use crate::http::backendtls::{BackendTLS, BackendTLSCache, ResolvedBackendTLS};
 
 const JWKS_TTL: Duration = Duration::from_mins(15);
 const OPENAPI_TTL: Duration = Duration::from_hours(24);
@@ -70,7 +71,13 @@ pub struct ResourceFetcher {
 	mode: ResourceFetcherMode,
 	// Populated only while a ResourceFetchScope is active for managed fetchers.
 	// It records the resources the successful config should retain.
-	tracking: Arc<std::sync::Mutex<Option<HashSet<ResourceRef>>>>,
+	tracking: Arc<std::sync::Mutex<Option<Tracked>>>,
+}
+
+#[derive(Debug, Default)]
+struct Tracked {
+	resources: HashSet<ResourceRef>,
+	backend_tls: HashSet<ResolvedBackendTLS>,
 }
 
 pub struct ResourceFetchScope<'a> {
@@ -145,6 +152,25 @@ impl ResourceFetcher {
 		}
 	}
 
+	/// Builds the TLS config for a backend. Managed fetchers share the config built by an earlier
+	/// reload from identical inputs, so connections pooled for it remain usable.
+	pub fn backend_tls(&self, resolved: ResolvedBackendTLS) -> anyhow::Result<BackendTLS> {
+		match &self.mode {
+			ResourceFetcherMode::Managed(manager) => {
+				if let Some(tracking) = self
+					.tracking
+					.lock()
+					.expect("resource fetcher tracking mutex poisoned")
+					.as_mut()
+				{
+					tracking.backend_tls.insert(resolved.clone());
+				}
+				manager.inner.backend_tls.get_or_build(resolved)
+			},
+			_ => resolved.try_into(),
+		}
+	}
+
 	/// Records managed resource lookups during a full config computation.
 	/// The returned guard commits fetched resources on success, or restores the
 	/// last committed set on failure so failed reloads do not leave stale state.
@@ -160,14 +186,14 @@ impl ResourceFetcher {
 			.tracking
 			.lock()
 			.expect("resource fetcher tracking mutex poisoned");
-		*tracking = Some(HashSet::new());
+		*tracking = Some(Tracked::default());
 		ResourceFetchScope {
 			fetcher: self,
 			active: true,
 		}
 	}
 
-	fn take_tracked_resources(&self) -> Option<HashSet<ResourceRef>> {
+	fn take_tracked_resources(&self) -> Option<Tracked> {
 		let mut tracking = self
 			.tracking
 			.lock()
@@ -182,7 +208,7 @@ impl ResourceFetcher {
 			.expect("resource fetcher tracking mutex poisoned")
 			.as_mut()
 		{
-			tracking.insert(resource);
+			tracking.resources.insert(resource);
 		}
 	}
 }
@@ -196,7 +222,8 @@ impl ResourceFetchScope<'_> {
 		let fetched = self.fetcher.take_tracked_resources();
 		if let (Some(fetched), ResourceFetcherMode::Managed(manager)) = (fetched, &self.fetcher.mode) {
 			if success {
-				manager.retain_resources(fetched);
+				manager.retain_resources(fetched.resources);
+				manager.retain_backend_tls(fetched.backend_tls);
 			} else {
 				manager.retain_active_resources();
 			}
@@ -221,6 +248,9 @@ struct Inner {
 	// Resources referenced by the last successfully normalized local config.
 	// Background refreshes check this before publishing changes.
 	active_resources: Mutex<HashSet<ResourceRef>>,
+	// Backend TLS configs built for local config, pruned like the resource cache.
+	backend_tls: BackendTLSCache,
+	active_backend_tls: Mutex<HashSet<ResolvedBackendTLS>>,
 	watched_files: FileWatchRegistry,
 	scheduler_tx: mpsc::UnboundedSender<ScheduledRefresh>,
 	change_tx: watch::Sender<ResourceChange>,
@@ -339,6 +369,8 @@ impl ResourceManager {
 				client,
 				entries: Default::default(),
 				active_resources: Default::default(),
+				backend_tls: Default::default(),
+				active_backend_tls: Default::default(),
 				watched_files: Default::default(),
 				scheduler_tx,
 				change_tx,
@@ -396,6 +428,15 @@ impl ResourceManager {
 		self.retain_cached_and_watched_resources(&retained);
 	}
 
+	fn retain_backend_tls(&self, retained: HashSet<ResolvedBackendTLS>) {
+		self.inner.backend_tls.retain(&retained);
+		*self
+			.inner
+			.active_backend_tls
+			.lock()
+			.expect("backend TLS active set mutex poisoned") = retained;
+	}
+
 	fn retain_active_resources(&self) {
 		// Failed reloads may have fetched temporary resources before erroring.
 		// Prune back to the last successful dependency set.
@@ -406,6 +447,13 @@ impl ResourceManager {
 			.expect("resource active set mutex poisoned")
 			.clone();
 		self.retain_cached_and_watched_resources(&retained);
+		self.inner.backend_tls.retain(
+			&self
+				.inner
+				.active_backend_tls
+				.lock()
+				.expect("backend TLS active set mutex poisoned"),
+		);
 	}
 
 	fn retain_cached_and_watched_resources(&self, retained: &HashSet<ResourceRef>) {
@@ -783,6 +831,9 @@ mod tests {
 			&crate::client::Config {
 				resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
 				resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+				negative_ttl: crate::client::DEFAULT_DNS_NEGATIVE_TTL,
+				doh: None,
+				client_subnet: None,
 			},
 			None,
 			crate::BackendConfig::default(),
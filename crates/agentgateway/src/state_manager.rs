@@ -50,6 +50,7 @@ impl StateManager {
 				addr.clone(),
 				xds.auth.clone(),
 				xds.ca_cert.clone(),
+				xds.client_identity.clone(),
 				vec![],
 			)
 			.await?;
@@ -59,6 +60,7 @@ impl StateManager {
 					xds.gateway.clone(),
 					xds.namespace.clone(),
 				)
+				.with_backoff(xds.backoff)
 				.with_watched_handler::<XdsAddress>(ADDRESS_TYPE, stores.clone().discovery.clone())
 				.with_watched_handler::<ADPResource>(ADP_TYPE, stores.clone().binds.clone())
 				// .with_watched_handler::<XdsAuthorization>(AUTHORIZATION_TYPE, state)
@@ -380,6 +382,9 @@ mod tests {
 			&client::Config {
 				resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
 				resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+				negative_ttl: client::DEFAULT_DNS_NEGATIVE_TTL,
+				doh: None,
+				client_subnet: None,
 			},
 			None,
 			crate::BackendConfig::default(),
@@ -16,7 +16,9 @@ use crate::http::auth::BackendAuth;
 use crate::http::authorization::{HTTPAuthorizationSet, NetworkAuthorizationSet};
 use crate::http::backendtls::BackendTLS;
 use crate::http::ext_proc::InferenceRouting;
-use crate::http::{ext_authz, ext_proc, filters, health, oidc, remoteratelimit, retry, timeout};
+use crate::http::{
+	ext_authz, ext_proc, filters, health, oidc, redisratelimit, remoteratelimit, retry, timeout,
+};
 use crate::llm::policy::ResponseGuard;
 use crate::mcp::McpAuthorizationSet;
 use crate::proxy::dtrace;
@@ -194,6 +196,7 @@ impl FrontendPolices {
 			remove: _,
 			otlp,
 			database,
+			format: _,
 			access_log_policy: _,
 		}) = &self.access_log
 		{
@@ -257,6 +260,7 @@ pub struct BackendPolicies {
 	pub session_persistence: Option<http::sessionpersistence::Policy>,
 
 	pub health: Option<health::Policy>,
+	pub circuit_breaker: Option<http::circuitbreaker::CircuitBreaker>,
 
 	/// Internal-only override for destination endpoint selection.
 	/// Used for stateful MCP routing (session affinity).
@@ -308,6 +312,7 @@ impl BackendPolicies {
 			transformation: other.transformation.or(self.transformation),
 			session_persistence: other.session_persistence.or(self.session_persistence),
 			health: other.health.or(self.health),
+			circuit_breaker: other.circuit_breaker.or(self.circuit_breaker),
 			override_dest: other.override_dest.or(self.override_dest),
 		}
 	}
@@ -335,6 +340,12 @@ impl BackendPolicies {
 				ctx.register_expression(expr);
 			}
 		}
+		if let Some(llm::AIProvider::Bedrock(bedrock)) = self.llm_provider.as_ref().map(|p| &p.provider)
+		{
+			for expr in bedrock.expressions() {
+				ctx.register_expression(expr);
+			}
+		}
 		if let Some(health) = self.health.as_ref() {
 			health.register_expressions(ctx);
 		}
@@ -347,6 +358,7 @@ impl BackendPolicies {
 pub struct RoutePolicies {
 	pub local_rate_limit: RequestPolicy<Vec<http::localratelimit::RateLimit>>,
 	pub remote_rate_limit: RequestPolicy<remoteratelimit::RemoteRateLimit>,
+	pub redis_rate_limit: RequestPolicy<redisratelimit::RedisRateLimit>,
 	pub authorization: RequestPolicy<HTTPAuthorizationSet>,
 	pub jwt: RequestPolicy<JwtAuthentication>,
 	pub oidc: RequestPolicy<oidc::OidcPolicy>,
@@ -356,6 +368,9 @@ pub struct RoutePolicies {
 	pub ext_proc: RequestPolicy<ext_proc::ExtProc>,
 	pub transformation: RequestPolicy<http::transformation_cel::Transformation>,
 	pub csrf: RequestPolicy<http::csrf::Csrf>,
+	pub request_id: RequestPolicy<http::requestid::RequestId>,
+	pub header_limit: RequestPolicy<http::headerlimit::HeaderLimit>,
+	pub grpc_web: RequestPolicy<http::grpcweb::GrpcWeb>,
 	pub direct_response: RequestPolicy<filters::DirectResponse>,
 
 	pub llm: RequestPolicy<llm::Policy>,
@@ -415,6 +430,7 @@ impl RoutePolicies {
 		[
 			&self.local_rate_limit as &dyn PolicyExpressions,
 			&self.remote_rate_limit as &dyn PolicyExpressions,
+			&self.redis_rate_limit as &dyn PolicyExpressions,
 			&self.authorization as &dyn PolicyExpressions,
 			&self.jwt as &dyn PolicyExpressions,
 			&self.oidc as &dyn PolicyExpressions,
@@ -424,6 +440,9 @@ impl RoutePolicies {
 			&self.ext_proc as &dyn PolicyExpressions,
 			&self.transformation as &dyn PolicyExpressions,
 			&self.csrf as &dyn PolicyExpressions,
+			&self.request_id as &dyn PolicyExpressions,
+			&self.header_limit as &dyn PolicyExpressions,
+			&self.grpc_web as &dyn PolicyExpressions,
 			&self.direct_response as &dyn PolicyExpressions,
 			&self.llm as &dyn PolicyExpressions,
 			&self.request_header_modifier as &dyn PolicyExpressions,
@@ -446,6 +465,7 @@ impl RoutePolicies {
 pub struct LLMRequestPolicies {
 	pub local_rate_limit: Option<Arc<Vec<http::localratelimit::RateLimit>>>,
 	pub remote_rate_limit: Option<Arc<http::remoteratelimit::RemoteRateLimit>>,
+	pub redis_rate_limit: Option<Arc<http::redisratelimit::RedisRateLimit>>,
 	pub llm: Option<Arc<llm::Policy>>,
 }
 
@@ -514,6 +534,12 @@ impl LLMRequestPolicies {
 			} else {
 				preferred.routes.clone()
 			},
+			fallbacks: if preferred.fallbacks.is_empty() {
+				fallback.fallbacks.clone()
+			} else {
+				preferred.fallbacks.clone()
+			},
+			force_stream_usage: preferred.force_stream_usage.or(fallback.force_stream_usage),
 		})
 	}
 }
@@ -522,6 +548,7 @@ impl LLMRequestPolicies {
 pub struct LLMResponsePolicies {
 	pub local_rate_limit: Vec<http::localratelimit::RateLimit>,
 	pub remote_rate_limit: Option<http::remoteratelimit::LLMResponseAmend>,
+	pub redis_rate_limit: Option<http::redisratelimit::RedisRateLimitAmend>,
 	pub request_traceparent: Option<HeaderValue>,
 	pub prompt_guard: Vec<ResponseGuard>,
 	pub streaming_prompt_guard_enabled: bool,
@@ -859,6 +886,11 @@ impl Store {
 						.remote_rate_limit
 						.merge_with_inheritance(p, lock_inheritance);
 				},
+				TrafficPolicy::RedisRateLimit(p) => {
+					pol
+						.redis_rate_limit
+						.merge_with_inheritance(p, lock_inheritance);
+				},
 				TrafficPolicy::JwtAuth(p) => {
 					pol.jwt.merge_with_inheritance(p, lock_inheritance);
 				},
@@ -890,6 +922,15 @@ impl Store {
 				TrafficPolicy::Csrf(p) => {
 					pol.csrf.merge_with_inheritance(p, lock_inheritance);
 				},
+				TrafficPolicy::RequestId(p) => {
+					pol.request_id.merge_with_inheritance(p, lock_inheritance);
+				},
+				TrafficPolicy::HeaderLimit(p) => {
+					pol.header_limit.merge_with_inheritance(p, lock_inheritance);
+				},
+				TrafficPolicy::GrpcWeb(p) => {
+					pol.grpc_web.merge_with_inheritance(p, lock_inheritance);
+				},
 
 				TrafficPolicy::Timeout(p) => {
 					pol
@@ -1194,6 +1235,9 @@ impl Store {
 				BackendTrafficPolicy::Health(p) => {
 					pol.health.get_or_insert_with(|| p.clone());
 				},
+				BackendTrafficPolicy::CircuitBreaker(p) => {
+					pol.circuit_breaker.get_or_insert_with(|| p.clone());
+				},
 				BackendTrafficPolicy::RequestMirror(p) => {
 					if pol.request_mirror.is_empty() {
 						pol.request_mirror = p.clone();
@@ -2094,6 +2138,7 @@ mod tests {
 			remove: Arc::new(FzHashSet::new(vec![remove_item.into()])),
 			otlp: None,
 			database: None,
+			format: None,
 			access_log_policy: None,
 		})
 	}
@@ -2144,6 +2189,7 @@ mod tests {
 			},
 			hostnames: vec![],
 			matches: vec![],
+			backend_hash_key: None,
 			llm_router: None,
 			inline_policies: vec![],
 			backends: vec![],
@@ -2949,10 +2995,12 @@ mod tests {
 				streaming: Default::default(),
 				request: vec![RequestGuard {
 					rejection: Default::default(),
+					mode: Default::default(),
 					kind: RequestGuardKind::Regex(RegexRules {
 						action: Default::default(),
 						rules: vec![RegexRule::Regex {
 							pattern: regex::Regex::new("secret").unwrap(),
+							mask: None,
 						}],
 					}),
 				}],
@@ -33,6 +33,7 @@ use tracing::{Level, debug, trace};
 use value_bag::visit::Visit;
 
 use crate::cel::{ContextBuilder, Expression, LLMContext};
+use crate::http::circuitbreaker::CircuitBreakerPermit;
 use crate::http::{Request, health};
 use crate::llm::InputFormat;
 use crate::llm::cost::{CostLookupStatus, ModelCatalog};
@@ -40,14 +41,14 @@ use crate::mcp::{MCPInfo, MCPOperation};
 use crate::proxy::{ProxyResponseReason, dtrace};
 use crate::telemetry::metrics::{
 	CostCatalogLookupLabels, GenAILabels, GenAILabelsTokenUsage, HTTPLabels, MCPCall, Metrics,
-	RouteIdentifier,
+	RouteIdentifier, TraceExemplar,
 };
 use crate::telemetry::trc::TraceParent;
 use crate::telemetry::{log_store, trc};
 use crate::transport::stream::{TCPConnectionInfo, TLSConnectionInfo};
 use crate::types::agent::{BackendInfo, BindKey, ListenerName, RouteName, Target};
 use crate::types::loadbalancer::ActiveHandle;
-use crate::{cel, llm, mcp};
+use crate::{apply, cel, llm, mcp, schema};
 
 fn u64_to_i64(value: Option<u64>) -> Option<i64> {
 	value.map(|value| value.min(i64::MAX as u64) as i64)
@@ -233,6 +234,8 @@ pub struct MetricsConfig {
 pub struct Config {
 	/// Deprecated: use frontendPolicies.accessLog
 	pub filter: Option<Arc<cel::Expression>>,
+	/// Per-request sampling of access logs; a float is treated as the probability of logging.
+	pub sample: Option<Arc<cel::Expression>>,
 	/// Deprecated: use frontendPolicies.accessLog
 	pub fields: LoggingFields,
 	/// Database-only request log fields.
@@ -249,6 +252,38 @@ pub struct Config {
 pub struct LoggingFields {
 	pub remove: Arc<FzHashSet<String>>,
 	pub add: Arc<OrderedStringMap<Arc<cel::Expression>>>,
+	pub redact: Arc<FzHashSet<String>>,
+	pub redact_strategy: RedactStrategy,
+}
+
+/// How the value of a redacted log field is rendered.
+#[apply(schema!)]
+#[derive(Default, Copy, PartialEq, Eq)]
+pub enum RedactStrategy {
+	/// Replace the value with a fixed mask.
+	#[default]
+	Mask,
+	/// Replace the value with a prefix of its SHA-256 digest, so equal values can still be correlated.
+	Sha256Prefix,
+}
+
+const REDACTED_MASK: &str = "***";
+const REDACTED_HASH_PREFIX_BYTES: usize = 8;
+
+impl RedactStrategy {
+	pub fn redact(&self, value: &str) -> String {
+		match self {
+			RedactStrategy::Mask => REDACTED_MASK.to_string(),
+			RedactStrategy::Sha256Prefix => {
+				use sha2::Digest;
+				let digest = sha2::Sha256::digest(value.as_bytes());
+				format!(
+					"sha256:{}",
+					hex::encode(&digest[..REDACTED_HASH_PREFIX_BYTES])
+				)
+			},
+		}
+	}
 }
 
 #[derive(serde::Serialize, Default, Clone, Debug)]
@@ -347,6 +382,26 @@ impl LoggingFields {
 	pub fn has(&self, k: &str) -> bool {
 		self.remove.contains(k) || self.add.contains_key(k)
 	}
+
+	/// Returns the redacted rendering of each present field configured for redaction, keyed by its
+	/// index in `kv`. Redacted fields are kept in the output so their presence is still visible.
+	fn redactions(&self, kv: &[(&str, Option<ValueBag>)]) -> Vec<(usize, String)> {
+		if self.redact.is_empty() {
+			return Vec::new();
+		}
+		kv.iter()
+			.enumerate()
+			.filter_map(|(i, (k, v))| Some((i, self.redacted(k, v.as_ref()?)?)))
+			.collect()
+	}
+
+	/// Returns the redacted rendering of `v` if the field `k` is configured for redaction.
+	pub fn redacted(&self, k: &str, v: &ValueBag) -> Option<String> {
+		self
+			.redact
+			.contains(k)
+			.then(|| self.redact_strategy.redact(&v.to_string()))
+	}
 }
 
 fn json_value_to_value_bag(v: &Value) -> ValueBag<'_> {
@@ -381,15 +436,22 @@ fn original_model_from_metadata<'a>(
 pub struct TraceSampler {
 	pub random_sampling: Option<Arc<cel::Expression>>,
 	pub client_sampling: Option<Arc<cel::Expression>>,
+	pub sampler: Option<trc::Sampler>,
 }
 
 impl TraceSampler {
-	pub fn trace_sampled(&self, req: &Request, tp: Option<&TraceParent>) -> bool {
+	/// trace_sampled decides whether to trace a request. `parent` is the incoming trace, if any, and
+	/// `trace_id` the ID of the trace the request belongs to; only the built-in sampler uses it.
+	pub fn trace_sampled(&self, req: &Request, parent: Option<&TraceParent>, trace_id: u128) -> bool {
 		let TraceSampler {
 			random_sampling,
 			client_sampling,
+			sampler,
 		} = &self;
-		let expr = if tp.is_some() {
+		if let Some(sampler) = sampler {
+			return sampler.sampled(parent, trace_id);
+		}
+		let expr = if parent.is_some() {
 			let Some(cs) = client_sampling else {
 				// If client_sampling is not set, default to include it
 				return true;
@@ -411,16 +473,20 @@ impl TraceSampler {
 pub struct CelLogging {
 	pub cel_context: cel::ContextBuilder,
 	pub filter: Option<Arc<cel::Expression>>,
+	pub sample: Option<Arc<cel::Expression>>,
 	pub fields: LoggingFields,
 	pub otlp_filter: Option<Arc<cel::Expression>>,
 	pub otlp_fields: LoggingFields,
 	pub database_fields: LoggingFields,
 	pub metric_fields: MetricFields,
+	/// Overrides the process log format for access logs.
+	pub format: Option<crate::LoggingFormat>,
 }
 
 pub struct CelLoggingExecutor<'a> {
 	pub executor: cel::Executor<'a>,
 	pub filter: &'a Option<Arc<cel::Expression>>,
+	pub sample: &'a Option<Arc<cel::Expression>>,
 	pub fields: &'a LoggingFields,
 	pub otlp_filter: &'a Option<Arc<cel::Expression>>,
 	pub otlp_fields: &'a LoggingFields,
@@ -433,6 +499,15 @@ impl<'a> CelLoggingExecutor<'a> {
 		self.eval_filter_with(self.filter)
 	}
 
+	/// eval_sample decides whether this request is sampled into the access log. Requests are always
+	/// logged when no sample expression is configured.
+	fn eval_sample(&self) -> bool {
+		match self.sample.as_deref() {
+			Some(s) => self.executor.eval_rng(s),
+			None => true,
+		}
+	}
+
 	fn eval_otlp_filter(&self) -> bool {
 		self.eval_filter_with(self.otlp_filter)
 	}
@@ -558,6 +633,9 @@ impl CelLogging {
 		if let Some(f) = &cfg.filter {
 			cel_context.register_log_expression(f.as_ref());
 		}
+		if let Some(s) = &cfg.sample {
+			cel_context.register_log_expression(s.as_ref());
+		}
 		for v in cfg.fields.add.values_unordered() {
 			cel_context.register_log_expression(v.as_ref());
 		}
@@ -574,11 +652,13 @@ impl CelLogging {
 		Self {
 			cel_context,
 			filter: cfg.filter,
+			sample: cfg.sample,
 			fields: cfg.fields,
 			otlp_filter: None,
 			otlp_fields: LoggingFields::default(),
 			database_fields: cfg.database_fields,
 			metric_fields: metrics.metric_fields,
+			format: None,
 		}
 	}
 
@@ -596,11 +676,13 @@ impl CelLogging {
 		let CelLogging {
 			cel_context: _,
 			filter,
+			sample,
 			fields,
 			otlp_filter,
 			otlp_fields,
 			database_fields,
 			metric_fields,
+			format: _,
 		} = self;
 		let executor = if inputs.req.is_none() && inputs.source_context.is_some() {
 			// TCP case: use new_tcp_logger
@@ -619,6 +701,7 @@ impl CelLogging {
 		CelLoggingExecutor {
 			executor,
 			filter,
+			sample,
 			fields,
 			otlp_filter,
 			otlp_fields,
@@ -821,7 +904,7 @@ impl DropOnLog {
 					.metrics
 					.gen_ai_time_to_first_token
 					.get_or_create(&gen_ai_labels)
-					.observe(ttft.as_secs_f64());
+					.observe(ttft.as_secs_f64(), log.trace_exemplar(), None);
 			}
 			if let Some(time_per_output_token) = llm_response
 				.time_per_output_token
@@ -922,6 +1005,8 @@ impl RequestLog {
 			jwt_sub: None,
 			retry_attempt: None,
 			error: None,
+			route_timeout: false,
+			guardrail_findings: Vec::new(),
 			grpc_status: Default::default(),
 			mcp_status: Default::default(),
 			incoming_span: None,
@@ -931,10 +1016,13 @@ impl RequestLog {
 			a2a_method: None,
 			inference_pool: None,
 			request_handle: None,
+			circuit_breaker_permit: None,
 			request_snapshot: None,
 			response_snapshot: None,
 			source_context: None,
 			response_bytes: 0,
+			request_header_bytes: None,
+			response_header_bytes: None,
 		}
 	}
 
@@ -956,6 +1044,17 @@ impl RequestLog {
 		})
 	}
 
+	/// The exemplar linking metric observations for this request to its trace, if it is sampled.
+	fn trace_exemplar(&self) -> Option<TraceExemplar> {
+		self
+			.outgoing_span
+			.as_ref()
+			.filter(|tp| tp.is_sampled())
+			.map(|tp| TraceExemplar {
+				trace_id: tp.trace_id(),
+			})
+	}
+
 	fn finish_request_handle(
 		&self,
 		rh: ActiveHandle,
@@ -983,6 +1082,12 @@ impl RequestLog {
 			rh.times_ejected(),
 			unhealthy,
 		);
+		let eviction_duration = eviction_duration.filter(|_| {
+			self
+				.health_policy
+				.as_ref()
+				.is_none_or(|p| p.eviction_allowed(rh.evicted_fraction()))
+		});
 		rh.finish_request(
 			health,
 			end_time.duration_since(&self.start),
@@ -1071,6 +1176,10 @@ pub struct RequestLog {
 
 	pub retry_attempt: Option<u8>,
 	pub error: Option<String>,
+	/// Whether the route request timeout, which spans all attempts, expired before a response.
+	pub route_timeout: bool,
+	/// Findings from prompt guards running in `audit` mode.
+	pub guardrail_findings: Vec<String>,
 
 	pub grpc_status: AsyncLog<u8>,
 	pub mcp_status: AsyncLog<mcp::MCPInfo>,
@@ -1086,12 +1195,18 @@ pub struct RequestLog {
 	pub inference_pool: Option<SocketAddr>,
 
 	pub request_handle: Option<ActiveHandle>,
+	/// Backend circuit breaker slot held for the lifetime of the request.
+	pub circuit_breaker_permit: Option<CircuitBreakerPermit>,
 	pub request_snapshot: Option<Arc<cel::RequestSnapshot>>,
 	pub response_snapshot: Option<cel::ResponseSnapshot>,
 	/// Source context for TCP connections (where we don't have an HTTP request)
 	pub source_context: Option<cel::SourceContext>,
 
 	pub response_bytes: u64,
+	/// Cumulative size of the request headers sent to the backend, in bytes.
+	pub request_header_bytes: Option<u64>,
+	/// Cumulative size of the response headers received from the backend, in bytes.
+	pub response_header_bytes: Option<u64>,
 }
 
 impl Drop for DropOnLog {
@@ -1220,7 +1335,7 @@ impl Drop for DropOnLog {
 				.metrics
 				.request_duration
 				.get_or_create(&http_labels)
-				.observe(duration.as_secs_f64());
+				.observe(duration.as_secs_f64(), log.trace_exemplar(), None);
 
 			if let Some(retry_count) = log.retry_attempt {
 				log
@@ -1229,6 +1344,16 @@ impl Drop for DropOnLog {
 					.get_or_create(&http_labels)
 					.inc_by(retry_count as u64);
 			}
+			if log.route_timeout {
+				log.metrics.route_timeouts.get_or_create(&http_labels).inc();
+			}
+			if log.reason == Some(ProxyResponseReason::CircuitBreaker) {
+				log
+					.metrics
+					.circuit_breaker_rejections
+					.get_or_create(&http_labels)
+					.inc();
+			}
 			if !is_tcp {
 				let labels = http_labels.into();
 				if let Some(duration) = log.request_processing_duration {
@@ -1287,6 +1412,8 @@ impl Drop for DropOnLog {
 			}
 
 			let dur = format!("{}ms", duration.as_millis());
+			let guardrail_findings =
+				(!log.guardrail_findings.is_empty()).then(|| log.guardrail_findings.join(","));
 			let grpc = log.grpc_status.load();
 
 			let input_tokens = llm_response.as_ref().and_then(|l| l.input_tokens);
@@ -1376,6 +1503,14 @@ impl Drop for DropOnLog {
 					"http.status",
 					log.status.as_ref().map(|s| s.as_u16().into()),
 				),
+				(
+					"http.request.headers.size",
+					log.request_header_bytes.map(Into::into),
+				),
+				(
+					"http.response.headers.size",
+					log.response_header_bytes.map(Into::into),
+				),
 				("grpc.status", grpc.map(Into::into)),
 				(
 					"tls.sni",
@@ -1545,6 +1680,7 @@ impl Drop for DropOnLog {
 				),
 				("retry.attempt", log.retry_attempt.display()),
 				("error", log.error.quoted()),
+				("guardrail.audit", guardrail_findings.display()),
 				("reason", reason.display()),
 				("duration", Some(dur.as_str().into())),
 			];
@@ -1592,11 +1728,15 @@ impl Drop for DropOnLog {
 					let eval = v.as_ref().map(json_value_to_value_bag);
 					otlp_kv.push((k, eval));
 				}
+				let otlp_redacted = cel_exec.otlp_fields.redactions(&otlp_kv);
+				for (i, v) in &otlp_redacted {
+					otlp_kv[*i].1 = Some(v.as_str().into());
+				}
 				otel.emit("info", "request", &otlp_kv);
 			}
 
 			if maybe_enable_log || log_store_enabled {
-				let passes_log_filter = cel_exec.eval_filter();
+				let passes_log_filter = cel_exec.eval_filter() && cel_exec.eval_sample();
 				if !passes_log_filter {
 					return;
 				}
@@ -1616,9 +1756,18 @@ impl Drop for DropOnLog {
 					let eval = v.as_ref().map(json_value_to_value_bag);
 					kv.push((k, eval));
 				}
+				let redacted = fields.redactions(&kv);
+				for (i, v) in &redacted {
+					kv[*i].1 = Some(v.as_str().into());
+				}
 
 				if maybe_enable_log {
-					agent_core::telemetry::log("info", "request", &kv);
+					let json = log
+						.cel
+						.format
+						.as_ref()
+						.map(|f| *f == crate::LoggingFormat::Json);
+					agent_core::telemetry::log_with_format("info", "request", &kv, json);
 				}
 
 				if log_store_enabled {
@@ -2214,11 +2363,13 @@ mod tests {
 		let cel = CelLogging {
 			cel_context: crate::cel::ContextBuilder::new(),
 			filter: None,
+			sample: None,
 			fields: LoggingFields::default(),
 			otlp_filter: None,
 			otlp_fields: LoggingFields::default(),
 			metric_fields: MetricFields::default(),
 			database_fields: LoggingFields::default(),
+			format: None,
 		};
 		let mut registry = Registry::default();
 		let metrics = Arc::new(Metrics::new(&mut registry, Default::default()));
@@ -2247,6 +2398,88 @@ mod tests {
 		assert!(!DropOnLog::default_unhealthy(&log));
 	}
 
+	#[test]
+	fn sample_expression_keeps_server_errors() {
+		use crate::cel::{Executor, snapshot_request, snapshot_response};
+
+		let sample = Some(Arc::new(
+			Expression::new_strict("response.code >= 500 ? true : 0.0").unwrap(),
+		));
+		let filter = None;
+		let fields = LoggingFields::default();
+		let metric_fields = MetricFields::default();
+		let sampled = |code: u16| {
+			let mut req = ::http::Request::builder()
+				.uri("http://example.com/")
+				.body(crate::http::Body::empty())
+				.unwrap();
+			let mut resp = ::http::Response::builder()
+				.status(code)
+				.body(crate::http::Body::empty())
+				.unwrap();
+			let req_snap = snapshot_request(&mut req, true);
+			let resp_snap = snapshot_response(&mut resp);
+			let exec = CelLoggingExecutor {
+				executor: Executor::new_logger(Some(&req_snap), Some(&resp_snap), None, None, None, None),
+				filter: &filter,
+				sample: &sample,
+				fields: &fields,
+				otlp_filter: &filter,
+				otlp_fields: &fields,
+				database_fields: &fields,
+				metric_fields: &metric_fields,
+			};
+			exec.eval_filter() && exec.eval_sample()
+		};
+		for _ in 0..10 {
+			assert!(!sampled(200), "2xx responses should be dropped");
+			assert!(sampled(500), "5xx responses should be kept");
+			assert!(sampled(503), "5xx responses should be kept");
+		}
+	}
+
+	#[test]
+	fn redacted_fields_are_present_but_masked() {
+		let render = |strategy: RedactStrategy| {
+			let fields = LoggingFields {
+				redact: Arc::new(["authorization".to_string()].into_iter().collect()),
+				redact_strategy: strategy,
+				..Default::default()
+			};
+			let mut kv: Vec<(&str, Option<ValueBag>)> = vec![
+				("http.path", Some("/v1/chat".into())),
+				("authorization", Some("Bearer secret-token".into())),
+				("missing", None),
+			];
+			let redacted = fields.redactions(&kv);
+			for (i, v) in &redacted {
+				kv[*i].1 = Some(v.as_str().into());
+			}
+			kv.iter()
+				.map(|(k, v)| (k.to_string(), v.as_ref().map(|v| v.to_string())))
+				.collect_vec()
+		};
+
+		let masked = render(RedactStrategy::Mask);
+		assert_eq!(
+			masked,
+			vec![
+				("http.path".to_string(), Some("/v1/chat".to_string())),
+				("authorization".to_string(), Some("***".to_string())),
+				("missing".to_string(), None),
+			]
+		);
+
+		let hashed = render(RedactStrategy::Sha256Prefix);
+		let auth = hashed[1].1.as_deref().unwrap();
+		assert!(auth.starts_with("sha256:"), "{auth}");
+		assert_eq!(auth.len(), "sha256:".len() + 16);
+		assert!(!auth.contains("secret-token"));
+		// Equal values hash to the same prefix so they can still be correlated.
+		assert_eq!(render(RedactStrategy::Sha256Prefix)[1], hashed[1]);
+		assert_eq!(hashed[0].1.as_deref(), Some("/v1/chat"));
+	}
+
 	#[test]
 	fn span_writer_flushes_recorded_spans_as_children_of_request_span() {
 		let (tracer, exporter) = test_tracer();
@@ -2298,6 +2531,93 @@ mod tests {
 		assert!(exporter.finished_spans().is_empty());
 	}
 
+	#[test]
+	fn header_sizes_are_logged() {
+		let mut request_headers = ::http::HeaderMap::new();
+		request_headers.insert("content-type", "application/json".parse().unwrap());
+		request_headers.insert("x-request-id", "abc123".parse().unwrap());
+		request_headers.append("x-request-id", "def".parse().unwrap());
+		let mut response_headers = ::http::HeaderMap::new();
+		response_headers.insert("content-length", "5".parse().unwrap());
+
+		let (tracer, exporter) = test_tracer();
+		let mut log = test_request_log();
+		log.tracer = Some(tracer.clone());
+		let mut outgoing = trc::TraceParent::new();
+		outgoing.flags = 1;
+		log.outgoing_span = Some(outgoing);
+		log.request_header_bytes =
+			Some(crate::http::headerlimit::header_bytes(&request_headers) as u64);
+		log.response_header_bytes =
+			Some(crate::http::headerlimit::header_bytes(&response_headers) as u64);
+
+		drop(DropOnLog::from(log));
+		let _ = tracer.provider.force_flush();
+
+		let spans = exporter.finished_spans();
+		let span = spans.first().expect("request span should be exported");
+		let attr = |key: &str| {
+			span
+				.attributes
+				.iter()
+				.find(|attr| attr.key.as_str() == key)
+				.map(|attr| attr.value.as_str().to_string())
+		};
+		// content-type: application/json (12 + 16), x-request-id: abc123 (12 + 6), x-request-id: def (12 + 3)
+		assert_eq!(attr("http.request.headers.size").as_deref(), Some("61"));
+		// content-length: 5 (14 + 1)
+		assert_eq!(attr("http.response.headers.size").as_deref(), Some("15"));
+	}
+
+	#[test]
+	fn request_duration_carries_trace_exemplar() {
+		let mut registry = Registry::default();
+		let mut log = test_request_log();
+		log.metrics = Arc::new(Metrics::new(&mut registry, Default::default()));
+		let mut outgoing = trc::TraceParent::new();
+		outgoing.flags = 1;
+		let trace_id = outgoing.trace_id();
+		log.outgoing_span = Some(outgoing);
+
+		drop(DropOnLog::from(log));
+
+		let mut body = String::new();
+		prometheus_client::encoding::text::encode(&mut body, &registry).unwrap();
+		let exemplar = format!("# {{trace_id=\"{trace_id}\"}}");
+		assert!(
+			body
+				.lines()
+				.any(|l| l.starts_with("request_duration_seconds_bucket") && l.contains(&exemplar)),
+			"{body}"
+		);
+	}
+
+	#[tokio::test]
+	async fn json_access_log_escapes_cel_fields() {
+		agent_core::telemetry::testing::setup_test_logging();
+		let marker = format!("json-access-log-{}", uuid::Uuid::new_v4());
+		let expr = |e: &str| Arc::new(Expression::new_strict(e).unwrap());
+		let fields = LoggingFields {
+			add: Arc::new(OrderedStringMap::from_iter([
+				("marker", expr(&format!("'{marker}'"))),
+				("quoted", expr(r#"'say "hi"\nbye'"#)),
+			])),
+			..Default::default()
+		};
+		let mut log = test_request_log();
+		log.cel.register(&fields);
+		log.cel.fields = fields;
+		log.cel.format = Some(crate::LoggingFormat::Json);
+
+		drop(DropOnLog::from(log));
+
+		let line =
+			agent_core::telemetry::testing::eventually_find(&[("scope", "request"), ("marker", &marker)])
+				.await
+				.expect("access log should be written as json");
+		assert_eq!(line["quoted"], "say \"hi\"\nbye");
+	}
+
 	#[tokio::test]
 	async fn llm_cost_breakdown_span_attributes() {
 		let catalog_file = tempfile::NamedTempFile::new().unwrap();
@@ -6,7 +6,9 @@ use agent_core::version;
 use frozen_collections::FzHashSet;
 use prometheus_client::encoding::EncodeLabelSet;
 use prometheus_client::metrics::counter;
+use prometheus_client::metrics::exemplar::HistogramWithExemplars;
 use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
 use prometheus_client::metrics::histogram::Histogram as PromHistogram;
 use prometheus_client::metrics::info::Info;
 use prometheus_client::registry::{Metric, Registry, Unit};
@@ -43,12 +45,29 @@ pub enum GuardrailAction {
 	Mask,
 	Reject,
 	FailOpen,
+	Audit,
 }
 
 #[derive(Clone, Hash, Default, Debug, PartialEq, Eq, EncodeLabelSet)]
 pub struct GuardrailLabels {
 	pub phase: GuardrailPhase,
+	pub kind: GuardrailKind,
 	pub action: GuardrailAction,
+	/// The entity type the guard detected, such as `SSN`, when the guard reports one.
+	pub entity_type: DefaultedUnknown<RichStrng>,
+}
+
+#[derive(
+	Copy, Clone, Hash, Debug, PartialEq, Eq, prometheus_client::encoding::EncodeLabelValue, Default,
+)]
+pub enum GuardrailKind {
+	#[default]
+	Regex,
+	Webhook,
+	Moderation,
+	BedrockGuardrails,
+	GoogleModelArmor,
+	AzureContentSafety,
 }
 
 #[derive(Clone, Hash, Default, Debug, PartialEq, Eq, EncodeLabelSet)]
@@ -110,6 +129,12 @@ pub struct GenAILabelsTokenUsage {
 	pub common: EncodeArc<GenAILabels>,
 }
 
+#[derive(Clone, Hash, Default, Debug, PartialEq, Eq, EncodeLabelSet)]
+pub struct GenAIBodyLabels {
+	pub gen_ai_system: DefaultedUnknown<RichStrng>,
+	pub gen_ai_input_format: DefaultedUnknown<EncodeDebug<crate::llm::InputFormat>>,
+}
+
 #[derive(Clone, Hash, Default, Debug, PartialEq, Eq, EncodeLabelSet)]
 pub struct CostCatalogLookupLabels {
 	pub status: crate::llm::cost::CostLookupStatus,
@@ -141,11 +166,39 @@ pub struct TCPLabels {
 	pub protocol: TransportProtocol,
 }
 
+#[derive(Clone, Hash, Debug, PartialEq, Eq, EncodeLabelSet)]
+pub struct BindLabels {
+	pub bind: DefaultedUnknown<RichStrng>,
+}
+
 #[derive(Clone, Hash, Debug, PartialEq, Eq, EncodeLabelSet)]
 pub struct ConnectLabels {
 	pub transport: DefaultedUnknown<RichStrng>,
 }
 
+#[derive(
+	Copy, Clone, Hash, Debug, PartialEq, Eq, prometheus_client::encoding::EncodeLabelValue, Default,
+)]
+pub enum DnsCacheResult {
+	/// Addresses were served from the cache
+	#[default]
+	Hit,
+	/// The hostname was not cached, and was resolved
+	Miss,
+	/// A recent failed lookup was served from the cache
+	Negative,
+}
+
+#[derive(Clone, Hash, Debug, PartialEq, Eq, EncodeLabelSet)]
+pub struct DnsCacheLabels {
+	pub result: DnsCacheResult,
+}
+
+#[derive(Clone, Hash, Debug, PartialEq, Eq, EncodeLabelSet)]
+pub struct CircuitBreakerLabels {
+	pub backend: DefaultedUnknown<RichStrng>,
+}
+
 #[derive(
 	Copy, Clone, Hash, Debug, PartialEq, Eq, prometheus_client::encoding::EncodeLabelValue, Default,
 )]
@@ -183,8 +236,16 @@ pub struct OutboundCallLabels {
 	pub subtype: OutboundCallSubtype,
 }
 
+/// Exemplar attached to histogram observations, linking the sample to the trace of the request.
+/// Exemplars are only exposed in the OpenMetrics format.
+#[derive(Clone, Hash, Debug, PartialEq, Eq, EncodeLabelSet)]
+pub struct TraceExemplar {
+	pub trace_id: String,
+}
+
 type Counter = Family<HTTPLabels, counter::Counter>;
 type Histogram<T> = Family<T, prometheus_client::metrics::histogram::Histogram>;
+type ExemplarHistogram<T> = Family<T, HistogramWithExemplars<TraceExemplar>>;
 type TCPCounter = Family<TCPLabels, counter::Counter>;
 
 #[derive(Clone, Hash, Debug, PartialEq, Eq, EncodeLabelSet)]
@@ -195,7 +256,7 @@ pub struct BuildLabel {
 #[derive(Debug)]
 pub struct Metrics {
 	pub requests: Counter,
-	pub request_duration: Histogram<HTTPLabels>,
+	pub request_duration: ExemplarHistogram<HTTPLabels>,
 	pub request_processing_duration: Histogram<MinimalHTTPLabels>,
 	pub response_processing_duration: Histogram<MinimalHTTPLabels>,
 	pub response_bytes: Family<HTTPLabels, counter::Counter>,
@@ -206,24 +267,38 @@ pub struct Metrics {
 	pub gen_ai_cost: Family<GenAILabels, counter::Counter<f64>>,
 	pub gen_ai_request_duration: Histogram<GenAILabels>,
 	pub gen_ai_time_per_output_token: Histogram<GenAILabels>,
-	pub gen_ai_time_to_first_token: Histogram<GenAILabels>,
+	pub gen_ai_time_to_first_token: ExemplarHistogram<GenAILabels>,
+	pub gen_ai_request_body_bytes: Histogram<GenAIBodyLabels>,
+	pub gen_ai_response_body_bytes: Histogram<GenAIBodyLabels>,
 
 	pub tls_handshake_duration: Histogram<TCPLabels>,
 
 	pub downstream_connection: TCPCounter,
+	// metrics for downstream connections that are still open, including those remaining during a drain
+	pub downstream_active_connections: Family<BindLabels, Gauge>,
 	pub tcp_downstream_rx_bytes: Family<TCPLabels, counter::Counter>,
 	pub tcp_downstream_tx_bytes: Family<TCPLabels, counter::Counter>,
 
 	pub upstream_connect_duration: Histogram<ConnectLabels>,
 	pub upstream_call_duration: Histogram<OutboundCallLabels>,
+	// metrics for DNS lookups served by the resolver cache
+	pub dns_cache_lookups: Family<DnsCacheLabels, counter::Counter>,
 
-	// metrics for guardrail checks (allow/mask/reject) for request/response
+	// metrics for guardrail checks (allow/mask/reject) for request/response, by guard kind and
+	// detected entity type
 	pub guardrail_checks: Family<GuardrailLabels, counter::Counter>,
 
 	pub cost_catalog_lookups: Family<CostCatalogLookupLabels, counter::Counter>,
 
 	// metrics for request retries
 	pub retries: Counter,
+	// metrics for requests that exceeded the route request timeout
+	pub route_timeouts: Counter,
+	// metrics for requests rejected by a backend circuit breaker
+	pub circuit_breaker_rejections: Counter,
+	// metrics for requests waiting for a backend circuit breaker slot
+	pub circuit_breaker_queue_depth: Family<CircuitBreakerLabels, Gauge>,
+	pub circuit_breaker_queue_wait: Histogram<CircuitBreakerLabels>,
 }
 
 // FilteredRegistry is a wrapper around Registry that allows to filter out certain metrics.
@@ -341,7 +416,7 @@ impl Metrics {
 		);
 
 		let gen_ai_time_to_first_token = Family::<GenAILabels, _>::new_with_constructor(move || {
-			PromHistogram::new(FIRST_TOKEN_BUCKET)
+			HistogramWithExemplars::new(FIRST_TOKEN_BUCKET)
 		});
 		registry.register(
 			"gen_ai_server_time_to_first_token",
@@ -349,6 +424,27 @@ impl Metrics {
 			gen_ai_time_to_first_token.clone(),
 		);
 
+		let gen_ai_request_body_bytes = Family::<GenAIBodyLabels, _>::new_with_constructor(move || {
+			PromHistogram::new(BODY_SIZE_BUCKET)
+		});
+		registry.register_with_unit(
+			"gen_ai_request_body",
+			"Size of generative AI request bodies, after decompression",
+			Unit::Bytes,
+			gen_ai_request_body_bytes.clone(),
+		);
+
+		let gen_ai_response_body_bytes =
+			Family::<GenAIBodyLabels, _>::new_with_constructor(move || {
+				PromHistogram::new(BODY_SIZE_BUCKET)
+			});
+		registry.register_with_unit(
+			"gen_ai_response_body",
+			"Size of buffered generative AI response bodies, after decompression",
+			Unit::Bytes,
+			gen_ai_response_body_bytes.clone(),
+		);
+
 		Metrics {
 			requests: build(
 				&mut registry,
@@ -378,6 +474,15 @@ impl Metrics {
 				"downstream_connections",
 				"The total number of downstream connections established",
 			),
+			downstream_active_connections: {
+				let m = Family::<BindLabels, Gauge>::default();
+				registry.register(
+					"downstream_active_connections",
+					"The number of open downstream connections, per bind",
+					m.clone(),
+				);
+				m
+			},
 
 			mcp_requests: build(
 				&mut registry,
@@ -390,6 +495,8 @@ impl Metrics {
 			gen_ai_request_duration,
 			gen_ai_time_per_output_token,
 			gen_ai_time_to_first_token,
+			gen_ai_request_body_bytes,
+			gen_ai_response_body_bytes,
 
 			response_bytes: {
 				let m = Family::<HTTPLabels, _>::default();
@@ -403,7 +510,7 @@ impl Metrics {
 			},
 			request_duration: {
 				let m = Family::<HTTPLabels, _>::new_with_constructor(move || {
-					PromHistogram::new(HTTP_REQUEST_DURATION_BUCKET)
+					HistogramWithExemplars::new(HTTP_REQUEST_DURATION_BUCKET)
 				});
 				registry.register_with_unit(
 					"request_duration",
@@ -481,6 +588,11 @@ impl Metrics {
 				);
 				m
 			},
+			dns_cache_lookups: build(
+				&mut registry,
+				"dns_cache_lookups",
+				"The total number of DNS lookups made through the resolver cache, by cache result",
+			),
 			tls_handshake_duration: {
 				let m = Family::<TCPLabels, _>::new_with_constructor(move || {
 					PromHistogram::new(CONNECT_DURATION_BUCKET)
@@ -498,6 +610,37 @@ impl Metrics {
 				"retries",
 				"The total number of request retries",
 			),
+			route_timeouts: build(
+				&mut registry,
+				"route_timeouts",
+				"The total number of requests that exceeded the route request timeout",
+			),
+			circuit_breaker_rejections: build(
+				&mut registry,
+				"circuit_breaker_rejections",
+				"The total number of requests rejected by a backend circuit breaker",
+			),
+			circuit_breaker_queue_depth: {
+				let m = Family::<CircuitBreakerLabels, Gauge>::default();
+				registry.register(
+					"circuit_breaker_queue_depth",
+					"The number of requests waiting for a backend circuit breaker slot",
+					m.clone(),
+				);
+				m
+			},
+			circuit_breaker_queue_wait: {
+				let m = Family::<CircuitBreakerLabels, _>::new_with_constructor(move || {
+					PromHistogram::new(HTTP_REQUEST_DURATION_BUCKET)
+				});
+				registry.register_with_unit(
+					"circuit_breaker_queue_wait",
+					"Time requests spent waiting for a backend circuit breaker slot (seconds)",
+					Unit::Seconds,
+					m.clone(),
+				);
+				m
+			},
 		}
 	}
 }
@@ -521,6 +664,10 @@ const TOKEN_USAGE_BUCKET: [f64; 14] = [
 const REQUEST_DURATION_BUCKET: [f64; 14] = [
 	0.01, 0.02, 0.04, 0.08, 0.16, 0.32, 0.64, 1.28, 2.56, 5.12, 10.24, 20.48, 40.96, 81.92,
 ];
+// LLM payload sizes. Covers 256B up to 16MB, growing 4x per bucket.
+const BODY_SIZE_BUCKET: [f64; 9] = [
+	256., 1024., 4096., 16384., 65536., 262144., 1048576., 4194304., 16777216.,
+];
 // Finer-grained, exponentially growing buckets for TCP/TLS connect.
 // Keep in seconds (Prometheus convention). Prioritize sub-second resolution, with a few larger outlier buckets.
 const CONNECT_DURATION_BUCKET: [f64; 10] = [
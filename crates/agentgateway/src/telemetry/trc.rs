@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use agent_core::telemetry::ValueBag;
+pub use baggage::Baggage;
 use http::Version;
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
@@ -16,9 +17,10 @@ use opentelemetry_sdk::trace::{
 };
 pub use traceparent::TraceParent;
 
-use crate::cel;
+use crate::serdes::schema;
 use crate::telemetry::log::{CelLoggingExecutor, LoggingFields, RequestLog};
 use crate::types::agent::{BackendTrafficPolicy, SimpleBackendReference, TracingConfig};
+use crate::{apply, cel};
 
 #[derive(Clone, Debug)]
 pub struct Tracer {
@@ -151,9 +153,65 @@ pub struct DeprecatedConfig {
 	pub fields: LoggingFields,
 	pub random_sampling: Option<Arc<cel::Expression>>,
 	pub client_sampling: Option<Arc<cel::Expression>>,
+	pub sampler: Option<Sampler>,
 	pub path: String,
 }
 
+/// A built-in trace sampler, named after the OpenTelemetry `OTEL_TRACES_SAMPLER` values.
+/// Unlike `randomSampling` and `clientSampling`, ratios are applied to the trace ID rather than
+/// drawn at random, so every hop configured with the same ratio makes the same decision.
+#[apply(schema!)]
+#[derive(Copy, PartialEq)]
+pub enum Sampler {
+	/// Sample every request.
+	#[serde(rename = "always_on")]
+	AlwaysOn,
+	/// Never sample.
+	#[serde(rename = "always_off")]
+	AlwaysOff,
+	/// Sample a fraction (0.0-1.0) of traces, regardless of any incoming sampling decision.
+	#[serde(rename = "traceidratio")]
+	TraceIdRatio(f64),
+	/// Sample a fraction (0.0-1.0) of new traces. Requests that are already part of a trace follow
+	/// the sampled flag of their parent.
+	#[serde(rename = "parentbased_traceidratio")]
+	ParentBasedTraceIdRatio(f64),
+}
+
+impl Sampler {
+	pub fn validate(&self) -> anyhow::Result<()> {
+		match self {
+			Sampler::TraceIdRatio(r) | Sampler::ParentBasedTraceIdRatio(r)
+				if !(0.0..=1.0).contains(r) =>
+			{
+				anyhow::bail!("sampling ratio must be between 0.0 and 1.0, got {r}")
+			},
+			_ => Ok(()),
+		}
+	}
+
+	/// sampled decides whether to trace a request. `parent` is the incoming trace, if any, and
+	/// `trace_id` the ID of the trace the request belongs to.
+	pub fn sampled(&self, parent: Option<&TraceParent>, trace_id: u128) -> bool {
+		match self {
+			Sampler::AlwaysOn => true,
+			Sampler::AlwaysOff => false,
+			Sampler::TraceIdRatio(r) => trace_id_ratio_sampled(trace_id, *r),
+			Sampler::ParentBasedTraceIdRatio(r) => match parent {
+				Some(parent) => parent.is_sampled(),
+				None => trace_id_ratio_sampled(trace_id, *r),
+			},
+		}
+	}
+}
+
+/// trace_id_ratio_sampled matches the OpenTelemetry TraceIdRatioBased sampler: the low 64 bits of
+/// the trace ID, shifted down to 63 bits, must fall below `ratio` of that range.
+fn trace_id_ratio_sampled(trace_id: u128, ratio: f64) -> bool {
+	let upper_bound = (ratio.max(0.0) * (1u64 << 63) as f64) as u64;
+	((trace_id as u64) >> 1) < upper_bound
+}
+
 mod semconv {
 	use opentelemetry::Key;
 
@@ -286,7 +344,7 @@ impl Tracer {
 			.iter()
 			.filter(|(k, _)| !self.fields.has(k))
 			.filter_map(|(k, v)| v.as_ref().map(|v| (k, v)))
-			.map(|(k, v)| KeyValue::new(Key::new(k.to_string()), to_otel(v)))
+			.map(|(k, v)| KeyValue::new(Key::new(k.to_string()), self.attribute_value(k, v)))
 			.collect_vec();
 		let out_span = request.outgoing_span.as_ref().unwrap();
 		if !out_span.is_sampled() {
@@ -323,7 +381,10 @@ impl Tracer {
 			{
 				span_name = Some(s);
 			} else if let Some(eval) = v.as_ref().map(ValueBag::capture_serde1) {
-				attributes.push(KeyValue::new(Key::new(k.to_string()), to_otel(&eval)));
+				attributes.push(KeyValue::new(
+					Key::new(k.to_string()),
+					self.attribute_value(&k, &eval),
+				));
 			}
 		}
 
@@ -345,6 +406,14 @@ impl Tracer {
 			attributes,
 		));
 	}
+
+	/// Converts a span attribute, applying the redaction configured for its field.
+	fn attribute_value(&self, k: &str, v: &ValueBag) -> opentelemetry::Value {
+		match self.fields.redacted(k, v) {
+			Some(redacted) => redacted.into(),
+			None => to_otel(v),
+		}
+	}
 }
 
 /// Policy-aware OTLP gRPC exporter that routes via `GrpcReferenceChannel`, ensuring
@@ -635,6 +704,74 @@ pub fn set_resource_defaults_from_config(cfg: &crate::Config) {
 	});
 }
 
+mod baggage {
+	use std::collections::BTreeMap;
+
+	use http::HeaderMap;
+	use percent_encoding::percent_decode_str;
+
+	use crate::apply;
+	use crate::http::x_headers::BAGGAGE;
+	use crate::serdes::schema;
+
+	/// Entries of the W3C baggage (https://www.w3.org/TR/baggage/) received on a request, with
+	/// values percent-decoded. Exposed to CEL as `baggage`.
+	#[apply(schema!)]
+	#[derive(Default, ::cel::DynamicType)]
+	pub struct Baggage(pub BTreeMap<String, String>);
+
+	impl Baggage {
+		pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+			let entries: BTreeMap<String, String> = members(headers)
+				.map(|(key, value, _)| {
+					(
+						key.to_string(),
+						percent_decode_str(value).decode_utf8_lossy().into_owned(),
+					)
+				})
+				.collect();
+			(!entries.is_empty()).then_some(Baggage(entries))
+		}
+
+		/// Rewrites the baggage header to only carry the `allowed` keys, removing it if none remain.
+		/// Entries are forwarded as received, including any properties.
+		pub fn retain_allowed(headers: &mut HeaderMap, allowed: &[String]) {
+			let kept = members(headers)
+				.filter(|(key, _, _)| allowed.iter().any(|a| a == key))
+				.map(|(_, _, member)| member)
+				.collect::<Vec<_>>()
+				.join(",");
+			match http::HeaderValue::from_str(&kept) {
+				Ok(hv) if !kept.is_empty() => {
+					headers.insert(BAGGAGE, hv);
+				},
+				_ => {
+					headers.remove(BAGGAGE);
+				},
+			}
+		}
+	}
+
+	/// Returns the (key, raw value, member) of each valid list member across all baggage headers.
+	fn members(headers: &HeaderMap) -> impl Iterator<Item = (&str, &str, &str)> {
+		headers
+			.get_all(BAGGAGE)
+			.iter()
+			.filter_map(|v| v.to_str().ok())
+			.flat_map(|v| v.split(','))
+			.filter_map(|member| {
+				let member = member.trim();
+				let kv = member.split(';').next().unwrap_or_default();
+				let (key, value) = kv.split_once('=')?;
+				let key = key.trim();
+				if key.is_empty() {
+					return None;
+				}
+				Some((key, value.trim(), member))
+			})
+	}
+}
+
 mod traceparent {
 	use std::fmt;
 
@@ -721,13 +858,30 @@ mod traceparent {
 			}
 
 			let segs: Vec<&str> = value.split('-').collect();
+			let [version, trace_id, span_id, flags] = segs[..] else {
+				anyhow::bail!(
+					"traceparent malformed: expected 4 fields, got {}",
+					segs.len()
+				)
+			};
+			let hex = |s: &str, len: usize| {
+				s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+			};
+			if !hex(version, 2) || !hex(trace_id, 32) || !hex(span_id, 16) || !hex(flags, 2) {
+				anyhow::bail!("traceparent malformed: fields must be lowercase hex")
+			}
 
-			Ok(Self {
-				version: u8::from_str_radix(segs[0], 16)?,
-				trace_id: u128::from_str_radix(segs[1], 16)?,
-				span_id: u64::from_str_radix(segs[2], 16)?,
-				flags: u8::from_str_radix(segs[3], 16)?,
-			})
+			let tp = Self {
+				version: u8::from_str_radix(version, 16)?,
+				trace_id: u128::from_str_radix(trace_id, 16)?,
+				span_id: u64::from_str_radix(span_id, 16)?,
+				flags: u8::from_str_radix(flags, 16)?,
+			};
+			// Per the spec, version ff and all-zero ids are invalid.
+			if tp.version == 0xff || tp.trace_id == 0 || tp.span_id == 0 {
+				anyhow::bail!("traceparent invalid: {value}")
+			}
+			Ok(tp)
 		}
 	}
 }
@@ -795,11 +949,13 @@ mod tests {
 		let cel = CelLogging {
 			cel_context: crate::cel::ContextBuilder::new(),
 			filter: None,
+			sample: None,
 			fields: LoggingFields::default(),
 			otlp_filter: None,
 			otlp_fields: LoggingFields::default(),
 			metric_fields: MetricFields::default(),
 			database_fields: LoggingFields::default(),
+			format: None,
 		};
 		let mut registry = Registry::default();
 		let metrics = Arc::new(Metrics::new(&mut registry, Default::default()));
@@ -832,6 +988,7 @@ mod tests {
 		request.outgoing_span = Some(outgoing.clone());
 
 		let filter = None;
+		let sample = None;
 		let fields = LoggingFields::default();
 		let otlp_filter = None;
 		let otlp_fields = LoggingFields::default();
@@ -840,6 +997,7 @@ mod tests {
 		let cel_exec = CelLoggingExecutor {
 			executor: crate::cel::Executor::new_empty(),
 			filter: &filter,
+			sample: &sample,
 			fields: &fields,
 			otlp_filter: &otlp_filter,
 			otlp_fields: &otlp_fields,
@@ -861,6 +1019,127 @@ mod tests {
 		assert!(span.links.iter().next().is_none());
 	}
 
+	#[test]
+	fn send_redacts_configured_attributes() {
+		let (mut tracer, exporter) = test_tracer();
+		tracer.fields = Arc::new(LoggingFields {
+			redact: Arc::new(["src.addr".to_string()].into_iter().collect()),
+			..Default::default()
+		});
+		let mut request = test_request_log();
+		let mut outgoing = TraceParent::new();
+		outgoing.flags = 1;
+		request.outgoing_span = Some(outgoing);
+
+		let filter = None;
+		let sample = None;
+		let fields = LoggingFields::default();
+		let otlp_filter = None;
+		let otlp_fields = LoggingFields::default();
+		let metric_fields = Arc::new(MetricFields::default());
+		let database_fields = LoggingFields::default();
+		let cel_exec = CelLoggingExecutor {
+			executor: crate::cel::Executor::new_empty(),
+			filter: &filter,
+			sample: &sample,
+			fields: &fields,
+			otlp_filter: &otlp_filter,
+			otlp_fields: &otlp_fields,
+			metric_fields: &metric_fields,
+			database_fields: &database_fields,
+		};
+
+		tracer.send(
+			&request,
+			&Timestamp::now(),
+			&cel_exec,
+			&[
+				("src.addr", Some("10.0.0.1:1234".into())),
+				("http.method", Some("GET".into())),
+			],
+		);
+		let _ = tracer.provider.force_flush();
+
+		let spans = exporter.finished_spans();
+		assert_eq!(spans.len(), 1);
+		let attr = |k: &str| {
+			spans[0]
+				.attributes
+				.iter()
+				.find(|kv| kv.key.as_str() == k)
+				.map(|kv| kv.value.as_str().to_string())
+		};
+		assert_eq!(attr("src.addr").as_deref(), Some("***"));
+		assert_eq!(attr("http.method").as_deref(), Some("GET"));
+	}
+
+	#[test]
+	fn traceparent_parsing() {
+		let tp =
+			TraceParent::try_from("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+		assert_eq!(tp.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+		assert_eq!(tp.span_id(), "00f067aa0ba902b7");
+		assert!(tp.is_sampled());
+		assert_eq!(
+			format!("{tp:?}"),
+			"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+		);
+
+		let child = tp.new_span();
+		assert_eq!(child.trace_id, tp.trace_id);
+		assert_ne!(child.span_id, tp.span_id);
+		assert_eq!(child.flags, tp.flags);
+
+		for invalid in [
+			"",
+			// Wrong field separators
+			"00_4bf92f3577b34da6a3ce929d0e0e4736_00f067aa0ba902b7_01",
+			// Uppercase hex
+			"00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+			// Sign characters are accepted by from_str_radix but not by the spec
+			"00-+bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+			"ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+			"00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+			"00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+		] {
+			assert!(TraceParent::try_from(invalid).is_err(), "{invalid}");
+		}
+	}
+
+	#[test]
+	fn baggage_parsing_and_filtering() {
+		let mut headers = http::HeaderMap::new();
+		headers.append(
+			"baggage",
+			"tenant=acme, user%20id = a%2Cb;prop=1,invalid,=novalue"
+				.parse()
+				.unwrap(),
+		);
+		headers.append("baggage", "internal=secret".parse().unwrap());
+
+		let baggage = Baggage::from_headers(&headers).unwrap();
+		assert_eq!(
+			baggage.0,
+			[
+				("tenant".to_string(), "acme".to_string()),
+				("user%20id".to_string(), "a,b".to_string()),
+				("internal".to_string(), "secret".to_string()),
+			]
+			.into()
+		);
+
+		Baggage::retain_allowed(
+			&mut headers,
+			&["tenant".to_string(), "user%20id".to_string()],
+		);
+		let values: Vec<_> = headers.get_all("baggage").iter().collect();
+		assert_eq!(values, ["tenant=acme,user%20id = a%2Cb;prop=1"]);
+
+		Baggage::retain_allowed(&mut headers, &["other".to_string()]);
+		assert!(headers.get("baggage").is_none());
+		assert!(Baggage::from_headers(&headers).is_none());
+	}
+
 	#[test]
 	fn should_export_span_keep_filter_cases() {
 		use crate::cel::{Executor, Expression, snapshot_request, snapshot_response};
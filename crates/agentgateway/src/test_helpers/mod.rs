@@ -9,7 +9,7 @@ pub mod proxymock;
 pub mod ratelimitmock;
 pub use common::MockInstance;
 #[cfg(any(test, feature = "internal_benches"))]
-pub use policy::{policy_client, test_policy};
+pub use policy::{policy_client, request_log, test_policy};
 
 mod common {
 	use std::net::SocketAddr;
@@ -18,20 +18,31 @@ where
 }
 
 fn make_min_req_log() -> crate::telemetry::log::RequestLog {
-	use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 	use std::sync::Arc;
 
 	use frozen_collections::FzHashSet;
 	use prometheus_client::registry::Registry;
 
+	use crate::telemetry::metrics::Metrics;
+
+	let mut prom = Registry::default();
+	let metrics = Arc::new(Metrics::new(&mut prom, FzHashSet::default()));
+	request_log(metrics)
+}
+
+pub fn request_log(
+	metrics: std::sync::Arc<crate::telemetry::metrics::Metrics>,
+) -> crate::telemetry::log::RequestLog {
+	use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
 	use crate::llm::cost::ModelCatalog;
 	use crate::telemetry::log;
 	use crate::telemetry::log::{LoggingFields, RequestLog};
-	use crate::telemetry::metrics::Metrics;
 	use crate::transport::stream::TCPConnectionInfo;
 
 	let log_cfg = log::Config {
 		filter: None,
+		sample: None,
 		fields: LoggingFields::default(),
 		database_fields: LoggingFields::default(),
 		level: "info".to_string(),
@@ -39,8 +50,6 @@ fn make_min_req_log() -> crate::telemetry::log::RequestLog {
 		database: None,
 	};
 	let cel = log::CelLogging::new(log_cfg, MetricsConfig::default());
-	let mut prom = Registry::default();
-	let metrics = Arc::new(Metrics::new(&mut prom, FzHashSet::default()));
 	let model_catalog = ModelCatalog::empty();
 	let start = agent_core::Timestamp::now();
 	let tcp_info = TCPConnectionInfo {
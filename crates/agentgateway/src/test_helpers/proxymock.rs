@@ -189,6 +189,9 @@ pub fn llm_named_provider(
 		path_override: None,
 		path_prefix: None,
 		tokenize,
+		tokenizer: None,
+		response_buffer_limit: None,
+		client_certificate: None,
 		policies: None,
 	}
 }
@@ -225,6 +228,9 @@ pub fn custom_llm_backend_with_formats(
 		path_override: None,
 		path_prefix: None,
 		tokenize: false,
+		tokenizer: None,
+		response_buffer_limit: None,
+		client_tls: None,
 		inline_policies: vec![],
 	};
 	let providers = EndpointSet::new(vec![vec![(provider.name.clone(), provider)]]);
@@ -257,6 +263,7 @@ pub fn basic_named_route(target: Strng) -> Route {
 			method: None,
 			query: vec![],
 		}],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: Default::default(),
 		backends: vec![RouteBackendReference {
@@ -747,6 +754,7 @@ impl TestBind {
 				BackendWithPolicies {
 					backend: opb,
 					inline_policies: target_policies,
+					metadata: Default::default(),
 				},
 			);
 			bw.insert_backend(
@@ -754,6 +762,7 @@ impl TestBind {
 				BackendWithPolicies {
 					backend: b,
 					inline_policies: policies,
+					metadata: Default::default(),
 				},
 			);
 		}
@@ -836,6 +845,7 @@ impl TestBind {
 				BackendWithPolicies {
 					backend: b,
 					inline_policies: policies,
+					metadata: Default::default(),
 				},
 			);
 		}
@@ -860,6 +870,7 @@ impl TestBind {
 		let bps = BackendWithPolicies {
 			backend: Backend::Opaque(crate::types::local::local_name(b.name), host),
 			inline_policies: policies,
+			metadata: Default::default(),
 		};
 		self
 			.pi
@@ -1063,6 +1074,7 @@ impl TestBind {
 			key: None,
 			root: Some(MOCK_TLS_CA_CERT.to_vec()),
 			hostname: sni.map(|s| s.to_string()),
+			verify_hostname: None,
 			insecure: false,
 			insecure_host: true,
 			alpn: None,
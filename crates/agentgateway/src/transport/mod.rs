@@ -1,4 +1,5 @@
 pub mod hbone;
+pub mod ocsp;
 mod rewind;
 pub mod stream;
 pub mod tls;
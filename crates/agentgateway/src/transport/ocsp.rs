@@ -0,0 +1,549 @@
+//! OCSP stapling for served certificates.
+//!
+//! The leaf certificate's OCSP responder, from its Authority Information Access extension, is
+//! queried in the background and the latest response is stapled to handshakes, so clients do not
+//! need to query the responder themselves. Responses are refreshed halfway through their validity
+//! window. If the responder fails, the previous response is served until it expires, after which
+//! handshakes continue without a staple.
+//!
+//! A response is only stapled if it covers the served certificate and is signed by its issuer, or
+//! by a responder certificate the issuer delegated OCSP signing to.
+
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, anyhow, bail};
+use arc_swap::ArcSwapOption;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use sha1::{Digest, Sha1};
+use tracing::{debug, warn};
+use x509_parser::certificate::X509Certificate;
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, ASN1Result, Tag};
+
+use crate::client;
+use crate::http::filters::BackendRequestTimeout;
+
+#[cfg(test)]
+#[path = "ocsp_tests.rs"]
+mod ocsp_tests;
+
+/// How long to wait before retrying a failed fetch.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How often to refresh responses that do not set `nextUpdate`.
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const RESPONSE_LIMIT: usize = 64 * 1024;
+/// Tolerated clock skew between the gateway and the responder.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// id-ad-ocsp, the access method of an OCSP responder in the Authority Information Access extension.
+const OCSP_ACCESS_METHOD: &str = "1.3.6.1.5.5.7.48.1";
+/// id-pkix-ocsp-basic
+const OCSP_BASIC_RESPONSE: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 48, 1, 1];
+/// id-sha1
+const SHA1: &[u64] = &[1, 3, 14, 3, 2, 26];
+
+/// A parsed OCSP response, stapled to the certificate.
+#[derive(Debug)]
+struct Staple {
+	key: Arc<CertifiedKey>,
+	next_update: Option<SystemTime>,
+	refresh_at: SystemTime,
+}
+
+impl Staple {
+	fn is_expired(&self, now: SystemTime) -> bool {
+		self.next_update.is_some_and(|next| now >= next)
+	}
+}
+
+/// Identifies the certificate in OCSP requests and responses, by SHA-1 hashes of its issuer's name
+/// and public key and by its serial number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CertId {
+	issuer_name_hash: Vec<u8>,
+	issuer_key_hash: Vec<u8>,
+	serial: Vec<u8>,
+}
+
+impl CertId {
+	fn new(leaf: &X509Certificate<'_>, issuer: &X509Certificate<'_>) -> Self {
+		Self {
+			issuer_name_hash: Sha1::digest(leaf.issuer().as_raw()).to_vec(),
+			issuer_key_hash: Sha1::digest(&issuer.public_key().subject_public_key.data).to_vec(),
+			serial: leaf.tbs_certificate.raw_serial().to_vec(),
+		}
+	}
+}
+
+#[derive(Debug)]
+struct RefreshTask(tokio::task::AbortHandle);
+
+impl Drop for RefreshTask {
+	fn drop(&mut self) {
+		self.0.abort();
+	}
+}
+
+/// Resolves the served certificate, with the latest OCSP response stapled when one is available.
+pub struct StaplingResolver {
+	key: Arc<CertifiedKey>,
+	issuer: CertificateDer<'static>,
+	responder: String,
+	cert_id: CertId,
+	staple: ArcSwapOption<Staple>,
+	/// The background refresh, if started. Aborted when the resolver is dropped.
+	refresh_task: OnceLock<RefreshTask>,
+}
+
+impl std::fmt::Debug for StaplingResolver {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("StaplingResolver")
+			.field("responder", &self.responder)
+			.finish_non_exhaustive()
+	}
+}
+
+impl StaplingResolver {
+	/// Builds a resolver for `cert_chain`, which must start with the leaf certificate followed by its
+	/// issuer. Fails if the leaf does not name an OCSP responder. Nothing is fetched until
+	/// [`StaplingResolver::start_refresh`] is called.
+	pub fn new(
+		cert_chain: Vec<CertificateDer<'static>>,
+		private_key: PrivateKeyDer<'static>,
+	) -> anyhow::Result<Self> {
+		let signing_key = super::tls::provider()
+			.key_provider
+			.load_private_key(private_key)?;
+		let key = CertifiedKey::new(cert_chain, signing_key);
+		let responder = {
+			let [leaf, _, ..] = key.cert.as_slice() else {
+				bail!("OCSP stapling requires the issuer certificate to follow the leaf certificate");
+			};
+			let (_, leaf) = x509_parser::parse_x509_certificate(leaf)
+				.map_err(|e| anyhow!("failed to parse certificate: {e}"))?;
+			ocsp_responder(&leaf).ok_or_else(|| {
+				anyhow!("OCSP stapling requires the certificate to include an OCSP responder URL")
+			})?
+		};
+		Self::with_responder(key, responder)
+	}
+
+	fn with_responder(key: CertifiedKey, responder: String) -> anyhow::Result<Self> {
+		let [leaf, issuer, ..] = key.cert.as_slice() else {
+			bail!("OCSP stapling requires the issuer certificate to follow the leaf certificate");
+		};
+		let (_, leaf_cert) = x509_parser::parse_x509_certificate(leaf)
+			.map_err(|e| anyhow!("failed to parse certificate: {e}"))?;
+		let (_, issuer_cert) = x509_parser::parse_x509_certificate(issuer)
+			.map_err(|e| anyhow!("failed to parse issuer certificate: {e}"))?;
+		let cert_id = CertId::new(&leaf_cert, &issuer_cert);
+		let issuer = issuer.clone();
+		Ok(Self {
+			key: Arc::new(key),
+			issuer,
+			responder,
+			cert_id,
+			staple: ArcSwapOption::empty(),
+			refresh_task: OnceLock::new(),
+		})
+	}
+
+	/// Keeps the staple fresh in the background, fetching through `client`, unless that is already
+	/// running. The task is aborted when the resolver is dropped, e.g. once its listener is removed
+	/// or replaced.
+	pub fn start_refresh(self: &Arc<Self>, client: &client::Client) {
+		self.refresh_task.get_or_init(|| {
+			let resolver = Arc::downgrade(self);
+			let client = client.clone();
+			let task = tokio::spawn(async move {
+				loop {
+					let delay = match Weak::upgrade(&resolver) {
+						Some(resolver) => resolver.refresh(&client).await,
+						None => return,
+					};
+					tokio::time::sleep(delay).await;
+				}
+			});
+			RefreshTask(task.abort_handle())
+		});
+	}
+
+	/// Fetches a new response if the current one is due for a refresh, and returns how long to wait
+	/// before calling this again.
+	pub async fn refresh(&self, client: &client::Client) -> Duration {
+		let now = SystemTime::now();
+		if let Some(staple) = self.staple.load().as_ref()
+			&& now < staple.refresh_at
+		{
+			return staple
+				.refresh_at
+				.duration_since(now)
+				.unwrap_or(RETRY_INTERVAL);
+		}
+		let res = match self.fetch(client).await {
+			Ok(response) => self.set_staple(response),
+			Err(e) => Err(e),
+		};
+		match res {
+			Ok(()) => {
+				debug!(responder = %self.responder, "refreshed OCSP staple");
+				let refresh_at = self
+					.staple
+					.load()
+					.as_ref()
+					.map(|s| s.refresh_at)
+					.unwrap_or(now);
+				refresh_at
+					.duration_since(SystemTime::now())
+					.unwrap_or(RETRY_INTERVAL)
+			},
+			Err(e) => {
+				warn!(
+					responder = %self.responder,
+					"failed to refresh OCSP staple: {e:#}"
+				);
+				RETRY_INTERVAL
+			},
+		}
+	}
+
+	async fn fetch(&self, client: &client::Client) -> anyhow::Result<Vec<u8>> {
+		let mut req = ::http::Request::builder()
+			.method(::http::Method::POST)
+			.uri(&self.responder)
+			.header(::http::header::CONTENT_TYPE, "application/ocsp-request")
+			.body(crate::http::Body::from(encode_request(&self.cert_id)))?;
+		req
+			.extensions_mut()
+			.insert(BackendRequestTimeout(FETCH_TIMEOUT));
+		let resp = client
+			.simple_call(req)
+			.await
+			.context("fetch OCSP response")?;
+		let status = resp.status();
+		if !status.is_success() {
+			bail!("OCSP responder returned {status}");
+		}
+		let body = crate::http::read_body_with_limit(resp.into_body(), RESPONSE_LIMIT)
+			.await
+			.context("read OCSP response")?;
+		Ok(body.to_vec())
+	}
+
+	/// Staples `response` to the certificate, if it is a good, correctly signed response for it.
+	pub fn set_staple(&self, response: Vec<u8>) -> anyhow::Result<()> {
+		let validity = verify_response(&response, &self.cert_id, &self.issuer)?;
+		let now = SystemTime::now();
+		if validity.this_update > now + MAX_CLOCK_SKEW {
+			bail!("OCSP response is not yet valid");
+		}
+		let next_update = validity.next_update;
+		if next_update.is_some_and(|next| next <= now) {
+			bail!("OCSP response has already expired");
+		}
+		let refresh_at = match next_update {
+			Some(next) => {
+				let window = next
+					.duration_since(validity.this_update)
+					.unwrap_or_default();
+				validity.this_update + window / 2
+			},
+			None => now + DEFAULT_REFRESH_INTERVAL,
+		};
+		let mut key = CertifiedKey::clone(&self.key);
+		key.ocsp = Some(response);
+		self.staple.store(Some(Arc::new(Staple {
+			key: Arc::new(key),
+			next_update,
+			refresh_at,
+		})));
+		Ok(())
+	}
+}
+
+impl StaplingResolver {
+	/// Returns the certificate to serve, stapled unless the latest response has expired.
+	fn current(&self) -> Arc<CertifiedKey> {
+		match self.staple.load().as_ref() {
+			Some(staple) if !staple.is_expired(SystemTime::now()) => staple.key.clone(),
+			_ => self.key.clone(),
+		}
+	}
+}
+
+impl ResolvesServerCert for StaplingResolver {
+	fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+		Some(self.current())
+	}
+}
+
+fn ocsp_responder(cert: &X509Certificate<'_>) -> Option<String> {
+	use x509_parser::extensions::{GeneralName, ParsedExtension};
+
+	cert.extensions().iter().find_map(|ext| {
+		let ParsedExtension::AuthorityInfoAccess(aia) = ext.parsed_extension() else {
+			return None;
+		};
+		aia.accessdescs.iter().find_map(|desc| {
+			if desc.access_method.to_id_string() != OCSP_ACCESS_METHOD {
+				return None;
+			}
+			match desc.access_location {
+				GeneralName::URI(uri) => Some(uri.to_string()),
+				_ => None,
+			}
+		})
+	})
+}
+
+/// Encodes an OCSP request for a single certificate.
+fn encode_request(cert_id: &CertId) -> Vec<u8> {
+	yasna::construct_der(|w| {
+		// OCSPRequest
+		w.write_sequence(|w| {
+			// TBSRequest
+			w.next().write_sequence(|w| {
+				// requestList
+				w.next().write_sequence(|w| {
+					// Request
+					w.next().write_sequence(|w| {
+						w.next().write_sequence(|w| {
+							w.next().write_sequence(|w| {
+								w.next().write_oid(&ObjectIdentifier::from_slice(SHA1));
+								w.next().write_null();
+							});
+							w.next().write_bytes(&cert_id.issuer_name_hash);
+							w.next().write_bytes(&cert_id.issuer_key_hash);
+							// The serial is written as-is, keeping the certificate's own encoding.
+							w.next()
+								.write_tagged_implicit(yasna::tags::TAG_INTEGER, |w| {
+									w.write_bytes(&cert_id.serial)
+								});
+						});
+					});
+				});
+			});
+		});
+	})
+}
+
+#[derive(Debug)]
+struct Validity {
+	this_update: SystemTime,
+	next_update: Option<SystemTime>,
+}
+
+/// The signed part of a BasicOCSPResponse.
+struct BasicResponse {
+	tbs_response_data: Vec<u8>,
+	signature_algorithm: Vec<u8>,
+	signature: Vec<u8>,
+	certs: Vec<Vec<u8>>,
+}
+
+struct SingleResponse {
+	cert_id: Option<CertId>,
+	good: bool,
+	this_update: String,
+	next_update: Option<String>,
+}
+
+/// Verifies an OCSP response for the certificate identified by `cert_id`, returning the validity of
+/// the `good` status it carries. Responses that are unsuccessful, not signed by `issuer` or a
+/// responder it authorized, or that do not report the certificate as good, are rejected.
+fn verify_response(
+	response: &[u8],
+	cert_id: &CertId,
+	issuer: &CertificateDer<'_>,
+) -> anyhow::Result<Validity> {
+	let (status, bytes) = yasna::parse_der(response, |r| {
+		r.read_sequence(|r| {
+			let status = r.next().read_enum()?;
+			let bytes = r.read_optional(|r| {
+				r.read_tagged(Tag::context(0), |r| {
+					r.read_sequence(|r| Ok((r.next().read_oid()?, r.next().read_bytes()?)))
+				})
+			})?;
+			Ok((status, bytes))
+		})
+	})
+	.context("invalid OCSP response")?;
+	if status != 0 {
+		bail!("OCSP responder returned status {status}");
+	}
+	let Some((response_type, basic)) = bytes else {
+		bail!("OCSP response has no response bytes");
+	};
+	if response_type.components().as_slice() != OCSP_BASIC_RESPONSE {
+		bail!("unsupported OCSP response type");
+	}
+	let basic = parse_basic_response(&basic).context("invalid OCSP response")?;
+
+	let (_, issuer) = x509_parser::parse_x509_certificate(issuer)
+		.map_err(|e| anyhow!("failed to parse issuer certificate: {e}"))?;
+	let signer = responder_key(&basic, &issuer);
+	verify_signature(
+		&signer,
+		&basic.signature_algorithm,
+		&basic.signature,
+		&basic.tbs_response_data,
+	)?;
+
+	let responses =
+		parse_single_responses(&basic.tbs_response_data).context("invalid OCSP response")?;
+	let Some(single) = responses
+		.into_iter()
+		.find(|r| r.cert_id.as_ref() == Some(cert_id))
+	else {
+		bail!("OCSP response does not cover the certificate");
+	};
+	if !single.good {
+		bail!("OCSP responder did not report the certificate as good");
+	}
+	Ok(Validity {
+		this_update: parse_generalized_time(&single.this_update)?,
+		next_update: single
+			.next_update
+			.as_deref()
+			.map(parse_generalized_time)
+			.transpose()?,
+	})
+}
+
+/// Returns the SubjectPublicKeyInfo the response must be signed with: the issuer's, unless the
+/// response carries a certificate the issuer signed for OCSP signing.
+fn responder_key(basic: &BasicResponse, issuer: &X509Certificate<'_>) -> Vec<u8> {
+	basic
+		.certs
+		.iter()
+		.filter_map(|der| x509_parser::parse_x509_certificate(der).ok())
+		.find(|(_, cert)| {
+			let delegated = cert
+				.extended_key_usage()
+				.ok()
+				.flatten()
+				.is_some_and(|eku| eku.value.ocsp_signing);
+			delegated
+				&& cert.issuer().as_raw() == issuer.subject().as_raw()
+				&& cert.verify_signature(Some(issuer.public_key())).is_ok()
+		})
+		.map(|(_, cert)| cert.public_key().raw.to_vec())
+		.unwrap_or_else(|| issuer.public_key().raw.to_vec())
+}
+
+/// Verifies `signature` over `message` with the key in `spki`, using the signature algorithms of the
+/// gateway's crypto provider.
+fn verify_signature(
+	spki: &[u8],
+	signature_algorithm: &[u8],
+	signature: &[u8],
+	message: &[u8],
+) -> anyhow::Result<()> {
+	let (key_algorithm, key) = yasna::parse_der(spki, |r| {
+		r.read_sequence(|r| {
+			let algorithm = r.next().read_tagged_der()?.value().to_vec();
+			let (key, _) = r.next().read_bitvec_bytes()?;
+			Ok((algorithm, key))
+		})
+	})
+	.context("invalid OCSP responder public key")?;
+	let alg = super::tls::provider()
+		.signature_verification_algorithms
+		.all
+		.iter()
+		.find(|alg| {
+			alg.public_key_alg_id().as_ref() == key_algorithm.as_slice()
+				&& alg.signature_alg_id().as_ref() == signature_algorithm
+		})
+		.ok_or_else(|| anyhow!("unsupported OCSP response signature algorithm"))?;
+	alg
+		.verify_signature(&key, message, signature)
+		.map_err(|_| anyhow!("OCSP response signature is invalid"))
+}
+
+fn parse_basic_response(basic: &[u8]) -> ASN1Result<BasicResponse> {
+	yasna::parse_der(basic, |r| {
+		r.read_sequence(|r| {
+			let tbs_response_data = r.next().read_der()?;
+			let signature_algorithm = r.next().read_tagged_der()?.value().to_vec();
+			let (signature, _) = r.next().read_bitvec_bytes()?;
+			let certs = r
+				.read_optional(|r| {
+					r.read_tagged(Tag::context(0), |r| r.collect_sequence_of(|r| r.read_der()))
+				})?
+				.unwrap_or_default();
+			Ok(BasicResponse {
+				tbs_response_data,
+				signature_algorithm,
+				signature,
+				certs,
+			})
+		})
+	})
+}
+
+fn parse_single_responses(tbs_response_data: &[u8]) -> ASN1Result<Vec<SingleResponse>> {
+	yasna::parse_der(tbs_response_data, |r| {
+		r.read_sequence(|r| {
+			// version
+			r.read_optional(|r| r.read_tagged(Tag::context(0), |r| r.read_der()))?;
+			// responderID
+			r.next().read_tagged_der()?;
+			// producedAt
+			r.next().read_tagged_der()?;
+			let responses = r.next().collect_sequence_of(|r| {
+				r.read_sequence(|r| {
+					let cert_id = r.next().read_sequence(|r| {
+						let sha1 = r.next().read_sequence(|r| {
+							let oid = r.next().read_oid()?;
+							r.read_optional(|r| r.read_null())?;
+							Ok(oid.components().as_slice() == SHA1)
+						})?;
+						let cert_id = CertId {
+							issuer_name_hash: r.next().read_bytes()?,
+							issuer_key_hash: r.next().read_bytes()?,
+							serial: r.next().read_tagged_der()?.value().to_vec(),
+						};
+						// Only SHA-1 is requested, so other hash algorithms cannot match.
+						Ok(sha1.then_some(cert_id))
+					})?;
+					let status = r.next().read_tagged_der()?;
+					let this_update = read_generalized_time(r.next())?;
+					let next_update =
+						r.read_optional(|r| r.read_tagged(Tag::context(0), read_generalized_time))?;
+					// singleExtensions
+					r.read_optional(|r| r.read_tagged(Tag::context(1), |r| r.read_der()))?;
+					Ok(SingleResponse {
+						cert_id,
+						good: status.tag() == Tag::context(0),
+						this_update,
+						next_update,
+					})
+				})
+			})?;
+			// responseExtensions
+			r.read_optional(|r| r.read_tagged(Tag::context(1), |r| r.read_der()))?;
+			Ok(responses)
+		})
+	})
+}
+
+fn read_generalized_time(r: yasna::BERReader<'_, '_>) -> ASN1Result<String> {
+	let bytes = r.read_tagged_implicit(yasna::tags::TAG_GENERALIZEDTIME, |r| r.read_bytes())?;
+	String::from_utf8(bytes).map_err(|_| ASN1Error::new(ASN1ErrorKind::Invalid))
+}
+
+/// Parses a DER GeneralizedTime, `YYYYMMDDHHMMSS[.fff]Z`.
+fn parse_generalized_time(value: &str) -> anyhow::Result<SystemTime> {
+	let seconds = value
+		.strip_suffix('Z')
+		.and_then(|v| v.split('.').next())
+		.ok_or_else(|| anyhow!("invalid time {value}"))?;
+	let time = chrono::NaiveDateTime::parse_from_str(seconds, "%Y%m%d%H%M%S")
+		.with_context(|| format!("invalid time {value}"))?;
+	Ok(time.and_utc().into())
+}
@@ -0,0 +1,497 @@
+use std::sync::Mutex;
+
+use rustls::DigitallySignedStruct;
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{PrivatePkcs8KeyDer, ServerName, UnixTime};
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::*;
+
+const SERIAL: &[u8] = &[0x01, 0x23, 0x45];
+const HOUR: Duration = Duration::from_secs(60 * 60);
+/// ecdsa-with-SHA256
+const ECDSA_SHA256: &[u64] = &[1, 2, 840, 10045, 4, 3, 2];
+
+struct TestPki {
+	root: CertificateDer<'static>,
+	chain: Vec<CertificateDer<'static>>,
+	key: PrivateKeyDer<'static>,
+	issuer: rcgen::Issuer<'static, rcgen::KeyPair>,
+	/// PKCS#8 encoding of the CA key, which signs OCSP responses.
+	ca_key: Vec<u8>,
+}
+
+fn test_pki(responder: Option<&str>) -> TestPki {
+	let ca_key = rcgen::KeyPair::generate().expect("generate CA key");
+	let ca_key_der = ca_key.serialize_der();
+	let mut ca_params = rcgen::CertificateParams::default();
+	ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+	let ca_cert = ca_params.self_signed(&ca_key).expect("generate CA cert");
+	let issuer = rcgen::Issuer::from_ca_cert_pem(&ca_cert.pem(), ca_key).expect("parse CA");
+
+	let mut params =
+		rcgen::CertificateParams::new(vec!["localhost".to_string()]).expect("cert params");
+	params.serial_number = Some(rcgen::SerialNumber::from_slice(SERIAL));
+	if let Some(responder) = responder {
+		// AuthorityInfoAccess with a single OCSP access description.
+		let aia = yasna::construct_der(|w| {
+			w.write_sequence(|w| {
+				w.next().write_sequence(|w| {
+					w.next()
+						.write_oid(&ObjectIdentifier::from_slice(&[1, 3, 6, 1, 5, 5, 7, 48, 1]));
+					w.next()
+						.write_tagged_implicit(Tag::context(6), |w| w.write_ia5_string(responder));
+				});
+			});
+		});
+		params
+			.custom_extensions
+			.push(rcgen::CustomExtension::from_oid_content(
+				&[1, 3, 6, 1, 5, 5, 7, 1, 1],
+				aia,
+			));
+	}
+	let key = rcgen::KeyPair::generate().expect("generate key");
+	let cert = params.signed_by(&key, &issuer).expect("generate cert");
+	TestPki {
+		root: ca_cert.der().clone(),
+		chain: vec![cert.der().clone(), ca_cert.der().clone()],
+		key: PrivatePkcs8KeyDer::from(key.serialize_der()).into(),
+		issuer,
+		ca_key: ca_key_der,
+	}
+}
+
+fn resolver(pki: &TestPki, responder: &str) -> StaplingResolver {
+	let signing_key = super::super::tls::provider()
+		.key_provider
+		.load_private_key(pki.key.clone_key())
+		.expect("load key");
+	StaplingResolver::with_responder(
+		CertifiedKey::new(pki.chain.clone(), signing_key),
+		responder.to_string(),
+	)
+	.expect("build resolver")
+}
+
+fn test_client() -> client::Client {
+	client::Client::new(
+		&client::Config {
+			resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
+			resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+			negative_ttl: client::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
+		},
+		None,
+		Default::default(),
+		None,
+	)
+}
+
+fn write_time(w: yasna::DERWriter<'_>, time: SystemTime) {
+	let time = chrono::DateTime::<chrono::Utc>::from(time)
+		.format("%Y%m%d%H%M%SZ")
+		.to_string();
+	w.write_tagged_implicit(yasna::tags::TAG_GENERALIZEDTIME, |w| {
+		w.write_bytes(time.as_bytes())
+	});
+}
+
+/// The parts of a test OCSP response that tests vary.
+struct Response<'a> {
+	cert_id: CertId,
+	this_update: SystemTime,
+	next_update: Option<SystemTime>,
+	/// PKCS#8 encoding of the P-256 key the response is signed with.
+	signer: &'a [u8],
+	certs: Vec<CertificateDer<'static>>,
+}
+
+impl<'a> Response<'a> {
+	/// A response reporting the test certificate as good, signed by the CA.
+	fn new(pki: &'a TestPki, this_update: SystemTime, next_update: Option<SystemTime>) -> Self {
+		let (_, leaf) = x509_parser::parse_x509_certificate(&pki.chain[0]).unwrap();
+		let (_, issuer) = x509_parser::parse_x509_certificate(&pki.chain[1]).unwrap();
+		Self {
+			cert_id: CertId::new(&leaf, &issuer),
+			this_update,
+			next_update,
+			signer: &pki.ca_key,
+			certs: vec![],
+		}
+	}
+
+	fn encode(&self) -> Vec<u8> {
+		let tbs = yasna::construct_der(|w| {
+			w.write_sequence(|w| {
+				// responderID, byKey
+				w.next()
+					.write_tagged(Tag::context(2), |w| w.write_bytes(&[0; 20]));
+				write_time(w.next(), self.this_update);
+				w.next().write_sequence(|w| {
+					w.next().write_sequence(|w| {
+						w.next().write_sequence(|w| {
+							w.next().write_sequence(|w| {
+								w.next().write_oid(&ObjectIdentifier::from_slice(SHA1));
+								w.next().write_null();
+							});
+							w.next().write_bytes(&self.cert_id.issuer_name_hash);
+							w.next().write_bytes(&self.cert_id.issuer_key_hash);
+							w.next()
+								.write_tagged_implicit(yasna::tags::TAG_INTEGER, |w| {
+									w.write_bytes(&self.cert_id.serial)
+								});
+						});
+						// good
+						w.next()
+							.write_tagged_implicit(Tag::context(0), |w| w.write_null());
+						write_time(w.next(), self.this_update);
+						if let Some(next_update) = self.next_update {
+							w.next()
+								.write_tagged(Tag::context(0), |w| write_time(w, next_update));
+						}
+					});
+				});
+			});
+		});
+		let signer = aws_lc_rs::signature::EcdsaKeyPair::from_pkcs8(
+			&aws_lc_rs::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+			self.signer,
+		)
+		.expect("load signing key");
+		let signature = signer
+			.sign(&aws_lc_rs::rand::SystemRandom::new(), &tbs)
+			.expect("sign response");
+		let basic = yasna::construct_der(|w| {
+			w.write_sequence(|w| {
+				w.next().write_der(&tbs);
+				w.next().write_sequence(|w| {
+					w.next()
+						.write_oid(&ObjectIdentifier::from_slice(ECDSA_SHA256));
+				});
+				w.next()
+					.write_bitvec_bytes(signature.as_ref(), signature.as_ref().len() * 8);
+				if !self.certs.is_empty() {
+					w.next().write_tagged(Tag::context(0), |w| {
+						w.write_sequence(|w| {
+							for cert in &self.certs {
+								w.next().write_der(cert);
+							}
+						})
+					});
+				}
+			});
+		});
+		yasna::construct_der(|w| {
+			w.write_sequence(|w| {
+				w.next().write_enum(0);
+				w.next().write_tagged(Tag::context(0), |w| {
+					w.write_sequence(|w| {
+						w.next()
+							.write_oid(&ObjectIdentifier::from_slice(OCSP_BASIC_RESPONSE));
+						w.next().write_bytes(&basic);
+					})
+				});
+			});
+		})
+	}
+}
+
+/// Builds a successful OCSP response reporting the test certificate as good, signed by the CA.
+fn ocsp_response(
+	pki: &TestPki,
+	this_update: SystemTime,
+	next_update: Option<SystemTime>,
+) -> Vec<u8> {
+	Response::new(pki, this_update, next_update).encode()
+}
+
+fn stapled(resolver: &StaplingResolver) -> Option<Vec<u8>> {
+	resolver.current().ocsp.clone()
+}
+
+/// Records the OCSP response the server staples, and otherwise verifies as usual.
+#[derive(Debug)]
+struct RecordOcsp {
+	inner: Arc<WebPkiServerVerifier>,
+	ocsp: Arc<Mutex<Option<Vec<u8>>>>,
+}
+
+impl ServerCertVerifier for RecordOcsp {
+	fn verify_server_cert(
+		&self,
+		end_entity: &CertificateDer<'_>,
+		intermediates: &[CertificateDer<'_>],
+		server_name: &ServerName<'_>,
+		ocsp_response: &[u8],
+		now: UnixTime,
+	) -> Result<ServerCertVerified, rustls::Error> {
+		*self.ocsp.lock().unwrap() = Some(ocsp_response.to_vec());
+		self
+			.inner
+			.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+	}
+
+	fn verify_tls12_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, rustls::Error> {
+		self.inner.verify_tls12_signature(message, cert, dss)
+	}
+
+	fn verify_tls13_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, rustls::Error> {
+		self.inner.verify_tls13_signature(message, cert, dss)
+	}
+
+	fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+		self.inner.supported_verify_schemes()
+	}
+}
+
+#[test]
+fn new_reads_responder_from_certificate() {
+	let pki = test_pki(Some("http://ocsp.example.com"));
+	let resolver = StaplingResolver::new(pki.chain, pki.key).expect("build resolver");
+	assert_eq!(resolver.responder, "http://ocsp.example.com");
+	assert_eq!(resolver.cert_id.serial, SERIAL);
+}
+
+#[test]
+fn new_requires_responder_and_issuer() {
+	let pki = test_pki(None);
+	let err = StaplingResolver::new(pki.chain, pki.key).unwrap_err();
+	assert!(err.to_string().contains("OCSP responder"), "{err}");
+
+	let pki = test_pki(Some("http://ocsp.example.com"));
+	let err = StaplingResolver::new(pki.chain[..1].to_vec(), pki.key).unwrap_err();
+	assert!(err.to_string().contains("issuer"), "{err}");
+}
+
+#[tokio::test]
+async fn staple_is_attached_to_handshake() {
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, "http://ocsp.invalid");
+	let now = SystemTime::now();
+	let response = ocsp_response(&pki, now - HOUR, Some(now + HOUR));
+	resolver.set_staple(response.clone()).expect("set staple");
+
+	let server = rustls::ServerConfig::builder_with_provider(super::super::tls::provider())
+		.with_safe_default_protocol_versions()
+		.expect("server config")
+		.with_no_client_auth()
+		.with_cert_resolver(Arc::new(resolver));
+	let mut roots = rustls::RootCertStore::empty();
+	roots.add(pki.root.clone()).expect("add root");
+	let ocsp = Arc::new(Mutex::new(None));
+	let verifier = RecordOcsp {
+		inner: WebPkiServerVerifier::builder_with_provider(
+			Arc::new(roots),
+			super::super::tls::provider(),
+		)
+		.build()
+		.expect("verifier"),
+		ocsp: ocsp.clone(),
+	};
+	let client = rustls::ClientConfig::builder_with_provider(super::super::tls::provider())
+		.with_safe_default_protocol_versions()
+		.expect("client config")
+		.dangerous()
+		.with_custom_certificate_verifier(Arc::new(verifier))
+		.with_no_client_auth();
+
+	let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+	let server_name = ServerName::try_from("localhost").expect("server name");
+	let (accepted, connected) = tokio::join!(
+		tokio_rustls::TlsAcceptor::from(Arc::new(server)).accept(server_io),
+		tokio_rustls::TlsConnector::from(Arc::new(client)).connect(server_name, client_io),
+	);
+	accepted.expect("server handshake");
+	connected.expect("client handshake");
+	assert_eq!(ocsp.lock().unwrap().as_deref(), Some(response.as_slice()));
+}
+
+#[tokio::test]
+async fn stale_staple_is_refreshed() {
+	let pki = test_pki(None);
+	let now = SystemTime::now();
+	let fresh = ocsp_response(&pki, now, Some(now + 2 * HOUR));
+	let server = MockServer::start().await;
+	Mock::given(method("POST"))
+		.and(header("content-type", "application/ocsp-request"))
+		.respond_with(ResponseTemplate::new(200).set_body_bytes(fresh.clone()))
+		.expect(1)
+		.mount(&server)
+		.await;
+
+	let client = test_client();
+	let resolver = resolver(&pki, &server.uri());
+	// Still valid, but past the midpoint of its validity window.
+	let stale = ocsp_response(&pki, now - 2 * HOUR, Some(now + HOUR));
+	resolver.set_staple(stale).expect("set staple");
+
+	let delay = resolver.refresh(&client).await;
+	assert_eq!(stapled(&resolver), Some(fresh));
+	// The next refresh is due halfway to the new nextUpdate.
+	assert!(delay > HOUR / 2 && delay <= HOUR, "{delay:?}");
+}
+
+#[tokio::test]
+async fn fresh_staple_is_not_refetched() {
+	let server = MockServer::start().await;
+	Mock::given(method("POST"))
+		.respond_with(ResponseTemplate::new(200))
+		.expect(0)
+		.mount(&server)
+		.await;
+
+	let client = test_client();
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, &server.uri());
+	let now = SystemTime::now();
+	let response = ocsp_response(&pki, now, Some(now + 2 * HOUR));
+	resolver.set_staple(response.clone()).expect("set staple");
+
+	let delay = resolver.refresh(&client).await;
+	assert_eq!(stapled(&resolver), Some(response));
+	assert!(delay > Duration::ZERO && delay <= HOUR, "{delay:?}");
+}
+
+#[tokio::test]
+async fn responder_failure_keeps_staple_until_expiry() {
+	let server = MockServer::start().await;
+	Mock::given(method("POST"))
+		.respond_with(ResponseTemplate::new(500))
+		.mount(&server)
+		.await;
+
+	let client = test_client();
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, &server.uri());
+	assert_eq!(resolver.refresh(&client).await, RETRY_INTERVAL);
+	assert_eq!(stapled(&resolver), None);
+
+	// A stale but unexpired staple is still served.
+	let now = SystemTime::now();
+	let stale = ocsp_response(&pki, now - 2 * HOUR, Some(now + HOUR));
+	resolver.set_staple(stale.clone()).expect("set staple");
+	assert_eq!(resolver.refresh(&client).await, RETRY_INTERVAL);
+	assert_eq!(stapled(&resolver), Some(stale));
+
+	// Once it expires, the certificate is served without a staple.
+	let expired = resolver.staple.load_full().expect("staple");
+	resolver.staple.store(Some(Arc::new(Staple {
+		key: expired.key.clone(),
+		next_update: Some(now - HOUR),
+		refresh_at: now - 2 * HOUR,
+	})));
+	assert_eq!(resolver.refresh(&client).await, RETRY_INTERVAL);
+	assert_eq!(stapled(&resolver), None);
+}
+
+#[test]
+fn rejects_unusable_responses() {
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, "http://ocsp.invalid");
+	let now = SystemTime::now();
+
+	let expired = ocsp_response(&pki, now - 2 * HOUR, Some(now - HOUR));
+	assert!(resolver.set_staple(expired).is_err());
+	// tryLater, with no response bytes.
+	let unsuccessful = yasna::construct_der(|w| w.write_sequence(|w| w.next().write_enum(3)));
+	assert!(resolver.set_staple(unsuccessful).is_err());
+	assert!(resolver.set_staple(b"garbage".to_vec()).is_err());
+	assert_eq!(stapled(&resolver), None);
+}
+
+#[test]
+fn rejects_responses_not_signed_by_issuer() {
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, "http://ocsp.invalid");
+	let now = SystemTime::now();
+	let other_key = rcgen::KeyPair::generate().expect("generate key");
+	let other = other_key.serialize_der();
+
+	let mut response = Response::new(&pki, now, Some(now + HOUR));
+	response.signer = &other;
+	let err = resolver.set_staple(response.encode()).unwrap_err();
+	assert!(err.to_string().contains("signature"), "{err}");
+
+	// Bundling an untrusted certificate for the signing key does not help.
+	let mut params = rcgen::CertificateParams::default();
+	params.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::OcspSigning];
+	response.certs = vec![params.self_signed(&other_key).expect("cert").der().clone()];
+	assert!(resolver.set_staple(response.encode()).is_err());
+	assert_eq!(stapled(&resolver), None);
+}
+
+#[test]
+fn accepts_responses_from_delegated_responder() {
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, "http://ocsp.invalid");
+	let now = SystemTime::now();
+
+	let key = rcgen::KeyPair::generate().expect("generate key");
+	let key_der = key.serialize_der();
+	let mut params = rcgen::CertificateParams::default();
+	params.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::OcspSigning];
+	let delegate = params.signed_by(&key, &pki.issuer).expect("delegate cert");
+
+	let mut response = Response::new(&pki, now, Some(now + HOUR));
+	response.signer = &key_der;
+	response.certs = vec![delegate.der().clone()];
+	let encoded = response.encode();
+	resolver.set_staple(encoded.clone()).expect("set staple");
+	assert_eq!(stapled(&resolver), Some(encoded));
+}
+
+#[test]
+fn rejects_responses_for_other_certificates() {
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, "http://ocsp.invalid");
+	let now = SystemTime::now();
+
+	let mut other_serial = Response::new(&pki, now, Some(now + HOUR));
+	other_serial.cert_id.serial = vec![0x42];
+	let mut other_issuer = Response::new(&pki, now, Some(now + HOUR));
+	other_issuer.cert_id.issuer_key_hash = vec![0; 20];
+	for response in [other_serial, other_issuer] {
+		let err = resolver.set_staple(response.encode()).unwrap_err();
+		assert!(err.to_string().contains("does not cover"), "{err}");
+	}
+	assert_eq!(stapled(&resolver), None);
+}
+
+#[test]
+fn request_identifies_certificate() {
+	let pki = test_pki(None);
+	let resolver = resolver(&pki, "http://ocsp.invalid");
+	let request = encode_request(&resolver.cert_id);
+	let serial = yasna::parse_der(&request, |r| {
+		r.read_sequence(|r| {
+			r.next().read_sequence(|r| {
+				r.next().read_sequence(|r| {
+					r.next().read_sequence(|r| {
+						r.next().read_sequence(|r| {
+							r.next().read_der()?;
+							assert_eq!(r.next().read_bytes()?, resolver.cert_id.issuer_name_hash);
+							assert_eq!(r.next().read_bytes()?, resolver.cert_id.issuer_key_hash);
+							r.next().read_tagged_der().map(|v| v.value().to_vec())
+						})
+					})
+				})
+			})
+		})
+	})
+	.expect("parse request");
+	assert_eq!(serial, SERIAL);
+}
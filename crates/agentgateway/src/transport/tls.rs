@@ -944,8 +944,10 @@ pub mod trustdomain {
 
 pub mod identity {
 	use std::fmt::Debug;
-	use std::sync::Arc;
+	use std::sync::{Arc, Mutex};
+	use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+	use arc_swap::ArcSwap;
 	use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
 	use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
 	use rustls::server::ParsedCertificate;
@@ -956,9 +958,88 @@ pub mod identity {
 	use crate::types::discovery::Identity;
 	use crate::*;
 
+	/// Trust anchors used to validate mesh peers.
+	///
+	/// The bundle is shared by the workload certificate and the verifiers built from it. Roots
+	/// distributed by the CA are merged in rather than replacing the current ones, so peers issued by
+	/// either the old or the new root validate while a rotation is in progress; anchors are dropped
+	/// once they pass their notAfter. Verifiers load the current roots on each handshake, so changes
+	/// apply to new connections while established connections are left alone.
+	#[derive(Debug, Default)]
+	pub struct TrustBundle {
+		anchors: Mutex<Vec<TrustAnchor>>,
+		roots: ArcSwap<rustls::RootCertStore>,
+	}
+
+	#[derive(Debug)]
+	struct TrustAnchor {
+		der: CertificateDer<'static>,
+		not_after: SystemTime,
+	}
+
+	impl TrustBundle {
+		pub fn roots(&self) -> Arc<rustls::RootCertStore> {
+			self.roots.load_full()
+		}
+
+		/// Adds trust anchors to the bundle, keeping the existing ones. Returns the number of anchors
+		/// added; certificates that cannot be parsed, are already present, or have expired are skipped.
+		pub fn add(&self, certs: &[CertificateDer<'static>]) -> usize {
+			self.merge(certs, SystemTime::now())
+		}
+
+		/// Drops anchors that have passed their notAfter.
+		pub fn remove_expired(&self) {
+			self.merge(&[], SystemTime::now());
+		}
+
+		fn merge(&self, certs: &[CertificateDer<'static>], now: SystemTime) -> usize {
+			let mut anchors = self.anchors.lock().expect("trust bundle lock poisoned");
+			let before = anchors.len();
+			anchors.retain(|a| a.not_after > now);
+			let mut added = 0;
+			for der in certs {
+				if anchors.iter().any(|a| a.der == *der) {
+					continue;
+				}
+				let not_after = match x509_parser::parse_x509_certificate(der) {
+					Ok((_, cert)) => {
+						UNIX_EPOCH
+							+ Duration::from_secs(
+								cert
+									.validity()
+									.not_after
+									.timestamp()
+									.try_into()
+									.unwrap_or_default(),
+							)
+					},
+					Err(e) => {
+						debug!("skipping unparsable trust anchor: {e}");
+						continue;
+					},
+				};
+				if not_after <= now {
+					continue;
+				}
+				anchors.push(TrustAnchor {
+					der: der.clone(),
+					not_after,
+				});
+				added += 1;
+			}
+			if added > 0 || anchors.len() != before {
+				let mut roots = rustls::RootCertStore::empty();
+				roots.add_parsable_certificates(anchors.iter().map(|a| a.der.clone()));
+				self.roots.store(Arc::new(roots));
+			}
+			added
+		}
+	}
+
 	#[derive(Debug)]
 	pub struct IdentityVerifier {
-		pub roots: Arc<rustls::RootCertStore>,
+		pub trust_bundle: Arc<TrustBundle>,
 		pub identity: Vec<Identity>,
 	}
 
@@ -1011,7 +1092,7 @@ pub mod identity {
 			let algs = provider().signature_verification_algorithms;
 			rustls::client::verify_server_cert_signed_by_trust_anchor(
 				&cert,
-				&self.roots,
+				&self.trust_bundle.roots(),
 				intermediates,
 				now,
 				algs.all,
@@ -1062,6 +1143,97 @@ pub mod identity {
 				.supported_schemes()
 		}
 	}
+
+	#[cfg(test)]
+	mod tests {
+		use std::str::FromStr;
+
+		use rcgen::{
+			BasicConstraints, CertificateParams, ExtendedKeyUsagePurpose, IsCa, Issuer, KeyPair, SanType,
+		};
+
+		use super::*;
+
+		const SPIFFE_ID: &str = "spiffe://cluster.local/ns/default/sa/test";
+
+		fn make_ca() -> (CertificateDer<'static>, Issuer<'static, KeyPair>) {
+			make_ca_until(None)
+		}
+
+		fn make_ca_until(
+			not_after: Option<SystemTime>,
+		) -> (CertificateDer<'static>, Issuer<'static, KeyPair>) {
+			let key = KeyPair::generate().unwrap();
+			let mut params = CertificateParams::default();
+			params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+			if let Some(not_after) = not_after {
+				params.not_after = not_after.into();
+			}
+			let cert = params.self_signed(&key).unwrap();
+			let issuer = Issuer::from_ca_cert_pem(&cert.pem(), key).unwrap();
+			(cert.der().clone(), issuer)
+		}
+
+		fn make_peer_cert(issuer: &Issuer<'static, KeyPair>) -> CertificateDer<'static> {
+			let key = KeyPair::generate().unwrap();
+			let mut params = CertificateParams::default();
+			params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+			params.subject_alt_names = vec![SanType::URI(SPIFFE_ID.try_into().unwrap())];
+			params.signed_by(&key, issuer).unwrap().der().clone()
+		}
+
+		fn verify(verifier: &IdentityVerifier, cert: &CertificateDer<'_>) -> bool {
+			let sn = ServerName::try_from("example.com").unwrap();
+			verifier
+				.verify_server_cert(cert, &[], &sn, &[], UnixTime::now())
+				.is_ok()
+		}
+
+		#[test]
+		fn added_trust_anchor_validates_existing_verifier() {
+			let (old_root, _) = make_ca();
+			let (new_root, new_issuer) = make_ca();
+			let peer = make_peer_cert(&new_issuer);
+
+			let trust_bundle = Arc::new(TrustBundle::default());
+			assert_eq!(trust_bundle.add(std::slice::from_ref(&old_root)), 1);
+			let verifier = IdentityVerifier {
+				trust_bundle: trust_bundle.clone(),
+				identity: vec![Identity::from_str(SPIFFE_ID).unwrap()],
+			};
+			assert!(!verify(&verifier, &peer));
+
+			assert_eq!(trust_bundle.add(std::slice::from_ref(&new_root)), 1);
+			assert!(verify(&verifier, &peer));
+			assert_eq!(trust_bundle.roots().len(), 2);
+		}
+
+		#[test]
+		fn rotation_keeps_old_anchors_until_they_expire() {
+			let old_expiry = UNIX_EPOCH + Duration::from_secs(1_924_992_000); // 2031-01-01
+			let (old_root, old_issuer) = make_ca_until(Some(old_expiry));
+			let (new_root, new_issuer) = make_ca();
+			let old_peer = make_peer_cert(&old_issuer);
+			let new_peer = make_peer_cert(&new_issuer);
+
+			let trust_bundle = Arc::new(TrustBundle::default());
+			trust_bundle.add(std::slice::from_ref(&old_root));
+			let verifier = IdentityVerifier {
+				trust_bundle: trust_bundle.clone(),
+				identity: vec![Identity::from_str(SPIFFE_ID).unwrap()],
+			};
+
+			// The CA now distributes both roots; re-adding the old one is a no-op.
+			assert_eq!(trust_bundle.add(&[old_root.clone(), new_root]), 1);
+			assert!(verify(&verifier, &old_peer));
+			assert!(verify(&verifier, &new_peer));
+
+			// Once the old root expires it is dropped from the bundle.
+			trust_bundle.merge(&[], old_expiry + Duration::from_secs(1));
+			assert_eq!(trust_bundle.roots().len(), 1);
+			assert!(verify(&verifier, &new_peer));
+		}
+	}
 }
 
 #[apply(schema!)]
@@ -27,8 +27,8 @@ use crate::http::authorization::RuleSet;
 use crate::http::backendtls::ResolvedBackendTLS;
 use crate::http::ext_proc::GrpcReferenceChannel;
 use crate::http::{
-	HeaderOrPseudo, HeaderValue, ext_authz, ext_proc, filters, health, remoteratelimit, retry,
-	timeout,
+	HeaderOrPseudo, HeaderValue, ext_authz, ext_proc, filters, health, redisratelimit,
+	remoteratelimit, retry, timeout,
 };
 use crate::mcp::{FailureMode, McpAuthorization};
 use crate::proxy::httpproxy::PolicyClient;
@@ -170,6 +170,8 @@ pub struct ServerTLSConfig {
 	/// Original strict verifier used when ALLOW_INSECURE_FALLBACK is enabled.
 	insecure_fallback_verifier: Option<Arc<dyn ClientCertVerifier>>,
 	per_profile_config: Arc<RwLock<HashMap<ServerTlsProfileKey, Arc<ServerConfig>>>>,
+	/// Serves the certificate with a stapled OCSP response, when enabled.
+	ocsp: Option<Arc<crate::transport::ocsp::StaplingResolver>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -182,7 +184,9 @@ enum ServerTlsCertificateSource {
 impl Eq for ServerTLSConfig {}
 impl PartialEq for ServerTLSConfig {
 	fn eq(&self, other: &Self) -> bool {
-		self.source == other.source && self.inputs == other.inputs
+		self.source == other.source
+			&& self.inputs == other.inputs
+			&& self.ocsp.is_some() == other.ocsp.is_some()
 	}
 }
 
@@ -203,6 +207,7 @@ impl ServerTLSConfig {
 			inputs: None,
 			insecure_fallback_verifier: None,
 			per_profile_config: Arc::new(Default::default()),
+			ocsp: None,
 		}
 	}
 
@@ -222,6 +227,7 @@ impl ServerTLSConfig {
 			None,
 			None,
 			false,
+			false,
 		)
 	}
 
@@ -236,6 +242,7 @@ impl ServerTLSConfig {
 		cipher_suites: Option<Vec<crate::transport::tls::CipherSuite>>,
 		key_exchange_groups: Option<Vec<crate::transport::tls::KeyExchangeGroup>>,
 		allow_insecure_mtls: bool,
+		ocsp_stapling: bool,
 	) -> anyhow::Result<Self> {
 		let inputs = Arc::new(ServerTlsInputs {
 			cert_pem,
@@ -247,6 +254,15 @@ impl ServerTLSConfig {
 			default_key_exchange_groups: key_exchange_groups.clone().unwrap_or_default(),
 			dynamic_ca_cert_cache: Default::default(),
 		});
+		let ocsp = if ocsp_stapling {
+			let resolver = Arc::new(crate::transport::ocsp::StaplingResolver::new(
+				parse_cert(&inputs.cert_pem)?,
+				parse_key(&inputs.key_pem)?,
+			)?);
+			Some(resolver)
+		} else {
+			None
+		};
 		let suites = cipher_suites.as_deref().filter(|s| !s.is_empty());
 		let groups = key_exchange_groups.as_deref().filter(|g| !g.is_empty());
 		let (base, insecure_fallback_verifier) = Self::build_server_config(
@@ -256,6 +272,7 @@ impl ServerTLSConfig {
 			max_version,
 			suites.unwrap_or(&[]),
 			groups.unwrap_or(&[]),
+			ocsp.as_ref(),
 		)?;
 		Ok(Self {
 			source: ServerTlsCertificateSource::Static,
@@ -263,6 +280,7 @@ impl ServerTLSConfig {
 			inputs: Some(inputs),
 			insecure_fallback_verifier,
 			per_profile_config: Arc::new(Default::default()),
+			ocsp,
 		})
 	}
 
@@ -306,6 +324,7 @@ impl ServerTLSConfig {
 			inputs: Some(inputs),
 			insecure_fallback_verifier: None,
 			per_profile_config: Arc::new(Default::default()),
+			ocsp: None,
 		})
 	}
 
@@ -317,6 +336,7 @@ impl ServerTLSConfig {
 			inputs: None,
 			insecure_fallback_verifier: None,
 			per_profile_config: Arc::new(Default::default()),
+			ocsp: None,
 		}
 	}
 
@@ -330,6 +350,7 @@ impl ServerTLSConfig {
 			inputs: None,
 			insecure_fallback_verifier: None,
 			per_profile_config: Arc::new(Default::default()),
+			ocsp: None,
 		}
 	}
 
@@ -401,6 +422,7 @@ impl ServerTLSConfig {
 					key.max_version,
 					&key.cipher_suites,
 					&key.key_exchange_groups,
+					self.ocsp.as_ref(),
 				)?;
 				base
 			},
@@ -424,6 +446,12 @@ impl ServerTLSConfig {
 		Ok(base)
 	}
 
+	pub fn start_ocsp_refresh(&self, client: &client::Client) {
+		if let Some(ocsp) = &self.ocsp {
+			ocsp.start_refresh(client);
+		}
+	}
+
 	pub fn allow_insecure_mtls(&self) -> bool {
 		if matches!(
 			self.source,
@@ -476,13 +504,14 @@ impl ServerTLSConfig {
 		max_version: Option<TLSVersion>,
 		cipher_suites: &[crate::transport::tls::CipherSuite],
 		key_exchange_groups: &[crate::transport::tls::KeyExchangeGroup],
+		ocsp: Option<&Arc<crate::transport::ocsp::StaplingResolver>>,
 	) -> anyhow::Result<(ServerConfig, Option<Arc<dyn ClientCertVerifier>>)> {
 		let provider = crate::transport::tls::provider_with_options(cipher_suites, key_exchange_groups);
 
 		let versions = tls_versions_for_range(min_version, max_version)?;
 		let scb = ServerConfig::builder_with_provider(provider.clone())
 			.with_protocol_versions(&versions)
-			.expect("server config must be valid");
+			.map_err(|e| anyhow!("cipher suites are not usable with the configured TLS versions: {e}"))?;
 
 		let mut insecure_fallback_verifier = None;
 
@@ -506,9 +535,14 @@ impl ServerTLSConfig {
 			scb.with_no_client_auth()
 		};
 
-		let cert_chain = parse_cert(&inputs.cert_pem)?;
-		let private_key = parse_key(&inputs.key_pem)?;
-		let mut sc = scb.with_single_cert(cert_chain, private_key)?;
+		let mut sc = match ocsp {
+			Some(resolver) => scb.with_cert_resolver(resolver.clone()),
+			None => {
+				let cert_chain = parse_cert(&inputs.cert_pem)?;
+				let private_key = parse_key(&inputs.key_pem)?;
+				scb.with_single_cert(cert_chain, private_key)?
+			},
+		};
 		sc.key_log = crate::transport::tls::key_log();
 		sc.alpn_protocols = alpns
 			.map(|a| a.to_vec())
@@ -635,6 +669,15 @@ impl ListenerProtocol {
 		}
 	}
 
+	/// Starts refreshing the stapled OCSP response, if enabled. The refresh stops once the listener
+	/// configuration is dropped.
+	pub fn start_ocsp_refresh(&self, client: &client::Client) {
+		match self {
+			ListenerProtocol::HTTPS(t) | ListenerProtocol::TLS(Some(t)) => t.start_ocsp_refresh(client),
+			_ => {},
+		}
+	}
+
 	pub fn allow_insecure_mtls(&self) -> bool {
 		match self {
 			ListenerProtocol::HTTPS(t) => t.allow_insecure_mtls(),
@@ -722,6 +765,11 @@ pub struct Route {
 	pub matches: Vec<RouteMatch>,
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub backends: Vec<RouteBackendReference>,
+	/// CEL expression computing a key used to pick among weighted backends. Requests with the same
+	/// key are sent to the same backend. When unset, or when the key evaluates to null, a backend is
+	/// picked at random.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub backend_hash_key: Option<Arc<cel::Expression>>,
 	#[serde(default, skip_serializing_if = "Option::is_none", skip_deserializing)]
 	#[cfg_attr(feature = "schema", schemars(with = "serde_json::Value"))]
 	pub llm_router: Option<Arc<llm::model_router::ModelRouter>>,
@@ -1237,6 +1285,19 @@ pub struct BackendWithPolicies {
 
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub inline_policies: Vec<BackendTrafficPolicy>,
+
+	/// Operator-defined metadata, exposed to CEL as `backend.metadata`.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub metadata: Arc<HashMap<String, String>>,
+}
+
+impl BackendWithPolicies {
+	pub fn backend_info(&self) -> BackendInfo {
+		BackendInfo {
+			metadata: self.metadata.clone(),
+			..self.backend.backend_info()
+		}
+	}
 }
 
 impl From<SimpleBackendWithPolicies> for BackendWithPolicies {
@@ -1244,6 +1305,7 @@ impl From<SimpleBackendWithPolicies> for BackendWithPolicies {
 		Self {
 			backend: Backend::from(backend.backend),
 			inline_policies: backend.inline_policies,
+			metadata: Default::default(),
 		}
 	}
 }
@@ -1275,6 +1337,7 @@ impl From<Backend> for BackendWithPolicies {
 		BackendWithPolicies {
 			backend: val,
 			inline_policies: vec![],
+			metadata: Default::default(),
 		}
 	}
 }
@@ -1579,6 +1642,7 @@ impl SimpleBackend {
 		BackendInfo {
 			backend_type: self.backend_type(),
 			backend_name: strng::format!("{}", self),
+			metadata: Default::default(),
 		}
 	}
 }
@@ -1673,6 +1737,7 @@ impl Backend {
 		BackendInfo {
 			backend_type: self.backend_type(),
 			backend_name: self.name(),
+			metadata: Default::default(),
 		}
 	}
 }
@@ -1681,6 +1746,8 @@ impl Backend {
 pub struct BackendInfo {
 	pub backend_type: cel::BackendType,
 	pub backend_name: Strng,
+	/// Operator-defined metadata, exposed to CEL as `backend.metadata`.
+	pub metadata: Arc<HashMap<String, String>>,
 }
 
 /// Controls how upstream tool/prompt names are exposed to clients.
@@ -2350,6 +2417,10 @@ pub struct TracingConfig {
 	#[serde(default, deserialize_with = "deserialize_sampling_expr_opt")]
 	#[cfg_attr(feature = "schema", schemars(with = "Option<crate::StringBoolFloat>"))]
 	pub client_sampling: Option<Arc<cel::Expression>>,
+	/// Built-in sampler, as an alternative to `randomSampling` and `clientSampling`. When set, it
+	/// takes precedence over both.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub sampler: Option<crate::telemetry::trc::Sampler>,
 	/// Optional CEL filter with KEEP semantics. When set, only requests for which the expression
 	/// evaluates to `true` have their trace span(s) exported; all other spans are dropped. When
 	/// unset, no filtering is applied (all sampled spans are exported). Composes after sampling
@@ -2364,6 +2435,10 @@ pub struct TracingConfig {
 	/// OTLP protocol used to export traces. Defaults to HTTP.
 	#[serde(default)]
 	pub protocol: TracingProtocol,
+	/// Baggage keys to propagate to backends. When set, all other W3C baggage entries are removed
+	/// from upstream requests; when unset, the baggage header is forwarded unchanged.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub baggage: Option<Vec<String>>,
 }
 
 fn default_otlp_path() -> String {
@@ -2647,6 +2722,7 @@ pub enum TrafficPolicy {
 	Authorization(Authorization),
 	LocalRateLimit(RequestPolicy<Vec<crate::http::localratelimit::RateLimit>>),
 	RemoteRateLimit(RequestPolicy<remoteratelimit::RemoteRateLimit>),
+	RedisRateLimit(RequestPolicy<redisratelimit::RedisRateLimit>),
 	ExtAuthz(RequestPolicy<ext_authz::ExtAuthz>),
 	ExtProc(RequestPolicy<ext_proc::ExtProc>),
 	JwtAuth(RequestPolicy<JwtAuthentication>),
@@ -2655,6 +2731,9 @@ pub enum TrafficPolicy {
 	APIKey(RequestPolicy<crate::http::apikey::APIKeyAuthentication>),
 	Transformation(RequestPolicy<crate::http::transformation_cel::Transformation>),
 	Csrf(RequestPolicy<crate::http::csrf::Csrf>),
+	RequestId(RequestPolicy<crate::http::requestid::RequestId>),
+	HeaderLimit(RequestPolicy<crate::http::headerlimit::HeaderLimit>),
+	GrpcWeb(RequestPolicy<crate::http::grpcweb::GrpcWeb>),
 
 	RequestHeaderModifier(RequestPolicy<filters::HeaderModifier>),
 	ResponseHeaderModifier(RequestPolicy<filters::HeaderModifier>),
@@ -2691,6 +2770,7 @@ pub enum BackendTrafficPolicy {
 	SessionPersistence(http::sessionpersistence::Policy),
 	Transformation(Arc<crate::http::transformation_cel::Transformation>),
 	Health(health::Policy),
+	CircuitBreaker(http::circuitbreaker::CircuitBreaker),
 
 	RequestHeaderModifier(filters::HeaderModifier),
 	ResponseHeaderModifier(Arc<filters::HeaderModifier>),
@@ -3084,6 +3164,7 @@ mod tests {
 			hostnames: hostnames.into_iter().map(strng::new).collect(),
 			matches,
 			backends: vec![],
+			backend_hash_key: None,
 			llm_router: None,
 			inline_policies: vec![],
 		}
@@ -3182,6 +3263,81 @@ mod tests {
 		assert_eq!(profiled.alpn_protocols, vec![b"http/1.1".to_vec()]);
 	}
 
+	fn self_signed_tls(
+		min_version: Option<TLSVersion>,
+		cipher_suites: Option<Vec<crate::transport::tls::CipherSuite>>,
+	) -> (anyhow::Result<ServerTLSConfig>, CertificateDer<'static>) {
+		let key = rcgen::KeyPair::generate().expect("generate key");
+		let cert = rcgen::CertificateParams::new(vec!["localhost".to_string()])
+			.expect("cert params")
+			.self_signed(&key)
+			.expect("generate cert");
+		let tls = ServerTLSConfig::from_pem_with_profile(
+			cert.pem().into_bytes(),
+			key.serialize_pem().into_bytes(),
+			None,
+			vec![],
+			min_version,
+			None,
+			cipher_suites,
+			None,
+			false,
+			false,
+		);
+		(tls, cert.der().clone())
+	}
+
+	/// Runs a TLS handshake against `server` with a client limited to `version`.
+	async fn handshake(
+		server: Arc<ServerConfig>,
+		root: CertificateDer<'static>,
+		version: &'static rustls::SupportedProtocolVersion,
+	) -> bool {
+		let mut roots = rustls::RootCertStore::empty();
+		roots.add(root).expect("add root");
+		let client = rustls::ClientConfig::builder_with_provider(crate::transport::tls::provider())
+			.with_protocol_versions(&[version])
+			.expect("client config")
+			.with_root_certificates(roots)
+			.with_no_client_auth();
+		let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+		let server_name = rustls::pki_types::ServerName::try_from("localhost").expect("server name");
+		let (accepted, connected) = tokio::join!(
+			tokio_rustls::TlsAcceptor::from(server).accept(server_io),
+			tokio_rustls::TlsConnector::from(Arc::new(client)).connect(server_name, client_io),
+		);
+		accepted.is_ok() && connected.is_ok()
+	}
+
+	#[tokio::test]
+	async fn min_tls_version_rejects_older_clients() {
+		let (tls, root) = self_signed_tls(Some(TLSVersion::TLS_V1_3), None);
+		let server = tls
+			.expect("build TLS config")
+			.config_for(None, None)
+			.await
+			.expect("base config");
+		assert!(!handshake(server.clone(), root.clone(), &rustls::version::TLS12).await);
+		assert!(handshake(server, root, &rustls::version::TLS13).await);
+	}
+
+	#[test]
+	fn unusable_tls_profile_fails_to_build() {
+		use crate::transport::tls::CipherSuite;
+
+		// Only TLS 1.2 cipher suites, but TLS 1.2 is not allowed.
+		let (tls, _) = self_signed_tls(
+			Some(TLSVersion::TLS_V1_3),
+			Some(vec![CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256]),
+		);
+		let err = tls.expect_err("config should be rejected").to_string();
+		assert!(err.contains("cipher suites are not usable"), "{err}");
+
+		let (tls, _) = self_signed_tls(Some(TLSVersion::TLS_V1_0), None);
+		let err = tls.expect_err("config should be rejected").to_string();
+		assert!(err.contains("unsupported TLS version"), "{err}");
+	}
+
 	#[test]
 	fn test_backend_type_categorization() {
 		let opaque_backend = Backend::Opaque(
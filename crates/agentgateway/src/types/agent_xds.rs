@@ -331,6 +331,7 @@ fn server_tls_config_from_proto(
 		cipher_suites,
 		key_exchange_groups,
 		mtls_mode == proto::agent::tls_config::MtlsMode::AllowInsecureFallback,
+		false,
 	) {
 		Ok(sc) => sc,
 		Err(e) => {
@@ -810,7 +811,11 @@ fn convert_backend_ai_policy(
 						})
 					},
 				};
-				Ok(llm::policy::RequestGuard { rejection, kind })
+				Ok(llm::policy::RequestGuard {
+					rejection,
+					mode: Default::default(),
+					kind,
+				})
 			})
 			.collect::<Result<Vec<_>, ProtoError>>()?;
 
@@ -953,6 +958,15 @@ fn convert_backend_ai_policy(
 			.iter()
 			.map(|(k, v)| (strng::new(k), convert_route_type(*v, diagnostics)))
 			.collect(),
+		fallbacks: ai
+			.fallbacks
+			.iter()
+			.map(|f| llm::policy::ModelFallback {
+				provider: strng::new(&f.provider),
+				model: f.model.as_deref().map(strng::new),
+			})
+			.collect(),
+		force_stream_usage: None,
 	};
 
 	// Compile wildcard patterns from model_aliases
@@ -985,6 +999,7 @@ fn backend_auth_from_proto(
 				None | Some(gcp::TokenType::AccessToken(gcp::AccessToken {})) => GcpAuth::AccessToken {
 					r#type: Some(auth::gcp::AccessToken),
 					credential,
+					impersonate: None,
 				},
 				Some(gcp::TokenType::IdToken(gcp::IdToken { audience })) => GcpAuth::IdToken {
 					r#type: auth::gcp::IdToken,
@@ -1087,6 +1102,7 @@ fn backend_auth_from_proto(
 				},
 				Some(proto::agent::aws::Kind::Implicit(_)) => AwsAuth::Implicit {
 					service_name,
+					region: None,
 					assume_role,
 					source_credentials_cache: Default::default(),
 					assume_role_cache: Default::default(),
@@ -1321,6 +1337,7 @@ impl Route {
 				.iter()
 				.map(|backend| route_backend_reference_from_proto(backend, diagnostics))
 				.collect::<Result<Vec<_>, _>>()?,
+			backend_hash_key: None,
 			llm_router: None,
 			inline_policies: s
 				.traffic_policies
@@ -1421,6 +1438,8 @@ pub(crate) fn backend_with_policies_from_proto(
 								region: strng::new(&bedrock.region),
 								guardrail_identifier: bedrock.guardrail_identifier.as_deref().map(strng::new),
 								guardrail_version: bedrock.guardrail_version.as_deref().map(strng::new),
+								inference_profile: None,
+								latency_optimized: None,
 							})
 						},
 						Some(provider::Provider::Azure(azure)) => {
@@ -1493,6 +1512,9 @@ pub(crate) fn backend_with_policies_from_proto(
 						name: provider_name.clone(),
 						provider,
 						tokenize: false,
+						tokenizer: None,
+						response_buffer_limit: None,
+						client_tls: None,
 						provider_backend,
 						host_override,
 						path_override: provider_config.path_override.as_ref().map(strng::new),
@@ -1551,6 +1573,7 @@ pub(crate) fn backend_with_policies_from_proto(
 	Ok(BackendWithPolicies {
 		backend,
 		inline_policies: pols,
+		metadata: Arc::new(s.metadata.clone()),
 	})
 }
 
@@ -1723,7 +1746,9 @@ fn transformation_from_proto(
 			set,
 			remove,
 			body,
+			json_body: None,
 			metadata,
+			cache_size: None,
 		}
 	}
 
@@ -1765,7 +1790,9 @@ fn backend_policy_from_proto(
 					HttpVersion::Http1 => Some(::http::Version::HTTP_11),
 					HttpVersion::Http2 => Some(::http::Version::HTTP_2),
 				},
+				http_version_preference: Default::default(),
 				request_timeout: bhttp.request_timeout.map(convert_duration),
+				websocket: None,
 			})
 		},
 		Some(bps::Kind::BackendTcp(btcp)) => BackendTrafficPolicy::TCP(backend::TCP {
@@ -1791,6 +1818,7 @@ fn backend_policy_from_proto(
 				insecure: mode == bps::backend_tls::VerificationMode::InsecureAll,
 				insecure_host: mode == bps::backend_tls::VerificationMode::InsecureHost,
 				hostname: btls.hostname.clone(),
+				verify_hostname: None,
 				alpn: btls.alpn.as_ref().map(|a| a.protocols.clone()),
 				subject_alt_names: if btls.verify_subject_alt_names.is_empty() {
 					None
@@ -1916,10 +1944,12 @@ fn convert_health(
 		restore_health: ev.restore_health,
 		consecutive_failures: ev.consecutive_failures,
 		health_threshold: ev.health_threshold,
+		max_eviction_percent: None,
 	});
 	health::Policy {
 		unhealthy_expression,
 		eviction,
+		active: None,
 	}
 }
 
@@ -1999,6 +2029,7 @@ fn traffic_policy_from_proto(
 					tps::local_rate_limit::Type::Request => http::localratelimit::RateLimitType::Requests,
 					tps::local_rate_limit::Type::Token => http::localratelimit::RateLimitType::Tokens,
 				},
+				key: None,
 			};
 			// Yes, its single with a vec, because we originally supported multiple rate limit policies before
 			// we added the generic multiple support.
@@ -2384,6 +2415,7 @@ fn traffic_policy_from_proto(
 				allow_headers: c.allow_headers.clone(),
 				allow_methods: c.allow_methods.clone(),
 				allow_origins: c.allow_origins.clone(),
+				allow_origin_regexes: Vec::new(),
 				expose_headers: c.expose_headers.clone(),
 				max_age: c.max_age.as_ref().map(|d| (*d).try_into()).transpose()?,
 			})
@@ -2917,6 +2949,7 @@ fn frontend_policy_from_proto(
 				remove: Arc::new(FzHashSet::new(rm)),
 				otlp,
 				database: None,
+				format: None,
 				access_log_policy: None,
 			};
 			logging_policy.init_access_log_policy();
@@ -2930,6 +2963,7 @@ fn frontend_policy_from_proto(
 			let logging_fields = Arc::new(crate::telemetry::log::LoggingFields {
 				remove: Arc::new(tracing_config.remove.iter().cloned().collect()),
 				add: Arc::new(tracing_config.attributes.clone()),
+				..Default::default()
 			});
 
 			FrontendPolicy::Tracing(Arc::new(types::agent::TracingPolicy {
@@ -3036,9 +3070,11 @@ fn tracing_config_from_proto(
 		remove: t.remove.clone(),
 		random_sampling,
 		client_sampling,
+		sampler: None,
 		filter,
 		path,
 		protocol,
+		baggage: None,
 	}
 }
 
@@ -3228,12 +3264,16 @@ fn conditional_traffic_policy_to_policy(
 		TrafficPolicy::ExtProc(_) => build!(ExtProc),
 		TrafficPolicy::LocalRateLimit(_) => build!(LocalRateLimit),
 		TrafficPolicy::RemoteRateLimit(_) => build!(RemoteRateLimit),
+		TrafficPolicy::RedisRateLimit(_) => build!(RedisRateLimit),
 		TrafficPolicy::JwtAuth(_) => build!(JwtAuth),
 		TrafficPolicy::Oidc(_) => build!(Oidc),
 		TrafficPolicy::BasicAuth(_) => build!(BasicAuth),
 		TrafficPolicy::APIKey(_) => build!(APIKey),
 		TrafficPolicy::Transformation(_) => build!(Transformation),
 		TrafficPolicy::Csrf(_) => build!(Csrf),
+		TrafficPolicy::RequestId(_) => build!(RequestId),
+		TrafficPolicy::HeaderLimit(_) => build!(HeaderLimit),
+		TrafficPolicy::GrpcWeb(_) => build!(GrpcWeb),
 		TrafficPolicy::RequestHeaderModifier(_) => build!(RequestHeaderModifier),
 		TrafficPolicy::ResponseHeaderModifier(_) => build!(ResponseHeaderModifier),
 		TrafficPolicy::RequestRedirect(_) => build!(RequestRedirect),
@@ -3256,6 +3296,7 @@ fn traffic_policy_kind_name(policy: &TrafficPolicy) -> &'static str {
 		TrafficPolicy::Authorization(_) => "authorization",
 		TrafficPolicy::LocalRateLimit(_) => "localRateLimit",
 		TrafficPolicy::RemoteRateLimit(_) => "remoteRateLimit",
+		TrafficPolicy::RedisRateLimit(_) => "redisRateLimit",
 		TrafficPolicy::ExtAuthz(_) => "extAuthz",
 		TrafficPolicy::ExtProc(_) => "extProc",
 		TrafficPolicy::JwtAuth(_) => "jwt",
@@ -3264,6 +3305,9 @@ fn traffic_policy_kind_name(policy: &TrafficPolicy) -> &'static str {
 		TrafficPolicy::APIKey(_) => "apiKey",
 		TrafficPolicy::Transformation(_) => "transformation",
 		TrafficPolicy::Csrf(_) => "csrf",
+		TrafficPolicy::RequestId(_) => "requestId",
+		TrafficPolicy::HeaderLimit(_) => "headerLimit",
+		TrafficPolicy::GrpcWeb(_) => "grpcWeb",
 		TrafficPolicy::RequestHeaderModifier(_) => "requestHeaderModifier",
 		TrafficPolicy::ResponseHeaderModifier(_) => "responseHeaderModifier",
 		TrafficPolicy::RequestRedirect(_) => "requestRedirect",
@@ -3368,6 +3412,7 @@ fn convert_prompt_caching(
 		cache_tools: pc.cache_tools,
 		min_tokens: pc.min_tokens.map(|t| t as usize),
 		cache_message_offset: pc.cache_message_offset.unwrap_or(0) as usize,
+		supported_models: Vec::new(),
 	}
 }
 
@@ -3395,6 +3440,7 @@ fn convert_webhook(
 	Ok(llm::policy::Webhook {
 		target,
 		forward_header_matches,
+		forward_header_names: Vec::new(),
 		failure_mode,
 	})
 }
@@ -3438,7 +3484,10 @@ fn convert_regex_rules(
 								return None;
 							},
 						};
-						Some(llm::policy::RegexRule::Builtin { builtin })
+						Some(llm::policy::RegexRule::Builtin {
+							builtin,
+							mask: None,
+						})
 					},
 					Err(_) => {
 						diagnostics.add_warning(format!("invalid builtin regex rule value {b}; skipping"));
@@ -3448,7 +3497,10 @@ fn convert_regex_rules(
 			},
 			Some(proto::agent::backend_policy_spec::ai::regex_rule::Kind::Regex(n)) => {
 				match regex::Regex::new(n) {
-					Ok(pattern) => Some(llm::policy::RegexRule::Regex { pattern }),
+					Ok(pattern) => Some(llm::policy::RegexRule::Regex {
+						pattern,
+						mask: None,
+					}),
 					Err(err) => {
 						diagnostics.add_warning(format!("invalid regex pattern {n:?}: {err}; skipping"));
 						None
@@ -4013,6 +4065,16 @@ mod tests {
 				]
 				.into_iter()
 				.collect(),
+				fallbacks: vec![
+					proto::agent::backend_policy_spec::ai::ModelFallback {
+						provider: "secondary".to_string(),
+						model: Some("gpt-4o-mini".to_string()),
+					},
+					proto::agent::backend_policy_spec::ai::ModelFallback {
+						provider: "tertiary".to_string(),
+						model: None,
+					},
+				],
 			})),
 		};
 
@@ -4070,6 +4132,13 @@ mod tests {
 				ai_policy.routes.get("/v1/detect"),
 				Some(&llm::RouteType::Detect)
 			);
+
+			// Verify fallbacks keep their order and optional model
+			assert_eq!(ai_policy.fallbacks.len(), 2);
+			assert_eq!(ai_policy.fallbacks[0].provider.as_str(), "secondary");
+			assert_eq!(ai_policy.fallbacks[0].model.as_deref(), Some("gpt-4o-mini"));
+			assert_eq!(ai_policy.fallbacks[1].provider.as_str(), "tertiary");
+			assert_eq!(ai_policy.fallbacks[1].model, None);
 		} else {
 			panic!("Expected AI policy variant");
 		}
@@ -4131,6 +4200,7 @@ mod tests {
 				})),
 			})),
 			inline_policies: vec![],
+			metadata: HashMap::from([("tier".to_string(), "gold".to_string())]),
 		};
 
 		let bw = backend_with_policies_from_proto(&proto_backend, &mut Diagnostics::default())?;
@@ -4138,6 +4208,7 @@ mod tests {
 			panic!("Expected Backend::Aws, got {:?}", bw.backend);
 		};
 		assert_eq!(name.to_string(), "test-ns/aws-backend");
+		assert_eq!(bw.metadata.get("tier").map(String::as_str), Some("gold"));
 		assert_eq!(config.region(), "us-east-1");
 		assert_eq!(config.service_name(), "bedrock-agentcore");
 		assert_eq!(
@@ -4179,6 +4250,7 @@ mod tests {
 				}],
 			})),
 			inline_policies: vec![],
+			metadata: Default::default(),
 		};
 
 		let bw = backend_with_policies_from_proto(&proto_backend, &mut Diagnostics::default())?;
@@ -4223,6 +4295,7 @@ mod tests {
 				}],
 			})),
 			inline_policies: vec![],
+			metadata: Default::default(),
 		};
 
 		let bw = backend_with_policies_from_proto(&proto_backend, &mut Diagnostics::default())?;
@@ -4285,6 +4358,7 @@ mod tests {
 				}],
 			})),
 			inline_policies: vec![],
+			metadata: Default::default(),
 		};
 
 		let bw = backend_with_policies_from_proto(&proto_backend, &mut Diagnostics::default())?;
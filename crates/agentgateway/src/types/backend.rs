@@ -10,6 +10,9 @@ pub struct HTTP {
 	#[serde(default, with = "http_serde::option::version")]
 	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
 	pub version: Option<::http::Version>,
+	/// Whether to attempt HTTP/2 with the backend. Takes precedence over `version`.
+	#[serde(default)]
+	pub http_version_preference: HttpVersionPreference,
 	/// Maximum time allowed for a backend HTTP request.
 	#[serde(
 		default,
@@ -18,6 +21,70 @@ pub struct HTTP {
 	)]
 	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
 	pub request_timeout: Option<Duration>,
+	/// Keepalive, idle timeout and message size settings for WebSocket connections to the backend.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub websocket: Option<WebSocket>,
+}
+
+#[apply(schema!)]
+#[derive(Default, Copy, PartialEq, Eq)]
+pub enum HttpVersionPreference {
+	/// Use the version configured on the backend or service, falling back to the version of the
+	/// incoming request. Over TLS, both `h2` and `http/1.1` are offered.
+	#[default]
+	Auto,
+	/// Always use HTTP/1.1. Over TLS, only `http/1.1` is offered.
+	Http1Only,
+	/// Always use HTTP/2. Over TLS, only `h2` is offered.
+	Http2Only,
+	/// Always use HTTP/2 without negotiating it. Over TLS, no ALPN protocols are offered.
+	Http2PriorKnowledge,
+}
+
+impl HttpVersionPreference {
+	/// The version requests must be sent with, if the preference fixes one.
+	pub fn version(&self) -> Option<::http::Version> {
+		match self {
+			HttpVersionPreference::Auto => None,
+			HttpVersionPreference::Http1Only => Some(::http::Version::HTTP_11),
+			HttpVersionPreference::Http2Only | HttpVersionPreference::Http2PriorKnowledge => {
+				Some(::http::Version::HTTP_2)
+			},
+		}
+	}
+}
+
+#[apply(schema!)]
+#[derive(Default)]
+pub struct WebSocket {
+	/// Close the connection after this long without messages in either direction.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		with = "serde_dur_option"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	pub idle_timeout: Option<Duration>,
+	/// Send a ping to the client and to the backend after this long without hearing from them.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		with = "serde_dur_option"
+	)]
+	#[cfg_attr(feature = "schema", schemars(with = "Option<String>"))]
+	pub ping_interval: Option<Duration>,
+	/// Close the connection when a ping is not answered within this time. Defaults to 10s.
+	#[serde(default = "default_ping_timeout", with = "serde_dur")]
+	#[cfg_attr(feature = "schema", schemars(with = "String"))]
+	pub ping_timeout: Duration,
+	/// Maximum size of a message in bytes, counted across all of its fragments. A larger message
+	/// closes the connection with status 1009. Defaults to 16MiB.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_message_size: Option<usize>,
+}
+
+fn default_ping_timeout() -> Duration {
+	Duration::from_secs(10)
 }
 
 impl HTTP {
@@ -27,7 +94,12 @@ impl HTTP {
 		};
 		// Version override comes from a Service having a version specified. A policy is more specific
 		// so we use the policy first.
-		let set_version = match self.version.or(version_override) {
+		let set_version = match self
+			.http_version_preference
+			.version()
+			.or(self.version)
+			.or(version_override)
+		{
 			Some(v) => Some(v),
 			None => {
 				// There are a few cases here...
@@ -162,7 +162,7 @@ pub(super) fn build_dynamic_ca_server_config(
 	let versions = super::agent::tls_versions_for_range(min_version, max_version)?;
 	let mut config = rustls::ServerConfig::builder_with_provider(Arc::clone(&provider))
 		.with_protocol_versions(&versions)
-		.expect("server config must be valid")
+		.map_err(|e| anyhow!("cipher suites are not usable with the configured TLS versions: {e}"))?
 		.with_no_client_auth()
 		.with_cert_resolver(Arc::new(DynamicCaCertResolver {
 			ca: Arc::new(DynamicCa::from_pem(ca_cert_pem, ca_key_pem)?),
@@ -288,6 +288,9 @@ pub struct LoggingPolicy {
 	/// Database-specific access log settings.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub database: Option<DatabaseLoggingConfig>,
+	/// Access log output format: `text` or `json`. Defaults to the process log format.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub format: Option<crate::LoggingFormat>,
 	#[serde(skip)]
 	#[cfg_attr(feature = "schema", schemars(skip))]
 	pub access_log_policy: Option<Arc<super::agent::AccessLogPolicy>>,
@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::future::pending;
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
 
 use arc_swap::ArcSwap;
@@ -86,12 +88,116 @@ fn build_sampler<T>(active: &IndexMap<EndpointKey, EndpointWithInfo<T>>) -> Samp
 	Sampler::Weighted(dist)
 }
 
+/// Points placed on the hash ring per unit of endpoint capacity.
+const RING_POINTS_PER_CAPACITY: usize = 160;
+/// Capacity is clamped to this when placing points, bounding the ring size for large capacities.
+const RING_MAX_CAPACITY: u32 = 16;
+
+fn hash_of<K: Hash + ?Sized>(key: &K) -> u64 {
+	let mut h = RingHasher::new();
+	key.hash(&mut h);
+	h.finish()
+}
+
+/// FNV-1a with a murmur3 finalizer. Unlike DefaultHasher, whose algorithm may change between Rust
+/// releases, this is fixed, so every replica and release maps a key to the same endpoint. Integers
+/// are hashed little-endian so the result does not depend on the platform either.
+struct RingHasher(u64);
+
+impl RingHasher {
+	const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+	const PRIME: u64 = 0x100000001b3;
+
+	fn new() -> Self {
+		Self(Self::OFFSET_BASIS)
+	}
+}
+
+impl Hasher for RingHasher {
+	fn write(&mut self, bytes: &[u8]) {
+		for b in bytes {
+			self.0 ^= u64::from(*b);
+			self.0 = self.0.wrapping_mul(Self::PRIME);
+		}
+	}
+
+	fn write_u16(&mut self, i: u16) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u32(&mut self, i: u32) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u64(&mut self, i: u64) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u128(&mut self, i: u128) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_usize(&mut self, i: usize) {
+		self.write_u64(i as u64);
+	}
+
+	fn finish(&self) -> u64 {
+		// FNV-1a alone mixes the last bytes poorly, which would cluster the points of one endpoint.
+		let mut k = self.0;
+		k ^= k >> 33;
+		k = k.wrapping_mul(0xff51afd7ed558ccd);
+		k ^= k >> 33;
+		k = k.wrapping_mul(0xc4ceb9fe1a85ec53);
+		k ^= k >> 33;
+		k
+	}
+}
+
+/// Ketama-style consistent hash ring over one pool of an EndpointGroup. Each endpoint owns a
+/// number of points proportional to its capacity; a key maps to the first point at or after its
+/// hash. Adding or removing an endpoint only moves the keys landing on that endpoint's points.
+#[derive(Debug, Clone, Default)]
+struct HashRing {
+	/// (point, index into the pool), sorted by point.
+	points: Vec<(u64, usize)>,
+}
+
+impl HashRing {
+	fn build<T>(pool: &IndexMap<EndpointKey, EndpointWithInfo<T>>, weighted: bool) -> Self {
+		let mut points = Vec::new();
+		for (idx, (key, ewi)) in pool.iter().enumerate() {
+			let capacity = if weighted {
+				ewi.capacity.min(RING_MAX_CAPACITY)
+			} else {
+				1
+			};
+			for i in 0..capacity as usize * RING_POINTS_PER_CAPACITY {
+				points.push((hash_of(&(key.as_str(), i)), idx));
+			}
+		}
+		points.sort_unstable();
+		Self { points }
+	}
+
+	fn lookup(&self, hash: u64) -> Option<usize> {
+		let pos = self.points.partition_point(|(p, _)| *p < hash);
+		self
+			.points
+			.get(pos)
+			.or_else(|| self.points.first())
+			.map(|(_, idx)| *idx)
+	}
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct EndpointGroup<T> {
 	active: IndexMap<EndpointKey, EndpointWithInfo<T>>,
 	rejected: IndexMap<EndpointKey, EndpointWithInfo<T>>,
 	#[serde(skip)]
 	sampler: Sampler,
+	/// Built on first use by `select_by_hash`, and reset whenever the pools change.
+	#[serde(skip)]
+	ring: OnceLock<HashRing>,
 }
 
 impl<T> EndpointGroup<T> {
@@ -104,6 +210,7 @@ impl<T> EndpointGroup<T> {
 			active,
 			rejected,
 			sampler,
+			ring: OnceLock::new(),
 		}
 	}
 
@@ -145,8 +252,10 @@ impl<T> EndpointGroup<T> {
 		}
 	}
 
-	// rebuilds the sampler, unless the change is guaranteed to preserve the same distribution
+	// rebuilds the sampler, unless the change is guaranteed to preserve the same distribution.
+	// The hash ring is always invalidated; it is rebuilt lazily on the next lookup.
 	fn update_sampler(&mut self, added_ep_cap: Option<u32>) {
+		self.ring = OnceLock::new();
 		let preserved = match (&self.sampler, added_ep_cap) {
 			// change doesn't modify any capacity, still Uniform
 			// this is the common case for unweighted EndpointGroups
@@ -170,6 +279,7 @@ impl<T> Default for EndpointGroup<T> {
 			active: IndexMap::new(),
 			rejected: IndexMap::new(),
 			sampler: Sampler::default(),
+			ring: OnceLock::new(),
 		}
 	}
 }
@@ -185,6 +295,19 @@ pub struct EndpointSet<T> {
 	// action_mutex serializes all mutators: XDS add/delete, rebucket, and the eviction worker.
 	// Readers don't take it — they just load_full the ArcSwap.
 	action_mutex: Arc<Mutex<()>>,
+
+	/// The active health checker, if started. Shared by every clone of the set and aborted when
+	/// the last one is dropped.
+	health_checker: Arc<OnceLock<HealthCheckTask>>,
+}
+
+#[derive(Debug)]
+struct HealthCheckTask(tokio::task::AbortHandle);
+
+impl Drop for HealthCheckTask {
+	fn drop(&mut self) {
+		self.0.abort();
+	}
 }
 fn contains_target_port(ep: &Endpoint, wanted_target: u16) -> bool {
 	ep.port.values().any(|tp| *tp == wanted_target)
@@ -472,6 +595,8 @@ impl<T> std::fmt::Debug for EvictionWorkerState<T> {
 
 trait EvictionStarter: std::fmt::Debug + Send + Sync {
 	fn start(&self);
+	/// Fraction (0.0–1.0) of all endpoints, across buckets, that are currently evicted.
+	fn evicted_fraction(&self) -> f64;
 }
 
 impl PartialEq for UnevictEntry {
@@ -516,6 +641,20 @@ impl<T: Clone + Sync + Send + 'static> EvictionStarter for EvictionWorkerState<T
 			self.action_mutex.clone(),
 		);
 	}
+
+	fn evicted_fraction(&self) -> f64 {
+		let (mut evicted, mut total) = (0, 0);
+		for bucket in &self.buckets {
+			let group = bucket.load_full();
+			evicted += group.rejected.len();
+			total += group.active.len() + group.rejected.len();
+		}
+		if total == 0 {
+			0.0
+		} else {
+			evicted as f64 / total as f64
+		}
+	}
 }
 
 impl<T: Clone + Sync + Send + 'static> Default for EndpointSet<T> {
@@ -559,6 +698,7 @@ impl<T: Clone + Sync + Send + 'static> EndpointSet<T> {
 			tx_eviction,
 			eviction_worker,
 			action_mutex,
+			health_checker: Default::default(),
 		}
 	}
 
@@ -628,6 +768,23 @@ impl<T: Clone + Sync + Send + 'static> EndpointSet<T> {
 		ActiveEndpointsIter(self.best_bucket())
 	}
 
+	/// Consistent-hash selection: the same key maps to the same endpoint for as long as it is in
+	/// the pool, and adding or removing an endpoint only remaps the keys it gains or loses. Picks
+	/// from the same pool as `iter` (active endpoints of the best bucket, or its rejected ones if
+	/// none are active), weighted by capacity. Drained (capacity 0) active endpoints are never
+	/// selected.
+	pub fn select_by_hash<K: Hash + ?Sized>(&self, key: &K) -> Option<(Arc<T>, Arc<EndpointInfo>)> {
+		let iter = self.iter();
+		let index = iter.index();
+		let ring = iter
+			.0
+			.ring
+			.get_or_init(|| HashRing::build(index, iter.is_active_phase()));
+		let idx = ring.lookup(hash_of(key))?;
+		let (_, ewi) = index.get_index(idx).expect("ring built from this pool");
+		Some((ewi.endpoint.clone(), ewi.info.clone()))
+	}
+
 	/// Visit every endpoint, returning the first `Some` produced by `f`. Active
 	/// endpoints from all buckets are visited before any rejected endpoint, e.g.:
 	///   active in bucket 0
@@ -660,6 +817,57 @@ impl<T: Clone + Sync + Send + 'static> EndpointSet<T> {
 		None
 	}
 
+	/// Every endpoint in the set, active and rejected, across all buckets.
+	pub fn all_endpoints(&self) -> Vec<(EndpointKey, Arc<T>)> {
+		let mut out = Vec::new();
+		for bucket in &self.buckets {
+			let group = bucket.load_full();
+			for (key, ewi) in group.active.iter().chain(group.rejected.iter()) {
+				out.push((key.clone(), ewi.endpoint.clone()));
+			}
+		}
+		out
+	}
+
+	/// Starts active health checking for this set, unless it is already running. `spawn` is given
+	/// a handle to the set that does not keep the checker alive, and returns the checker task. The
+	/// task is aborted once the last clone of this set is dropped.
+	pub fn start_health_checks(
+		&self,
+		spawn: impl FnOnce(EndpointSet<T>) -> tokio::task::JoinHandle<()>,
+	) {
+		self.health_checker.get_or_init(|| {
+			let detached = EndpointSet {
+				health_checker: Default::default(),
+				..self.clone()
+			};
+			HealthCheckTask(spawn(detached).abort_handle())
+		});
+	}
+
+	/// Records an active health check status change. An unhealthy endpoint is moved out of the
+	/// active set until it passes again; a healthy endpoint that is still passively evicted stays
+	/// out until its eviction expires.
+	pub fn set_health_check_status(&self, key: EndpointKey, healthy: bool) {
+		let _mu = self.action_mutex.lock();
+		let Some(bucket) = Self::find_bucket_atomic(self.buckets.as_slice(), &key) else {
+			return;
+		};
+		let mut eps = Arc::unwrap_or_clone(bucket.load_full());
+		if let Some(ewi) = eps.active.get(&key).or_else(|| eps.rejected.get(&key)) {
+			ewi
+				.info
+				.health_check_failed
+				.store(!healthy, AtomicOrdering::Relaxed);
+		}
+		if healthy {
+			eps.unevict(key, |ep| ep.info.evicted_until.load().is_none());
+		} else {
+			eps.evict(key);
+		}
+		bucket.store(Arc::new(eps));
+	}
+
 	pub fn insert_key(&self, key: EndpointKey, ep: T, bucket: usize) {
 		self.event(EndpointEvent::Add(key, EndpointWithInfo::new(ep), bucket))
 	}
@@ -776,7 +984,9 @@ impl<T: Clone + Sync + Send + 'static> EndpointSet<T> {
 						// Health scoring assumes normalized values in [0.0, 1.0].
 						ep.info.health.set(h.clamp(0.0, 1.0));
 					}
-					true
+					// An endpoint failing active health checks stays rejected; the health checker
+					// restores it once it passes again.
+					!ep.info.health_check_failed.load(AtomicOrdering::Relaxed)
 				});
 				bucket.store(Arc::new(eps));
 			};
@@ -858,6 +1068,8 @@ pub struct EndpointInfo {
 	#[serde(with = "serde_instant_option")]
 	/// evicted_until is the time at which the endpoint will be evicted.
 	evicted_until: AtomicOption<Instant>,
+	/// Set while active health checks report the endpoint unhealthy.
+	health_check_failed: AtomicBool,
 }
 
 impl Default for EndpointInfo {
@@ -871,6 +1083,7 @@ impl Default for EndpointInfo {
 			consecutive_failures: Default::default(),
 			times_ejected: Default::default(),
 			evicted_until: Arc::new(Default::default()),
+			health_check_failed: Default::default(),
 		}
 	}
 }
@@ -889,6 +1102,9 @@ impl EndpointInfo {
 	pub fn times_ejected(&self) -> u64 {
 		self.times_ejected.load(AtomicOrdering::Relaxed)
 	}
+	pub fn health_check_failed(&self) -> bool {
+		self.health_check_failed.load(AtomicOrdering::Relaxed)
+	}
 	// Todo: fine-tune the algorithm here
 	pub fn score(&self) -> f64 {
 		let latency_penalty =
@@ -972,6 +1188,10 @@ impl ActiveHandle {
 	pub fn times_ejected(&self) -> u64 {
 		self.info.times_ejected()
 	}
+	/// Fraction (0.0–1.0) of the endpoint set this handle belongs to that is currently evicted.
+	pub fn evicted_fraction(&self) -> f64 {
+		self.eviction_starter.evicted_fraction()
+	}
 	pub fn finish_request(
 		self,
 		success: bool,
@@ -1240,6 +1460,61 @@ mod tests {
 		assert_eq!(ep_info.health_score(), 1.0, "health should be reset to 1.0");
 	}
 
+	#[tokio::test]
+	async fn failing_endpoint_is_ejected_and_readmitted_with_max_eviction_percent() {
+		use crate::http::health;
+
+		tokio::time::pause();
+		let policy = health::Policy {
+			eviction: Some(health::Eviction {
+				duration: Some(Duration::from_millis(100)),
+				consecutive_failures: Some(2),
+				max_eviction_percent: Some(50.0),
+				..Default::default()
+			}),
+			..Default::default()
+		};
+		let (a, b): (Strng, Strng) = ("a".into(), "b".into());
+		let eps = EndpointSet::new(vec![vec![(a.clone(), "a"), (b.clone(), "b")]]);
+
+		// Mirrors how the request log finishes a handle for an unhealthy response.
+		let fail = |key: &Strng| {
+			let group = eps.buckets[0].load_full();
+			let ewi = group.active.get(key).or_else(|| group.rejected.get(key));
+			let handle = eps.start_request(key.clone(), &ewi.unwrap().info);
+			let (healthy, eviction, restore) = policy.eviction_decision(
+				handle.health_score(),
+				handle.consecutive_failures(),
+				handle.times_ejected(),
+				true,
+				None,
+			);
+			let eviction = eviction.filter(|_| policy.eviction_allowed(handle.evicted_fraction()));
+			handle.finish_request(healthy, Duration::from_millis(10), eviction, restore);
+		};
+
+		// The first failure is below the consecutive failure threshold.
+		fail(&a);
+		tokio::task::yield_now().await;
+		assert!(eps.best_bucket().active.contains_key(&a));
+
+		fail(&a);
+		yield_until(|| eps.best_bucket().rejected.contains_key(&a))
+			.await
+			.expect("endpoint should be ejected after consecutive failures");
+
+		// Half the endpoints are already ejected, so "b" stays active despite failing.
+		fail(&b);
+		fail(&b);
+		tokio::task::yield_now().await;
+		assert!(eps.best_bucket().active.contains_key(&b));
+
+		tokio::time::advance(Duration::from_millis(150)).await;
+		yield_until(|| eps.best_bucket().active.contains_key(&a))
+			.await
+			.expect("endpoint should be readmitted after the ejection time");
+	}
+
 	#[tokio::test]
 	async fn endpoint_set_repeated_failure_during_window_does_not_bump_times_ejected() {
 		let key: Strng = "ep1".into();
@@ -1833,4 +2108,87 @@ mod tests {
 		assert_eq!(r.rank(&wl("_", "_", "_", "nodeB", "clusterA")), Some(1));
 		assert_eq!(r.rank(&wl("_", "_", "_", "nodeA", "clusterB")), Some(0));
 	}
+
+	// --- Consistent hashing ---
+
+	#[test]
+	fn hash_of_is_stable() {
+		// Pinned values: changing them remaps every hashed key onto different endpoints.
+		assert_eq!(hash_of("abc"), 0x3ee0641e1a674131);
+		assert_eq!(hash_of(&("ep", 3usize)), 0x4dc6fb4d0e1583b6);
+	}
+
+	fn endpoint_set(names: &[&'static str]) -> EndpointSet<&'static str> {
+		EndpointSet::new(vec![names.iter().map(|n| (Strng::from(*n), *n)).collect()])
+	}
+
+	fn hash_selections(eps: &EndpointSet<&'static str>, keys: usize) -> Vec<&'static str> {
+		(0..keys)
+			.map(|k| *eps.select_by_hash(&format!("key-{k}")).unwrap().0)
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn select_by_hash_empty() {
+		let eps = EndpointSet::<&'static str>::new_empty(1);
+		assert!(eps.select_by_hash("key").is_none());
+	}
+
+	#[tokio::test]
+	async fn select_by_hash_is_stable() {
+		let eps = endpoint_set(&["a", "b", "c", "d"]);
+		let first = hash_selections(&eps, 1_000);
+		assert_eq!(hash_selections(&eps, 1_000), first);
+		// An identical set built independently maps keys the same way.
+		assert_eq!(
+			hash_selections(&endpoint_set(&["a", "b", "c", "d"]), 1_000),
+			first
+		);
+	}
+
+	#[tokio::test]
+	async fn select_by_hash_is_balanced() {
+		let eps = endpoint_set(&["a", "b", "c", "d"]);
+		let n = 10_000;
+		let counts = hash_selections(&eps, n).into_iter().counts();
+		for name in ["a", "b", "c", "d"] {
+			let share = counts[&name] as f64 / n as f64;
+			// Ideal is 25%; 160 points per endpoint keeps the spread well inside ±8%.
+			assert!(
+				(share - 0.25).abs() < 0.08,
+				"{name} share = {share} ({counts:?})"
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn select_by_hash_minimal_reshuffle_on_add() {
+		let eps = endpoint_set(&["a", "b", "c", "d"]);
+		let n = 10_000;
+		let before = hash_selections(&eps, n);
+		eps.insert_key("e".into(), "e", 0);
+		let after = hash_selections(&eps, n);
+
+		let mut moved = 0;
+		for (b, a) in before.iter().zip(&after) {
+			if b != a {
+				// Keys only ever move to the new endpoint.
+				assert_eq!(*a, "e");
+				moved += 1;
+			}
+		}
+		// Ideal is 1/5 of keys; far fewer than a full reshuffle would move.
+		let share = moved as f64 / n as f64;
+		assert!((share - 0.2).abs() < 0.08, "moved share = {share}");
+	}
+
+	#[tokio::test]
+	async fn select_by_hash_skips_drained_endpoints() {
+		let eps = EndpointSet::<&'static str>::new_empty(1);
+		let mut group = EndpointGroup::default();
+		group.add("a".into(), EndpointWithInfo::with_capacity("a", 1));
+		group.add("b".into(), EndpointWithInfo::with_capacity("b", 0));
+		eps.buckets[0].store(Arc::new(group));
+		assert!(hash_selections(&eps, 1_000).iter().all(|n| *n == "a"));
+	}
 }
@@ -44,6 +44,8 @@ type LocalDirectResponsePolicy = LocalExplicitOrConditional<filters::DirectRespo
 type LocalExtProcPolicy = LocalExplicitOrConditional<crate::http::ext_proc::ExtProc>;
 type LocalRemoteRateLimitPolicy =
 	LocalExplicitOrConditional<crate::http::remoteratelimit::RemoteRateLimit>;
+type LocalRedisRateLimitPolicy =
+	LocalExplicitOrConditional<crate::http::redisratelimit::RedisRateLimit>;
 type LocalTransformationPolicy = LocalExplicitOrConditional<LocalTransformationConfig>;
 type LocalMcpGuardrails = crate::mcp::guardrails::McpGuardrails;
 const DEFAULT_LLM_PORT: u16 = 4000;
@@ -71,6 +73,19 @@ impl NormalizedLocalConfig {
 		let t = result?;
 		Ok(t)
 	}
+
+	/// Check a local config against its schema, returning every error found rather than stopping at
+	/// the first one. Checks that require converting the config, such as conflicting binds, are not run.
+	pub fn validate(s: &str) -> anyhow::Result<Vec<String>> {
+		let s = s.replace("# yaml-language-server: $schema", "#");
+		let s = shellexpand::full(&s)?;
+		let value: serde_json::Value = serdes::yamlviajson::from_str(&s)?;
+		Ok(
+			serdes::from_value_collecting_errors::<LocalConfig>(value)
+				.err()
+				.unwrap_or_default(),
+		)
+	}
 }
 
 pub fn migrate_deprecated_local_config(s: &str) -> anyhow::Result<String> {
@@ -189,6 +204,7 @@ fn merge_deprecated_frontend_policies(
 			remove: log.fields.remove.clone(),
 			otlp: None,
 			database: None,
+			format: None,
 			access_log_policy: None,
 		});
 	}
@@ -203,6 +219,7 @@ fn merge_deprecated_frontend_policies(
 			fields,
 			random_sampling,
 			client_sampling,
+			sampler,
 			path,
 		} = tracing;
 
@@ -241,11 +258,13 @@ fn merge_deprecated_frontend_policies(
 				remove: Arc::unwrap_or_clone(fields.remove).into_iter().collect(),
 				random_sampling,
 				client_sampling,
+				sampler,
 				path,
 				protocol: match protocol {
 					Protocol::Grpc => crate::types::agent::TracingProtocol::Grpc,
 					Protocol::Http => crate::types::agent::TracingProtocol::Http,
 				},
+				baggage: None,
 			});
 		}
 	}
@@ -908,6 +927,9 @@ pub struct LocalLLMParams {
 	/// Whether to tokenize the request before forwarding it upstream.
 	#[serde(default)]
 	tokenize: bool,
+	/// Explicit tokenizer to use when counting tokens, overriding the one inferred from the model.
+	#[serde(default)]
+	tokenizer: Option<crate::llm::Tokenizer>,
 }
 
 impl LocalLLMModels {
@@ -928,6 +950,7 @@ impl LocalLLMModels {
 			path_override: None,
 			path_prefix: None,
 			tokenize: false,
+			tokenizer: None,
 		} = std::mem::take(&mut self.params)
 		else {
 			bail!(
@@ -1310,6 +1333,10 @@ pub struct LocalTLSServerConfig {
 	#[cfg_attr(feature = "schema", schemars(with = "Option<Vec<String>>"))]
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub key_exchange_groups: Option<Vec<crate::transport::tls::KeyExchangeGroup>>,
+	/// Staple OCSP responses from the certificate's OCSP responder to handshakes. The certificate
+	/// file must include the issuer certificate. Only supported in static mode.
+	#[serde(default)]
+	pub ocsp_stapling: bool,
 }
 
 #[apply(schema_enum!)]
@@ -1357,6 +1384,11 @@ pub struct LocalRoute {
 	/// Weighted backends this route forwards traffic to.
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	backends: Vec<LocalRouteBackend>,
+	/// CEL expression computing a key used to pick among weighted backends, such as
+	/// `request.headers["x-user-id"]`. Requests with the same key are sent to the same backend.
+	/// When unset, or when the key evaluates to null, a backend is picked at random.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	backend_hash_key: Option<Arc<cel::Expression>>,
 }
 
 #[apply(schema_de!)]
@@ -1384,6 +1416,9 @@ pub struct FullLocalBackend {
 	/// Backend-level policies such as TLS, authentication, transformations, and health checks.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub policies: Option<LocalBackendPolicies>,
+	/// Arbitrary key/value metadata for this backend, exposed to CEL as `backend.metadata`.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub metadata: HashMap<String, String>,
 }
 
 #[apply(schema_de!)]
@@ -1536,11 +1571,49 @@ pub struct LocalNamedAIProvider {
 	/// This comes with the cost of an expensive operation.
 	#[serde(default)]
 	pub tokenize: bool,
+	/// Explicit tokenizer to use when counting tokens, overriding the one inferred from the model.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub tokenizer: Option<crate::llm::Tokenizer>,
+	/// Maximum size, in bytes, of a buffered (non-streaming) response from this provider.
+	/// Defaults to the response buffer limit of the gateway.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub response_buffer_limit: Option<usize>,
+	/// Client certificate presented to the provider, for providers that require mutual TLS.
+	/// Only used when connecting to the provider's default endpoint.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub client_certificate: Option<LocalClientCertificate>,
 	/// Backend policies applied to traffic to this provider.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub policies: Option<LocalBackendPolicies>,
 }
 
+#[apply(schema_de!)]
+pub struct LocalClientCertificate {
+	/// Client certificate file to present to the provider.
+	pub cert: PathBuf,
+	/// Private key file for the client certificate.
+	pub key: PathBuf,
+}
+
+impl LocalClientCertificate {
+	async fn try_into(
+		self,
+		resources: &crate::resource_manager::ResourceFetcher,
+	) -> anyhow::Result<http::backendtls::BackendTLS> {
+		let cert = resources
+			.fetch(crate::resource_manager::ResourceRef::File(self.cert))
+			.await?;
+		let key = resources
+			.fetch(crate::resource_manager::ResourceRef::File(self.key))
+			.await?;
+		resources.backend_tls(ResolvedBackendTLS {
+			cert: Some(cert.to_vec()),
+			key: Some(key.to_vec()),
+			..Default::default()
+		})
+	}
+}
+
 impl LocalAIBackend {
 	pub async fn translate(
 		self,
@@ -1564,6 +1637,10 @@ impl LocalAIBackend {
 					Some(p) => p.translate(resources).await?,
 					None => Vec::new(),
 				};
+				let client_tls = match p.client_certificate {
+					Some(c) => Some(c.try_into(resources).await?),
+					None => None,
+				};
 				group.push((
 					p.name.clone(),
 					NamedAIProvider {
@@ -1574,6 +1651,9 @@ impl LocalAIBackend {
 						path_override: p.path_override,
 						path_prefix: p.path_prefix,
 						tokenize: p.tokenize,
+						tokenizer: p.tokenizer,
+						response_buffer_limit: p.response_buffer_limit,
+						client_tls,
 						inline_policies: policies,
 					},
 				));
@@ -1620,6 +1700,7 @@ impl LocalBackend {
 		Ok(BackendWithPolicies {
 			backend: b,
 			inline_policies,
+			metadata: Default::default(),
 		})
 	}
 
@@ -2441,6 +2522,10 @@ pub struct LocalBackendPolicies {
 	#[serde(default)]
 	pub health: Option<health::LocalHealthPolicy>,
 
+	/// Limit concurrent and queued requests to this backend, rejecting the excess with a 503.
+	#[serde(default)]
+	pub circuit_breaker: Option<http::circuitbreaker::CircuitBreaker>,
+
 	/// Authorize incoming requests by calling an external authorization service after this backend is selected.
 	#[serde(default)]
 	pub ext_authz: Option<crate::http::ext_authz::ExtAuthz>,
@@ -2519,6 +2604,7 @@ impl LocalBackendPolicies {
 			response_header_modifier,
 			request_redirect,
 			health,
+			circuit_breaker,
 			ext_authz,
 			authorization,
 		} = self;
@@ -2584,6 +2670,9 @@ impl LocalBackendPolicies {
 				|e: crate::cel::Error| anyhow::anyhow!("health.unhealthyExpression: {}", e),
 			)?));
 		}
+		if let Some(p) = circuit_breaker {
+			pols.push(BackendTrafficPolicy::CircuitBreaker(p));
+		}
 		Ok(pols)
 	}
 }
@@ -2718,6 +2807,9 @@ pub struct FilterOrPolicy {
 	/// Remote rate limit checks for incoming requests.
 	#[serde(default)]
 	remote_rate_limit: Option<LocalRemoteRateLimitPolicy>,
+	/// Distributed rate limits shared through Redis.
+	#[serde(default)]
+	redis_rate_limit: Option<LocalRedisRateLimitPolicy>,
 	/// Authenticate incoming requests with JWT bearer tokens.
 	#[serde(default)]
 	jwt_auth: Option<crate::http::jwt::LocalJwtConfig>,
@@ -2748,6 +2840,19 @@ pub struct FilterOrPolicy {
 	#[serde(default)]
 	csrf: Option<http::csrf::Csrf>,
 
+	/// Propagate the incoming `x-request-id` header, or generate one when absent. The ID is available
+	/// as the `request.id` CEL attribute and is echoed on the response.
+	#[serde(default)]
+	request_id: Option<http::requestid::RequestId>,
+
+	/// Reject requests exceeding a maximum header count or cumulative header size with a 431.
+	#[serde(default)]
+	header_limit: Option<http::headerlimit::HeaderLimit>,
+
+	/// Translate gRPC-Web requests from browsers into gRPC, and their responses back to gRPC-Web.
+	#[serde(default)]
+	grpc_web: Option<http::grpcweb::GrpcWeb>,
+
 	// TrafficPolicy
 	/// Buffer request and response bodies.
 	#[serde(default)]
@@ -2900,6 +3005,7 @@ async fn convert(
 			None => Vec::new(),
 		};
 		let name = local_name(b.name);
+		let metadata = Arc::new(b.metadata);
 		let lb: LocalBackend = b.spec.into();
 		let mut bws = lb
 			.as_backends(name.clone(), resources, config.mcp.session_ttl)
@@ -2919,6 +3025,7 @@ async fn convert(
 			Backend::Service(_, _) | Backend::Invalid => false,
 		}) {
 			primary_bw.inline_policies.extend_from_slice(&policies);
+			primary_bw.metadata = metadata;
 		} else {
 			anyhow::bail!("as_backends did not return a backend with the expected name: {name}");
 		}
@@ -3773,6 +3880,7 @@ async fn convert_attached_ui(
 			target: BackendReference::Backend(strng::format!("/{backend_key}")).into(),
 			inline_policies: vec![],
 		}],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: resolved_policies.route_policies,
 	}];
@@ -4333,6 +4441,8 @@ async fn convert_llm_config(
 				region: p.aws_region.context("bedrock requires aws_region")?,
 				guardrail_identifier: None,
 				guardrail_version: None,
+				inference_profile: None,
+				latency_optimized: None,
 			}),
 			LocalModelAIProvider::Azure => AIProvider::azure(crate::llm::azure::Provider {
 				model,
@@ -4366,6 +4476,9 @@ async fn convert_llm_config(
 			path_override: p.path_override,
 			path_prefix: p.path_prefix,
 			tokenize: p.tokenize,
+			tokenizer: p.tokenizer,
+			response_buffer_limit: None,
+			client_tls: None,
 			inline_policies: pols,
 		};
 		let resolved_provider = named_provider.clone();
@@ -4415,11 +4528,14 @@ async fn convert_llm_config(
 			wildcard_patterns: Arc::new(vec![]),
 			prompt_caching: model_config.prompt_caching.clone(),
 			routes: Default::default(),
+			fallbacks: Vec::new(),
+			force_stream_usage: None,
 		})));
 		let resolved_inline_policies = pols.clone();
 		let backend_with_policies = BackendWithPolicies {
 			backend: Backend::AI(local_name(backend_key.clone()), ai_backend),
 			inline_policies: pols,
+			metadata: Default::default(),
 		};
 		all_backends.push(backend_with_policies);
 		resolved_models.push(ResolvedLLMModelTarget {
@@ -4517,6 +4633,7 @@ async fn convert_llm_config(
 						},
 					),
 					inline_policies: vec![],
+					metadata: Default::default(),
 				});
 				llm::model_router::VirtualModelRouting::Failover { backend_key }
 			},
@@ -4539,6 +4656,7 @@ async fn convert_llm_config(
 			)),
 		),
 		inline_policies: vec![],
+		metadata: Default::default(),
 	});
 
 	routes.push(Route {
@@ -4563,6 +4681,7 @@ async fn convert_llm_config(
 			target: BackendReference::Backend(strng::format!("/{router_backend_key}")).into(),
 			inline_policies: vec![],
 		}],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: llm_request_policies,
 	});
@@ -4686,6 +4805,7 @@ async fn convert_mcp_config(
 			target: BackendReference::Backend(strng::new("/mcp")).into(),
 			inline_policies: resolved_policies.backend_policies,
 		}],
+		backend_hash_key: None,
 		llm_router: None,
 		inline_policies: resolved_policies.route_policies,
 	};
@@ -4905,6 +5025,7 @@ pub async fn convert_route(
 		matches,
 		policies,
 		backends,
+		backend_hash_key,
 	} = lr;
 
 	let route_name = name.name.unwrap_or_else(|| strng::format!("route{}", idx));
@@ -4990,6 +5111,7 @@ pub async fn convert_route(
 		hostnames,
 		matches,
 		backends: backend_refs,
+		backend_hash_key,
 		llm_router: None,
 		inline_policies,
 	};
@@ -5063,6 +5185,7 @@ async fn split_frontend_policies(
 		let logging_fields = Arc::new(crate::telemetry::log::LoggingFields {
 			remove: Arc::new(tracing_config.remove.iter().cloned().collect()),
 			add: Arc::new(tracing_config.attributes.clone()),
+			..Default::default()
 		});
 
 		add(
@@ -5117,12 +5240,16 @@ pub(crate) async fn split_policies_for_target(
 		authorization,
 		local_rate_limit,
 		remote_rate_limit,
+		redis_rate_limit,
 		jwt_auth,
 		oidc: oidc_config,
 		basic_auth,
 		api_key,
 		transformations,
 		csrf,
+		request_id,
+		header_limit,
+		grpc_web,
 		ext_authz,
 		ext_proc,
 		buffer,
@@ -5270,6 +5397,15 @@ pub(crate) async fn split_policies_for_target(
 	if let Some(p) = csrf {
 		route_policies.push(TrafficPolicy::Csrf(RequestPolicy::single(p)))
 	}
+	if let Some(p) = request_id {
+		route_policies.push(TrafficPolicy::RequestId(RequestPolicy::single(p)))
+	}
+	if let Some(p) = header_limit {
+		route_policies.push(TrafficPolicy::HeaderLimit(RequestPolicy::single(p)))
+	}
+	if let Some(p) = grpc_web {
+		route_policies.push(TrafficPolicy::GrpcWeb(RequestPolicy::single(p)))
+	}
 	if let Some(p) = authorization {
 		if backend_target {
 			backend_policies.push(BackendTrafficPolicy::Authorization(p));
@@ -5300,6 +5436,9 @@ pub(crate) async fn split_policies_for_target(
 	if let Some(p) = remote_rate_limit {
 		route_policies.push(TrafficPolicy::RemoteRateLimit(p.into_policy()?))
 	}
+	if let Some(p) = redis_rate_limit {
+		route_policies.push(TrafficPolicy::RedisRateLimit(p.into_policy()?))
+	}
 
 	// Traffic policies
 	if let Some(p) = buffer {
@@ -5441,11 +5580,15 @@ impl LocalTLSServerConfig {
 				self.cipher_suites,
 				self.key_exchange_groups,
 				false,
+				self.ocsp_stapling,
 			),
 			LocalTLSServerMode::DynamicCa => {
 				if root_pem.is_some() {
 					anyhow::bail!("tls.root is not supported with tls.mode=dynamicCa")
 				}
+				if self.ocsp_stapling {
+					anyhow::bail!("tls.ocspStapling is not supported with tls.mode=dynamicCa")
+				}
 				super::dynamic_ca_cert::build_dynamic_ca_tls_config_with_profile(
 					cert_pem,
 					key_pem,
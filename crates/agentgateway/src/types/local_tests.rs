@@ -71,6 +71,9 @@ fn test_client() -> client::Client {
 		&client::Config {
 			resolver_cfg: hickory_resolver::config::ResolverConfig::default(),
 			resolver_opts: hickory_resolver::config::ResolverOpts::default(),
+			negative_ttl: client::DEFAULT_DNS_NEGATIVE_TTL,
+			doh: None,
+			client_subnet: None,
 		},
 		None,
 		BackendConfig::default(),
@@ -1756,6 +1759,51 @@ binds:
 	}));
 }
 
+#[tokio::test]
+async fn test_named_backend_metadata_is_visible_to_cel() {
+	let normalized = normalize_test_yaml(
+		r#"
+backends:
+- name: upstream
+  host: 127.0.0.1:8000
+  metadata:
+    tier: premium
+binds:
+- port: 3000
+  listeners:
+  - routes:
+    - backends:
+      - backend: upstream
+"#,
+	)
+	.await
+	.expect("named backend with metadata should normalize");
+
+	let backend = normalized
+		.backends
+		.iter()
+		.find(|b| matches!(&b.backend, Backend::Opaque(n, _) if n.name.as_str() == "upstream"))
+		.expect("named backend");
+	let info = backend.backend_info();
+
+	let mut req = ::http::Request::builder()
+		.uri("http://example.com")
+		.body(crate::http::Body::empty())
+		.unwrap();
+	req.extensions_mut().insert(crate::cel::BackendContext {
+		name: info.backend_name,
+		backend_type: info.backend_type,
+		protocol: crate::cel::BackendProtocol::http,
+		metadata: info.metadata,
+	});
+	let exp = crate::cel::Expression::new_strict(r#"backend.metadata.tier"#).unwrap();
+	let exec = crate::cel::Executor::new_request(&req);
+	assert_eq!(
+		exec.eval(&exp).unwrap().json().unwrap(),
+		serde_json::json!("premium")
+	);
+}
+
 #[tokio::test]
 async fn test_mcp_named_backend_reference_reuses_existing_backend() {
 	let normalized = normalize_test_yaml(
@@ -1981,3 +2029,212 @@ binds:
 		"returned unexpected error: {err}"
 	);
 }
+
+#[test]
+fn test_validate_reports_all_errors() {
+	let errors = NormalizedLocalConfig::validate(
+		r#"
+binds:
+- port: 3000
+  listeners:
+  - routes:
+    - policies:
+        retry:
+          codes: [503]
+          condition: 'response.code =='
+      backends:
+      - host: 127.0.0.1:8080
+    - bogus: true
+      backends:
+      - host: 127.0.0.1:8080
+"#,
+	)
+	.unwrap();
+	assert_eq!(errors.len(), 2, "{errors:?}");
+	assert!(
+		errors[0].starts_with("binds[0].listeners[0].routes[0]"),
+		"{errors:?}"
+	);
+	// The second route keeps its original index even though the first one was dropped.
+	assert!(
+		errors[1].starts_with("binds[0].listeners[0].routes[1]"),
+		"{errors:?}"
+	);
+	assert!(errors[1].contains("unknown field `bogus`"), "{errors:?}");
+}
+
+fn reload_tls_config(backend: &str, prefix: &str, hostname: &str) -> String {
+	format!(
+		r#"
+binds:
+- port: 3000
+  listeners:
+  - routes:
+    - matches:
+      - path:
+          pathPrefix: {prefix}
+      backends:
+      - host: 127.0.0.1:8000
+    - backends:
+      - host: {backend}
+        policies:
+          backendTLS:
+            hostname: {hostname}
+            insecure: true
+"#
+	)
+}
+
+async fn normalize_managed(
+	manager: &crate::resource_manager::ResourceManager,
+	yaml: &str,
+) -> NormalizedLocalConfig {
+	let resources = crate::resource_manager::ResourceFetcher::managed(manager.clone());
+	NormalizedLocalConfig::from(
+		&test_config(),
+		&resources,
+		ListenerTarget {
+			gateway_name: "name".into(),
+			gateway_namespace: "ns".into(),
+			listener_name: None,
+			port: None,
+		},
+		yaml,
+	)
+	.await
+	.unwrap()
+}
+
+fn reloaded_backend_tls(normalized: &NormalizedLocalConfig) -> crate::http::backendtls::BackendTLS {
+	let route = &normalized.listener_routes[0].1[1];
+	route.backends[0]
+		.inline_policies
+		.iter()
+		.find_map(|p| match p {
+			BackendTrafficPolicy::BackendTLS(tls) => Some(tls.clone()),
+			_ => None,
+		})
+		.expect("backend TLS policy")
+}
+
+#[tokio::test]
+async fn test_reload_keeps_tls_config_for_unchanged_backends() {
+	let manager = crate::resource_manager::ResourceManager::new(test_client()).unwrap();
+	let config = |prefix: &str, hostname: &str| reload_tls_config("127.0.0.1:8443", prefix, hostname);
+
+	let initial = normalize_managed(&manager, &config("/a", "tls-reuse.example.com")).await;
+	// Changing an unrelated route yields the same TLS config, and so the same connection pool key.
+	let reloaded = normalize_managed(&manager, &config("/b", "tls-reuse.example.com")).await;
+	assert_eq!(
+		reloaded_backend_tls(&initial).base_config(),
+		reloaded_backend_tls(&reloaded).base_config()
+	);
+
+	let changed = normalize_managed(&manager, &config("/b", "tls-changed.example.com")).await;
+	assert_ne!(
+		reloaded_backend_tls(&initial).base_config(),
+		reloaded_backend_tls(&changed).base_config()
+	);
+
+	// The config no longer used by the latest reload was dropped from the cache, so going back to
+	// it builds a new one.
+	let reverted = normalize_managed(&manager, &config("/b", "tls-reuse.example.com")).await;
+	assert_ne!(
+		reloaded_backend_tls(&initial).base_config(),
+		reloaded_backend_tls(&reverted).base_config()
+	);
+
+	// Without a resource manager, nothing is shared.
+	let unmanaged = normalize_test_yaml(&config("/b", "tls-changed.example.com"))
+		.await
+		.unwrap();
+	assert_ne!(
+		reloaded_backend_tls(&changed).base_config(),
+		reloaded_backend_tls(&unmanaged).base_config()
+	);
+}
+
+/// Serves HTTPS on a local port, counting the TLS connections accepted.
+async fn counting_tls_server() -> (std::net::SocketAddr, Arc<std::sync::atomic::AtomicUsize>) {
+	let key = rcgen::KeyPair::generate().unwrap();
+	let cert = rcgen::CertificateParams::new(vec!["localhost".to_string()])
+		.unwrap()
+		.self_signed(&key)
+		.unwrap();
+	let server = rustls::ServerConfig::builder_with_provider(crate::transport::tls::provider())
+		.with_safe_default_protocol_versions()
+		.unwrap()
+		.with_no_client_auth()
+		.with_single_cert(
+			vec![cert.der().clone()],
+			rustls::pki_types::PrivatePkcs8KeyDer::from(key.serialize_der()).into(),
+		)
+		.unwrap();
+	let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(server));
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let addr = listener.local_addr().unwrap();
+	let connections = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+	let counter = connections.clone();
+	tokio::spawn(async move {
+		loop {
+			let (stream, _) = listener.accept().await.unwrap();
+			counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+			let acceptor = acceptor.clone();
+			tokio::spawn(async move {
+				let Ok(tls) = acceptor.accept(stream).await else {
+					return;
+				};
+				let svc = hyper::service::service_fn(|_req| async {
+					Ok::<_, std::convert::Infallible>(::http::Response::new(crate::http::Body::empty()))
+				});
+				let _ = hyper::server::conn::http1::Builder::new()
+					.serve_connection(hyper_util::rt::TokioIo::new(tls), svc)
+					.await;
+			});
+		}
+	});
+	(addr, connections)
+}
+
+#[tokio::test]
+async fn test_reload_reuses_backend_tls_connections() {
+	let (addr, connections) = counting_tls_server().await;
+	let client = test_client();
+	let manager = crate::resource_manager::ResourceManager::new(client.clone()).unwrap();
+	let call = |tls: crate::http::backendtls::BackendTLS| {
+		let client = client.clone();
+		async move {
+			let req = ::http::Request::builder()
+				.uri(format!("https://{addr}/"))
+				.body(crate::http::Body::empty())
+				.unwrap();
+			let resp = client
+				.call(client::Call {
+					req,
+					target: Target::Address(addr),
+					transport: Some(tls.base_config()).into(),
+				})
+				.await
+				.unwrap();
+			assert_eq!(resp.status(), ::http::StatusCode::OK);
+			crate::http::read_body_with_limit(resp.into_body(), 1024)
+				.await
+				.unwrap();
+		}
+	};
+	let backend = addr.to_string();
+
+	let initial = normalize_managed(&manager, &reload_tls_config(&backend, "/a", "localhost")).await;
+	call(reloaded_backend_tls(&initial)).await;
+	assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+	// An unrelated change keeps the pooled connection to the backend.
+	let reloaded = normalize_managed(&manager, &reload_tls_config(&backend, "/b", "localhost")).await;
+	call(reloaded_backend_tls(&reloaded)).await;
+	assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+	// Changing the backend's TLS settings needs a new connection.
+	let changed = normalize_managed(&manager, &reload_tls_config(&backend, "/b", "other")).await;
+	call(reloaded_backend_tls(&changed)).await;
+	assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
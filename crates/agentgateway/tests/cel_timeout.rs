@@ -0,0 +1,32 @@
+/// The CEL context is process-global and configured once from the config, so the evaluation time
+/// budget is tested in its own binary rather than alongside tests that use the default context.
+use agentgateway::cel::{Executor, Expression};
+
+#[test]
+fn cel_function_timeout_from_config_bounds_evaluation() {
+	agentgateway::config::parse_config(
+		r#"
+config:
+  celFunctionTimeout: 1ms
+"#
+		.to_string(),
+		None,
+	)
+	.expect("config should parse");
+
+	let list = format!(
+		"[{}]",
+		(0..20).map(|i| i.to_string()).collect::<Vec<_>>().join(",")
+	);
+	let slow = Expression::new_strict(format!(
+		"{list}.map(a, {list}.map(b, {list}.map(c, {list}.map(d, a + b + c + d)))).size()"
+	))
+	.unwrap();
+	let exec = Executor::new_empty();
+	let err = exec.eval(&slow).unwrap_err();
+	assert!(err.to_string().contains("time budget"), "{err}");
+
+	// The budget applies to each evaluation, so cheap expressions still succeed afterwards.
+	let cheap = Expression::new_strict(format!("{list}.exists(x, x == 10)")).unwrap();
+	assert_eq!(exec.eval(&cheap).unwrap(), true.into());
+}
@@ -265,6 +265,108 @@ async fn tracing_exports_to_otel_trace_mock() {
 	assert_eq!(exports.load(Ordering::SeqCst), 1);
 }
 
+struct NoopTraceHandler;
+
+#[async_trait::async_trait]
+impl oteltracemock::Handler for NoopTraceHandler {
+	async fn export(
+		&mut self,
+		_request: &opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest,
+	) -> Result<
+		opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceResponse,
+		tonic::Status,
+	> {
+		oteltracemock::ok_response()
+	}
+}
+
+/// Sends a request through a gateway with tracing enabled, with `tracing` merged into the tracing
+/// policy, returning the headers the upstream received.
+async fn traced_upstream_headers(tracing: Value, headers: &[(&str, &str)]) -> HeaderMap {
+	let otel = oteltracemock::OtelTraceMock::new(|| NoopTraceHandler)
+		.spawn()
+		.await;
+	let (_mock, mut bind, io) = basic_setup().await;
+	let mut policy = json!({
+		"host": otel.address.to_string(),
+		"randomSampling": true
+	});
+	policy
+		.as_object_mut()
+		.unwrap()
+		.extend(tracing.as_object().cloned().unwrap_or_default());
+	bind
+		.attach_frontend_policy(json!({ "tracing": policy }))
+		.await;
+
+	let res = send_request_headers(io, Method::GET, "http://lo", headers).await;
+	assert_eq!(res.status(), 200);
+	read_body(res.into_body()).await.headers
+}
+
+async fn upstream_trace_headers(headers: &[(&str, &str)]) -> (String, Option<String>) {
+	let headers = traced_upstream_headers(json!({}), headers).await;
+	let header = |name: &str| headers.get(name).map(|v| v.to_str().unwrap().to_string());
+	(
+		header("traceparent").expect("upstream should receive a traceparent"),
+		header("tracestate"),
+	)
+}
+
+#[tokio::test]
+async fn incoming_traceparent_is_continued_upstream() {
+	let incoming = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+	let (traceparent, tracestate) =
+		upstream_trace_headers(&[("traceparent", incoming), ("tracestate", "vendor=value")]).await;
+
+	let [version, trace_id, span_id, flags] = traceparent.split('-').collect::<Vec<_>>()[..] else {
+		panic!("malformed traceparent {traceparent}");
+	};
+	assert_eq!(version, "00");
+	assert_eq!(trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+	// The upstream request is a child span of the incoming one.
+	assert_eq!(span_id.len(), 16);
+	assert_ne!(span_id, "00f067aa0ba902b7");
+	assert_eq!(flags, "01");
+	assert_eq!(tracestate.as_deref(), Some("vendor=value"));
+}
+
+#[tokio::test]
+async fn traceparent_is_generated_when_absent() {
+	let (traceparent, tracestate) = upstream_trace_headers(&[("tracestate", "vendor=stale")]).await;
+
+	let [version, trace_id, span_id, flags] = traceparent.split('-').collect::<Vec<_>>()[..] else {
+		panic!("malformed traceparent {traceparent}");
+	};
+	assert_eq!(version, "00");
+	assert_eq!(trace_id.len(), 32);
+	assert_ne!(trace_id, "0".repeat(32));
+	assert_eq!(span_id.len(), 16);
+	assert_eq!(flags, "01");
+	// A tracestate without a traceparent does not belong to the new trace.
+	assert_eq!(tracestate, None);
+}
+
+#[tokio::test]
+async fn baggage_is_filtered_to_allowed_keys() {
+	let headers = traced_upstream_headers(
+		json!({ "baggage": ["tenant"] }),
+		&[("baggage", "tenant=acme,internal.user=alice")],
+	)
+	.await;
+	assert_eq!(
+		headers.get("baggage").map(|v| v.to_str().unwrap()),
+		Some("tenant=acme")
+	);
+
+	let headers = traced_upstream_headers(
+		json!({ "baggage": ["tenant"] }),
+		&[("baggage", "internal.user=alice")],
+	)
+	.await;
+	assert_eq!(headers.get("baggage"), None);
+}
+
 #[tokio::test]
 async fn multiple_requests() {
 	let (_mock, _bind, io) = basic_setup().await;
@@ -410,6 +512,7 @@ async fn grpc_status_trailer_is_available_to_access_log_cel() {
 			version: Some(Version::HTTP_2),
 			..Default::default()
 		})],
+		metadata: Default::default(),
 	})
 	.with_bind(simple_bind())
 	.with_route(basic_route(backend));
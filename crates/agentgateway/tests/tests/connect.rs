@@ -1229,6 +1229,7 @@ async fn incoming_connect_applies_backend_tls() {
 				Target::Address(*mock.address()),
 			),
 			inline_policies: vec![BackendTrafficPolicy::BackendTLS(backend_tls)],
+			metadata: Default::default(),
 		})
 		.with_bind(simple_bind())
 		.with_route(basic_route(*mock.address()));
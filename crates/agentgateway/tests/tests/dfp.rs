@@ -82,6 +82,7 @@ async fn dfp_rejects_inference_routing() {
 				failure_mode: ext_proc::FailureMode::FailClosed,
 			},
 		)],
+		metadata: Default::default(),
 	};
 
 	let route = basic_named_route("/dynamic".into());
@@ -76,6 +76,9 @@ async fn llm_detect_mode_passthrough_without_rewrite() {
 		path_override: None,
 		path_prefix: None,
 		tokenize: false,
+		tokenizer: None,
+		response_buffer_limit: None,
+		client_certificate: None,
 		policies: serde_json::from_value(json!({
 			"ai": {
 				"routes": {
@@ -141,6 +144,9 @@ async fn llm_detect_mode_respects_model_rewrite() {
 		path_override: None,
 		path_prefix: None,
 		tokenize: false,
+		tokenizer: None,
+		response_buffer_limit: None,
+		client_certificate: None,
 		policies: serde_json::from_value(json!({
 			"ai": {
 				"routes": {
@@ -513,6 +519,9 @@ async fn llm_custom_rerank() {
 		path_override: None,
 		path_prefix: None,
 		tokenize: false,
+		tokenizer: None,
+		response_buffer_limit: None,
+		client_certificate: None,
 		policies: serde_json::from_value(json!({
 			"ai": {"routes": {"/v1/rerank": "rerank"}}
 		}))
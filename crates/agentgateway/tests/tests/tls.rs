@@ -49,6 +49,7 @@ async fn serve_https_http1_connection(
 			key: None,
 			root: Some(include_bytes!("../../../../examples/mcp-tls/certs/ca-cert.pem").to_vec()),
 			hostname: Some(sni.to_string()),
+			verify_hostname: None,
 			insecure: false,
 			insecure_host: true,
 			alpn: None,
@@ -229,6 +230,7 @@ async fn tls_backend_connection() {
 				Target::Address(*mock.address()),
 			),
 			inline_policies: vec![BackendTrafficPolicy::BackendTLS(backend_tls)],
+			metadata: Default::default(),
 		})
 		.with_bind(simple_bind())
 		.with_route(basic_route(*mock.address()));
@@ -263,6 +265,7 @@ async fn tls_backend_connection_alpn() {
 				Target::Address(*mock.address()),
 			),
 			inline_policies: vec![BackendTrafficPolicy::BackendTLS(backend_tls)],
+			metadata: Default::default(),
 		})
 		.with_bind(simple_bind())
 		.with_route(basic_route(*mock.address()));
@@ -311,6 +314,7 @@ async fn tls_backend_http2_version() {
 				BackendTrafficPolicy::BackendTLS(backend_tls),
 				BackendTrafficPolicy::HTTP(backend_version),
 			],
+			metadata: Default::default(),
 		})
 		.with_bind(simple_bind())
 		.with_route(basic_route(*mock.address()));
@@ -353,6 +357,7 @@ async fn tls_backend_http1_version() {
 				BackendTrafficPolicy::BackendTLS(backend_tls),
 				BackendTrafficPolicy::HTTP(backend_version),
 			],
+			metadata: Default::default(),
 		})
 		.with_bind(simple_bind())
 		.with_route(basic_route(*mock.address()));
@@ -396,6 +401,7 @@ async fn tls_backend_version_with_alpn() {
 				BackendTrafficPolicy::BackendTLS(backend_tls),
 				BackendTrafficPolicy::HTTP(backend_version),
 			],
+			metadata: Default::default(),
 		})
 		.with_bind(simple_bind())
 		.with_route(basic_route(*mock.address()));
@@ -411,6 +417,43 @@ async fn tls_backend_version_with_alpn() {
 	assert_eq!(read_body(res.into_body()).await.version, Version::HTTP_11);
 }
 
+#[tokio::test]
+#[cfg(feature = "tls-aws-lc")]
+async fn tls_backend_verify_hostname() {
+	let (mock, certs) = tls_mock().await;
+	let send = async |verify_hostname: Option<&str>| {
+		// The SNI does not match the certificate, as when fronting a backend through a CDN.
+		let backend_tls = agentgateway::http::backendtls::ResolvedBackendTLS {
+			root: Some(certs.root_cert.pem().into_bytes()),
+			hostname: Some("cdn.example.com".to_string()),
+			verify_hostname: verify_hostname.map(str::to_string),
+			..Default::default()
+		}
+		.try_into()
+		.unwrap();
+		let t = setup_proxy_test("{}")
+			.unwrap()
+			.with_raw_backend(BackendWithPolicies {
+				backend: Backend::Opaque(
+					ResourceName::new(strng::format!("{}", mock.address()), "".into()),
+					Target::Address(*mock.address()),
+				),
+				inline_policies: vec![BackendTrafficPolicy::BackendTLS(backend_tls)],
+				metadata: Default::default(),
+			})
+			.with_bind(simple_bind())
+			.with_route(basic_route(*mock.address()));
+		send_http_version(&t, Version::HTTP_11).await.status()
+	};
+
+	assert_eq!(send(None).await, StatusCode::SERVICE_UNAVAILABLE);
+	assert_eq!(
+		send(Some("other.example.com")).await,
+		StatusCode::SERVICE_UNAVAILABLE
+	);
+	assert_eq!(send(Some("localhost")).await, StatusCode::OK);
+}
+
 async fn send_http_version(t: &TestBind, v: Version) -> Response {
 	let io = if v == Version::HTTP_11 {
 		t.serve_http(strng::new("bind"))
@@ -80,6 +80,14 @@ use syn::{
 ///   // Accessing: claims.foo will look up metadata.field("foo") if "foo" is not a direct field
 ///   ```
 ///
+///   Structs with flattened fields do not get a `DYNAMIC_FIELDS` constant (see "Generated items"),
+///   as their keys are open-ended.
+///
+///   By default a flattened key silently overwrites any earlier key of the same name. Add
+///   `#[dynamic(strict_flatten)]` on the struct to panic on such collisions (between two
+///   flattened fields, or a flattened field and a direct field) when materializing in debug
+///   builds. Release builds keep the last-write-wins behavior.
+///
 /// ## Variant-level attributes (Unit Enums Only)
 ///
 /// - `#[dynamic(rename = "name")]` - Use a different string value for this variant
@@ -135,6 +143,14 @@ use syn::{
 ///   }
 ///   ```
 ///
+/// # Generated items
+///
+/// Besides the trait impls, named structs without flattened fields get an inherent
+/// `DYNAMIC_FIELDS: &'static [&'static str]` constant listing the CEL names of their fields (after
+/// renames, excluding skipped fields), for validating field accesses ahead of evaluation.
+///
+/// # Examples
+///
 /// ```rust,ignore
 /// use cel::DynamicType;
 ///
@@ -325,6 +341,7 @@ fn derive_for_named_struct(
 
 	// Get struct-level rename_all setting
 	let rename_all = get_rename_all(&input.attrs);
+	let strict_flatten = has_field_attr(&input.attrs, "strict_flatten");
 
 	// Filter and process fields
 	let processed_fields: Result<Vec<_>, syn::Error> = fields
@@ -509,9 +526,33 @@ fn derive_for_named_struct(
 				_is_flatten,
 				_skip_serializing_if,
 			)| {
-				quote! {
-						// Materialize the flattened field directly into the map
-						#crate_path::types::dynamic::DynamicFlatten::materialize_into(&self.#ident, __cel_map);
+				if strict_flatten {
+					quote! {
+							// Materialize the flattened field directly into the map; in debug builds, go through
+							// a scratch map first so collisions with earlier keys are caught.
+							#[cfg(not(debug_assertions))]
+							#crate_path::types::dynamic::DynamicFlatten::materialize_into(&self.#ident, __cel_map);
+							#[cfg(debug_assertions)]
+							{
+									let mut __cel_flat = ::vector_map::VecMap::new();
+									#crate_path::types::dynamic::DynamicFlatten::materialize_into(&self.#ident, &mut __cel_flat);
+									for (__cel_k, __cel_v) in __cel_flat {
+											assert!(
+													!__cel_map.contains_key(&__cel_k),
+													"{}: flattened field `{}` duplicates key `{}`",
+													stringify!(#name),
+													stringify!(#ident),
+													__cel_k,
+											);
+											__cel_map.insert(__cel_k, __cel_v);
+									}
+							}
+					}
+				} else {
+					quote! {
+							// Materialize the flattened field directly into the map
+							#crate_path::types::dynamic::DynamicFlatten::materialize_into(&self.#ident, __cel_map);
+					}
 				}
 			},
 		)
@@ -625,7 +666,22 @@ fn derive_for_named_struct(
 	let generics = &input.generics;
 	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+	// Flattened fields contribute open-ended keys, so only list field names when they are exhaustive
+	let field_names = if flatten_fields.is_empty() {
+		let names = normal_fields.iter().map(|(_ident, name, ..)| name);
+		quote! {
+				impl #impl_generics #name #ty_generics #where_clause {
+						#[allow(dead_code)]
+						pub const DYNAMIC_FIELDS: &'static [&'static str] = &[#(#names),*];
+				}
+		}
+	} else {
+		quote! {}
+	};
+
 	let generated = quote! {
+			#field_names
+
 			impl #impl_generics #crate_path::types::dynamic::DynamicType for #name #ty_generics #where_clause {
 					fn materialize(&self) -> #crate_path::Value<'_> {
 							let mut m = ::vector_map::VecMap::with_capacity(#field_count);
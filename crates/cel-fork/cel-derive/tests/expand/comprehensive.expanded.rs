@@ -4,6 +4,10 @@ pub struct BasicStruct {
     age: i32,
     active: bool,
 }
+impl BasicStruct {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &["name", "age", "active"];
+}
 impl ::cel::types::dynamic::DynamicType for BasicStruct {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(3usize);
@@ -84,6 +88,14 @@ pub struct WithOption {
     optional: Option<String>,
     nested_optional: Option<i32>,
 }
+impl WithOption {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &[
+        "required",
+        "optional",
+        "nested_optional",
+    ];
+}
 impl ::cel::types::dynamic::DynamicType for WithOption {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(3usize);
@@ -178,6 +190,10 @@ pub struct WithSkip {
     #[dynamic(skip)]
     internal_field: u64,
 }
+impl WithSkip {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &["public_field"];
+}
 impl ::cel::types::dynamic::DynamicType for WithSkip {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(1usize);
@@ -217,6 +233,14 @@ pub struct WithSkipSerializingIf<'a> {
     #[serde(skip_serializing_if = "str::is_empty")]
     name: &'a str,
 }
+impl<'a> WithSkipSerializingIf<'a> {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &[
+        "required",
+        "optional",
+        "name",
+    ];
+}
 impl<'a> ::cel::types::dynamic::DynamicType for WithSkipSerializingIf<'a> {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(3usize);
@@ -286,6 +310,10 @@ pub struct RenameAllCamel {
     user_name: String,
     user_age: i32,
 }
+impl RenameAllCamel {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &["userName", "userAge"];
+}
 impl ::cel::types::dynamic::DynamicType for RenameAllCamel {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(2usize);
@@ -333,6 +361,10 @@ pub struct RenameAllLower {
     UserName: String,
     UserAge: i32,
 }
+impl RenameAllLower {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &["username", "userage"];
+}
 impl ::cel::types::dynamic::DynamicType for RenameAllLower {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(2usize);
@@ -382,6 +414,14 @@ pub struct IndividualRename {
     last_name: String,
     age: i32,
 }
+impl IndividualRename {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &[
+        "firstName",
+        "lastName",
+        "age",
+    ];
+}
 impl ::cel::types::dynamic::DynamicType for IndividualRename {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(3usize);
@@ -444,6 +484,10 @@ pub struct SerdeBasic {
     #[serde(rename = "custom_name")]
     original_name: String,
 }
+impl SerdeBasic {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &["required", "custom_name"];
+}
 impl ::cel::types::dynamic::DynamicType for SerdeBasic {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(2usize);
@@ -493,6 +537,14 @@ pub struct SerdeMultiArgs {
     user_id: Option<i32>,
     normal_field: String,
 }
+impl SerdeMultiArgs {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &[
+        "apiKey",
+        "userId",
+        "normal_field",
+    ];
+}
 impl ::cel::types::dynamic::DynamicType for SerdeMultiArgs {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(3usize);
@@ -562,6 +614,10 @@ pub struct SerdeRenameAll {
     user_name: String,
     user_id: i32,
 }
+impl SerdeRenameAll {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &["userName", "userId"];
+}
 impl ::cel::types::dynamic::DynamicType for SerdeRenameAll {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(2usize);
@@ -668,6 +724,15 @@ pub struct WithMixedAnnotations {
     #[dynamic(rename = "dynamicWins")]
     both_rename: String,
 }
+impl WithMixedAnnotations {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &[
+        "userName",
+        "serdeCustom",
+        "dynamicCustom",
+        "dynamicWins",
+    ];
+}
 impl ::cel::types::dynamic::DynamicType for WithMixedAnnotations {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(4usize);
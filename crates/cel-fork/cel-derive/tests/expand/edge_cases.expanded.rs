@@ -12,6 +12,10 @@ pub struct WithHelpers<'a> {
     #[dynamic(with_value = "to_value")]
     method: &'a str,
 }
+impl<'a> WithHelpers<'a> {
+    #[allow(dead_code)]
+    pub const DYNAMIC_FIELDS: &'static [&'static str] = &["custom", "method"];
+}
 impl<'a> ::cel::types::dynamic::DynamicType for WithHelpers<'a> {
     fn materialize(&self) -> ::cel::Value<'_> {
         let mut m = ::vector_map::VecMap::with_capacity(2usize);
@@ -1,7 +1,10 @@
+use std::cell::{Cell, OnceCell};
 use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
 
 use hashbrown::Equivalent;
 
+use crate::ExecutionError;
 use crate::common::ast::OptimizedExpr;
 use crate::functions;
 use crate::magic::{Function, IntoFunction};
@@ -34,6 +37,26 @@ use crate::objects::{KeyRef, MapValue, TryIntoValue, Value};
 pub struct Context {
 	pub functions: BTreeMap<String, Function>,
 	pub qualified_functions: hashbrown::HashMap<(String, String), Function>,
+	/// Time budget for a single evaluation. See [`Context::with_function_timeout`].
+	pub function_timeout: Option<Duration>,
+}
+
+thread_local! {
+	// Evaluation is synchronous, so the active deadline can live on the evaluating thread rather than
+	// in the (shared) context.
+	static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Keeps an evaluation deadline active until dropped. See [`Context::start_evaluation`].
+#[must_use]
+pub struct DeadlineGuard {
+	prev: Option<Instant>,
+}
+
+impl Drop for DeadlineGuard {
+	fn drop(&mut self) {
+		DEADLINE.set(self.prev);
+	}
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -62,6 +85,39 @@ impl Context {
 			.insert(name.to_string(), value.into_function());
 	}
 
+	/// Bounds each evaluation to `timeout`. Functions can't be interrupted, so this is checked
+	/// cooperatively after each function call and on each comprehension iteration; exceeding it
+	/// fails the evaluation with [`ExecutionError::FunctionError`].
+	pub fn with_function_timeout(mut self, timeout: Duration) -> Self {
+		self.function_timeout = Some(timeout);
+		self
+	}
+
+	/// Starts the deadline for an evaluation, if a timeout is configured. [`Program::execute`]
+	/// does this automatically; callers resolving expressions directly should hold the guard for
+	/// the duration of the evaluation. A nested evaluation never extends an outer deadline.
+	///
+	/// [`Program::execute`]: crate::Program::execute
+	pub fn start_evaluation(&self) -> Option<DeadlineGuard> {
+		let deadline = Instant::now() + self.function_timeout?;
+		let prev = DEADLINE.get();
+		DEADLINE.set(Some(prev.map_or(deadline, |p| p.min(deadline))));
+		Some(DeadlineGuard { prev })
+	}
+
+	pub(crate) fn check_deadline(&self, function: &str) -> Result<(), ExecutionError> {
+		if self.function_timeout.is_none() {
+			return Ok(());
+		}
+		match DEADLINE.get() {
+			Some(deadline) if Instant::now() > deadline => Err(ExecutionError::function_error(
+				function,
+				"evaluation exceeded its time budget",
+			)),
+			_ => Ok(()),
+		}
+	}
+
 	pub fn add_function_direct(&mut self, name: &str, value: Function) {
 		self.functions.insert(name.to_string(), value);
 	}
@@ -81,6 +137,7 @@ impl Default for Context {
 		let mut ctx = Context {
 			functions: Default::default(),
 			qualified_functions: Default::default(),
+			function_timeout: None,
 		};
 
 		ctx.add_function("contains", functions::contains);
@@ -233,3 +290,58 @@ impl<'a> VariableResolver<'a> for MapResolver<'a> {
 		Some(Value::Map(MapValue::Borrow(variables)))
 	}
 }
+
+/// LazyResolver resolves each variable with a closure that runs on its first access, caching the
+/// result. This avoids building expensive variables that an expression never references.
+#[derive(Default)]
+pub struct LazyResolver<'a> {
+	variables: HashMap<&'a str, LazyVariable<'a>>,
+}
+
+struct LazyVariable<'a> {
+	compute: Cell<Option<Box<dyn FnOnce() -> Value<'a> + 'a>>>,
+	value: OnceCell<Value<'a>>,
+}
+
+impl<'a> LazyVariable<'a> {
+	fn get(&self) -> &Value<'a> {
+		self.value.get_or_init(|| {
+			self
+				.compute
+				.take()
+				.map(|compute| compute())
+				.unwrap_or(Value::Null)
+		})
+	}
+}
+
+impl<'a> LazyResolver<'a> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn add_variable(&mut self, name: &'a str, compute: impl FnOnce() -> Value<'a> + 'a) {
+		self.variables.insert(
+			name,
+			LazyVariable {
+				compute: Cell::new(Some(Box::new(compute))),
+				value: OnceCell::new(),
+			},
+		);
+	}
+}
+
+impl<'a> VariableResolver<'a> for LazyResolver<'a> {
+	fn resolve(&self, expr: &str) -> Option<Value<'a>> {
+		self.variables.get(expr).map(|v| v.get().clone())
+	}
+
+	fn variables(&self) -> Option<Value<'a>> {
+		let variables = self
+			.variables
+			.iter()
+			.map(|(k, v)| (KeyRef::String((*k).into()), v.get().clone()))
+			.collect();
+		Some(Value::Map(MapValue::Borrow(variables)))
+	}
+}
@@ -208,10 +208,12 @@ impl Program {
 		context: &'vars Context,
 		vars: &'rf dyn VariableResolver<'vars>,
 	) -> ResolveResult<'a> {
+		let _deadline = context.start_evaluation();
 		Value::resolve(&self.expression, context, vars)
 	}
 
 	pub fn execute<'a>(&'a self, context: &'a Context) -> ResolveResult<'a> {
+		let _deadline = context.start_evaluation();
 		Value::resolve(&self.expression, context, &DefaultVariableResolver)
 	}
 
@@ -318,10 +320,20 @@ mod tests {
 		// Test that we can index into an array
 		assert_output("arr[0] == 1", Ok(true.into()));
 
-		// Test that we can index into a string
+		// Test that strings cannot be indexed, by byte or by character
 		assert_output(
 			"str[0]",
-			Err(ExecutionError::NoSuchKey("0".to_string().into())),
+			Err(ExecutionError::UnsupportedIndex(
+				"foobar".into(),
+				0i64.into(),
+			)),
+		);
+		assert_output(
+			"str[0u]",
+			Err(ExecutionError::UnsupportedIndex(
+				"foobar".into(),
+				0u64.into(),
+			)),
 		);
 	}
 
@@ -237,15 +237,15 @@ impl PartialEq for Value<'_> {
 				.try_into()
 				.map(|a: u64| a == *b)
 				.unwrap_or(false),
-			(Value::Int(a), Value::Float(b)) => (*a as f64) == *b,
+			(Value::Int(a), Value::Float(b)) => cmp_int_float(*a, *b) == Some(Ordering::Equal),
 			(Value::UInt(a), Value::Int(b)) => a
 				.to_owned()
 				.try_into()
 				.map(|a: i64| a == *b)
 				.unwrap_or(false),
-			(Value::UInt(a), Value::Float(b)) => (*a as f64) == *b,
-			(Value::Float(a), Value::Int(b)) => *a == (*b as f64),
-			(Value::Float(a), Value::UInt(b)) => *a == (*b as f64),
+			(Value::UInt(a), Value::Float(b)) => cmp_uint_float(*a, *b) == Some(Ordering::Equal),
+			(Value::Float(a), Value::Int(b)) => cmp_int_float(*b, *a) == Some(Ordering::Equal),
+			(Value::Float(a), Value::UInt(b)) => cmp_uint_float(*b, *a) == Some(Ordering::Equal),
 			(Value::Object(a), Value::Object(b)) => a.eq(b),
 			(Value::Type(a), Value::Type(b)) => a == b,
 			(_, _) => false,
@@ -280,7 +280,7 @@ impl PartialOrd for Value<'_> {
 					// If the i64 doesn't fit into a u64 it must be less than 0.
 					.unwrap_or(Ordering::Less),
 			),
-			(Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+			(Value::Int(a), Value::Float(b)) => cmp_int_float(*a, *b),
 			(Value::UInt(a), Value::Int(b)) => Some(
 				a.to_owned()
 					.try_into()
@@ -288,14 +288,56 @@ impl PartialOrd for Value<'_> {
 					// If the u64 doesn't fit into a i64 it must be greater than i64::MAX.
 					.unwrap_or(Ordering::Greater),
 			),
-			(Value::UInt(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
-			(Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
-			(Value::Float(a), Value::UInt(b)) => a.partial_cmp(&(*b as f64)),
+			(Value::UInt(a), Value::Float(b)) => cmp_uint_float(*a, *b),
+			(Value::Float(a), Value::Int(b)) => cmp_int_float(*b, *a).map(Ordering::reverse),
+			(Value::Float(a), Value::UInt(b)) => cmp_uint_float(*b, *a).map(Ordering::reverse),
 			_ => None,
 		}
 	}
 }
 
+// 2^63 and 2^64 are exactly representable as f64, unlike i64::MAX and u64::MAX.
+const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+const TWO_POW_64: f64 = 18_446_744_073_709_551_616.0;
+
+/// Compares an int to a double by mathematical value, rather than by casting the int to a double
+/// (which rounds above 2^53). Returns None if `b` is NaN.
+fn cmp_int_float(a: i64, b: f64) -> Option<Ordering> {
+	if b.is_nan() {
+		return None;
+	}
+	if b >= TWO_POW_63 {
+		return Some(Ordering::Less);
+	}
+	if b < -TWO_POW_63 {
+		return Some(Ordering::Greater);
+	}
+	// b is within i64 range, so its integral part converts exactly.
+	let whole = b.trunc();
+	Some(
+		a.cmp(&(whole as i64))
+			.then_with(|| 0.0_f64.partial_cmp(&(b - whole)).unwrap_or(Ordering::Equal)),
+	)
+}
+
+/// Compares a uint to a double by mathematical value. Returns None if `b` is NaN.
+fn cmp_uint_float(a: u64, b: f64) -> Option<Ordering> {
+	if b.is_nan() {
+		return None;
+	}
+	if b >= TWO_POW_64 {
+		return Some(Ordering::Less);
+	}
+	if b < 0.0 {
+		return Some(Ordering::Greater);
+	}
+	let whole = b.trunc();
+	Some(
+		a.cmp(&(whole as u64))
+			.then_with(|| 0.0_f64.partial_cmp(&(b - whole)).unwrap_or(Ordering::Equal)),
+	)
+}
+
 impl Debug for Value<'_> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		match self {
@@ -526,7 +568,16 @@ impl<'a> Value<'a> {
 			},
 			Value::Map(m) => match m {
 				MapValue::Owned(map) => Value::Map(MapValue::Owned(map.clone())),
-				MapValue::Borrow(_) => Value::Map(MapValue::Owned(Arc::new(m.iter_owned().collect()))),
+				MapValue::Borrow(map) if map.len() > crate::types::map::ORDERED_MAP_MAX_LEN => {
+					Value::Map(MapValue::Owned(Arc::new(m.iter_owned().collect())))
+				},
+				// Keep small borrowed maps as a VecMap, so their field order survives.
+				MapValue::Borrow(map) => Value::Map(MapValue::Borrow(
+					map
+						.iter()
+						.map(|(k, v)| (KeyRef::from(Key::from(k)), v.as_static()))
+						.collect(),
+				)),
 			},
 			Value::Int(i) => Value::Int(*i),
 			Value::UInt(u) => Value::UInt(*u),
@@ -605,6 +656,13 @@ impl<'a> Value<'a> {
 				}
 				let left: Value<'a> = resolve(left_op)?;
 				if select.test {
+					if let Value::Dynamic(d) = &left
+						&& d.field(select.field.as_str()).is_some()
+					{
+						// Present without materializing. A `None` may just mean the type doesn't implement
+						// field lookups, so fall through to the materialized check in that case.
+						return Ok(Value::Bool(true));
+					}
 					match left.always_materialize().as_ref() {
 						Value::Map(map) => {
 							let b = map.contains_key(&KeyRef::String(select.field.as_str().into()));
@@ -839,9 +897,6 @@ impl<'a> Value<'a> {
 								Err(ExecutionError::IndexOutOfBounds(idx.into()))
 							}
 						},
-						(Value::String(_), Value::Int(idx)) => {
-							Err(ExecutionError::NoSuchKey(idx.to_string().into()))
-						},
 						(Value::Map(map), Value::String(property)) => map
 							.get(&KeyRef::String(StringValue::Borrowed(&property)))
 							.cloned()
@@ -860,6 +915,8 @@ impl<'a> Value<'a> {
 							.ok_or_else(|| ExecutionError::NoSuchKey(property.to_string().into())),
 						(Value::Map(_), index) => Err(ExecutionError::UnsupportedMapIndex(index.as_static())),
 						(Value::List(_), index) => Err(ExecutionError::UnsupportedListIndex(index.as_static())),
+						// Strings are not indexable, matching CEL: it's ambiguous whether `s[0]` would be a byte
+						// or a character, so the strings extension's `charAt()` and `substring()` should be used instead.
 						(value, index) => Err(ExecutionError::UnsupportedIndex(
 							value.as_static(),
 							index.as_static(),
@@ -928,8 +985,10 @@ impl<'a> Value<'a> {
 						call.func_name.clone().into(),
 					));
 				};
-				let mut ctx = FunctionContext::new(&call.func_name, None, ctx, &call.args, resolver);
-				(func)(&mut ctx)
+				let mut fctx = FunctionContext::new(&call.func_name, None, ctx, &call.args, resolver);
+				let res = (func)(&mut fctx);
+				ctx.check_deadline(&call.func_name)?;
+				res
 			},
 			Some(target) => {
 				let qualified_func = if let Expr::Ident(prefix) = &target.expr {
@@ -939,7 +998,9 @@ impl<'a> Value<'a> {
 				};
 				if let Some(func) = qualified_func {
 					let mut fctx = FunctionContext::new(&call.func_name, None, ctx, &call.args, resolver);
-					return (func)(&mut fctx);
+					let res = (func)(&mut fctx);
+					ctx.check_deadline(&call.func_name)?;
+					return res;
 				}
 				let tgt = Some(resolve(target)?);
 
@@ -948,12 +1009,14 @@ impl<'a> Value<'a> {
 					let ob = ob.clone();
 					let mut fctx = FunctionContext::new(&call.func_name, None, ctx, &call.args, resolver);
 					if let Some(result) = ob.call_function(call.func_name.as_str(), &mut fctx) {
+						ctx.check_deadline(&call.func_name)?;
 						return result;
 					}
 				}
 				if let Some(Value::Dynamic(dynamic)) = &tgt {
 					let mut fctx = FunctionContext::new(&call.func_name, None, ctx, &call.args, resolver);
 					if let Some(result) = dynamic.call_function(call.func_name.as_str(), &mut fctx) {
+						ctx.check_deadline(&call.func_name)?;
 						return result;
 					}
 				}
@@ -967,7 +1030,9 @@ impl<'a> Value<'a> {
 				};
 				let mut fctx =
 					FunctionContext::new(&call.func_name, tgt.clone(), ctx, &call.args, resolver);
-				(func)(&mut fctx)
+				let res = (func)(&mut fctx);
+				ctx.check_deadline(&call.func_name)?;
+				res
 			},
 		}
 	}
@@ -1045,6 +1110,7 @@ impl<'a> Value<'a> {
 		match iter {
 			Value::List(items) => {
 				for item in items.as_ref() {
+					ctx.check_deadline("comprehension")?;
 					let comp_resolver =
 						SingleVarResolver::new(resolver, &comprehension.accu_var, accu.clone());
 					if !Value::resolve(&comprehension.loop_cond, ctx, &comp_resolver)?.to_bool()? {
@@ -1057,6 +1123,7 @@ impl<'a> Value<'a> {
 			},
 			Value::Map(map) => {
 				for key in map.iter_keys() {
+					ctx.check_deadline("comprehension")?;
 					let comp_resolver =
 						SingleVarResolver::new(resolver, &comprehension.accu_var, accu.clone());
 					if !Value::resolve(&comprehension.loop_cond, ctx, &comp_resolver)?.to_bool()? {
@@ -1407,6 +1474,95 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_mixed_numeric_compare() {
+		let context = Context::default();
+		let eval = |expr: &str| Program::compile(expr).unwrap().execute(&context).unwrap();
+
+		for expr in [
+			"1 == 1u",
+			"1u == 1",
+			"1 == 1.0",
+			"1u == 1.0",
+			"1 < 2.0",
+			"2.0 > 1",
+			"1 < 1.5",
+			"2 > 1.5",
+			"-1 < 0u",
+			"1.5 < 2u",
+			"-0.5 < 0u",
+			"-1 != 18446744073709551615u",
+			"response_code >= 400u",
+		] {
+			let program = Program::compile(expr).unwrap();
+			let mut vars = MapResolver::new();
+			vars.add_variable_from_value("response_code", Value::Int(404));
+			assert_eq!(
+				program.execute_with(&context, &vars).unwrap(),
+				true.into(),
+				"{expr}"
+			);
+		}
+
+		// Large magnitudes must not be rounded through f64: 2^63 - 1 and 2^64 - 1 both round up to a
+		// power of two as doubles, but are strictly less than it.
+		assert_eq!(
+			eval("9223372036854775807 == 9223372036854775808.0"),
+			false.into()
+		);
+		assert_eq!(
+			eval("9223372036854775807 < 9223372036854775808.0"),
+			true.into()
+		);
+		assert_eq!(
+			eval("18446744073709551615u == 18446744073709551616.0"),
+			false.into()
+		);
+		assert_eq!(
+			eval("18446744073709551615u < 18446744073709551616.0"),
+			true.into()
+		);
+		assert_eq!(
+			eval("-9223372036854775807 - 1 == -9223372036854775808.0"),
+			true.into()
+		);
+		assert_eq!(eval("-9223372036854775807 - 1 > -1e19"), true.into());
+		assert_eq!(eval("9007199254740993 > 9007199254740992.0"), true.into());
+		assert_eq!(
+			eval("18446744073709551615u > 9223372036854775807"),
+			true.into()
+		);
+		assert_eq!(eval("1e300 > 18446744073709551615u"), true.into());
+
+		let result = Program::compile("1 < double('NaN')")
+			.unwrap()
+			.execute(&context);
+		assert!(result.is_err(), "NaN should not be comparable to ints");
+		assert_eq!(eval("1u == double('NaN')"), false.into());
+	}
+
+	#[test]
+	fn test_function_timeout() {
+		use std::time::Duration;
+
+		let big: Vec<i64> = (0..10_000).collect();
+		let mut vars = MapResolver::new();
+		vars.add_variable_from_value("big", big);
+		let program =
+			Program::compile("big.map(x, big.filter(y, y == x).size()).size() == size(big)").unwrap();
+
+		let ctx = Context::default().with_function_timeout(Duration::from_millis(10));
+		let err = program.execute_with(&ctx, &vars).unwrap_err();
+		assert!(
+			matches!(&err, ExecutionError::FunctionError { message, .. } if message.contains("time budget")),
+			"{err:?}"
+		);
+
+		// The deadline is per evaluation, so a cheap expression still succeeds afterwards.
+		let program = Program::compile("big.exists(x, x == 10)").unwrap();
+		assert_eq!(program.execute_with(&ctx, &vars).unwrap(), true.into());
+	}
+
 	#[test]
 	fn test_float_compare() {
 		let context = Context::default();
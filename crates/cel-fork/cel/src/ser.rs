@@ -12,9 +12,11 @@ use chrono::FixedOffset;
 use serde::Serialize;
 use serde::ser::{self, Impossible, SerializeStruct};
 use thiserror::Error;
+use vector_map::VecMap;
 
 use crate::Value;
-use crate::objects::{BytesValue, Key, ListValue, StringValue};
+use crate::objects::{BytesValue, Key, KeyRef, ListValue, MapValue, StringValue};
+use crate::types::map::ORDERED_MAP_MAX_LEN;
 
 pub struct Serializer;
 pub struct KeySerializer;
@@ -287,9 +289,9 @@ impl ser::Serializer for Serializer {
 		})
 	}
 
-	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
 		Ok(SerializeMap {
-			map: HashMap::new(),
+			map: MapBuilder::with_capacity(len.unwrap_or_default()),
 			next_key: None,
 		})
 	}
@@ -303,11 +305,11 @@ impl ser::Serializer for Serializer {
 		_name: &'static str,
 		_variant_index: u32,
 		variant: &'static str,
-		_len: usize,
+		len: usize,
 	) -> Result<Self::SerializeStructVariant> {
 		Ok(SerializeStructVariant {
 			name: String::from(variant),
-			map: HashMap::new(),
+			map: MapBuilder::with_capacity(len),
 		})
 	}
 }
@@ -323,13 +325,58 @@ pub struct SerializeTupleVariant {
 }
 
 pub struct SerializeMap {
-	map: HashMap<Key, Value<'static>>,
+	map: MapBuilder,
 	next_key: Option<Key>,
 }
 
 pub struct SerializeStructVariant {
 	name: String,
-	map: HashMap<Key, Value<'static>>,
+	map: MapBuilder,
+}
+
+// Small maps are collected into a VecMap, rather than a HashMap, so fields keep the order they
+// were serialized in (for example, when a struct is logged). Once a map grows past
+// ORDERED_MAP_MAX_LEN it is moved into a HashMap, as VecMap inserts are linear.
+enum MapBuilder {
+	Ordered(VecMap<KeyRef<'static>, Value<'static>>),
+	Hashed(hashbrown::HashMap<Key, Value<'static>>),
+}
+
+impl MapBuilder {
+	fn with_capacity(len: usize) -> Self {
+		if len > ORDERED_MAP_MAX_LEN {
+			MapBuilder::Hashed(hashbrown::HashMap::with_capacity(len))
+		} else {
+			MapBuilder::Ordered(VecMap::with_capacity(len))
+		}
+	}
+
+	fn insert(&mut self, key: Key, value: Value<'static>) {
+		match self {
+			MapBuilder::Ordered(map) if map.len() < ORDERED_MAP_MAX_LEN => {
+				map.insert(KeyRef::from(key), value);
+			},
+			MapBuilder::Ordered(map) => {
+				let mut hashed: hashbrown::HashMap<Key, Value<'static>> =
+					std::mem::replace(map, VecMap::new())
+						.into_iter()
+						.map(|(k, v)| (Key::from(&k), v))
+						.collect();
+				hashed.insert(key, value);
+				*self = MapBuilder::Hashed(hashed);
+			},
+			MapBuilder::Hashed(map) => {
+				map.insert(key, value);
+			},
+		}
+	}
+
+	fn into_value(self) -> Value<'static> {
+		match self {
+			MapBuilder::Ordered(map) => Value::Map(MapValue::Borrow(map)),
+			MapBuilder::Hashed(map) => Value::Map(MapValue::Owned(Arc::new(map))),
+		}
+	}
 }
 
 #[derive(Debug, Default)]
@@ -431,7 +478,7 @@ impl ser::SerializeMap for SerializeMap {
 	}
 
 	fn end(self) -> Result<Self::Ok> {
-		Ok(self.map.into())
+		Ok(self.map.into_value())
 	}
 }
 
@@ -466,7 +513,7 @@ impl ser::SerializeStructVariant for SerializeStructVariant {
 	}
 
 	fn end(self) -> Result<Self::Ok> {
-		let map: HashMap<String, Value> = HashMap::from_iter([(self.name, self.map.into())]);
+		let map: HashMap<String, Value> = HashMap::from_iter([(self.name, self.map.into_value())]);
 		Ok(map.into())
 	}
 }
@@ -898,16 +945,16 @@ impl ser::Serializer for TimeSerializer {
 
 #[cfg(test)]
 mod tests {
-	use std::collections::HashMap;
+	use std::collections::{BTreeMap, HashMap};
 	use std::iter::FromIterator;
 	use std::sync::Arc;
 
 	use serde::Serialize;
 	use serde_bytes::Bytes;
 
-	use super::{Duration, Timestamp};
+	use super::{Duration, ORDERED_MAP_MAX_LEN, Timestamp};
 	use crate::context::MapResolver;
-	use crate::objects::{BytesValue, Key, ListValue};
+	use crate::objects::{BytesValue, Key, ListValue, MapValue};
 	use crate::{Context, Program, Value, to_value};
 
 	macro_rules! primitive_test {
@@ -1154,6 +1201,57 @@ mod tests {
 		assert_eq!(map, expected)
 	}
 
+	#[test]
+	fn test_struct_field_order() {
+		#[derive(Serialize)]
+		struct Ordered {
+			c: i32,
+			a: i32,
+			b: i32,
+		}
+		let value = to_value(Ordered { c: 1, a: 2, b: 3 }).unwrap();
+		let Value::Map(map) = &value else {
+			panic!("expected a map, got {value:?}");
+		};
+		let keys =
+			|map: &crate::objects::MapValue| map.iter_keys().map(|k| k.to_string()).collect::<Vec<_>>();
+		assert_eq!(keys(map), ["c", "a", "b"]);
+		// as_static must not lose the order either
+		let Value::Map(map) = value.as_static() else {
+			unreachable!()
+		};
+		assert_eq!(keys(&map), ["c", "a", "b"]);
+	}
+
+	#[test]
+	fn test_large_maps_are_hashed() {
+		let small: BTreeMap<String, i32> = (0..ORDERED_MAP_MAX_LEN as i32)
+			.map(|i| (format!("k{i}"), i))
+			.collect();
+		let Value::Map(map) = to_value(&small).unwrap() else {
+			unreachable!()
+		};
+		assert!(matches!(map, MapValue::Borrow(_)));
+
+		// A map that grows past the threshold is moved into a HashMap without losing entries.
+		let large: BTreeMap<String, i32> = (0..=ORDERED_MAP_MAX_LEN as i32 * 4)
+			.map(|i| (format!("k{i}"), i))
+			.collect();
+		let Value::Map(map) = to_value(&large).unwrap() else {
+			unreachable!()
+		};
+		let MapValue::Owned(hashed) = &map else {
+			panic!("expected a hashed map");
+		};
+		assert_eq!(hashed.len(), large.len());
+		for (k, v) in &large {
+			assert_eq!(
+				hashed.get(&Key::from(k.as_str())),
+				Some(&Value::Int(*v as i64))
+			);
+		}
+	}
+
 	#[derive(Serialize)]
 	struct TestTimeTypes {
 		dur: Duration,
@@ -514,3 +514,196 @@ fn invalid_functions() {
 	// TODO(https://github.com/cel-rust/cel-rust/issues/269)
 	assert!(Value::resolve(&expr.expression, &ctx, &resolver).is_ok())
 }
+
+#[test]
+fn dynamic_value_string_types() {
+	use std::borrow::Cow;
+	use std::sync::Arc;
+
+	use cel::context::VariableResolver;
+	use cel_derive::DynamicType;
+
+	#[derive(Debug, DynamicType)]
+	struct Names<'a> {
+		cow: Cow<'a, str>,
+		arc: Arc<str>,
+		boxed: Box<str>,
+	}
+	struct Resolver<'a>(&'a Names<'a>);
+	impl<'a> VariableResolver<'a> for Resolver<'a> {
+		fn resolve(&self, variable: &str) -> Option<Value<'a>> {
+			(variable == "names").then(|| Value::Dynamic(DynamicValue::new(self.0)))
+		}
+	}
+
+	let names = Names {
+		cow: Cow::Borrowed("a"),
+		arc: Arc::from("b"),
+		boxed: Box::from("c"),
+	};
+	for field in ["cow", "arc", "boxed"] {
+		let v = DynamicValue::new(&names).field(field).unwrap();
+		// Should be materialized
+		assert!(matches!(&v, Value::String(_)), "{v:?}");
+	}
+
+	let ctx = Context::default();
+	let p = Program::compile("names.cow + '/' + names.arc + '/' + names.boxed").unwrap();
+	let res = Value::resolve(&p.expression, &ctx, &Resolver(&names)).unwrap();
+	assert_eq!(res.json().unwrap(), json!("a/b/c"));
+}
+
+#[test]
+fn dynamic_value_ordered_maps() {
+	use std::collections::BTreeMap;
+
+	use cel::context::VariableResolver;
+	use indexmap::IndexMap;
+
+	struct Resolver<'a> {
+		index: &'a IndexMap<String, String>,
+		btree: &'a BTreeMap<String, i64>,
+	}
+	impl<'a> VariableResolver<'a> for Resolver<'a> {
+		fn resolve(&self, variable: &str) -> Option<Value<'a>> {
+			match variable {
+				"index" => Some(Value::Dynamic(DynamicValue::new(self.index))),
+				"btree" => Some(Value::Dynamic(DynamicValue::new(self.btree))),
+				_ => None,
+			}
+		}
+	}
+
+	let index = IndexMap::from([
+		("z".to_string(), "1".to_string()),
+		("a".to_string(), "2".to_string()),
+		("m".to_string(), "3".to_string()),
+	]);
+	let btree = BTreeMap::from([("z".to_string(), 1), ("a".to_string(), 2)]);
+	let resolver = Resolver {
+		index: &index,
+		btree: &btree,
+	};
+	let ctx = Context::default();
+	let eval = |expr: &str| {
+		let p = Program::compile(expr).unwrap();
+		Value::resolve(&p.expression, &ctx, &resolver)
+			.unwrap()
+			.json()
+			.unwrap()
+	};
+
+	// IndexMap keeps insertion order; BTreeMap iterates sorted.
+	assert_eq!(eval("index.map(k, k)"), json!(["z", "a", "m"]));
+	assert_eq!(eval("btree.map(k, k)"), json!(["a", "z"]));
+	assert_eq!(eval("index.a + index['m']"), json!("23"));
+	assert_eq!(eval("btree.z"), json!(1));
+	assert_eq!(eval("'a' in index && !('b' in btree)"), json!(true));
+
+	let Value::Map(m) = DynamicValue::new(&index).materialize() else {
+		panic!("expected a map");
+	};
+	let keys: Vec<String> = m.iter_keys().map(|k| k.to_string()).collect();
+	assert_eq!(keys, vec!["z", "a", "m"]);
+}
+
+#[derive(Debug, cel_derive::DynamicType)]
+#[dynamic(strict_flatten)]
+struct StrictFlatten {
+	name: String,
+	#[dynamic(flatten)]
+	labels: HashMap<String, String>,
+	#[dynamic(flatten)]
+	annotations: HashMap<String, String>,
+}
+
+#[test]
+fn dynamic_strict_flatten_allows_disjoint_keys() {
+	let v = StrictFlatten {
+		name: "n".to_string(),
+		labels: HashMap::from([("a".to_string(), "1".to_string())]),
+		annotations: HashMap::from([("b".to_string(), "2".to_string())]),
+	};
+	let Value::Map(m) = cel::types::dynamic::DynamicType::materialize(&v) else {
+		panic!("expected a map");
+	};
+	assert_eq!(m.iter_keys().count(), 3);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "flattened field `annotations` duplicates key `a`")]
+fn dynamic_strict_flatten_panics_on_duplicate_key() {
+	let v = StrictFlatten {
+		name: "n".to_string(),
+		labels: HashMap::from([("a".to_string(), "1".to_string())]),
+		annotations: HashMap::from([("a".to_string(), "2".to_string())]),
+	};
+	let _ = cel::types::dynamic::DynamicType::materialize(&v);
+}
+
+#[test]
+fn dynamic_has_respects_skip_serializing_if() {
+	use cel::context::VariableResolver;
+	use cel_derive::DynamicType;
+
+	#[derive(Debug, DynamicType)]
+	struct Obj {
+		name: String,
+		#[dynamic(skip_serializing_if = "Option::is_none")]
+		field: Option<String>,
+	}
+	struct Resolver<'a>(&'a Obj);
+	impl<'a> VariableResolver<'a> for Resolver<'a> {
+		fn resolve(&self, variable: &str) -> Option<Value<'a>> {
+			(variable == "obj").then(|| Value::Dynamic(DynamicValue::new(self.0)))
+		}
+	}
+
+	let ctx = Context::default();
+	let has = |obj: &Obj, expr: &str| {
+		let p = Program::compile(expr).unwrap();
+		Value::resolve(&p.expression, &ctx, &Resolver(obj))
+			.unwrap()
+			.json()
+			.unwrap()
+	};
+
+	let absent = Obj {
+		name: "a".to_string(),
+		field: None,
+	};
+	assert_eq!(has(&absent, "has(obj.field)"), json!(false));
+	assert_eq!(has(&absent, "has(obj.name)"), json!(true));
+	assert_eq!(has(&absent, "has(obj.missing)"), json!(false));
+
+	let present = Obj {
+		name: "a".to_string(),
+		field: Some("b".to_string()),
+	};
+	assert_eq!(has(&present, "has(obj.field)"), json!(true));
+}
+
+#[test]
+fn lazy_resolver_computes_on_first_use() {
+	use std::cell::Cell;
+
+	let ctx = Context::default();
+	let cheap_only = Program::compile("cheap + 1").unwrap();
+	let both = Program::compile("cheap + expensive").unwrap();
+	let twice = Program::compile("expensive * expensive").unwrap();
+	let calls = Cell::new(0);
+	let mut resolver = context::LazyResolver::new();
+	resolver.add_variable("cheap", || Value::from(1));
+	resolver.add_variable("expensive", || {
+		calls.set(calls.get() + 1);
+		Value::from(2)
+	});
+	let eval = |program: &Program| program.execute_with(&ctx, &resolver).unwrap().as_static();
+
+	assert_eq!(eval(&cheap_only), Value::from(2));
+	assert_eq!(calls.get(), 0, "unreferenced variable must not be computed");
+	assert_eq!(eval(&both), Value::from(3));
+	assert_eq!(eval(&twice), Value::from(4));
+	assert_eq!(calls.get(), 1, "computed value must be cached");
+}
@@ -188,6 +188,37 @@ impl DynamicType for String {
 	}
 }
 
+// Owned and shared string types - auto-materialize to String value
+impl DynamicType for std::borrow::Cow<'_, str> {
+	fn auto_materialize(&self) -> bool {
+		true
+	}
+
+	fn materialize(&self) -> Value<'_> {
+		Value::from(self.as_ref())
+	}
+}
+
+impl DynamicType for Arc<str> {
+	fn auto_materialize(&self) -> bool {
+		true
+	}
+
+	fn materialize(&self) -> Value<'_> {
+		Value::from(self.as_ref())
+	}
+}
+
+impl DynamicType for Box<str> {
+	fn auto_materialize(&self) -> bool {
+		true
+	}
+
+	fn materialize(&self) -> Value<'_> {
+		Value::from(self.as_ref())
+	}
+}
+
 // bool - auto-materializes to Bool value
 impl DynamicType for bool {
 	fn auto_materialize(&self) -> bool {
@@ -315,6 +346,82 @@ impl DynamicFlatten for std::collections::HashMap<String, String> {
 	}
 }
 
+// Generic string-keyed maps - materialize to Map value, in the map's iteration order
+fn materialize_map_entries<'a, K, V>(
+	entries: impl Iterator<Item = (&'a K, &'a V)>,
+	map: &mut vector_map::VecMap<crate::objects::KeyRef<'a>, Value<'a>>,
+) where
+	K: std::borrow::Borrow<str> + 'a,
+	V: DynamicType + 'a,
+{
+	for (k, v) in entries {
+		map.insert(
+			crate::objects::KeyRef::from(std::borrow::Borrow::<str>::borrow(k)),
+			maybe_materialize(v),
+		);
+	}
+}
+
+impl<K, V> DynamicType for std::collections::BTreeMap<K, V>
+where
+	K: std::borrow::Borrow<str> + Ord + Debug + Send + Sync,
+	V: DynamicType,
+{
+	fn materialize(&self) -> Value<'_> {
+		let mut map = vector_map::VecMap::with_capacity(self.len());
+		materialize_map_entries(self.iter(), &mut map);
+		Value::Map(crate::objects::MapValue::Borrow(map))
+	}
+
+	fn field(&self, field: &str) -> Option<Value<'_>> {
+		self.get(field).map(maybe_materialize)
+	}
+}
+
+impl<K, V> DynamicFlatten for std::collections::BTreeMap<K, V>
+where
+	K: std::borrow::Borrow<str> + Ord + Debug + Send + Sync,
+	V: DynamicType,
+{
+	fn materialize_into<'a>(
+		&'a self,
+		map: &mut vector_map::VecMap<crate::objects::KeyRef<'a>, Value<'a>>,
+	) {
+		materialize_map_entries(self.iter(), map);
+	}
+}
+
+impl<K, V, S> DynamicType for indexmap::IndexMap<K, V, S>
+where
+	K: std::borrow::Borrow<str> + std::hash::Hash + Eq + Debug + Send + Sync,
+	V: DynamicType,
+	S: std::hash::BuildHasher + Send + Sync,
+{
+	fn materialize(&self) -> Value<'_> {
+		let mut map = vector_map::VecMap::with_capacity(self.len());
+		materialize_map_entries(self.iter(), &mut map);
+		Value::Map(crate::objects::MapValue::Borrow(map))
+	}
+
+	fn field(&self, field: &str) -> Option<Value<'_>> {
+		self.get(field).map(maybe_materialize)
+	}
+}
+
+impl<K, V, S> DynamicFlatten for indexmap::IndexMap<K, V, S>
+where
+	K: std::borrow::Borrow<str> + std::hash::Hash + Eq + Debug + Send + Sync,
+	V: DynamicType,
+	S: std::hash::BuildHasher + Send + Sync,
+{
+	fn materialize_into<'a>(
+		&'a self,
+		map: &mut vector_map::VecMap<crate::objects::KeyRef<'a>, Value<'a>>,
+	) {
+		materialize_map_entries(self.iter(), map);
+	}
+}
+
 impl<T: DynamicType> DynamicType for &T {
 	fn auto_materialize(&self) -> bool {
 		(*self).auto_materialize()
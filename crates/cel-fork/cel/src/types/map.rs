@@ -8,6 +8,10 @@ use hashbrown::Equivalent;
 use crate::Value;
 use crate::objects::StringValue;
 
+/// Largest map kept as an ordered `VecMap`. Lookups and inserts into a `VecMap` are linear, so
+/// larger maps are stored hashed and lose their field order.
+pub(crate) const ORDERED_MAP_MAX_LEN: usize = 32;
+
 #[derive(Debug, Clone)]
 pub enum MapValue<'a> {
 	Owned(Arc<hashbrown::HashMap<Key, Value<'static>>>),
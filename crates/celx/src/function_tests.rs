@@ -102,6 +102,63 @@ fn unvalidated_jwt_payload() {
 	assert_fails(r#"unvalidatedJwtPayload("a.b.c")"#);
 }
 
+#[test]
+fn timestamp_truncate() {
+	assert(
+		json!("2023-05-28T12:34:00Z"),
+		r#"timestamp("2023-05-28T12:34:56.789Z").truncate(duration("1m"))"#,
+	);
+	assert(
+		json!("2023-05-28T12:00:00Z"),
+		r#"timestamp("2023-05-28T12:34:56Z").truncate(duration("1h"))"#,
+	);
+	assert(
+		json!("2023-05-28T12:30:00Z"),
+		r#"timestamp("2023-05-28T12:34:56Z").truncate(duration("15m"))"#,
+	);
+	assert_fails(r#"timestamp("2023-05-28T12:34:56Z").truncate(duration("0s"))"#);
+}
+
+#[test]
+fn timestamp_format() {
+	assert(
+		json!("2023-05-28T12:34:56+00:00"),
+		r#"timestamp("2023-05-28T12:34:56Z").format("%Y-%m-%dT%H:%M:%S%:z")"#,
+	);
+	assert(
+		json!("2023-05-28 12h"),
+		r#"timestamp("2023-05-28T12:34:56Z").format("%Y-%m-%d %Hh")"#,
+	);
+	assert_fails(r#"timestamp("2023-05-28T12:34:56Z").format("%Q")"#);
+}
+
+#[test]
+fn headers() {
+	let headers = r#"{"Content-Type": "text/plain", "set-cookie": ["a=1", "b=2"], "X-Multi": "x", "x-multi": "y"}"#;
+	assert(
+		json!("text/plain"),
+		&format!(r#"{headers}.get("content-type")"#),
+	);
+	assert(
+		json!("text/plain"),
+		&format!(r#"{headers}.get("CONTENT-TYPE")"#),
+	);
+	assert(json!("a=1,b=2"), &format!(r#"{headers}.get("Set-Cookie")"#));
+	assert(json!("x,y"), &format!(r#"{headers}.get("x-multi")"#));
+	assert_fails(&format!(r#"{headers}.get("missing")"#));
+
+	assert(
+		json!(["a=1", "b=2"]),
+		&format!(r#"{headers}.values("SET-COOKIE")"#),
+	);
+	assert(
+		json!(["text/plain"]),
+		&format!(r#"{headers}.values("content-type")"#),
+	);
+	assert(json!([]), &format!(r#"{headers}.values("missing")"#));
+	assert_fails(r#""not a map".values("a")"#);
+}
+
 #[test]
 fn random() {
 	let expr = r#"int(random() * 10.0)"#;
@@ -139,6 +196,28 @@ fn base64() {
 	assert(json!("f"), expr);
 }
 
+#[test]
+fn base64_url() {
+	// 0xfb 0xff encodes to '+/8=' in standard base64
+	let expr = r#"base64.urlEncode(b'\xfb\xff')"#;
+	assert(json!("-_8"), expr);
+	let expr = r#"base64.urlDecode('-_8') == b'\xfb\xff'"#;
+	assert(json!(true), expr);
+	// Padded input is accepted too
+	let expr = r#"base64.urlDecode('-_8=') == b'\xfb\xff'"#;
+	assert(json!(true), expr);
+
+	let expr =
+		r#"base64.urlDecode(base64.urlEncode(b'\x00\x01\xfe\xff\x80')) == b'\x00\x01\xfe\xff\x80'"#;
+	assert(json!(true), expr);
+	let expr = r#"string(base64.urlDecode(base64.urlEncode("hello?")))"#;
+	assert(json!("hello?"), expr);
+
+	// Standard alphabet characters are rejected
+	assert_fails(r#"base64.urlDecode('+/8=')"#);
+	assert_fails(r#"base64.urlDecode('!!')"#);
+}
+
 #[test]
 fn url() {
 	assert(
@@ -157,6 +236,8 @@ fn url() {
 		r#"url.decode(url.encode("hello world"))"#,
 	);
 
+	assert(json!("a&b=c d/é"), r#"url.decode(url.encode("a&b=c d/é"))"#);
+	assert(json!("%C3%A9%2B%3D"), r#"url.encode("é+=")"#);
 	assert(json!("hello"), r#"url.encode("hello")"#);
 	assert_fails(r#"url.decode("%FF")"#);
 }
@@ -420,6 +501,42 @@ fn coalesce() {
 	.unwrap();
 }
 
+#[test]
+fn json_get() {
+	let body = r#"json('{"a": {"b": [{"c": 1}, {"c": 2, "d.e": "x"}]}, "f": null}')"#;
+	let expr = format!(r#"jsonGet({body}, "a.b[1].c")"#);
+	assert(json!(2), &expr);
+	let expr = format!(r#"jsonGet({body}, "a.b[0]")"#);
+	assert(json!({"c": 1}), &expr);
+	let expr = format!(r#"jsonGet({body}, "a['b'][1]['d.e']")"#);
+	assert(json!("x"), &expr);
+	let expr = format!(r#"jsonGet({body}, 'a["b"][1].c')"#);
+	assert(json!(2), &expr);
+	let expr = format!(r#"jsonGet({body}, "")"#);
+	assert(
+		json!({"a": {"b": [{"c": 1}, {"c": 2, "d.e": "x"}]}, "f": null}),
+		&expr,
+	);
+
+	// Missing segments resolve to null
+	for path in [
+		"missing",
+		"a.missing.c",
+		"a.b[5].c",
+		"a.b.c",
+		"a.b[0].c.d",
+		"f.g",
+	] {
+		let expr = format!(r#"jsonGet({body}, "{path}")"#);
+		assert(json!(null), &expr);
+	}
+
+	// Malformed paths are an error
+	for path in ["a.", "a..b", "a[0", "a[x]", ".a"] {
+		assert_fails(&format!(r#"jsonGet({body}, "{path}")"#));
+	}
+}
+
 #[test]
 fn regex_replace() {
 	let expr = r#""/path/1/id/499c81c2/bar".regexReplace("/path/([0-9]+?)/id/([0-9a-z]{8})/bar", "/path/{n}/id/{id}/bar")"#;
@@ -440,6 +557,29 @@ fn merge_maps() {
 	assert(json!({"a":3}), expr);
 }
 
+#[test]
+fn reject() {
+	assert(
+		json!({"status": 429, "body": "slow down"}),
+		r#"reject(429, "slow down")"#,
+	);
+	assert(json!(429), r#"reject(429, "slow down").status()"#);
+	assert(json!("slow down"), r#"reject(429, "slow down").body()"#);
+	let v = eval(r#"reject(429, "slow down")"#).unwrap();
+	assert_eq!(
+		crate::Reject::from_value(&v),
+		Some(crate::Reject {
+			status: 429,
+			body: "slow down".to_string(),
+		})
+	);
+	assert_eq!(crate::Reject::from_value(&eval("true").unwrap()), None);
+
+	assert_fails(r#"reject(99, "too low")"#);
+	assert_fails(r#"reject(600, "too high")"#);
+	assert_fails(r#"reject("429", "not an int")"#);
+}
+
 #[test]
 fn ip() {
 	let expr = r#"ip('192.168.0.1')"#;
@@ -585,6 +725,30 @@ fn cidr() {
 	assert(json!(true), expr);
 }
 
+#[test]
+fn regex() {
+	let expr = r#"regex('^/api/v[0-9]+/').matches('/api/v2/users')"#;
+	assert(json!(true), expr);
+	let expr = r#"regex('^/api/v[0-9]+/').matches('/static/app.js')"#;
+	assert(json!(false), expr);
+
+	let expr = r#"regex('[0-9]+').find('id=1234 rev=56')"#;
+	assert(json!("1234"), expr);
+	let expr = r#"regex('[0-9]+').find('no digits')"#;
+	assert(json!(""), expr);
+
+	let expr = r#"regex('[0-9]+').findAll('id=1234 rev=56')"#;
+	assert(json!(["1234", "56"]), expr);
+	let expr = r#"regex('[0-9]+').findAll('no digits')"#;
+	assert(json!([]), expr);
+
+	let expr = r#"regex('a' + '+').find('baaad')"#;
+	assert(json!("aaa"), expr);
+
+	let expr = r#"regex('(unclosed')"#;
+	assert_fails(expr);
+}
+
 #[test]
 fn uuid() {
 	// Test that uuid() returns a string
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use ::cel::extractors::{Argument, This};
-use ::cel::objects::{Key, MapValue, StringValue, ValueType};
+use ::cel::objects::{Key, KeyRef, MapValue, StringValue, ValueType};
 use ::cel::{Context, FunctionContext, ResolveResult, Value};
 use base64::alphabet;
 use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
@@ -21,6 +21,7 @@ pub fn insert_all(ctx: &mut Context) {
 	// Custom to agentgateway
 	ctx.add_function("json", json_parse);
 	ctx.add_function("jsonField", json_parse_field);
+	ctx.add_function("jsonGet", json_get);
 	ctx.add_function("unvalidatedJwtPayload", unvalidated_jwt_payload);
 	ctx.add_function("to_json", to_json);
 	// Keep old and new name for compatibility
@@ -36,12 +37,16 @@ pub fn insert_all(ctx: &mut Context) {
 	ctx.add_function("regexReplace", regex_replace);
 	ctx.add_function("fail", fail);
 	ctx.add_function("uuid", uuid_generate);
+	ctx.add_function("truncate", timestamp_truncate);
+	ctx.add_function("format", timestamp_format);
 
 	// Support legacy and modern name
 	ctx.add_function("base64Encode", base64_encode);
 	ctx.add_function("base64Decode", base64_decode);
 	ctx.add_qualified_function("base64", "encode", base64_encode);
 	ctx.add_qualified_function("base64", "decode", base64_decode);
+	ctx.add_qualified_function("base64", "urlEncode", base64_url_encode);
+	ctx.add_qualified_function("base64", "urlDecode", base64_url_decode);
 	ctx.add_qualified_function("url", "encode", url_encode);
 	ctx.add_qualified_function("url", "decode", url_decode);
 	ctx.add_qualified_function("form", "decode", form_decode);
@@ -85,6 +90,25 @@ pub fn base64_decode<'a>(ftx: &mut FunctionContext<'a, '_>, v: Argument) -> Reso
 		.map_err(|e| ftx.error(e))
 }
 
+pub fn base64_url_encode<'a>(ftx: &mut FunctionContext<'a, '_>, v: Argument) -> ResolveResult<'a> {
+	let v = v.load(ftx)?.always_materialize_owned();
+	use base64::Engine;
+	// Unpadded, as `=` would itself need escaping in a URL.
+	Ok(
+		base64::prelude::BASE64_URL_SAFE_NO_PAD
+			.encode(v.as_bytes_pre_materialized()?)
+			.into(),
+	)
+}
+
+pub fn base64_url_decode<'a>(ftx: &mut FunctionContext<'a, '_>, v: Argument) -> ResolveResult<'a> {
+	let v = v.load(ftx)?.always_materialize_owned();
+	use base64::Engine;
+	URL_SAFE_MAYBE_PADDED
+		.decode(v.as_bytes_pre_materialized()?)
+		.map(|v| v.into())
+		.map_err(|e| ftx.error(e))
+}
 const URL_COMPONENT_ENCODE_SET: &AsciiSet = &CONTROLS
 	.add(b' ')
 	.add(b'"')
@@ -325,6 +349,34 @@ fn fail<'a>(ftx: &mut FunctionContext<'a, '_>, v: Argument) -> ResolveResult<'a>
 	Err(ftx.error(format!("fail() called: {}", v.as_ref())))
 }
 
+/// Truncates a timestamp down to a multiple of the duration (for example, the start of the hour),
+/// which is useful for bucketing.
+fn timestamp_truncate<'a>(
+	ftx: &mut FunctionContext<'a, '_>,
+	this: This,
+	d: Argument,
+) -> ResolveResult<'a> {
+	use chrono::DurationRound;
+	let this: chrono::DateTime<chrono::FixedOffset> = this.load_value(ftx)?;
+	let d: chrono::Duration = d.load_value(ftx)?;
+	let truncated = this.duration_trunc(d).map_err(|e| ftx.error(e))?;
+	Ok(Value::Timestamp(truncated))
+}
+
+/// Formats a timestamp using a strftime-style layout, such as `%Y-%m-%dT%H:%M:%S%:z`.
+fn timestamp_format<'a>(
+	ftx: &mut FunctionContext<'a, '_>,
+	this: This,
+	layout: Argument,
+) -> ResolveResult<'a> {
+	let this: chrono::DateTime<chrono::FixedOffset> = this.load_value(ftx)?;
+	let layout: StringValue = layout.load_value(ftx)?;
+	let items = chrono::format::StrftimeItems::new(layout.as_ref())
+		.parse()
+		.map_err(|_| ftx.error(format!("invalid timestamp layout {:?}", layout.as_ref())))?;
+	Ok(this.format_with_items(items.iter()).to_string().into())
+}
+
 fn json_parse<'a>(ftx: &mut FunctionContext<'a, '_>, v: Argument) -> ResolveResult<'a> {
 	let v: Value = v.load_value(ftx)?;
 	let sv = match v {
@@ -336,6 +388,78 @@ fn json_parse<'a>(ftx: &mut FunctionContext<'a, '_>, v: Argument) -> ResolveResu
 	cel::to_value(sv).map_err(|e| ftx.error(e))
 }
 
+/// Walks a path like `a.b[0]['c.d']` through nested maps and lists, returning null if any segment
+/// is missing rather than failing.
+fn json_get<'a>(
+	ftx: &mut FunctionContext<'a, '_>,
+	v: Argument,
+	path: Argument,
+) -> ResolveResult<'a> {
+	let mut cur: Value<'a> = v.load_value(ftx)?;
+	let path: StringValue = path.load_value(ftx)?;
+	let segments =
+		parse_json_path(path.as_ref()).map_err(|e| ftx.error(format!("invalid path: {e}")))?;
+	for segment in segments {
+		let next = match (cur.always_materialize_owned(), segment) {
+			(Value::Map(map), JsonPathSegment::Key(k)) => map.get(&KeyRef::String(k.into())).cloned(),
+			(Value::List(list), JsonPathSegment::Index(i)) => list.as_ref().get(i).cloned(),
+			_ => None,
+		};
+		match next {
+			Some(v) => cur = v,
+			None => return Ok(Value::Null),
+		}
+	}
+	Ok(cur)
+}
+
+#[derive(Debug, PartialEq)]
+enum JsonPathSegment<'p> {
+	Key(&'p str),
+	Index(usize),
+}
+
+fn parse_json_path(path: &str) -> Result<Vec<JsonPathSegment<'_>>, String> {
+	let mut segments = Vec::new();
+	let mut rest = path;
+	while !rest.is_empty() {
+		if let Some(bracketed) = rest.strip_prefix('[') {
+			let end = bracketed
+				.find(']')
+				.ok_or_else(|| format!("unclosed '[' in {path:?}"))?;
+			let inner = &bracketed[..end];
+			let quoted = inner
+				.strip_prefix('\'')
+				.and_then(|s| s.strip_suffix('\''))
+				.or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+			segments.push(match quoted {
+				Some(key) => JsonPathSegment::Key(key),
+				None => JsonPathSegment::Index(
+					inner
+						.parse()
+						.map_err(|_| format!("invalid index {inner:?} in {path:?}"))?,
+				),
+			});
+			rest = &bracketed[end + 1..];
+		} else {
+			let end = rest.find(['.', '[']).unwrap_or(rest.len());
+			if end == 0 {
+				return Err(format!("empty segment in {path:?}"));
+			}
+			segments.push(JsonPathSegment::Key(&rest[..end]));
+			rest = &rest[end..];
+		}
+		// A '.' separates segments; it may not trail or appear before a bracket.
+		if let Some(after) = rest.strip_prefix('.') {
+			if after.is_empty() || after.starts_with('[') {
+				return Err(format!("empty segment in {path:?}"));
+			}
+			rest = after;
+		}
+	}
+	Ok(segments)
+}
+
 fn unvalidated_jwt_payload<'a>(
 	ftx: &mut FunctionContext<'a, '_>,
 	v: Argument,
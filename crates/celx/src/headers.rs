@@ -0,0 +1,59 @@
+use cel::extractors::{Argument, This};
+use cel::objects::{KeyRef, ListValue, StringValue, ValueType};
+use cel::{Context, ExecutionError, FunctionContext, ResolveResult, Value};
+
+pub fn insert_all(ctx: &mut Context) {
+	ctx.add_function("get", get);
+	ctx.add_function("values", values);
+}
+
+/// Collects every value of a header, matching the name case-insensitively. Repeated headers may
+/// be represented either as a list or as separate keys differing only in case.
+fn header_values(this: Value, name: &str) -> Result<Vec<Value<'static>>, ExecutionError> {
+	let Value::Map(map) = this else {
+		return Err(this.error_expected_type(ValueType::Map));
+	};
+	let mut res = Vec::new();
+	for (k, v) in map.iter() {
+		let KeyRef::String(k) = k else { continue };
+		if !k.as_ref().eq_ignore_ascii_case(name) {
+			continue;
+		}
+		match v {
+			Value::List(l) => res.extend(l.as_ref().iter().map(|v| v.clone().as_static())),
+			v => res.push(v.clone().as_static()),
+		}
+	}
+	Ok(res)
+}
+
+/// `headers.get(name)` returns the value of the header, with repeated values joined by a comma.
+fn get<'a>(ftx: &mut FunctionContext<'a, '_>, this: This, name: Argument) -> ResolveResult<'a> {
+	let this: Value = this.load_value(ftx)?;
+	let name: StringValue = name.load_value(ftx)?;
+	let values = header_values(this, name.as_ref())?;
+	if values.len() == 1 {
+		return Ok(values.into_iter().next().expect("len checked"));
+	}
+	if values.is_empty() {
+		return Err(ExecutionError::no_such_key(name.as_ref()));
+	}
+	let joined = values
+		.iter()
+		.map(|v| match v {
+			Value::String(s) => Ok(s.as_ref().to_string()),
+			v => Err(v.error_expected_type(ValueType::String)),
+		})
+		.collect::<Result<Vec<_>, _>>()?
+		.join(",");
+	Ok(joined.into())
+}
+
+/// `headers.values(name)` returns every value of the header as a list, which is empty if the
+/// header is not present.
+fn values<'a>(ftx: &mut FunctionContext<'a, '_>, this: This, name: Argument) -> ResolveResult<'a> {
+	let this: Value = this.load_value(ftx)?;
+	let name: StringValue = name.load_value(ftx)?;
+	let values = header_values(this, name.as_ref())?;
+	Ok(Value::List(ListValue::PartiallyOwned(values.into())))
+}
@@ -6,8 +6,11 @@ mod benches;
 mod cidr;
 mod flatten;
 mod general;
+mod headers;
 mod math;
 mod optimize;
+mod regexp;
+mod reject;
 mod strings;
 #[cfg(test)]
 #[path = "function_tests.rs"]
@@ -15,10 +18,13 @@ mod tests;
 
 pub use flatten::FlattenSignal;
 pub use optimize::DefaultOptimizer;
+pub use reject::Reject;
 
 pub fn insert_all(ctx: &mut Context) {
 	// General agentgateway additional functions
 	general::insert_all(ctx);
+	// Case-insensitive header map accessors
+	headers::insert_all(ctx);
 	// "Strings" extension
 	// https://pkg.go.dev/github.com/google/cel-go/ext#Strings
 	strings::insert_all(ctx);
@@ -28,9 +34,12 @@ pub fn insert_all(ctx: &mut Context) {
 	// https://kubernetes.io/docs/reference/using-api/cel/#kubernetes-cidr-library and
 	// https://kubernetes.io/docs/reference/using-api/cel/#kubernetes-ip-address-library
 	cidr::insert_all(ctx);
+	// Precompiled regular expressions
+	regexp::insert_all(ctx);
 	// Optimized functions
 	optimize::insert_all(ctx);
 	flatten::insert_all(ctx);
+	reject::insert_all(ctx);
 }
 
 mod helpers {
@@ -56,6 +56,14 @@ impl DefaultOptimizer {
 				let parsed = super::cidr::IP::new(&arg)?;
 				Some(Expr::Inline(Value::Object(OpaqueValue::new(parsed))))
 			},
+			"regex" if c.args.len() == 1 && c.target.is_none() => {
+				let arg = c.args.first()?.clone();
+				let Value::String(arg) = expr_as_value(arg)? else {
+					return None;
+				};
+				let parsed = super::regexp::Regex::new(&arg)?;
+				Some(Expr::Inline(Value::Object(OpaqueValue::new(parsed))))
+			},
 			"matches" if c.args.len() == 1 && c.target.is_some() => {
 				let t = c.target.clone()?;
 				let arg = c.args.first()?.clone();
@@ -0,0 +1,66 @@
+use cel::objects::StringValue;
+use cel::{Context, FunctionContext, Value};
+use serde::Serialize;
+
+use crate::helpers::{FResult, FVResult, wrapnew};
+
+pub fn insert_all(ctx: &mut Context) {
+	ctx.add_function_direct("regex", wrapnew(Regex::parse));
+}
+
+/// A compiled regular expression, so hot paths can reuse a pattern rather than compiling it on
+/// each evaluation (as `matches` and `regexReplace` do with string patterns).
+#[derive(Debug, Clone, Serialize)]
+pub struct Regex(#[serde(with = "serde_regex")] regex::Regex);
+crate::impl_opaque!(Regex, "regex");
+
+impl PartialEq for Regex {
+	fn eq(&self, other: &Self) -> bool {
+		self.0.as_str() == other.0.as_str()
+	}
+}
+impl Eq for Regex {}
+
+impl Regex {
+	crate::impl_functions! {
+		{},
+		{
+			matches => "matches",
+			find => "find",
+			find_all => "findAll",
+		}
+	}
+
+	pub fn new(s: &str) -> Option<Regex> {
+		Some(Regex(regex::Regex::new(s).ok()?))
+	}
+	fn parse(ftx: &FunctionContext, s: &str) -> FResult<Regex> {
+		Ok(Regex(regex::Regex::new(s).map_err(|x| ftx.error(x))?))
+	}
+
+	fn matches(&self, ftx: &FunctionContext) -> FVResult<'static> {
+		let s: StringValue = ftx.arg(0)?;
+		Ok(self.0.is_match(s.as_ref()).into())
+	}
+
+	/// Returns the first match, or an empty string if there is none.
+	fn find(&self, ftx: &FunctionContext) -> FVResult<'static> {
+		let s: StringValue = ftx.arg(0)?;
+		let found = self
+			.0
+			.find(s.as_ref())
+			.map(|m| m.as_str())
+			.unwrap_or_default();
+		Ok(Value::from(found.to_string()))
+	}
+
+	fn find_all(&self, ftx: &FunctionContext) -> FVResult<'static> {
+		let s: StringValue = ftx.arg(0)?;
+		let found: Vec<String> = self
+			.0
+			.find_iter(s.as_ref())
+			.map(|m| m.as_str().to_string())
+			.collect();
+		Ok(found.into())
+	}
+}
@@ -0,0 +1,65 @@
+use cel::extractors::Argument;
+use cel::objects::{OpaqueValue, StringValue};
+use cel::{Context, FunctionContext, ResolveResult, Value};
+use serde::Serialize;
+
+use crate::helpers::FVResult;
+
+pub fn insert_all(ctx: &mut Context) {
+	ctx.add_function("reject", reject);
+}
+
+/// Reject is returned by `reject(status, body)` to carry the intended HTTP response back out of a
+/// policy expression, rather than a generic deny.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Reject {
+	pub status: u16,
+	pub body: String,
+}
+crate::impl_opaque!(Reject, "reject");
+
+impl Reject {
+	crate::impl_functions! {
+		{
+			status => "status",
+			body => "body",
+		},
+		{
+		}
+	}
+
+	/// Extracts the rejection from an expression result, if it is one.
+	pub fn from_value(v: &Value) -> Option<Reject> {
+		let Value::Object(o) = v else { return None };
+		o.downcast_ref::<Reject>().cloned()
+	}
+
+	fn status(&self) -> FVResult<'static> {
+		Ok((self.status as u64).into())
+	}
+
+	fn body(&self) -> FVResult<'static> {
+		Ok(self.body.clone().into())
+	}
+}
+
+fn reject<'a>(
+	ftx: &mut FunctionContext<'a, '_>,
+	status: Argument,
+	body: Argument,
+) -> ResolveResult<'a> {
+	let status: Value = status.load_value(ftx)?;
+	let body: StringValue = body.load_value(ftx)?;
+	let code = match &status {
+		Value::Int(i) => u16::try_from(*i).ok(),
+		Value::UInt(u) => u16::try_from(*u).ok(),
+		_ => return Err(ftx.error(format!("invalid type {}", status.type_of()))),
+	};
+	let status = code
+		.filter(|s| (100..=599).contains(s))
+		.ok_or_else(|| ftx.error(format!("reject() called with invalid status {status:?}")))?;
+	Ok(Value::Object(OpaqueValue::new(Reject {
+		status,
+		body: body.as_ref().to_string(),
+	})))
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use once_cell::sync::Lazy;
+use secrecy::SecretString;
+
+/// A reference to a secret stored outside the config, written as `<scheme>://<path>`.
+///
+/// `file://` and `env://` are always available; other schemes (for example `vault://`) resolve
+/// through a [`SecretProvider`] registered with [`register_provider`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SecretRef {
+	scheme: String,
+	path: String,
+}
+
+impl SecretRef {
+	pub fn scheme(&self) -> &str {
+		&self.scheme
+	}
+
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	/// Resolve the secret with the provider registered for its scheme.
+	pub fn resolve(&self) -> anyhow::Result<SecretString> {
+		let provider = PROVIDERS
+			.read()
+			.expect("secret providers lock poisoned")
+			.get(&self.scheme)
+			.cloned()
+			.ok_or_else(|| anyhow::anyhow!("unknown secret provider {:?} in {self}", self.scheme))?;
+		provider
+			.resolve(&self.path)
+			.map_err(|e| anyhow::anyhow!("failed to resolve secret {self}: {e}"))
+	}
+}
+
+impl FromStr for SecretRef {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (scheme, path) = s.split_once("://").ok_or_else(|| {
+			anyhow::anyhow!("secret reference {s:?} must be of the form <scheme>://<path>")
+		})?;
+		if scheme.is_empty()
+			|| !scheme
+				.bytes()
+				.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+		{
+			anyhow::bail!("secret reference {s:?} has an invalid scheme");
+		}
+		if path.is_empty() {
+			anyhow::bail!("secret reference {s:?} has an empty path");
+		}
+		Ok(SecretRef {
+			scheme: scheme.to_ascii_lowercase(),
+			path: path.to_string(),
+		})
+	}
+}
+
+impl fmt::Display for SecretRef {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}://{}", self.scheme, self.path)
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for SecretRef {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		let s = String::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+impl serde::Serialize for SecretRef {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.collect_str(self)
+	}
+}
+
+/// Resolves the path of a [`SecretRef`] to the secret value.
+///
+/// Resolution happens while the config is loaded, so implementations may block.
+pub trait SecretProvider: Send + Sync + 'static {
+	fn resolve(&self, path: &str) -> anyhow::Result<SecretString>;
+}
+
+/// Reads the secret from a file, trimming surrounding whitespace.
+pub struct FileSecretProvider;
+
+impl SecretProvider for FileSecretProvider {
+	fn resolve(&self, path: &str) -> anyhow::Result<SecretString> {
+		let contents = fs_err::read_to_string(path)?;
+		Ok(SecretString::from(contents.trim().to_string()))
+	}
+}
+
+/// Reads the secret from an environment variable.
+pub struct EnvSecretProvider;
+
+impl EnvSecretProvider {
+	fn resolve_with(
+		path: &str,
+		var: impl FnOnce(&str) -> Result<String, std::env::VarError>,
+	) -> anyhow::Result<SecretString> {
+		let value = var(path).map_err(|e| anyhow::anyhow!("environment variable {path}: {e}"))?;
+		Ok(SecretString::from(value))
+	}
+}
+
+impl SecretProvider for EnvSecretProvider {
+	fn resolve(&self, path: &str) -> anyhow::Result<SecretString> {
+		Self::resolve_with(path, std::env::var)
+	}
+}
+
+static PROVIDERS: Lazy<RwLock<HashMap<String, Arc<dyn SecretProvider>>>> = Lazy::new(|| {
+	let mut providers: HashMap<String, Arc<dyn SecretProvider>> = HashMap::new();
+	providers.insert("file".to_string(), Arc::new(FileSecretProvider));
+	providers.insert("env".to_string(), Arc::new(EnvSecretProvider));
+	RwLock::new(providers)
+});
+
+/// Register a provider for `scheme`, replacing any existing provider for it.
+///
+/// Providers must be registered before the config referencing them is loaded.
+pub fn register_provider(scheme: &str, provider: impl SecretProvider) {
+	PROVIDERS
+		.write()
+		.expect("secret providers lock poisoned")
+		.insert(scheme.to_ascii_lowercase(), Arc::new(provider));
+}
+
+#[cfg(test)]
+mod tests {
+	use secrecy::ExposeSecret;
+
+	use super::*;
+
+	fn resolve(s: &str) -> anyhow::Result<String> {
+		let secret = s.parse::<SecretRef>()?.resolve()?;
+		Ok(secret.expose_secret().to_string())
+	}
+
+	#[test]
+	fn test_resolve_file() {
+		let path = std::env::temp_dir().join(format!("agentgateway-secret-{}", std::process::id()));
+		fs_err::write(&path, "file-secret\n").unwrap();
+		let resolved = resolve(&format!("file://{}", path.display()));
+		fs_err::remove_file(&path).unwrap();
+		assert_eq!(resolved.unwrap(), "file-secret");
+	}
+
+	#[test]
+	fn test_resolve_env() {
+		// Look the variable up through a stub; mutating the real environment races other tests.
+		let secret = EnvSecretProvider::resolve_with("AGENTGATEWAY_TEST_SECRET_REF", |k| {
+			assert_eq!(k, "AGENTGATEWAY_TEST_SECRET_REF");
+			Ok("env-secret".to_string())
+		})
+		.unwrap();
+		assert_eq!(secret.expose_secret(), "env-secret");
+		let err = resolve("env://AGENTGATEWAY_TEST_SECRET_REF_UNSET").unwrap_err();
+		assert!(
+			err
+				.to_string()
+				.contains("AGENTGATEWAY_TEST_SECRET_REF_UNSET"),
+			"{err}"
+		);
+	}
+
+	#[test]
+	fn test_unknown_scheme() {
+		let err = resolve("vault://secret/data/api-key").unwrap_err();
+		assert!(
+			err
+				.to_string()
+				.contains("unknown secret provider \"vault\""),
+			"{err}"
+		);
+
+		struct Static;
+		impl SecretProvider for Static {
+			fn resolve(&self, path: &str) -> anyhow::Result<SecretString> {
+				Ok(SecretString::from(format!("static:{path}")))
+			}
+		}
+		register_provider("test-static", Static);
+		assert_eq!(resolve("test-static://a/b").unwrap(), "static:a/b");
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		assert!("api-key".parse::<SecretRef>().is_err());
+		assert!("://path".parse::<SecretRef>().is_err());
+		assert!("env://".parse::<SecretRef>().is_err());
+	}
+}
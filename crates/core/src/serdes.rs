@@ -5,7 +5,7 @@ use std::path::PathBuf;
 
 #[cfg(feature = "schema")]
 pub use schemars::JsonSchema;
-use secrecy::SecretString;
+use secrecy::{ExposeSecret, SecretString};
 use serde::de::DeserializeOwned;
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serializer};
@@ -15,6 +15,8 @@ pub trait JsonSchema {}
 pub use macro_rules_attribute::{apply, attribute_alias};
 pub use serde_with;
 
+use crate::secrets::SecretRef;
+
 #[macro_export]
 macro_rules! define_schema_aliases {
 	() => {
@@ -64,6 +66,168 @@ pub mod yamlviajson {
 	}
 }
 
+/// Deserialize `value`, reporting every error instead of only the first one.
+///
+/// After each failure the offending node is dropped and deserialization is retried, so errors in
+/// independent parts of the document are all found. Errors that only occur because an earlier
+/// node was dropped (such as a now-missing required field) are not reported.
+pub fn from_value_collecting_errors<T: DeserializeOwned>(
+	mut value: serde_json::Value,
+) -> Result<T, Vec<String>> {
+	const MAX_ERRORS: usize = 100;
+	let mut errors = Vec::new();
+	let mut pruned = Pruned::default();
+	loop {
+		let err = match serde_path_to_error::deserialize::<_, T>(&value) {
+			Ok(t) if errors.is_empty() => return Ok(t),
+			Ok(_) => return Err(errors),
+			Err(err) => err,
+		};
+		let path = pruned.original_path(err.path());
+		let msg = err.inner().to_string();
+		let caused_by_prune = msg
+			.strip_prefix("missing field `")
+			.and_then(|m| m.split_once('`'))
+			.is_some_and(|(field, _)| {
+				let mut missing = path.clone();
+				missing.push(PathSegment::Key(field.to_string()));
+				pruned.paths.contains(&missing)
+			});
+		if !caused_by_prune {
+			if path.is_empty() {
+				errors.push(msg);
+			} else {
+				errors.push(format!("{}: {msg}", display_path(&path)));
+			}
+		}
+		if path.is_empty() || errors.len() >= MAX_ERRORS || !pruned.remove(&mut value, path) {
+			return Err(errors);
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+	Key(String),
+	Index(usize),
+}
+
+/// Tracks the nodes dropped by [from_value_collecting_errors], so that paths into the pruned
+/// document can be reported relative to the original one.
+#[derive(Default)]
+struct Pruned {
+	paths: Vec<Vec<PathSegment>>,
+}
+
+impl Pruned {
+	fn original_path(&self, path: &serde_path_to_error::Path) -> Vec<PathSegment> {
+		let mut out = Vec::new();
+		for segment in path.iter() {
+			match segment {
+				serde_path_to_error::Segment::Map { key } => out.push(PathSegment::Key(key.clone())),
+				serde_path_to_error::Segment::Enum { variant } => {
+					out.push(PathSegment::Key(variant.clone()))
+				},
+				serde_path_to_error::Segment::Seq { index } => {
+					// Shift the index past any earlier elements of this array we already dropped.
+					let mut removed = self
+						.paths
+						.iter()
+						.filter_map(|p| match p.split_last() {
+							Some((PathSegment::Index(i), parent)) if parent == out.as_slice() => Some(*i),
+							_ => None,
+						})
+						.collect::<Vec<_>>();
+					removed.sort_unstable();
+					let mut index = *index;
+					for r in removed {
+						if r <= index {
+							index += 1;
+						}
+					}
+					out.push(PathSegment::Index(index))
+				},
+				// The rest of the path is unknown (for example, inside an untagged enum).
+				serde_path_to_error::Segment::Unknown => break,
+			}
+		}
+		out
+	}
+
+	/// Remove the node at `path` (relative to the original document) from `value`.
+	fn remove(&mut self, value: &mut serde_json::Value, path: Vec<PathSegment>) -> bool {
+		let Some((last, parent)) = path.split_last() else {
+			return false;
+		};
+		let mut node = value;
+		let mut prefix = Vec::with_capacity(parent.len());
+		for segment in parent {
+			node = match (node, segment) {
+				(serde_json::Value::Object(m), PathSegment::Key(k)) => match m.get_mut(k) {
+					Some(v) => v,
+					None => return false,
+				},
+				(serde_json::Value::Array(a), PathSegment::Index(i)) => {
+					match a.get_mut(self.current_index(&prefix, *i)) {
+						Some(v) => v,
+						None => return false,
+					}
+				},
+				_ => return false,
+			};
+			prefix.push(segment.clone());
+		}
+		let removed = match (node, last) {
+			(serde_json::Value::Object(m), PathSegment::Key(k)) => m.remove(k).is_some(),
+			(serde_json::Value::Array(a), PathSegment::Index(i)) => {
+				let i = self.current_index(&prefix, *i);
+				if i < a.len() {
+					a.remove(i);
+					true
+				} else {
+					false
+				}
+			},
+			_ => false,
+		};
+		if removed {
+			self.paths.push(path);
+		}
+		removed
+	}
+
+	fn current_index(&self, parent: &[PathSegment], original: usize) -> usize {
+		original
+			- self
+				.paths
+				.iter()
+				.filter(|p| {
+					matches!(p.split_last(), Some((PathSegment::Index(i), pp)) if pp == parent && *i < original)
+				})
+				.count()
+	}
+}
+
+fn display_path(path: &[PathSegment]) -> String {
+	let mut out = String::new();
+	for segment in path {
+		match segment {
+			PathSegment::Key(k) => {
+				if !out.is_empty() {
+					out.push('.');
+				}
+				out.push_str(k);
+			},
+			PathSegment::Index(i) => {
+				out.push('[');
+				out.push_str(&i.to_string());
+				out.push(']');
+			},
+		}
+	}
+	out
+}
+
 pub struct SerAsStr;
 impl<T> serde_with::SerializeAs<T> for SerAsStr
 where
@@ -422,6 +586,12 @@ pub enum FileOrInline {
 		/// Path to a file on disk to load the value from.
 		file: PathBuf,
 	},
+	SecretRef {
+		/// Reference to a secret to load the value from, such as `env://API_KEY` or `file:///path/to/key`.
+		#[serde(rename = "secretRef")]
+		#[cfg_attr(feature = "schema", schemars(with = "String"))]
+		secret_ref: SecretRef,
+	},
 	Inline(String),
 }
 
@@ -429,6 +599,10 @@ impl FileOrInline {
 	pub fn load(&self) -> io::Result<String> {
 		match self {
 			FileOrInline::File { file } => fs_err::read_to_string(file),
+			FileOrInline::SecretRef { secret_ref } => secret_ref
+				.resolve()
+				.map(|s| s.expose_secret().to_string())
+				.map_err(io::Error::other),
 			FileOrInline::Inline(s) => Ok(s.clone()),
 		}
 	}
@@ -170,9 +170,21 @@ pub fn enabled(target: &'static str, level: &tracing::Level) -> bool {
 // log is like using tracing macros, but allows arbitrary k/v pairs. Tracing requires compile-time keys!
 // This does NOT respect tracing enable/log level; users can do that themselves before calling this function.
 pub fn log(level: &str, target: &str, kv: &[(&str, Option<ValueBag>)]) {
-	let Some((nb, json)) = NON_BLOCKING.get() else {
+	log_with_format(level, target, kv, None)
+}
+
+// log_with_format is like log, but allows overriding the output format; `json` of None uses the
+// process-wide log format. JSON output is a single object per line, with keys in the order of `kv`.
+pub fn log_with_format(
+	level: &str,
+	target: &str,
+	kv: &[(&str, Option<ValueBag>)],
+	json: Option<bool>,
+) {
+	let Some((nb, default_json)) = NON_BLOCKING.get() else {
 		return;
 	};
+	let json = json.unwrap_or(*default_json);
 	thread_local! {
 		static BUF: RefCell<String> = const { RefCell::new(String::new()) };
 	}
@@ -193,7 +205,7 @@ pub fn log(level: &str, target: &str, kv: &[(&str, Option<ValueBag>)]) {
 			},
 		};
 
-		if *json {
+		if json {
 			write_json_log(buf, level, target, kv)?;
 		} else {
 			date::write(buf);
@@ -363,6 +375,24 @@ pub fn set_level(reset: bool, level: &str) -> Result<(), Error> {
 	}
 }
 
+/// set_filter replaces the entire logging filter with `filter`, rather than adding to the current one.
+pub fn set_filter(filter: &str) -> Result<(), Error> {
+	if let Some(handle) = LOG_HANDLE.get() {
+		replace_filter(handle, filter)
+	} else {
+		warn!("failed to get log handle");
+		Err(Error::Uninitialized)
+	}
+}
+
+fn replace_filter(handle: &LogHandle, filter: &str) -> Result<(), Error> {
+	let new_filter = filter::Targets::from_str(filter)?;
+	info!("new log filter is {new_filter}");
+	Ok(handle.modify(|layer| {
+		*layer.filter_mut() = new_filter;
+	})?)
+}
+
 pub fn get_current_loglevel() -> Result<String, Error> {
 	if let Some(handle) = LOG_HANDLE.get() {
 		Ok(handle.with_current(|f| f.filter().to_string())?)
@@ -881,6 +911,30 @@ pub mod testing {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn replace_filter_updates_handle() {
+		let inner: BoxLayer = Box::new(tracing_subscriber::fmt::layer());
+		let initial = filter::Targets::from_str("info").unwrap();
+		let (_layer, handle) = reload::Layer::new(inner.with_filter(initial));
+
+		replace_filter(&handle, "warn,agent_core=debug").unwrap();
+		let expected = filter::Targets::from_str("warn,agent_core=debug").unwrap();
+		assert_eq!(
+			handle.with_current(|f| f.filter().to_string()).unwrap(),
+			expected.to_string()
+		);
+
+		// An invalid filter is rejected, and leaves the current one in place.
+		assert!(matches!(
+			replace_filter(&handle, "agent_core=notalevel"),
+			Err(Error::InvalidFilter(_))
+		));
+		assert_eq!(
+			handle.with_current(|f| f.filter().to_string()).unwrap(),
+			expected.to_string()
+		);
+	}
+
 	#[test]
 	fn json_log_writes_floats_without_exponents() {
 		let value = ValueBag::from_f64(1.2e41);
@@ -908,6 +962,45 @@ mod tests {
 		assert_eq!(json_field_value(&buf, "ordinary_float"), "123.456");
 	}
 
+	#[test]
+	fn json_log_escapes_strings_and_keeps_key_order() {
+		let kv = [
+			("zeta", Some(ValueBag::from("first"))),
+			("quoted", Some(ValueBag::from(r#"say "hi""#))),
+			("skipped", None),
+			("multiline", Some(ValueBag::from("line1\nline2\t\\end"))),
+			("alpha", Some(ValueBag::from(1u64))),
+		];
+		let mut buf = String::new();
+
+		write_json_log(&mut buf, "info", "request", &kv).expect("log should serialize");
+
+		assert!(!buf.contains('\n'), "output must be a single line: {buf}");
+		assert!(buf.contains(r#""quoted":"say \"hi\"""#), "{buf}");
+		assert!(
+			buf.contains(r#""multiline":"line1\nline2\t\\end""#),
+			"{buf}"
+		);
+		let parsed: serde_json::Map<String, serde_json::Value> =
+			serde_json::from_str(&buf).expect("log must be valid json");
+		assert_eq!(parsed["quoted"], r#"say "hi""#);
+		assert_eq!(parsed["multiline"], "line1\nline2\t\\end");
+		// serde_json is built with preserve_order, so the parsed map reflects the written order.
+		let keys: Vec<_> = parsed.keys().map(String::as_str).collect();
+		assert_eq!(
+			keys,
+			[
+				"level",
+				"time",
+				"scope",
+				"zeta",
+				"quoted",
+				"multiline",
+				"alpha"
+			]
+		);
+	}
+
 	fn json_field_value<'a>(json: &'a str, field: &str) -> &'a str {
 		let prefix = format!("\"{field}\":");
 		let start = json.find(&prefix).expect("field should be present") + prefix.len();
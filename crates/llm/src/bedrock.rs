@@ -22,6 +22,14 @@ pub struct Provider {
 	/// Version of the Bedrock guardrail to apply.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub guardrail_version: Option<Strng>,
+	/// Inference profile ID or ARN to invoke instead of the model, for example a cross-region
+	/// profile such as `us.anthropic.claude-3-5-sonnet-20241022-v2:0`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub inference_profile: Option<Strng>,
+	/// Request latency-optimized inference. Only some models and regions support this; others
+	/// reject the request.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub latency_optimized: Option<bool>,
 }
 
 impl super::Provider for Provider {
@@ -39,15 +47,30 @@ impl Provider {
 		model.contains("anthropic.claude")
 	}
 
+	pub fn performance_config(&self) -> Option<crate::types::bedrock::PerformanceConfiguration> {
+		use crate::types::bedrock::{PerformanceConfigLatency, PerformanceConfiguration};
+		self
+			.latency_optimized
+			.unwrap_or_default()
+			.then_some(PerformanceConfiguration {
+				latency: PerformanceConfigLatency::Optimized,
+			})
+	}
+
 	pub fn get_path_for_route(
 		&self,
 		route_type: super::RouteType,
 		streaming: bool,
 		model: &str,
 	) -> Strng {
-		let model = self.model.as_deref().unwrap_or(model);
+		let model = self
+			.inference_profile
+			.as_deref()
+			.or(self.model.as_deref())
+			.unwrap_or(model);
 		const MODEL_SEGMENT: &percent_encoding::AsciiSet =
 			&percent_encoding::CONTROLS.add(b'/').add(b'%');
+		// Profile ARNs contain both `:` and `/`, so the whole identifier is encoded as one segment.
 		let model = percent_encoding::utf8_percent_encode(model, MODEL_SEGMENT);
 		match route_type {
 			super::RouteType::AnthropicTokenCount => strng::format!("/model/{model}/count-tokens"),
@@ -68,3 +91,60 @@ impl Provider {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::RouteType;
+
+	fn make_provider(inference_profile: Option<&str>) -> Provider {
+		Provider {
+			model: None,
+			region: strng::new("us-east-1"),
+			guardrail_identifier: Some(strng::new("guardrail")),
+			guardrail_version: Some(strng::new("1")),
+			inference_profile: inference_profile.map(strng::new),
+			latency_optimized: None,
+		}
+	}
+
+	#[rstest::rstest]
+	#[case::bare_model(
+		None,
+		false,
+		"/model/anthropic.claude-3-5-sonnet-20241022-v2:0/converse"
+	)]
+	#[case::cross_region_profile(
+		Some("us.anthropic.claude-3-5-sonnet-20241022-v2:0"),
+		false,
+		"/model/us.anthropic.claude-3-5-sonnet-20241022-v2:0/converse"
+	)]
+	#[case::profile_arn(
+		Some(
+			"arn:aws:bedrock:us-east-1:123456789012:inference-profile/us.anthropic.claude-3-5-sonnet-20241022-v2:0"
+		),
+		true,
+		"/model/arn:aws:bedrock:us-east-1:123456789012:inference-profile%2Fus.anthropic.claude-3-5-sonnet-20241022-v2:0/converse-stream"
+	)]
+	fn test_get_path_for_route(
+		#[case] inference_profile: Option<&str>,
+		#[case] streaming: bool,
+		#[case] expected: &str,
+	) {
+		let p = make_provider(inference_profile);
+		assert_eq!(
+			p.get_path_for_route(
+				RouteType::Completions,
+				streaming,
+				"anthropic.claude-3-5-sonnet-20241022-v2:0"
+			)
+			.as_str(),
+			expected
+		);
+		// The profile only changes the path; region handling is unaffected.
+		assert_eq!(
+			p.get_host(RouteType::Completions).as_str(),
+			"bedrock-runtime.us-east-1.amazonaws.com"
+		);
+	}
+}
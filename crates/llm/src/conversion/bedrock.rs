@@ -619,12 +619,14 @@ pub mod from_completions {
 		req: &types::completions::Request,
 		provider: &Provider,
 		headers: Option<&http::HeaderMap>,
+		request_metadata: Option<&HashMap<String, String>>,
 		prompt_caching: Option<&crate::PromptCachingConfig>,
 	) -> Result<super::BedrockRequest, AIError> {
 		let typed = json::convert::<_, completions::Request>(req).map_err(AIError::RequestParsing)?;
 		let model_id = typed.model.clone().unwrap_or_default();
-		let (xlated, tool_name_map) =
+		let (mut xlated, tool_name_map) =
 			translate_internal(typed, model_id, provider, headers, prompt_caching)?;
+		helpers::merge_request_metadata(&mut xlated, request_metadata);
 		let body = serde_json::to_vec(&xlated).map_err(AIError::RequestMarshal)?;
 		Ok(super::BedrockRequest {
 			body,
@@ -809,7 +811,7 @@ pub mod from_completions {
 			.as_ref()
 			.and_then(completions_response_format_to_bedrock_output_config);
 
-		let supports_caching = helpers::supports_prompt_caching(&model_id);
+		let supports_caching = helpers::supports_prompt_caching(&model_id, prompt_caching);
 		let system_content = if system_text.is_empty() {
 			None
 		} else {
@@ -850,7 +852,7 @@ pub mod from_completions {
 			prompt_variables: None,
 			additional_model_response_field_paths: None,
 			request_metadata: metadata,
-			performance_config: None,
+			performance_config: provider.performance_config(),
 		};
 		if let Some(caching) = prompt_caching {
 			if caching.cache_messages && supports_caching {
@@ -1113,6 +1115,10 @@ pub mod from_completions {
 					};
 					mk(vec![choice], None)
 				},
+				bedrock::ConverseStreamOutput::Unknown(event_type) => {
+					tracing::debug!(%event_type, "skipping unknown bedrock stream event");
+					None
+				},
 				bedrock::ConverseStreamOutput::MessageStop(stop) => {
 					let finish_reason = Some(translate_stop_reason(&stop.stop_reason));
 
@@ -1163,7 +1169,7 @@ pub mod from_completions {
 		append_done_on_success(body.into_data_stream())
 	}
 
-	pub(super) fn append_done_on_success<S>(stream: S) -> Body
+	pub(crate) fn append_done_on_success<S>(stream: S) -> Body
 	where
 		S: futures_core::Stream<Item = Result<Bytes, axum_core::Error>> + Send + 'static,
 	{
@@ -1207,7 +1213,7 @@ pub mod from_completions {
 }
 
 pub mod from_messages {
-	use std::collections::HashSet;
+	use std::collections::{HashMap, HashSet};
 	use std::time::Instant;
 
 	use agent_core::strng;
@@ -1226,9 +1232,11 @@ pub mod from_messages {
 		req: &types::messages::Request,
 		provider: &Provider,
 		headers: Option<&http::HeaderMap>,
+		request_metadata: Option<&HashMap<String, String>>,
 	) -> Result<super::BedrockRequest, AIError> {
 		let typed = json::convert::<_, messages::Request>(req).map_err(AIError::RequestParsing)?;
-		let (xlated, tool_name_map) = translate_internal(typed, provider, headers)?;
+		let (mut xlated, tool_name_map) = translate_internal(typed, provider, headers)?;
+		helpers::merge_request_metadata(&mut xlated, request_metadata);
 		let body = serde_json::to_vec(&xlated).map_err(AIError::RequestMarshal)?;
 		Ok(super::BedrockRequest {
 			body,
@@ -1632,7 +1640,7 @@ pub mod from_messages {
 				prompt_variables: None,
 				additional_model_response_field_paths: None,
 				request_metadata: metadata,
-				performance_config: None,
+				performance_config: provider.performance_config(),
 			},
 			tool_name_map,
 		))
@@ -1883,6 +1891,10 @@ pub mod from_messages {
 					let (event_name, event_data) = event.into_sse_tuple();
 					vec![(event_name, serde_json::to_value(event_data).unwrap())]
 				},
+				bedrock::ConverseStreamOutput::Unknown(event_type) => {
+					tracing::debug!(%event_type, "skipping unknown bedrock stream event");
+					vec![]
+				},
 				bedrock::ConverseStreamOutput::MessageStop(stop) => {
 					pending_stop_reason = Some(stop.stop_reason);
 					vec![]
@@ -2064,13 +2076,14 @@ pub mod from_responses {
 		req: &types::responses::Request,
 		provider: &Provider,
 		headers: Option<&http::HeaderMap>,
+		request_metadata: Option<&HashMap<String, String>>,
 		prompt_caching: Option<&crate::PromptCachingConfig>,
 	) -> Result<super::BedrockRequest, AIError> {
 		let typed =
 			json::convert::<_, responses::CreateResponse>(req).map_err(AIError::RequestMarshal)?;
 		let explicit_thinking_budget = extract_responses_thinking_budget_tokens(req);
 		let model_id = typed.model.clone().unwrap_or_default();
-		let (xlated, tool_name_map) = translate_internal(
+		let (mut xlated, tool_name_map) = translate_internal(
 			typed,
 			explicit_thinking_budget,
 			model_id,
@@ -2078,6 +2091,7 @@ pub mod from_responses {
 			headers,
 			prompt_caching,
 		)?;
+		merge_request_metadata(&mut xlated, request_metadata);
 		let body = serde_json::to_vec(&xlated).map_err(AIError::RequestMarshal)?;
 		Ok(super::BedrockRequest {
 			body,
@@ -2178,7 +2192,10 @@ pub mod from_responses {
 			(vec![], None)
 		};
 
-		let supports_caching = req.model.as_deref().is_some_and(supports_prompt_caching);
+		let supports_caching = req
+			.model
+			.as_deref()
+			.is_some_and(|m| supports_prompt_caching(m, prompt_caching));
 
 		// Convert input to Bedrock messages and system content
 		let mut messages: Vec<bedrock::Message> = Vec::new();
@@ -2225,12 +2242,8 @@ pub mod from_responses {
 								"bedrock image inputs must be base64 data URLs; remote URLs and file_ids are unsupported"
 							)));
 						};
-						if !image_url.starts_with("data:") {
-							// Remote URLs and file_ids would require the gateway to fetch content itself
-							return Err(AIError::UnsupportedConversion(strng::literal!(
-								"bedrock image inputs must be base64 data URLs; remote URLs and file_ids are unsupported"
-							)));
-						};
+						// Remote URLs would require the gateway to fetch content itself; from_data_url
+						// rejects them.
 						blocks
 							.push(super::CanonicalImage::from_data_url(image_url)?.into_bedrock_content_block());
 					},
@@ -2579,7 +2592,7 @@ pub mod from_responses {
 			prompt_variables: None,
 			additional_model_response_field_paths: None,
 			request_metadata: metadata,
-			performance_config: None,
+			performance_config: provider.performance_config(),
 		};
 
 		// Apply user message and tool caching
@@ -2977,6 +2990,10 @@ pub mod from_responses {
 
 					events
 				},
+				bedrock::ConverseStreamOutput::Unknown(event_type) => {
+					tracing::debug!(%event_type, "skipping unknown bedrock stream event");
+					vec![]
+				},
 				bedrock::ConverseStreamOutput::MessageStop(stop) => {
 					pending_stop_reason = Some(stop.stop_reason);
 					vec![]
@@ -3157,8 +3174,18 @@ mod helpers {
 		}
 	}
 
-	pub fn supports_prompt_caching(model_id: &str) -> bool {
+	pub fn supports_prompt_caching(
+		model_id: &str,
+		prompt_caching: Option<&crate::PromptCachingConfig>,
+	) -> bool {
 		let model_lower = model_id.to_lowercase();
+		if prompt_caching.is_some_and(|c| {
+			c.supported_models
+				.iter()
+				.any(|m| model_lower.contains(&m.to_lowercase()))
+		}) {
+			return true;
+		}
 		if model_lower.contains("anthropic.claude") {
 			let excluded = ["claude-instant", "claude-v1", "claude-v2"];
 			if excluded.iter().any(|pattern| model_lower.contains(pattern)) {
@@ -3225,6 +3252,21 @@ mod helpers {
 		);
 	}
 
+	/// Merge metadata evaluated from the provider's `metadataExpressions` into the request.
+	/// Configured values take precedence over same-named keys from the x-bedrock-metadata header.
+	pub fn merge_request_metadata(
+		req: &mut crate::types::bedrock::ConverseRequest,
+		metadata: Option<&HashMap<String, String>>,
+	) {
+		let Some(metadata) = metadata.filter(|m| !m.is_empty()) else {
+			return;
+		};
+		req
+			.request_metadata
+			.get_or_insert_default()
+			.extend(metadata.iter().map(|(k, v)| (k.clone(), v.clone())));
+	}
+
 	/// Extract metadata from x-bedrock-metadata header.
 	/// Gateway operators can use CEL transformation to populate this header with extauthz data.
 	pub fn extract_metadata_from_headers(
@@ -116,6 +116,8 @@ fn test_metadata_from_header() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	// Simulate transformation CEL setting x-bedrock-metadata header
@@ -172,6 +174,8 @@ fn test_messages_metadata_is_preserved_in_additional_model_request_fields() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let json_encoded_user_id = r#"{"device_id":"704cb53c2074e9","account_uuid":"","session_id":"180423cd-fe24-4f48-bbde-b4ab5bfd36e7"}"#;
@@ -222,6 +226,8 @@ fn test_output_config_effort_without_thinking_is_passed_through() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::typed::Request {
@@ -275,6 +281,8 @@ fn test_output_config_format_maps_to_converse_output_config() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let schema = json!({
@@ -349,6 +357,8 @@ fn test_explicit_empty_output_config_is_preserved() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::typed::Request {
@@ -404,6 +414,8 @@ fn test_thinking_and_output_config_are_both_passed_through() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::typed::Request {
@@ -459,6 +471,8 @@ fn test_adaptive_thinking_preserves_sampling_and_tool_choice() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::typed::Request {
@@ -533,6 +547,8 @@ fn test_enabled_thinking_applies_sampling_and_tool_choice_constraints() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::typed::Request {
@@ -596,6 +612,8 @@ fn test_messages_image_url_to_bedrock_returns_error() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::typed::Request {
@@ -642,6 +660,8 @@ fn test_completions_image_data_url_maps_to_converse_image_block() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::completions::Request = serde_json::from_value(json!({
@@ -662,7 +682,7 @@ fn test_completions_image_data_url_maps_to_converse_image_block() {
 	}))
 	.expect("valid completions request");
 
-	let translated = super::from_completions::translate(&req, &provider, None, None)
+	let translated = super::from_completions::translate(&req, &provider, None, None, None)
 		.unwrap()
 		.body;
 	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
@@ -685,6 +705,8 @@ fn test_completions_request_metadata_only_uses_bedrock_header() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = types::completions::typed::Request {
@@ -769,6 +791,8 @@ fn test_completions_json_schema_response_format_maps_to_converse_output_config()
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let schema = json!({
@@ -864,6 +888,8 @@ fn test_completions_reasoning_effort_maps_to_enabled_thinking_budget() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = types::completions::typed::Request {
@@ -938,6 +964,8 @@ fn test_completions_explicit_thinking_budget_forces_enabled_thinking() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = types::completions::typed::Request {
@@ -1015,6 +1043,8 @@ fn test_responses_json_schema_text_format_maps_to_converse_output_config() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let schema = json!({
@@ -1040,7 +1070,7 @@ fn test_responses_json_schema_text_format_maps_to_converse_output_config() {
 	}))
 	.expect("valid responses request");
 
-	let translated = super::from_responses::translate(&req, &provider, None, None)
+	let translated = super::from_responses::translate(&req, &provider, None, None, None)
 		.unwrap()
 		.body;
 	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
@@ -1070,6 +1100,8 @@ fn test_responses_request_metadata_only_uses_bedrock_header() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1091,7 +1123,7 @@ fn test_responses_request_metadata_only_uses_bedrock_header() {
 			.unwrap(),
 	);
 
-	let translated = super::from_responses::translate(&req, &provider, Some(&headers), None)
+	let translated = super::from_responses::translate(&req, &provider, Some(&headers), None, None)
 		.unwrap()
 		.body;
 	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
@@ -1107,6 +1139,104 @@ fn test_responses_request_metadata_only_uses_bedrock_header() {
 	assert_eq!(translated["requestMetadata"]["bad?key"], "bad{}");
 }
 
+#[test]
+fn test_configured_request_metadata_merges_over_header() {
+	let provider = Provider {
+		model: None,
+		region: strng::new("us-east-1"),
+		guardrail_identifier: None,
+		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
+	};
+
+	let req: types::completions::Request = serde_json::from_value(json!({
+		"model": "anthropic.claude-3-sonnet",
+		"messages": [{"role": "user", "content": "Hello"}]
+	}))
+	.expect("valid completions request");
+
+	let mut headers = HeaderMap::new();
+	headers.insert(
+		"x-bedrock-metadata",
+		r#"{"tenant": "from-header", "user": "spoofed"}"#.parse().unwrap(),
+	);
+	let configured = HashMap::from([("user".to_string(), "alice".to_string())]);
+
+	let translated =
+		super::from_completions::translate(&req, &provider, Some(&headers), Some(&configured), None)
+			.unwrap()
+			.body;
+	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
+	assert_eq!(
+		translated["requestMetadata"],
+		json!({"tenant": "from-header", "user": "alice"})
+	);
+
+	// Configured metadata applies on its own, without the header.
+	let translated =
+		super::from_completions::translate(&req, &provider, None, Some(&configured), None)
+			.unwrap()
+			.body;
+	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
+	assert_eq!(translated["requestMetadata"], json!({"user": "alice"}));
+}
+
+#[test]
+fn test_latency_optimized_sets_performance_config() {
+	let mut provider = Provider {
+		model: None,
+		region: strng::new("us-east-1"),
+		guardrail_identifier: None,
+		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
+	};
+	let completions: types::completions::Request = serde_json::from_value(json!({
+		"model": "anthropic.claude-3-5-haiku",
+		"messages": [{"role": "user", "content": "Hello"}]
+	}))
+	.unwrap();
+	let messages: types::messages::Request = serde_json::from_value(json!({
+		"model": "anthropic.claude-3-5-haiku",
+		"max_tokens": 16,
+		"messages": [{"role": "user", "content": "Hello"}]
+	}))
+	.unwrap();
+	let responses: types::responses::Request = serde_json::from_value(json!({
+		"model": "anthropic.claude-3-5-haiku",
+		"input": "Hello"
+	}))
+	.unwrap();
+	let translate_all = |provider: &Provider| -> Vec<serde_json::Value> {
+		[
+			super::from_completions::translate(&completions, provider, None, None, None),
+			super::from_messages::translate(&messages, provider, None, None),
+			super::from_responses::translate(&responses, provider, None, None, None),
+		]
+		.into_iter()
+		.map(|r| serde_json::from_slice(&r.unwrap().body).unwrap())
+		.collect()
+	};
+
+	for translated in translate_all(&provider) {
+		assert!(translated.get("performanceConfig").is_none());
+	}
+
+	provider.latency_optimized = Some(false);
+	for translated in translate_all(&provider) {
+		assert!(translated.get("performanceConfig").is_none());
+	}
+
+	provider.latency_optimized = Some(true);
+	for translated in translate_all(&provider) {
+		assert_eq!(
+			translated["performanceConfig"],
+			json!({"latency": "optimized"})
+		);
+	}
+}
+
 #[test]
 fn test_responses_reasoning_effort_maps_to_enabled_thinking_budget() {
 	let provider = Provider {
@@ -1114,6 +1244,8 @@ fn test_responses_reasoning_effort_maps_to_enabled_thinking_budget() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1126,7 +1258,7 @@ fn test_responses_reasoning_effort_maps_to_enabled_thinking_budget() {
 	}))
 	.expect("valid responses request");
 
-	let translated = super::from_responses::translate(&req, &provider, None, None)
+	let translated = super::from_responses::translate(&req, &provider, None, None, None)
 		.unwrap()
 		.body;
 	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
@@ -1149,6 +1281,8 @@ fn test_responses_explicit_thinking_budget_forces_enabled_thinking() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1164,7 +1298,7 @@ fn test_responses_explicit_thinking_budget_forces_enabled_thinking() {
 	}))
 	.expect("valid responses request");
 
-	let translated = super::from_responses::translate(&req, &provider, None, None)
+	let translated = super::from_responses::translate(&req, &provider, None, None, None)
 		.unwrap()
 		.body;
 	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
@@ -1187,6 +1321,8 @@ fn test_responses_vendor_extension_thinking_budget_forces_enabled_thinking() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1199,7 +1335,7 @@ fn test_responses_vendor_extension_thinking_budget_forces_enabled_thinking() {
 	}))
 	.expect("valid responses request");
 
-	let translated = super::from_responses::translate(&req, &provider, None, None)
+	let translated = super::from_responses::translate(&req, &provider, None, None, None)
 		.unwrap()
 		.body;
 	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
@@ -1222,6 +1358,8 @@ fn test_embeddings_translation_titan() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = types::embeddings::Request {
@@ -1248,6 +1386,8 @@ fn test_embeddings_titan_with_encoding_format() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = types::embeddings::Request {
@@ -1277,6 +1417,8 @@ fn test_embeddings_titan_rejects_array_input() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = types::embeddings::Request {
@@ -1301,6 +1443,8 @@ fn test_embeddings_cohere_with_passthrough_fields() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = types::embeddings::Request {
@@ -1327,6 +1471,8 @@ fn test_embeddings_rejects_invalid_input() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	for input in [json!(["hello", 42]), json!(42)] {
@@ -1509,6 +1655,95 @@ fn test_insert_cache_point_empty_messages_noop() {
 	assert!(msgs.is_empty());
 }
 
+fn translate_with_caching(
+	model: &str,
+	prompt_caching: &crate::PromptCachingConfig,
+) -> serde_json::Value {
+	let provider = Provider {
+		model: None,
+		region: strng::new("us-east-1"),
+		guardrail_identifier: None,
+		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
+	};
+	let req: types::completions::Request = serde_json::from_value(json!({
+		"model": model,
+		"messages": [
+			{"role": "system", "content": "You are a helpful assistant."},
+			{"role": "user", "content": "Hello"},
+			{"role": "assistant", "content": "Hi"},
+			{"role": "user", "content": "How are you?"}
+		]
+	}))
+	.unwrap();
+	let translated =
+		super::from_completions::translate(&req, &provider, None, None, Some(prompt_caching))
+			.unwrap()
+			.body;
+	serde_json::from_slice(&translated).unwrap()
+}
+
+fn count_cache_points(v: &serde_json::Value) -> usize {
+	match v {
+		serde_json::Value::Object(o) => {
+			usize::from(o.contains_key("cachePoint")) + o.values().map(count_cache_points).sum::<usize>()
+		},
+		serde_json::Value::Array(a) => a.iter().map(count_cache_points).sum(),
+		_ => 0,
+	}
+}
+
+#[test]
+fn test_prompt_caching_skips_unknown_models() {
+	let caching = crate::PromptCachingConfig {
+		min_tokens: None,
+		..Default::default()
+	};
+	let out = translate_with_caching("acme.future-model-v1", &caching);
+	assert_eq!(count_cache_points(&out), 0);
+}
+
+#[test]
+fn test_prompt_caching_operator_supported_model_inserts_cache_points() {
+	let caching = crate::PromptCachingConfig {
+		min_tokens: None,
+		supported_models: vec![strng::new("ACME.future-model")],
+		..Default::default()
+	};
+	let out = translate_with_caching("acme.future-model-v1", &caching);
+	let system = out["system"].as_array().expect("system blocks");
+	assert!(system.iter().any(|b| b.get("cachePoint").is_some()));
+	let messages = out["messages"].as_array().expect("messages");
+	assert!(
+		messages[1]["content"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.any(|b| b.get("cachePoint").is_some())
+	);
+}
+
+#[test]
+fn test_prompt_caching_builtin_models_still_supported() {
+	assert!(helpers::supports_prompt_caching(
+		"anthropic.claude-3-sonnet",
+		None
+	));
+	let caching = crate::PromptCachingConfig {
+		supported_models: vec![strng::new("acme.")],
+		..Default::default()
+	};
+	assert!(helpers::supports_prompt_caching(
+		"us.amazon.nova-pro-v1:0",
+		Some(&caching)
+	));
+	assert!(!helpers::supports_prompt_caching(
+		"anthropic.claude-v2",
+		Some(&caching)
+	));
+}
+
 #[test]
 fn test_bedrock_tool_name_sanitizes_long_mcp_names() {
 	let long_name = "mcp__plugin_atlassian_atlassian__createCompassComponentRelationship";
@@ -1552,6 +1787,8 @@ fn test_messages_long_tool_names_fit_bedrock_tool_config() {
 		region: strng::new("us-west-2"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::Request {
@@ -1608,6 +1845,8 @@ fn test_messages_long_tool_name_round_trip_response() {
 		region: strng::new("us-west-2"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req = messages::Request {
@@ -1699,6 +1938,8 @@ fn test_responses_input_image_data_url_maps_to_converse_image_block() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1718,7 +1959,7 @@ fn test_responses_input_image_data_url_maps_to_converse_image_block() {
 	}))
 	.expect("valid responses request");
 
-	let translated = super::from_responses::translate(&req, &provider, None, None)
+	let translated = super::from_responses::translate(&req, &provider, None, None, None)
 		.unwrap()
 		.body;
 	let translated: serde_json::Value = serde_json::from_slice(&translated).unwrap();
@@ -1741,6 +1982,8 @@ fn test_responses_assistant_input_image_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1757,7 +2000,7 @@ fn test_responses_assistant_input_image_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err
@@ -1773,6 +2016,8 @@ fn test_responses_input_image_remote_url_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1789,7 +2034,7 @@ fn test_responses_input_image_remote_url_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err
@@ -1805,6 +2050,8 @@ fn test_responses_input_image_non_base64_data_url_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1821,7 +2068,7 @@ fn test_responses_input_image_non_base64_data_url_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err
@@ -1837,6 +2084,8 @@ fn test_responses_input_image_non_image_data_url_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1853,7 +2102,7 @@ fn test_responses_input_image_non_image_data_url_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err
@@ -1869,6 +2118,8 @@ fn test_responses_input_image_empty_media_type_data_url_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1885,7 +2136,7 @@ fn test_responses_input_image_empty_media_type_data_url_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err
@@ -1901,6 +2152,8 @@ fn test_responses_input_image_file_id_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1917,7 +2170,7 @@ fn test_responses_input_image_file_id_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 }
 
@@ -1928,6 +2181,8 @@ fn test_responses_system_input_file_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1944,7 +2199,7 @@ fn test_responses_system_input_file_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err
@@ -1961,6 +2216,8 @@ fn test_responses_input_file_id_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -1976,7 +2233,7 @@ fn test_responses_input_file_id_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err.to_string().contains("file_id is unsupported"),
@@ -1991,6 +2248,8 @@ fn test_responses_input_file_remote_url_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -2007,7 +2266,7 @@ fn test_responses_input_file_remote_url_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err.to_string().contains("remote URLs are unsupported"),
@@ -2022,6 +2281,8 @@ fn test_responses_input_file_data_url_maps_to_document_block() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	// PDF via file_data data URL — format derived from MIME type
@@ -2042,7 +2303,7 @@ fn test_responses_input_file_data_url_maps_to_document_block() {
 	}))
 	.expect("valid responses request");
 
-	let bedrock_req = super::from_responses::translate(&req, &provider, None, None)
+	let bedrock_req = super::from_responses::translate(&req, &provider, None, None, None)
 		.expect("translation should succeed")
 		.body;
 	let body: serde_json::Value = serde_json::from_slice(&bedrock_req).expect("valid JSON");
@@ -2061,6 +2322,8 @@ fn test_responses_input_file_data_url_in_file_url_field_maps_to_document_block()
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	// CSV via file_url data URL — format derived from filename extension
@@ -2078,7 +2341,7 @@ fn test_responses_input_file_data_url_in_file_url_field_maps_to_document_block()
 	}))
 	.expect("valid responses request");
 
-	let bedrock_req = super::from_responses::translate(&req, &provider, None, None)
+	let bedrock_req = super::from_responses::translate(&req, &provider, None, None, None)
 		.expect("translation should succeed")
 		.body;
 	let body: serde_json::Value = serde_json::from_slice(&bedrock_req).expect("valid JSON");
@@ -2095,6 +2358,8 @@ fn test_responses_input_file_format_from_extension_fallback() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	// Unknown MIME type but known extension — format derived from filename
@@ -2112,7 +2377,7 @@ fn test_responses_input_file_format_from_extension_fallback() {
 	}))
 	.expect("valid responses request");
 
-	let bedrock_req = super::from_responses::translate(&req, &provider, None, None)
+	let bedrock_req = super::from_responses::translate(&req, &provider, None, None, None)
 		.expect("translation should succeed")
 		.body;
 	let body: serde_json::Value = serde_json::from_slice(&bedrock_req).expect("valid JSON");
@@ -2127,6 +2392,8 @@ fn test_responses_input_file_unknown_format_is_rejected() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	let req: types::responses::Request = serde_json::from_value(json!({
@@ -2143,7 +2410,7 @@ fn test_responses_input_file_unknown_format_is_rejected() {
 	}))
 	.expect("valid responses request");
 
-	let err = super::from_responses::translate(&req, &provider, None, None).unwrap_err();
+	let err = super::from_responses::translate(&req, &provider, None, None, None).unwrap_err();
 	assert!(matches!(err, crate::AIError::UnsupportedConversion(_)));
 	assert!(
 		err
@@ -2160,6 +2427,8 @@ fn test_responses_input_file_duplicate_names_are_deduplicated() {
 		region: strng::new("us-east-1"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 
 	// Bedrock requires unique document names within a request
@@ -2189,7 +2458,7 @@ fn test_responses_input_file_duplicate_names_are_deduplicated() {
 	}))
 	.expect("valid responses request");
 
-	let bedrock_req = super::from_responses::translate(&req, &provider, None, None)
+	let bedrock_req = super::from_responses::translate(&req, &provider, None, None, None)
 		.expect("translation should succeed")
 		.body;
 	let body: serde_json::Value = serde_json::from_slice(&bedrock_req).expect("valid JSON");
@@ -2198,3 +2467,128 @@ fn test_responses_input_file_duplicate_names_are_deduplicated() {
 	assert_eq!(content[2]["document"]["name"], json!("report [2]"));
 	assert_eq!(content[3]["document"]["name"], json!("document"));
 }
+
+fn converse_event_stream(events: &[(&str, serde_json::Value)]) -> axum_core::body::Body {
+	use aws_smithy_eventstream::frame::write_message_to;
+	use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
+
+	let mut encoded = bytes::BytesMut::new();
+	for (event_type, payload) in events {
+		let message = Message::new(Bytes::from(payload.to_string()))
+			.add_header(Header::new(
+				":message-type",
+				HeaderValue::String("event".into()),
+			))
+			.add_header(Header::new(
+				":event-type",
+				HeaderValue::String(event_type.to_string().into()),
+			));
+		write_message_to(&message, &mut encoded).expect("message should encode");
+	}
+	axum_core::body::Body::from(encoded.freeze())
+}
+
+fn sse_events(body: &[u8]) -> Vec<(String, serde_json::Value)> {
+	std::str::from_utf8(body)
+		.unwrap()
+		.split("\n\n")
+		.filter(|e| !e.trim().is_empty())
+		.filter_map(|e| {
+			let mut name = "message".to_string();
+			let mut data = None;
+			for line in e.lines() {
+				if let Some(n) = line.strip_prefix("event: ") {
+					name = n.to_string();
+				} else if let Some(d) = line.strip_prefix("data: ") {
+					data = serde_json::from_str(d).ok();
+				}
+			}
+			Some((name, data?))
+		})
+		.collect()
+}
+
+#[tokio::test]
+async fn test_stream_skips_unknown_event_types() {
+	let events = [
+		("messageStart", json!({"role": "assistant"})),
+		(
+			"contentBlockDelta",
+			json!({"contentBlockIndex": 0, "delta": {"text": "Hello"}}),
+		),
+		(
+			"someFutureEvent",
+			json!({"contentBlockIndex": 0, "whatever": {"nested": true}}),
+		),
+		(
+			"contentBlockDelta",
+			json!({"contentBlockIndex": 0, "delta": {"text": " world"}}),
+		),
+		("contentBlockStop", json!({"contentBlockIndex": 0})),
+		("messageStop", json!({"stopReason": "end_turn"})),
+		(
+			"metadata",
+			json!({"usage": {"inputTokens": 3, "outputTokens": 2, "totalTokens": 5}, "metrics": {"latencyMs": 1}}),
+		),
+	];
+
+	let body = super::from_messages::translate_stream(
+		converse_event_stream(&events),
+		1 << 20,
+		crate::StreamingUsageGuard::default(),
+		"anthropic.claude-3-5-haiku",
+		"msg_1",
+		false,
+		None,
+	);
+	let out = sse_events(&body.collect().await.unwrap().to_bytes());
+	assert!(out.iter().all(|(name, _)| name != "error"), "{out:?}");
+	let text: String = out
+		.iter()
+		.filter_map(|(_, data)| data["delta"]["text"].as_str())
+		.collect();
+	assert_eq!(text, "Hello world");
+	assert!(out.iter().any(|(name, _)| name == "message_stop"));
+
+	let body = super::from_completions::translate_stream(
+		converse_event_stream(&events),
+		1 << 20,
+		crate::StreamingUsageGuard::default(),
+		"anthropic.claude-3-5-haiku",
+		"msg_1",
+		None,
+	);
+	let out = body.collect().await.unwrap().to_bytes();
+	let out = std::str::from_utf8(&out).unwrap();
+	let chunks: Vec<serde_json::Value> = out
+		.split("\n\n")
+		.filter_map(|e| e.trim().strip_prefix("data: "))
+		.filter(|d| *d != "[DONE]")
+		.map(|d| serde_json::from_str(d).unwrap())
+		.collect();
+	let text: String = chunks
+		.iter()
+		.filter_map(|c| c["choices"][0]["delta"]["content"].as_str())
+		.collect();
+	assert_eq!(text, "Hello world");
+}
+
+#[tokio::test]
+async fn test_stream_malformed_known_event_is_an_error() {
+	let events = [
+		("messageStart", json!({"role": "assistant"})),
+		("contentBlockDelta", json!({"delta": "not-a-delta"})),
+	];
+
+	let body = super::from_messages::translate_stream(
+		converse_event_stream(&events),
+		1 << 20,
+		crate::StreamingUsageGuard::default(),
+		"anthropic.claude-3-5-haiku",
+		"msg_1",
+		false,
+		None,
+	);
+	let out = sse_events(&body.collect().await.unwrap().to_bytes());
+	assert_eq!(out.last().map(|(name, _)| name.as_str()), Some("error"));
+}
@@ -1,3 +1,510 @@
+#[cfg(test)]
+#[path = "gemini_tests.rs"]
+mod tests;
+
+pub mod from_completions {
+	use std::collections::HashMap;
+	use std::time::Instant;
+
+	use agent_core::strng;
+	use axum_core::body::Body;
+	use bytes::Bytes;
+	use types::completions::typed as completions;
+	use types::gemini;
+
+	use crate::conversion::completions::{extract_system_text, parse_data_url};
+	use crate::types::ResponseType;
+	use crate::types::completions::typed::{UsageCompletionDetails, UsagePromptDetails};
+	use crate::{AIError, StreamingUsageGuard, json, logged_response_parsing, parse, types};
+
+	fn text_part(text: impl Into<String>) -> gemini::Part {
+		gemini::Part {
+			text: Some(text.into()),
+			..Default::default()
+		}
+	}
+
+	fn user_parts(content: &completions::RequestUserMessageContent) -> Vec<gemini::Part> {
+		match content {
+			completions::RequestUserMessageContent::Text(text) => vec![text_part(text)],
+			completions::RequestUserMessageContent::Array(parts) => parts
+				.iter()
+				.filter_map(|part| match part {
+					completions::RequestUserMessageContentPart::Text(text) => Some(text_part(&text.text)),
+					completions::RequestUserMessageContentPart::ImageUrl(image) => {
+						// Only inline images can be forwarded; Gemini cannot fetch arbitrary URLs.
+						let (mime_type, data) = parse_data_url(&image.image_url.url)?;
+						Some(gemini::Part {
+							inline_data: Some(gemini::Blob {
+								mime_type: mime_type.to_string(),
+								data: data.to_string(),
+							}),
+							..Default::default()
+						})
+					},
+					completions::RequestUserMessageContentPart::InputAudio(_)
+					| completions::RequestUserMessageContentPart::File(_) => None,
+				})
+				.collect(),
+		}
+	}
+
+	fn assistant_parts(msg: &completions::RequestAssistantMessage) -> Vec<gemini::Part> {
+		let mut parts = Vec::new();
+		match &msg.content {
+			Some(completions::RequestAssistantMessageContent::Text(text)) if !text.trim().is_empty() => {
+				parts.push(text_part(text));
+			},
+			Some(completions::RequestAssistantMessageContent::Array(content)) => {
+				for part in content {
+					match part {
+						completions::RequestAssistantMessageContentPart::Text(text) => {
+							parts.push(text_part(&text.text));
+						},
+						completions::RequestAssistantMessageContentPart::Refusal(refusal) => {
+							parts.push(text_part(&refusal.refusal));
+						},
+					}
+				}
+			},
+			_ => {},
+		}
+		for call in msg.tool_calls.iter().flatten() {
+			let (id, name, args) = match call {
+				completions::MessageToolCalls::Function(call) => {
+					(&call.id, &call.function.name, &call.function.arguments)
+				},
+				completions::MessageToolCalls::Custom(call) => {
+					(&call.id, &call.custom_tool.name, &call.custom_tool.input)
+				},
+			};
+			parts.push(gemini::Part {
+				function_call: Some(gemini::FunctionCall {
+					id: Some(id.clone()),
+					name: name.clone(),
+					args: serde_json::from_str(args).unwrap_or_else(|_| serde_json::json!({})),
+				}),
+				..Default::default()
+			});
+		}
+		parts
+	}
+
+	fn tool_part(
+		msg: &completions::RequestToolMessage,
+		tool_names: &HashMap<&str, &str>,
+	) -> Result<gemini::Part, AIError> {
+		// Gemini correlates function responses by name, while OpenAI only carries the call id.
+		let name = tool_names
+			.get(msg.tool_call_id.as_str())
+			.ok_or_else(|| AIError::MissingField(strng::format!("tool call {}", msg.tool_call_id)))?;
+		let text = match &msg.content {
+			completions::RequestToolMessageContent::Text(text) => text.clone(),
+			completions::RequestToolMessageContent::Array(parts) => parts
+				.iter()
+				.map(|completions::RequestToolMessageContentPart::Text(t)| t.text.as_str())
+				.collect::<Vec<_>>()
+				.join("\n"),
+		};
+		// The response must be a JSON object; wrap anything else.
+		let response = match serde_json::from_str::<serde_json::Value>(&text) {
+			Ok(v @ serde_json::Value::Object(_)) => v,
+			Ok(v) => serde_json::json!({ "result": v }),
+			Err(_) => serde_json::json!({ "result": text }),
+		};
+		Ok(gemini::Part {
+			function_response: Some(gemini::FunctionResponse {
+				id: Some(msg.tool_call_id.clone()),
+				name: name.to_string(),
+				response,
+			}),
+			..Default::default()
+		})
+	}
+
+	/// Append a content, merging consecutive turns from the same role as Gemini expects.
+	fn push_content(
+		contents: &mut Vec<gemini::Content>,
+		role: gemini::Role,
+		parts: Vec<gemini::Part>,
+	) {
+		if parts.is_empty() {
+			return;
+		}
+		match contents.last_mut() {
+			Some(last) if last.role == Some(role) => last.parts.extend(parts),
+			_ => contents.push(gemini::Content {
+				role: Some(role),
+				parts,
+			}),
+		}
+	}
+
+	/// translate an OpenAI completions request to a Gemini generateContent request
+	pub fn translate(req: &types::completions::Request) -> Result<Vec<u8>, AIError> {
+		let typed = json::convert::<_, completions::Request>(req).map_err(AIError::RequestParsing)?;
+		let xlated = translate_internal(typed)?;
+		serde_json::to_vec(&xlated).map_err(AIError::RequestMarshal)
+	}
+
+	pub(super) fn translate_internal(
+		req: completions::Request,
+	) -> Result<gemini::GenerateContentRequest, AIError> {
+		let system_text = req
+			.messages
+			.iter()
+			.filter_map(extract_system_text)
+			.collect::<Vec<String>>()
+			.join("\n");
+
+		let tool_names: HashMap<&str, &str> = req
+			.messages
+			.iter()
+			.filter_map(|msg| match msg {
+				completions::RequestMessage::Assistant(assistant) => assistant.tool_calls.as_ref(),
+				_ => None,
+			})
+			.flatten()
+			.map(|call| match call {
+				completions::MessageToolCalls::Function(call) => {
+					(call.id.as_str(), call.function.name.as_str())
+				},
+				completions::MessageToolCalls::Custom(call) => {
+					(call.id.as_str(), call.custom_tool.name.as_str())
+				},
+			})
+			.collect();
+
+		let mut contents = Vec::new();
+		for msg in &req.messages {
+			match msg {
+				completions::RequestMessage::System(_) | completions::RequestMessage::Developer(_) => {},
+				completions::RequestMessage::User(user) => {
+					push_content(&mut contents, gemini::Role::User, user_parts(&user.content))
+				},
+				completions::RequestMessage::Assistant(assistant) => push_content(
+					&mut contents,
+					gemini::Role::Model,
+					assistant_parts(assistant),
+				),
+				completions::RequestMessage::Tool(tool) => push_content(
+					&mut contents,
+					gemini::Role::User,
+					vec![tool_part(tool, &tool_names)?],
+				),
+				completions::RequestMessage::Function(function) => {
+					if let Some(content) = function.content.as_ref().filter(|s| !s.trim().is_empty()) {
+						push_content(&mut contents, gemini::Role::User, vec![text_part(content)]);
+					}
+				},
+			}
+		}
+
+		let declarations: Vec<_> = req
+			.tools
+			.into_iter()
+			.flatten()
+			.filter_map(|tool| match tool {
+				completions::Tool::Function(tool) => Some(gemini::FunctionDeclaration {
+					name: tool.function.name,
+					description: tool.function.description,
+					parameters_json_schema: tool.function.parameters,
+				}),
+				_ => {
+					tracing::warn!("Unsupported tool type in Gemini conversion");
+					None
+				},
+			})
+			.collect();
+		let tools = if declarations.is_empty() {
+			vec![]
+		} else {
+			vec![gemini::Tool {
+				function_declarations: declarations,
+			}]
+		};
+
+		let function_calling_config = match req.tool_choice {
+			Some(completions::ToolChoiceOption::Function(completions::NamedToolChoice { function })) => {
+				Some(gemini::FunctionCallingConfig {
+					mode: gemini::FunctionCallingMode::Any,
+					allowed_function_names: Some(vec![function.name]),
+				})
+			},
+			Some(completions::ToolChoiceOption::Mode(mode)) => Some(gemini::FunctionCallingConfig {
+				mode: match mode {
+					completions::ToolChoiceOptions::None => gemini::FunctionCallingMode::None,
+					completions::ToolChoiceOptions::Auto => gemini::FunctionCallingMode::Auto,
+					completions::ToolChoiceOptions::Required => gemini::FunctionCallingMode::Any,
+				},
+				allowed_function_names: None,
+			}),
+			_ => None,
+		};
+		let tool_config =
+			function_calling_config
+				.filter(|_| !tools.is_empty())
+				.map(|function_calling_config| gemini::ToolConfig {
+					function_calling_config,
+				});
+
+		let (response_mime_type, response_json_schema) = match &req.response_format {
+			Some(completions::ResponseFormat::JsonObject) => (Some("application/json".to_string()), None),
+			Some(completions::ResponseFormat::JsonSchema { json_schema }) => (
+				Some("application/json".to_string()),
+				Some(json_schema.schema.clone()),
+			),
+			_ => (None, None),
+		};
+
+		let generation_config = gemini::GenerationConfig {
+			temperature: req.temperature,
+			top_p: req.top_p,
+			top_k: req.vendor_extensions.top_k,
+			max_output_tokens: req.max_tokens_option(),
+			stop_sequences: req.stop_sequence(),
+			candidate_count: req.n,
+			seed: req.seed,
+			presence_penalty: req.presence_penalty,
+			frequency_penalty: req.frequency_penalty,
+			response_mime_type,
+			response_json_schema,
+		};
+
+		Ok(gemini::GenerateContentRequest {
+			contents,
+			system_instruction: (!system_text.is_empty()).then(|| gemini::Content {
+				role: None,
+				parts: vec![text_part(system_text)],
+			}),
+			tools,
+			tool_config,
+			generation_config: Some(generation_config),
+		})
+	}
+
+	pub fn translate_response(bytes: &Bytes, model: &str) -> Result<Box<dyn ResponseType>, AIError> {
+		let resp = serde_json::from_slice::<gemini::GenerateContentResponse>(bytes)
+			.map_err(logged_response_parsing(bytes))?;
+		let openai = translate_response_internal(resp, model);
+		let passthrough = json::convert::<_, types::completions::Response>(&openai)
+			.map_err(AIError::ResponseParsing)?;
+		Ok(Box::new(passthrough))
+	}
+
+	fn translate_response_internal(
+		resp: gemini::GenerateContentResponse,
+		model: &str,
+	) -> completions::Response {
+		let mut tool_index = 0;
+		let choices = resp
+			.candidates
+			.into_iter()
+			.enumerate()
+			.map(|(i, candidate)| {
+				let mut content: Option<String> = None;
+				let mut reasoning_content: Option<String> = None;
+				let mut tool_calls = Vec::new();
+				for part in candidate.content.into_iter().flat_map(|c| c.parts) {
+					if let Some(call) = part.function_call {
+						tool_calls.push(completions::MessageToolCalls::Function(
+							completions::MessageToolCall {
+								id: call.id.unwrap_or_else(|| tool_call_id(tool_index)),
+								function: completions::FunctionCall {
+									name: call.name,
+									arguments: call.args.to_string(),
+								},
+							},
+						));
+						tool_index += 1;
+					} else if let Some(text) = part.text {
+						let target = if part.thought == Some(true) {
+							&mut reasoning_content
+						} else {
+							&mut content
+						};
+						target.get_or_insert_with(String::new).push_str(&text);
+					}
+				}
+				let finish_reason = candidate
+					.finish_reason
+					.map(|r| translate_finish_reason(r, !tool_calls.is_empty()));
+				completions::ChatChoice {
+					index: candidate.index.unwrap_or(i as u32),
+					message: completions::ResponseMessage {
+						role: completions::Role::Assistant,
+						content,
+						tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+						#[allow(deprecated)]
+						function_call: None,
+						refusal: None,
+						audio: None,
+						extra: None,
+						reasoning_content,
+						reasoning_signature: None,
+					},
+					finish_reason,
+					logprobs: None,
+				}
+			})
+			.collect();
+
+		completions::Response {
+			id: resp
+				.response_id
+				.unwrap_or_else(|| format!("gemini-{}", chrono::Utc::now().timestamp_millis())),
+			object: "chat.completion".to_string(),
+			created: chrono::Utc::now().timestamp() as u32,
+			model: resp.model_version.unwrap_or_else(|| model.to_string()),
+			choices,
+			usage: Some(resp.usage_metadata.map(translate_usage).unwrap_or_default()),
+			service_tier: None,
+			system_fingerprint: None,
+		}
+	}
+
+	pub fn translate_error(bytes: &Bytes) -> Result<Bytes, AIError> {
+		crate::conversion::completions::translate_google_error(bytes)
+	}
+
+	pub fn translate_stream(
+		b: Body,
+		buffer_limit: usize,
+		log: StreamingUsageGuard,
+		model: &str,
+	) -> Body {
+		// This is static for all chunks!
+		let created = chrono::Utc::now().timestamp() as u32;
+		let fallback_id = format!("gemini-{}", chrono::Utc::now().timestamp_millis());
+		let model = model.to_string();
+		let mut saw_token = false;
+		let mut sent_role = false;
+		// Gemini sends each function call whole, so every call gets its own tool call index.
+		let mut tool_index = 0;
+		let mut saw_tool_call = false;
+		let body =
+			parse::sse::json_transform::<gemini::GenerateContentResponse, _>(b, buffer_limit, move |f| {
+				let resp = f.ok()?;
+				if !saw_token {
+					saw_token = true;
+					log.update(|r| {
+						r.response.first_token = Some(Instant::now());
+					});
+				}
+				let usage = resp.usage_metadata.map(|u| {
+					log.update(|r| {
+						r.response.input_tokens = Some(u.prompt_token_count);
+						r.response.output_tokens = Some(u.candidates_token_count);
+						r.response.total_tokens = Some(u.total_token_count);
+						r.response.cached_input_tokens = u.cached_content_token_count;
+						r.response.reasoning_tokens = u.thoughts_token_count;
+					});
+					translate_usage(u)
+				});
+
+				let mut delta = completions::StreamResponseDelta::default();
+				if !sent_role {
+					sent_role = true;
+					delta.role = Some(completions::Role::Assistant);
+				}
+				let candidate = resp.candidates.into_iter().next();
+				let finish_reason = candidate.as_ref().and_then(|c| c.finish_reason);
+				for part in candidate
+					.and_then(|c| c.content)
+					.into_iter()
+					.flat_map(|c| c.parts)
+				{
+					if let Some(call) = part.function_call {
+						saw_tool_call = true;
+						delta.tool_calls.get_or_insert_with(Vec::new).push(
+							completions::ChatCompletionMessageToolCallChunk {
+								index: tool_index,
+								id: Some(call.id.unwrap_or_else(|| tool_call_id(tool_index))),
+								r#type: Some(completions::FunctionType::Function),
+								function: Some(completions::FunctionCallStream {
+									name: Some(call.name),
+									arguments: Some(call.args.to_string()),
+								}),
+							},
+						);
+						tool_index += 1;
+					} else if let Some(text) = part.text {
+						let target = if part.thought == Some(true) {
+							&mut delta.reasoning_content
+						} else {
+							&mut delta.content
+						};
+						target.get_or_insert_with(String::new).push_str(&text);
+					}
+				}
+				let choice = completions::ChatChoiceStream {
+					index: 0,
+					logprobs: None,
+					delta,
+					finish_reason: finish_reason.map(|r| translate_finish_reason(r, saw_tool_call)),
+				};
+				Some(completions::StreamResponse {
+					id: resp.response_id.unwrap_or_else(|| fallback_id.clone()),
+					model: resp.model_version.unwrap_or_else(|| model.clone()),
+					object: "chat.completion.chunk".to_string(),
+					system_fingerprint: None,
+					service_tier: None,
+					created,
+					choices: vec![choice],
+					// Gemini reports running totals on every chunk; only surface the final one.
+					usage: usage.filter(|_| finish_reason.is_some()),
+				})
+			});
+		// Gemini does not terminate the stream with [DONE], but OpenAI clients expect it.
+		crate::conversion::bedrock::from_completions::append_done_on_success(body.into_data_stream())
+	}
+
+	fn tool_call_id(index: u32) -> String {
+		format!("call_{index}")
+	}
+
+	fn translate_usage(u: gemini::UsageMetadata) -> completions::Usage {
+		completions::Usage {
+			prompt_tokens: u.prompt_token_count as u32,
+			completion_tokens: u.candidates_token_count as u32,
+			total_tokens: u.total_token_count as u32,
+			completion_tokens_details: u.thoughts_token_count.map(|t| UsageCompletionDetails {
+				reasoning_tokens: Some(t),
+				audio_tokens: None,
+				rest: Default::default(),
+			}),
+			prompt_tokens_details: u.cached_content_token_count.map(|t| UsagePromptDetails {
+				cached_tokens: Some(t),
+				audio_tokens: None,
+				rest: Default::default(),
+			}),
+			cache_read_input_tokens: u.cached_content_token_count,
+			cache_creation_input_tokens: None,
+		}
+	}
+
+	pub fn translate_finish_reason(
+		reason: gemini::FinishReason,
+		called_tool: bool,
+	) -> completions::FinishReason {
+		match reason {
+			// Gemini reports STOP after emitting function calls.
+			gemini::FinishReason::Stop if called_tool => completions::FinishReason::ToolCalls,
+			gemini::FinishReason::Stop => completions::FinishReason::Stop,
+			gemini::FinishReason::MaxTokens => completions::FinishReason::Length,
+			gemini::FinishReason::Safety
+			| gemini::FinishReason::Recitation
+			| gemini::FinishReason::Blocklist
+			| gemini::FinishReason::ProhibitedContent
+			| gemini::FinishReason::Spii
+			| gemini::FinishReason::ImageSafety => completions::FinishReason::ContentFilter,
+			gemini::FinishReason::MalformedFunctionCall
+			| gemini::FinishReason::UnexpectedToolCall
+			| gemini::FinishReason::Other => completions::FinishReason::Stop,
+		}
+	}
+}
+
 pub mod from_responses {
 	use bytes::Bytes;
 
@@ -0,0 +1,193 @@
+use axum_core::body::Body;
+use bytes::Bytes;
+use http_body_util::BodyExt;
+use serde_json::json;
+
+use super::*;
+use crate::StreamingUsageGuard;
+use crate::types;
+
+fn completions_request(v: serde_json::Value) -> types::completions::Request {
+	serde_json::from_value(v).unwrap()
+}
+
+fn translate(v: serde_json::Value) -> serde_json::Value {
+	let body = from_completions::translate(&completions_request(v)).unwrap();
+	serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn test_function_calling_request() {
+	let raw = translate(json!({
+		"model": "gemini-2.5-flash",
+		"messages": [
+			{"role": "system", "content": "You are a weather bot."},
+			{"role": "user", "content": "Weather in Paris?"}
+		],
+		"tools": [{
+			"type": "function",
+			"function": {
+				"name": "get_weather",
+				"description": "Get the weather",
+				"parameters": {
+					"type": "object",
+					"properties": {"city": {"type": "string"}},
+					"required": ["city"]
+				}
+			}
+		}],
+		"tool_choice": {"type": "function", "function": {"name": "get_weather"}},
+		"max_completion_tokens": 256,
+		"temperature": 0.2
+	}));
+
+	assert_eq!(
+		raw["systemInstruction"],
+		json!({"parts": [{"text": "You are a weather bot."}]})
+	);
+	assert_eq!(
+		raw["contents"],
+		json!([{"role": "user", "parts": [{"text": "Weather in Paris?"}]}])
+	);
+	assert_eq!(
+		raw["tools"],
+		json!([{"functionDeclarations": [{
+			"name": "get_weather",
+			"description": "Get the weather",
+			"parametersJsonSchema": {
+				"type": "object",
+				"properties": {"city": {"type": "string"}},
+				"required": ["city"]
+			}
+		}]}])
+	);
+	assert_eq!(
+		raw["toolConfig"],
+		json!({"functionCallingConfig": {"mode": "ANY", "allowedFunctionNames": ["get_weather"]}})
+	);
+	assert_eq!(raw["generationConfig"]["maxOutputTokens"], 256);
+	assert!(raw.get("model").is_none(), "model belongs in the path");
+}
+
+#[test]
+fn test_function_call_response() {
+	let resp = Bytes::from_static(
+		br#"{
+			"candidates": [{
+				"content": {
+					"role": "model",
+					"parts": [{"functionCall": {"name": "get_weather", "args": {"city": "Paris"}}}]
+				},
+				"finishReason": "STOP",
+				"index": 0
+			}],
+			"usageMetadata": {"promptTokenCount": 20, "candidatesTokenCount": 5, "totalTokenCount": 25},
+			"modelVersion": "gemini-2.5-flash",
+			"responseId": "resp-1"
+		}"#,
+	);
+	let translated = from_completions::translate_response(&resp, "gemini-2.5-flash").unwrap();
+	let raw: serde_json::Value = serde_json::from_slice(&translated.serialize().unwrap()).unwrap();
+
+	assert_eq!(raw["id"], "resp-1");
+	assert_eq!(raw["model"], "gemini-2.5-flash");
+	let choice = &raw["choices"][0];
+	assert_eq!(choice["finish_reason"], "tool_calls");
+	assert_eq!(choice["message"]["role"], "assistant");
+	let call = &choice["message"]["tool_calls"][0];
+	assert_eq!(call["id"], "call_0");
+	assert_eq!(call["type"], "function");
+	assert_eq!(call["function"]["name"], "get_weather");
+	let args: serde_json::Value =
+		serde_json::from_str(call["function"]["arguments"].as_str().unwrap()).unwrap();
+	assert_eq!(args, json!({"city": "Paris"}));
+	assert_eq!(raw["usage"]["prompt_tokens"], 20);
+	assert_eq!(raw["usage"]["completion_tokens"], 5);
+	assert_eq!(raw["usage"]["total_tokens"], 25);
+
+	let llm = translated.to_llm_response(false);
+	assert_eq!(llm.input_tokens, Some(20));
+	assert_eq!(llm.output_tokens, Some(5));
+}
+
+#[test]
+fn test_function_result_request() {
+	// Second leg of the round trip: the client replays the call and its result.
+	let raw = translate(json!({
+		"model": "gemini-2.5-flash",
+		"messages": [
+			{"role": "user", "content": "Weather in Paris?"},
+			{"role": "assistant", "content": null, "tool_calls": [{
+				"id": "call_0",
+				"type": "function",
+				"function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+			}]},
+			{"role": "tool", "tool_call_id": "call_0", "content": "{\"temp_c\": 18}"},
+			{"role": "tool", "tool_call_id": "call_0", "content": "sunny"}
+		]
+	}));
+
+	assert_eq!(
+		raw["contents"],
+		json!([
+			{"role": "user", "parts": [{"text": "Weather in Paris?"}]},
+			{"role": "model", "parts": [{"functionCall": {
+				"id": "call_0",
+				"name": "get_weather",
+				"args": {"city": "Paris"}
+			}}]},
+			{"role": "user", "parts": [
+				{"functionResponse": {"id": "call_0", "name": "get_weather", "response": {"temp_c": 18}}},
+				{"functionResponse": {"id": "call_0", "name": "get_weather", "response": {"result": "sunny"}}}
+			]}
+		])
+	);
+}
+
+#[test]
+fn test_function_result_requires_known_call() {
+	let req = completions_request(json!({
+		"model": "gemini-2.5-flash",
+		"messages": [
+			{"role": "user", "content": "hi"},
+			{"role": "tool", "tool_call_id": "missing", "content": "{}"}
+		]
+	}));
+	assert!(from_completions::translate(&req).is_err());
+}
+
+#[tokio::test]
+async fn test_function_call_stream() {
+	let upstream = concat!(
+		"data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"Checking\"}]}}],\"responseId\":\"r1\"}\r\n\r\n",
+		"data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"functionCall\":{\"name\":\"get_weather\",\"args\":{\"city\":\"Paris\"}}}]},\"finishReason\":\"STOP\"}],",
+		"\"usageMetadata\":{\"promptTokenCount\":20,\"candidatesTokenCount\":7,\"totalTokenCount\":27},\"responseId\":\"r1\"}\r\n\r\n",
+	);
+	let body = from_completions::translate_stream(
+		Body::from(upstream),
+		1 << 20,
+		StreamingUsageGuard::default(),
+		"gemini-2.5-flash",
+	);
+	let out = body.collect().await.unwrap().to_bytes();
+	let out = std::str::from_utf8(&out).unwrap();
+	let events: Vec<&str> = out
+		.split("\n\n")
+		.filter_map(|e| e.trim().strip_prefix("data: "))
+		.collect();
+	assert_eq!(events.len(), 3, "{out}");
+	assert_eq!(events[2], "[DONE]");
+
+	let first: serde_json::Value = serde_json::from_str(events[0]).unwrap();
+	assert_eq!(first["id"], "r1");
+	assert_eq!(first["choices"][0]["delta"]["role"], "assistant");
+	assert_eq!(first["choices"][0]["delta"]["content"], "Checking");
+	assert!(first["usage"].is_null());
+
+	let second: serde_json::Value = serde_json::from_str(events[1]).unwrap();
+	let call = &second["choices"][0]["delta"]["tool_calls"][0];
+	assert_eq!(call["index"], 0);
+	assert_eq!(call["function"]["name"], "get_weather");
+	assert_eq!(second["choices"][0]["finish_reason"], "tool_calls");
+	assert_eq!(second["usage"]["total_tokens"], 27);
+}
@@ -10,6 +10,8 @@ fn bedrock_provider(model: &str, region: &str) -> crate::bedrock::Provider {
 		region: agent_core::strng::new(region),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	}
 }
 
@@ -24,3 +24,14 @@ pub fn path(route: RouteType) -> &'static str {
 		_ => "/v1beta/openai/chat/completions",
 	}
 }
+
+/// Path for the native `generateContent` API, used instead of the OpenAI-compatible endpoint
+/// for chat completions.
+pub fn native_path(model: &str, streaming: bool) -> Strng {
+	let model = model.strip_prefix("models/").unwrap_or(model);
+	if streaming {
+		strng::format!("/v1beta/models/{model}:streamGenerateContent?alt=sse")
+	} else {
+		strng::format!("/v1beta/models/{model}:generateContent")
+	}
+}
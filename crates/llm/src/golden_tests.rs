@@ -149,24 +149,32 @@ fn request_conversion_golden() {
 		region: strng::new("us-west-2"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 	let bedrock_titan = bedrock::Provider {
 		model: Some(strng::new("amazon.titan-embed-text-v2:0")),
 		region: strng::new("us-west-2"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 	let bedrock_cohere = bedrock::Provider {
 		model: Some(strng::new("cohere.embed-english-v3")),
 		region: strng::new("us-west-2"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 	let bedrock_rerank = bedrock::Provider {
 		model: Some(strng::new("cohere.rerank-v3-5:0")),
 		region: strng::new("us-west-2"),
 		guardrail_identifier: None,
 		guardrail_version: None,
+		inference_profile: None,
+		latency_optimized: None,
 	};
 	let vertex_anthropic = vertex::Provider {
 		model: Some(strng::new("anthropic/claude-sonnet-4-5")),
@@ -186,7 +194,7 @@ fn request_conversion_golden() {
 		});
 		if name != "reasoning_max" {
 			test_request(BEDROCK, &path, |i| {
-				conversion::bedrock::from_completions::translate(&i, &bedrock_claude, None, None)
+				conversion::bedrock::from_completions::translate(&i, &bedrock_claude, None, None, None)
 					.map(|r| r.body)
 			});
 		}
@@ -198,7 +206,7 @@ fn request_conversion_golden() {
 	] {
 		let path = format!("requests/completions/{name}.json");
 		test_request(BEDROCK, &path, |i| {
-			conversion::bedrock::from_completions::translate(&i, &bedrock_claude, None, None)
+			conversion::bedrock::from_completions::translate(&i, &bedrock_claude, None, None, None)
 				.map(|r| r.body)
 		});
 	}
@@ -209,7 +217,7 @@ fn request_conversion_golden() {
 			conversion::completions::from_messages::translate(&i)
 		});
 		test_request(BEDROCK, &path, |i| {
-			conversion::bedrock::from_messages::translate(&i, &bedrock_claude, None).map(|r| r.body)
+			conversion::bedrock::from_messages::translate(&i, &bedrock_claude, None, None).map(|r| r.body)
 		});
 		test_request(VERTEX, &path, |input: types::messages::Request| {
 			let body = serde_json::to_vec(&input).map_err(AIError::RequestMarshal)?;
@@ -222,13 +230,13 @@ fn request_conversion_golden() {
 		|i| conversion::completions::from_messages::translate(&i),
 	);
 	test_request(BEDROCK, "requests/messages/reasoning_replay.json", |i| {
-		conversion::bedrock::from_messages::translate(&i, &bedrock_claude, None).map(|r| r.body)
+		conversion::bedrock::from_messages::translate(&i, &bedrock_claude, None, None).map(|r| r.body)
 	});
 
 	for name in ["basic", "instructions", "input-list", "parallel-tool-call"] {
 		let path = format!("requests/responses/{name}.json");
 		test_request(BEDROCK, &path, |i| {
-			conversion::bedrock::from_responses::translate(&i, &bedrock_claude, None, None)
+			conversion::bedrock::from_responses::translate(&i, &bedrock_claude, None, None, None)
 				.map(|r| r.body)
 		});
 		test_request(GEMINI, &path, |i| {
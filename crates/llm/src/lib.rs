@@ -106,7 +106,7 @@ pub enum RouteType {
 	Rerank,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum InputFormat {
 	Completions,
 	Messages,
@@ -146,6 +146,7 @@ pub enum ChatFormat {
 	OpenAIResponses,
 	AnthropicMessages,
 	BedrockConverse,
+	GeminiGenerateContent,
 }
 
 #[derive(Debug, Clone)]
@@ -384,6 +385,11 @@ pub struct PromptCachingConfig {
 	/// Message offset used when choosing where to place cache markers.
 	#[serde(rename = "cacheMessageOffset")]
 	pub cache_message_offset: usize,
+
+	/// Additional model IDs that support prompt caching, on top of the built-in list.
+	/// Each entry matches any model ID containing it, case-insensitively.
+	#[serde(rename = "supportedModels")]
+	pub supported_models: Vec<Strng>,
 }
 
 impl Default for PromptCachingConfig {
@@ -394,6 +400,7 @@ impl Default for PromptCachingConfig {
 			cache_tools: false,
 			min_tokens: Some(1024),
 			cache_message_offset: 0,
+			supported_models: Vec::new(),
 		}
 	}
 }
@@ -1,16 +1,159 @@
 use tiktoken_rs::CoreBPE;
-use tiktoken_rs::tokenizer::{Tokenizer, get_tokenizer};
+use tiktoken_rs::tokenizer::get_tokenizer;
 
-use crate::{AIError, SimpleChatCompletionMessage};
+use crate::{AIError, SimpleChatCompletionMessage, apply};
+
+/// An explicit tokenizer, used in place of the one tiktoken associates with the model.
+/// This is useful for models tiktoken does not know about.
+#[apply(schema!)]
+#[derive(Copy, PartialEq, Eq)]
+pub enum Tokenizer {
+	O200kHarmony,
+	O200kBase,
+	Cl100kBase,
+	R50kBase,
+	P50kBase,
+	P50kEdit,
+	Gpt2,
+}
+
+impl From<Tokenizer> for tiktoken_rs::tokenizer::Tokenizer {
+	fn from(value: Tokenizer) -> Self {
+		use tiktoken_rs::tokenizer::Tokenizer as T;
+		match value {
+			Tokenizer::O200kHarmony => T::O200kHarmony,
+			Tokenizer::O200kBase => T::O200kBase,
+			Tokenizer::Cl100kBase => T::Cl100kBase,
+			Tokenizer::R50kBase => T::R50kBase,
+			Tokenizer::P50kBase => T::P50kBase,
+			Tokenizer::P50kEdit => T::P50kEdit,
+			Tokenizer::Gpt2 => T::Gpt2,
+		}
+	}
+}
+
+/// Tokenize controls whether, and with which tokenizer, requests are tokenized upfront.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tokenize {
+	#[default]
+	Disabled,
+	/// Tokenize using the tokenizer associated with the request model.
+	FromModel,
+	/// Tokenize using an explicit tokenizer.
+	With(Tokenizer),
+}
+
+impl Tokenize {
+	pub fn new(enabled: bool, tokenizer: Option<Tokenizer>) -> Self {
+		match (enabled, tokenizer) {
+			(false, _) => Tokenize::Disabled,
+			(true, None) => Tokenize::FromModel,
+			(true, Some(t)) => Tokenize::With(t),
+		}
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		!matches!(self, Tokenize::Disabled)
+	}
+
+	pub fn tokenizer(&self) -> Option<Tokenizer> {
+		match self {
+			Tokenize::With(t) => Some(*t),
+			_ => None,
+		}
+	}
+}
+
+/// The detail tier of an image input, which determines its approximate token cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageDetail {
+	Low,
+	Auto,
+	High,
+}
+
+impl ImageDetail {
+	pub fn parse(detail: Option<&str>) -> Self {
+		match detail {
+			Some("low") => ImageDetail::Low,
+			Some("high") => ImageDetail::High,
+			_ => ImageDetail::Auto,
+		}
+	}
+
+	/// Approximate input tokens for one image of this tier.
+	///
+	/// Images are not decoded, so their resolution is unknown. These follow OpenAI's vision
+	/// pricing (85 base tokens plus 170 per 512px tile) for a typical image at each tier:
+	/// low detail is a flat 85, auto assumes a 1024x1024 image (4 tiles), and high assumes a
+	/// 1536x768 image after scaling (6 tiles). Other providers count differently, so this is
+	/// only meant to keep pre-request rate limiting in the right range.
+	pub fn tokens(self) -> u64 {
+		match self {
+			ImageDetail::Low => 85,
+			ImageDetail::Auto => 85 + 170 * 4,
+			ImageDetail::High => 85 + 170 * 6,
+		}
+	}
+}
+
+/// Request input that is not part of the message text, but still consumes input tokens.
+#[derive(Debug, Default)]
+pub struct NonTextInput<'a> {
+	pub images: Vec<ImageDetail>,
+	/// Tool definitions, counted as their serialized JSON.
+	pub tools: Vec<&'a serde_json::Value>,
+}
+
+impl NonTextInput<'_> {
+	/// Record every image content part found in `value`, in any of the OpenAI completions
+	/// (`image_url`), Responses (`input_image`) or Anthropic messages (`image`) shapes.
+	pub fn add_images(&mut self, value: &serde_json::Value) {
+		match value {
+			serde_json::Value::Object(o) => match o.get("type").and_then(|t| t.as_str()) {
+				Some("image_url") => self.images.push(ImageDetail::parse(
+					value.pointer("/image_url/detail").and_then(|d| d.as_str()),
+				)),
+				Some("input_image") => self
+					.images
+					.push(ImageDetail::parse(o.get("detail").and_then(|d| d.as_str()))),
+				Some("image") => self.images.push(ImageDetail::Auto),
+				_ => o.values().for_each(|v| self.add_images(v)),
+			},
+			serde_json::Value::Array(a) => a.iter().for_each(|v| self.add_images(v)),
+			_ => {},
+		}
+	}
+}
 
 pub fn num_tokens_from_messages(
 	model: &str,
+	tokenizer: Option<Tokenizer>,
 	messages: &[SimpleChatCompletionMessage],
 ) -> Result<u64, AIError> {
-	let tokenizer = get_tokenizer(model).unwrap_or(Tokenizer::Cl100kBase);
-	if tokenizer != Tokenizer::Cl100kBase && tokenizer != Tokenizer::O200kBase {
-		return Err(AIError::UnsupportedModel);
-	}
+	num_tokens_from_request(model, tokenizer, messages, &NonTextInput::default())
+}
+
+/// Estimate the input tokens of a request: the message text is tokenized, while images and
+/// tool definitions are approximated (see [`ImageDetail::tokens`]).
+pub fn num_tokens_from_request(
+	model: &str,
+	tokenizer: Option<Tokenizer>,
+	messages: &[SimpleChatCompletionMessage],
+	non_text: &NonTextInput,
+) -> Result<u64, AIError> {
+	let tokenizer = match tokenizer {
+		// An explicit tokenizer is always honored
+		Some(t) => t.into(),
+		None => {
+			use tiktoken_rs::tokenizer::Tokenizer as T;
+			let tokenizer = get_tokenizer(model).unwrap_or(T::Cl100kBase);
+			if tokenizer != T::Cl100kBase && tokenizer != T::O200kBase {
+				return Err(AIError::UnsupportedModel);
+			}
+			tokenizer
+		},
+	};
 	let bpe = get_bpe_from_tokenizer(tokenizer);
 	let tokens_per_message = 3;
 
@@ -23,6 +166,10 @@ pub fn num_tokens_from_messages(
 			.len() as u64;
 	}
 	num_tokens += 3;
+	num_tokens += non_text.images.iter().map(|i| i.tokens()).sum::<u64>();
+	for tool in &non_text.tools {
+		num_tokens += bpe.encode_with_special_tokens(&tool.to_string()).len() as u64;
+	}
 	Ok(num_tokens)
 }
 
@@ -31,7 +178,8 @@ pub fn preload_tokenizers() {
 	let _ = tiktoken_rs::o200k_base_singleton();
 }
 
-pub fn get_bpe_from_tokenizer<'a>(tokenizer: Tokenizer) -> &'a CoreBPE {
+pub fn get_bpe_from_tokenizer<'a>(tokenizer: tiktoken_rs::tokenizer::Tokenizer) -> &'a CoreBPE {
+	use tiktoken_rs::tokenizer::Tokenizer;
 	match tokenizer {
 		Tokenizer::O200kHarmony => tiktoken_rs::o200k_harmony_singleton(),
 		Tokenizer::O200kBase => tiktoken_rs::o200k_base_singleton(),
@@ -42,3 +190,102 @@ pub fn get_bpe_from_tokenizer<'a>(tokenizer: Tokenizer) -> &'a CoreBPE {
 		Tokenizer::Gpt2 => tiktoken_rs::r50k_base_singleton(),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use agent_core::strng;
+	use serde_json::json;
+
+	use super::*;
+	use crate::types::RequestType;
+
+	fn input_tokens<T: RequestType + serde::de::DeserializeOwned>(body: serde_json::Value) -> u64 {
+		let req: T = serde_json::from_value(body).unwrap();
+		req
+			.to_llm_request(strng::literal!("test"), Tokenize::FromModel)
+			.unwrap()
+			.input_tokens
+			.unwrap()
+	}
+
+	fn completions(content: serde_json::Value, tools: Option<serde_json::Value>) -> u64 {
+		let mut body = json!({
+			"model": "gpt-4o",
+			"messages": [{"role": "user", "content": content}]
+		});
+		if let Some(tools) = tools {
+			body["tools"] = tools;
+		}
+		input_tokens::<crate::types::completions::Request>(body)
+	}
+
+	#[test]
+	fn completions_images_add_tokens_by_detail() {
+		let text = json!([{"type": "text", "text": "What is in this image?"}]);
+		let with_image = |detail: Option<&str>| {
+			let mut image = json!({"url": "https://example.com/cat.png"});
+			if let Some(detail) = detail {
+				image["detail"] = json!(detail);
+			}
+			json!([
+				{"type": "text", "text": "What is in this image?"},
+				{"type": "image_url", "image_url": image}
+			])
+		};
+
+		let text_only = completions(text, None);
+		assert_eq!(
+			completions(with_image(None), None),
+			text_only + ImageDetail::Auto.tokens()
+		);
+		assert_eq!(completions(with_image(Some("low")), None), text_only + 85);
+		assert_eq!(
+			completions(with_image(Some("high")), None),
+			text_only + ImageDetail::High.tokens()
+		);
+	}
+
+	#[test]
+	fn completions_tools_add_tokens() {
+		let tools = json!([{
+			"type": "function",
+			"function": {
+				"name": "get_weather",
+				"parameters": {"type": "object", "properties": {"city": {"type": "string"}}}
+			}
+		}]);
+		let without = completions(json!("What's the weather?"), None);
+		let with = completions(json!("What's the weather?"), Some(tools));
+		assert!(with > without + 10, "{with} should include the tool schema");
+	}
+
+	#[test]
+	fn messages_and_responses_images_add_tokens() {
+		let messages = |content: serde_json::Value| {
+			input_tokens::<crate::types::messages::Request>(json!({
+				"model": "gpt-4o",
+				"max_tokens": 16,
+				"messages": [{"role": "user", "content": content}]
+			}))
+		};
+		let text_only = messages(json!([{"type": "text", "text": "Describe this"}]));
+		let with_image = messages(json!([
+			{"type": "text", "text": "Describe this"},
+			{"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}}
+		]));
+		assert_eq!(with_image, text_only + ImageDetail::Auto.tokens());
+
+		let responses = |content: serde_json::Value| {
+			input_tokens::<crate::types::responses::Request>(json!({
+				"model": "gpt-4o",
+				"input": [{"role": "user", "content": content}]
+			}))
+		};
+		let text_only = responses(json!([{"type": "input_text", "text": "Describe this"}]));
+		let with_image = responses(json!([
+			{"type": "input_text", "text": "Describe this"},
+			{"type": "input_image", "image_url": "https://example.com/cat.png", "detail": "low"}
+		]));
+		assert_eq!(with_image, text_only + ImageDetail::Low.tokens());
+	}
+}
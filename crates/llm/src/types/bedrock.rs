@@ -315,7 +315,15 @@ pub struct PromptVariableValues {
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct PerformanceConfiguration {
-	// TODO: Implement performance configuration
+	/// Whether to use standard or latency-optimized inference.
+	pub latency: PerformanceConfigLatency,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformanceConfigLatency {
+	Standard,
+	Optimized,
 }
 
 /// The actual response from the Bedrock Converse API (matches AWS SDK ConverseOutput)
@@ -469,6 +477,9 @@ pub enum ConverseStreamOutput {
 	MessageStop(MessageStopEvent),
 	/// Metadata for the converse output stream.
 	Metadata(ConverseStreamMetadataEvent),
+	/// A well-formed event of a type this gateway does not know yet. Translators skip these so
+	/// new Bedrock event types do not break streaming.
+	Unknown(String),
 }
 
 impl ConverseStreamOutput {
@@ -515,7 +526,7 @@ impl ConverseStreamOutput {
 			"metadata" => ConverseStreamOutput::Metadata(serde_json::from_slice::<
 				ConverseStreamMetadataEvent,
 			>(payload)?),
-			other => anyhow::bail!("unexpected event type: {other}"),
+			other => ConverseStreamOutput::Unknown(other.to_owned()),
 		})
 	}
 }
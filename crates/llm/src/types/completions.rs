@@ -3,6 +3,7 @@ use agent_core::strng::Strng;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+use crate::tokenizer::Tokenize;
 use crate::types::{ResponseType, SimpleChatCompletionMessage};
 use crate::webhook::{Message, ResponseChoice};
 use crate::{AIError, InputFormat, LLMRequest, LLMRequestParams, LLMResponse, json};
@@ -252,11 +253,16 @@ impl super::RequestType for Request {
 			.extend(prompts.into_iter().map(convert_message));
 	}
 
-	fn to_llm_request(&self, provider: Strng, tokenize: bool) -> Result<LLMRequest, AIError> {
+	fn to_llm_request(&self, provider: Strng, tokenize: Tokenize) -> Result<LLMRequest, AIError> {
 		let model = strng::new(self.model.as_deref().unwrap_or_default());
-		let input_tokens = if tokenize {
+		let input_tokens = if tokenize.is_enabled() {
 			let messages = self.get_messages();
-			let tokens = crate::tokenizer::num_tokens_from_messages(&model, &messages)?;
+			let tokens = crate::tokenizer::num_tokens_from_request(
+				&model,
+				tokenize.tokenizer(),
+				&messages,
+				&self.non_text_input(),
+			)?;
 			Some(tokens)
 		} else {
 			None
@@ -325,6 +331,13 @@ impl super::RequestType for Request {
 	fn set_messages(&mut self, messages: Vec<SimpleChatCompletionMessage>) {
 		self.messages = messages.into_iter().map(convert_message).collect();
 	}
+
+	fn normalize_roles(&mut self) -> Result<(), AIError> {
+		self
+			.messages
+			.iter_mut()
+			.try_for_each(|m| super::normalize_role_str(&mut m.role))
+	}
 }
 
 fn convert_message(r: SimpleChatCompletionMessage) -> RequestMessage {
@@ -378,6 +391,28 @@ pub struct ContentPart {
 	pub rest: serde_json::Value,
 }
 
+impl Request {
+	/// Image parts and tool definitions, which the tokenizer cannot count from message text.
+	fn non_text_input(&self) -> crate::tokenizer::NonTextInput<'_> {
+		let mut non_text = crate::tokenizer::NonTextInput::default();
+		for part in self.messages.iter().flat_map(|m| match &m.content {
+			Some(Content::Array(parts)) => parts.as_slice(),
+			_ => &[][..],
+		}) {
+			if part.r#type == "image_url" {
+				non_text.images.push(crate::tokenizer::ImageDetail::parse(
+					part
+						.rest
+						.pointer("/image_url/detail")
+						.and_then(|d| d.as_str()),
+				));
+			}
+		}
+		non_text.tools.extend(self.tools.iter().flatten());
+		non_text
+	}
+}
+
 impl TryInto<typed::Request> for &Request {
 	type Error = AIError;
 
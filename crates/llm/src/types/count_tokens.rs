@@ -3,6 +3,7 @@ use agent_core::strng;
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
+use crate::tokenizer::Tokenize;
 use crate::types::{RequestType, messages};
 use crate::{
 	AIError, InputFormat, LLMRequest, SimpleChatCompletionMessage, logged_response_parsing,
@@ -32,7 +33,7 @@ impl RequestType for Request {
 		messages::append_prompts_helper(&mut self.messages, &mut self.system, prompts);
 	}
 
-	fn to_llm_request(&self, provider: Strng, _tokenize: bool) -> Result<LLMRequest, AIError> {
+	fn to_llm_request(&self, provider: Strng, _tokenize: Tokenize) -> Result<LLMRequest, AIError> {
 		let model = strng::new(self.model.as_deref().unwrap_or_default());
 		Ok(LLMRequest {
 			// We never tokenize these, so always empty
@@ -6,6 +6,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use tracing::debug;
 
+use crate::tokenizer::Tokenize;
 use crate::webhook::ResponseChoice;
 use crate::{
 	AIError, InputFormat, LLMRequest, LLMRequestParams, LLMResponse, RequestType, ResponseType,
@@ -72,7 +73,7 @@ impl RequestType for Request {
 		// Not supported
 	}
 
-	fn to_llm_request(&self, provider: Strng, _tokenize: bool) -> Result<LLMRequest, AIError> {
+	fn to_llm_request(&self, provider: Strng, _tokenize: Tokenize) -> Result<LLMRequest, AIError> {
 		Ok(LLMRequest {
 			// We never tokenize these, so always empty
 			input_tokens: None,
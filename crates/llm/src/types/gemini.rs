@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+// ---- Gemini generateContent API ----
+// https://ai.google.dev/api/generate-content
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentRequest {
+	pub contents: Vec<Content>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub system_instruction: Option<Content>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub tools: Vec<Tool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tool_config: Option<ToolConfig>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub generation_config: Option<GenerationConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+	User,
+	Model,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Content {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub role: Option<Role>,
+	#[serde(default)]
+	pub parts: Vec<Part>,
+}
+
+/// A single part of a `Content`. Exactly one of the data fields is expected to be set.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Part {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub text: Option<String>,
+	/// Set when `text` is a thought summary rather than answer content.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub thought: Option<bool>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub inline_data: Option<Blob>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub function_call: Option<FunctionCall>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub function_response: Option<FunctionResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Blob {
+	pub mime_type: String,
+	pub data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FunctionCall {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+	pub name: String,
+	#[serde(default)]
+	pub args: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FunctionResponse {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+	pub name: String,
+	pub response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+	pub function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionDeclaration {
+	pub name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+	/// Full JSON schema for the arguments; unlike `parameters`, this is not limited to the OpenAPI subset.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parameters_json_schema: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfig {
+	pub function_calling_config: FunctionCallingConfig,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCallingConfig {
+	pub mode: FunctionCallingMode,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub allowed_function_names: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FunctionCallingMode {
+	Auto,
+	Any,
+	None,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationConfig {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub temperature: Option<f32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub top_p: Option<f32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub top_k: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_output_tokens: Option<u64>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub stop_sequences: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub candidate_count: Option<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub seed: Option<i64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub presence_penalty: Option<f32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub frequency_penalty: Option<f32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub response_mime_type: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub response_json_schema: Option<serde_json::Value>,
+}
+
+/// A full response, or a single streamed chunk when using `streamGenerateContent`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentResponse {
+	#[serde(default)]
+	pub candidates: Vec<Candidate>,
+	#[serde(default)]
+	pub usage_metadata: Option<UsageMetadata>,
+	#[serde(default)]
+	pub model_version: Option<String>,
+	#[serde(default)]
+	pub response_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+	#[serde(default)]
+	pub content: Option<Content>,
+	#[serde(default)]
+	pub finish_reason: Option<FinishReason>,
+	#[serde(default)]
+	pub index: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinishReason {
+	Stop,
+	MaxTokens,
+	Safety,
+	Recitation,
+	Blocklist,
+	ProhibitedContent,
+	Spii,
+	ImageSafety,
+	MalformedFunctionCall,
+	UnexpectedToolCall,
+	#[serde(other)]
+	Other,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetadata {
+	#[serde(default)]
+	pub prompt_token_count: u64,
+	#[serde(default)]
+	pub candidates_token_count: u64,
+	#[serde(default)]
+	pub total_token_count: u64,
+	#[serde(default)]
+	pub cached_content_token_count: Option<u64>,
+	#[serde(default)]
+	pub thoughts_token_count: Option<u64>,
+}
@@ -3,6 +3,7 @@ use agent_core::strng;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+use crate::tokenizer::Tokenize;
 use crate::types::{RequestType, ResponseType, SimpleChatCompletionMessage};
 use crate::webhook::{Message, ResponseChoice};
 use crate::{AIError, InputFormat, LLMRequest, LLMRequestParams, LLMResponse};
@@ -179,6 +180,30 @@ pub fn get_messages_helper(
 	out
 }
 
+impl Request {
+	/// Image blocks and tool definitions, which the tokenizer cannot count from message text.
+	fn non_text_input(&self) -> crate::tokenizer::NonTextInput<'_> {
+		let mut non_text = crate::tokenizer::NonTextInput::default();
+		for part in self.messages.iter().flat_map(|m| match &m.content {
+			Some(ContentBlock::Array(parts)) => parts.as_slice(),
+			_ => &[][..],
+		}) {
+			if let ContentPart::Unknown(v) = part {
+				non_text.add_images(v);
+			}
+		}
+		non_text.tools.extend(
+			self
+				.rest
+				.get("tools")
+				.and_then(|t| t.as_array())
+				.into_iter()
+				.flatten(),
+		);
+		non_text
+	}
+}
+
 impl RequestType for Request {
 	fn model(&mut self) -> &mut Option<String> {
 		&mut self.model
@@ -192,11 +217,16 @@ impl RequestType for Request {
 		append_prompts_helper(&mut self.messages, &mut self.system, prompts);
 	}
 
-	fn to_llm_request(&self, provider: Strng, tokenize: bool) -> Result<LLMRequest, AIError> {
+	fn to_llm_request(&self, provider: Strng, tokenize: Tokenize) -> Result<LLMRequest, AIError> {
 		let model = strng::new(self.model.as_deref().unwrap_or_default());
-		let input_tokens = if tokenize {
+		let input_tokens = if tokenize.is_enabled() {
 			let messages = self.get_messages();
-			let tokens = crate::tokenizer::num_tokens_from_messages(&model, &messages)?;
+			let tokens = crate::tokenizer::num_tokens_from_request(
+				&model,
+				tokenize.tokenizer(),
+				&messages,
+				&self.non_text_input(),
+			)?;
 			Some(tokens)
 		} else {
 			None
@@ -250,6 +280,13 @@ impl RequestType for Request {
 		};
 		self.messages = message_prompts.into_iter().map(Into::into).collect();
 	}
+
+	fn normalize_roles(&mut self) -> Result<(), AIError> {
+		self
+			.messages
+			.iter_mut()
+			.try_for_each(|m| super::normalize_role_str(&mut m.role))
+	}
 }
 
 pub fn prepend_prompts_helper(
@@ -3,6 +3,7 @@ pub mod completions;
 pub mod count_tokens;
 pub mod detect;
 pub mod embeddings;
+pub mod gemini;
 pub mod messages;
 pub mod rerank;
 pub mod responses;
@@ -12,6 +13,7 @@ use agent_core::prelude::Strng;
 use agent_core::strng;
 use serde::Serialize;
 
+use crate::tokenizer::Tokenize;
 use crate::{AIError, LLMRequest, LLMResponse, apply};
 
 pub enum ChatRequest<'a> {
@@ -41,9 +43,14 @@ pub trait RequestType: Send + Sync {
 	fn model(&mut self) -> &mut Option<String>;
 	fn prepend_prompts(&mut self, prompts: Vec<SimpleChatCompletionMessage>);
 	fn append_prompts(&mut self, prompts: Vec<SimpleChatCompletionMessage>);
-	fn to_llm_request(&self, provider: Strng, tokenize: bool) -> Result<LLMRequest, AIError>;
+	fn to_llm_request(&self, provider: Strng, tokenize: Tokenize) -> Result<LLMRequest, AIError>;
 	fn get_messages(&self) -> Vec<SimpleChatCompletionMessage>;
 	fn set_messages(&mut self, messages: Vec<SimpleChatCompletionMessage>);
+	/// Rewrites client message roles to their canonical names before the request is translated,
+	/// rejecting roles no provider understands.
+	fn normalize_roles(&mut self) -> Result<(), AIError> {
+		Ok(())
+	}
 }
 
 /// SimpleChatCompletionMessage is a simplified chat message
@@ -56,6 +63,127 @@ pub struct SimpleChatCompletionMessage {
 	pub content: Strng,
 }
 
+impl SimpleChatCompletionMessage {
+	/// Rewrites the role to its canonical name, mapping common synonyms (such as `human` for
+	/// `user`). Unknown roles are rejected rather than forwarded to an upstream that would refuse them.
+	pub fn normalize_role(&mut self) -> Result<(), AIError> {
+		self.role = canonical_role(&self.role).ok_or(AIError::UnsupportedContent)?;
+		Ok(())
+	}
+}
+
+/// Returns the canonical name for a message role, or `None` if the role is unknown.
+pub fn canonical_role(role: &str) -> Option<Strng> {
+	let role = match role.to_ascii_lowercase().as_str() {
+		"system" => strng::literal!("system"),
+		"developer" => strng::literal!("developer"),
+		"user" | "human" => strng::literal!("user"),
+		"assistant" | "ai" | "model" => strng::literal!("assistant"),
+		"tool" => strng::literal!("tool"),
+		"function" => strng::literal!("function"),
+		_ => return None,
+	};
+	Some(role)
+}
+
+/// Normalizes a role string in place, failing if the role is unknown.
+pub fn normalize_role_str(role: &mut String) -> Result<(), AIError> {
+	let canonical = canonical_role(role).ok_or(AIError::UnsupportedContent)?;
+	if role.as_str() != canonical.as_str() {
+		*role = canonical.to_string();
+	}
+	Ok(())
+}
+
+/// Normalizes the role of each message, failing on the first unknown role.
+pub fn normalize_roles(messages: &mut [SimpleChatCompletionMessage]) -> Result<(), AIError> {
+	messages
+		.iter_mut()
+		.try_for_each(SimpleChatCompletionMessage::normalize_role)
+}
+
+/// Deserializes a list of messages, normalizing their roles so unknown roles are rejected when
+/// the configuration is loaded rather than on each request.
+pub fn deserialize_messages<'de, D>(
+	deserializer: D,
+) -> Result<Vec<SimpleChatCompletionMessage>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let mut messages =
+		<Vec<SimpleChatCompletionMessage> as serde::Deserialize>::deserialize(deserializer)?;
+	for msg in &mut messages {
+		msg.role = canonical_role(&msg.role)
+			.ok_or_else(|| serde::de::Error::custom(format!("unknown message role {:?}", msg.role)))?;
+	}
+	Ok(messages)
+}
+
 pub fn serialize_str<T: Serialize>(value: &T) -> Option<Strng> {
 	serde_json::to_value(value).ok()?.as_str().map(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn message(role: &str) -> SimpleChatCompletionMessage {
+		SimpleChatCompletionMessage {
+			role: strng::new(role),
+			content: strng::literal!("hello"),
+		}
+	}
+
+	#[test]
+	fn normalize_role_keeps_known_roles() {
+		for role in ["system", "user", "assistant", "tool"] {
+			let mut msg = message(role);
+			msg.normalize_role().unwrap();
+			assert_eq!(msg.role.as_str(), role);
+		}
+	}
+
+	#[test]
+	fn normalize_role_maps_synonyms() {
+		for (role, want) in [
+			("Human", "user"),
+			("model", "assistant"),
+			("Tool", "tool"),
+			("SYSTEM", "system"),
+		] {
+			let mut msg = message(role);
+			msg.normalize_role().unwrap();
+			assert_eq!(msg.role.as_str(), want);
+		}
+	}
+
+	#[test]
+	fn normalize_roles_rejects_unknown_role() {
+		let mut msgs = vec![message("user"), message("assistent")];
+		assert!(matches!(
+			normalize_roles(&mut msgs),
+			Err(AIError::UnsupportedContent)
+		));
+	}
+
+	#[test]
+	fn deserialize_messages_validates_roles() {
+		#[derive(serde::Deserialize)]
+		struct Prompts {
+			#[serde(deserialize_with = "deserialize_messages")]
+			messages: Vec<SimpleChatCompletionMessage>,
+		}
+		let prompts: Prompts = serde_json::from_value(serde_json::json!({
+			"messages": [{"role": "AI", "content": "hello"}]
+		}))
+		.unwrap();
+		assert_eq!(prompts.messages[0].role.as_str(), "assistant");
+
+		let err = serde_json::from_value::<Prompts>(serde_json::json!({
+			"messages": [{"role": "assistent", "content": "hello"}]
+		}))
+		.err()
+		.unwrap();
+		assert!(err.to_string().contains("unknown message role"), "{err}");
+	}
+}
@@ -2,6 +2,7 @@ use agent_core::prelude::Strng;
 use agent_core::strng;
 use serde::{Deserialize, Serialize};
 
+use crate::tokenizer::Tokenize;
 use crate::types::RequestType;
 use crate::{AIError, InputFormat, LLMRequest, LLMRequestParams, SimpleChatCompletionMessage};
 
@@ -103,7 +104,7 @@ impl RequestType for Request {
 
 	fn append_prompts(&mut self, _prompts: Vec<SimpleChatCompletionMessage>) {}
 
-	fn to_llm_request(&self, provider: Strng, _tokenize: bool) -> Result<LLMRequest, AIError> {
+	fn to_llm_request(&self, provider: Strng, _tokenize: Tokenize) -> Result<LLMRequest, AIError> {
 		let model = strng::new(self.model.as_deref().unwrap_or_default());
 		Ok(LLMRequest {
 			input_tokens: None,
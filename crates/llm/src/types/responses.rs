@@ -7,6 +7,7 @@ use self::typed::{
 	Role,
 };
 use super::*;
+use crate::tokenizer::Tokenize;
 use crate::{
 	AIError, InputFormat, LLMRequest, LLMRequestParams, LLMResponse, RequestType, ResponseType,
 };
@@ -300,6 +301,23 @@ impl Request {
 			RequestInput::Items(items) => items,
 		}
 	}
+
+	/// Image inputs and tool definitions, which the tokenizer cannot count from message text.
+	fn non_text_input(&self) -> crate::tokenizer::NonTextInput<'_> {
+		let mut non_text = crate::tokenizer::NonTextInput::default();
+		if let RequestInput::Items(items) = &self.input {
+			items.iter().for_each(|item| non_text.add_images(&item.0));
+		}
+		non_text.tools.extend(
+			self
+				.rest
+				.get("tools")
+				.and_then(|t| t.as_array())
+				.into_iter()
+				.flatten(),
+		);
+		non_text
+	}
 }
 
 impl RequestType for Request {
@@ -323,11 +341,16 @@ impl RequestType for Request {
 		self.input = RequestInput::Items(items);
 	}
 
-	fn to_llm_request(&self, provider: Strng, tokenize: bool) -> Result<LLMRequest, AIError> {
+	fn to_llm_request(&self, provider: Strng, tokenize: Tokenize) -> Result<LLMRequest, AIError> {
 		let model = strng::new(self.model.as_deref().unwrap_or_default());
-		let input_tokens = if tokenize {
+		let input_tokens = if tokenize.is_enabled() {
 			let messages = self.get_messages();
-			let tokens = crate::tokenizer::num_tokens_from_messages(&model, &messages)?;
+			let tokens = crate::tokenizer::num_tokens_from_request(
+				&model,
+				tokenize.tokenizer(),
+				&messages,
+				&self.non_text_input(),
+			)?;
 			Some(tokens)
 		} else {
 			None
@@ -377,6 +400,18 @@ impl RequestType for Request {
 				.collect(),
 		);
 	}
+
+	fn normalize_roles(&mut self) -> Result<(), AIError> {
+		let RequestInput::Items(items) = &mut self.input else {
+			return Ok(());
+		};
+		for item in items {
+			if let Some(Value::String(role)) = item.0.get_mut("role") {
+				super::normalize_role_str(role)?;
+			}
+		}
+		Ok(())
+	}
 }
 
 impl ResponseType for Response {
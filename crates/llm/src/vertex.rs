@@ -44,6 +44,10 @@ impl Provider {
 		self.anthropic_model(request_model).is_some()
 	}
 
+	pub fn is_google_model(&self, request_model: Option<&str>) -> bool {
+		self.google_model(request_model).is_some()
+	}
+
 	pub fn prepare_anthropic_message_body(&self, body: Vec<u8>) -> Result<Vec<u8>, AIError> {
 		prepare_anthropic_message_body(body)
 	}
@@ -85,27 +89,42 @@ impl Provider {
 		request_model: Option<&str>,
 		streaming: bool,
 	) -> Strng {
-		let location = self
-			.region
-			.clone()
-			.unwrap_or_else(|| strng::literal!("global"));
-
-		match (route, self.anthropic_model(request_model)) {
-			(RouteType::AnthropicTokenCount, _) => {
+		let location = self.location();
+
+		match (
+			route,
+			self.anthropic_model(request_model),
+			self.google_model(request_model),
+		) {
+			// Google publisher models are served natively by generateContent.
+			(RouteType::Completions, None, Some(model)) => {
+				strng::format!(
+					"/v1/projects/{}/locations/{}/publishers/google/models/{}:{}",
+					self.project_id,
+					location,
+					model,
+					if streaming {
+						"streamGenerateContent?alt=sse"
+					} else {
+						"generateContent"
+					}
+				)
+			},
+			(RouteType::AnthropicTokenCount, _, _) => {
 				strng::format!(
 					"/v1/projects/{}/locations/{}/publishers/anthropic/models/count-tokens:rawPredict",
 					self.project_id,
 					location
 				)
 			},
-			(RouteType::Rerank, _) => {
+			(RouteType::Rerank, _, _) => {
 				strng::format!(
 					"/v1/projects/{}/locations/{}/rankingConfigs/default_ranking_config:rank",
 					self.project_id,
 					location
 				)
 			},
-			(RouteType::Embeddings, _) => {
+			(RouteType::Embeddings, _, _) => {
 				let model = self.configured_model(request_model).unwrap_or_default();
 				strng::format!(
 					"/v1/projects/{}/locations/{}/publishers/google/models/{}:predict",
@@ -114,7 +133,7 @@ impl Provider {
 					model
 				)
 			},
-			(_, Some(model)) => {
+			(_, Some(model), _) => {
 				strng::format!(
 					"/v1/projects/{}/locations/{}/publishers/anthropic/models/{}:{}",
 					self.project_id,
@@ -127,16 +146,26 @@ impl Provider {
 					}
 				)
 			},
-			_ => {
-				strng::format!(
-					"/v1/projects/{}/locations/{}/endpoints/openapi/chat/completions",
-					self.project_id,
-					location
-				)
-			},
+			_ => self.openai_compat_path(),
 		}
 	}
 
+	/// Path of the OpenAI-compatible chat completions endpoint.
+	pub fn openai_compat_path(&self) -> Strng {
+		strng::format!(
+			"/v1/projects/{}/locations/{}/endpoints/openapi/chat/completions",
+			self.project_id,
+			self.location()
+		)
+	}
+
+	fn location(&self) -> Strng {
+		self
+			.region
+			.clone()
+			.unwrap_or_else(|| strng::literal!("global"))
+	}
+
 	pub fn get_host(&self, route_type: RouteType) -> Strng {
 		// Rerank is served by the Discovery Engine ranking endpoint, not the aiplatform host. Deciding
 		// it here keeps the request authority (Host header) and the TCP/TLS connection target in sync.
@@ -153,6 +182,16 @@ impl Provider {
 		}
 	}
 
+	fn google_model(&self, request_model: Option<&str>) -> Option<Strng> {
+		let model = self.configured_model(request_model)?;
+		model
+			.split_once("publishers/google/models/")
+			.map(|(_, m)| m)
+			.or_else(|| model.strip_prefix("google/"))
+			.or_else(|| model.starts_with("gemini-").then_some(model))
+			.map(strng::new)
+	}
+
 	fn anthropic_model<'a>(&'a self, request_model: Option<&'a str>) -> Option<Strng> {
 		let model = self.configured_model(request_model)?;
 
@@ -260,6 +299,49 @@ mod tests {
 		assert_eq!(actual.as_deref(), expected);
 	}
 
+	#[rstest::rstest]
+	#[case::gemini(
+		"gemini-1.5-pro",
+		false,
+		"/v1/projects/test-project/locations/us-central1/publishers/google/models/gemini-1.5-pro:generateContent"
+	)]
+	#[case::gemini_streaming(
+		"gemini-1.5-pro",
+		true,
+		"/v1/projects/test-project/locations/us-central1/publishers/google/models/gemini-1.5-pro:streamGenerateContent?alt=sse"
+	)]
+	#[case::google_prefix(
+		"google/gemini-2.5-flash",
+		false,
+		"/v1/projects/test-project/locations/us-central1/publishers/google/models/gemini-2.5-flash:generateContent"
+	)]
+	#[case::anthropic(
+		"claude-sonnet-4-5-20251001",
+		false,
+		"/v1/projects/test-project/locations/us-central1/publishers/anthropic/models/claude-sonnet-4-5@20251001:rawPredict"
+	)]
+	#[case::other_publisher(
+		"meta/llama-3.1-405b-instruct-maas",
+		false,
+		"/v1/projects/test-project/locations/us-central1/endpoints/openapi/chat/completions"
+	)]
+	fn test_get_path_for_publisher_model(
+		#[case] model: &str,
+		#[case] streaming: bool,
+		#[case] expected: &str,
+	) {
+		let p = Provider {
+			project_id: strng::new("test-project"),
+			model: None,
+			region: Some(strng::new("us-central1")),
+		};
+		assert_eq!(
+			p.get_path_for_model(RouteType::Completions, Some(model), streaming)
+				.as_str(),
+			expected
+		);
+	}
+
 	#[rstest::rstest]
 	#[case::no_region(None, "aiplatform.googleapis.com")]
 	#[case::global_region(Some("global"), "aiplatform.googleapis.com")]
@@ -18,6 +18,7 @@ use prost::{DecodeError, EncodeError};
 use prost_wkt_types::value::Kind;
 use prost_wkt_types::{Struct, Value};
 use protos::envoy::service::common::v3::Status;
+use rand::RngExt;
 use split_iter::Splittable;
 use thiserror::Error;
 use tokio::sync::mpsc;
@@ -301,6 +302,7 @@ pub struct Config {
 	proxy_metadata: HashMap<String, String>,
 	handlers: HashMap<Strng, Box<dyn RawHandler>>,
 	initial_requests: Vec<DeltaDiscoveryRequest>,
+	backoff: Backoff,
 	// Environment variables
 	instance_ip: String,
 	pod_name: String,
@@ -315,6 +317,7 @@ impl Config {
 			client,
 			handlers: HashMap::new(),
 			initial_requests: Vec::new(),
+			backoff: Backoff::default(),
 			proxy_metadata: HashMap::from([
 				("GATEWAY_NAME".to_string(), gateway_name.to_string()),
 				("NAMESPACE".to_string(), namespace.to_string()),
@@ -346,6 +349,11 @@ impl State {
 }
 
 impl Config {
+	pub fn with_backoff(mut self, backoff: Backoff) -> Config {
+		self.backoff = backoff;
+		self
+	}
+
 	pub fn with_watched_handler<F>(self, type_url: Strng, f: impl Handler<F>) -> Config
 	where
 		F: 'static + prost::Message + Default + Debug,
@@ -463,8 +471,47 @@ impl Display for XdsSignal {
 	}
 }
 
-const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
-const MAX_BACKOFF: Duration = Duration::from_secs(15);
+/// Backoff controls how long the client waits before reconnecting after a failed stream.
+/// The delay doubles on each consecutive failure, up to `max`, and resets once the stream
+/// receives a response.
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Backoff {
+	#[serde(with = "agent_core::serdes::serde_dur")]
+	pub initial: Duration,
+	#[serde(with = "agent_core::serdes::serde_dur")]
+	pub max: Duration,
+	/// Fraction, between 0 and 1, by which each delay is randomly shortened or lengthened, so
+	/// that many clients do not reconnect in lockstep.
+	pub jitter: f64,
+}
+
+impl Default for Backoff {
+	fn default() -> Self {
+		Self {
+			initial: Duration::from_millis(10),
+			max: Duration::from_secs(15),
+			jitter: 0.2,
+		}
+	}
+}
+
+impl Backoff {
+	/// Returns the delay following `current`.
+	fn next(&self, current: Duration) -> Duration {
+		std::cmp::min(self.max, current * 2)
+	}
+
+	/// Applies jitter to `delay`; the result never exceeds `max`.
+	fn jittered(&self, delay: Duration) -> Duration {
+		let jitter = self.jitter.clamp(0.0, 1.0);
+		if jitter == 0.0 {
+			return delay;
+		}
+		let factor = rand::rng().random_range(1.0 - jitter..=1.0 + jitter);
+		std::cmp::min(self.max, delay.mul_f64(factor))
+	}
+}
 
 impl AdsClient {
 	fn new(
@@ -496,60 +543,67 @@ impl AdsClient {
 	}
 
 	async fn run_loop(&mut self, mut backoff: Duration) -> Duration {
+		let policy = self.config.backoff;
 		match self.run_internal(&mut backoff).await {
 			Err(e @ Error::Connection(_)) => {
 				// For connection errors, we add backoff
-				let backoff = std::cmp::min(MAX_BACKOFF, backoff * 2);
+				let backoff = policy.next(backoff);
+				let delay = policy.jittered(backoff);
 				warn!(
 					"XDS client connection error: {}, retrying in {:?}",
-					e, backoff
+					e, delay
 				);
 				self
 					.metrics
 					.increment(&ConnectionTerminationReason::ConnectionError);
-				tokio::time::sleep(backoff).await;
+				tokio::time::sleep(delay).await;
 				backoff
 			},
 			Err(e @ Error::Transport(_)) => {
 				// For connection errors, we add backoff
-				let backoff = std::cmp::min(MAX_BACKOFF, backoff * 2);
+				let backoff = policy.next(backoff);
+				let delay = policy.jittered(backoff);
 				warn!(
 					"XDS client connection error: {:?}, retrying in {:?}",
-					e, backoff
+					e, delay
 				);
 				self
 					.metrics
 					.increment(&ConnectionTerminationReason::ConnectionError);
-				tokio::time::sleep(backoff).await;
+				tokio::time::sleep(delay).await;
 				backoff
 			},
 			Err(ref e @ Error::GrpcStatus(ref status)) => {
 				let err_detail = e.to_string();
-				let backoff = if status.code() == tonic::Code::Cancelled
+				let reconnect = status.code() == tonic::Code::Cancelled
 					|| status.code() == tonic::Code::DeadlineExceeded
 					|| (status.code() == tonic::Code::Unavailable
 						&& status.message().contains("transport is closing"))
 					|| (status.code() == tonic::Code::Unavailable
-						&& status.message().contains("received prior goaway"))
-				{
+						&& status.message().contains("received prior goaway"));
+				let backoff = if reconnect {
+					policy.initial
+				} else {
+					// For gRPC errors, we add backoff
+					policy.next(backoff)
+				};
+				let delay = policy.jittered(backoff);
+				if reconnect {
 					debug!(
 						"XDS client terminated: {}, retrying in {:?}",
-						err_detail, backoff
+						err_detail, delay
 					);
 					self
 						.metrics
 						.increment(&ConnectionTerminationReason::Reconnect);
-					INITIAL_BACKOFF
 				} else {
 					warn!(
 						"XDS client error: {e:?} {status:?}, retrying in {:?}",
-						backoff
+						delay
 					);
 					self.metrics.increment(&ConnectionTerminationReason::Error);
-					// For gRPC errors, we add backoff
-					std::cmp::min(MAX_BACKOFF, backoff * 2)
-				};
-				tokio::time::sleep(backoff).await;
+				}
+				tokio::time::sleep(delay).await;
 				backoff
 			},
 			Err(e) => {
@@ -559,7 +613,7 @@ impl AdsClient {
 				warn!("XDS client error: {:?}, retrying", e);
 				self.metrics.increment(&ConnectionTerminationReason::Error);
 				// Reset backoff
-				INITIAL_BACKOFF
+				policy.initial
 			},
 			Ok(_) => {
 				self
@@ -567,16 +621,19 @@ impl AdsClient {
 					.increment(&ConnectionTerminationReason::Complete);
 				warn!("XDS client complete");
 				// Reset backoff
-				INITIAL_BACKOFF
+				policy.initial
 			},
 		}
 	}
 
 	pub async fn run(mut self) -> Result<(), Error> {
-		let mut backoff = INITIAL_BACKOFF;
+		let mut backoff = self.config.backoff.initial;
 		loop {
 			self.connection_id += 1;
 			let id = self.connection_id;
+			if id > 1 {
+				self.metrics.reconnect_attempts.inc();
+			}
 			backoff = self
 				.run_loop(backoff)
 				.instrument(info_span!("xds", id))
@@ -650,7 +707,7 @@ impl AdsClient {
 						return Ok(());
 					};
 					// A response proves the connection recovered from prior failures.
-					*backoff = INITIAL_BACKOFF;
+					*backoff = self.config.backoff.initial;
 					let mut received_type = None;
 					if !self.types_to_expect.is_empty() {
 						received_type = Some(msg.type_url.clone())
@@ -784,3 +841,117 @@ pub enum AdsError {
 	#[error("encode: {0}")]
 	Encode(#[from] EncodeError),
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	use prometheus_client::registry::Registry;
+
+	use super::*;
+	use crate::metrics::ConnectionTermination;
+
+	/// A client that fails the first `failures` calls, then returns an empty, successful stream.
+	#[derive(Debug, Clone)]
+	struct FlakyClient {
+		failures: Arc<AtomicUsize>,
+	}
+
+	impl ClientTrait for FlakyClient {
+		fn make_call(
+			&mut self,
+			_req: Request<Body>,
+		) -> Pin<
+			Box<dyn Future<Output = Result<http::Response<axum_core::body::Body>, anyhow::Error>> + Send>,
+		> {
+			let failed = self
+				.failures
+				.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+				.is_ok();
+			Box::pin(async move {
+				if failed {
+					anyhow::bail!("control plane unavailable");
+				}
+				// A trailers-only response; the stream completes without any messages.
+				Ok(
+					http::Response::builder()
+						.header("content-type", "application/grpc")
+						.header("grpc-status", "0")
+						.body(axum_core::body::Body::empty())?,
+				)
+			})
+		}
+
+		fn box_clone(&self) -> Box<dyn ClientTrait> {
+			Box::new(self.clone())
+		}
+	}
+
+	#[tokio::test]
+	async fn reconnect_backoff_grows_and_is_bounded() {
+		let backoff = Backoff {
+			initial: Duration::from_millis(1),
+			max: Duration::from_millis(8),
+			jitter: 0.0,
+		};
+		let failures = Arc::new(AtomicUsize::new(5));
+		let metrics = Arc::new(Metrics::new(&mut Registry::default()));
+		let (block_ready, _ready) = tokio::sync::watch::channel(());
+		let mut client = Config::new(
+			GrpcClient::new(FlakyClient {
+				failures: failures.clone(),
+			}),
+			strng::new("gateway"),
+			strng::new("namespace"),
+		)
+		.with_backoff(backoff)
+		.build(metrics.clone(), block_ready);
+
+		let mut delay = backoff.initial;
+		let mut delays = Vec::new();
+		for _ in 0..5 {
+			delay = client.run_loop(delay).await;
+			delays.push(delay);
+		}
+		assert_eq!(
+			delays,
+			[2, 4, 8, 8, 8].map(Duration::from_millis).to_vec(),
+			"delays should double up to the max"
+		);
+		assert_eq!(
+			metrics
+				.connection_terminations
+				.get_or_create(&ConnectionTermination {
+					reason: ConnectionTerminationReason::ConnectionError,
+				})
+				.get(),
+			5
+		);
+
+		// Once the control plane recovers, the backoff resets.
+		assert_eq!(client.run_loop(delay).await, backoff.initial);
+		assert_eq!(failures.load(Ordering::SeqCst), 0);
+	}
+
+	#[test]
+	fn jittered_backoff_is_bounded() {
+		let backoff = Backoff {
+			initial: Duration::from_millis(100),
+			max: Duration::from_secs(1),
+			jitter: 0.5,
+		};
+		for _ in 0..100 {
+			let delay = backoff.jittered(Duration::from_millis(800));
+			assert!(delay >= Duration::from_millis(400), "{delay:?}");
+			assert!(delay <= backoff.max, "{delay:?}");
+		}
+		assert_eq!(
+			Backoff {
+				jitter: 0.0,
+				..backoff
+			}
+			.jittered(Duration::from_millis(800)),
+			Duration::from_millis(800)
+		);
+	}
+}
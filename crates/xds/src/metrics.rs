@@ -13,6 +13,7 @@ pub struct Metrics {
 	pub message_types: Family<TypeUrl, Counter>,
 	pub total_messages_size: Family<TypeUrl, Counter>,
 	pub config_synchronized: Gauge,
+	pub reconnect_attempts: Counter,
 }
 
 #[derive(Clone, Hash, Debug, PartialEq, Eq, EncodeLabelSet)]
@@ -67,8 +68,16 @@ impl Metrics {
             config_synchronized.clone()
         );
 
+		let reconnect_attempts = Counter::default();
+		registry.register(
+			"xds_reconnect_attempts",
+			"Total number of attempts to reconnect to the xds server (unstable)",
+			reconnect_attempts.clone(),
+		);
+
 		Self {
 			connection_terminations,
+			reconnect_attempts,
 			message_types: message_count,
 			total_messages_size,
 			config_synchronized,
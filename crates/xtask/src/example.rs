@@ -0,0 +1,180 @@
+use agentgateway::serdes::yamlviajson;
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+// Guards against recursive types; the static config is nowhere near this deep.
+const MAX_DEPTH: usize = 10;
+
+/// Emit an example config with every field of the `config` section documented and at its default.
+/// Optional sections without a default are emitted commented out, ready to be uncommented.
+pub fn example_config(json: bool) -> Result<String> {
+	let settings = schemars::generate::SchemaSettings::default().with(|s| {
+		s.contract = schemars::generate::Contract::Deserialize;
+	});
+	let schema =
+		schemars::SchemaGenerator::new(settings).into_root_schema_for::<agentgateway::RawConfig>();
+	let schema = serde_json::to_value(&schema)?;
+	let defs = schema
+		.get("$defs")
+		.and_then(Value::as_object)
+		.cloned()
+		.unwrap_or_default();
+
+	let mut out = String::from(
+		"# Example agentgateway configuration, with every setting at its default.\n\
+		 # Generated by `cargo xtask example-config`.\n\
+		 config:\n",
+	);
+	Renderer { defs: &defs }.object(&schema, None, 1, None, &mut out);
+	if json {
+		let value: Value = yamlviajson::from_str(&out)?;
+		return Ok(serde_json::to_string_pretty(&value)? + "\n");
+	}
+	Ok(out)
+}
+
+struct Renderer<'a> {
+	defs: &'a Map<String, Value>,
+}
+
+impl Renderer<'_> {
+	/// Render the properties of `schema`. `values` overrides the defaults of the properties, and
+	/// `comment` is the depth at which an enclosing commented-out block started.
+	fn object(
+		&self,
+		schema: &Value,
+		values: Option<&Map<String, Value>>,
+		depth: usize,
+		comment: Option<usize>,
+		out: &mut String,
+	) {
+		let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+			return;
+		};
+		if depth > MAX_DEPTH {
+			return;
+		}
+		for (i, (key, prop)) in properties.iter().enumerate() {
+			if depth == 1 && i > 0 {
+				out.push('\n');
+			}
+			let description = prop
+				.get("description")
+				.or_else(|| self.resolve(prop).get("description"))
+				.and_then(Value::as_str);
+			for l in description.into_iter().flat_map(str::lines) {
+				line(out, depth, comment, format!("# {l}").trim_end());
+			}
+
+			let value = values
+				.and_then(|v| v.get(key))
+				.or_else(|| prop.get("default"));
+			match (value, self.object_schema(prop)) {
+				(Some(Value::Object(v)), Some(obj)) if !v.is_empty() => {
+					line(out, depth, comment, &format!("{key}:"));
+					self.object(obj, Some(v), depth + 1, comment, out);
+				},
+				(Some(v), _) => line(out, depth, comment, &format!("{key}: {v}")),
+				(None, Some(obj)) => {
+					// Enabling an optional section can change behavior, so only show it.
+					let comment = match comment {
+						None if is_nullable(prop) => Some(depth),
+						c => c,
+					};
+					line(out, depth, comment, &format!("{key}:"));
+					self.object(obj, None, depth + 1, comment, out);
+				},
+				(None, None) if is_nullable(prop) => line(out, depth, comment, &format!("{key}: null")),
+				(None, None) => {
+					let comment = comment.or(Some(depth));
+					line(
+						out,
+						depth,
+						comment,
+						&format!("{key}: {}", self.type_hint(prop)),
+					);
+				},
+			}
+		}
+	}
+
+	fn resolve<'s>(&'s self, schema: &'s Value) -> &'s Value {
+		schema
+			.get("$ref")
+			.and_then(Value::as_str)
+			.and_then(|r| r.strip_prefix("#/$defs/"))
+			.and_then(|name| self.defs.get(name))
+			.unwrap_or(schema)
+	}
+
+	/// The struct schema of a (possibly optional) field, if it is a struct.
+	fn object_schema<'s>(&'s self, schema: &'s Value) -> Option<&'s Value> {
+		let schema = self.resolve(schema);
+		if schema.get("properties").is_some() {
+			return Some(schema);
+		}
+		schema
+			.get("anyOf")
+			.and_then(Value::as_array)?
+			.iter()
+			.map(|s| self.resolve(s))
+			.find(|s| s.get("properties").is_some())
+	}
+
+	fn type_hint(&self, schema: &Value) -> String {
+		let schema = self.resolve(schema);
+		let ty = match schema.get("type") {
+			Some(Value::String(t)) => Some(t.as_str()),
+			Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).find(|t| *t != "null"),
+			_ => None,
+		};
+		format!("<{}>", ty.unwrap_or("value"))
+	}
+}
+
+fn is_nullable(schema: &Value) -> bool {
+	match schema.get("type") {
+		Some(Value::String(t)) => t == "null",
+		Some(Value::Array(ts)) => ts.iter().any(|t| t == "null"),
+		_ => schema
+			.get("anyOf")
+			.or_else(|| schema.get("oneOf"))
+			.and_then(Value::as_array)
+			.is_some_and(|v| v.iter().any(is_nullable)),
+	}
+}
+
+/// Write `text` at `depth`. Lines within a commented-out block keep their relative indentation, so
+/// removing the leading `# ` of the block yields valid YAML.
+fn line(out: &mut String, depth: usize, comment: Option<usize>, text: &str) {
+	match comment {
+		None => out.push_str(&"  ".repeat(depth)),
+		Some(c) => {
+			out.push_str(&"  ".repeat(c));
+			out.push_str("# ");
+			out.push_str(&"  ".repeat(depth - c));
+		},
+	}
+	out.push_str(text);
+	out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+	use super::example_config;
+
+	#[test]
+	fn example_config_round_trips() {
+		for json in [false, true] {
+			let cfg = example_config(json).unwrap();
+			let errors = agentgateway::config::validate_config(&cfg).unwrap();
+			assert!(errors.is_empty(), "{errors:?}\n{cfg}");
+			agentgateway::config::parse_config(cfg.clone(), None).unwrap();
+		}
+
+		let yaml = example_config(false).unwrap();
+		assert!(yaml.contains("\n  backend:\n"), "{yaml}");
+		assert!(yaml.contains("\n  # dns:\n"), "{yaml}");
+		assert!(yaml.contains("\n  enableIpv6: null\n"), "{yaml}");
+	}
+}
@@ -1,4 +1,5 @@
 mod cel;
+mod example;
 mod schema;
 
 use std::env::args;
@@ -8,6 +9,7 @@ use anyhow::{Context, Result, bail};
 enum Task {
 	Schema,
 	Cel,
+	ExampleConfig { json: bool },
 }
 
 fn get_task() -> Result<Task> {
@@ -16,6 +18,9 @@ fn get_task() -> Result<Task> {
 	match arg.as_str() {
 		"schema" => Ok(Task::Schema),
 		"cel" => Ok(Task::Cel),
+		"example-config" => Ok(Task::ExampleConfig {
+			json: args().nth(2).as_deref() == Some("--json"),
+		}),
 		arg => bail!("unknown task: {}", arg),
 	}
 }
@@ -24,5 +29,9 @@ fn main() -> Result<()> {
 	match get_task()? {
 		Task::Schema => schema::generate_schema(),
 		Task::Cel => cel::evaluate_command(),
+		Task::ExampleConfig { json } => {
+			print!("{}", example::example_config(json)?);
+			Ok(())
+		},
 	}
 }
@@ -46,7 +46,14 @@ import bedrockIcon from "../assets/providers/bedrock.svg";
 import googleCloudIcon from "../assets/providers/googlecloud.svg";
 import openAiIcon from "../assets/providers/openai.svg";
 
-type BuiltinRule = "ssn" | "creditCard" | "phoneNumber" | "email" | "caSin";
+type BuiltinRule =
+  | "ssn"
+  | "creditCard"
+  | "phoneNumber"
+  | "email"
+  | "caSin"
+  | "iban"
+  | "passport";
 type GuardPhase = "request" | "response";
 type GuardObject = NonNullable<LlmGuardrail["request"]>[number];
 type GuardKind =
@@ -133,6 +140,8 @@ const builtinOptions: Array<{ value: BuiltinRule; label: string }> = [
   { value: "creditCard", label: "Credit card" },
   { value: "ssn", label: "SSN" },
   { value: "caSin", label: "CA SIN" },
+  { value: "iban", label: "IBAN" },
+  { value: "passport", label: "Passport" },
 ];
 
 const requestGuardKinds: Array<EnumSelectorOption<GuardKind>> = [